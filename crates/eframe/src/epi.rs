@@ -41,6 +41,46 @@ pub type EventLoopBuilderHook = Box<dyn FnOnce(&mut EventLoopBuilder<UserEvent>)
 #[cfg(any(feature = "glow", feature = "wgpu"))]
 pub type WindowBuilderHook = Box<dyn FnOnce(egui::ViewportBuilder) -> egui::ViewportBuilder>;
 
+/// Called with the report of a panic, e.g. to log or upload it.
+///
+/// See [`NativeOptions::on_panic`].
+#[cfg(not(target_arch = "wasm32"))]
+pub type PanicHook = std::sync::Arc<dyn Fn(&PanicReport) + Send + Sync>;
+
+/// Work to finish before the process exits, see [`App::shutdown_hook`].
+#[cfg(not(target_arch = "wasm32"))]
+pub type ShutdownHook = Box<dyn FnOnce() + Send>;
+
+/// A panic reported by [`NativeOptions::report_panics`].
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+pub struct PanicReport {
+    /// The panic message, e.g. `"index out of bounds: the len is 3 but the index is 3"`.
+    pub message: String,
+
+    /// Where the panic happened, e.g. `"src/app.rs:42:13"`.
+    pub location: Option<String>,
+
+    /// The callstack at the point of the panic.
+    pub backtrace: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl std::fmt::Display for PanicReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            message,
+            location,
+            backtrace,
+        } = self;
+        write!(f, "Panic: {message}")?;
+        if let Some(location) = location {
+            write!(f, " at {location}")?;
+        }
+        write!(f, "\n\nBacktrace:\n{backtrace}")
+    }
+}
+
 type DynError = Box<dyn std::error::Error + Send + Sync>;
 
 /// This is how your app is created.
@@ -183,6 +223,9 @@ pub trait App {
     /// If you need to abort an exit check `ctx.input(|i| i.viewport().close_requested())`
     /// and respond with [`egui::ViewportCommand::CancelClose`].
     ///
+    /// When the root viewport is closed, [`Self::update`] is called one last time
+    /// with [`egui::ViewportEvent::Closing`] before this, see [`egui::ViewportEvent::Close`].
+    ///
     /// To get a [`glow`] context you need to compile with the `glow` feature flag,
     /// and run eframe with the glow backend.
     #[cfg(feature = "glow")]
@@ -191,9 +234,25 @@ pub trait App {
     /// Called once on shutdown, after [`Self::save`].
     ///
     /// If you need to abort an exit use [`Self::on_close_event`].
+    ///
+    /// When the root viewport is closed, [`Self::update`] is called one last time
+    /// with [`egui::ViewportEvent::Closing`] before this, see [`egui::ViewportEvent::Close`].
     #[cfg(not(feature = "glow"))]
     fn on_exit(&mut self) {}
 
+    /// Called once on shutdown, after [`Self::on_exit`].
+    ///
+    /// Return work that should finish before the process exits,
+    /// e.g. closing network connections or flushing a database.
+    /// It is run on a background thread, and eframe waits for it at most
+    /// [`NativeOptions::shutdown_timeout`] before exiting anyway.
+    ///
+    /// This is also run when the OS ends the session, see [`LifecycleEvent::SessionEnding`].
+    #[cfg(not(target_arch = "wasm32"))]
+    fn shutdown_hook(&mut self) -> Option<ShutdownHook> {
+        None
+    }
+
     // ---------
     // Settings:
 
@@ -244,6 +303,138 @@ pub trait App {
     ///
     /// This function does not return a value. Any changes to the input should be made directly to `_raw_input`.
     fn raw_input_hook(&mut self, _ctx: &egui::Context, _raw_input: &mut egui::RawInput) {}
+
+    /// Called when the platform reports a change in the lifecycle of the app,
+    /// e.g. when it is moved to the background, or the OS is running low on memory.
+    ///
+    /// This is a good place to drop caches, pause background work,
+    /// or re-authenticate after having been suspended.
+    ///
+    /// See [`LifecycleEvent`] for what is reported on which platform.
+    fn on_lifecycle_event(&mut self, _ctx: &egui::Context, _event: LifecycleEvent) {}
+
+    /// Called when the OS switches between dark and light mode while the app is running,
+    /// right before the [`Self::update`] where the new theme takes effect.
+    ///
+    /// egui already switches between the dark and light [`egui::Style`] on its own
+    /// (as long as the theme preference is [`egui::ThemePreference::System`]),
+    /// so if you have a custom pair of [`egui::Visuals`], the easiest is to install them once
+    /// with [`egui::Context::set_visuals_of`].
+    /// Use this hook if you need to do more, e.g. reload themed images or apply a theme
+    /// that is computed at runtime.
+    ///
+    /// Reported by both the native backends and on web (via the `prefers-color-scheme` media query).
+    fn on_system_theme_changed(&mut self, _ctx: &egui::Context, _theme: egui::Theme) {}
+
+    /// Called when the user asks for something from the menus of the OS shell,
+    /// e.g. picks one of the [`Frame::set_shell_tasks`] from the dock menu,
+    /// or opens a recent document with the app.
+    ///
+    /// Called right before the [`Self::update`] it should take effect in.
+    ///
+    /// Only reported on native Windows and Mac. See [`ShellEvent`] for the details.
+    fn on_shell_event(&mut self, _ctx: &egui::Context, _event: ShellEvent) {}
+}
+
+/// A change in the lifecycle of the app, as reported by the platform.
+///
+/// Delivered to [`App::on_lifecycle_event`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LifecycleEvent {
+    /// The app was suspended.
+    ///
+    /// On Android and iOS this is sent when the app loses its surface,
+    /// and any native graphics resources have been released.
+    /// On web this is sent on `pagehide`, e.g. when the page is put into the back/forward cache.
+    Suspended,
+
+    /// The app was resumed after having been [`Self::Suspended`].
+    ///
+    /// On web this is sent on `pageshow` when the page is restored from the back/forward cache.
+    Resumed,
+
+    /// The app is no longer visible to the user.
+    ///
+    /// On web this is sent when the browser tab is hidden.
+    /// On native this is sent when the root window is fully occluded or minimized
+    /// (only reported by some windowing systems).
+    Background,
+
+    /// The app is visible to the user again after having been in the [`Self::Background`].
+    Foreground,
+
+    /// The OS is running low on memory, and the app should free up what it can.
+    ///
+    /// Only reported on Android and iOS.
+    LowMemory,
+
+    /// The OS is ending the session (the user is logging off, or the computer shutting down),
+    /// or asked the app to terminate.
+    ///
+    /// eframe calls [`App::save`] right after delivering this, and flushes the [`Storage`].
+    /// On native it then shuts down as if the app was closed, including [`App::on_exit`]
+    /// and [`App::shutdown_hook`].
+    ///
+    /// On Unix this is sent on `SIGTERM` and `SIGHUP`, and on Windows on `WM_QUERYENDSESSION`,
+    /// see [`NativeOptions::handle_session_end`].
+    /// On web this is sent on `pagehide` when the page is being unloaded
+    /// (rather than put into the back/forward cache, which sends [`Self::Suspended`]).
+    SessionEnding,
+}
+
+/// Something the user asked the app to do from the menus of the OS shell,
+/// rather than from the app's own windows.
+///
+/// Delivered to [`App::on_shell_event`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShellEvent {
+    /// The user picked the [`ShellTask`] with this [`ShellTask::id`].
+    ///
+    /// On Mac the running app gets this from its dock menu.
+    /// On Windows picking a task in the jump list starts a _new_ instance of the app,
+    /// which gets this right after starting. The task id is passed on the command line
+    /// (as `--eframe-shell-command=<id>`), so if you want to forward it to an
+    /// instance that is already running, you need to do that yourself,
+    /// e.g. with the help of [`crate::parse_shell_command`].
+    ///
+    /// Parse your own command line arguments from [`crate::args_os`],
+    /// which leaves out this argument.
+    Command(String),
+
+    /// The user asked to open these files with the app, e.g. one of the
+    /// [`Frame::add_recent_document`] from the dock menu on Mac.
+    ///
+    /// On Mac this includes the files the app was started with.
+    /// On Windows opening a file starts a new instance of the app with the path on the command line,
+    /// and that instance gets this for the arguments that are paths of existing files right after starting.
+    OpenFiles(Vec<std::path::PathBuf>),
+}
+
+/// A command in the menu of the app in the OS shell:
+/// the taskbar jump list on Windows, and the dock menu on Mac.
+///
+/// See [`Frame::set_shell_tasks`].
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShellTask {
+    /// Given back in [`ShellEvent::Command`] when the user picks this task.
+    ///
+    /// Must not contain `"`.
+    pub id: String,
+
+    /// What the menu shows.
+    pub title: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ShellTask {
+    /// A task with this [`Self::id`] and [`Self::title`].
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+        }
+    }
 }
 
 /// Selects the level of hardware graphics acceleration.
@@ -262,6 +453,166 @@ pub enum HardwareAcceleration {
     Off,
 }
 
+/// Whether to lay out the UI from right to left, see [`egui::Style::right_to_left`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LayoutDirection {
+    /// Left to right.
+    #[default]
+    LeftToRight,
+
+    /// Right to left, e.g. for Arabic and Hebrew.
+    RightToLeft,
+
+    /// Right to left if the preferred language of the user (from the OS or the browser)
+    /// is written right to left, see [`IntegrationInfo::prefers_right_to_left`].
+    ///
+    /// Follows changes to the preferred language while the app is running.
+    FromLocale,
+}
+
+impl LayoutDirection {
+    /// Set [`egui::Style::right_to_left`] accordingly, for both dark and light mode.
+    pub(crate) fn apply(self, egui_ctx: &egui::Context, prefers_right_to_left: bool) {
+        let right_to_left = match self {
+            Self::LeftToRight => false,
+            Self::RightToLeft => true,
+            Self::FromLocale => prefers_right_to_left,
+        };
+        egui_ctx.all_styles_mut(|style| style.right_to_left = right_to_left);
+    }
+}
+
+/// How often to repaint, see [`Frame::set_repaint_mode`].
+///
+/// Use this to save battery, e.g. in apps that show mostly static content.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RepaintMode {
+    /// Repaint whenever egui asks for it: on input, for animations,
+    /// and on [`egui::Context::request_repaint`].
+    #[default]
+    Reactive,
+
+    /// Like [`Self::Reactive`], but at most this many frames per second.
+    CappedFps(f32),
+
+    /// Like [`Self::Reactive`], but at most once per refresh of the monitor the window is on.
+    ///
+    /// This is useful if vsync is off, or not honored by the driver.
+    /// On web this is the same as [`Self::Reactive`], since the browser already does this.
+    MonitorRefreshRate,
+
+    /// Only repaint on user input, and for a short while after it (so hover effects and animations can finish).
+    ///
+    /// Repaints requested with [`egui::Context::request_repaint`] while the user is idle are ignored.
+    OnInput,
+}
+
+impl RepaintMode {
+    /// For how long after input we honor the repaints egui asks for, in [`Self::OnInput`].
+    pub(crate) const INPUT_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(1);
+
+    /// The shortest allowed time between two frames, if any.
+    ///
+    /// `monitor_refresh_rate` is in Hz.
+    pub(crate) fn min_frame_interval(
+        self,
+        monitor_refresh_rate: Option<f32>,
+    ) -> Option<std::time::Duration> {
+        let max_fps = match self {
+            Self::Reactive | Self::OnInput => None,
+            Self::CappedFps(max_fps) => Some(max_fps),
+            Self::MonitorRefreshRate => monitor_refresh_rate,
+        }?;
+        (max_fps > 0.0).then(|| std::time::Duration::from_secs_f32(1.0 / max_fps))
+    }
+
+    /// Should we honor a repaint that egui asked for (as opposed to one caused by input)?
+    pub(crate) fn allows_requested_repaint(self, time_since_input: std::time::Duration) -> bool {
+        self != Self::OnInput || time_since_input < Self::INPUT_GRACE_PERIOD
+    }
+}
+
+/// Receives the screenshots from [`Frame::request_screenshot_of`] and [`Frame::start_recording`].
+pub type ScreenshotReceiver = std::sync::mpsc::Receiver<std::sync::Arc<egui::ColorImage>>;
+
+/// The [`egui::UserData`] of the screenshots requested via [`Frame`],
+/// so we can tell them apart from the ones requested by the app.
+#[derive(Clone)]
+pub(crate) struct ScreenshotSender(std::sync::mpsc::Sender<std::sync::Arc<egui::ColorImage>>);
+
+/// Send the screenshots requested via [`Frame`] to their receivers,
+/// and remove them from `events`.
+///
+/// Other [`egui::Event::Screenshot`] are left for the app.
+pub(crate) fn deliver_screenshots(events: &mut Vec<egui::Event>) {
+    events.retain(|event| {
+        if let egui::Event::Screenshot {
+            user_data, image, ..
+        } = event
+        {
+            if let Some(sender) = user_data
+                .data
+                .as_ref()
+                .and_then(|data| data.downcast_ref::<ScreenshotSender>())
+            {
+                // The receiver may have been dropped, and that's fine.
+                sender.0.send(image.clone()).ok();
+                return false;
+            }
+        }
+        true
+    });
+}
+
+/// Tell the app about any [`egui::Event::SystemThemeChanged`] in `events`.
+///
+/// With several viewports, every window reports the same change,
+/// so we only call [`App::on_system_theme_changed`] once per actual change.
+pub(crate) fn deliver_system_theme_changes(
+    app: &mut dyn App,
+    egui_ctx: &egui::Context,
+    events: &[egui::Event],
+) {
+    let mut current_theme = egui_ctx.system_theme();
+    for event in events {
+        if let egui::Event::SystemThemeChanged(theme) = event {
+            if current_theme != Some(*theme) {
+                log::debug!("System theme changed to {theme:?}");
+                current_theme = Some(*theme);
+                app.on_system_theme_changed(egui_ctx, *theme);
+            }
+        }
+    }
+}
+
+/// When to keep the display awake, see [`Frame::set_inhibit_sleep`].
+///
+/// Useful for media players and dashboards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum InhibitSleep {
+    /// Let the display sleep as usual.
+    #[default]
+    Never,
+
+    /// Keep the display awake for as long as the app is open.
+    ///
+    /// On web, this only applies while the page is visible.
+    WhileOpen,
+
+    /// Keep the display awake while the app has keyboard focus.
+    WhileFocused,
+}
+
+impl InhibitSleep {
+    pub(crate) fn should_inhibit(self, has_focus: bool) -> bool {
+        match self {
+            Self::Never => false,
+            Self::WhileOpen => true,
+            Self::WhileFocused => has_focus,
+        }
+    }
+}
+
 /// Options controlling the behavior of a native window.
 ///
 /// Additional windows can be opened using (egui viewports)[`egui::viewport`].
@@ -376,8 +727,17 @@ pub struct NativeOptions {
 
     /// The folder where `eframe` will store the app state. If not set, eframe will use a default
     /// data storage path for each target system.
+    ///
+    /// If set, this takes precedence over [`Self::storage_location`].
     pub persistence_path: Option<std::path::PathBuf>,
 
+    /// Where to store the app state, e.g. next to the executable for a portable install.
+    ///
+    /// See also [`crate::migrate_storage`].
+    ///
+    /// Default: [`StorageLocation::UserData`].
+    pub storage_location: StorageLocation,
+
     /// Controls whether to apply dithering to minimize banding artifacts.
     ///
     /// Dithering assumes an sRGB output and thus will apply noise to any input value that lies between
@@ -387,6 +747,52 @@ pub struct NativeOptions {
     /// Defaults to true.
     pub dithering: bool,
 
+    /// Whether to lay out the UI from right to left.
+    ///
+    /// This applies to all viewports. You can change it at runtime with [`egui::Style::right_to_left`].
+    ///
+    /// Default: [`LayoutDirection::LeftToRight`].
+    pub layout_direction: LayoutDirection,
+
+    /// Report panics instead of letting the app die silently.
+    ///
+    /// While the app runs, eframe installs a panic hook which calls [`Self::on_panic`]
+    /// with the message, location and backtrace of any panic, on any thread
+    /// (so deferred viewports are covered too).
+    /// The previous panic hook is still called, and is restored when the app exits.
+    ///
+    /// Panics in [`App::update`] are caught: from then on a minimal error screen
+    /// with the report and a "Copy report" button is shown instead of the app,
+    /// and [`App::save`] is skipped so the broken state isn't persisted.
+    ///
+    /// Other panics, and all panics with `panic = "abort"`, still end the app.
+    /// With the `persistence` feature their report is saved next to the app state,
+    /// and shown in the same error screen the next time the app starts.
+    ///
+    /// Defaults to `false`.
+    pub report_panics: bool,
+
+    /// Called with the report when [`Self::report_panics`] sees a panic,
+    /// e.g. to log it or upload it to your crash reporting service.
+    pub on_panic: Option<PanicHook>,
+
+    /// Save and close the app when the OS ends the session or asks it to terminate,
+    /// see [`LifecycleEvent::SessionEnding`].
+    ///
+    /// On Unix this installs handlers for `SIGTERM` and `SIGHUP` while the app runs,
+    /// unless the process already handles them, and restores the previous handlers when it exits.
+    /// A second signal terminates the process right away.
+    /// On Windows this listens for `WM_QUERYENDSESSION`. If the app has [unsaved changes](Frame::set_unsaved_changes),
+    /// the end of the session is held off (showing "Saving…" as the reason) until the app has been saved.
+    ///
+    /// Defaults to `false`.
+    pub handle_session_end: bool,
+
+    /// How long to wait for [`App::shutdown_hook`] before exiting anyway.
+    ///
+    /// Defaults to 2 seconds.
+    pub shutdown_timeout: std::time::Duration,
+
     /// Android application for `winit`'s event loop.
     ///
     /// This value is required on Android to correctly create the event loop. See
@@ -414,6 +820,9 @@ impl Clone for NativeOptions {
             wgpu_options: self.wgpu_options.clone(),
 
             persistence_path: self.persistence_path.clone(),
+            storage_location: self.storage_location.clone(),
+
+            on_panic: self.on_panic.clone(),
 
             #[cfg(target_os = "android")]
             android_app: self.android_app.clone(),
@@ -457,9 +866,18 @@ impl Default for NativeOptions {
             persist_window: true,
 
             persistence_path: None,
+            storage_location: StorageLocation::default(),
 
             dithering: true,
 
+            layout_direction: LayoutDirection::default(),
+
+            report_panics: false,
+            on_panic: None,
+
+            handle_session_end: false,
+            shutdown_timeout: std::time::Duration::from_secs(2),
+
             #[cfg(target_os = "android")]
             android_app: None,
         }
@@ -468,6 +886,44 @@ impl Default for NativeOptions {
 
 // ----------------------------------------------------------------------------
 
+/// Where `eframe` stores the app state on native, see [`NativeOptions::storage_location`].
+///
+/// Only used with the "persistence" feature.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum StorageLocation {
+    /// The folder the OS prefers for app data, see [`crate::storage_dir`].
+    #[default]
+    UserData,
+
+    /// A `data` folder next to the executable, for portable installs (e.g. on a USB stick).
+    Portable,
+
+    /// This folder.
+    Folder(std::path::PathBuf),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl StorageLocation {
+    /// [`Self::Portable`] if there is a file called `portable` next to the executable,
+    /// otherwise [`Self::UserData`].
+    ///
+    /// This lets the same build be both installed and run portably.
+    pub fn portable_if_marked() -> Self {
+        let is_marked = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.parent()?.join("portable")))
+            .is_some_and(|marker| marker.is_file());
+        if is_marked {
+            Self::Portable
+        } else {
+            Self::UserData
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 /// Options when using `eframe` in a web page.
 #[cfg(target_arch = "wasm32")]
 pub struct WebOptions {
@@ -483,6 +939,15 @@ pub struct WebOptions {
     #[cfg(feature = "glow")]
     pub webgl_context_option: WebGlContextOption,
 
+    /// Spread the upload of large replacement textures (e.g. a font atlas rebuild)
+    /// over several frames, uploading at most this many bytes per frame.
+    ///
+    /// This avoids frame time spikes, at the cost of briefly showing the old texture.
+    ///
+    /// Default: `None` (upload everything right away).
+    #[cfg(feature = "glow")]
+    pub texture_upload_budget: Option<usize>,
+
     /// Configures wgpu instance/device/adapter/surface creation and renderloop.
     #[cfg(feature = "wgpu")]
     pub wgpu_options: egui_wgpu::WgpuConfiguration,
@@ -496,6 +961,36 @@ pub struct WebOptions {
     /// Defaults to true.
     pub dithering: bool,
 
+    /// Give the canvas keyboard focus as soon as the app has started.
+    ///
+    /// The app can later focus the canvas with [`egui::ViewportCommand::Focus`],
+    /// and check if it has focus with `ctx.input(|i| i.focused)`.
+    ///
+    /// Default: `false`.
+    pub autofocus: bool,
+
+    /// The CSS `outline` of the canvas while it has keyboard focus, e.g. `"2px solid #4af"`.
+    ///
+    /// Default: `None` (no outline).
+    pub focus_outline: Option<String>,
+
+    /// Mirror the [AccessKit](https://accesskit.dev/) tree into hidden DOM elements inside the canvas,
+    /// so that screen readers can navigate the app.
+    ///
+    /// This builds the accessibility tree every frame and keeps a DOM node per widget,
+    /// so it is opt-in.
+    ///
+    /// Default: `false`.
+    #[cfg(feature = "accesskit")]
+    pub accesskit: bool,
+
+    /// Whether to lay out the UI from right to left.
+    ///
+    /// You can change it at runtime with [`egui::Style::right_to_left`].
+    ///
+    /// Default: [`LayoutDirection::LeftToRight`].
+    pub layout_direction: LayoutDirection,
+
     /// If the web event corresponding to an egui event should be propagated
     /// to the rest of the web page.
     ///
@@ -503,6 +998,11 @@ pub struct WebOptions {
     /// [`stopPropagation`](https://developer.mozilla.org/en-US/docs/Web/API/Event/stopPropagation)
     /// is called on every event.
     pub should_propagate_event: Box<dyn Fn(&egui::Event) -> bool>,
+
+    /// How to register the DOM event listener for each event, given the event name (e.g. `"wheel"`).
+    ///
+    /// Default: [`EventListenerOptions::default_for`].
+    pub event_listener_options: fn(&str) -> EventListenerOptions,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -514,12 +1014,73 @@ impl Default for WebOptions {
             #[cfg(feature = "glow")]
             webgl_context_option: WebGlContextOption::BestFirst,
 
+            #[cfg(feature = "glow")]
+            texture_upload_budget: None,
+
             #[cfg(feature = "wgpu")]
             wgpu_options: egui_wgpu::WgpuConfiguration::default(),
 
             dithering: true,
 
+            autofocus: false,
+            focus_outline: None,
+
+            #[cfg(feature = "accesskit")]
+            accesskit: false,
+
+            layout_direction: LayoutDirection::default(),
+
             should_propagate_event: Box::new(|_| false),
+
+            event_listener_options: EventListenerOptions::default_for,
+        }
+    }
+}
+
+/// How `eframe` registers a DOM event listener, see [`WebOptions::event_listener_options`].
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EventListenerOptions {
+    /// A passive listener never calls
+    /// [`preventDefault`](https://developer.mozilla.org/en-US/docs/Web/API/Event/preventDefault),
+    /// so the browser doesn't have to wait for it before e.g. scrolling the page.
+    ///
+    /// This means egui can't stop the browser from also handling the event,
+    /// e.g. from scrolling the page while egui scrolls a [`egui::ScrollArea`].
+    pub passive: bool,
+
+    /// Listen in the capture phase, i.e. before any listeners of the children of the event target.
+    pub capture: bool,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl EventListenerOptions {
+    /// What `eframe` uses by default for the event with this name.
+    ///
+    /// Wheel and touch events are active, as egui needs to prevent the browser from
+    /// scrolling or zooming the page while it uses them.
+    /// Browsers make those passive by default on the `document`, and warn when they are blocked.
+    ///
+    /// Events `eframe` never calls `preventDefault` on are passive, and the rest active.
+    /// Nothing is captured.
+    pub fn default_for(event_name: &str) -> Self {
+        let passive = matches!(
+            event_name,
+            "blur"
+                | "focus"
+                | "focusin"
+                | "fullscreenchange"
+                | "hashchange"
+                | "load"
+                | "pagehide"
+                | "pageshow"
+                | "pointerdown"
+                | "popstate"
+                | "visibilitychange"
+        );
+        Self {
+            passive,
+            capture: false,
         }
     }
 }
@@ -649,6 +1210,28 @@ pub struct Frame {
     /// Raw platform display handle for window
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) raw_display_handle: Result<RawDisplayHandle, HandleError>,
+
+    /// See [`Self::set_inhibit_sleep`].
+    pub(crate) inhibit_sleep: InhibitSleep,
+
+    /// See [`Self::set_unsaved_changes`].
+    pub(crate) unsaved_changes: bool,
+
+    /// See [`Self::set_repaint_mode`].
+    pub(crate) repaint_mode: RepaintMode,
+
+    /// See [`Self::request_screenshot_of`].
+    pub(crate) screenshot_requests: Vec<(egui::ViewportId, ScreenshotSender)>,
+
+    /// See [`Self::start_recording`].
+    pub(crate) recordings: egui::ViewportIdMap<ScreenshotSender>,
+
+    /// See [`Self::set_fullscreen`].
+    pub(crate) fullscreen_request: Option<bool>,
+
+    /// See [`Self::swap_app`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) app_swap: Option<AppCreator<'static>>,
 }
 
 // Implementing `Clone` would violate the guarantees of `HasWindowHandle` and `HasDisplayHandle`.
@@ -690,6 +1273,14 @@ impl Frame {
             storage: None,
             #[cfg(feature = "wgpu")]
             wgpu_render_state: None,
+            inhibit_sleep: InhibitSleep::Never,
+            repaint_mode: RepaintMode::Reactive,
+            unsaved_changes: false,
+            screenshot_requests: Vec::new(),
+            recordings: Default::default(),
+            fullscreen_request: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            app_swap: None,
         }
     }
 
@@ -701,6 +1292,276 @@ impl Frame {
         cfg!(target_arch = "wasm32")
     }
 
+    /// Keep the display from going to sleep, e.g. while playing a video.
+    ///
+    /// The inhibition is released when the policy no longer applies (e.g. on focus loss),
+    /// or when the app is closed.
+    ///
+    /// On native this uses `SetThreadExecutionState` on Windows, an `IOPMAssertion` on Mac,
+    /// and `org.freedesktop.ScreenSaver` on Linux (requires the `dbus` feature).
+    /// On web this uses the [Screen Wake Lock API](https://developer.mozilla.org/en-US/docs/Web/API/Screen_Wake_Lock_API).
+    ///
+    /// The default is [`InhibitSleep::Never`].
+    pub fn set_inhibit_sleep(&mut self, inhibit_sleep: InhibitSleep) {
+        self.inhibit_sleep = inhibit_sleep;
+    }
+
+    /// See [`Self::set_inhibit_sleep`].
+    pub fn inhibit_sleep(&self) -> InhibitSleep {
+        self.inhibit_sleep
+    }
+
+    /// Limit how often the app repaints, to save battery.
+    ///
+    /// Can be changed at any time. The default is [`RepaintMode::Reactive`].
+    pub fn set_repaint_mode(&mut self, repaint_mode: RepaintMode) {
+        self.repaint_mode = repaint_mode;
+    }
+
+    /// See [`Self::set_repaint_mode`].
+    pub fn repaint_mode(&self) -> RepaintMode {
+        self.repaint_mode
+    }
+
+    /// Take a screenshot of a viewport.
+    ///
+    /// The screenshot is sent to the returned receiver once that viewport has been painted,
+    /// which is usually a frame or two later, so poll it with `try_recv`.
+    /// If the viewport is closed before that, the receiver is disconnected instead.
+    ///
+    /// This is a convenience over sending [`egui::ViewportCommand::Screenshot`] and
+    /// looking for [`egui::Event::Screenshot`] in the input of the right viewport.
+    ///
+    /// On web, only [`egui::ViewportId::ROOT`] is supported.
+    pub fn request_screenshot_of(&mut self, viewport_id: egui::ViewportId) -> ScreenshotReceiver {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.screenshot_requests
+            .push((viewport_id, ScreenshotSender(sender)));
+        receiver
+    }
+
+    /// Capture a viewport continuously, e.g. for screen recording or exporting a gif.
+    ///
+    /// While recording, the app repaints continuously, and a screenshot of the viewport is sent
+    /// to the returned receiver for each frame of the root viewport.
+    /// Drain it with `try_iter` each frame to not run out of memory,
+    /// and call [`Self::stop_recording`] when you are done.
+    ///
+    /// Replaces any earlier recording of the same viewport.
+    pub fn start_recording(&mut self, viewport_id: egui::ViewportId) -> ScreenshotReceiver {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.recordings
+            .insert(viewport_id, ScreenshotSender(sender));
+        receiver
+    }
+
+    /// Stop a recording started with [`Self::start_recording`].
+    ///
+    /// Screenshots that are already in flight may still arrive.
+    pub fn stop_recording(&mut self, viewport_id: egui::ViewportId) {
+        self.recordings.remove(&viewport_id);
+    }
+
+    /// Are we recording this viewport? See [`Self::start_recording`].
+    pub fn is_recording(&self, viewport_id: egui::ViewportId) -> bool {
+        self.recordings.contains_key(&viewport_id)
+    }
+
+    /// Enter or leave fullscreen, on both native and web.
+    ///
+    /// On native this is the same as sending [`egui::ViewportCommand::Fullscreen`] to the root viewport.
+    /// On web the canvas is made fullscreen with the
+    /// [Fullscreen API](https://developer.mozilla.org/en-US/docs/Web/API/Fullscreen_API).
+    /// Browsers only allow that in response to user input (e.g. a click),
+    /// and let the user leave fullscreen with `Esc` at any time.
+    ///
+    /// Read the current state from [`egui::ViewportInfo::fullscreen`].
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.fullscreen_request = Some(fullscreen);
+    }
+
+    /// Replace the running [`App`] with a new one, e.g. after hot-reloading a plugin.
+    ///
+    /// The window, the painter and the egui memory are all kept,
+    /// so the user sees no difference apart from what the new app shows.
+    ///
+    /// The swap happens at the end of the current frame, once it has been painted and presented:
+    /// * [`App::save`] of the old app is called with an in-memory [`Storage`].
+    /// * `app_creator` is called, and can read what the old app saved from
+    ///   [`CreationContext::storage`]. Keys the old app did not save are read from the
+    ///   persistent storage instead.
+    /// * [`App::on_exit`] of the old app is called, and the old app is dropped.
+    ///
+    /// If `app_creator` fails, the error is logged and the old app keeps running.
+    ///
+    /// Only available on native.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn swap_app(&mut self, app_creator: AppCreator<'static>) {
+        self.app_swap = Some(app_creator);
+    }
+
+    /// Set the commands in the menu of the app in the OS shell:
+    /// the taskbar jump list on Windows, and the dock menu on Mac.
+    ///
+    /// When the user picks one, you get a [`ShellEvent::Command`] in [`App::on_shell_event`].
+    /// Replaces the tasks set before. An empty list removes the jump list on Windows.
+    ///
+    /// Only available on native, and does nothing on other platforms than Windows and Mac.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(clippy::unused_self)]
+    pub fn set_shell_tasks(&self, tasks: Vec<ShellTask>) {
+        crate::native::shell_integration::set_tasks(tasks);
+    }
+
+    /// Tell the OS that the user opened (or saved) this document,
+    /// so that it is listed among the recent documents of the app.
+    ///
+    /// On Windows they are listed in the jump list, and on Mac in the dock menu
+    /// and in the "Open Recent" menu. In both cases the app must be registered to open the
+    /// file type (in the registry on Windows, or with `CFBundleDocumentTypes` in `Info.plist` on Mac),
+    /// or else the OS ignores it. Opening one gives you a [`ShellEvent::OpenFiles`] on Mac.
+    ///
+    /// Only available on native, and does nothing on other platforms than Windows and Mac.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(clippy::unused_self)]
+    pub fn add_recent_document(&self, path: &std::path::Path) {
+        crate::native::shell_integration::add_recent_document(path);
+    }
+
+    /// Take the app requested with [`Self::swap_app`], if any.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn take_app_swap(&mut self) -> Option<AppCreator<'static>> {
+        self.app_swap.take()
+    }
+
+    /// Send the viewport commands for [`Self::request_screenshot_of`], [`Self::start_recording`]
+    /// and [`Self::set_fullscreen`].
+    ///
+    /// Called at the end of each pass of the root viewport.
+    pub(crate) fn send_viewport_commands(&mut self, egui_ctx: &egui::Context) {
+        if let Some(fullscreen) = self.fullscreen_request.take() {
+            egui_ctx.send_viewport_cmd_to(
+                egui::ViewportId::ROOT,
+                egui::ViewportCommand::Fullscreen(fullscreen),
+            );
+        }
+
+        for (viewport_id, sender) in self.screenshot_requests.drain(..) {
+            egui_ctx.send_viewport_cmd_to(
+                viewport_id,
+                egui::ViewportCommand::Screenshot(egui::UserData::new(sender)),
+            );
+        }
+
+        for (&viewport_id, sender) in &self.recordings {
+            egui_ctx.send_viewport_cmd_to(
+                viewport_id,
+                egui::ViewportCommand::Screenshot(egui::UserData::new(sender.clone())),
+            );
+            egui_ctx.request_repaint_of(viewport_id);
+        }
+        if !self.recordings.is_empty() {
+            egui_ctx.request_repaint();
+        }
+    }
+
+    /// Save some data as a file, e.g. for an "Export CSV" button.
+    ///
+    /// On web, this shows a save dialog if the browser supports it,
+    /// otherwise the file is downloaded as `filename`.
+    /// See `eframe::web::save_file`.
+    ///
+    /// On native, `filename` must be an absolute path, which is written to directly,
+    /// and `mime` is ignored.
+    /// Use a file dialog crate (like [`rfd`](https://crates.io/crates/rfd)) if you want the user to pick the path.
+    ///
+    /// # Errors
+    /// If the file could not be written, or the browser refused to save it.
+    /// On native, also if `filename` is a relative path: what it is relative to
+    /// (the working directory of the process) is up to how the app was started.
+    #[allow(clippy::unused_self)]
+    pub fn save_file(&self, bytes: &[u8], filename: &str, mime: &str) -> Result<(), String> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            crate::web::save_file(bytes, filename, mime)
+                .map_err(|err| crate::web::string_from_js_value(&err))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            _ = mime;
+            if !std::path::Path::new(filename).is_absolute() {
+                return Err(format!(
+                    "Can't save {filename:?}: the path must be absolute on native"
+                ));
+            }
+            std::fs::write(filename, bytes)
+                .map_err(|err| format!("Failed to write {filename:?}: {err}"))
+        }
+    }
+
+    /// Set the `#fragment` part of the URL, adding a new entry to the browser history.
+    ///
+    /// This will also result in a [`NavigationEvent::HashChange`] next frame.
+    #[cfg(target_arch = "wasm32")]
+    pub fn set_location_hash(&mut self, hash: &str) {
+        match crate::web::set_location_hash(hash) {
+            Ok(()) => self.info.web_info.location.hash = crate::web::location_hash(),
+            Err(err) => log::warn!(
+                "Failed to set location hash: {}",
+                crate::web::string_from_js_value(&err)
+            ),
+        }
+    }
+
+    /// Navigate to `url` without reloading the page, adding a new entry to the browser history
+    /// (`history.pushState`).
+    ///
+    /// `url` may be relative, e.g. `"/settings"` or `"?tab=2"`.
+    /// The `state` is given back in [`NavigationEvent::PopState`] when the user navigates back to this entry.
+    #[cfg(target_arch = "wasm32")]
+    pub fn push_history_state(&mut self, url: &str, state: Option<&str>) {
+        match crate::web::push_history_state(url, state) {
+            Ok(()) => self.info.web_info.location = crate::web::web_location(),
+            Err(err) => log::warn!(
+                "Failed to push history state: {}",
+                crate::web::string_from_js_value(&err)
+            ),
+        }
+    }
+
+    /// Like [`Self::push_history_state`], but replaces the current history entry
+    /// (`history.replaceState`).
+    #[cfg(target_arch = "wasm32")]
+    pub fn replace_history_state(&mut self, url: &str, state: Option<&str>) {
+        match crate::web::replace_history_state(url, state) {
+            Ok(()) => self.info.web_info.location = crate::web::web_location(),
+            Err(err) => log::warn!(
+                "Failed to replace history state: {}",
+                crate::web::string_from_js_value(&err)
+            ),
+        }
+    }
+
+    /// Tell eframe whether the app has unsaved changes.
+    ///
+    /// On web, while set, the browser asks the user to confirm before they close the tab,
+    /// reload the page or navigate away from it.
+    /// If they choose to stay, you get a `NavigationEvent::LeaveAttempted` next frame,
+    /// e.g. to offer to save - much like [`egui::ViewportCommand::CancelClose`] on native.
+    /// Browsers don't let you change the text of the confirmation dialog.
+    ///
+    /// On Windows, with [`crate::NativeOptions::handle_session_end`], logging off or shutting down
+    /// is held off while set, until the app has been saved.
+    pub fn set_unsaved_changes(&mut self, unsaved_changes: bool) {
+        self.unsaved_changes = unsaved_changes;
+    }
+
+    /// See [`Self::set_unsaved_changes`].
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_changes
+    }
+
     /// Information about the integration.
     pub fn info(&self) -> &IntegrationInfo {
         &self.info
@@ -762,6 +1623,83 @@ pub struct WebInfo {
 
     /// Information about the URL.
     pub location: Location,
+
+    /// What browser features are available to the app.
+    pub capabilities: WebCapabilities,
+
+    /// Navigation that happened since the last frame, oldest first.
+    ///
+    /// [`Self::location`] is already updated to the latest URL.
+    pub navigation_events: Vec<NavigationEvent>,
+}
+
+/// The user navigated within the page (e.g. with the back button), or tried to leave it.
+///
+/// See [`WebInfo::navigation_events`].
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NavigationEvent {
+    /// The `#fragment` part of the URL changed (`hashchange`).
+    HashChange {
+        /// The new hash, including the leading `#`, percent-decoded.
+        hash: String,
+    },
+
+    /// The user moved back or forward through history (`popstate`).
+    PopState {
+        /// The new URL, percent-decoded.
+        url: String,
+
+        /// The state passed to [`Frame::push_history_state`] for this history entry, if any.
+        state: Option<String>,
+    },
+
+    /// The user tried to leave the page while [`Frame::set_unsaved_changes`] was set,
+    /// and chose to stay when the browser asked them to confirm.
+    LeaveAttempted,
+}
+
+/// Which browser features are available, detected once at startup.
+///
+/// Many of these depend on the page being served from a secure context (HTTPS or localhost),
+/// and [`Self::threads`] additionally requires the page to be
+/// [cross-origin isolated](https://developer.mozilla.org/en-US/docs/Web/API/crossOriginIsolated)
+/// (i.e. served with the `Cross-Origin-Opener-Policy: same-origin`
+/// and `Cross-Origin-Embedder-Policy: require-corp` headers).
+///
+/// A `true` value means the API is present, not that the user has granted permission to use it.
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WebCapabilities {
+    /// Is the page served from a [secure context](https://developer.mozilla.org/en-US/docs/Web/Security/Secure_Contexts)?
+    pub secure_context: bool,
+
+    /// Is the page cross-origin isolated (COOP/COEP headers set)?
+    pub cross_origin_isolated: bool,
+
+    /// Can we use `SharedArrayBuffer`, and thus threads?
+    ///
+    /// Requires [`Self::cross_origin_isolated`].
+    pub threads: bool,
+
+    /// Can we read text and images from the system clipboard?
+    pub clipboard_read: bool,
+
+    /// Can we write text and images to the system clipboard?
+    pub clipboard_write: bool,
+
+    /// Is `localStorage` available (used by [`Storage`] for persistence)?
+    pub local_storage: bool,
+
+    /// Can we ask the browser to make our storage persistent
+    /// (`navigator.storage.persist`)?
+    pub persistent_storage: bool,
+
+    /// Is WebGPU available (`navigator.gpu`)?
+    pub webgpu: bool,
+
+    /// Can we lock the pointer to the canvas (`requestPointerLock`)?
+    pub pointer_lock: bool,
 }
 
 /// Information about the URL.
@@ -769,6 +1707,7 @@ pub struct WebInfo {
 /// Everything has been percent decoded (`%20` -> ` ` etc).
 #[cfg(target_arch = "wasm32")]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Location {
     /// The full URL (`location.href`) without the hash, percent-decoded.
     ///
@@ -834,6 +1773,13 @@ pub struct IntegrationInfo {
     ///
     /// `None` if this is the first frame.
     pub cpu_usage: Option<f32>,
+
+    /// Is the preferred language of the user (from the OS or the browser) written right to left,
+    /// like Arabic or Hebrew?
+    ///
+    /// Detected at startup, and read again when the native window gains focus
+    /// or when the browser tells us the language changed.
+    pub prefers_right_to_left: bool,
 }
 
 impl IntegrationInfo {
@@ -853,8 +1799,11 @@ impl IntegrationInfo {
                     query_map: Default::default(),
                     origin: "http://localhost".to_owned(),
                 },
+                capabilities: Default::default(),
+                navigation_events: Default::default(),
             },
             cpu_usage: None,
+            prefers_right_to_left: false,
         }
     }
 }
@@ -906,3 +1855,237 @@ pub fn set_value<T: serde::Serialize>(storage: &mut dyn Storage, key: &str, valu
 
 /// [`Storage`] key used for app
 pub const APP_KEY: &str = "app";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn save_file_needs_an_absolute_path() {
+        let frame = Frame::_new_kittest();
+
+        let err = frame
+            .save_file(b"a,b", "export.csv", "text/csv")
+            .unwrap_err();
+        assert!(err.contains("absolute"), "{err}");
+        assert!(!std::path::Path::new("export.csv").exists());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.csv");
+        frame
+            .save_file(b"a,b", path.to_str().unwrap(), "text/csv")
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"a,b");
+    }
+
+    #[test]
+    fn repaint_mode_min_frame_interval() {
+        // Rounded, since the interval is computed from an `f32`:
+        let millis = |mode: RepaintMode, monitor_refresh_rate| {
+            mode.min_frame_interval(monitor_refresh_rate)
+                .map(|interval| (interval.as_secs_f64() * 1000.0).round())
+        };
+
+        assert_eq!(millis(RepaintMode::Reactive, Some(60.0)), None);
+        assert_eq!(millis(RepaintMode::OnInput, Some(60.0)), None);
+
+        assert_eq!(millis(RepaintMode::CappedFps(20.0), Some(60.0)), Some(50.0));
+        assert_eq!(millis(RepaintMode::CappedFps(0.0), None), None);
+        assert_eq!(millis(RepaintMode::CappedFps(-1.0), None), None);
+
+        assert_eq!(
+            millis(RepaintMode::MonitorRefreshRate, Some(50.0)),
+            Some(20.0)
+        );
+        assert_eq!(
+            millis(RepaintMode::MonitorRefreshRate, None),
+            None,
+            "Unknown refresh rate means no limit"
+        );
+    }
+
+    #[test]
+    fn repaint_mode_allows_requested_repaint() {
+        use std::time::Duration;
+
+        let just_now = Duration::ZERO;
+        let long_ago = Duration::from_secs(60);
+        let grace = RepaintMode::INPUT_GRACE_PERIOD;
+
+        for mode in [
+            RepaintMode::Reactive,
+            RepaintMode::CappedFps(30.0),
+            RepaintMode::MonitorRefreshRate,
+        ] {
+            assert!(mode.allows_requested_repaint(just_now));
+            assert!(mode.allows_requested_repaint(long_ago));
+        }
+
+        assert!(RepaintMode::OnInput.allows_requested_repaint(just_now));
+        assert!(RepaintMode::OnInput.allows_requested_repaint(grace / 2));
+        assert!(!RepaintMode::OnInput.allows_requested_repaint(grace));
+        assert!(!RepaintMode::OnInput.allows_requested_repaint(long_ago));
+    }
+
+    #[test]
+    fn system_theme_changes_reach_the_app_once() {
+        #[derive(Default)]
+        struct ThemeApp {
+            changes: Vec<egui::Theme>,
+        }
+
+        impl App for ThemeApp {
+            fn update(&mut self, _ctx: &egui::Context, _frame: &mut Frame) {}
+
+            fn on_system_theme_changed(&mut self, _ctx: &egui::Context, theme: egui::Theme) {
+                self.changes.push(theme);
+            }
+        }
+
+        use egui::{Event::SystemThemeChanged, Theme};
+
+        let ctx = egui::Context::default();
+        let _ = ctx.run(
+            egui::RawInput {
+                system_theme: Some(Theme::Light),
+                ..Default::default()
+            },
+            |_| {},
+        );
+
+        let mut app = ThemeApp::default();
+
+        // Every window reports the same change:
+        let events = [
+            SystemThemeChanged(Theme::Dark),
+            egui::Event::Copy,
+            SystemThemeChanged(Theme::Dark),
+        ];
+        deliver_system_theme_changes(&mut app, &ctx, &events);
+        assert_eq!(app.changes, [Theme::Dark]);
+
+        // Flipping back and forth within a frame is reported as it happens:
+        app.changes.clear();
+        let events = [
+            SystemThemeChanged(Theme::Dark),
+            SystemThemeChanged(Theme::Light),
+        ];
+        deliver_system_theme_changes(&mut app, &ctx, &events);
+        assert_eq!(app.changes, [Theme::Dark, Theme::Light]);
+
+        // Reporting the theme we already have is not a change:
+        app.changes.clear();
+        deliver_system_theme_changes(&mut app, &ctx, &[SystemThemeChanged(Theme::Light)]);
+        assert!(app.changes.is_empty());
+
+        // Custom visuals for each theme follow the system theme:
+        let mut dark_visuals = egui::Visuals::dark();
+        dark_visuals.panel_fill = egui::Color32::DARK_RED;
+        ctx.set_visuals_of(Theme::Dark, dark_visuals);
+        let _ = ctx.run(
+            egui::RawInput {
+                system_theme: Some(Theme::Dark),
+                ..Default::default()
+            },
+            |_| {},
+        );
+        assert_eq!(ctx.style().visuals.panel_fill, egui::Color32::DARK_RED);
+    }
+
+    #[test]
+    fn deliver_screenshots_takes_only_frame_screenshots() {
+        let deferred = egui::ViewportId::from_hash_of("deferred");
+        let immediate = egui::ViewportId::from_hash_of("immediate");
+        let image = std::sync::Arc::new(egui::ColorImage::new([1, 1], egui::Color32::RED));
+
+        let mut receivers = vec![];
+        let screenshot = |viewport_id, user_data| egui::Event::Screenshot {
+            viewport_id,
+            user_data,
+            image: image.clone(),
+        };
+        let mut frame_screenshot = |viewport_id| {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            receivers.push(receiver);
+            screenshot(viewport_id, egui::UserData::new(ScreenshotSender(sender)))
+        };
+
+        // Each viewport delivers its own input, so test them one at a time:
+        for viewport_id in [deferred, immediate] {
+            let mut events = vec![
+                frame_screenshot(viewport_id),
+                egui::Event::Copy,
+                screenshot(viewport_id, egui::UserData::new("requested by the app")),
+            ];
+            deliver_screenshots(&mut events);
+
+            assert_eq!(events.len(), 2, "Only our own screenshot should be taken");
+            assert!(matches!(events[1], egui::Event::Screenshot { .. }));
+        }
+
+        for receiver in receivers {
+            assert_eq!(receiver.try_recv().unwrap(), image);
+        }
+    }
+
+    /// What the backend does with the [`egui::ViewportCommand::Screenshot`]s in `output`,
+    /// minus the actual rendering.
+    fn take_screenshots(
+        output: &egui::FullOutput,
+        image: &std::sync::Arc<egui::ColorImage>,
+    ) -> Vec<egui::Event> {
+        let mut events = vec![];
+        for (&viewport_id, viewport_output) in &output.viewport_output {
+            for command in &viewport_output.commands {
+                if let egui::ViewportCommand::Screenshot(user_data) = command {
+                    events.push(egui::Event::Screenshot {
+                        viewport_id,
+                        user_data: user_data.clone(),
+                        image: image.clone(),
+                    });
+                }
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn frame_screenshots_reach_their_receivers() {
+        let child = egui::ViewportId::from_hash_of("child");
+        let image = std::sync::Arc::new(egui::ColorImage::new([1, 1], egui::Color32::RED));
+
+        let ctx = egui::Context::default();
+        ctx.set_embed_viewports(false); // like eframe on native
+        let mut frame = Frame::_new_kittest();
+        let screenshot = frame.request_screenshot_of(child);
+        let recording = frame.start_recording(egui::ViewportId::ROOT);
+
+        let run = |frame: &mut Frame| {
+            let output = ctx.run(Default::default(), |ctx| {
+                ctx.show_viewport_deferred(child, egui::ViewportBuilder::default(), |_, _| {});
+                ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot(egui::UserData::new(
+                    "requested by the app",
+                )));
+                frame.send_viewport_commands(ctx);
+            });
+            let mut events = take_screenshots(&output, &image);
+            deliver_screenshots(&mut events);
+            events
+        };
+
+        let events = run(&mut frame);
+        assert_eq!(events.len(), 1, "The screenshot of the app is left for it");
+        assert_eq!(screenshot.try_iter().count(), 1);
+        assert_eq!(recording.try_iter().count(), 1);
+
+        // A screenshot is taken once, a recording every frame:
+        run(&mut frame);
+        assert_eq!(screenshot.try_iter().count(), 0);
+        assert_eq!(recording.try_iter().count(), 1);
+
+        frame.stop_recording(egui::ViewportId::ROOT);
+        run(&mut frame);
+        assert_eq!(recording.try_iter().count(), 0);
+    }
+}
@@ -158,6 +158,7 @@ mod epi;
 // Re-export everything in `epi` so `eframe` users don't have to care about what `epi` is:
 pub use epi::*;
 
+pub(crate) mod locale;
 pub(crate) mod stopwatch;
 
 // ----------------------------------------------------------------------------
@@ -173,7 +174,7 @@ pub use web_sys;
 pub mod web;
 
 #[cfg(target_arch = "wasm32")]
-pub use web::{WebLogger, WebRunner};
+pub use web::{MultiRunner, WebLogger, WebRunner};
 
 // ----------------------------------------------------------------------------
 // When compiling natively
@@ -185,7 +186,11 @@ mod native;
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(any(feature = "glow", feature = "wgpu"))]
 #[cfg(feature = "persistence")]
-pub use native::file_storage::storage_dir;
+pub use native::file_storage::{migrate_storage, storage_dir};
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(any(feature = "glow", feature = "wgpu"))]
+pub use native::shell_integration::{args_os, parse_shell_command};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod icon_data;
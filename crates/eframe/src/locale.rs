@@ -0,0 +1,92 @@
+//! Detect the preferred language of the user, for [`crate::LayoutDirection::FromLocale`].
+
+/// Is the preferred language of the user written right to left?
+pub(crate) fn prefers_right_to_left() -> bool {
+    system_language().is_some_and(|language| is_right_to_left_language(&language))
+}
+
+/// Read the preferred language of the user again, since it can change while the app is running.
+///
+/// If the direction changed, [`crate::IntegrationInfo::prefers_right_to_left`] is updated,
+/// and `layout_direction` is applied again.
+pub(crate) fn refresh(
+    info: &mut crate::IntegrationInfo,
+    layout_direction: crate::LayoutDirection,
+    egui_ctx: &egui::Context,
+) {
+    let prefers_right_to_left = prefers_right_to_left();
+    if prefers_right_to_left != info.prefers_right_to_left {
+        log::debug!("The preferred language changed. Right to left: {prefers_right_to_left}");
+        info.prefers_right_to_left = prefers_right_to_left;
+        layout_direction.apply(egui_ctx, prefers_right_to_left);
+        egui_ctx.request_repaint();
+    }
+}
+
+/// The preferred language of the user,
+/// e.g. `"ar-EG"` (BCP 47, on Windows and web) or `"he_IL.UTF-8"` (POSIX locale).
+#[cfg(target_arch = "wasm32")]
+fn system_language() -> Option<String> {
+    web_sys::window()?.navigator().language()
+}
+
+/// The preferred language of the user,
+/// e.g. `"ar-EG"` (BCP 47, on Windows and web) or `"he_IL.UTF-8"` (POSIX locale).
+#[cfg(windows)]
+fn system_language() -> Option<String> {
+    use windows_sys::Win32::Globalization::{GetUserDefaultLocaleName, LOCALE_NAME_MAX_LENGTH};
+
+    let mut buffer = [0_u16; LOCALE_NAME_MAX_LENGTH as usize];
+    // SAFETY: the buffer is as long as we say it is.
+    #[allow(unsafe_code)]
+    let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+
+    // `len` includes the null terminator, and is 0 on failure:
+    let len = (len as usize).checked_sub(1)?;
+    String::from_utf16(&buffer[..len]).ok()
+}
+
+/// The preferred language of the user,
+/// e.g. `"ar-EG"` (BCP 47, on Windows and web) or `"he_IL.UTF-8"` (POSIX locale).
+#[cfg(not(any(target_arch = "wasm32", windows)))]
+fn system_language() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+fn is_right_to_left_language(locale: &str) -> bool {
+    const RIGHT_TO_LEFT_LANGUAGES: &[&str] = &[
+        "ar",  // Arabic
+        "ckb", // Central Kurdish
+        "dv",  // Divehi
+        "fa",  // Persian
+        "he",  // Hebrew
+        "iw",  // Hebrew (old code)
+        "ps",  // Pashto
+        "sd",  // Sindhi
+        "ug",  // Uyghur
+        "ur",  // Urdu
+        "yi",  // Yiddish
+    ];
+
+    let language = locale
+        .split(['-', '_', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    RIGHT_TO_LEFT_LANGUAGES.contains(&language.as_str())
+}
+
+#[test]
+fn test_is_right_to_left_language() {
+    assert!(is_right_to_left_language("ar"));
+    assert!(is_right_to_left_language("ar-EG"));
+    assert!(is_right_to_left_language("he_IL.UTF-8"));
+    assert!(is_right_to_left_language("FA"));
+    assert!(!is_right_to_left_language("en-US"));
+    assert!(!is_right_to_left_language("de_DE.UTF-8"));
+    assert!(!is_right_to_left_language("arn")); // Mapudungun
+    assert!(!is_right_to_left_language(""));
+}
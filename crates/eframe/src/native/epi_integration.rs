@@ -72,9 +72,9 @@ pub fn viewport_builder(
             .primary_monitor()
             .or_else(|| event_loop.available_monitors().next())
         {
-            let monitor_size = monitor
-                .size()
-                .to_logical::<f32>(egui_zoom_factor as f64 * monitor.scale_factor());
+            let monitor_size = monitor.size().to_logical::<f32>(f64::from(
+                egui_zoom_factor * egui_winit::sanitize_scale_factor(monitor.scale_factor()),
+            ));
             let inner_size = inner_size_points.unwrap_or(egui::Vec2 { x: 800.0, y: 600.0 });
             if 0.0 < monitor_size.width && 0.0 < monitor_size.height {
                 let x = (monitor_size.width - inner_size.x) / 2.0;
@@ -92,7 +92,7 @@ pub fn viewport_builder(
 
 pub fn apply_window_settings(
     window: &winit::window::Window,
-    window_settings: Option<WindowSettings>,
+    window_settings: Option<&WindowSettings>,
 ) {
     profiling::function_scope!();
     if let Some(window_settings) = window_settings {
@@ -111,9 +111,9 @@ fn largest_monitor_point_size(egui_zoom_factor: f32, event_loop: &ActiveEventLoo
     };
 
     for monitor in available_monitors {
-        let size = monitor
-            .size()
-            .to_logical::<f32>(egui_zoom_factor as f64 * monitor.scale_factor());
+        let size = monitor.size().to_logical::<f32>(f64::from(
+            egui_zoom_factor * egui_winit::sanitize_scale_factor(monitor.scale_factor()),
+        ));
         let size = egui::vec2(size.width, size.height);
         max_size = max_size.max(size);
     }
@@ -128,9 +128,12 @@ fn largest_monitor_point_size(egui_zoom_factor: f32, event_loop: &ActiveEventLoo
 // ----------------------------------------------------------------------------
 
 /// For loading/saving app state and/or egui memory to disk.
-pub fn create_storage(_app_name: &str) -> Option<Box<dyn epi::Storage>> {
+pub fn create_storage(
+    _app_name: &str,
+    _location: &epi::StorageLocation,
+) -> Option<Box<dyn epi::Storage>> {
     #[cfg(feature = "persistence")]
-    if let Some(storage) = super::file_storage::FileStorage::from_app_id(_app_name) {
+    if let Some(storage) = super::file_storage::FileStorage::from_location(_app_name, _location) {
         return Some(Box::new(storage));
     }
     None
@@ -148,6 +151,191 @@ pub fn create_storage_with_file(_file: impl Into<PathBuf>) -> Option<Box<dyn epi
 
 // ----------------------------------------------------------------------------
 
+/// Carries the state of the old app over to the new one in [`epi::Frame::swap_app`].
+///
+/// What the old app saves is kept in memory.
+/// Keys it did not save are read from the persistent storage.
+pub struct SwapStorage<'a> {
+    saved: std::collections::HashMap<String, String>,
+    persistent: Option<&'a dyn epi::Storage>,
+}
+
+impl<'a> SwapStorage<'a> {
+    /// Let the old app save its state.
+    pub fn save(app: &mut dyn epi::App) -> std::collections::HashMap<String, String> {
+        profiling::function_scope!();
+        let mut storage = SwapStorage {
+            saved: Default::default(),
+            persistent: None,
+        };
+        app.save(&mut storage);
+        storage.saved
+    }
+
+    /// Let the new app load what the old app saved.
+    pub fn new(
+        saved: std::collections::HashMap<String, String>,
+        persistent: Option<&'a dyn epi::Storage>,
+    ) -> Self {
+        Self { saved, persistent }
+    }
+}
+
+impl epi::Storage for SwapStorage<'_> {
+    fn get_string(&self, key: &str) -> Option<String> {
+        self.saved
+            .get(key)
+            .cloned()
+            .or_else(|| self.persistent?.get_string(key))
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        self.saved.insert(key.to_owned(), value);
+    }
+
+    fn flush(&mut self) {}
+}
+
+/// Replace `app` with the one made by `create`, see [`epi::Frame::swap_app`].
+///
+/// Only call this once the last frame of the old app has been painted and presented:
+/// `on_exit` is called with the old app, which may then free resources
+/// that the paint callbacks of that frame use.
+///
+/// If `create` fails, the error is logged and the old app is kept.
+pub fn swap_app<'app>(
+    app: &mut Box<dyn 'app + epi::App>,
+    persistent: Option<&dyn epi::Storage>,
+    create: impl FnOnce(
+        &SwapStorage<'_>,
+    )
+        -> Result<Box<dyn 'app + epi::App>, Box<dyn std::error::Error + Send + Sync>>,
+    on_exit: impl FnOnce(&mut dyn epi::App),
+) {
+    profiling::function_scope!();
+
+    let saved = SwapStorage::save(app.as_mut());
+    let storage = SwapStorage::new(saved, persistent);
+
+    match create(&storage) {
+        Ok(new_app) => {
+            let mut old_app = std::mem::replace(app, new_app);
+            on_exit(old_app.as_mut());
+        }
+        Err(err) => {
+            log::error!("Failed to swap app, keeping the old one: {err}");
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Where a viewport is in its close lifecycle, see [`egui::ViewportEvent::Close`].
+///
+/// Each viewport has one, root, deferred and immediate alike.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CloseLifecycle {
+    /// The viewport was asked to close and nobody canceled it.
+    ///
+    /// The next frame gets [`egui::ViewportEvent::Closing`], and is the last one.
+    closing: bool,
+
+    /// The last frame of this child viewport was rendered, yet it is still shown.
+    ///
+    /// Its window is destroyed, and not created again until the viewport stops being shown.
+    closed: bool,
+}
+
+impl CloseLifecycle {
+    /// Is the viewport waiting for its last frame?
+    pub fn is_closing(self) -> bool {
+        self.closing
+    }
+
+    /// Has the last frame of this child viewport been rendered?
+    ///
+    /// Then it should have no window, even if it is still shown.
+    pub fn is_closed(self) -> bool {
+        self.closed
+    }
+
+    /// Call before a pass of the viewport, with the info that goes into its input.
+    pub fn begin_pass(self, info: &mut egui::ViewportInfo) {
+        if self.closing {
+            info.events.push(egui::ViewportEvent::Closing);
+        }
+    }
+
+    /// Call after a pass of the viewport.
+    ///
+    /// `close_requested` is [`egui::ViewportInfo::close_requested`] of the input of the pass,
+    /// and `output` is the output of the viewport, if it is still shown.
+    ///
+    /// Returns `true` if the viewport should be repainted right away to render its last frame.
+    /// Don't leave that to [`egui::Context::request_repaint`],
+    /// which the [`epi::RepaintMode`] may ignore.
+    pub fn end_pass(
+        &mut self,
+        viewport_id: ViewportId,
+        close_requested: bool,
+        output: Option<&egui::ViewportOutput>,
+    ) -> bool {
+        if std::mem::take(&mut self.closing) {
+            log::debug!("Rendered the last frame of {viewport_id:?}");
+            // The root exits instead.
+            self.closed = viewport_id != ViewportId::ROOT;
+            return false;
+        }
+
+        let Some(output) = output else {
+            return false; // No longer shown, so it is already gone.
+        };
+
+        if close_requested {
+            if output
+                .commands
+                .contains(&egui::ViewportCommand::CancelClose)
+            {
+                log::debug!(
+                    "Closing of {viewport_id:?} canceled with ViewportCommand::CancelClose"
+                );
+            } else {
+                log::debug!("Closing {viewport_id:?} (ViewportCommand::CancelClose was not sent)");
+                self.closing = true;
+            }
+        }
+
+        self.closing
+    }
+}
+
+/// The viewports that are no longer shown, i.e. not in the `viewport_output` of the last pass.
+pub fn removed_viewports(
+    viewports: impl IntoIterator<Item = egui::ViewportIdPair>,
+    viewport_output: &egui::ViewportIdMap<egui::ViewportOutput>,
+) -> Vec<egui::ViewportIdPair> {
+    viewports
+        .into_iter()
+        .filter(|ids| !viewport_output.contains_key(&ids.this))
+        .collect()
+}
+
+/// Tell the parent of a viewport that it is gone, with [`egui::ViewportEvent::ChildDestroyed`].
+///
+/// `parent_info` is `None` if the parent is gone too.
+pub fn child_destroyed(
+    egui_ctx: &egui::Context,
+    parent_info: Option<&mut egui::ViewportInfo>,
+    ids: egui::ViewportIdPair,
+) {
+    if let Some(parent_info) = parent_info {
+        parent_info
+            .events
+            .push(egui::ViewportEvent::ChildDestroyed(ids.this));
+        egui_ctx.request_repaint_of(ids.parent);
+    }
+}
+
 /// Everything needed to make a winit-based integration for [`epi`].
 ///
 /// Only one instance per app (not one per viewport).
@@ -166,6 +354,31 @@ pub struct EpiIntegration {
     #[cfg(feature = "persistence")]
     persist_window: bool,
     app_icon_setter: super::app_icon::AppTitleIconSetter,
+
+    /// Set if [`epi::NativeOptions::report_panics`] is enabled.
+    /// Restores the previous panic hook on drop.
+    panic_reporter: Option<super::panic_screen::PanicReporter>,
+
+    /// The report of a panic from the last time the app ran, shown until the user dismisses it.
+    previous_panic_report: Option<String>,
+
+    /// The report of a panic caught in [`epi::App::update`], shown instead of the app from then on.
+    caught_panic_report: Option<String>,
+
+    /// Shown to the user by some operating systems when we inhibit sleep.
+    app_name: String,
+
+    /// Held while [`epi::Frame::inhibit_sleep`] applies. Released on drop.
+    sleep_inhibitor: Option<egui_winit::SleepInhibitor>,
+
+    /// Do we want to inhibit sleep? Tracked separately so we don't retry (and warn) every frame if it fails.
+    sleep_inhibited: bool,
+
+    /// Applied again when the preferred language of the user changes.
+    layout_direction: epi::LayoutDirection,
+
+    /// See [`epi::NativeOptions::shutdown_timeout`].
+    shutdown_timeout: std::time::Duration,
 }
 
 impl EpiIntegration {
@@ -182,8 +395,35 @@ impl EpiIntegration {
         >,
         #[cfg(feature = "wgpu")] wgpu_render_state: Option<egui_wgpu::RenderState>,
     ) -> Self {
+        let (panic_reporter, previous_panic_report) = if native_options.report_panics {
+            let app_id = native_options
+                .viewport
+                .app_id
+                .as_deref()
+                .unwrap_or(app_name);
+            let report_path = super::panic_screen::report_path(native_options, app_id);
+            let previous_panic_report = report_path
+                .as_deref()
+                .and_then(super::panic_screen::take_previous_report);
+            let panic_reporter = super::panic_screen::PanicReporter::install(
+                native_options.on_panic.clone(),
+                report_path,
+            );
+            (Some(panic_reporter), previous_panic_report)
+        } else {
+            (None, None)
+        };
+
+        let prefers_right_to_left = crate::locale::prefers_right_to_left();
+        native_options
+            .layout_direction
+            .apply(&egui_ctx, prefers_right_to_left);
+
         let frame = epi::Frame {
-            info: epi::IntegrationInfo { cpu_usage: None },
+            info: epi::IntegrationInfo {
+                cpu_usage: None,
+                prefers_right_to_left,
+            },
             storage,
             #[cfg(feature = "glow")]
             gl,
@@ -193,8 +433,20 @@ impl EpiIntegration {
             wgpu_render_state,
             raw_display_handle: window.display_handle().map(|h| h.as_raw()),
             raw_window_handle: window.window_handle().map(|h| h.as_raw()),
+            inhibit_sleep: epi::InhibitSleep::Never,
+            repaint_mode: epi::RepaintMode::Reactive,
+            unsaved_changes: false,
+            screenshot_requests: Vec::new(),
+            recordings: Default::default(),
+            fullscreen_request: None,
+            app_swap: None,
         };
 
+        #[cfg(target_os = "windows")]
+        if native_options.handle_session_end {
+            super::session_end::watch_window(window);
+        }
+
         let icon = native_options
             .viewport
             .icon
@@ -220,6 +472,14 @@ impl EpiIntegration {
             #[cfg(feature = "persistence")]
             persist_window: native_options.persist_window,
             app_icon_setter,
+            panic_reporter,
+            previous_panic_report,
+            caught_panic_report: None,
+            app_name: app_name.to_owned(),
+            sleep_inhibitor: None,
+            sleep_inhibited: false,
+            layout_direction: native_options.layout_direction,
+            shutdown_timeout: native_options.shutdown_timeout,
             beginning: Instant::now(),
             is_first_frame: true,
         }
@@ -249,9 +509,55 @@ impl EpiIntegration {
             self.can_drag_window = true;
         }
 
+        if matches!(event, WindowEvent::Focused(true)) {
+            // The user may have changed their language in the system settings:
+            crate::locale::refresh(&mut self.frame.info, self.layout_direction, &self.egui_ctx);
+        }
+
         egui_winit.on_window_event(window, event)
     }
 
+    /// Run [`epi::App::shutdown_hook`] on a background thread,
+    /// waiting for it at most [`epi::NativeOptions::shutdown_timeout`].
+    pub fn run_shutdown_hook(&self, app: &mut dyn epi::App) {
+        let Some(hook) = app.shutdown_hook() else {
+            return;
+        };
+        profiling::function_scope!();
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("eframe_shutdown".to_owned())
+            .spawn(move || {
+                hook();
+                done_tx.send(()).ok();
+            });
+        if let Err(err) = spawned {
+            log::warn!("Failed to spawn thread for the shutdown hook: {err}");
+            return;
+        }
+
+        match done_rx.recv_timeout(self.shutdown_timeout) {
+            Ok(()) => {}
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                log::warn!(
+                    "The shutdown hook did not finish within {:?} - exiting anyway",
+                    self.shutdown_timeout
+                );
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                log::warn!("The shutdown hook panicked");
+            }
+        }
+    }
+
+    /// Tell the app about a change in its lifecycle.
+    pub fn on_lifecycle_event(&self, app: &mut dyn epi::App, event: epi::LifecycleEvent) {
+        log::debug!("Lifecycle event: {event:?}");
+        app.on_lifecycle_event(&self.egui_ctx, event);
+        self.egui_ctx.request_repaint();
+    }
+
     pub fn pre_update(&mut self) {
         self.app_icon_setter.update();
     }
@@ -267,7 +573,16 @@ impl EpiIntegration {
     ) -> egui::FullOutput {
         raw_input.time = Some(self.beginning.elapsed().as_secs_f64());
 
-        let close_requested = raw_input.viewport().close_requested();
+        let is_root_viewport = viewport_ui_cb.is_none();
+        let closing = is_root_viewport && raw_input.viewport().is_closing();
+
+        epi::deliver_screenshots(&mut raw_input.events);
+        epi::deliver_system_theme_changes(app, &self.egui_ctx, &raw_input.events);
+        if is_root_viewport {
+            for event in super::shell_integration::take_events() {
+                app.on_shell_event(&self.egui_ctx, event);
+            }
+        }
 
         app.raw_input_hook(&self.egui_ctx, &mut raw_input);
 
@@ -278,21 +593,35 @@ impl EpiIntegration {
                 viewport_ui_cb(egui_ctx);
             } else {
                 profiling::scope!("App::update");
-                app.update(egui_ctx, &mut self.frame);
+                if let Some(report) = &self.caught_panic_report {
+                    super::panic_screen::panic_screen(egui_ctx, "The app crashed", report, false);
+                } else if let Some(report) = &self.previous_panic_report {
+                    if super::panic_screen::panic_screen(
+                        egui_ctx,
+                        "The app crashed the last time it ran",
+                        report,
+                        true,
+                    ) {
+                        self.previous_panic_report = None;
+                    }
+                } else if let Some(panic_reporter) = &self.panic_reporter {
+                    if let Err(report) =
+                        panic_reporter.catch_panic(|| app.update(egui_ctx, &mut self.frame))
+                    {
+                        log::error!("Caught a panic in App::update: {report}");
+                        self.caught_panic_report = Some(report.to_string());
+                        egui_ctx.request_repaint();
+                    }
+                } else {
+                    app.update(egui_ctx, &mut self.frame);
+                }
+                self.frame.send_viewport_commands(egui_ctx);
             }
         });
 
-        let is_root_viewport = viewport_ui_cb.is_none();
-        if is_root_viewport && close_requested {
-            let canceled = full_output.viewport_output[&ViewportId::ROOT]
-                .commands
-                .contains(&egui::ViewportCommand::CancelClose);
-            if canceled {
-                log::debug!("Closing of root viewport canceled with ViewportCommand::CancelClose");
-            } else {
-                log::debug!("Closing root viewport (ViewportCommand::CancelClose was not sent)");
-                self.close = true;
-            }
+        if closing {
+            // See `CloseLifecycle`.
+            self.close = true;
         }
 
         self.pending_full_output.append(full_output);
@@ -309,6 +638,27 @@ impl EpiIntegration {
             // We keep hidden until we've painted something. See https://github.com/emilk/egui/pull/2279
             window.set_visible(true);
         }
+        self.update_sleep_inhibitor();
+        super::session_end::set_unsaved_changes(self.frame.unsaved_changes);
+    }
+
+    fn update_sleep_inhibitor(&mut self) {
+        // Any of our viewports having focus counts as the app having focus:
+        let has_focus = self.egui_ctx.input(|i| {
+            i.raw
+                .viewports
+                .values()
+                .any(|info| info.focused == Some(true))
+        });
+        let inhibit = self.frame.inhibit_sleep.should_inhibit(has_focus);
+        if inhibit != self.sleep_inhibited {
+            self.sleep_inhibited = inhibit;
+            self.sleep_inhibitor = if inhibit {
+                egui_winit::SleepInhibitor::new(&self.app_name, "Requested by the app")
+            } else {
+                None
+            };
+        }
     }
 
     // ------------------------------------------------------------------------
@@ -347,7 +697,8 @@ impl EpiIntegration {
                 self.egui_ctx
                     .memory(|mem| epi::set_value(storage, STORAGE_EGUI_MEMORY_KEY, mem));
             }
-            {
+            // Don't persist the state of an app that panicked:
+            if self.caught_panic_report.is_none() {
                 profiling::scope!("App::save");
                 _app.save(storage);
             }
@@ -373,12 +724,21 @@ pub fn load_window_settings(_storage: Option<&dyn epi::Storage>) -> Option<Windo
     profiling::function_scope!();
     #[cfg(feature = "persistence")]
     {
-        epi::get_value(_storage?, STORAGE_WINDOW_KEY)
+        let mut window_settings: WindowSettings = epi::get_value(_storage?, STORAGE_WINDOW_KEY)?;
+        window_settings.migrate();
+        Some(window_settings)
     }
     #[cfg(not(feature = "persistence"))]
     None
 }
 
+/// Restore the zoom factor the window had in the previous session.
+pub fn restore_zoom_factor(egui_ctx: &egui::Context, window_settings: Option<&WindowSettings>) {
+    if let Some(zoom_factor) = window_settings.and_then(WindowSettings::zoom_factor) {
+        egui_ctx.set_zoom_factor(zoom_factor);
+    }
+}
+
 pub fn load_egui_memory(_storage: Option<&dyn epi::Storage>) -> Option<egui::Memory> {
     profiling::function_scope!();
     #[cfg(feature = "persistence")]
@@ -388,3 +748,173 @@ pub fn load_egui_memory(_storage: Option<&dyn epi::Storage>) -> Option<egui::Mem
     #[cfg(not(feature = "persistence"))]
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::{ViewportCommand, ViewportEvent, ViewportIdPair, ViewportInfo, ViewportOutput};
+
+    fn output_with(commands: Vec<ViewportCommand>) -> ViewportOutput {
+        ViewportOutput {
+            parent: ViewportId::ROOT,
+            class: egui::ViewportClass::Deferred,
+            builder: Default::default(),
+            viewport_ui_cb: None,
+            commands,
+            repaint_delay: std::time::Duration::MAX,
+        }
+    }
+
+    struct NamedApp(&'static str);
+
+    impl epi::App for NamedApp {
+        fn update(&mut self, _ctx: &egui::Context, _frame: &mut epi::Frame) {}
+
+        fn save(&mut self, storage: &mut dyn epi::Storage) {
+            storage.set_string("name", self.0.to_owned());
+        }
+    }
+
+    fn name_of(app: &mut dyn epi::App) -> String {
+        SwapStorage::save(app)["name"].clone()
+    }
+
+    #[test]
+    fn swap_app_carries_state_over_and_exits_the_old_app() {
+        let persistent =
+            SwapStorage::new([("only_on_disk".to_owned(), "yes".to_owned())].into(), None);
+        let mut app: Box<dyn epi::App> = Box::new(NamedApp("old"));
+        let mut exited = vec![];
+
+        swap_app(
+            &mut app,
+            Some(&persistent),
+            |storage| {
+                use epi::Storage as _;
+                assert_eq!(storage.get_string("name").as_deref(), Some("old"));
+                assert_eq!(storage.get_string("only_on_disk").as_deref(), Some("yes"));
+                Ok(Box::new(NamedApp("new")))
+            },
+            |old_app| exited.push(name_of(old_app)),
+        );
+
+        assert_eq!(name_of(app.as_mut()), "new");
+        assert_eq!(exited, vec!["old".to_owned()]);
+    }
+
+    #[test]
+    fn failed_swap_keeps_the_old_app_running() {
+        let mut app: Box<dyn epi::App> = Box::new(NamedApp("old"));
+        let mut exited = vec![];
+
+        swap_app(
+            &mut app,
+            None,
+            |_storage| Err("plugin failed to load".into()),
+            |old_app| exited.push(name_of(old_app)),
+        );
+
+        assert_eq!(name_of(app.as_mut()), "old");
+        assert!(exited.is_empty(), "the old app should not exit");
+    }
+
+    #[test]
+    fn canceled_close_gets_no_closing_frame() {
+        let id = ViewportId::ROOT;
+        let mut close = CloseLifecycle::default();
+
+        let output = output_with(vec![ViewportCommand::CancelClose]);
+        assert!(!close.end_pass(id, true, Some(&output)));
+        assert!(!close.is_closing());
+
+        let mut info = ViewportInfo::default();
+        close.begin_pass(&mut info);
+        assert!(!info.is_closing());
+    }
+
+    #[test]
+    fn accepted_close_gets_exactly_one_closing_frame() {
+        let id = ViewportId::from_hash_of("child");
+        let mut close = CloseLifecycle::default();
+
+        // The frame with the `Close` event: nobody cancels it.
+        assert!(
+            close.end_pass(id, true, Some(&output_with(vec![]))),
+            "The last frame should be forced"
+        );
+        assert!(close.is_closing());
+
+        // The last frame:
+        let mut info = ViewportInfo::default();
+        close.begin_pass(&mut info);
+        assert!(info.is_closing());
+        assert_eq!(info.events, vec![ViewportEvent::Closing]);
+        assert!(!close.end_pass(id, false, Some(&output_with(vec![]))));
+
+        // Done:
+        let mut info = ViewportInfo::default();
+        close.begin_pass(&mut info);
+        assert!(!info.is_closing());
+    }
+
+    #[test]
+    fn child_still_shown_after_closing_is_closed() {
+        let id = ViewportId::from_hash_of("child");
+        let mut close = CloseLifecycle::default();
+
+        close.end_pass(id, true, Some(&output_with(vec![])));
+        assert!(!close.is_closed(), "The last frame is still to come");
+
+        // The user keeps showing the child during and after its last frame:
+        close.end_pass(id, false, Some(&output_with(vec![])));
+        assert!(close.is_closed());
+        close.end_pass(id, false, Some(&output_with(vec![])));
+        assert!(close.is_closed(), "It should stay closed while shown");
+
+        // The root exits instead:
+        let mut close = CloseLifecycle::default();
+        close.end_pass(ViewportId::ROOT, true, Some(&output_with(vec![])));
+        close.end_pass(ViewportId::ROOT, false, Some(&output_with(vec![])));
+        assert!(!close.is_closed());
+    }
+
+    #[test]
+    fn hidden_viewport_gets_no_closing_frame() {
+        let id = ViewportId::from_hash_of("child");
+        let mut close = CloseLifecycle::default();
+
+        // The child stopped being shown the same frame as it got `Close`:
+        assert!(!close.end_pass(id, true, None));
+        assert!(!close.is_closing());
+    }
+
+    #[test]
+    fn parent_is_told_about_destroyed_child() {
+        let ctx = egui::Context::default();
+        let child = ViewportIdPair::from_self_and_parent(
+            ViewportId::from_hash_of("child"),
+            ViewportId::ROOT,
+        );
+        let orphan = ViewportIdPair::from_self_and_parent(
+            ViewportId::from_hash_of("orphan"),
+            ViewportId::from_hash_of("gone"),
+        );
+
+        let mut viewport_output = egui::ViewportIdMap::default();
+        viewport_output.insert(ViewportId::ROOT, output_with(vec![]));
+        let removed = removed_viewports([ViewportIdPair::ROOT, child, orphan], &viewport_output);
+        assert_eq!(removed, vec![child, orphan]);
+
+        let mut root_info = ViewportInfo::default();
+        for ids in removed {
+            let parent_info = (ids.parent == ViewportId::ROOT).then_some(&mut root_info);
+            child_destroyed(&ctx, parent_info, ids);
+        }
+
+        assert_eq!(
+            root_info.destroyed_children().collect::<Vec<_>>(),
+            vec![child.this]
+        );
+        assert!(ctx.has_requested_repaint_for(&ViewportId::ROOT));
+    }
+}
@@ -39,6 +39,73 @@ pub fn storage_dir(app_id: &str) -> Option<PathBuf> {
     }
 }
 
+impl crate::StorageLocation {
+    /// The folder where the app with the given id stores its state, if it can be determined.
+    ///
+    /// The `app_id` is only used for [`crate::StorageLocation::UserData`], see [`storage_dir`].
+    pub fn dir(&self, app_id: &str) -> Option<PathBuf> {
+        match self {
+            Self::UserData => storage_dir(app_id),
+            Self::Portable => std::env::current_exe()
+                .ok()
+                .and_then(|exe| Some(exe.parent()?.join("data"))),
+            Self::Folder(dir) => Some(dir.clone()),
+        }
+    }
+}
+
+/// Move the stored state of the app with the given id from one [`crate::StorageLocation`] to another,
+/// e.g. when the user switches to a portable install.
+///
+/// Call this before starting the app, as the state is loaded on startup.
+///
+/// Returns `Ok(false)` if there was no stored state to move.
+///
+/// # Errors
+/// If either folder can't be determined, if there already is stored state at `to`
+/// (which is never overwritten), or if the state can't be moved.
+/// If the state was copied, but the old file couldn't be removed,
+/// the state is in both places, and the error is returned.
+pub fn migrate_storage(
+    app_id: &str,
+    from: &crate::StorageLocation,
+    to: &crate::StorageLocation,
+) -> std::io::Result<bool> {
+    use std::io::{Error, ErrorKind};
+
+    let dir = |location: &crate::StorageLocation| {
+        location.dir(app_id).ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("Failed to find the storage folder for {location:?}"),
+            )
+        })
+    };
+    let from_path = dir(from)?.join(STORAGE_FILE_NAME);
+    let to_dir = dir(to)?;
+    let to_path = to_dir.join(STORAGE_FILE_NAME);
+
+    if from_path == to_path || !from_path.is_file() {
+        return Ok(false);
+    }
+    if to_path.exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("There already is stored state at {to_path:?}"),
+        ));
+    }
+
+    std::fs::create_dir_all(&to_dir)?;
+    // Copy rather than rename, as the folders may be on different file systems:
+    std::fs::copy(&from_path, &to_path)?;
+    std::fs::remove_file(&from_path)?;
+    log::info!("Moved app state from {from_path:?} to {to_path:?}");
+    Ok(true)
+}
+
+/// The name of the file in the storage folder.
+const STORAGE_FILE_NAME: &str = "app.ron";
+
 // Adapted from
 // https://github.com/rust-lang/cargo/blob/6e11c77384989726bb4f412a0e23b59c27222c34/crates/home/src/windows.rs#L19-L37
 #[cfg(all(windows, not(target_vendor = "uwp")))]
@@ -129,8 +196,13 @@ impl FileStorage {
 
     /// Find a good place to put the files that the OS likes.
     pub fn from_app_id(app_id: &str) -> Option<Self> {
+        Self::from_location(app_id, &crate::StorageLocation::UserData)
+    }
+
+    /// Store the state in the given location.
+    pub fn from_location(app_id: &str, location: &crate::StorageLocation) -> Option<Self> {
         profiling::function_scope!();
-        if let Some(data_dir) = storage_dir(app_id) {
+        if let Some(data_dir) = location.dir(app_id) {
             if let Err(err) = std::fs::create_dir_all(&data_dir) {
                 log::warn!(
                     "Saving disabled: Failed to create app path at {:?}: {}",
@@ -139,7 +211,7 @@ impl FileStorage {
                 );
                 None
             } else {
-                Some(Self::from_ron_filepath(data_dir.join("app.ron")))
+                Some(Self::from_ron_filepath(data_dir.join(STORAGE_FILE_NAME)))
             }
         } else {
             log::warn!("Saving disabled: Failed to find path to data_dir.");
@@ -264,4 +336,30 @@ mod tests {
             assert_eq!(directories_storage_dir(app_id), storage_dir(app_id));
         }
     }
+
+    #[test]
+    fn migrate_storage_between_folders() {
+        use crate::StorageLocation;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let from = StorageLocation::Folder(root.join("from"));
+        let to = StorageLocation::Folder(root.join("to"));
+
+        assert!(
+            !migrate_storage("app", &from, &to).unwrap(),
+            "nothing to move"
+        );
+
+        std::fs::create_dir_all(root.join("from")).unwrap();
+        std::fs::write(root.join("from").join(STORAGE_FILE_NAME), "{}").unwrap();
+        assert!(migrate_storage("app", &from, &to).unwrap());
+        assert!(!root.join("from").join(STORAGE_FILE_NAME).exists());
+        assert!(root.join("to").join(STORAGE_FILE_NAME).is_file());
+
+        // Never overwrite:
+        std::fs::write(root.join("from").join(STORAGE_FILE_NAME), "{}").unwrap();
+        let err = migrate_storage("app", &from, &to).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    }
 }
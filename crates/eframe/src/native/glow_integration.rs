@@ -32,12 +32,12 @@ use egui::{
 use egui_winit::accesskit_winit;
 
 use crate::{
-    native::epi_integration::EpiIntegration, App, AppCreator, CreationContext, NativeOptions,
-    Result, Storage,
+    native::epi_integration::EpiIntegration, App, AppCreator, CreationContext, LifecycleEvent,
+    NativeOptions, Result, Storage,
 };
 
 use super::{
-    epi_integration, event_loop_context,
+    epi_integration, event_loop_context, winit_integration,
     winit_integration::{create_egui_context, EventResult, UserEvent, WinitApp},
 };
 
@@ -113,6 +113,9 @@ struct Viewport {
     /// None for immediate viewports.
     viewport_ui_cb: Option<Arc<DeferredViewportUiCallback>>,
 
+    /// See [`epi_integration::CloseLifecycle`].
+    close: epi_integration::CloseLifecycle,
+
     // These three live and die together.
     // TODO(emilk): clump them together into one struct!
     gl_surface: Option<glutin::surface::Surface<glutin::surface::WindowSurface>>,
@@ -148,12 +151,13 @@ impl<'app> GlowWinitApp<'app> {
     ) -> Result<(GlutinWindowContext, egui_glow::Painter)> {
         profiling::function_scope!();
         let window_settings = epi_integration::load_window_settings(storage);
+        epi_integration::restore_zoom_factor(egui_ctx, window_settings.as_ref());
 
         let winit_window_builder = epi_integration::viewport_builder(
             egui_ctx.zoom_factor(),
             event_loop,
             native_options,
-            window_settings,
+            window_settings.clone(),
         )
         .with_visible(false); // Start hidden until we render the first frame to fix white flash on startup (https://github.com/emilk/egui/pull/3631)
 
@@ -167,7 +171,7 @@ impl<'app> GlowWinitApp<'app> {
         {
             let viewport = &glutin_window_context.viewports[&ViewportId::ROOT];
             let window = viewport.window.as_ref().unwrap(); // Can't fail - we just called `initialize_all_viewports`
-            epi_integration::apply_window_settings(window, window_settings);
+            epi_integration::apply_window_settings(window, window_settings.as_ref());
         }
 
         let gl = unsafe {
@@ -205,6 +209,7 @@ impl<'app> GlowWinitApp<'app> {
                     .app_id
                     .as_ref()
                     .unwrap_or(&self.app_name),
+                &self.native_options.storage_location,
             )
         };
 
@@ -366,6 +371,12 @@ impl WinitApp for GlowWinitApp<'_> {
             .and_then(|r| r.glutin.borrow().window_from_viewport.get(&id).copied())
     }
 
+    fn repaint_mode(&self) -> crate::RepaintMode {
+        self.running
+            .as_ref()
+            .map_or_else(Default::default, |r| r.integration.frame.repaint_mode())
+    }
+
     fn save_and_destroy(&mut self) {
         if let Some(mut running) = self.running.take() {
             profiling::function_scope!();
@@ -374,7 +385,17 @@ impl WinitApp for GlowWinitApp<'_> {
                 running.app.as_mut(),
                 Some(&running.glutin.borrow().window(ViewportId::ROOT)),
             );
+            if let Some(egui_winit) = running
+                .glutin
+                .borrow()
+                .viewports
+                .get(&ViewportId::ROOT)
+                .and_then(|viewport| viewport.egui_winit.as_ref())
+            {
+                winit_integration::save_frame_telemetry(egui_winit);
+            }
             running.app.on_exit(Some(running.painter.borrow().gl()));
+            running.integration.run_shutdown_hook(running.app.as_mut());
             running.painter.borrow_mut().destroy();
         }
     }
@@ -401,6 +422,9 @@ impl WinitApp for GlowWinitApp<'_> {
                 .borrow_mut()
                 .initialize_all_windows(event_loop);
             running
+                .integration
+                .on_lifecycle_event(running.app.as_mut(), LifecycleEvent::Resumed);
+            running
         } else {
             // First resume event. Create our root window etc.
             self.init_run_state(event_loop)?
@@ -412,10 +436,31 @@ impl WinitApp for GlowWinitApp<'_> {
     fn suspended(&mut self, _: &ActiveEventLoop) -> crate::Result<EventResult> {
         if let Some(running) = &mut self.running {
             running.glutin.borrow_mut().on_suspend()?;
+            running
+                .integration
+                .on_lifecycle_event(running.app.as_mut(), LifecycleEvent::Suspended);
         }
         Ok(EventResult::Wait)
     }
 
+    fn memory_warning(&mut self) -> crate::Result<EventResult> {
+        if let Some(running) = &mut self.running {
+            running
+                .integration
+                .on_lifecycle_event(running.app.as_mut(), LifecycleEvent::LowMemory);
+        }
+        Ok(EventResult::Wait)
+    }
+
+    fn session_ending(&mut self) -> crate::Result<EventResult> {
+        if let Some(running) = &mut self.running {
+            running
+                .integration
+                .on_lifecycle_event(running.app.as_mut(), LifecycleEvent::SessionEnding);
+        }
+        Ok(EventResult::Exit)
+    }
+
     fn device_event(
         &mut self,
         _: &ActiveEventLoop,
@@ -458,8 +503,6 @@ impl WinitApp for GlowWinitApp<'_> {
 
     #[cfg(feature = "accesskit")]
     fn on_accesskit_event(&mut self, event: accesskit_winit::Event) -> crate::Result<EventResult> {
-        use super::winit_integration;
-
         if let Some(running) = &self.running {
             let mut glutin = running.glutin.borrow_mut();
             if let Some(viewport_id) = glutin.viewport_from_window.get(&event.window_id).copied() {
@@ -528,10 +571,12 @@ impl GlowWinitRunning<'_> {
                 return Ok(EventResult::Wait);
             };
             egui_winit::update_viewport_info(&mut viewport.info, &egui_ctx, window, false);
+            viewport.close.begin_pass(&mut viewport.info);
 
             let Some(egui_winit) = viewport.egui_winit.as_mut() else {
                 return Ok(EventResult::Wait);
             };
+            egui_winit.update_monitors(&mut viewport.info, window);
             let mut raw_input = egui_winit.take_egui_input(window);
             let viewport_ui_cb = viewport.viewport_ui_cb.clone();
 
@@ -614,7 +659,7 @@ impl GlowWinitRunning<'_> {
             viewport_output,
         } = full_output;
 
-        glutin.remove_viewports_not_in(&viewport_output);
+        glutin.remove_viewports_not_in(&integration.egui_ctx, &viewport_output);
 
         let GlutinWindowContext {
             viewports,
@@ -623,11 +668,26 @@ impl GlowWinitRunning<'_> {
             ..
         } = &mut *glutin;
 
+        // An immediate viewport can only render its last frame together with its parent:
+        let immediate_child_closing = viewports.values().any(|viewport| {
+            viewport.ids.parent == viewport_id
+                && viewport.ids.this != viewport_id
+                && viewport.viewport_ui_cb.is_none()
+                && viewport.close.is_closing()
+        });
+
         let Some(viewport) = viewports.get_mut(&viewport_id) else {
             return Ok(EventResult::Wait);
         };
 
+        let close_requested = viewport.info.close_requested();
         viewport.info.events.clear(); // they should have been processed
+        viewport.info.user_commands.clear();
+        let closing = viewport.close.end_pass(
+            viewport_id,
+            close_requested,
+            viewport_output.get(&viewport_id),
+        );
         let window = viewport.window.clone().unwrap();
         let gl_surface = viewport.gl_surface.as_ref().unwrap();
         let egui_winit = viewport.egui_winit.as_mut().unwrap();
@@ -649,6 +709,7 @@ impl GlowWinitRunning<'_> {
             painter.clear(screen_size_in_pixels, clear_color);
         }
 
+        painter.set_render_scale(viewport.info.render_scale.unwrap_or(1.0));
         painter.paint_and_update_textures(
             screen_size_in_pixels,
             pixels_per_point,
@@ -707,6 +768,8 @@ impl GlowWinitRunning<'_> {
             frame_timer.resume();
         }
 
+        egui_winit.on_frame_presented();
+
         // give it time to settle:
         #[cfg(feature = "__screenshot")]
         if integration.egui_ctx.cumulative_pass_nr() == 2 {
@@ -728,11 +791,59 @@ impl GlowWinitRunning<'_> {
             std::thread::sleep(std::time::Duration::from_millis(10));
         }
 
-        if integration.should_close() {
-            Ok(EventResult::Exit)
+        let event_result = if integration.should_close() {
+            EventResult::Exit
+        } else if closing || immediate_child_closing {
+            // Render the last frame even if the repaint mode would ignore a repaint request:
+            EventResult::RepaintNow(window_id)
         } else {
-            Ok(EventResult::Wait)
+            EventResult::Wait
+        };
+
+        drop(glutin);
+        drop(painter);
+
+        // Only now that the frame is presented is the old app done with its GL resources:
+        if let Some(app_creator) = self.integration.frame.take_app_swap() {
+            self.swap_app(app_creator);
         }
+
+        Ok(event_result)
+    }
+
+    /// See [`crate::Frame::swap_app`].
+    ///
+    /// Call this after painting and presenting the frame,
+    /// so that its paint callbacks are done with the resources of the old app.
+    fn swap_app(&mut self, app_creator: AppCreator<'static>) {
+        // Use latest raw_window_handle for eframe compatibility
+        use raw_window_handle::{HasDisplayHandle as _, HasWindowHandle as _};
+
+        let gl = self.painter.borrow().gl().clone();
+        let glutin = self.glutin.borrow();
+        let get_proc_address = |addr: &_| glutin.get_proc_address(addr);
+        let window = glutin.window(ViewportId::ROOT);
+
+        epi_integration::swap_app(
+            &mut self.app,
+            self.integration.frame.storage(),
+            |storage| {
+                let cc = CreationContext {
+                    egui_ctx: self.integration.egui_ctx.clone(),
+                    integration_info: self.integration.frame.info().clone(),
+                    storage: Some(storage),
+                    gl: Some(gl.clone()),
+                    get_proc_address: Some(&get_proc_address),
+                    #[cfg(feature = "wgpu")]
+                    wgpu_render_state: None,
+                    raw_display_handle: window.display_handle().map(|h| h.as_raw()),
+                    raw_window_handle: window.window_handle().map(|h| h.as_raw()),
+                };
+                profiling::scope!("app_creator");
+                app_creator(&cc)
+            },
+            |old_app| old_app.on_exit(Some(&gl)),
+        );
     }
 
     fn on_window_event(
@@ -763,6 +874,15 @@ impl GlowWinitRunning<'_> {
                 glutin.focused_viewport = new_focused.then(|| viewport_id).flatten();
             }
 
+            winit::event::WindowEvent::Occluded(occluded) => {
+                if let Some(event) =
+                    winit_integration::lifecycle_event_from_occluded(viewport_id, *occluded)
+                {
+                    self.integration
+                        .on_lifecycle_event(self.app.as_mut(), event);
+                }
+            }
+
             winit::event::WindowEvent::Resized(physical_size) => {
                 // Resize with 0 width and height is used by winit to signal a minimize event on Windows.
                 // See: https://github.com/rust-windowing/winit/issues/208
@@ -808,9 +928,14 @@ impl GlowWinitRunning<'_> {
                 );
                 if viewport_id == Some(ViewportId::ROOT) {
                     return EventResult::Exit;
-                } else {
-                    return EventResult::Wait;
                 }
+                if let Some(ids) = viewport_id
+                    .and_then(|viewport_id| glutin.viewports.get(&viewport_id))
+                    .map(|viewport| viewport.ids)
+                {
+                    glutin.child_destroyed(&self.integration.egui_ctx, ids);
+                }
+                return EventResult::Wait;
             }
 
             _ => {}
@@ -1017,7 +1142,12 @@ impl GlutinWindowContext {
 
         let mut viewport_from_window = HashMap::default();
         let mut window_from_viewport = ViewportIdMap::default();
-        let mut info = ViewportInfo::default();
+        let mut info = ViewportInfo {
+            render_scale: viewport_builder
+                .render_scale
+                .map(egui::emath::OrderedFloat::into_inner),
+            ..Default::default()
+        };
         if let Some(window) = &window {
             viewport_from_window.insert(window.id(), ViewportId::ROOT);
             window_from_viewport.insert(ViewportId::ROOT, window.id());
@@ -1035,6 +1165,7 @@ impl GlutinWindowContext {
                 info,
                 actions_requested: Default::default(),
                 viewport_ui_cb: None,
+                close: Default::default(),
                 gl_surface: None,
                 window: window.map(Arc::new),
                 egui_winit: None,
@@ -1070,7 +1201,11 @@ impl GlutinWindowContext {
     fn initialize_all_windows(&mut self, event_loop: &ActiveEventLoop) {
         profiling::function_scope!();
 
-        let viewports: Vec<ViewportId> = self.viewports.keys().copied().collect();
+        let viewports = egui_winit::owners_first(
+            self.viewports
+                .iter()
+                .map(|(id, viewport)| (*id, viewport.builder.owner)),
+        );
 
         for viewport_id in viewports {
             if let Err(err) = self.initialize_window(viewport_id, event_loop) {
@@ -1088,20 +1223,40 @@ impl GlutinWindowContext {
     ) -> Result {
         profiling::function_scope!();
 
+        let owner = self
+            .viewports
+            .get(&viewport_id)
+            .and_then(|viewport| viewport.builder.owner)
+            .and_then(|owner| self.viewports.get(&owner));
+        let owner_window = owner.and_then(|owner| owner.window.clone());
+        let waiting_for_owner = owner.is_some() && owner_window.is_none();
+
         let viewport = self
             .viewports
             .get_mut(&viewport_id)
             .expect("viewport doesn't exist");
 
+        if viewport.window.is_none() && waiting_for_owner {
+            // Create the window once the owner has one, so that it gets its owner:
+            log::debug!("Viewport {viewport_id:?} is waiting for its owner to get a window");
+            return Ok(());
+        }
+        if viewport.window.is_none() && viewport.close.is_closed() {
+            return Ok(()); // Still shown after its last frame, see `CloseLifecycle`.
+        }
+
         let window = if let Some(window) = &mut viewport.window {
             window
         } else {
             log::debug!("Creating a window for viewport {viewport_id:?}");
-            let window_attributes = egui_winit::create_winit_window_attributes(
+            let mut window_attributes = egui_winit::create_winit_window_attributes(
                 &self.egui_ctx,
                 event_loop,
                 viewport.builder.clone(),
             );
+            if let Some(owner_window) = &owner_window {
+                window_attributes = egui_winit::with_owner_window(window_attributes, owner_window);
+            }
             if window_attributes.transparent()
                 && self.gl_config.supports_transparency() == Some(false)
             {
@@ -1121,14 +1276,16 @@ impl GlutinWindowContext {
 
         viewport.egui_winit.get_or_insert_with(|| {
             log::debug!("Initializing egui_winit for viewport {viewport_id:?}");
-            egui_winit::State::new(
+            let mut egui_winit = egui_winit::State::new(
                 self.egui_ctx.clone(),
                 viewport_id,
                 event_loop,
-                Some(window.scale_factor() as f32),
+                Some(egui_winit::native_pixels_per_point(window)),
                 event_loop.system_theme(),
                 self.max_texture_side,
-            )
+            );
+            winit_integration::init_frame_telemetry(&mut egui_winit, viewport_id);
+            egui_winit
         });
 
         if viewport.gl_surface.is_none() {
@@ -1249,8 +1406,14 @@ impl GlutinWindowContext {
 
     pub(crate) fn remove_viewports_not_in(
         &mut self,
+        egui_ctx: &egui::Context,
         viewport_output: &ViewportIdMap<ViewportOutput>,
     ) {
+        let removed = epi_integration::removed_viewports(
+            self.viewports.values().map(|viewport| viewport.ids),
+            viewport_output,
+        );
+
         // GC old viewports
         self.viewports
             .retain(|id, _| viewport_output.contains_key(id));
@@ -1258,6 +1421,19 @@ impl GlutinWindowContext {
             .retain(|_, id| viewport_output.contains_key(id));
         self.window_from_viewport
             .retain(|id, _| viewport_output.contains_key(id));
+
+        for ids in removed {
+            self.child_destroyed(egui_ctx, ids);
+        }
+    }
+
+    /// Tell the parent of a viewport that it is gone.
+    fn child_destroyed(&mut self, egui_ctx: &egui::Context, ids: ViewportIdPair) {
+        let parent_info = self
+            .viewports
+            .get_mut(&ids.parent)
+            .map(|parent| &mut parent.info);
+        epi_integration::child_destroyed(egui_ctx, parent_info, ids);
     }
 
     fn handle_viewport_output(
@@ -1290,6 +1466,13 @@ impl GlutinWindowContext {
                 viewport_ui_cb,
             );
 
+            if viewport.close.is_closed() && viewport.window.is_some() {
+                log::debug!("Viewport {viewport_id:?} is still shown after its last frame - closing its window anyway");
+                viewport.window = None;
+                viewport.egui_winit = None;
+                viewport.gl_surface = None;
+            }
+
             if let Some(window) = &viewport.window {
                 let old_inner_size = window.inner_size();
 
@@ -1316,7 +1499,7 @@ impl GlutinWindowContext {
         // Create windows for any new viewports:
         self.initialize_all_windows(event_loop);
 
-        self.remove_viewports_not_in(viewport_output);
+        self.remove_viewports_not_in(egui_ctx, viewport_output);
     }
 }
 
@@ -1343,11 +1526,17 @@ fn initialize_or_update_viewport(
             entry.insert(Viewport {
                 ids,
                 class,
-                builder,
                 deferred_commands: vec![],
-                info: Default::default(),
+                info: ViewportInfo {
+                    render_scale: builder
+                        .render_scale
+                        .map(egui::emath::OrderedFloat::into_inner),
+                    ..Default::default()
+                },
+                builder,
                 actions_requested: Default::default(),
                 viewport_ui_cb,
+                close: Default::default(),
                 window: None,
                 egui_winit: None,
                 gl_surface: None,
@@ -1434,6 +1623,8 @@ fn render_immediate_viewport(
             return;
         };
         egui_winit::update_viewport_info(&mut viewport.info, egui_ctx, window, false);
+        egui_winit.update_monitors(&mut viewport.info, window);
+        viewport.close.begin_pass(&mut viewport.info);
 
         let mut raw_input = egui_winit.take_egui_input(window);
         raw_input.viewports = glutin
@@ -1442,6 +1633,7 @@ fn render_immediate_viewport(
             .map(|(id, viewport)| (*id, viewport.info.clone()))
             .collect();
         raw_input.time = Some(beginning.elapsed().as_secs_f64());
+        crate::epi::deliver_screenshots(&mut raw_input.events);
         raw_input
     };
 
@@ -1476,7 +1668,16 @@ fn render_immediate_viewport(
         return;
     };
 
+    let close_requested = viewport.info.close_requested();
     viewport.info.events.clear(); // they should have been processed
+    viewport.info.user_commands.clear();
+
+    // The parent forces the repaint for the last frame, see `GlowWinitRunning::run_ui_and_paint`.
+    viewport.close.end_pass(
+        viewport_id,
+        close_requested,
+        viewport_output.get(&viewport_id),
+    );
 
     let (Some(egui_winit), Some(window), Some(gl_surface)) = (
         &mut viewport.egui_winit,
@@ -1506,12 +1707,16 @@ fn render_immediate_viewport(
         [0.0, 0.0, 0.0, 0.0],
     );
 
-    painter.borrow_mut().paint_and_update_textures(
-        screen_size_in_pixels,
-        pixels_per_point,
-        &clipped_primitives,
-        &textures_delta,
-    );
+    {
+        let mut painter = painter.borrow_mut();
+        painter.set_render_scale(viewport.info.render_scale.unwrap_or(1.0));
+        painter.paint_and_update_textures(
+            screen_size_in_pixels,
+            pixels_per_point,
+            &clipped_primitives,
+            &textures_delta,
+        );
+    }
 
     {
         profiling::scope!("swap_buffers");
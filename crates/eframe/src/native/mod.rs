@@ -1,7 +1,11 @@
 mod app_icon;
 mod epi_integration;
 mod event_loop_context;
+mod panic_screen;
+mod repaint_scheduler;
 pub mod run;
+mod session_end;
+pub(crate) mod shell_integration;
 
 /// File storage which can be used by native backends.
 #[cfg(feature = "persistence")]
@@ -0,0 +1,269 @@
+//! Report panics, catch them in [`crate::App::update`],
+//! and show the report the next time the app starts if we couldn't catch them,
+//! see [`crate::NativeOptions::report_panics`].
+
+use std::{cell::RefCell, panic::AssertUnwindSafe, path::PathBuf, sync::Arc};
+
+use crate::{PanicHook, PanicReport};
+
+/// Name of the file in the storage folder where we keep the report of the last panic.
+#[cfg(feature = "persistence")]
+const REPORT_FILE_NAME: &str = "last_panic.txt";
+
+/// Where to keep the report of the last panic, so we can show it on the next start.
+#[cfg(feature = "persistence")]
+pub fn report_path(native_options: &crate::NativeOptions, app_id: &str) -> Option<PathBuf> {
+    if let Some(file) = &native_options.persistence_path {
+        Some(file.with_file_name(REPORT_FILE_NAME))
+    } else {
+        Some(
+            native_options
+                .storage_location
+                .dir(app_id)?
+                .join(REPORT_FILE_NAME),
+        )
+    }
+}
+
+#[cfg(not(feature = "persistence"))]
+pub fn report_path(_native_options: &crate::NativeOptions, _app_id: &str) -> Option<PathBuf> {
+    None
+}
+
+thread_local! {
+    /// Filled in by our panic hook, so we have the location and backtrace when we catch the panic.
+    static LAST_PANIC: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
+
+/// A panic hook that reports panics on any thread, installed for as long as this is alive.
+///
+/// The previous hook is still called, and then the panic unwinds (or aborts) as usual,
+/// unless it happened in [`Self::catch_panic`].
+/// The previous hook is restored on drop.
+pub struct PanicReporter {
+    report_path: Option<PathBuf>,
+    restore_previous_hook: Option<Box<dyn FnOnce()>>,
+}
+
+impl PanicReporter {
+    /// Install the panic hook.
+    ///
+    /// `on_panic` is called with every report, and the report is written to `report_path`, if any.
+    pub fn install(on_panic: Option<PanicHook>, report_path: Option<PathBuf>) -> Self {
+        let previous_hook = Arc::new(std::panic::take_hook());
+
+        let hook_previous = previous_hook.clone();
+        let hook_report_path = report_path.clone();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let report = PanicReport {
+                message: panic_message(panic_info.payload()),
+                location: panic_info.location().map(|location| location.to_string()),
+                backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            };
+
+            if let Some(report_path) = &hook_report_path {
+                if let Err(err) = std::fs::write(report_path, report.to_string()) {
+                    log::warn!("Failed to write panic report to {report_path:?}: {err}");
+                }
+            }
+            if let Some(on_panic) = &on_panic {
+                on_panic(&report);
+            }
+            LAST_PANIC.with(|last_panic| *last_panic.borrow_mut() = Some(report));
+
+            hook_previous(panic_info);
+        }));
+
+        Self {
+            report_path,
+            restore_previous_hook: Some(Box::new(move || {
+                std::panic::set_hook(Box::new(move |panic_info| previous_hook(panic_info)));
+            })),
+        }
+    }
+}
+
+impl PanicReporter {
+    /// Call `update`, and catch the panic if it panics.
+    ///
+    /// A caught panic has been reported as usual, but is not shown again on the next start,
+    /// since the caller shows it right away.
+    ///
+    /// With `panic = "abort"` nothing is caught, and the report is shown on the next start instead.
+    pub fn catch_panic(&self, update: impl FnOnce()) -> Result<(), PanicReport> {
+        // `update` is not called again after a panic, so broken invariants don't matter:
+        #[allow(clippy::disallowed_methods)]
+        let result = std::panic::catch_unwind(AssertUnwindSafe(update));
+        let Err(payload) = result else {
+            return Ok(());
+        };
+
+        if let Some(report_path) = &self.report_path {
+            if let Err(err) = std::fs::remove_file(report_path) {
+                log::warn!("Failed to remove panic report {report_path:?}: {err}");
+            }
+        }
+
+        Err(LAST_PANIC
+            .with(|last_panic| last_panic.borrow_mut().take())
+            .unwrap_or_else(|| PanicReport {
+                message: panic_message(&*payload),
+                location: None,
+                backtrace: String::new(),
+            }))
+    }
+}
+
+impl Drop for PanicReporter {
+    fn drop(&mut self) {
+        if let Some(restore_previous_hook) = self.restore_previous_hook.take() {
+            restore_previous_hook();
+        }
+    }
+}
+
+/// Read and remove the report of a panic from the last time the app ran.
+pub fn take_previous_report(report_path: &std::path::Path) -> Option<String> {
+    let report = std::fs::read_to_string(report_path).ok()?;
+    if let Err(err) = std::fs::remove_file(report_path) {
+        log::warn!("Failed to remove panic report {report_path:?}: {err}");
+    }
+    Some(report)
+}
+
+/// Show the report of a panic.
+///
+/// With `can_continue`, returns `true` once the user wants to continue to the app.
+pub fn panic_screen(
+    egui_ctx: &egui::Context,
+    heading: &str,
+    report: &str,
+    can_continue: bool,
+) -> bool {
+    let mut dismissed = false;
+    egui::CentralPanel::default().show(egui_ctx, |ui| {
+        ui.heading(heading);
+        ui.add_space(8.0);
+
+        let message = report.lines().next().unwrap_or_default();
+        ui.label(egui::RichText::new(message).color(ui.visuals().error_fg_color));
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            if ui.button("📋 Copy report").clicked() {
+                ui.ctx().copy_text(report.to_owned());
+            }
+            if can_continue && ui.button("Continue").clicked() {
+                dismissed = true;
+            }
+        });
+        ui.separator();
+
+        egui::ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+            ui.monospace(report);
+        });
+    });
+    dismissed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn panic_on_thread(message: &'static str) {
+        let result = std::thread::Builder::new()
+            .name("panic_test".to_owned())
+            .spawn(move || panic!("{message}"))
+            .unwrap()
+            .join();
+        assert!(result.is_err());
+    }
+
+    /// The panic hook is process-wide, so everything that touches it is in this one test.
+    #[test]
+    fn reporter_reports_panics_and_restores_previous_hook() {
+        static PREVIOUS_HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {
+            PREVIOUS_HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let reports = Arc::new(egui::mutex::Mutex::new(Vec::<PanicReport>::new()));
+        let temp_dir = tempfile::tempdir().unwrap();
+        let report_path = temp_dir.path().join("last_panic.txt");
+
+        let reporter = {
+            let reports = reports.clone();
+            PanicReporter::install(
+                Some(Arc::new(move |report| reports.lock().push(report.clone()))),
+                Some(report_path.clone()),
+            )
+        };
+        panic_on_thread("first");
+
+        {
+            let reports = reports.lock();
+            assert_eq!(reports.len(), 1);
+            assert_eq!(reports[0].message, "first");
+            assert!(reports[0]
+                .location
+                .as_ref()
+                .unwrap()
+                .contains("panic_screen.rs"));
+        }
+        assert_eq!(
+            PREVIOUS_HOOK_CALLS.load(Ordering::SeqCst),
+            1,
+            "the previous hook should still be called"
+        );
+
+        let previous_report = take_previous_report(&report_path).unwrap();
+        assert!(previous_report.starts_with("Panic: first at "));
+        assert!(
+            take_previous_report(&report_path).is_none(),
+            "the report should only be shown once"
+        );
+
+        assert!(reporter.catch_panic(|| {}).is_ok());
+        let caught = reporter.catch_panic(|| panic!("caught")).unwrap_err();
+        assert_eq!(caught.message, "caught");
+        assert!(
+            caught.location.unwrap().contains("panic_screen.rs"),
+            "the report should come from the hook"
+        );
+        assert_eq!(reports.lock().len(), 2, "caught panics are reported too");
+        assert!(
+            !report_path.exists(),
+            "caught panics should not be shown again on the next start"
+        );
+
+        drop(reporter);
+        panic_on_thread("second");
+
+        assert_eq!(
+            reports.lock().len(),
+            2,
+            "we should no longer report after drop"
+        );
+        assert_eq!(
+            PREVIOUS_HOOK_CALLS.load(Ordering::SeqCst),
+            3,
+            "the previous hook should be restored on drop"
+        );
+        assert!(!report_path.exists());
+
+        std::panic::set_hook(original_hook);
+    }
+}
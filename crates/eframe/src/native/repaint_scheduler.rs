@@ -0,0 +1,138 @@
+//! Applies [`crate::RepaintMode`] to the repaints requested by the windows.
+
+use std::time::Instant;
+
+use ahash::HashMap;
+use winit::{
+    event::WindowEvent,
+    window::{Window, WindowId},
+};
+
+use crate::RepaintMode;
+
+/// Keeps track of input and paint times, to decide when a window may be repainted.
+pub struct RepaintScheduler {
+    last_input: Instant,
+    last_paint: HashMap<WindowId, Instant>,
+
+    /// In Hz, for [`RepaintMode::MonitorRefreshRate`].
+    ///
+    /// Cached, because asking for the monitor of a window is slow on some platforms.
+    /// Forgotten when the window moves, since it may have moved to another monitor.
+    monitor_refresh_rates: HashMap<WindowId, Option<f32>>,
+}
+
+impl Default for RepaintScheduler {
+    fn default() -> Self {
+        Self {
+            // Treat startup like input, so the first frames are painted:
+            last_input: Instant::now(),
+            last_paint: Default::default(),
+            monitor_refresh_rates: Default::default(),
+        }
+    }
+}
+
+impl RepaintScheduler {
+    /// Call on each [`WindowEvent`] except [`WindowEvent::RedrawRequested`].
+    pub fn on_window_event(&mut self, window_id: WindowId, event: &WindowEvent) {
+        if is_user_input(event) {
+            self.last_input = Instant::now();
+        }
+
+        if matches!(
+            event,
+            WindowEvent::Moved(_) | WindowEvent::ScaleFactorChanged { .. }
+        ) {
+            self.monitor_refresh_rates.remove(&window_id);
+        } else if matches!(event, WindowEvent::Destroyed) {
+            self.monitor_refresh_rates.remove(&window_id);
+            self.last_paint.remove(&window_id);
+        }
+    }
+
+    /// We just painted this window.
+    pub fn on_paint(&mut self, window_id: WindowId) {
+        self.last_paint.insert(window_id, Instant::now());
+    }
+
+    /// Should we honor a repaint that egui asked for (as opposed to one caused by input)?
+    pub fn allows_requested_repaint(&self, mode: RepaintMode) -> bool {
+        mode.allows_requested_repaint(self.last_input.elapsed())
+    }
+
+    /// The earliest time `window` may be repainted.
+    pub fn earliest_repaint(&mut self, mode: RepaintMode, window: &Window) -> Option<Instant> {
+        let last_paint = *self.last_paint.get(&window.id())?;
+        let monitor_refresh_rate = if mode == RepaintMode::MonitorRefreshRate {
+            *self
+                .monitor_refresh_rates
+                .entry(window.id())
+                .or_insert_with(|| {
+                    window
+                        .current_monitor()
+                        .and_then(|monitor| monitor.refresh_rate_millihertz())
+                        .map(|millihertz| millihertz as f32 / 1000.0)
+                })
+        } else {
+            None
+        };
+        Some(last_paint + mode.min_frame_interval(monitor_refresh_rate)?)
+    }
+}
+
+/// Is this the user doing something (keyboard, pointer, touch, IME, …),
+/// as opposed to the system telling us about the window?
+fn is_user_input(event: &WindowEvent) -> bool {
+    match event {
+        WindowEvent::KeyboardInput { .. }
+        | WindowEvent::ModifiersChanged(_)
+        | WindowEvent::Ime(_)
+        | WindowEvent::CursorMoved { .. }
+        | WindowEvent::CursorEntered { .. }
+        | WindowEvent::CursorLeft { .. }
+        | WindowEvent::MouseWheel { .. }
+        | WindowEvent::MouseInput { .. }
+        | WindowEvent::Touch(_)
+        | WindowEvent::TouchpadPressure { .. }
+        | WindowEvent::PinchGesture { .. }
+        | WindowEvent::PanGesture { .. }
+        | WindowEvent::DoubleTapGesture { .. }
+        | WindowEvent::RotationGesture { .. }
+        | WindowEvent::AxisMotion { .. }
+        | WindowEvent::DroppedFile(_)
+        | WindowEvent::HoveredFile(_)
+        | WindowEvent::HoveredFileCancelled
+        | WindowEvent::Focused(_)
+        | WindowEvent::CloseRequested => true,
+
+        WindowEvent::ActivationTokenDone { .. }
+        | WindowEvent::Resized(_)
+        | WindowEvent::Moved(_)
+        | WindowEvent::Destroyed
+        | WindowEvent::ScaleFactorChanged { .. }
+        | WindowEvent::ThemeChanged(_)
+        | WindowEvent::Occluded(_)
+        | WindowEvent::RedrawRequested => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_user_input_counts_as_input() {
+        assert!(is_user_input(&WindowEvent::Focused(true)));
+        assert!(is_user_input(&WindowEvent::Ime(winit::event::Ime::Enabled)));
+        assert!(is_user_input(&WindowEvent::CloseRequested));
+
+        assert!(!is_user_input(&WindowEvent::Moved(Default::default())));
+        assert!(!is_user_input(&WindowEvent::Resized(Default::default())));
+        assert!(!is_user_input(&WindowEvent::Occluded(true)));
+        assert!(!is_user_input(&WindowEvent::ThemeChanged(
+            winit::window::Theme::Dark
+        )));
+        assert!(!is_user_input(&WindowEvent::Destroyed));
+    }
+}
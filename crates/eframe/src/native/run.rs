@@ -8,10 +8,15 @@ use winit::{
 
 use ahash::HashMap;
 
-use super::winit_integration::{UserEvent, WinitApp};
+use super::{
+    session_end, shell_integration,
+    winit_integration::{UserEvent, WinitApp},
+};
 use crate::{
     epi,
-    native::{event_loop_context, winit_integration::EventResult},
+    native::{
+        event_loop_context, repaint_scheduler::RepaintScheduler, winit_integration::EventResult,
+    },
     Result,
 };
 
@@ -68,6 +73,7 @@ fn with_event_loop<R>(
 /// some events, but otherwise forwards events to the [`WinitApp`].
 struct WinitAppWrapper<T: WinitApp> {
     windows_next_repaint_times: HashMap<WindowId, Instant>,
+    repaint_scheduler: RepaintScheduler,
     winit_app: T,
     return_result: Result<(), crate::Error>,
     run_and_return: bool,
@@ -77,6 +83,7 @@ impl<T: WinitApp> WinitAppWrapper<T> {
     fn new(winit_app: T, run_and_return: bool) -> Self {
         Self {
             windows_next_repaint_times: HashMap::default(),
+            repaint_scheduler: RepaintScheduler::default(),
             winit_app,
             return_result: Ok(()),
             run_and_return,
@@ -103,6 +110,7 @@ impl<T: WinitApp> WinitAppWrapper<T> {
 
                     if cfg!(target_os = "windows") {
                         // Fix flickering on Windows, see https://github.com/emilk/egui/pull/2280
+                        self.repaint_scheduler.on_paint(window_id);
                         self.winit_app.run_ui_and_paint(event_loop, window_id)
                     } else {
                         // Fix for https://github.com/emilk/egui/issues/2425
@@ -159,6 +167,7 @@ impl<T: WinitApp> WinitAppWrapper<T> {
 
     fn check_redraw_requests(&mut self, event_loop: &ActiveEventLoop) {
         let now = Instant::now();
+        let repaint_mode = self.winit_app.repaint_mode();
 
         self.windows_next_repaint_times
             .retain(|window_id, repaint_time| {
@@ -166,9 +175,21 @@ impl<T: WinitApp> WinitAppWrapper<T> {
                     return true; // not yet ready
                 };
 
+                let window = self.winit_app.window(*window_id);
+
+                if let Some(earliest) = window.as_ref().and_then(|window| {
+                    self.repaint_scheduler
+                        .earliest_repaint(repaint_mode, window)
+                }) {
+                    if now < earliest {
+                        *repaint_time = earliest; // Too soon, according to the repaint mode
+                        return true;
+                    }
+                }
+
                 event_loop.set_control_flow(ControlFlow::Poll);
 
-                if let Some(window) = self.winit_app.window(*window_id) {
+                if let Some(window) = window {
                     log::trace!("request_redraw for {window_id:?}");
                     window.request_redraw();
                 } else {
@@ -204,6 +225,15 @@ impl<T: WinitApp> ApplicationHandler<UserEvent> for WinitAppWrapper<T> {
         });
     }
 
+    fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
+        profiling::scope!("Event::MemoryWarning");
+
+        event_loop_context::with_event_loop_context(event_loop, move || {
+            let event_result = self.winit_app.memory_warning();
+            self.handle_event_result(event_loop, event_result);
+        });
+    }
+
     fn exiting(&mut self, event_loop: &ActiveEventLoop) {
         // On Mac, Cmd-Q we get here and then `run_app_on_demand` doesn't return (despite its name),
         // so we need to save state now:
@@ -233,6 +263,8 @@ impl<T: WinitApp> ApplicationHandler<UserEvent> for WinitAppWrapper<T> {
             UserEvent::RequestRepaint { .. } => "UserEvent::RequestRepaint",
             #[cfg(feature = "accesskit")]
             UserEvent::AccessKitActionRequest(_) => "UserEvent::AccessKitActionRequest",
+            UserEvent::SessionEnding => "UserEvent::SessionEnding",
+            UserEvent::Shell => "UserEvent::Shell",
         });
 
         event_loop_context::with_event_loop_context(event_loop, move || {
@@ -246,9 +278,15 @@ impl<T: WinitApp> ApplicationHandler<UserEvent> for WinitAppWrapper<T> {
                         .winit_app
                         .egui_ctx()
                         .map_or(0, |ctx| ctx.cumulative_pass_nr_for(viewport_id));
-                    if current_pass_nr == cumulative_pass_nr
-                        || current_pass_nr == cumulative_pass_nr + 1
+                    let is_current = current_pass_nr == cumulative_pass_nr
+                        || current_pass_nr == cumulative_pass_nr + 1;
+                    if !self
+                        .repaint_scheduler
+                        .allows_requested_repaint(self.winit_app.repaint_mode())
                     {
+                        log::trace!("Ignoring UserEvent::RequestRepaint: the user is idle");
+                        Ok(EventResult::Wait)
+                    } else if is_current {
                         log::trace!("UserEvent::RequestRepaint scheduling repaint at {when:?}");
                         if let Some(window_id) =
                             self.winit_app.window_id_from_viewport_id(viewport_id)
@@ -266,6 +304,23 @@ impl<T: WinitApp> ApplicationHandler<UserEvent> for WinitAppWrapper<T> {
                 UserEvent::AccessKitActionRequest(request) => {
                     self.winit_app.on_accesskit_event(request)
                 }
+                UserEvent::SessionEnding => {
+                    log::debug!("The session is ending - saving app state…");
+                    let event_result = self.winit_app.session_ending();
+
+                    // Don't wait for the event loop to wind down: the OS may kill us any moment now.
+                    self.winit_app.save_and_destroy();
+                    session_end::mark_handled();
+
+                    event_result
+                }
+                UserEvent::Shell => {
+                    // The events are delivered in the next frame of the root viewport:
+                    Ok(self
+                        .winit_app
+                        .window_id_from_viewport_id(egui::ViewportId::ROOT)
+                        .map_or(EventResult::Wait, EventResult::RepaintNext))
+                }
             };
             self.handle_event_result(event_loop, event_result);
         });
@@ -289,11 +344,12 @@ impl<T: WinitApp> ApplicationHandler<UserEvent> for WinitAppWrapper<T> {
 
         // Nb: Make sure this guard is dropped after this function returns.
         event_loop_context::with_event_loop_context(event_loop, move || {
-            let event_result = match event {
-                winit::event::WindowEvent::RedrawRequested => {
-                    self.winit_app.run_ui_and_paint(event_loop, window_id)
-                }
-                _ => self.winit_app.window_event(event_loop, window_id, event),
+            let event_result = if event == winit::event::WindowEvent::RedrawRequested {
+                self.repaint_scheduler.on_paint(window_id);
+                self.winit_app.run_ui_and_paint(event_loop, window_id)
+            } else {
+                self.repaint_scheduler.on_window_event(window_id, &event);
+                self.winit_app.window_event(event_loop, window_id, event)
             };
 
             self.handle_event_result(event_loop, event_result);
@@ -302,20 +358,36 @@ impl<T: WinitApp> ApplicationHandler<UserEvent> for WinitAppWrapper<T> {
 }
 
 #[cfg(not(target_os = "ios"))]
-fn run_and_return(event_loop: &mut EventLoop<UserEvent>, winit_app: impl WinitApp) -> Result {
+fn run_and_return(
+    event_loop: &mut EventLoop<UserEvent>,
+    winit_app: impl WinitApp,
+    handle_session_end: bool,
+) -> Result {
     use winit::platform::run_on_demand::EventLoopExtRunOnDemand;
 
     log::trace!("Entering the winit event loop (run_app_on_demand)…");
 
+    let _session_end_listener =
+        handle_session_end.then(|| session_end::listen(event_loop.create_proxy()));
+    let _shell_listener = shell_integration::listen(event_loop.create_proxy());
+
     let mut app = WinitAppWrapper::new(winit_app, true);
     event_loop.run_app_on_demand(&mut app)?;
     log::debug!("eframe window closed");
     app.return_result
 }
 
-fn run_and_exit(event_loop: EventLoop<UserEvent>, winit_app: impl WinitApp) -> Result {
+fn run_and_exit(
+    event_loop: EventLoop<UserEvent>,
+    winit_app: impl WinitApp,
+    handle_session_end: bool,
+) -> Result {
     log::trace!("Entering the winit event loop (run_app)…");
 
+    let _session_end_listener =
+        handle_session_end.then(|| session_end::listen(event_loop.create_proxy()));
+    let _shell_listener = shell_integration::listen(event_loop.create_proxy());
+
     // When to repaint what window
     let mut app = WinitAppWrapper::new(winit_app, false);
     event_loop.run_app(&mut app)?;
@@ -339,14 +411,16 @@ pub fn run_glow(
     #[cfg(not(target_os = "ios"))]
     if native_options.run_and_return {
         return with_event_loop(native_options, |event_loop, native_options| {
+            let handle_session_end = native_options.handle_session_end;
             let glow_eframe = GlowWinitApp::new(event_loop, app_name, native_options, app_creator);
-            run_and_return(event_loop, glow_eframe)
+            run_and_return(event_loop, glow_eframe, handle_session_end)
         })?;
     }
 
+    let handle_session_end = native_options.handle_session_end;
     let event_loop = create_event_loop(&mut native_options)?;
     let glow_eframe = GlowWinitApp::new(&event_loop, app_name, native_options, app_creator);
-    run_and_exit(event_loop, glow_eframe)
+    run_and_exit(event_loop, glow_eframe, handle_session_end)
 }
 
 // ----------------------------------------------------------------------------
@@ -364,12 +438,14 @@ pub fn run_wgpu(
     #[cfg(not(target_os = "ios"))]
     if native_options.run_and_return {
         return with_event_loop(native_options, |event_loop, native_options| {
+            let handle_session_end = native_options.handle_session_end;
             let wgpu_eframe = WgpuWinitApp::new(event_loop, app_name, native_options, app_creator);
-            run_and_return(event_loop, wgpu_eframe)
+            run_and_return(event_loop, wgpu_eframe, handle_session_end)
         })?;
     }
 
+    let handle_session_end = native_options.handle_session_end;
     let event_loop = create_event_loop(&mut native_options)?;
     let wgpu_eframe = WgpuWinitApp::new(&event_loop, app_name, native_options, app_creator);
-    run_and_exit(event_loop, wgpu_eframe)
+    run_and_exit(event_loop, wgpu_eframe, handle_session_end)
 }
@@ -0,0 +1,452 @@
+//! Notice when the OS ends the session (log off, shut down) or asks us to terminate,
+//! so that the app gets a chance to save before the process is killed.
+//!
+//! See [`crate::LifecycleEvent::SessionEnding`] and [`crate::NativeOptions::handle_session_end`].
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use winit::event_loop::EventLoopProxy;
+
+use super::winit_integration::UserEvent;
+
+/// Sends [`UserEvent::SessionEnding`] to the event loop, returning `false` if it is gone.
+type SendSessionEnding = Box<dyn Fn() -> bool + Send>;
+
+/// Where to send [`UserEvent::SessionEnding`]. `None` while no eframe event loop is running.
+static SEND: parking_lot::Mutex<Option<SendSessionEnding>> = parking_lot::const_mutex(None);
+
+/// Set once [`UserEvent::SessionEnding`] has been sent to the current event loop.
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Set once the app has been saved in response to [`UserEvent::SessionEnding`].
+static HANDLED: AtomicBool = AtomicBool::new(false);
+
+/// See [`crate::Frame::set_unsaved_changes`].
+static UNSAVED_CHANGES: AtomicBool = AtomicBool::new(false);
+
+/// Forwards the end of the session to an event loop until dropped.
+///
+/// Any signal handlers we installed are uninstalled on drop.
+pub struct SessionEndListener {
+    #[cfg(unix)]
+    _signal_handlers: Option<unix::SignalHandlers>,
+}
+
+impl Drop for SessionEndListener {
+    fn drop(&mut self) {
+        *SEND.lock() = None;
+    }
+}
+
+/// Start sending [`UserEvent::SessionEnding`] to the given event loop when the session ends.
+pub fn listen(proxy: EventLoopProxy<UserEvent>) -> SessionEndListener {
+    start(Box::new(move || {
+        proxy.send_event(UserEvent::SessionEnding).is_ok()
+    }));
+
+    SessionEndListener {
+        #[cfg(unix)]
+        _signal_handlers: unix::SignalHandlers::install(),
+    }
+}
+
+fn start(send: SendSessionEnding) {
+    REQUESTED.store(false, Ordering::SeqCst);
+    HANDLED.store(false, Ordering::SeqCst);
+    UNSAVED_CHANGES.store(false, Ordering::SeqCst);
+    *SEND.lock() = Some(send);
+}
+
+/// Call once the app has been saved in response to [`UserEvent::SessionEnding`].
+pub fn mark_handled() {
+    HANDLED.store(true, Ordering::SeqCst);
+}
+
+/// Has the app been saved in response to [`UserEvent::SessionEnding`]?
+#[cfg(any(target_os = "windows", test))]
+fn is_handled() -> bool {
+    HANDLED.load(Ordering::SeqCst)
+}
+
+/// Called after each frame with [`crate::Frame::has_unsaved_changes`].
+pub fn set_unsaved_changes(unsaved_changes: bool) {
+    UNSAVED_CHANGES.store(unsaved_changes, Ordering::SeqCst);
+}
+
+/// Windows asks whether it may end the session.
+///
+/// We ask the event loop to save and shut down either way, but only hold off the end of the session
+/// (by returning `true`) while the app has unsaved changes that haven't been saved yet.
+#[cfg(any(target_os = "windows", test))]
+fn query_end_session() -> bool {
+    if is_handled() {
+        return false;
+    }
+    if request_shutdown() {
+        log::info!("Windows is ending the session - saving and shutting down…");
+    }
+    UNSAVED_CHANGES.load(Ordering::SeqCst) && SEND.lock().is_some()
+}
+
+/// Ask the running event loop to save and shut down.
+///
+/// Returns `false` if there is no event loop to ask, or if we already asked it once.
+fn request_shutdown() -> bool {
+    if REQUESTED.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    SEND.lock().as_ref().is_some_and(|send| send())
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(unix)]
+#[allow(unsafe_code)]
+mod unix {
+    use std::{
+        io::Read as _,
+        os::{fd::AsRawFd as _, unix::net::UnixStream},
+        sync::atomic::{AtomicI32, Ordering},
+    };
+
+    pub const SIGNALS: [libc::c_int; 2] = [libc::SIGTERM, libc::SIGHUP];
+
+    /// The write end of the self-pipe, written to by [`on_signal`]. `-1` while not installed.
+    static SIGNAL_WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+
+    /// Our handlers for [`SIGNALS`], and the thread they hand the signals to.
+    ///
+    /// The previous handlers are restored on drop, which also stops the thread.
+    pub struct SignalHandlers {
+        /// The signals we installed a handler for, with the handler we replaced.
+        previous: Vec<(libc::c_int, libc::sigaction)>,
+
+        /// Closing this ends the thread reading from the other end.
+        writer: UnixStream,
+    }
+
+    impl SignalHandlers {
+        /// Install handlers for [`SIGNALS`], unless the app already handles (or ignores) them.
+        pub fn install() -> Option<Self> {
+            if SIGNAL_WRITE_FD.load(Ordering::SeqCst) != -1 {
+                log::debug!("Signal handlers are already installed");
+                return None;
+            }
+
+            // A signal handler can't do much more than write to a file descriptor,
+            // so the real work happens on a thread reading from the other end.
+            let (reader, writer) = match UnixStream::pair() {
+                Ok(pair) => pair,
+                Err(err) => {
+                    log::warn!("Failed to create a pipe for signal handling: {err}");
+                    return None;
+                }
+            };
+
+            let spawned = std::thread::Builder::new()
+                .name("eframe_signals".to_owned())
+                .spawn(move || forward_signals(reader));
+            if let Err(err) = spawned {
+                log::warn!("Failed to spawn thread for signal handling: {err}");
+                return None;
+            }
+
+            SIGNAL_WRITE_FD.store(writer.as_raw_fd(), Ordering::SeqCst);
+
+            let mut previous = Vec::new();
+            for signal in SIGNALS {
+                // SAFETY: `on_signal` only does async-signal-safe things.
+                unsafe {
+                    let mut previous_action: libc::sigaction = std::mem::zeroed();
+                    if libc::sigaction(signal, std::ptr::null(), &mut previous_action) != 0
+                        || previous_action.sa_sigaction != libc::SIG_DFL
+                    {
+                        log::debug!("Leaving the existing handler of signal {signal} in place");
+                        continue;
+                    }
+
+                    #[allow(clippy::fn_to_numeric_cast_any)] // That's how `sigaction` wants it
+                    let handler = on_signal as libc::sighandler_t;
+
+                    let mut action: libc::sigaction = std::mem::zeroed();
+                    action.sa_sigaction = handler;
+                    action.sa_flags = libc::SA_RESTART;
+                    libc::sigemptyset(&mut action.sa_mask);
+                    if libc::sigaction(signal, &action, std::ptr::null_mut()) == 0 {
+                        previous.push((signal, previous_action));
+                    }
+                }
+            }
+
+            Some(Self { previous, writer })
+        }
+    }
+
+    impl Drop for SignalHandlers {
+        fn drop(&mut self) {
+            for (signal, previous_action) in &self.previous {
+                // SAFETY: putting back the handler that was there before us.
+                unsafe {
+                    libc::sigaction(*signal, previous_action, std::ptr::null_mut());
+                }
+            }
+
+            SIGNAL_WRITE_FD.store(-1, Ordering::SeqCst);
+            if let Err(err) = self.writer.shutdown(std::net::Shutdown::Write) {
+                log::debug!("Failed to stop the signal handling thread: {err}");
+            }
+        }
+    }
+
+    extern "C" fn on_signal(signal: libc::c_int) {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        // Signal numbers are small
+        let byte = signal as u8;
+        let fd = SIGNAL_WRITE_FD.load(Ordering::SeqCst);
+
+        // SAFETY: `write` is async-signal-safe.
+        // Our handler is uninstalled before `fd` is closed, and writing to `-1` just fails.
+        unsafe {
+            libc::write(fd, std::ptr::from_ref(&byte).cast(), 1);
+        }
+    }
+
+    fn forward_signals(mut reader: UnixStream) {
+        let mut byte = [0_u8];
+        // Ends when the `SignalHandlers` are dropped:
+        while reader.read_exact(&mut byte).is_ok() {
+            let signal = libc::c_int::from(byte[0]);
+            if super::request_shutdown() {
+                log::info!("Received signal {signal} - saving and shutting down…");
+            } else {
+                // Nobody to hand it to, or we got it twice (the user is impatient):
+                // do what the signal would have done without us.
+                log::info!("Received signal {signal} - terminating");
+                // SAFETY: restoring the default disposition and re-raising is always allowed.
+                unsafe {
+                    libc::signal(signal, libc::SIG_DFL);
+                    libc::raise(signal);
+                }
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Listen for `WM_QUERYENDSESSION` on the given (root) window.
+///
+/// Windows terminates the process soon after the session ends, so when asked whether it may end
+/// the session, we ask the event loop to save and shut down. If the app has unsaved changes,
+/// we hold off the end of the session with a block reason until that is done.
+/// We return to the event loop right away: the app is saved as it processes [`UserEvent::SessionEnding`],
+/// and destroying the window lifts the block.
+#[cfg(target_os = "windows")]
+#[allow(unsafe_code)]
+pub fn watch_window(window: &winit::window::Window) {
+    use raw_window_handle::{HasWindowHandle as _, RawWindowHandle};
+    use windows_sys::Win32::UI::Shell::SetWindowSubclass;
+
+    /// Arbitrary, but unique among the subclasses of our windows.
+    const SUBCLASS_ID: usize = 0x6566_7261; // "efra"
+
+    let Ok(handle) = window.window_handle() else {
+        return;
+    };
+    let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+        return;
+    };
+
+    // SAFETY: the window handle is valid, and the subclass is removed by Windows when the window is destroyed.
+    let ok = unsafe {
+        SetWindowSubclass(
+            handle.hwnd.get() as _,
+            Some(end_session_subclass_proc),
+            SUBCLASS_ID,
+            0,
+        )
+    };
+    if ok == 0 {
+        log::warn!("Failed to listen for WM_QUERYENDSESSION");
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[allow(unsafe_code)]
+unsafe extern "system" fn end_session_subclass_proc(
+    hwnd: windows_sys::Win32::Foundation::HWND,
+    msg: u32,
+    wparam: windows_sys::Win32::Foundation::WPARAM,
+    lparam: windows_sys::Win32::Foundation::LPARAM,
+    _subclass_id: usize,
+    _ref_data: usize,
+) -> windows_sys::Win32::Foundation::LRESULT {
+    use windows_sys::Win32::{
+        System::Shutdown::ShutdownBlockReasonCreate, UI::Shell::DefSubclassProc,
+        UI::WindowsAndMessaging::WM_QUERYENDSESSION,
+    };
+
+    if msg == WM_QUERYENDSESSION && query_end_session() {
+        let reason: Vec<u16> = "Saving…".encode_utf16().chain(Some(0)).collect();
+        // SAFETY: `hwnd` is our window, and `reason` is a nul-terminated wide string.
+        unsafe {
+            ShutdownBlockReasonCreate(hwnd, reason.as_ptr());
+        }
+        return 0; // Not yet, please
+    }
+
+    // SAFETY: forwarding the message we got, unchanged. This allows ending the session.
+    unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    /// The state (and the signal handlers) are global to the process,
+    /// so the tests that touch them must not run at the same time.
+    static GLOBAL_STATE: parking_lot::Mutex<()> = parking_lot::const_mutex(());
+
+    fn counting_sender() -> (Arc<AtomicUsize>, SendSessionEnding) {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let send = Box::new({
+            let sent = sent.clone();
+            move || {
+                sent.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+        });
+        (sent, send)
+    }
+
+    #[test]
+    fn request_shutdown_once_until_handled() {
+        let _lock = GLOBAL_STATE.lock();
+
+        let (sent, send) = counting_sender();
+        start(send);
+        let listener = SessionEndListener {
+            #[cfg(unix)]
+            _signal_handlers: None,
+        };
+
+        assert!(!is_handled());
+        assert!(request_shutdown(), "the first request should be sent");
+        assert!(
+            !request_shutdown(),
+            "a second request means we should give up"
+        );
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+        mark_handled();
+        assert!(is_handled());
+
+        // Starting over (e.g. the next `run_native`) forgets the old session:
+        start(Box::new(|| false));
+        assert!(!is_handled());
+        assert!(!request_shutdown(), "the event loop is gone");
+
+        start(Box::new(|| true));
+        drop(listener);
+        assert!(!request_shutdown(), "nobody is listening after drop");
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn only_hold_off_the_end_of_the_session_with_unsaved_changes() {
+        let _lock = GLOBAL_STATE.lock();
+
+        // Nothing to lose: let the session end, but still ask the app to save:
+        let (sent, send) = counting_sender();
+        start(send);
+        assert!(!query_end_session());
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+        // Unsaved changes: hold off until the app has been saved.
+        let (sent, send) = counting_sender();
+        start(send);
+        set_unsaved_changes(true);
+        assert!(query_end_session());
+        assert!(query_end_session(), "Windows asks again while we save");
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+        mark_handled();
+        assert!(!query_end_session());
+
+        // Nobody to save the changes:
+        *SEND.lock() = None;
+        HANDLED.store(false, Ordering::SeqCst);
+        assert!(!query_end_session());
+    }
+
+    /// Sets the default disposition for [`unix::SIGNALS`] for the duration of a test,
+    /// and puts back what was there before on drop, even if the test fails.
+    #[cfg(unix)]
+    struct DefaultSignalHandlers(Vec<(libc::c_int, libc::sigaction)>);
+
+    #[cfg(unix)]
+    #[allow(unsafe_code)]
+    impl DefaultSignalHandlers {
+        fn set() -> Self {
+            Self(
+                unix::SIGNALS
+                    .into_iter()
+                    .map(|signal| {
+                        // SAFETY: the default disposition is always valid.
+                        unsafe {
+                            let mut previous: libc::sigaction = std::mem::zeroed();
+                            let mut action: libc::sigaction = std::mem::zeroed();
+                            action.sa_sigaction = libc::SIG_DFL;
+                            libc::sigaction(signal, &action, &mut previous);
+                            (signal, previous)
+                        }
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    #[cfg(unix)]
+    #[allow(unsafe_code)]
+    impl Drop for DefaultSignalHandlers {
+        fn drop(&mut self) {
+            for (signal, previous) in &self.0 {
+                // SAFETY: putting back the action that was there before the test.
+                unsafe {
+                    libc::sigaction(*signal, previous, std::ptr::null_mut());
+                }
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[allow(unsafe_code)]
+    fn signal_handlers_are_restored_on_drop() {
+        fn current_handler(signal: libc::c_int) -> libc::sighandler_t {
+            // SAFETY: only queries the current action.
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                libc::sigaction(signal, std::ptr::null(), &mut action);
+                action.sa_sigaction
+            }
+        }
+
+        let _lock = GLOBAL_STATE.lock();
+        let _default_handlers = DefaultSignalHandlers::set();
+
+        assert_eq!(current_handler(libc::SIGHUP), libc::SIG_DFL);
+        let handlers = unix::SignalHandlers::install().unwrap();
+        assert_ne!(current_handler(libc::SIGHUP), libc::SIG_DFL);
+        assert!(
+            unix::SignalHandlers::install().is_none(),
+            "only one set of handlers at a time"
+        );
+        drop(handlers);
+        assert_eq!(current_handler(libc::SIGHUP), libc::SIG_DFL);
+    }
+}
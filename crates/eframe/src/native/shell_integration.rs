@@ -0,0 +1,684 @@
+//! Integrate with the menus of the OS shell:
+//! the taskbar jump list on Windows, and the dock menu and recent documents on Mac.
+//!
+//! See [`crate::Frame::set_shell_tasks`], [`crate::Frame::add_recent_document`]
+//! and [`crate::App::on_shell_event`].
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::Path,
+};
+
+use winit::event_loop::EventLoopProxy;
+
+use super::winit_integration::UserEvent;
+use crate::{ShellEvent, ShellTask};
+
+/// Where to send [`UserEvent::Shell`]. `None` while no eframe event loop is running.
+static PROXY: parking_lot::Mutex<Option<EventLoopProxy<UserEvent>>> =
+    parking_lot::const_mutex(None);
+
+/// Events that have not yet been delivered to the app.
+static PENDING: parking_lot::Mutex<Vec<ShellEvent>> = parking_lot::const_mutex(Vec::new());
+
+/// See [`crate::Frame::set_shell_tasks`].
+static TASKS: parking_lot::Mutex<Vec<ShellTask>> = parking_lot::const_mutex(Vec::new());
+
+/// Picking a task in the Windows jump list starts the app with this argument, followed by the task id.
+const COMMAND_ARGUMENT: &str = "--eframe-shell-command=";
+
+/// The id of the [`ShellTask`] if this is the argument that the Windows jump list
+/// starts the app with when the user picks it, i.e. `--eframe-shell-command=<id>`.
+///
+/// You only need this if you forward the command line to an instance of the app that is already running.
+/// eframe turns the argument into a [`ShellEvent::Command`] when it starts.
+pub fn parse_shell_command(arg: &OsStr) -> Option<&str> {
+    arg.to_str()?.strip_prefix(COMMAND_ARGUMENT)
+}
+
+/// The command line arguments of the app, without the `--eframe-shell-command=<id>`
+/// that the Windows jump list adds (see [`parse_shell_command`]).
+///
+/// Use this instead of [`std::env::args_os`] when parsing the command line,
+/// e.g. `Cli::parse_from(eframe::args_os())` with `clap`,
+/// or the unknown argument makes the parsing fail when the user picks a [`ShellTask`].
+pub fn args_os() -> impl Iterator<Item = OsString> {
+    std::env::args_os().filter(|arg| parse_shell_command(arg).is_none())
+}
+
+/// The events for the command line that the Windows shell started the app with,
+/// skipping the executable in `args[0]`.
+///
+/// The jump list passes `--eframe-shell-command=<id>`, and opening a (recent) document
+/// passes the path of it, so arguments that are paths of existing files are opened.
+/// Other arguments are left to the app.
+#[cfg(any(target_os = "windows", test))]
+fn events_from_args(args: impl IntoIterator<Item = OsString>) -> Vec<ShellEvent> {
+    let mut events = vec![];
+    let mut files = vec![];
+    for arg in args.into_iter().skip(1) {
+        if let Some(id) = parse_shell_command(&arg) {
+            events.push(ShellEvent::Command(id.to_owned()));
+        } else if !arg.to_string_lossy().starts_with('-') && Path::new(&arg).is_file() {
+            files.push(std::path::PathBuf::from(arg));
+        }
+    }
+    if !files.is_empty() {
+        events.push(ShellEvent::OpenFiles(files));
+    }
+    events
+}
+
+/// Forwards shell events to an event loop until dropped.
+pub struct ShellListener {
+    _private: (),
+}
+
+impl Drop for ShellListener {
+    fn drop(&mut self) {
+        *PROXY.lock() = None;
+    }
+}
+
+/// Start sending [`UserEvent::Shell`] to the given event loop when the user uses the shell menus.
+pub fn listen(proxy: EventLoopProxy<UserEvent>) -> ShellListener {
+    *PROXY.lock() = Some(proxy);
+
+    #[cfg(target_os = "windows")]
+    {
+        // Only once, even if the app runs several event loops:
+        static STARTED: std::sync::Once = std::sync::Once::new();
+        STARTED.call_once(|| {
+            for event in events_from_args(std::env::args_os()) {
+                send(event);
+            }
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    mac::install_delegate_methods();
+
+    ShellListener { _private: () }
+}
+
+/// The events to give to [`crate::App::on_shell_event`].
+pub fn take_events() -> Vec<ShellEvent> {
+    std::mem::take(&mut *PENDING.lock())
+}
+
+/// Queue an event for the app, and wake up the event loop to deliver it.
+#[cfg(any(target_os = "windows", target_os = "macos", test))]
+fn send(event: ShellEvent) {
+    log::debug!("Shell event: {event:?}");
+    PENDING.lock().push(event);
+    if let Some(proxy) = PROXY.lock().as_ref() {
+        proxy.send_event(UserEvent::Shell).ok();
+    }
+}
+
+/// See [`crate::Frame::set_shell_tasks`].
+pub fn set_tasks(tasks: Vec<ShellTask>) {
+    #[cfg(target_os = "windows")]
+    windows::set_tasks(&tasks);
+
+    *TASKS.lock() = tasks;
+}
+
+/// See [`crate::Frame::add_recent_document`].
+pub fn add_recent_document(path: &Path) {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_owned());
+
+    #[cfg(target_os = "windows")]
+    windows::add_recent_document(&path);
+
+    #[cfg(target_os = "macos")]
+    mac::add_recent_document(&path);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let _ = path;
+}
+
+// ----------------------------------------------------------------------------
+
+/// The taskbar jump list, via `ICustomDestinationList`.
+///
+/// `windows-sys` has no COM interfaces, so we declare the few methods we call ourselves.
+#[cfg(target_os = "windows")]
+#[allow(unsafe_code)]
+mod windows {
+    use std::{
+        ffi::{c_void, OsStr},
+        os::windows::ffi::OsStrExt as _,
+        path::Path,
+        ptr::{null, null_mut, NonNull},
+    };
+
+    use windows_sys::{
+        core::{GUID, HRESULT},
+        Win32::{
+            Foundation::E_FAIL,
+            System::Com::{
+                CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+                COINIT_APARTMENTTHREADED,
+            },
+            UI::Shell::{
+                DestinationList, EnumerableObjectCollection, SHAddToRecentDocs, ShellLink,
+                KDC_RECENT, SHARD_PATHW,
+            },
+        },
+    };
+
+    use super::COMMAND_ARGUMENT;
+    use crate::ShellTask;
+
+    const IID_ICUSTOM_DESTINATION_LIST: GUID =
+        GUID::from_u128(0x6332_debf_87b5_4670_90c0_5e57_b408_a49e);
+    const IID_IOBJECT_ARRAY: GUID = GUID::from_u128(0x92ca_9dcd_5622_4bba_a805_5e9f_541b_d8c9);
+    const IID_IOBJECT_COLLECTION: GUID = GUID::from_u128(0x5632_b1a4_e38a_400a_928a_d4cd_6323_0295);
+    const IID_ISHELL_LINK_W: GUID = GUID::from_u128(0x0002_14f9_0000_0000_c000_0000_0000_0046);
+    const IID_IPROPERTY_STORE: GUID = GUID::from_u128(0x886d_8eeb_8cf2_4446_8d02_cdba_1dbd_cf99);
+
+    /// `PKEY_Title`: the text shown for a task in the jump list.
+    const PKEY_TITLE: PropertyKey = PropertyKey {
+        fmtid: GUID::from_u128(0xf29f_85e0_4ff9_1068_ab91_0800_2b27_b3d9),
+        pid: 2,
+    };
+
+    const VT_LPWSTR: u16 = 31;
+
+    #[repr(C)]
+    struct PropertyKey {
+        fmtid: GUID,
+        pid: u32,
+    }
+
+    /// A `PROPVARIANT` holding a string (`VT_LPWSTR`).
+    #[repr(C)]
+    struct PropVariant {
+        vt: u16,
+        reserved: [u16; 3],
+        value: *const u16,
+        padding: usize,
+    }
+
+    type Unused = usize;
+
+    #[repr(C)]
+    struct IUnknownVtbl {
+        query_interface:
+            unsafe extern "system" fn(*mut c_void, *const GUID, *mut *mut c_void) -> HRESULT,
+        add_ref: Unused,
+        release: unsafe extern "system" fn(*mut c_void) -> u32,
+    }
+
+    #[repr(C)]
+    struct ICustomDestinationListVtbl {
+        base: IUnknownVtbl,
+        set_app_id: Unused,
+        begin_list: unsafe extern "system" fn(
+            *mut c_void,
+            *mut u32,
+            *const GUID,
+            *mut *mut c_void,
+        ) -> HRESULT,
+        append_category: Unused,
+        append_known_category: unsafe extern "system" fn(*mut c_void, i32) -> HRESULT,
+        add_user_tasks: unsafe extern "system" fn(*mut c_void, *mut c_void) -> HRESULT,
+        commit_list: unsafe extern "system" fn(*mut c_void) -> HRESULT,
+        get_removed_destinations: Unused,
+        delete_list: unsafe extern "system" fn(*mut c_void, *const u16) -> HRESULT,
+        abort_list: unsafe extern "system" fn(*mut c_void) -> HRESULT,
+    }
+
+    #[repr(C)]
+    struct IObjectCollectionVtbl {
+        base: IUnknownVtbl,
+        get_count: Unused,
+        get_at: Unused,
+        add_object: unsafe extern "system" fn(*mut c_void, *mut c_void) -> HRESULT,
+    }
+
+    #[repr(C)]
+    struct IShellLinkWVtbl {
+        base: IUnknownVtbl,
+        get_path: Unused,
+        get_id_list: Unused,
+        set_id_list: Unused,
+        get_description: Unused,
+        set_description: unsafe extern "system" fn(*mut c_void, *const u16) -> HRESULT,
+        get_working_directory: Unused,
+        set_working_directory: Unused,
+        get_arguments: Unused,
+        set_arguments: unsafe extern "system" fn(*mut c_void, *const u16) -> HRESULT,
+        get_hotkey: Unused,
+        set_hotkey: Unused,
+        get_show_cmd: Unused,
+        set_show_cmd: Unused,
+        get_icon_location: Unused,
+        set_icon_location: unsafe extern "system" fn(*mut c_void, *const u16, i32) -> HRESULT,
+        set_relative_path: Unused,
+        resolve: Unused,
+        set_path: unsafe extern "system" fn(*mut c_void, *const u16) -> HRESULT,
+    }
+
+    #[repr(C)]
+    struct IPropertyStoreVtbl {
+        base: IUnknownVtbl,
+        get_count: Unused,
+        get_at: Unused,
+        get_value: Unused,
+        set_value: unsafe extern "system" fn(
+            *mut c_void,
+            *const PropertyKey,
+            *const PropVariant,
+        ) -> HRESULT,
+        commit: unsafe extern "system" fn(*mut c_void) -> HRESULT,
+    }
+
+    /// An owned COM interface pointer with the vtable `V`, released on drop.
+    struct ComPtr<V>(NonNull<*const V>);
+
+    impl<V> ComPtr<V> {
+        /// # Safety
+        /// `V` must be the vtable of the interface `iid`.
+        unsafe fn create(clsid: &GUID, iid: &GUID) -> Result<Self, HRESULT> {
+            let mut ptr = null_mut();
+            // SAFETY: the caller guarantees that `V` matches `iid`.
+            let hr =
+                unsafe { CoCreateInstance(clsid, null_mut(), CLSCTX_INPROC_SERVER, iid, &mut ptr) };
+            // SAFETY: see above.
+            unsafe { Self::from_raw(hr, ptr) }
+        }
+
+        /// # Safety
+        /// `U` must be the vtable of the interface `iid`.
+        unsafe fn query<U>(&self, iid: &GUID) -> Result<ComPtr<U>, HRESULT> {
+            let mut ptr = null_mut();
+            // SAFETY: we own a valid interface pointer.
+            let hr = unsafe { (self.unknown().query_interface)(self.as_raw(), iid, &mut ptr) };
+            // SAFETY: the caller guarantees that `U` matches `iid`.
+            unsafe { ComPtr::from_raw(hr, ptr) }
+        }
+
+        /// # Safety
+        /// If `hr` is a success, `ptr` must be an owned interface pointer with the vtable `V`.
+        unsafe fn from_raw(hr: HRESULT, ptr: *mut c_void) -> Result<Self, HRESULT> {
+            check(hr)?;
+            NonNull::new(ptr.cast()).map(Self).ok_or(E_FAIL)
+        }
+
+        fn as_raw(&self) -> *mut c_void {
+            self.0.as_ptr().cast()
+        }
+
+        fn vtbl(&self) -> &V {
+            // SAFETY: a COM object starts with a pointer to its vtable.
+            unsafe { &**self.0.as_ptr() }
+        }
+
+        fn unknown(&self) -> &IUnknownVtbl {
+            // SAFETY: every COM vtable starts with the `IUnknown` methods.
+            unsafe { &*(*self.0.as_ptr()).cast::<IUnknownVtbl>() }
+        }
+    }
+
+    impl<V> Drop for ComPtr<V> {
+        fn drop(&mut self) {
+            // SAFETY: we own one reference.
+            unsafe {
+                (self.unknown().release)(self.as_raw());
+            }
+        }
+    }
+
+    fn check(hr: HRESULT) -> Result<(), HRESULT> {
+        if hr < 0 {
+            Err(hr)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// A null-terminated UTF-16 string.
+    fn wide(s: impl AsRef<OsStr>) -> Vec<u16> {
+        s.as_ref().encode_wide().chain(Some(0)).collect()
+    }
+
+    fn with_com<R>(f: impl FnOnce() -> R) -> R {
+        // SAFETY: balanced with `CoUninitialize` below.
+        // `winit` has usually initialized COM on this thread already, which is fine.
+        let initialized = unsafe { CoInitializeEx(null(), COINIT_APARTMENTTHREADED as u32) } >= 0;
+        let result = f();
+        if initialized {
+            // SAFETY: we initialized COM above.
+            unsafe { CoUninitialize() };
+        }
+        result
+    }
+
+    pub fn set_tasks(tasks: &[ShellTask]) {
+        // SAFETY: we only talk to COM on this thread.
+        if let Err(hr) = with_com(|| unsafe { update_jump_list(tasks) }) {
+            log::warn!("Failed to update the jump list: HRESULT {hr:#010x}");
+        }
+    }
+
+    unsafe fn update_jump_list(tasks: &[ShellTask]) -> Result<(), HRESULT> {
+        // SAFETY: the vtable matches the interface.
+        let list = unsafe {
+            ComPtr::<ICustomDestinationListVtbl>::create(
+                &DestinationList,
+                &IID_ICUSTOM_DESTINATION_LIST,
+            )?
+        };
+        let vtbl = list.vtbl();
+
+        if tasks.is_empty() {
+            // SAFETY: a null app id means our own jump list.
+            return check(unsafe { (vtbl.delete_list)(list.as_raw(), null()) });
+        }
+
+        let mut min_slots = 0;
+        let mut removed = null_mut();
+        // SAFETY: we release `removed` right away.
+        unsafe {
+            check((vtbl.begin_list)(
+                list.as_raw(),
+                &mut min_slots,
+                &IID_IOBJECT_ARRAY,
+                &mut removed,
+            ))?;
+            drop(ComPtr::<IUnknownVtbl>::from_raw(0, removed));
+        }
+
+        // SAFETY: all vtables match their interfaces.
+        let result = unsafe {
+            let add_tasks = || -> Result<(), HRESULT> {
+                let collection = ComPtr::<IObjectCollectionVtbl>::create(
+                    &EnumerableObjectCollection,
+                    &IID_IOBJECT_COLLECTION,
+                )?;
+                let exe = std::env::current_exe().map_err(|err| {
+                    log::warn!("Failed to find the path of the executable: {err}");
+                    E_FAIL
+                })?;
+                let exe = wide(exe);
+                for task in tasks {
+                    let link = shell_link(&exe, task)?;
+                    check((collection.vtbl().add_object)(
+                        collection.as_raw(),
+                        link.as_raw(),
+                    ))?;
+                }
+
+                // Only shows anything if the app is registered to open the recent documents:
+                (vtbl.append_known_category)(list.as_raw(), KDC_RECENT);
+
+                // An `IObjectCollection` is an `IObjectArray`:
+                check((vtbl.add_user_tasks)(list.as_raw(), collection.as_raw()))?;
+                check((vtbl.commit_list)(list.as_raw()))
+            };
+            add_tasks()
+        };
+
+        if result.is_err() {
+            // SAFETY: we began the list above.
+            unsafe { (vtbl.abort_list)(list.as_raw()) };
+        }
+        result
+    }
+
+    /// A shell link that starts the app again with [`COMMAND_ARGUMENT`].
+    unsafe fn shell_link(
+        exe: &[u16],
+        task: &ShellTask,
+    ) -> Result<ComPtr<IShellLinkWVtbl>, HRESULT> {
+        // SAFETY: the vtables match their interfaces, and the strings outlive the calls.
+        unsafe {
+            let link = ComPtr::<IShellLinkWVtbl>::create(&ShellLink, &IID_ISHELL_LINK_W)?;
+            let vtbl = link.vtbl();
+            let arguments = format!("\"{COMMAND_ARGUMENT}{}\"", task.id.replace('"', ""));
+            check((vtbl.set_path)(link.as_raw(), exe.as_ptr()))?;
+            check((vtbl.set_arguments)(
+                link.as_raw(),
+                wide(arguments).as_ptr(),
+            ))?;
+            check((vtbl.set_icon_location)(link.as_raw(), exe.as_ptr(), 0))?;
+            check((vtbl.set_description)(
+                link.as_raw(),
+                wide(&task.title).as_ptr(),
+            ))?;
+
+            let store = link.query::<IPropertyStoreVtbl>(&IID_IPROPERTY_STORE)?;
+            let title = wide(&task.title);
+            let value = PropVariant {
+                vt: VT_LPWSTR,
+                reserved: [0; 3],
+                value: title.as_ptr(),
+                padding: 0,
+            };
+            check((store.vtbl().set_value)(
+                store.as_raw(),
+                &PKEY_TITLE,
+                &value,
+            ))?;
+            check((store.vtbl().commit)(store.as_raw()))?;
+
+            Ok(link)
+        }
+    }
+
+    pub fn add_recent_document(path: &Path) {
+        let path = wide(path);
+        // SAFETY: `path` is a null-terminated wide string.
+        unsafe { SHAddToRecentDocs(SHARD_PATHW as u32, path.as_ptr().cast()) };
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The dock menu and recent documents.
+///
+/// `winit` owns the app delegate, so we make it an instance of a subclass
+/// that also implements the methods `AppKit` asks it for.
+/// We only do that to the delegate class of `winit` we know, which implements none of them.
+#[cfg(target_os = "macos")]
+#[allow(unsafe_code)]
+mod mac {
+    use std::path::{Path, PathBuf};
+
+    use objc2::{
+        declare::ClassBuilder,
+        ffi,
+        rc::Retained,
+        runtime::{AnyClass, AnyObject, Sel},
+        sel,
+    };
+    use objc2_app_kit::{NSApplication, NSDocumentController, NSMenu, NSMenuItem};
+    use objc2_foundation::{ns_string, MainThreadMarker, NSArray, NSString, NSURL};
+
+    use crate::ShellEvent;
+
+    const DELEGATE_CLASS: &str = "EframeShellAppDelegate";
+
+    /// The class of the app delegate of `winit`.
+    const WINIT_DELEGATE_CLASS: &str = "WinitApplicationDelegate";
+
+    pub fn install_delegate_methods() {
+        let Some(mtm) = MainThreadMarker::new() else {
+            log::debug!("Not on the main thread - the dock menu is not available");
+            return;
+        };
+        let app = NSApplication::sharedApplication(mtm);
+        // SAFETY: reading the delegate has no side effects.
+        let Some(delegate) = (unsafe { app.delegate() }) else {
+            log::debug!("NSApp has no delegate - the dock menu is not available");
+            return;
+        };
+        let delegate = Retained::as_ptr(&delegate).cast::<AnyObject>().cast_mut();
+        // SAFETY: every protocol object is an object.
+        let class = unsafe { &*delegate }.class();
+        if class.name() == DELEGATE_CLASS {
+            return; // E.g. if we already did this for an earlier event loop.
+        }
+        if class.name() != WINIT_DELEGATE_CLASS {
+            log::warn!(
+                "The app delegate is a {}, not a {WINIT_DELEGATE_CLASS} - the dock menu and opening files are not available",
+                class.name()
+            );
+            return;
+        }
+
+        let Some(subclass) = delegate_subclass(class) else {
+            log::debug!("Failed to subclass the app delegate - the dock menu is not available");
+            return;
+        };
+        // SAFETY: the subclass only adds methods to the class of the delegate, so its layout is the same.
+        unsafe { ffi::object_setClass(delegate.cast(), std::ptr::from_ref(subclass).cast()) };
+    }
+
+    /// A subclass of `class` that adds our delegate methods.
+    fn delegate_subclass(class: &'static AnyClass) -> Option<&'static AnyClass> {
+        let Some(mut builder) = ClassBuilder::new(DELEGATE_CLASS, class) else {
+            // Already registered, by an earlier event loop with a delegate of the same class.
+            return AnyClass::get(DELEGATE_CLASS)
+                .filter(|subclass| subclass.superclass() == Some(class));
+        };
+
+        // SAFETY: the functions have the signatures `AppKit` calls them with.
+        unsafe {
+            builder.add_method(
+                sel!(applicationDockMenu:),
+                dock_menu as unsafe extern "C" fn(_, _, _) -> _,
+            );
+            builder.add_method(
+                sel!(application:openURLs:),
+                open_urls as unsafe extern "C" fn(_, _, _, _),
+            );
+            builder.add_method(
+                sel!(eframeShellTask:),
+                on_task as unsafe extern "C" fn(_, _, _),
+            );
+        }
+        Some(builder.register())
+    }
+
+    unsafe extern "C" fn dock_menu(this: &AnyObject, _cmd: Sel, _app: &AnyObject) -> *mut NSMenu {
+        // SAFETY: AppKit talks to the app delegate on the main thread.
+        let mtm = unsafe { MainThreadMarker::new_unchecked() };
+        let menu = NSMenu::new(mtm);
+        for (index, task) in super::TASKS.lock().iter().enumerate() {
+            // SAFETY: `eframeShellTask:` is implemented by `this`.
+            unsafe {
+                let item = NSMenuItem::initWithTitle_action_keyEquivalent(
+                    mtm.alloc(),
+                    &NSString::from_str(&task.title),
+                    Some(sel!(eframeShellTask:)),
+                    ns_string!(""),
+                );
+                item.setTag(index as isize);
+                item.setTarget(Some(this));
+                menu.addItem(&item);
+            }
+        }
+        Retained::autorelease_return(menu)
+    }
+
+    unsafe extern "C" fn on_task(_this: &AnyObject, _cmd: Sel, item: &NSMenuItem) {
+        // SAFETY: reading the tag has no side effects.
+        let index = unsafe { item.tag() };
+        let id = usize::try_from(index)
+            .ok()
+            .and_then(|index| super::TASKS.lock().get(index).map(|task| task.id.clone()));
+        if let Some(id) = id {
+            super::send(ShellEvent::Command(id));
+        }
+    }
+
+    unsafe extern "C" fn open_urls(
+        _this: &AnyObject,
+        _cmd: Sel,
+        _app: &AnyObject,
+        urls: &NSArray<NSURL>,
+    ) {
+        let paths: Vec<PathBuf> = urls
+            .iter()
+            // SAFETY: reading the path has no side effects.
+            .filter_map(|url| unsafe { url.path() })
+            .map(|path| PathBuf::from(path.to_string()))
+            .collect();
+        if !paths.is_empty() {
+            super::send(ShellEvent::OpenFiles(paths));
+        }
+    }
+
+    pub fn add_recent_document(path: &Path) {
+        let Some(mtm) = MainThreadMarker::new() else {
+            log::warn!("Recent documents can only be added on the main thread");
+            return;
+        };
+        // SAFETY: plain method calls on the main thread.
+        unsafe {
+            let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+            NSDocumentController::sharedDocumentController(mtm).noteNewRecentDocumentURL(&url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_command_is_parsed() {
+        assert_eq!(
+            parse_shell_command(OsStr::new("--eframe-shell-command=new-window")),
+            Some("new-window")
+        );
+        assert_eq!(
+            parse_shell_command(OsStr::new("--eframe-shell-command=")),
+            Some("")
+        );
+        assert_eq!(parse_shell_command(OsStr::new("--verbose")), None);
+        assert_eq!(parse_shell_command(OsStr::new("new-window")), None);
+    }
+
+    #[test]
+    fn events_are_made_from_the_command_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("document.txt");
+        std::fs::write(&file, "hello").unwrap();
+
+        let args = [
+            OsString::from("app.exe"),
+            OsString::from("--eframe-shell-command=new-window"),
+            OsString::from("--verbose"),
+            file.clone().into_os_string(),
+            dir.path().join("missing.txt").into_os_string(),
+            dir.path().as_os_str().to_owned(),
+        ];
+        assert_eq!(
+            events_from_args(args),
+            vec![
+                ShellEvent::Command("new-window".to_owned()),
+                ShellEvent::OpenFiles(vec![file]),
+            ]
+        );
+
+        assert_eq!(events_from_args([OsString::from("app.exe")]), vec![]);
+    }
+
+    #[test]
+    fn events_are_taken_once() {
+        send(ShellEvent::Command("a".to_owned()));
+        send(ShellEvent::Command("b".to_owned()));
+        assert_eq!(
+            take_events(),
+            vec![
+                ShellEvent::Command("a".to_owned()),
+                ShellEvent::Command("b".to_owned()),
+            ]
+        );
+        assert_eq!(take_events(), vec![]);
+    }
+}
@@ -9,7 +9,7 @@ use std::{cell::RefCell, num::NonZeroU32, rc::Rc, sync::Arc, time::Instant};
 
 use egui_winit::ActionRequested;
 use parking_lot::Mutex;
-use raw_window_handle::{HasDisplayHandle as _, HasWindowHandle as _};
+use raw_window_handle::{HandleError, HasDisplayHandle as _, HasWindowHandle as _};
 use winit::{
     event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy},
     window::{Window, WindowId},
@@ -26,7 +26,7 @@ use winit_integration::UserEvent;
 
 use crate::{
     native::{epi_integration::EpiIntegration, winit_integration::EventResult},
-    App, AppCreator, CreationContext, NativeOptions, Result, Storage,
+    App, AppCreator, CreationContext, LifecycleEvent, NativeOptions, Result, Storage,
 };
 
 use super::{epi_integration, event_loop_context, winit_integration, winit_integration::WinitApp};
@@ -85,6 +85,9 @@ pub struct Viewport {
     /// `None` for sync viewports.
     viewport_ui_cb: Option<Arc<DeferredViewportUiCallback>>,
 
+    /// See [`epi_integration::CloseLifecycle`].
+    close: epi_integration::CloseLifecycle,
+
     /// Window surface state that's initialized when the app starts running via a Resumed event
     /// and on Android will also be destroyed if the application is paused.
     window: Option<Arc<Window>>,
@@ -132,13 +135,25 @@ impl<'app> WgpuWinitApp<'app> {
             ..
         } = &mut *shared;
 
-        for viewport in viewports.values_mut() {
-            viewport.initialize_window(
-                event_loop,
-                &running.integration.egui_ctx,
-                viewport_from_window,
-                painter,
-            );
+        let viewport_ids = egui_winit::owners_first(
+            viewports
+                .iter()
+                .map(|(id, viewport)| (*id, viewport.builder.owner)),
+        );
+        for viewport_id in viewport_ids {
+            if is_waiting_for_owner(viewports, viewport_id) {
+                continue;
+            }
+            let owner_window = owner_window(viewports, viewport_id);
+            if let Some(viewport) = viewports.get_mut(&viewport_id) {
+                viewport.initialize_window(
+                    event_loop,
+                    &running.integration.egui_ctx,
+                    viewport_from_window,
+                    painter,
+                    owner_window.as_deref(),
+                );
+            }
         }
     }
 
@@ -160,7 +175,7 @@ impl<'app> WgpuWinitApp<'app> {
             None,
             painter,
         )
-        .initialize_window(event_loop, egui_ctx, viewport_from_window, painter);
+        .initialize_window(event_loop, egui_ctx, viewport_from_window, painter, None);
     }
 
     #[cfg(target_os = "android")]
@@ -236,15 +251,15 @@ impl<'app> WgpuWinitApp<'app> {
             });
         }
 
-        #[allow(unused_mut)] // used for accesskit
         let mut egui_winit = egui_winit::State::new(
             egui_ctx.clone(),
             ViewportId::ROOT,
             event_loop,
-            Some(window.scale_factor() as f32),
+            Some(egui_winit::native_pixels_per_point(&window)),
             event_loop.system_theme(),
             painter.max_texture_side(),
         );
+        winit_integration::init_frame_telemetry(&mut egui_winit, ViewportId::ROOT);
 
         #[cfg(feature = "accesskit")]
         {
@@ -274,7 +289,12 @@ impl<'app> WgpuWinitApp<'app> {
         let mut viewport_from_window = HashMap::default();
         viewport_from_window.insert(window.id(), ViewportId::ROOT);
 
-        let mut info = ViewportInfo::default();
+        let mut info = ViewportInfo {
+            render_scale: builder
+                .render_scale
+                .map(egui::emath::OrderedFloat::into_inner),
+            ..Default::default()
+        };
         egui_winit::update_viewport_info(&mut info, &egui_ctx, &window, true);
 
         let mut viewports = Viewports::default();
@@ -288,6 +308,7 @@ impl<'app> WgpuWinitApp<'app> {
                 info,
                 actions_requested: Default::default(),
                 viewport_ui_cb: None,
+                close: Default::default(),
                 window: Some(window),
                 egui_winit: Some(egui_winit),
             },
@@ -355,6 +376,12 @@ impl WinitApp for WgpuWinitApp<'_> {
         )
     }
 
+    fn repaint_mode(&self) -> crate::RepaintMode {
+        self.running
+            .as_ref()
+            .map_or_else(Default::default, |r| r.integration.frame.repaint_mode())
+    }
+
     fn save_and_destroy(&mut self) {
         if let Some(mut running) = self.running.take() {
             running.save_and_destroy();
@@ -378,9 +405,17 @@ impl WinitApp for WgpuWinitApp<'_> {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) -> crate::Result<EventResult> {
         log::debug!("Event::Resumed");
 
-        let running = if let Some(running) = &self.running {
-            #[cfg(target_os = "android")]
+        #[cfg(target_os = "android")]
+        if let Some(running) = &self.running {
+            // Not the first resume event. Recreate the window before telling the app about it.
             self.recreate_window(event_loop, running);
+        }
+
+        let running = if let Some(running) = &mut self.running {
+            // Not the first resume event.
+            running
+                .integration
+                .on_lifecycle_event(running.app.as_mut(), LifecycleEvent::Resumed);
             running
         } else {
             let storage = if let Some(file) = &self.native_options.persistence_path {
@@ -392,6 +427,7 @@ impl WinitApp for WgpuWinitApp<'_> {
                         .app_id
                         .as_ref()
                         .unwrap_or(&self.app_name),
+                    &self.native_options.storage_location,
                 )
             };
             let egui_ctx = winit_integration::create_egui_context(storage.as_deref());
@@ -415,9 +451,32 @@ impl WinitApp for WgpuWinitApp<'_> {
     fn suspended(&mut self, _: &ActiveEventLoop) -> crate::Result<EventResult> {
         #[cfg(target_os = "android")]
         self.drop_window()?;
+        if let Some(running) = &mut self.running {
+            running
+                .integration
+                .on_lifecycle_event(running.app.as_mut(), LifecycleEvent::Suspended);
+        }
+        Ok(EventResult::Wait)
+    }
+
+    fn memory_warning(&mut self) -> crate::Result<EventResult> {
+        if let Some(running) = &mut self.running {
+            running
+                .integration
+                .on_lifecycle_event(running.app.as_mut(), LifecycleEvent::LowMemory);
+        }
         Ok(EventResult::Wait)
     }
 
+    fn session_ending(&mut self) -> crate::Result<EventResult> {
+        if let Some(running) = &mut self.running {
+            running
+                .integration
+                .on_lifecycle_event(running.app.as_mut(), LifecycleEvent::SessionEnding);
+        }
+        Ok(EventResult::Exit)
+    }
+
     fn device_event(
         &mut self,
         _: &ActiveEventLoop,
@@ -487,13 +546,72 @@ impl WinitApp for WgpuWinitApp<'_> {
     }
 }
 
+/// See [`crate::Frame::swap_app`].
+///
+/// Call this after painting and presenting the frame,
+/// so that its paint callbacks are done with the resources of the old app.
+fn swap_app<'app>(
+    app: &mut Box<dyn 'app + App>,
+    integration: &EpiIntegration,
+    shared: &RefCell<SharedState>,
+    app_creator: AppCreator<'static>,
+) {
+    let shared = shared.borrow();
+    let window = shared
+        .viewports
+        .get(&ViewportId::ROOT)
+        .and_then(|viewport| viewport.window.clone());
+
+    epi_integration::swap_app(
+        app,
+        integration.frame.storage(),
+        |storage| {
+            let cc = CreationContext {
+                egui_ctx: shared.egui_ctx.clone(),
+                integration_info: integration.frame.info().clone(),
+                storage: Some(storage),
+                #[cfg(feature = "glow")]
+                gl: None,
+                #[cfg(feature = "glow")]
+                get_proc_address: None,
+                wgpu_render_state: integration.frame.wgpu_render_state.clone(),
+                raw_display_handle: window
+                    .as_deref()
+                    .map_or(Err(HandleError::Unavailable), |window| {
+                        window.display_handle().map(|h| h.as_raw())
+                    }),
+                raw_window_handle: window
+                    .as_deref()
+                    .map_or(Err(HandleError::Unavailable), |window| {
+                        window.window_handle().map(|h| h.as_raw())
+                    }),
+            };
+            profiling::scope!("user_app_creator");
+            app_creator(&cc)
+        },
+        |old_app| {
+            #[cfg(feature = "glow")]
+            old_app.on_exit(None);
+
+            #[cfg(not(feature = "glow"))]
+            old_app.on_exit();
+        },
+    );
+}
+
 impl WgpuWinitRunning<'_> {
     fn save_and_destroy(&mut self) {
         profiling::function_scope!();
 
         let mut shared = self.shared.borrow_mut();
-        if let Some(Viewport { window, .. }) = shared.viewports.get(&ViewportId::ROOT) {
+        if let Some(Viewport {
+            window, egui_winit, ..
+        }) = shared.viewports.get(&ViewportId::ROOT)
+        {
             self.integration.save(self.app.as_mut(), window.as_deref());
+            if let Some(egui_winit) = egui_winit {
+                winit_integration::save_frame_telemetry(egui_winit);
+            }
         }
 
         #[cfg(feature = "glow")]
@@ -502,6 +620,8 @@ impl WgpuWinitRunning<'_> {
         #[cfg(not(feature = "glow"))]
         self.app.on_exit();
 
+        self.integration.run_shutdown_hook(self.app.as_mut());
+
         shared.painter.destroy();
     }
 
@@ -564,6 +684,7 @@ impl WgpuWinitRunning<'_> {
                 window,
                 egui_winit,
                 info,
+                close,
                 ..
             } = viewport;
 
@@ -573,6 +694,7 @@ impl WgpuWinitRunning<'_> {
                 return Ok(EventResult::Wait);
             };
             egui_winit::update_viewport_info(info, &integration.egui_ctx, window, false);
+            close.begin_pass(info);
 
             {
                 profiling::scope!("set_window");
@@ -582,6 +704,7 @@ impl WgpuWinitRunning<'_> {
             let Some(egui_winit) = egui_winit.as_mut() else {
                 return Ok(EventResult::Wait);
             };
+            egui_winit.update_monitors(info, window);
             let mut raw_input = egui_winit.take_egui_input(window);
 
             integration.pre_update();
@@ -623,13 +746,34 @@ impl WgpuWinitRunning<'_> {
             viewport_output,
         } = full_output;
 
-        remove_viewports_not_in(viewports, painter, viewport_from_window, &viewport_output);
+        remove_viewports_not_in(
+            egui_ctx,
+            viewports,
+            painter,
+            viewport_from_window,
+            &viewport_output,
+        );
+
+        // An immediate viewport can only render its last frame together with its parent:
+        let immediate_child_closing = viewports.values().any(|viewport| {
+            viewport.ids.parent == viewport_id
+                && viewport.ids.this != viewport_id
+                && viewport.viewport_ui_cb.is_none()
+                && viewport.close.is_closing()
+        });
 
         let Some(viewport) = viewports.get_mut(&viewport_id) else {
             return Ok(EventResult::Wait);
         };
 
+        let close_requested = viewport.info.close_requested();
         viewport.info.events.clear(); // they should have been processed
+        viewport.info.user_commands.clear();
+        let closing = viewport.close.end_pass(
+            viewport_id,
+            close_requested,
+            viewport_output.get(&viewport_id),
+        );
 
         let Viewport {
             window: Some(window),
@@ -653,6 +797,7 @@ impl WgpuWinitRunning<'_> {
                 true
             }
         });
+        painter.set_render_scale(viewport_id, viewport.info.render_scale.unwrap_or(1.0));
         let vsync_secs = painter.paint_and_update_textures(
             viewport_id,
             pixels_per_point,
@@ -661,6 +806,7 @@ impl WgpuWinitRunning<'_> {
             &textures_delta,
             screenshot_commands,
         );
+        egui_winit.on_frame_presented();
 
         for action in viewport.actions_requested.drain() {
             match action {
@@ -722,11 +868,23 @@ impl WgpuWinitRunning<'_> {
             }
         }
 
-        if integration.should_close() {
-            Ok(EventResult::Exit)
+        let event_result = if integration.should_close() {
+            EventResult::Exit
+        } else if closing || immediate_child_closing {
+            // Render the last frame even if the repaint mode would ignore a repaint request:
+            EventResult::RepaintNow(window_id)
         } else {
-            Ok(EventResult::Wait)
+            EventResult::Wait
+        };
+
+        drop(shared_mut);
+
+        // Only now that the frame is presented is the old app done with its GPU resources:
+        if let Some(app_creator) = integration.frame.take_app_swap() {
+            swap_app(app, integration, shared, app_creator);
         }
+
+        Ok(event_result)
     }
 
     fn on_window_event(
@@ -736,8 +894,8 @@ impl WgpuWinitRunning<'_> {
     ) -> EventResult {
         let Self {
             integration,
+            app,
             shared,
-            ..
         } = self;
         let mut shared = shared.borrow_mut();
 
@@ -763,6 +921,14 @@ impl WgpuWinitRunning<'_> {
                 shared.focused_viewport = new_focused.then(|| viewport_id).flatten();
             }
 
+            winit::event::WindowEvent::Occluded(occluded) => {
+                if let Some(event) =
+                    winit_integration::lifecycle_event_from_occluded(viewport_id, *occluded)
+                {
+                    integration.on_lifecycle_event(app.as_mut(), event);
+                }
+            }
+
             winit::event::WindowEvent::Resized(physical_size) => {
                 // Resize with 0 width and height is used by winit to signal a minimize event on Windows.
                 // See: https://github.com/rust-windowing/winit/issues/208
@@ -802,6 +968,20 @@ impl WgpuWinitRunning<'_> {
                 }
             }
 
+            winit::event::WindowEvent::Destroyed => {
+                log::debug!("Received WindowEvent::Destroyed for viewport {viewport_id:?}");
+                if viewport_id == Some(ViewportId::ROOT) {
+                    return EventResult::Exit;
+                }
+                if let Some(ids) = viewport_id
+                    .and_then(|viewport_id| shared.viewports.get(&viewport_id))
+                    .map(|viewport| viewport.ids)
+                {
+                    child_destroyed(&integration.egui_ctx, &mut shared.viewports, ids);
+                }
+                return EventResult::Wait;
+            }
+
             _ => {}
         };
 
@@ -839,16 +1019,31 @@ impl Viewport {
         egui_ctx: &egui::Context,
         windows_id: &mut HashMap<WindowId, ViewportId>,
         painter: &mut egui_wgpu::winit::Painter,
+        owner_window: Option<&Window>,
     ) {
         if self.window.is_some() {
             return; // we already have one
         }
+        if self.close.is_closed() {
+            return; // Still shown after its last frame, see `CloseLifecycle`.
+        }
 
         profiling::function_scope!();
 
         let viewport_id = self.ids.this;
 
-        match egui_winit::create_window(egui_ctx, event_loop, &self.builder) {
+        let mut window_attributes =
+            egui_winit::create_winit_window_attributes(egui_ctx, event_loop, self.builder.clone());
+        if let Some(owner_window) = owner_window {
+            window_attributes = egui_winit::with_owner_window(window_attributes, owner_window);
+        }
+        let window = event_loop
+            .create_window(window_attributes)
+            .inspect(|window| {
+                egui_winit::apply_viewport_builder_to_window(egui_ctx, window, &self.builder);
+            });
+
+        match window {
             Ok(window) => {
                 windows_id.insert(window.id(), viewport_id);
 
@@ -860,14 +1055,16 @@ impl Viewport {
                     log::error!("on set_window: viewport_id {viewport_id:?} {err}");
                 }
 
-                self.egui_winit = Some(egui_winit::State::new(
+                let mut egui_winit = egui_winit::State::new(
                     egui_ctx.clone(),
                     viewport_id,
                     event_loop,
-                    Some(window.scale_factor() as f32),
+                    Some(egui_winit::native_pixels_per_point(&window)),
                     event_loop.system_theme(),
                     painter.max_texture_side(),
-                ));
+                );
+                winit_integration::init_frame_telemetry(&mut egui_winit, viewport_id);
+                self.egui_winit = Some(egui_winit);
 
                 egui_winit::update_viewport_info(&mut self.info, egui_ctx, &window, true);
                 self.window = Some(window);
@@ -879,6 +1076,23 @@ impl Viewport {
     }
 }
 
+/// The window of the [`ViewportBuilder::owner`] of the given viewport, if it has one.
+fn owner_window(viewports: &Viewports, viewport_id: ViewportId) -> Option<Arc<Window>> {
+    let owner = viewports.get(&viewport_id)?.builder.owner?;
+    viewports.get(&owner)?.window.clone()
+}
+
+/// Does the [`ViewportBuilder::owner`] of the given viewport exist, but has no window yet?
+///
+/// Then we wait with creating the window of the viewport, so that it gets its owner.
+fn is_waiting_for_owner(viewports: &Viewports, viewport_id: ViewportId) -> bool {
+    viewports
+        .get(&viewport_id)
+        .and_then(|viewport| viewport.builder.owner)
+        .and_then(|owner| viewports.get(&owner))
+        .is_some_and(|owner| owner.window.is_none())
+}
+
 fn create_window(
     egui_ctx: &egui::Context,
     event_loop: &ActiveEventLoop,
@@ -888,16 +1102,17 @@ fn create_window(
     profiling::function_scope!();
 
     let window_settings = epi_integration::load_window_settings(storage);
+    epi_integration::restore_zoom_factor(egui_ctx, window_settings.as_ref());
     let viewport_builder = epi_integration::viewport_builder(
         egui_ctx.zoom_factor(),
         event_loop,
         native_options,
-        window_settings,
+        window_settings.clone(),
     )
     .with_visible(false); // Start hidden until we render the first frame to fix white flash on startup (https://github.com/emilk/egui/pull/3631)
 
     let window = egui_winit::create_window(egui_ctx, event_loop, &viewport_builder)?;
-    epi_integration::apply_window_settings(&window, window_settings);
+    epi_integration::apply_window_settings(&window, window_settings.as_ref());
     Ok((window, viewport_builder))
 }
 
@@ -923,7 +1138,7 @@ fn render_immediate_viewport(
             ..
         } = &mut *shared.borrow_mut();
 
-        let viewport = initialize_or_update_viewport(
+        initialize_or_update_viewport(
             viewports,
             ids,
             ViewportClass::Immediate,
@@ -931,9 +1146,20 @@ fn render_immediate_viewport(
             None,
             painter,
         );
-        if viewport.window.is_none() {
+        let waiting_for_owner = is_waiting_for_owner(viewports, ids.this);
+        let owner_window = owner_window(viewports, ids.this);
+        let Some(viewport) = viewports.get_mut(&ids.this) else {
+            return;
+        };
+        if viewport.window.is_none() && !waiting_for_owner {
             event_loop_context::with_current_event_loop(|event_loop| {
-                viewport.initialize_window(event_loop, egui_ctx, viewport_from_window, painter);
+                viewport.initialize_window(
+                    event_loop,
+                    egui_ctx,
+                    viewport_from_window,
+                    painter,
+                    owner_window.as_deref(),
+                );
             });
         }
 
@@ -941,6 +1167,8 @@ fn render_immediate_viewport(
             return;
         };
         egui_winit::update_viewport_info(&mut viewport.info, egui_ctx, window, false);
+        egui_winit.update_monitors(&mut viewport.info, window);
+        viewport.close.begin_pass(&mut viewport.info);
 
         let mut input = egui_winit.take_egui_input(window);
         input.viewports = viewports
@@ -948,6 +1176,7 @@ fn render_immediate_viewport(
             .map(|(id, viewport)| (*id, viewport.info.clone()))
             .collect();
         input.time = Some(beginning.elapsed().as_secs_f64());
+        crate::epi::deliver_screenshots(&mut input.events);
         input
     };
 
@@ -980,7 +1209,14 @@ fn render_immediate_viewport(
     let Some(viewport) = viewports.get_mut(&ids.this) else {
         return;
     };
+    let close_requested = viewport.info.close_requested();
     viewport.info.events.clear(); // they should have been processed
+    viewport.info.user_commands.clear();
+
+    // The parent forces the repaint for the last frame, see `WgpuWinitRunning::run_ui_and_paint`.
+    viewport
+        .close
+        .end_pass(ids.this, close_requested, viewport_output.get(&ids.this));
     let (Some(egui_winit), Some(window)) = (&mut viewport.egui_winit, &viewport.window) else {
         return;
     };
@@ -996,6 +1232,7 @@ fn render_immediate_viewport(
     }
 
     let clipped_primitives = egui_ctx.tessellate(shapes, pixels_per_point);
+    painter.set_render_scale(ids.this, viewport.info.render_scale.unwrap_or(1.0));
     painter.paint_and_update_textures(
         ids.this,
         pixels_per_point,
@@ -1017,6 +1254,7 @@ fn render_immediate_viewport(
 }
 
 pub(crate) fn remove_viewports_not_in(
+    egui_ctx: &egui::Context,
     viewports: &mut ViewportIdMap<Viewport>,
     painter: &mut egui_wgpu::winit::Painter,
     viewport_from_window: &mut HashMap<WindowId, ViewportId>,
@@ -1024,10 +1262,27 @@ pub(crate) fn remove_viewports_not_in(
 ) {
     let active_viewports_ids: ViewportIdSet = viewport_output.keys().copied().collect();
 
+    let removed = epi_integration::removed_viewports(
+        viewports.values().map(|viewport| viewport.ids),
+        viewport_output,
+    );
+
     // Prune dead viewports:
     viewports.retain(|id, _| active_viewports_ids.contains(id));
     viewport_from_window.retain(|_, id| active_viewports_ids.contains(id));
     painter.gc_viewports(&active_viewports_ids);
+
+    for ids in removed {
+        child_destroyed(egui_ctx, viewports, ids);
+    }
+}
+
+/// Tell the parent of a viewport that it is gone.
+fn child_destroyed(egui_ctx: &egui::Context, viewports: &mut Viewports, ids: ViewportIdPair) {
+    let parent_info = viewports
+        .get_mut(&ids.parent)
+        .map(|parent| &mut parent.info);
+    epi_integration::child_destroyed(egui_ctx, parent_info, ids);
 }
 
 /// Add new viewports, and update existing ones:
@@ -1055,6 +1310,15 @@ fn handle_viewport_output(
         let viewport =
             initialize_or_update_viewport(viewports, ids, class, builder, viewport_ui_cb, painter);
 
+        if viewport.close.is_closed() && viewport.window.is_some() {
+            log::debug!("Viewport {viewport_id:?} is still shown after its last frame - closing its window anyway");
+            viewport.window = None;
+            viewport.egui_winit = None;
+            if let Err(err) = pollster::block_on(painter.set_window(viewport_id, None)) {
+                log::error!("when closing viewport_id={viewport_id:?}, set_window Error {err}");
+            }
+        }
+
         if let Some(window) = viewport.window.as_ref() {
             let old_inner_size = window.inner_size();
 
@@ -1083,7 +1347,13 @@ fn handle_viewport_output(
         }
     }
 
-    remove_viewports_not_in(viewports, painter, viewport_from_window, viewport_output);
+    remove_viewports_not_in(
+        egui_ctx,
+        viewports,
+        painter,
+        viewport_from_window,
+        viewport_output,
+    );
 }
 
 fn initialize_or_update_viewport<'a>(
@@ -1110,11 +1380,17 @@ fn initialize_or_update_viewport<'a>(
             entry.insert(Viewport {
                 ids,
                 class,
-                builder,
                 deferred_commands: vec![],
-                info: Default::default(),
+                info: ViewportInfo {
+                    render_scale: builder
+                        .render_scale
+                        .map(egui::emath::OrderedFloat::into_inner),
+                    ..Default::default()
+                },
+                builder,
                 actions_requested: HashSet::new(),
                 viewport_ui_cb,
+                close: Default::default(),
                 window: None,
                 egui_winit: None,
             })
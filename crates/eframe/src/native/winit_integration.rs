@@ -54,6 +54,16 @@ pub enum UserEvent {
     /// A request related to [`accesskit`](https://accesskit.dev/).
     #[cfg(feature = "accesskit")]
     AccessKitActionRequest(accesskit_winit::Event),
+
+    /// The OS is ending the session, or asked us to terminate.
+    ///
+    /// See [`crate::LifecycleEvent::SessionEnding`].
+    SessionEnding,
+
+    /// The user asked for something from the menus of the OS shell.
+    ///
+    /// See [`crate::App::on_shell_event`].
+    Shell,
 }
 
 #[cfg(feature = "accesskit")]
@@ -72,6 +82,9 @@ pub trait WinitApp {
 
     fn save_and_destroy(&mut self);
 
+    /// See [`crate::Frame::set_repaint_mode`].
+    fn repaint_mode(&self) -> crate::RepaintMode;
+
     fn run_ui_and_paint(
         &mut self,
         event_loop: &ActiveEventLoop,
@@ -82,6 +95,11 @@ pub trait WinitApp {
 
     fn resumed(&mut self, event_loop: &ActiveEventLoop) -> crate::Result<EventResult>;
 
+    fn memory_warning(&mut self) -> crate::Result<EventResult>;
+
+    /// Tell the app that the session is ending. The caller saves and destroys it right after.
+    fn session_ending(&mut self) -> crate::Result<EventResult>;
+
     fn device_event(
         &mut self,
         event_loop: &ActiveEventLoop,
@@ -122,6 +140,48 @@ pub enum EventResult {
     Exit,
 }
 
+/// The [`crate::LifecycleEvent`] for a [`winit::event::WindowEvent::Occluded`] of this viewport, if any.
+///
+/// Only the root viewport decides whether the app as a whole is in the background.
+pub fn lifecycle_event_from_occluded(
+    viewport_id: Option<ViewportId>,
+    occluded: bool,
+) -> Option<crate::LifecycleEvent> {
+    (viewport_id == Some(ViewportId::ROOT)).then_some(if occluded {
+        crate::LifecycleEvent::Background
+    } else {
+        crate::LifecycleEvent::Foreground
+    })
+}
+
+/// Set this environment variable to a path to record the [`egui_winit::FrameTelemetry`]
+/// of the root viewport, and write it to that path as JSON on exit.
+///
+/// Useful for bug reports about stutter.
+const FRAME_TELEMETRY_ENV_VAR: &str = "EFRAME_FRAME_TELEMETRY_TO";
+
+/// Start recording [`egui_winit::FrameTelemetry`] if asked to by [`FRAME_TELEMETRY_ENV_VAR`].
+pub fn init_frame_telemetry(egui_winit: &mut egui_winit::State, viewport_id: ViewportId) {
+    if viewport_id == ViewportId::ROOT && std::env::var_os(FRAME_TELEMETRY_ENV_VAR).is_some() {
+        log::info!("Recording frame telemetry for {FRAME_TELEMETRY_ENV_VAR}");
+        egui_winit.set_frame_telemetry(Some(Default::default()));
+    }
+}
+
+/// Write the [`egui_winit::FrameTelemetry`] to where [`FRAME_TELEMETRY_ENV_VAR`] says, if we recorded any.
+pub fn save_frame_telemetry(egui_winit: &egui_winit::State) {
+    let (Some(telemetry), Some(path)) = (
+        egui_winit.frame_telemetry(),
+        std::env::var_os(FRAME_TELEMETRY_ENV_VAR),
+    ) else {
+        return;
+    };
+    match std::fs::write(&path, telemetry.to_json()) {
+        Ok(()) => log::info!("Wrote frame telemetry to {path:?}"),
+        Err(err) => log::warn!("Failed to write frame telemetry to {path:?}: {err}"),
+    }
+}
+
 #[cfg(feature = "accesskit")]
 pub(crate) fn on_accesskit_window_event(
     egui_winit: &mut egui_winit::State,
@@ -154,3 +214,28 @@ pub(crate) fn on_accesskit_window_event(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LifecycleEvent;
+
+    #[test]
+    fn occluded_root_goes_to_the_background() {
+        let root = Some(ViewportId::ROOT);
+        assert_eq!(
+            lifecycle_event_from_occluded(root, true),
+            Some(LifecycleEvent::Background)
+        );
+        assert_eq!(
+            lifecycle_event_from_occluded(root, false),
+            Some(LifecycleEvent::Foreground)
+        );
+
+        // Covering a child window, or a window we don't know, doesn't background the app:
+        let child = Some(ViewportId::from_hash_of("child"));
+        assert_eq!(lifecycle_event_from_occluded(child, true), None);
+        assert_eq!(lifecycle_event_from_occluded(child, false), None);
+        assert_eq!(lifecycle_event_from_occluded(None, true), None);
+    }
+}
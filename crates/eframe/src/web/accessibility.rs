@@ -0,0 +1,400 @@
+//! Mirror the egui [`accesskit`] tree into the DOM, so that real screen readers
+//! can navigate egui web apps, instead of relying on speech synthesis.
+//!
+//! The nodes are added as fallback content of the `<canvas>`:
+//! they are not rendered, but they are part of the browser's accessibility tree.
+//!
+//! Only installed if [`crate::WebOptions::accesskit`] is set.
+
+use std::collections::{HashMap, HashSet};
+
+use egui::accesskit::{self, Action, ActionRequest, NodeId, Role, Toggled, TreeUpdate};
+use wasm_bindgen::prelude::*;
+
+use super::{AppRunner, WebRunner};
+
+/// Which attribute stores the [`NodeId`] of a mirrored element.
+const NODE_ID_ATTRIBUTE: &str = "data-accesskit-id";
+
+pub(crate) struct AccessibilityAdapter {
+    /// Container for the mirrored tree, inside the canvas.
+    container: web_sys::HtmlElement,
+
+    /// Used to give each mirrored element a unique `id`, so several apps can share a page.
+    id_prefix: String,
+
+    root: Option<NodeId>,
+    elements: HashMap<NodeId, web_sys::HtmlElement>,
+}
+
+impl AccessibilityAdapter {
+    /// Attach the adapter to the canvas of the runner, and enable AccessKit in egui.
+    pub(crate) fn install(runner_ref: &WebRunner) -> Result<(), JsValue> {
+        let Some(canvas) = runner_ref.try_lock().map(|runner| runner.canvas().clone()) else {
+            return Ok(());
+        };
+
+        let document = web_sys::window().unwrap().document().unwrap();
+        let container = document
+            .create_element("div")?
+            .dyn_into::<web_sys::HtmlElement>()?;
+        canvas.append_child(&container)?;
+
+        let id_prefix = if canvas.id().is_empty() {
+            "egui-accesskit".to_owned()
+        } else {
+            format!("{}-accesskit", canvas.id())
+        };
+
+        // A screen reader "clicked" one of our elements:
+        runner_ref.add_event_listener(
+            &container,
+            "click",
+            |event: web_sys::MouseEvent, runner: &mut AppRunner| {
+                if let Some(target) = target_node_id(&event) {
+                    push_action_request(runner, Action::Click, target);
+                    event.prevent_default();
+                    event.stop_propagation();
+                }
+            },
+        )?;
+
+        // A screen reader moved the focus to one of our elements.
+        // Keyboard events bubble up to the canvas, so we don't need to forward those.
+        runner_ref.add_event_listener(
+            &container,
+            "focusin",
+            |event: web_sys::FocusEvent, runner: &mut AppRunner| {
+                if let Some(target) = target_node_id(&event) {
+                    push_action_request(runner, Action::Focus, target);
+                }
+                runner.update_focus();
+            },
+        )?;
+
+        if let Some(mut runner) = runner_ref.try_lock() {
+            runner.egui_ctx().enable_accesskit();
+            runner.accessibility = Some(Self {
+                container,
+                id_prefix,
+                root: None,
+                elements: Default::default(),
+            });
+            runner.needs_repaint.repaint_asap();
+        }
+
+        Ok(())
+    }
+
+    /// Does one of the mirrored elements have keyboard focus?
+    pub(crate) fn has_focus(&self) -> bool {
+        super::focused_element().is_some_and(|focused| self.container.contains(Some(&focused)))
+    }
+
+    /// Apply an update from [`egui::PlatformOutput::accesskit_update`].
+    ///
+    /// `move_focus` is whether we may move the keyboard focus of the page to the focused node.
+    pub(crate) fn update(&mut self, update: TreeUpdate, move_focus: bool) -> Result<(), JsValue> {
+        let TreeUpdate { nodes, tree, focus } = update;
+
+        if let Some(tree) = tree {
+            if self.root != Some(tree.root) {
+                self.root = Some(tree.root);
+                self.container.set_text_content(None);
+            }
+        }
+        let Some(root) = self.root else {
+            return Ok(());
+        };
+
+        for (id, node) in &nodes {
+            let element = self.element(*id)?;
+            apply_node(&element, node)?;
+        }
+
+        // Reorder the children of everything that changed.
+        // Appending an element that is already in the DOM moves it.
+        for (id, node) in &nodes {
+            let element = &self.elements[id];
+            for child in node.children() {
+                if let Some(child) = self.elements.get(child) {
+                    element.append_child(child)?;
+                }
+            }
+        }
+        if let Some(root) = self.elements.get(&root) {
+            if root.parent_node().as_ref() != Some(self.container.as_ref()) {
+                self.container.append_child(root)?;
+            }
+        }
+
+        if !nodes.is_empty() {
+            self.remove_unreachable(root, &nodes);
+        }
+
+        if move_focus {
+            self.move_focus(root, focus);
+        }
+
+        Ok(())
+    }
+
+    /// Get the element for a node, creating it if needed.
+    fn element(&mut self, id: NodeId) -> Result<web_sys::HtmlElement, JsValue> {
+        if let Some(element) = self.elements.get(&id) {
+            return Ok(element.clone());
+        }
+
+        let document = web_sys::window().unwrap().document().unwrap();
+        let element = document
+            .create_element("div")?
+            .dyn_into::<web_sys::HtmlElement>()?;
+        element.set_id(&format!("{}-{}", self.id_prefix, id.0));
+        element.set_attribute(NODE_ID_ATTRIBUTE, &id.0.to_string())?;
+        self.elements.insert(id, element.clone());
+        Ok(element)
+    }
+
+    /// Forget about the nodes that are no longer part of the tree.
+    fn remove_unreachable(&mut self, root: NodeId, nodes: &[(NodeId, accesskit::Node)]) {
+        let children: HashMap<NodeId, &[NodeId]> = nodes
+            .iter()
+            .map(|(id, node)| (*id, node.children()))
+            .collect();
+
+        let mut reachable = HashSet::new();
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            if reachable.insert(id) {
+                if let Some(children) = children.get(&id) {
+                    stack.extend_from_slice(children);
+                } else if let Some(element) = self.elements.get(&id) {
+                    // Unchanged node: its children are still in the DOM.
+                    stack.extend(child_node_ids(element));
+                }
+            }
+        }
+
+        self.elements.retain(|id, element| {
+            let keep = reachable.contains(id);
+            if !keep {
+                element.remove();
+            }
+            keep
+        });
+    }
+
+    fn move_focus(&self, root: NodeId, focus: NodeId) {
+        let element = if focus == root {
+            None
+        } else {
+            self.elements.get(&focus)
+        };
+
+        if let Some(element) = element {
+            if !super::has_focus(element) {
+                element.focus().ok();
+            }
+        } else if self.has_focus() {
+            // Nothing in egui has focus anymore: give it back to the canvas.
+            if let Some(canvas) = self.container.parent_element() {
+                if let Some(canvas) = canvas.dyn_ref::<web_sys::HtmlElement>() {
+                    canvas.focus().ok();
+                }
+            }
+        }
+    }
+}
+
+impl Drop for AccessibilityAdapter {
+    fn drop(&mut self) {
+        self.container.remove();
+    }
+}
+
+fn push_action_request(runner: &mut AppRunner, action: Action, target: NodeId) {
+    runner
+        .input
+        .raw
+        .events
+        .push(egui::Event::AccessKitActionRequest(ActionRequest {
+            action,
+            target,
+            data: None,
+        }));
+    runner.needs_repaint.repaint_asap();
+}
+
+/// The node that was the target of the event, if any.
+fn target_node_id(event: &web_sys::Event) -> Option<NodeId> {
+    let target = event.target()?.dyn_into::<web_sys::Element>().ok()?;
+    let element = target.closest(&format!("[{NODE_ID_ATTRIBUTE}]")).ok()??;
+    node_id(&element)
+}
+
+fn node_id(element: &web_sys::Element) -> Option<NodeId> {
+    element
+        .get_attribute(NODE_ID_ATTRIBUTE)?
+        .parse()
+        .ok()
+        .map(NodeId)
+}
+
+fn child_node_ids(element: &web_sys::HtmlElement) -> Vec<NodeId> {
+    let children = element.children();
+    (0..children.length())
+        .filter_map(|i| children.item(i))
+        .filter_map(|child| node_id(&child))
+        .collect()
+}
+
+/// Copy the properties of an AccessKit node to the attributes of a DOM element.
+fn apply_node(element: &web_sys::HtmlElement, node: &accesskit::Node) -> Result<(), JsValue> {
+    fn set_or_remove(
+        element: &web_sys::HtmlElement,
+        name: &str,
+        value: Option<String>,
+    ) -> Result<(), JsValue> {
+        match value {
+            Some(value) => element.set_attribute(name, &value),
+            None => element.remove_attribute(name),
+        }
+    }
+
+    let role = node.role();
+    set_or_remove(element, "role", aria_role(role).map(ToOwned::to_owned))?;
+    set_or_remove(element, "aria-label", node.label().map(ToOwned::to_owned))?;
+    set_or_remove(
+        element,
+        "aria-description",
+        node.description().map(ToOwned::to_owned),
+    )?;
+    set_or_remove(
+        element,
+        "aria-multiline",
+        (role == Role::MultilineTextInput).then(|| "true".to_owned()),
+    )?;
+    set_or_remove(
+        element,
+        "aria-hidden",
+        node.is_hidden().then(|| "true".to_owned()),
+    )?;
+    set_or_remove(
+        element,
+        "aria-disabled",
+        node.is_disabled().then(|| "true".to_owned()),
+    )?;
+    set_or_remove(
+        element,
+        "aria-readonly",
+        node.is_read_only().then(|| "true".to_owned()),
+    )?;
+
+    let toggled = node.toggled().map(|toggled| match toggled {
+        Toggled::False => "false",
+        Toggled::True => "true",
+        Toggled::Mixed => "mixed",
+    });
+    let (checked, pressed) = match role {
+        Role::Button => (None, toggled),
+        _ => (toggled, None),
+    };
+    set_or_remove(element, "aria-checked", checked.map(ToOwned::to_owned))?;
+    set_or_remove(element, "aria-pressed", pressed.map(ToOwned::to_owned))?;
+    set_or_remove(
+        element,
+        "aria-expanded",
+        node.is_expanded().map(|expanded| expanded.to_string()),
+    )?;
+    set_or_remove(
+        element,
+        "aria-selected",
+        node.is_selected().map(|selected| selected.to_string()),
+    )?;
+
+    set_or_remove(
+        element,
+        "aria-valuenow",
+        node.numeric_value().map(|value| value.to_string()),
+    )?;
+    set_or_remove(
+        element,
+        "aria-valuemin",
+        node.min_numeric_value().map(|value| value.to_string()),
+    )?;
+    set_or_remove(
+        element,
+        "aria-valuemax",
+        node.max_numeric_value().map(|value| value.to_string()),
+    )?;
+
+    if node.supports_action(Action::Focus) {
+        element.set_tab_index(-1);
+    } else {
+        element.remove_attribute("tabindex")?;
+    }
+
+    // Leaf nodes (labels, text edits, …) expose their value as text content.
+    // Nodes with children must not, or we would remove the children.
+    if node.children().is_empty() {
+        let text = node.value().unwrap_or_default();
+        if element.text_content().as_deref() != Some(text) {
+            element.set_text_content(Some(text));
+        }
+    }
+
+    Ok(())
+}
+
+/// The ARIA role corresponding to an AccessKit role.
+///
+/// `None` means the element is a plain container, or just text.
+fn aria_role(role: Role) -> Option<&'static str> {
+    Some(match role {
+        Role::Button | Role::DefaultButton => "button",
+        Role::CheckBox => "checkbox",
+        Role::RadioButton => "radio",
+        Role::RadioGroup => "radiogroup",
+        Role::Switch => "switch",
+        Role::Link => "link",
+        Role::TextInput
+        | Role::MultilineTextInput
+        | Role::EmailInput
+        | Role::PasswordInput
+        | Role::PhoneNumberInput
+        | Role::UrlInput => "textbox",
+        Role::SearchInput => "searchbox",
+        Role::Slider => "slider",
+        Role::SpinButton => "spinbutton",
+        Role::ProgressIndicator => "progressbar",
+        Role::Meter => "meter",
+        Role::ComboBox | Role::EditableComboBox => "combobox",
+        Role::Image => "img",
+        Role::Heading => "heading",
+        Role::List => "list",
+        Role::ListItem => "listitem",
+        Role::ListBox => "listbox",
+        Role::ListBoxOption => "option",
+        Role::Menu => "menu",
+        Role::MenuBar => "menubar",
+        Role::MenuItem => "menuitem",
+        Role::Tab => "tab",
+        Role::TabList => "tablist",
+        Role::TabPanel => "tabpanel",
+        Role::Table => "table",
+        Role::Row => "row",
+        Role::Cell => "cell",
+        Role::ColumnHeader => "columnheader",
+        Role::RowHeader => "rowheader",
+        Role::Grid => "grid",
+        Role::Tree => "tree",
+        Role::TreeItem => "treeitem",
+        Role::Tooltip => "tooltip",
+        Role::Dialog => "dialog",
+        Role::AlertDialog => "alertdialog",
+        Role::Alert => "alert",
+        Role::Group | Role::Window => "group",
+        Role::Paragraph => "paragraph",
+        _ => return None,
+    })
+}
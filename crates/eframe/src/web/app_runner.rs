@@ -1,11 +1,20 @@
 use egui::{TexturesDelta, UserData, ViewportCommand};
+use wasm_bindgen::JsValue;
 
 use crate::{epi, App};
 
-use super::{now_sec, text_agent::TextAgent, web_painter::WebPainter, NeedRepaint};
+use super::{
+    frame_timings::FrameTimings,
+    leave_guard::LeaveGuard,
+    now_sec,
+    repaint_waker::{RepaintWaker, WakerId},
+    text_agent::TextAgent,
+    wake_lock::WakeLock,
+    web_painter::WebPainter,
+    NeedRepaint,
+};
 
 pub struct AppRunner {
-    #[allow(dead_code)]
     pub(crate) web_options: crate::WebOptions,
     pub(crate) frame: epi::Frame,
     egui_ctx: egui::Context,
@@ -13,9 +22,41 @@ pub struct AppRunner {
     pub(crate) input: super::WebInput,
     app: Box<dyn epi::App>,
     pub(crate) needs_repaint: std::sync::Arc<NeedRepaint>,
+
+    /// Used to paint right away when a repaint is requested while idle.
+    pub(crate) waker_id: WakerId,
+    pub(crate) repaint_waker: Option<RepaintWaker>,
+
+    /// Have we painted since the start of the last animation frame?
+    pub(crate) painted_this_frame: bool,
+
     last_save_time: f64,
     pub(crate) text_agent: TextAgent,
 
+    /// Keeps the screen awake, see [`epi::Frame::set_inhibit_sleep`].
+    wake_lock: WakeLock,
+
+    /// Asks before leaving the page, see [`epi::Frame::set_unsaved_changes`].
+    leave_guard: LeaveGuard,
+
+    /// Timings of the frame in progress, and of the last painted frame.
+    pending_frame_timings: FrameTimings,
+    last_frame_timings: Option<FrameTimings>,
+
+    /// Called with [`FrameTimings::to_js_object`] after each painted frame.
+    pub(crate) frame_timings_hook: Option<js_sys::Function>,
+
+    /// When we last handled an event, and last painted, for [`epi::RepaintMode`].
+    pub(crate) last_input_time: f64,
+    pub(crate) last_paint_time: f64,
+
+    /// Mirrors the text fields that can be autofilled into the DOM, for password managers.
+    pub(crate) autofill: Option<super::autofill::Autofill>,
+
+    /// Mirrors the accessibility tree into the DOM, for screen readers.
+    #[cfg(feature = "accesskit")]
+    pub(crate) accessibility: Option<super::accessibility::AccessibilityAdapter>,
+
     // If not empty, the painter should capture n frames from now.
     // zero means capture the exact next frame.
     screenshot_commands_with_frame_delay: Vec<(UserData, usize)>,
@@ -47,8 +88,11 @@ impl AppRunner {
             web_info: epi::WebInfo {
                 user_agent: super::user_agent().unwrap_or_default(),
                 location: super::web_location(),
+                capabilities: super::capabilities::detect(),
+                navigation_events: Default::default(),
             },
             cpu_usage: None,
+            prefers_right_to_left: crate::locale::prefers_right_to_left(),
         };
         let storage = LocalStorage::default();
 
@@ -64,6 +108,9 @@ impl AppRunner {
             o.zoom_with_keyboard = false;
             o.zoom_factor = 1.0;
         });
+        web_options
+            .layout_direction
+            .apply(&egui_ctx, info.prefers_right_to_left);
 
         let cc = epi::CreationContext {
             egui_ctx: egui_ctx.clone(),
@@ -94,16 +141,29 @@ impl AppRunner {
             wgpu_render_state: painter.render_state(),
             #[cfg(all(feature = "wgpu", feature = "glow"))]
             wgpu_render_state: None,
+
+            inhibit_sleep: epi::InhibitSleep::Never,
+            repaint_mode: epi::RepaintMode::Reactive,
+            unsaved_changes: false,
+            screenshot_requests: Vec::new(),
+            recordings: Default::default(),
+            fullscreen_request: None,
         };
 
         let needs_repaint: std::sync::Arc<NeedRepaint> = Default::default();
+        let waker_id = WakerId::new();
         {
             let needs_repaint = needs_repaint.clone();
             egui_ctx.set_request_repaint_callback(move |info| {
                 needs_repaint.repaint_after(info.delay.as_secs_f64());
+                if info.delay.is_zero() {
+                    waker_id.wake();
+                }
             });
         }
 
+        let leave_guard = LeaveGuard::new(needs_repaint.clone(), waker_id);
+
         let mut runner = Self {
             web_options,
             frame,
@@ -112,8 +172,21 @@ impl AppRunner {
             input: Default::default(),
             app,
             needs_repaint,
+            waker_id,
+            repaint_waker: None,
+            painted_this_frame: false,
             last_save_time: now_sec(),
             text_agent,
+            wake_lock: WakeLock::default(),
+            leave_guard,
+            pending_frame_timings: FrameTimings::default(),
+            last_frame_timings: None,
+            frame_timings_hook: None,
+            last_input_time: now_sec(),
+            last_paint_time: f64::NEG_INFINITY,
+            autofill: None,
+            #[cfg(feature = "accesskit")]
+            accessibility: None,
             screenshot_commands_with_frame_delay: vec![],
             textures_delta: Default::default(),
             clipped_primitives: None,
@@ -128,6 +201,7 @@ impl AppRunner {
             .or_default()
             .native_pixels_per_point = Some(super::native_pixels_per_point());
         runner.input.raw.system_theme = super::system_theme();
+        runner.input.raw.system_high_contrast = super::system_high_contrast();
 
         Ok(runner)
     }
@@ -164,12 +238,65 @@ impl AppRunner {
         self.last_save_time = now_sec();
     }
 
+    /// Tell the app about a change in its lifecycle.
+    pub fn on_lifecycle_event(&mut self, event: epi::LifecycleEvent) {
+        log::debug!("Lifecycle event: {event:?}");
+        self.app.on_lifecycle_event(&self.egui_ctx, event);
+        if matches!(
+            event,
+            epi::LifecycleEvent::Suspended | epi::LifecycleEvent::SessionEnding
+        ) {
+            // We may never come back - good idea to save
+            self.save();
+        }
+        self.needs_repaint.repaint_asap();
+    }
+
+    /// The page was restored from the back/forward cache.
+    ///
+    /// Forget the input state from before the page was frozen, and don't count the time away
+    /// as idle time (see [`crate::RepaintMode`]).
+    pub fn on_restored_from_bfcache(&mut self) {
+        // We never got the `keyup` of the keys that were down when the user left,
+        // e.g. `Alt` + `ArrowLeft` for going back:
+        let keys_down = self.egui_ctx.input(|i| i.keys_down.clone());
+        for key in keys_down {
+            self.input.raw.events.push(egui::Event::Key {
+                key,
+                physical_key: None,
+                logical_key: None,
+                pressed: false,
+                repeat: false,
+                modifiers: egui::Modifiers::NONE,
+            });
+        }
+        self.input.set_focus(false); // also resets modifiers and touches
+        self.update_focus();
+
+        let now = now_sec();
+        self.last_input_time = now;
+        self.last_paint_time = f64::NEG_INFINITY;
+        self.last_save_time = now; // We saved on `pagehide`
+
+        self.on_lifecycle_event(epi::LifecycleEvent::Resumed);
+    }
+
     pub fn canvas(&self) -> &web_sys::HtmlCanvasElement {
         self.painter.canvas()
     }
 
+    /// Save the app state, let the app clean up with [`epi::App::on_exit`], and free the GPU resources.
+    ///
+    /// The text agent, and the other elements we added to the document, are removed when `self` is dropped.
     pub fn destroy(mut self) {
         log::debug!("Destroying AppRunner");
+        self.save();
+
+        #[cfg(feature = "glow")]
+        self.app.on_exit(Some(self.painter.gl().as_ref()));
+        #[cfg(not(feature = "glow"))]
+        self.app.on_exit();
+
         self.painter.destroy();
     }
 
@@ -179,7 +306,8 @@ impl AppRunner {
 
     /// Does the eframe app have focus?
     ///
-    /// Technically: does either the canvas or the [`TextAgent`] have focus?
+    /// Technically: does either the canvas, the [`TextAgent`],
+    /// or one of the elements mirroring the accessibility tree have focus?
     pub fn has_focus(&self) -> bool {
         let window = web_sys::window().unwrap();
         let document = window.document().unwrap();
@@ -187,7 +315,24 @@ impl AppRunner {
             return false;
         }
 
-        super::has_focus(self.canvas()) || self.text_agent.has_focus()
+        super::has_focus(self.canvas())
+            || self.text_agent.has_focus()
+            || self.accessibility_has_focus()
+    }
+
+    /// Does one of the elements mirroring the accessibility tree have focus?
+    #[cfg_attr(not(feature = "accesskit"), allow(clippy::unused_self))]
+    fn accessibility_has_focus(&self) -> bool {
+        #[cfg(feature = "accesskit")]
+        {
+            self.accessibility
+                .as_ref()
+                .is_some_and(|accessibility| accessibility.has_focus())
+        }
+        #[cfg(not(feature = "accesskit"))]
+        {
+            false
+        }
     }
 
     pub fn update_focus(&mut self) {
@@ -196,6 +341,11 @@ impl AppRunner {
             log::trace!("{} Focus changed to {has_focus}", self.canvas().id());
             self.input.set_focus(has_focus);
 
+            if let Some(focus_outline) = &self.web_options.focus_outline {
+                let outline = if has_focus { focus_outline } else { "none" };
+                self.canvas().style().set_property("outline", outline).ok();
+            }
+
             if !has_focus {
                 // We lost focus - good idea to save
                 self.save();
@@ -215,12 +365,38 @@ impl AppRunner {
 
         let canvas_size = super::canvas_size_in_points(self.canvas(), self.egui_ctx());
         let mut raw_input = self.input.new_frame(canvas_size);
+        raw_input
+            .viewports
+            .entry(egui::ViewportId::ROOT)
+            .or_default()
+            .fullscreen = Some(super::is_fullscreen(self.canvas()));
+
+        epi::deliver_screenshots(&mut raw_input.events);
+        epi::deliver_system_theme_changes(self.app.as_mut(), &self.egui_ctx, &raw_input.events);
 
         self.app.raw_input_hook(&self.egui_ctx, &mut raw_input);
 
+        if self.leave_guard.take_leave_attempted() {
+            self.frame
+                .info
+                .web_info
+                .navigation_events
+                .push(crate::NavigationEvent::LeaveAttempted);
+        }
+
+        let update_start = now_sec();
         let full_output = self.egui_ctx.run(raw_input, |egui_ctx| {
             self.app.update(egui_ctx, &mut self.frame);
+            self.frame.send_viewport_commands(egui_ctx);
         });
+        self.pending_frame_timings.update += (now_sec() - update_start) as f32;
+        self.frame.info.web_info.navigation_events.clear();
+        self.wake_lock.set_wanted(
+            self.frame
+                .inhibit_sleep
+                .should_inhibit(self.input.raw.focused),
+        );
+        self.leave_guard.set_wanted(self.frame.unsaved_changes);
         let egui::FullOutput {
             platform_output,
             textures_delta,
@@ -239,6 +415,22 @@ impl AppRunner {
                         self.screenshot_commands_with_frame_delay
                             .push((user_data, 1));
                     }
+                    ViewportCommand::Focus => {
+                        self.canvas().focus().ok();
+                    }
+                    ViewportCommand::Fullscreen(fullscreen) => {
+                        super::set_fullscreen(self.canvas(), fullscreen);
+                    }
+                    ViewportCommand::User(user_data) => {
+                        self.input
+                            .raw
+                            .viewports
+                            .entry(egui::ViewportId::ROOT)
+                            .or_default()
+                            .user_commands
+                            .push(user_data);
+                        self.needs_repaint.repaint_asap();
+                    }
                     _ => {
                         // TODO(emilk): handle some of the commands
                         log::warn!(
@@ -251,7 +443,10 @@ impl AppRunner {
 
         self.handle_platform_output(platform_output);
         self.textures_delta.append(textures_delta);
+
+        let tessellation_start = now_sec();
         self.clipped_primitives = Some(self.egui_ctx.tessellate(shapes, pixels_per_point));
+        self.pending_frame_timings.tessellation += (now_sec() - tessellation_start) as f32;
     }
 
     /// Paint the results of the last call to [`Self::logic`].
@@ -260,6 +455,8 @@ impl AppRunner {
         let clipped_primitives = std::mem::take(&mut self.clipped_primitives);
 
         if let Some(clipped_primitives) = clipped_primitives {
+            let paint_start = now_sec();
+
             let mut screenshot_commands = vec![];
             self.screenshot_commands_with_frame_delay
                 .retain_mut(|(user_data, frame_delay)| {
@@ -284,14 +481,62 @@ impl AppRunner {
             ) {
                 log::error!("Failed to paint: {}", super::string_from_js_value(&err));
             }
+
+            if self.painter.has_pending_work() {
+                self.needs_repaint.repaint_asap();
+            }
+
+            self.pending_frame_timings.paint += (now_sec() - paint_start) as f32;
+            self.finish_frame_timings();
+        }
+    }
+
+    fn finish_frame_timings(&mut self) {
+        let timings = std::mem::take(&mut self.pending_frame_timings);
+        self.last_frame_timings = Some(timings);
+
+        if let Some(hook) = &self.frame_timings_hook {
+            if let Err(err) = hook.call1(&JsValue::NULL, &timings.to_js_object()) {
+                log::warn!(
+                    "Frame timings hook failed: {}",
+                    super::string_from_js_value(&err)
+                );
+            }
         }
     }
 
+    /// Timings of the last painted frame.
+    pub fn frame_timings(&self) -> Option<FrameTimings> {
+        self.last_frame_timings
+    }
+
+    /// Run an event handler, and count its time towards [`FrameTimings::events`].
+    ///
+    /// Time spent running the logic from within the handler is not counted twice.
+    ///
+    /// `is_input` is whether the event comes from the user, and so counts as input
+    /// for [`epi::RepaintMode::OnInput`], see [`super::events::is_user_input`].
+    pub(crate) fn time_event_handler(&mut self, is_input: bool, handler: impl FnOnce(&mut Self)) {
+        let start = now_sec();
+        if is_input {
+            self.last_input_time = start;
+        }
+        let logic_before =
+            self.pending_frame_timings.update + self.pending_frame_timings.tessellation;
+        handler(self);
+        let logic_time = self.pending_frame_timings.update
+            + self.pending_frame_timings.tessellation
+            - logic_before;
+        let event_time = (now_sec() - start) as f32 - logic_time;
+        self.pending_frame_timings.events += event_time.max(0.0);
+    }
+
     pub fn report_frame_time(&mut self, cpu_usage_seconds: f32) {
         self.frame.info.cpu_usage = Some(cpu_usage_seconds);
     }
 
-    fn handle_platform_output(&self, platform_output: egui::PlatformOutput) {
+    #[cfg_attr(not(feature = "accesskit"), allow(clippy::needless_pass_by_ref_mut))]
+    fn handle_platform_output(&mut self, platform_output: egui::PlatformOutput) {
         #![allow(deprecated)]
 
         #[cfg(feature = "web_screen_reader")]
@@ -307,12 +552,14 @@ impl AppRunner {
             events: _,                    // already handled
             mutable_text_under_cursor: _, // TODO(#4569): https://github.com/emilk/egui/issues/4569
             ime,
+            autofill_fields,
             #[cfg(feature = "accesskit")]
-                accesskit_update: _, // not currently implemented
+            accesskit_update,
             num_completed_passes: _,    // handled by `Context::run`
             request_discard_reasons: _, // handled by `Context::run`
         } = platform_output;
 
+        let mut submit_autofill = false;
         for command in commands {
             match command {
                 egui::OutputCommand::CopyText(text) => {
@@ -321,9 +568,15 @@ impl AppRunner {
                 egui::OutputCommand::CopyImage(image) => {
                     super::set_clipboard_image(&image);
                 }
+                egui::OutputCommand::SetPrimarySelection(_) => {
+                    // There is no primary selection on the web.
+                }
                 egui::OutputCommand::OpenUrl(open_url) => {
                     super::open_url(&open_url.url, open_url.new_tab);
                 }
+                egui::OutputCommand::SubmitAutofill => {
+                    submit_autofill = true;
+                }
             }
         }
 
@@ -337,15 +590,41 @@ impl AppRunner {
             super::set_clipboard_text(&copied_text);
         }
 
+        if let Some(autofill) = &mut self.autofill {
+            if let Err(err) = autofill.update(&autofill_fields, submit_autofill) {
+                log::error!(
+                    "failed to update autofill fields: {}",
+                    super::string_from_js_value(&err)
+                );
+            }
+        }
+
+        #[cfg(feature = "accesskit")]
+        if let Some(accesskit_update) = accesskit_update {
+            // Only move the focus to the focused widget if we wouldn't steal it from the text agent:
+            let move_focus = ime.is_none() && self.has_focus();
+            if let Some(accessibility) = &mut self.accessibility {
+                if let Err(err) = accessibility.update(accesskit_update, move_focus) {
+                    log::error!(
+                        "failed to update accessibility tree: {}",
+                        super::string_from_js_value(&err)
+                    );
+                }
+            }
+        }
+
         if self.has_focus() {
             // The eframe app has focus.
             if ime.is_some() {
                 // We are editing text: give the focus to the text agent.
                 self.text_agent.focus();
             } else {
-                // We are not editing text - give the focus to the canvas.
+                // We are not editing text - give the focus to the canvas,
+                // unless a screen reader is focusing one of the accessibility elements.
                 self.text_agent.blur();
-                self.canvas().focus().ok();
+                if !self.accessibility_has_focus() {
+                    self.canvas().focus().ok();
+                }
             }
         }
 
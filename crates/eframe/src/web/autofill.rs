@@ -0,0 +1,180 @@
+//! Let password managers fill in egui text fields, see [`egui::TextEdit::autocomplete`].
+//!
+//! Browsers only autofill DOM `<input>` elements, so we mirror each field as a
+//! hidden `<input>` with the same `autocomplete` attribute, inside a `<form>`.
+//! When the browser fills one in, we send the text back to egui as [`egui::Event::Autofill`].
+//!
+//! The text of password fields is only put into the DOM on [`egui::OutputCommand::SubmitAutofill`],
+//! and taken out again the frame after.
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use egui::output::AutofillField;
+use wasm_bindgen::prelude::*;
+
+use super::{AppRunner, WebRunner};
+
+/// Which attribute stores the [`egui::Id`] of a mirrored field.
+const FIELD_ID_ATTRIBUTE: &str = "data-egui-autofill-id";
+
+pub(crate) struct Autofill {
+    /// The `<form>` containing the mirrored fields.
+    form: web_sys::HtmlFormElement,
+
+    inputs: HashMap<egui::Id, web_sys::HtmlInputElement>,
+
+    /// Keeps submitting the form from navigating away from the page.
+    _on_submit: Closure<dyn FnMut(web_sys::Event)>,
+
+    /// Did the last [`Self::update`] put the text of the password fields into the DOM?
+    submitted: bool,
+}
+
+impl Autofill {
+    /// Add the (empty) form to the document, and give it to the runner.
+    pub(crate) fn install(runner_ref: &WebRunner) -> Result<(), JsValue> {
+        let autofill = Self::new()?;
+
+        // Autofill fires `input` events, which bubble up to the form:
+        runner_ref.add_event_listener(
+            &autofill.form,
+            "input",
+            |event: web_sys::InputEvent, runner: &mut AppRunner| {
+                let Some(input) = event
+                    .target()
+                    .and_then(|target| target.dyn_into::<web_sys::HtmlInputElement>().ok())
+                else {
+                    return;
+                };
+                let id = runner
+                    .autofill
+                    .as_ref()
+                    .and_then(|autofill| autofill.id_of(&input));
+                if let Some(id) = id {
+                    runner.input.raw.events.push(egui::Event::Autofill {
+                        id,
+                        text: input.value(),
+                    });
+                    runner.needs_repaint.repaint_asap();
+                }
+            },
+        )?;
+
+        let Some(mut runner) = runner_ref.try_lock() else {
+            // Dropping `autofill` removes the form again.
+            return Err(JsValue::from_str(
+                "Failed to install autofill: the app runner is unavailable",
+            ));
+        };
+        runner.autofill = Some(autofill);
+
+        Ok(())
+    }
+
+    fn new() -> Result<Self, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let form = document
+            .create_element("form")?
+            .dyn_into::<web_sys::HtmlFormElement>()?;
+        form.set_attribute("autocomplete", "on")?;
+        form.set_attribute("aria-hidden", "true")?;
+
+        // Password managers ignore fields that are `display: none`,
+        // so we make the form transparent instead:
+        let style = form.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("top", "0")?;
+        style.set_property("left", "0")?;
+        style.set_property("width", "1px")?;
+        style.set_property("height", "1px")?;
+        style.set_property("overflow", "hidden")?;
+        style.set_property("opacity", "0")?;
+        style.set_property("pointer-events", "none")?;
+
+        // An event handler property is never passive, and goes away with the form:
+        let on_submit = Closure::<dyn FnMut(_)>::new(|event: web_sys::Event| {
+            event.prevent_default();
+        });
+        form.set_onsubmit(Some(on_submit.as_ref().unchecked_ref()));
+
+        document.body().unwrap().append_child(&form)?;
+
+        Ok(Self {
+            form,
+            inputs: Default::default(),
+            _on_submit: on_submit,
+            submitted: false,
+        })
+    }
+
+    fn id_of(&self, input: &web_sys::HtmlInputElement) -> Option<egui::Id> {
+        let value = input.get_attribute(FIELD_ID_ATTRIBUTE)?;
+        self.inputs
+            .keys()
+            .copied()
+            .find(|id| id.value().to_string() == value)
+    }
+
+    /// Mirror the fields from [`egui::PlatformOutput::autofill_fields`].
+    ///
+    /// `submit` is set on [`egui::OutputCommand::SubmitAutofill`].
+    pub(crate) fn update(&mut self, fields: &[AutofillField], submit: bool) -> Result<(), JsValue> {
+        self.inputs.retain(|id, input| {
+            let keep = fields.iter().any(|field| field.id == *id);
+            if !keep {
+                input.remove();
+            }
+            keep
+        });
+
+        for field in fields {
+            let input = match self.inputs.entry(field.id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let document = web_sys::window().unwrap().document().unwrap();
+                    let input = document
+                        .create_element("input")?
+                        .dyn_into::<web_sys::HtmlInputElement>()?;
+                    input.set_attribute(FIELD_ID_ATTRIBUTE, &field.id.value().to_string())?;
+                    input.set_tab_index(-1);
+                    self.form.append_child(&input)?;
+                    entry.insert(input)
+                }
+            };
+
+            input.set_type(if field.password { "password" } else { "text" });
+            if input.get_attribute("autocomplete").as_deref() != Some(&field.autocomplete) {
+                // Password managers look at the `name` as well:
+                input.set_name(&field.autocomplete);
+                input.set_attribute("autocomplete", &field.autocomplete)?;
+            }
+
+            let value = if field.password && !submit {
+                // Keep passwords out of the DOM, except for what the browser filled in itself:
+                self.submitted.then_some("")
+            } else {
+                Some(field.text.as_str())
+            };
+            if let Some(value) = value {
+                if input.value() != value {
+                    // Setting the value does not fire an `input` event.
+                    input.set_value(value);
+                }
+            }
+        }
+
+        if submit {
+            // Fires a `submit` event, which password managers look for:
+            self.form.request_submit()?;
+        }
+        self.submitted = submit;
+
+        Ok(())
+    }
+}
+
+impl Drop for Autofill {
+    fn drop(&mut self) {
+        self.form.remove();
+    }
+}
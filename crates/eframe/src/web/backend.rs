@@ -14,6 +14,9 @@ pub(crate) struct WebInput {
     /// Required because we don't get a position on touched
     pub primary_touch: Option<egui::TouchId>,
 
+    /// Center and distance of the two touches of an ongoing two-finger gesture.
+    pub two_finger_gesture: Option<(egui::Pos2, f32)>,
+
     /// The raw input to `egui`.
     pub raw: egui::RawInput,
 }
@@ -25,11 +28,12 @@ impl WebInput {
             time: Some(super::now_sec()),
             ..self.raw.take()
         };
-        raw_input
+        let viewport = raw_input
             .viewports
             .entry(egui::ViewportId::ROOT)
-            .or_default()
-            .native_pixels_per_point = Some(super::native_pixels_per_point());
+            .or_default();
+        viewport.native_pixels_per_point = Some(super::native_pixels_per_point());
+        viewport.focused = Some(raw_input.focused);
         raw_input
     }
 
@@ -44,6 +48,7 @@ impl WebInput {
         self.raw.focused = focused;
         self.raw.events.push(egui::Event::WindowFocused(focused));
         self.primary_touch = None;
+        self.two_finger_gesture = None;
     }
 }
 
@@ -0,0 +1,78 @@
+//! Detect which browser features are available to us.
+
+use wasm_bindgen::JsValue;
+
+use crate::epi::WebCapabilities;
+
+/// Detect what the browser supports, and log anything that is missing.
+pub(crate) fn detect() -> WebCapabilities {
+    let Some(window) = web_sys::window() else {
+        return WebCapabilities::default();
+    };
+    let navigator = window.navigator();
+
+    let secure_context = window.is_secure_context();
+    let cross_origin_isolated = has_true_property(&window, "crossOriginIsolated");
+    let threads = cross_origin_isolated && has_property(&js_sys::global(), "SharedArrayBuffer");
+
+    // `navigator.clipboard` is only present in secure contexts.
+    let clipboard = get_property(&navigator, "clipboard").filter(|c| !c.is_undefined());
+    let clipboard_read = clipboard.as_ref().is_some_and(|clipboard| {
+        has_property(clipboard, "read") || has_property(clipboard, "readText")
+    });
+    let clipboard_write = clipboard.as_ref().is_some_and(|clipboard| {
+        has_property(clipboard, "write") || has_property(clipboard, "writeText")
+    });
+
+    // Accessing `localStorage` can throw, e.g. if the user has disabled cookies.
+    let local_storage = window.local_storage().ok().flatten().is_some();
+    let persistent_storage = get_property(&navigator, "storage")
+        .filter(|storage| !storage.is_undefined())
+        .is_some_and(|storage| has_property(&storage, "persist"));
+
+    let webgpu = get_property(&navigator, "gpu").is_some_and(|gpu| !gpu.is_undefined());
+    let pointer_lock = window
+        .document()
+        .is_some_and(|document| has_property(&document, "exitPointerLock"));
+
+    let capabilities = WebCapabilities {
+        secure_context,
+        cross_origin_isolated,
+        threads,
+        clipboard_read,
+        clipboard_write,
+        local_storage,
+        persistent_storage,
+        webgpu,
+        pointer_lock,
+    };
+
+    log::debug!("Detected browser capabilities: {capabilities:?}");
+
+    if !secure_context {
+        log::warn!(
+            "The page is not served from a secure context, so the clipboard and other features will be unavailable. \
+            See https://developer.mozilla.org/en-US/docs/Web/Security/Secure_Contexts"
+        );
+    }
+    if !cross_origin_isolated {
+        log::debug!(
+            "The page is not cross-origin isolated, so SharedArrayBuffer and threads are unavailable. \
+            Serve it with `Cross-Origin-Opener-Policy: same-origin` and `Cross-Origin-Embedder-Policy: require-corp` to enable them."
+        );
+    }
+
+    capabilities
+}
+
+fn get_property(target: &JsValue, name: &str) -> Option<JsValue> {
+    js_sys::Reflect::get(target, &JsValue::from_str(name)).ok()
+}
+
+fn has_property(target: &JsValue, name: &str) -> bool {
+    js_sys::Reflect::has(target, &JsValue::from_str(name)).unwrap_or(false)
+}
+
+fn has_true_property(target: &JsValue, name: &str) -> bool {
+    get_property(target, name).is_some_and(|value| value.as_bool() == Some(true))
+}
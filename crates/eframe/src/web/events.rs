@@ -1,8 +1,8 @@
 use super::{
     button_from_mouse_event, location_hash, modifiers_from_kb_event, modifiers_from_mouse_event,
-    modifiers_from_wheel_event, pos_from_mouse_event, prefers_color_scheme_dark, primary_touch_pos,
-    push_touches, text_from_keyboard_event, theme_from_dark_mode, translate_key, AppRunner,
-    Closure, JsCast, JsValue, WebRunner,
+    modifiers_from_wheel_event, now_sec, pos_from_mouse_event, prefers_color_scheme_dark,
+    primary_touch_pos, push_touches, text_from_keyboard_event, theme_from_dark_mode, translate_key,
+    update_two_finger_gesture, AppRunner, Closure, JsCast, JsValue, WebRunner,
 };
 use web_sys::EventTarget;
 
@@ -17,6 +17,7 @@ use web_sys::EventTarget;
 pub(crate) fn paint_and_schedule(runner_ref: &WebRunner) -> Result<(), JsValue> {
     // Only paint and schedule if there has been no panic
     if let Some(mut runner_lock) = runner_ref.try_lock() {
+        runner_lock.painted_this_frame = false;
         paint_if_needed(&mut runner_lock);
         drop(runner_lock);
         runner_ref.request_animation_frame()?;
@@ -24,8 +25,53 @@ pub(crate) fn paint_and_schedule(runner_ref: &WebRunner) -> Result<(), JsValue>
     Ok(())
 }
 
+/// Called when a repaint was requested with no delay, e.g. from an async task.
+///
+/// If we haven't painted since the last animation frame, i.e. the app is idle,
+/// paint right away instead of waiting for the next animation frame.
+pub(crate) fn paint_on_wake(runner_ref: &WebRunner) {
+    let Some(mut runner_lock) = runner_ref.try_lock() else {
+        return;
+    };
+    if runner_lock.painted_this_frame {
+        return; // The next animation frame will handle it, at the normal frame rate.
+    }
+    let is_visible = web_sys::window()
+        .and_then(|window| window.document())
+        .is_some_and(|document| !document.hidden());
+    if is_visible {
+        paint_if_needed(&mut runner_lock);
+    }
+}
+
+/// Apply [`crate::RepaintMode`].
+fn is_repaint_allowed(runner: &AppRunner) -> bool {
+    let repaint_mode = runner.frame.repaint_mode();
+    let now = now_sec();
+
+    let time_since_input =
+        std::time::Duration::from_secs_f64((now - runner.last_input_time).max(0.0));
+    if !repaint_mode.allows_requested_repaint(time_since_input) {
+        // The user is idle - ignore the request.
+        runner.needs_repaint.clear();
+        return false;
+    }
+
+    // The browser already paces `requestAnimationFrame` to the monitor:
+    let monitor_refresh_rate = None;
+    repaint_mode
+        .min_frame_interval(monitor_refresh_rate)
+        .map_or(true, |interval| {
+            // If it is too soon, we try again next animation frame.
+            interval.as_secs_f64() <= now - runner.last_paint_time
+        })
+}
+
 fn paint_if_needed(runner: &mut AppRunner) {
-    if runner.needs_repaint.needs_repaint() {
+    if runner.needs_repaint.needs_repaint() && is_repaint_allowed(runner) {
+        runner.painted_this_frame = true;
+        runner.last_paint_time = now_sec();
+
         if runner.has_outstanding_paint_data() {
             // We have already run the logic, e.g. in an on-click event,
             // so let's only present the results:
@@ -100,10 +146,48 @@ pub(crate) fn install_event_handlers(runner_ref: &WebRunner) -> Result<(), JsVal
     install_wheel(runner_ref, &canvas)?;
     install_drag_and_drop(runner_ref, &canvas)?;
     install_window_events(runner_ref, &window)?;
+    install_visibility_change(runner_ref, &document)?;
+    install_fullscreen_change(runner_ref, &document)?;
     install_color_scheme_change_event(runner_ref, &window)?;
+    install_contrast_change_event(runner_ref, &window)?;
     Ok(())
 }
 
+/// Does an event with this name come from the user (pointer, keyboard, clipboard, …)?
+///
+/// Only those count as input for [`crate::RepaintMode::OnInput`], so that e.g. a `resize`,
+/// `visibilitychange` or `hashchange` doesn't keep an idle app repainting.
+pub(crate) fn is_user_input(event_name: &str) -> bool {
+    matches!(
+        event_name,
+        "blur"
+            | "click"
+            | "compositionend"
+            | "compositionstart"
+            | "compositionupdate"
+            | "copy"
+            | "cut"
+            | "dragleave"
+            | "dragover"
+            | "drop"
+            | "focus"
+            | "focusin"
+            | "input"
+            | "keydown"
+            | "keyup"
+            | "mouseleave"
+            | "mousemove"
+            | "paste"
+            | "pointerdown"
+            | "pointerup"
+            | "touchcancel"
+            | "touchend"
+            | "touchmove"
+            | "touchstart"
+            | "wheel"
+    )
+}
+
 fn install_blur_focus(runner_ref: &WebRunner, target: &EventTarget) -> Result<(), JsValue> {
     // NOTE: because of the text agent we sometime miss 'blur' events,
     // so we also poll the focus state each frame in `AppRunner::logic`.
@@ -140,7 +224,7 @@ fn install_keydown(runner_ref: &WebRunner, target: &EventTarget) -> Result<(), J
                     runner.needs_repaint.repaint_asap();
 
                     // If this is indeed text, then prevent any other action.
-                    event.prevent_default();
+                    prevent_default(runner, &event);
 
                     // Use web options to tell if the event should be propagated to parent elements.
                     if !should_propagate {
@@ -176,6 +260,7 @@ pub(crate) fn on_keydown(event: web_sys::KeyboardEvent, runner: &mut AppRunner)
         let egui_event = egui::Event::Key {
             key: egui_key,
             physical_key: None, // TODO(fornwall)
+            logical_key: Some(egui_key),
             pressed: true,
             repeat: false, // egui will fill this in for us!
             modifiers,
@@ -193,7 +278,7 @@ pub(crate) fn on_keydown(event: web_sys::KeyboardEvent, runner: &mut AppRunner)
         // );
 
         if prevent_default {
-            event.prevent_default();
+            self::prevent_default(runner, &event);
         }
 
         // Use web options to tell if the web event should be propagated to parent elements based on the egui event.
@@ -256,6 +341,7 @@ pub(crate) fn on_keyup(event: web_sys::KeyboardEvent, runner: &mut AppRunner) {
         let egui_event = egui::Event::Key {
             key,
             physical_key: None, // TODO(fornwall)
+            logical_key: Some(key),
             pressed: false,
             repeat: false,
             modifiers,
@@ -275,6 +361,7 @@ pub(crate) fn on_keyup(event: web_sys::KeyboardEvent, runner: &mut AppRunner) {
             let egui_event = egui::Event::Key {
                 key,
                 physical_key: None,
+                logical_key: None,
                 pressed: false,
                 repeat: false,
                 modifiers,
@@ -294,24 +381,43 @@ pub(crate) fn on_keyup(event: web_sys::KeyboardEvent, runner: &mut AppRunner) {
 }
 
 fn install_copy_cut_paste(runner_ref: &WebRunner, target: &EventTarget) -> Result<(), JsValue> {
-    runner_ref.add_event_listener(target, "paste", |event: web_sys::ClipboardEvent, runner| {
-        if let Some(data) = event.clipboard_data() {
-            if let Ok(text) = data.get_data("text") {
-                let text = text.replace("\r\n", "\n");
+    runner_ref.add_event_listener(target, "paste", {
+        let runner_ref = runner_ref.clone();
 
-                let mut should_propagate = false;
-                if !text.is_empty() && runner.input.raw.focused {
-                    let egui_event = egui::Event::Paste(text);
-                    should_propagate = (runner.web_options.should_propagate_event)(&egui_event);
-                    runner.input.raw.events.push(egui_event);
-                    runner.needs_repaint.repaint_asap();
+        move |event: web_sys::ClipboardEvent, runner| {
+            if let Some(data) = event.clipboard_data() {
+                if runner.input.raw.focused {
+                    // Pasted images (e.g. screenshots) and files are given to egui as dropped files:
+                    let items = data.items();
+                    for i in 0..items.length() {
+                        let Some(item) = items.get(i) else { continue };
+                        if item.kind() == "file" {
+                            if let Ok(Some(file)) = item.get_as_file() {
+                                load_dropped_file(&runner_ref, &file);
+                            }
+                        }
+                    }
                 }
 
-                // Use web options to tell if the web event should be propagated to parent elements based on the egui event.
-                if !should_propagate {
-                    event.stop_propagation();
+                if let Ok(text) = data.get_data("text") {
+                    let text = text.replace("\r\n", "\n");
+
+                    let mut should_propagate = false;
+                    if !text.is_empty() && runner.input.raw.focused {
+                        let egui_event = egui::Event::Paste(text);
+                        should_propagate = (runner.web_options.should_propagate_event)(&egui_event);
+                        runner.input.raw.events.push(egui_event);
+                        runner.needs_repaint.repaint_asap();
+                    }
+
+                    // Use web options to tell if the web event should be propagated to parent elements based on the egui event.
+                    if !should_propagate {
+                        event.stop_propagation();
+                    }
+                    if runner.input.raw.focused {
+                        prevent_default(runner, &event);
+                    }
                 }
-                event.prevent_default();
             }
         }
     })?;
@@ -332,7 +438,9 @@ fn install_copy_cut_paste(runner_ref: &WebRunner, target: &EventTarget) -> Resul
         if !(runner.web_options.should_propagate_event)(&egui::Event::Cut) {
             event.stop_propagation();
         }
-        event.prevent_default();
+        if runner.input.raw.focused {
+            prevent_default(runner, &event);
+        }
     })?;
 
     runner_ref.add_event_listener(target, "copy", |event: web_sys::ClipboardEvent, runner| {
@@ -351,7 +459,9 @@ fn install_copy_cut_paste(runner_ref: &WebRunner, target: &EventTarget) -> Resul
         if !(runner.web_options.should_propagate_event)(&egui::Event::Copy) {
             event.stop_propagation();
         }
-        event.prevent_default();
+        if runner.input.raw.focused {
+            prevent_default(runner, &event);
+        }
     })?;
 
     Ok(())
@@ -364,22 +474,101 @@ fn install_window_events(runner_ref: &WebRunner, window: &EventTarget) -> Result
     })?;
 
     // NOTE: resize is handled by `ResizeObserver` below
-    for event_name in &["load", "pagehide", "pageshow"] {
-        runner_ref.add_event_listener(window, event_name, move |_: web_sys::Event, runner| {
-            // log::debug!("{event_name:?}");
-            runner.needs_repaint.repaint_asap();
-        })?;
-    }
+    runner_ref.add_event_listener(window, "load", |_: web_sys::Event, runner| {
+        runner.needs_repaint.repaint_asap();
+    })?;
+
+    runner_ref.add_event_listener(
+        window,
+        "pagehide",
+        |event: web_sys::PageTransitionEvent, runner| {
+            if event.persisted() {
+                // Going into the back/forward cache, so we may come back:
+                runner.on_lifecycle_event(crate::LifecycleEvent::Suspended);
+            } else {
+                runner.on_lifecycle_event(crate::LifecycleEvent::SessionEnding);
+            }
+        },
+    )?;
+
+    runner_ref.add_event_listener(window, "pageshow", {
+        let web_runner = runner_ref.clone();
+        move |event: web_sys::PageTransitionEvent, runner| {
+            if event.persisted() {
+                // Restored from the back/forward cache:
+                web_runner.on_restored_from_bfcache(runner);
+            } else {
+                runner.needs_repaint.repaint_asap();
+            }
+        }
+    })?;
+
+    runner_ref.add_event_listener(window, "languagechange", |_: web_sys::Event, runner| {
+        let egui_ctx = runner.egui_ctx().clone();
+        crate::locale::refresh(
+            &mut runner.frame.info,
+            runner.web_options.layout_direction,
+            &egui_ctx,
+        );
+    })?;
 
     runner_ref.add_event_listener(window, "hashchange", |_: web_sys::Event, runner| {
         // `epi::Frame::info(&self)` clones `epi::IntegrationInfo`, but we need to modify the original here
-        runner.frame.info.web_info.location.hash = location_hash();
+        let web_info = &mut runner.frame.info.web_info;
+        web_info.location.hash = location_hash();
+        web_info
+            .navigation_events
+            .push(crate::NavigationEvent::HashChange {
+                hash: web_info.location.hash.clone(),
+            });
         runner.needs_repaint.repaint_asap(); // tell the user about the new hash
     })?;
 
+    runner_ref.add_event_listener(
+        window,
+        "popstate",
+        |event: web_sys::PopStateEvent, runner| {
+            let web_info = &mut runner.frame.info.web_info;
+            web_info.location = super::web_location();
+            web_info
+                .navigation_events
+                .push(crate::NavigationEvent::PopState {
+                    url: web_info.location.url.clone(),
+                    state: event.state().as_string(),
+                });
+            runner.needs_repaint.repaint_asap();
+        },
+    )?;
+
     Ok(())
 }
 
+fn install_visibility_change(
+    runner_ref: &WebRunner,
+    document: &web_sys::Document,
+) -> Result<(), JsValue> {
+    runner_ref.add_event_listener(document, "visibilitychange", |_: web_sys::Event, runner| {
+        let hidden = web_sys::window()
+            .and_then(|window| window.document())
+            .map_or(false, |document| document.hidden());
+        runner.on_lifecycle_event(if hidden {
+            crate::LifecycleEvent::Background
+        } else {
+            crate::LifecycleEvent::Foreground
+        });
+    })
+}
+
+fn install_fullscreen_change(
+    runner_ref: &WebRunner,
+    document: &web_sys::Document,
+) -> Result<(), JsValue> {
+    // Keep `ViewportInfo::fullscreen` up to date, also when the user leaves fullscreen with `Esc`:
+    runner_ref.add_event_listener(document, "fullscreenchange", |_: web_sys::Event, runner| {
+        runner.needs_repaint.repaint_asap();
+    })
+}
+
 fn install_color_scheme_change_event(
     runner_ref: &WebRunner,
     window: &web_sys::Window,
@@ -390,7 +579,14 @@ fn install_color_scheme_change_event(
             "change",
             |event, runner| {
                 let theme = theme_from_dark_mode(event.matches());
-                runner.input.raw.system_theme = Some(theme);
+                if runner.input.raw.system_theme != Some(theme) {
+                    runner.input.raw.system_theme = Some(theme);
+                    runner
+                        .input
+                        .raw
+                        .events
+                        .push(egui::Event::SystemThemeChanged(theme));
+                }
                 runner.needs_repaint.repaint_asap();
             },
         )?;
@@ -399,14 +595,42 @@ fn install_color_scheme_change_event(
     Ok(())
 }
 
+fn install_contrast_change_event(
+    runner_ref: &WebRunner,
+    window: &web_sys::Window,
+) -> Result<(), JsValue> {
+    for query in [super::PREFERS_MORE_CONTRAST, super::FORCED_COLORS] {
+        if let Some(media_query_list) = window.match_media(query)? {
+            runner_ref.add_event_listener::<web_sys::MediaQueryListEvent>(
+                &media_query_list,
+                "change",
+                |_event, runner| {
+                    runner.input.raw.system_high_contrast = super::system_high_contrast();
+                    runner.needs_repaint.repaint_asap();
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Call `preventDefault`, unless the listener is passive (see [`crate::EventListenerOptions`]),
+/// in which case the browser would ignore it with a warning.
+fn prevent_default(runner: &AppRunner, event: &web_sys::Event) {
+    if !(runner.web_options.event_listener_options)(&event.type_()).passive {
+        event.prevent_default();
+    }
+}
+
 fn prevent_default_and_stop_propagation(
     runner_ref: &WebRunner,
     target: &EventTarget,
     event_names: &[&'static str],
 ) -> Result<(), JsValue> {
     for event_name in event_names {
-        let closure = move |event: web_sys::MouseEvent, _runner: &mut AppRunner| {
-            event.prevent_default();
+        let closure = move |event: web_sys::MouseEvent, runner: &mut AppRunner| {
+            prevent_default(runner, &event);
             event.stop_propagation();
             // log::debug!("Preventing event {event_name:?}");
         };
@@ -437,10 +661,25 @@ fn install_pointerdown(runner_ref: &WebRunner, target: &EventTarget) -> Result<(
                 should_propagate = (runner.web_options.should_propagate_event)(&egui_event);
                 runner.input.raw.events.push(egui_event);
 
+                // Make sure keyboard input goes to us, and not the rest of the page.
+                // If the user is typing into the text agent, focusing the canvas would blur it
+                // (closing the virtual keyboard), so let the app logic decide first.
+                let text_agent_had_focus = runner.text_agent.has_focus();
+                if !text_agent_had_focus {
+                    runner.canvas().focus().ok();
+                }
+                runner.update_focus();
+
                 // In Safari we are only allowed to write to the clipboard during the
                 // event callback, which is why we run the app logic here and now:
                 runner.logic();
 
+                if text_agent_had_focus && !runner.text_agent.has_focus() {
+                    // The click ended the text editing:
+                    runner.canvas().focus().ok();
+                    runner.update_focus();
+                }
+
                 // Make sure we paint the output of the above logic call asap:
                 runner.needs_repaint.repaint_asap();
             }
@@ -495,7 +734,7 @@ fn install_pointerup(runner_ref: &WebRunner, target: &EventTarget) -> Result<(),
                     // Make sure we paint the output of the above logic call asap:
                     runner.needs_repaint.repaint_asap();
 
-                    event.prevent_default();
+                    prevent_default(runner, &event);
 
                     // Use web options to tell if the web event should be propagated to parent elements based on the egui event.
                     if !should_propagate {
@@ -541,7 +780,7 @@ fn install_mousemove(runner_ref: &WebRunner, target: &EventTarget) -> Result<(),
             if !should_propagate {
                 event.stop_propagation();
             }
-            event.prevent_default();
+            prevent_default(runner, &event);
         }
     })
 }
@@ -558,7 +797,7 @@ fn install_mouseleave(runner_ref: &WebRunner, target: &EventTarget) -> Result<()
             if !(runner.web_options.should_propagate_event)(&egui::Event::PointerGone) {
                 event.stop_propagation();
             }
-            event.prevent_default();
+            prevent_default(runner, &event);
         },
     )
 }
@@ -581,19 +820,29 @@ fn install_touchstart(runner_ref: &WebRunner, target: &EventTarget) -> Result<()
             }
 
             push_touches(runner, egui::TouchPhase::Start, &event);
+            update_two_finger_gesture(runner, &event);
             runner.needs_repaint.repaint_asap();
 
             // Use web options to tell if the web event should be propagated to parent elements based on the egui event.
             if !should_propagate {
                 event.stop_propagation();
             }
-            event.prevent_default();
+            prevent_default(runner, &event);
         },
     )
 }
 
 fn install_touchmove(runner_ref: &WebRunner, target: &EventTarget) -> Result<(), JsValue> {
     runner_ref.add_event_listener(target, "touchmove", |event: web_sys::TouchEvent, runner| {
+        if runner.input.two_finger_gesture.is_some() && update_two_finger_gesture(runner, &event) {
+            // Pinch/pan: don't also move the pointer, or scroll areas would be dragged as well as scrolled.
+            push_touches(runner, egui::TouchPhase::Move, &event);
+            runner.needs_repaint.repaint_asap();
+            event.stop_propagation();
+            prevent_default(runner, &event);
+            return;
+        }
+
         if let Some((pos, touch)) = primary_touch_pos(runner, &event) {
             if is_interested_in_pointer_event(
                 runner,
@@ -610,7 +859,7 @@ fn install_touchmove(runner_ref: &WebRunner, target: &EventTarget) -> Result<(),
                 if !should_propagate {
                     event.stop_propagation();
                 }
-                event.prevent_default();
+                prevent_default(runner, &event);
             }
         }
     })
@@ -618,6 +867,8 @@ fn install_touchmove(runner_ref: &WebRunner, target: &EventTarget) -> Result<(),
 
 fn install_touchend(runner_ref: &WebRunner, target: &EventTarget) -> Result<(), JsValue> {
     runner_ref.add_event_listener(target, "touchend", |event: web_sys::TouchEvent, runner| {
+        update_two_finger_gesture(runner, &event);
+
         if let Some((pos, touch)) = primary_touch_pos(runner, &event) {
             if is_interested_in_pointer_event(
                 runner,
@@ -646,7 +897,7 @@ fn install_touchend(runner_ref: &WebRunner, target: &EventTarget) -> Result<(),
                 if !should_propagate {
                     event.stop_propagation();
                 }
-                event.prevent_default();
+                prevent_default(runner, &event);
 
                 // Fix virtual keyboard IOS
                 // Need call focus at the same time of event
@@ -665,8 +916,9 @@ fn install_touchcancel(runner_ref: &WebRunner, target: &EventTarget) -> Result<(
         "touchcancel",
         |event: web_sys::TouchEvent, runner| {
             push_touches(runner, egui::TouchPhase::Cancel, &event);
+            update_two_finger_gesture(runner, &event);
             event.stop_propagation();
-            event.prevent_default();
+            prevent_default(runner, &event);
         },
     )?;
 
@@ -711,7 +963,12 @@ fn install_wheel(runner_ref: &WebRunner, target: &EventTarget) -> Result<(), JsV
         if !should_propagate {
             event.stop_propagation();
         }
-        event.prevent_default();
+
+        // Let the browser scroll (or zoom) the page if there is nothing in egui to scroll:
+        let egui_ctx = runner.egui_ctx();
+        if egui_ctx.is_pointer_over_area() || egui_ctx.is_using_pointer() {
+            prevent_default(runner, &event);
+        }
     })
 }
 
@@ -743,7 +1000,7 @@ fn install_drag_and_drop(runner_ref: &WebRunner, target: &EventTarget) -> Result
 
             runner.needs_repaint.repaint_asap();
             event.stop_propagation();
-            event.prevent_default();
+            prevent_default(runner, &event);
         }
     })?;
 
@@ -751,7 +1008,7 @@ fn install_drag_and_drop(runner_ref: &WebRunner, target: &EventTarget) -> Result
         runner.input.raw.hovered_files.clear();
         runner.needs_repaint.repaint_asap();
         event.stop_propagation();
-        event.prevent_default();
+        prevent_default(runner, &event);
     })?;
 
     runner_ref.add_event_listener(target, "drop", {
@@ -766,46 +1023,12 @@ fn install_drag_and_drop(runner_ref: &WebRunner, target: &EventTarget) -> Result
                 if let Some(files) = data_transfer.files() {
                     for i in 0..files.length() {
                         if let Some(file) = files.get(i) {
-                            let name = file.name();
-                            let mime = file.type_();
-                            let last_modified = std::time::UNIX_EPOCH
-                                + std::time::Duration::from_millis(file.last_modified() as u64);
-
-                            log::debug!("Loading {:?} ({} bytes)…", name, file.size());
-
-                            let future = wasm_bindgen_futures::JsFuture::from(file.array_buffer());
-
-                            let runner_ref = runner_ref.clone();
-                            let future = async move {
-                                match future.await {
-                                    Ok(array_buffer) => {
-                                        let bytes = js_sys::Uint8Array::new(&array_buffer).to_vec();
-                                        log::debug!("Loaded {:?} ({} bytes).", name, bytes.len());
-
-                                        if let Some(mut runner_lock) = runner_ref.try_lock() {
-                                            runner_lock.input.raw.dropped_files.push(
-                                                egui::DroppedFile {
-                                                    name,
-                                                    mime,
-                                                    last_modified: Some(last_modified),
-                                                    bytes: Some(bytes.into()),
-                                                    ..Default::default()
-                                                },
-                                            );
-                                            runner_lock.needs_repaint.repaint_asap();
-                                        }
-                                    }
-                                    Err(err) => {
-                                        log::error!("Failed to read file: {:?}", err);
-                                    }
-                                }
-                            };
-                            wasm_bindgen_futures::spawn_local(future);
+                            load_dropped_file(&runner_ref, &file);
                         }
                     }
                 }
                 event.stop_propagation();
-                event.prevent_default();
+                prevent_default(runner, &event);
             }
         }
     })?;
@@ -813,6 +1036,44 @@ fn install_drag_and_drop(runner_ref: &WebRunner, target: &EventTarget) -> Result
     Ok(())
 }
 
+/// Read the contents of the file asynchronously,
+/// and then hand it to egui as a [`egui::DroppedFile`].
+fn load_dropped_file(runner_ref: &WebRunner, file: &web_sys::File) {
+    let name = file.name();
+    let mime = file.type_();
+    let last_modified =
+        std::time::UNIX_EPOCH + std::time::Duration::from_millis(file.last_modified() as u64);
+
+    log::debug!("Loading {:?} ({} bytes)…", name, file.size());
+
+    let future = wasm_bindgen_futures::JsFuture::from(file.array_buffer());
+
+    let runner_ref = runner_ref.clone();
+    let future = async move {
+        match future.await {
+            Ok(array_buffer) => {
+                let bytes = js_sys::Uint8Array::new(&array_buffer).to_vec();
+                log::debug!("Loaded {:?} ({} bytes).", name, bytes.len());
+
+                if let Some(mut runner_lock) = runner_ref.try_lock() {
+                    runner_lock.input.raw.dropped_files.push(egui::DroppedFile {
+                        name,
+                        mime,
+                        last_modified: Some(last_modified),
+                        bytes: Some(bytes.into()),
+                        ..Default::default()
+                    });
+                    runner_lock.needs_repaint.repaint_asap();
+                }
+            }
+            Err(err) => {
+                log::error!("Failed to read file: {:?}", err);
+            }
+        }
+    };
+    wasm_bindgen_futures::spawn_local(future);
+}
+
 /// Install a `ResizeObserver` to observe changes to the size of the canvas.
 ///
 /// This is the only way to ensure a canvas size change without an associated window `resize` event
@@ -851,10 +1112,8 @@ pub(crate) fn install_resize_observer(runner_ref: &WebRunner) -> Result<(), JsVa
     }) as Box<dyn FnMut(js_sys::Array)>);
 
     let observer = web_sys::ResizeObserver::new(closure.as_ref().unchecked_ref())?;
-    let options = web_sys::ResizeObserverOptions::new();
-    options.set_box(web_sys::ResizeObserverBoxOptions::ContentBox);
     if let Some(runner_lock) = runner_ref.try_lock() {
-        observer.observe_with_options(runner_lock.canvas(), &options);
+        observe_canvas_size(&observer, runner_lock.canvas());
         drop(runner_lock);
         runner_ref.set_resize_observer(observer, closure);
     }
@@ -862,9 +1121,21 @@ pub(crate) fn install_resize_observer(runner_ref: &WebRunner) -> Result<(), JsVa
     Ok(())
 }
 
+/// Start observing the size of the canvas. The observer is called right away with the current size.
+pub(crate) fn observe_canvas_size(
+    observer: &web_sys::ResizeObserver,
+    canvas: &web_sys::HtmlCanvasElement,
+) {
+    let options = web_sys::ResizeObserverOptions::new();
+    options.set_box(web_sys::ResizeObserverBoxOptions::ContentBox);
+    observer.observe_with_options(canvas, &options);
+}
+
 // Code ported to Rust from:
 // https://webglfundamentals.org/webgl/lessons/webgl-resizing-the-canvas.html
-fn get_display_size(resize_observer_entries: &js_sys::Array) -> Result<(u32, u32), JsValue> {
+pub(crate) fn get_display_size(
+    resize_observer_entries: &js_sys::Array,
+) -> Result<(u32, u32), JsValue> {
     let width;
     let height;
     let mut dpr = web_sys::window().unwrap().device_pixel_ratio();
@@ -0,0 +1,91 @@
+use wasm_bindgen::JsValue;
+
+/// Where the time of a frame went, for triaging slow frames on the web.
+///
+/// All times are in seconds. See [`super::WebRunner::frame_timings`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameTimings {
+    /// Handling browser events (mouse, keyboard, …) since the previous frame.
+    ///
+    /// Does not include any [`Self::update`] or [`Self::tessellation`] run by the event handlers.
+    pub events: f32,
+
+    /// Running [`crate::App::update`], including the rest of [`egui::Context::run`].
+    pub update: f32,
+
+    /// Turning the shapes into triangles.
+    pub tessellation: f32,
+
+    /// Uploading textures and drawing with WebGL or WebGPU.
+    pub paint: f32,
+}
+
+impl FrameTimings {
+    /// Sum of all the phases.
+    pub fn total(&self) -> f32 {
+        let Self {
+            events,
+            update,
+            tessellation,
+            paint,
+        } = *self;
+        events + update + tessellation + paint
+    }
+
+    /// The name of the phase that took the longest time.
+    pub fn slowest_phase(&self) -> &'static str {
+        self.phases()
+            .into_iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or("", |(name, _)| name)
+    }
+
+    fn phases(&self) -> [(&'static str, f32); 4] {
+        [
+            ("events", self.events),
+            ("update", self.update),
+            ("tessellation", self.tessellation),
+            ("paint", self.paint),
+        ]
+    }
+
+    /// A JS object with the phases and their `total`, in milliseconds.
+    pub fn to_js_object(self) -> js_sys::Object {
+        let object = js_sys::Object::new();
+        for (name, seconds) in self
+            .phases()
+            .into_iter()
+            .chain(std::iter::once(("total", self.total())))
+        {
+            js_sys::Reflect::set(
+                &object,
+                &name.into(),
+                &JsValue::from_f64(1e3 * seconds as f64),
+            )
+            .ok();
+        }
+        object
+    }
+
+    /// Print the timings with `console.table`.
+    pub fn log_table(&self) {
+        web_sys::console::table_1(&self.to_js_object());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameTimings;
+
+    #[test]
+    fn slowest_phase() {
+        let timings = FrameTimings {
+            events: 0.001,
+            update: 0.002,
+            tessellation: 0.0005,
+            paint: 0.020,
+        };
+        assert_eq!(timings.slowest_phase(), "paint");
+        assert_eq!(FrameTimings::default().total(), 0.0);
+    }
+}
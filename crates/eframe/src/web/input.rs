@@ -97,6 +97,49 @@ pub fn push_touches(runner: &mut AppRunner, phase: egui::TouchPhase, event: &web
     }
 }
 
+/// Recognize two-finger pinch and pan gestures,
+/// turning them into [`egui::Event::Zoom`] and [`egui::Event::MouseWheel`] events.
+///
+/// Returns `true` while a two-finger gesture is ongoing.
+pub fn update_two_finger_gesture(runner: &mut AppRunner, event: &web_sys::TouchEvent) -> bool {
+    let touches = event.touches();
+    let (2, Some(first), Some(second)) = (touches.length(), touches.get(0), touches.get(1)) else {
+        runner.input.two_finger_gesture = None;
+        return false;
+    };
+
+    let canvas_rect = canvas_content_rect(runner.canvas());
+    let first = pos_from_touch(canvas_rect, &first, runner.egui_ctx());
+    let second = pos_from_touch(canvas_rect, &second, runner.egui_ctx());
+    let center = first.lerp(second, 0.5);
+    let distance = first.distance(second);
+
+    if let Some((last_center, last_distance)) =
+        runner.input.two_finger_gesture.replace((center, distance))
+    {
+        let modifiers = runner.input.raw.modifiers;
+
+        let pan = center - last_center;
+        if pan != egui::Vec2::ZERO {
+            runner.input.raw.events.push(egui::Event::MouseWheel {
+                unit: egui::MouseWheelUnit::Point,
+                delta: pan,
+                modifiers,
+            });
+        }
+
+        if 0.0 < last_distance && 0.0 < distance && distance != last_distance {
+            runner
+                .input
+                .raw
+                .events
+                .push(egui::Event::Zoom(distance / last_distance));
+        }
+    }
+
+    true
+}
+
 /// The text input from a keyboard event (e.g. `X` when pressing the `X` key).
 pub fn text_from_keyboard_event(event: &web_sys::KeyboardEvent) -> Option<String> {
     let key = event.key();
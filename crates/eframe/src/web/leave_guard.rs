@@ -0,0 +1,88 @@
+//! Ask the user to confirm before they leave the page with unsaved changes,
+//! for [`crate::Frame::set_unsaved_changes`].
+
+use std::{cell::Cell, rc::Rc, sync::Arc};
+
+use wasm_bindgen::{closure::Closure, JsCast as _};
+
+use super::{repaint_waker::WakerId, string_from_js_value, NeedRepaint};
+
+/// Registers a `beforeunload` listener while the app has unsaved changes.
+///
+/// We only register it while needed, because having one prevents the browser
+/// from keeping the page in the back/forward cache.
+pub(crate) struct LeaveGuard {
+    needs_repaint: Arc<NeedRepaint>,
+    waker_id: WakerId,
+
+    /// Set when the user tried to leave the page, and then decided to stay.
+    leave_attempted: Rc<Cell<bool>>,
+
+    /// The registered `beforeunload` listener, if any.
+    listener: Option<Closure<dyn FnMut(web_sys::BeforeUnloadEvent)>>,
+}
+
+impl Drop for LeaveGuard {
+    fn drop(&mut self) {
+        self.set_wanted(false);
+    }
+}
+
+impl LeaveGuard {
+    pub fn new(needs_repaint: Arc<NeedRepaint>, waker_id: WakerId) -> Self {
+        Self {
+            needs_repaint,
+            waker_id,
+            leave_attempted: Default::default(),
+            listener: None,
+        }
+    }
+
+    /// Call each frame with whether or not the app has unsaved changes.
+    pub fn set_wanted(&mut self, wanted: bool) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+
+        if wanted && self.listener.is_none() {
+            let needs_repaint = Arc::clone(&self.needs_repaint);
+            let waker_id = self.waker_id;
+            let leave_attempted = Rc::clone(&self.leave_attempted);
+            let listener = Closure::wrap(Box::new(move |event: web_sys::BeforeUnloadEvent| {
+                // Either of these makes the browser ask the user to confirm, depending on the browser:
+                event.prevent_default();
+                event.set_return_value("");
+
+                // If the page is still around after this, the user chose to stay:
+                leave_attempted.set(true);
+                needs_repaint.repaint_asap();
+                waker_id.wake();
+            })
+                as Box<dyn FnMut(web_sys::BeforeUnloadEvent)>);
+
+            match window
+                .add_event_listener_with_callback("beforeunload", listener.as_ref().unchecked_ref())
+            {
+                Ok(()) => self.listener = Some(listener),
+                Err(err) => log::warn!(
+                    "Failed to guard against leaving the page: {}",
+                    string_from_js_value(&err)
+                ),
+            }
+        } else if !wanted {
+            if let Some(listener) = self.listener.take() {
+                window
+                    .remove_event_listener_with_callback(
+                        "beforeunload",
+                        listener.as_ref().unchecked_ref(),
+                    )
+                    .ok();
+            }
+        }
+    }
+
+    /// Did the user try to leave the page since the last call?
+    pub fn take_leave_attempted(&self) -> bool {
+        self.leave_attempted.replace(false)
+    }
+}
@@ -2,12 +2,21 @@
 
 #![allow(clippy::missing_errors_doc)] // So many `-> Result<_, JsValue>`
 
+#[cfg(feature = "accesskit")]
+mod accessibility;
 mod app_runner;
+mod autofill;
 mod backend;
+mod capabilities;
 mod events;
+mod frame_timings;
 mod input;
+mod leave_guard;
+mod multi_runner;
 mod panic_handler;
+mod repaint_waker;
 mod text_agent;
+mod wake_lock;
 mod web_logger;
 mod web_runner;
 
@@ -18,10 +27,17 @@ pub mod screen_reader;
 /// Access to local browser storage.
 pub mod storage;
 
+#[cfg(feature = "web_worker")]
+mod worker;
+
 pub(crate) use app_runner::AppRunner;
+pub use frame_timings::FrameTimings;
+pub use multi_runner::MultiRunner;
 pub use panic_handler::{PanicHandler, PanicSummary};
 pub use web_logger::WebLogger;
 pub use web_runner::WebRunner;
+#[cfg(feature = "web_worker")]
+pub use worker::{WorkerHost, WorkerRunner};
 
 #[cfg(not(any(feature = "glow", feature = "wgpu")))]
 compile_error!("You must enable either the 'glow' or 'wgpu' feature");
@@ -46,7 +62,7 @@ use web_sys::MediaQueryList;
 use input::{
     button_from_mouse_event, modifiers_from_kb_event, modifiers_from_mouse_event,
     modifiers_from_wheel_event, pos_from_mouse_event, primary_touch_pos, push_touches,
-    text_from_keyboard_event, translate_key,
+    text_from_keyboard_event, translate_key, update_two_finger_gesture,
 };
 
 // ----------------------------------------------------------------------------
@@ -81,10 +97,13 @@ pub(crate) fn has_focus<T: JsCast>(element: &T) -> bool {
 /// Current time in seconds (since undefined point in time).
 ///
 /// Monotonically increasing.
+///
+/// Works both on the main thread and in a Web Worker.
 pub fn now_sec() -> f64 {
-    web_sys::window()
-        .expect("should have a Window")
-        .performance()
+    // The global object is the `Window` on the main thread, and the worker scope in a worker:
+    js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+        .ok()
+        .and_then(|performance| performance.dyn_into::<web_sys::Performance>().ok())
         .expect("should have a Performance")
         .now()
         / 1000.0
@@ -116,6 +135,26 @@ fn prefers_color_scheme_dark(window: &web_sys::Window) -> Result<Option<MediaQue
     window.match_media("(prefers-color-scheme: dark)")
 }
 
+/// Ask the browser if the user wants more contrast.
+///
+/// `None` means unknown.
+pub fn system_high_contrast() -> Option<bool> {
+    let window = web_sys::window()?;
+    let matches = |query: &str| {
+        window
+            .match_media(query)
+            .ok()
+            .flatten()
+            .is_some_and(|list| list.matches())
+    };
+    Some(matches(PREFERS_MORE_CONTRAST) || matches(FORCED_COLORS))
+}
+
+const PREFERS_MORE_CONTRAST: &str = "(prefers-contrast: more)";
+
+/// E.g. a Windows contrast theme.
+const FORCED_COLORS: &str = "(forced-colors: active)";
+
 fn theme_from_dark_mode(dark_mode: bool) -> egui::Theme {
     if dark_mode {
         egui::Theme::Dark
@@ -171,6 +210,30 @@ fn set_cursor_icon(cursor: egui::CursorIcon) -> Option<()> {
         .ok()
 }
 
+/// Is the canvas fullscreen?
+fn is_fullscreen(canvas: &web_sys::HtmlCanvasElement) -> bool {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.fullscreen_element())
+        .is_some_and(|element| &element == AsRef::<web_sys::Element>::as_ref(canvas))
+}
+
+/// Make the canvas fullscreen, or leave fullscreen.
+///
+/// Browsers only allow entering fullscreen in response to user input.
+fn set_fullscreen(canvas: &web_sys::HtmlCanvasElement, fullscreen: bool) {
+    if fullscreen == is_fullscreen(canvas) {
+        return;
+    }
+    if fullscreen {
+        if let Err(err) = canvas.request_fullscreen() {
+            log::warn!("Failed to enter fullscreen: {}", string_from_js_value(&err));
+        }
+    } else if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+        document.exit_fullscreen();
+    }
+}
+
 /// Set the clipboard text.
 fn set_clipboard_text(s: &str) {
     if let Some(window) = web_sys::window() {
@@ -333,6 +396,129 @@ pub fn open_url(url: &str, new_tab: bool) -> Option<()> {
     Some(())
 }
 
+/// Let the user save the given bytes as a file.
+///
+/// Uses the [File System Access API](https://developer.mozilla.org/en-US/docs/Web/API/Window/showSaveFilePicker)
+/// to show a save dialog if the browser supports it,
+/// otherwise falls back to downloading the file with the given `filename`.
+///
+/// Should be called in response to a user action (e.g. a button click),
+/// or the browser may refuse to show the save dialog.
+pub fn save_file(bytes: &[u8], filename: &str, mime: &str) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("No window")?;
+
+    let show_save_file_picker = js_sys::Reflect::get(&window, &"showSaveFilePicker".into())?;
+    let Some(show_save_file_picker) = show_save_file_picker.dyn_ref::<js_sys::Function>() else {
+        return download_file(bytes, filename, mime);
+    };
+
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"suggestedName".into(), &filename.into())?;
+    let picker = show_save_file_picker.call1(&window, &options)?;
+
+    let bytes = bytes.to_vec();
+    let filename = filename.to_owned();
+    let mime = mime.to_owned();
+    wasm_bindgen_futures::spawn_local(async move {
+        match write_to_picked_file(picker, &bytes).await {
+            Ok(()) => {}
+            Err(err) if is_abort_error(&err) => {
+                // The user closed the save dialog
+            }
+            Err(err) => {
+                log::warn!(
+                    "Failed to save file with the save dialog, downloading it instead: {}",
+                    string_from_js_value(&err)
+                );
+                if let Err(err) = download_file(&bytes, &filename, &mime) {
+                    log::error!("Failed to download file: {}", string_from_js_value(&err));
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// `picker` is the promise returned by `showSaveFilePicker`.
+async fn write_to_picked_file(picker: JsValue, bytes: &[u8]) -> Result<(), JsValue> {
+    async fn call_method(
+        target: &JsValue,
+        name: &str,
+        args: &js_sys::Array,
+    ) -> Result<JsValue, JsValue> {
+        let method: js_sys::Function = js_sys::Reflect::get(target, &name.into())?.dyn_into()?;
+        let promise: js_sys::Promise = method.apply(target, args)?.dyn_into()?;
+        wasm_bindgen_futures::JsFuture::from(promise).await
+    }
+
+    let file_handle = wasm_bindgen_futures::JsFuture::from(js_sys::Promise::from(picker)).await?;
+    let writable = call_method(&file_handle, "createWritable", &js_sys::Array::new()).await?;
+    let data = js_sys::Uint8Array::from(bytes);
+    call_method(&writable, "write", &js_sys::Array::of1(&data)).await?;
+    call_method(&writable, "close", &js_sys::Array::new()).await?;
+    Ok(())
+}
+
+fn is_abort_error(err: &JsValue) -> bool {
+    js_sys::Reflect::get(err, &"name".into())
+        .ok()
+        .and_then(|name| name.as_string())
+        .is_some_and(|name| name == "AbortError")
+}
+
+/// Download the bytes as a file by clicking a temporary `<a download>` link.
+fn download_file(bytes: &[u8], filename: &str, mime: &str) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("No window")?;
+    let document = window.document().ok_or("No document")?;
+
+    let array = js_sys::Uint8Array::from(bytes);
+    let blob_parts = js_sys::Array::of1(&array);
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&blob_parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    // Give the browser some time to start the download before we free the blob:
+    let revoke = Closure::once_into_js(move || {
+        web_sys::Url::revoke_object_url(&url).ok();
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 10_000)?;
+
+    Ok(())
+}
+
+/// Set the "#fragment" part of the URL, e.g. `"#settings"`.
+///
+/// The browser will fire a `hashchange` event, which eframe reports as [`crate::NavigationEvent::HashChange`].
+pub fn set_location_hash(hash: &str) -> Result<(), JsValue> {
+    web_sys::window()
+        .ok_or("No window")?
+        .location()
+        .set_hash(hash)
+}
+
+/// Navigate to `url` without reloading the page, adding a new history entry (`history.pushState`).
+pub fn push_history_state(url: &str, state: Option<&str>) -> Result<(), JsValue> {
+    let history = web_sys::window().ok_or("No window")?.history()?;
+    history.push_state_with_url(&history_state(state), "", Some(url))
+}
+
+/// Navigate to `url` without reloading the page, replacing the current history entry (`history.replaceState`).
+pub fn replace_history_state(url: &str, state: Option<&str>) -> Result<(), JsValue> {
+    let history = web_sys::window().ok_or("No window")?.history()?;
+    history.replace_state_with_url(&history_state(state), "", Some(url))
+}
+
+fn history_state(state: Option<&str>) -> JsValue {
+    state.map_or(JsValue::NULL, JsValue::from_str)
+}
+
 /// e.g. "#fragment" part of "www.example.com/index.html#fragment",
 ///
 /// Percent decoded
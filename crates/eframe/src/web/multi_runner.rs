@@ -0,0 +1,85 @@
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen::prelude::*;
+
+use crate::epi;
+
+use super::{PanicHandler, WebRunner};
+
+/// Runs several independent eframe apps on the same web page, each in its own canvas.
+///
+/// All apps share the same panic handler and are painted in the same animation frame,
+/// but each canvas has its own painter (WebGL contexts can't be shared between canvases),
+/// its own [`egui::Context`], and receives only the input events targeting it.
+///
+/// This is cheap to clone.
+///
+/// Call [`Self::start`] once for each canvas.
+#[derive(Clone)]
+pub struct MultiRunner {
+    panic_handler: PanicHandler,
+
+    /// The animation frame in flight, shared by all runners.
+    frame: Rc<RefCell<Option<super::web_runner::AnimationFrameRequest>>>,
+
+    /// All the runners that are currently running.
+    runners: Rc<RefCell<Vec<WebRunner>>>,
+}
+
+impl MultiRunner {
+    /// Will install a panic handler that will catch and log any panics
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            panic_handler: PanicHandler::install(),
+            frame: Default::default(),
+            runners: Default::default(),
+        }
+    }
+
+    /// Create a new app in the given canvas, and start running it.
+    ///
+    /// The returned [`WebRunner`] can be used to access the app, or to [`WebRunner::destroy`] it
+    /// without affecting the other apps.
+    ///
+    /// # Errors
+    /// Failing to initialize graphics, or failure to create app.
+    pub async fn start(
+        &self,
+        canvas: web_sys::HtmlCanvasElement,
+        web_options: crate::WebOptions,
+        app_creator: epi::AppCreator<'static>,
+    ) -> Result<WebRunner, JsValue> {
+        let runner = WebRunner::new_in_group(
+            self.panic_handler.clone(),
+            self.frame.clone(),
+            self.runners.clone(),
+        );
+        runner.start(canvas, web_options, app_creator).await?;
+        Ok(runner)
+    }
+
+    /// All the apps that are currently running.
+    pub fn runners(&self) -> Vec<WebRunner> {
+        self.runners.borrow().clone()
+    }
+
+    /// Has there been a panic in any of the apps?
+    ///
+    /// If so, all apps will have stopped.
+    pub fn has_panicked(&self) -> bool {
+        self.panic_handler.has_panicked()
+    }
+
+    /// What was the panic message and callstack?
+    pub fn panic_summary(&self) -> Option<super::PanicSummary> {
+        self.panic_handler.panic_summary()
+    }
+
+    /// Shut down all the apps and clean up their resources.
+    pub fn destroy(&self) {
+        for runner in self.runners() {
+            runner.destroy();
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Paint right away when a repaint is requested while the app is idle,
+//! e.g. by an async task that just received a websocket message.
+//!
+//! Normally a repaint request is handled in the next `requestAnimationFrame`,
+//! which can take a while, especially when the browser throttles animation frames.
+//! Instead we post a message on a `MessageChannel`, which the browser delivers
+//! as soon as the current task (and its microtasks) are done.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use wasm_bindgen::prelude::*;
+
+use super::{events, WebRunner};
+
+thread_local! {
+    /// The port to post wake-up messages to, for each installed [`RepaintWaker`].
+    ///
+    /// `MessagePort` isn't `Send`, so we can't capture it in the repaint callback of [`egui::Context`].
+    /// The browser runs everything on the same thread anyway.
+    static WAKE_PORTS: RefCell<HashMap<WakerId, WakePort>> = Default::default();
+}
+
+struct WakePort {
+    port: web_sys::MessagePort,
+
+    /// Has a message been posted that has not been received yet?
+    pending: bool,
+}
+
+/// Identifies the [`RepaintWaker`] of a runner.
+///
+/// Unlike the waker itself this is `Send`, so it can be used in the repaint callback of [`egui::Context`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct WakerId(u64);
+
+impl WakerId {
+    pub(crate) fn new() -> Self {
+        static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+
+    /// Schedule a call to [`events::paint_on_wake`], unless one is already scheduled.
+    ///
+    /// Does nothing if no [`RepaintWaker`] has been installed for this id.
+    pub(crate) fn wake(self) {
+        WAKE_PORTS.with(|ports| {
+            if let Some(wake_port) = ports.borrow_mut().get_mut(&self) {
+                if !wake_port.pending {
+                    wake_port.pending = wake_port.port.post_message(&JsValue::NULL).is_ok();
+                }
+            }
+        });
+    }
+
+    fn received(self) {
+        WAKE_PORTS.with(|ports| {
+            if let Some(wake_port) = ports.borrow_mut().get_mut(&self) {
+                wake_port.pending = false;
+            }
+        });
+    }
+}
+
+/// The receiving end of the wake-up messages of a runner.
+pub(crate) struct RepaintWaker {
+    id: WakerId,
+    port: web_sys::MessagePort,
+    _closure: Closure<dyn FnMut()>,
+}
+
+impl RepaintWaker {
+    /// Start listening to the wake-up messages for the runner, sent with [`WakerId::wake`].
+    pub(crate) fn install(runner_ref: &WebRunner) -> Result<(), JsValue> {
+        let Some(id) = runner_ref.try_lock().map(|runner| runner.waker_id) else {
+            return Ok(());
+        };
+
+        let channel = web_sys::MessageChannel::new()?;
+
+        let closure = Closure::wrap(Box::new({
+            let runner_ref = runner_ref.clone();
+            move || {
+                id.received();
+                events::paint_on_wake(&runner_ref);
+            }
+        }) as Box<dyn FnMut()>);
+        let port = channel.port1();
+        port.set_onmessage(Some(closure.as_ref().unchecked_ref()));
+
+        WAKE_PORTS.with(|ports| {
+            ports.borrow_mut().insert(
+                id,
+                WakePort {
+                    port: channel.port2(),
+                    pending: false,
+                },
+            );
+        });
+
+        if let Some(mut runner) = runner_ref.try_lock() {
+            runner.repaint_waker = Some(Self {
+                id,
+                port,
+                _closure: closure,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for RepaintWaker {
+    fn drop(&mut self) {
+        self.port.set_onmessage(None);
+        self.port.close();
+        if let Some(wake_port) = WAKE_PORTS.with(|ports| ports.borrow_mut().remove(&self.id)) {
+            wake_port.port.close();
+        }
+    }
+}
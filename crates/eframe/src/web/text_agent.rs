@@ -76,7 +76,9 @@ impl TextAgent {
 
         let on_composition_update = {
             move |event: web_sys::CompositionEvent, runner: &mut AppRunner| {
-                let Some(text) = event.data() else { return };
+                // An empty preedit is produced when the user deletes the whole composition,
+                // and egui needs to know about it so it can remove the old preedit text.
+                let text = event.data().unwrap_or_default();
                 let event = egui::Event::Ime(egui::ImeEvent::Preedit(text));
                 runner.input.raw.events.push(event);
                 runner.needs_repaint.repaint_asap();
@@ -86,10 +88,17 @@ impl TextAgent {
         let on_composition_end = {
             let input = input.clone();
             move |event: web_sys::CompositionEvent, runner: &mut AppRunner| {
-                let Some(text) = event.data() else { return };
                 input.set_value("");
-                let event = egui::Event::Ime(egui::ImeEvent::Commit(text));
-                runner.input.raw.events.push(event);
+                let text = event.data().unwrap_or_default();
+                let events = &mut runner.input.raw.events;
+                if text.is_empty() {
+                    // The composition was canceled: remove the preedit text.
+                    events.push(egui::Event::Ime(egui::ImeEvent::Preedit(String::new())));
+                } else {
+                    events.push(egui::Event::Ime(egui::ImeEvent::Commit(text)));
+                }
+                // Match what egui-winit does on native:
+                events.push(egui::Event::Ime(egui::ImeEvent::Disabled));
                 runner.needs_repaint.repaint_asap();
             }
         };
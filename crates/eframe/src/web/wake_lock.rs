@@ -0,0 +1,144 @@
+//! Keep the screen awake with the [Screen Wake Lock API](https://developer.mozilla.org/en-US/docs/Web/API/Screen_Wake_Lock_API),
+//! for [`crate::Frame::set_inhibit_sleep`].
+
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen::{JsCast as _, JsValue};
+
+use super::string_from_js_value;
+
+#[derive(Default)]
+enum State {
+    /// We don't hold a wake lock.
+    #[default]
+    Released,
+
+    /// Waiting for `navigator.wakeLock.request` to resolve.
+    Pending,
+
+    /// We hold this `WakeLockSentinel`.
+    ///
+    /// The browser releases it when the page is hidden.
+    Held(JsValue),
+
+    /// Not supported by the browser, or the request was denied.
+    ///
+    /// We don't try again until the app stops and starts asking for it.
+    Failed,
+}
+
+#[derive(Default)]
+struct Inner {
+    wanted: bool,
+    state: State,
+}
+
+/// Acquires and releases a screen wake lock as needed.
+#[derive(Default)]
+pub(crate) struct WakeLock {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl Drop for WakeLock {
+    fn drop(&mut self) {
+        self.set_wanted(false);
+    }
+}
+
+impl WakeLock {
+    /// Call each frame with whether or not we want to keep the screen awake.
+    pub fn set_wanted(&self, wanted: bool) {
+        let mut inner = self.inner.borrow_mut();
+        inner.wanted = wanted;
+
+        if wanted {
+            if let State::Held(sentinel) = &inner.state {
+                if is_released(sentinel) {
+                    // The page was hidden - we need to ask again.
+                    inner.state = State::Released;
+                }
+            }
+            if matches!(inner.state, State::Released) && is_page_visible() {
+                inner.state = match request_wake_lock(&self.inner) {
+                    Ok(()) => State::Pending,
+                    Err(err) => {
+                        log::warn!("Failed to keep the screen awake: {err}");
+                        State::Failed
+                    }
+                };
+            }
+        } else {
+            match std::mem::take(&mut inner.state) {
+                State::Held(sentinel) => release(&sentinel),
+                State::Pending => {
+                    // Released when the request resolves.
+                    inner.state = State::Pending;
+                }
+                State::Released | State::Failed => {}
+            }
+        }
+    }
+}
+
+fn is_page_visible() -> bool {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .is_some_and(|document| !document.hidden())
+}
+
+fn is_released(sentinel: &JsValue) -> bool {
+    js_sys::Reflect::get(sentinel, &"released".into())
+        .ok()
+        .and_then(|released| released.as_bool())
+        .unwrap_or(true)
+}
+
+fn release(sentinel: &JsValue) {
+    if let Ok(release) = js_sys::Reflect::get(sentinel, &"release".into()) {
+        if let Some(release) = release.dyn_ref::<js_sys::Function>() {
+            release.call0(sentinel).ok();
+        }
+    }
+}
+
+/// Calls `navigator.wakeLock.request("screen")`, updating `inner` once it resolves.
+fn request_wake_lock(inner: &Rc<RefCell<Inner>>) -> Result<(), String> {
+    let navigator = web_sys::window().ok_or("No window")?.navigator();
+    let wake_lock = js_sys::Reflect::get(&navigator, &"wakeLock".into())
+        .ok()
+        .filter(|wake_lock| !wake_lock.is_undefined())
+        .ok_or("the Screen Wake Lock API is not supported by this browser")?;
+    let request: js_sys::Function = js_sys::Reflect::get(&wake_lock, &"request".into())
+        .and_then(|request| request.dyn_into())
+        .map_err(|err| string_from_js_value(&err))?;
+    let promise: js_sys::Promise = request
+        .call1(&wake_lock, &"screen".into())
+        .and_then(|promise| promise.dyn_into())
+        .map_err(|err| string_from_js_value(&err))?;
+
+    let inner = Rc::clone(inner);
+    wasm_bindgen_futures::spawn_local(async move {
+        let result = wasm_bindgen_futures::JsFuture::from(promise).await;
+        let mut inner = inner.borrow_mut();
+        inner.state = match result {
+            Ok(sentinel) => {
+                if inner.wanted {
+                    State::Held(sentinel)
+                } else {
+                    release(&sentinel);
+                    State::Released
+                }
+            }
+            Err(_) if !inner.wanted => State::Released,
+            Err(err) => {
+                log::warn!(
+                    "Failed to keep the screen awake: {}",
+                    string_from_js_value(&err)
+                );
+                State::Failed
+            }
+        };
+    });
+
+    Ok(())
+}
@@ -30,6 +30,11 @@ pub(crate) trait WebPainter {
 
     fn handle_screenshots(&mut self, events: &mut Vec<Event>);
 
+    /// Is there work (e.g. texture uploads) that will continue in the next frame?
+    fn has_pending_work(&self) -> bool {
+        false
+    }
+
     /// Destroy all resources.
     fn destroy(&mut self);
 }
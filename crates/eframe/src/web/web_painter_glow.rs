@@ -30,8 +30,9 @@ impl WebPainterGlow {
         #[allow(clippy::arc_with_non_send_sync)]
         let gl = std::sync::Arc::new(gl);
 
-        let painter = egui_glow::Painter::new(gl, shader_prefix, None, options.dithering)
+        let mut painter = egui_glow::Painter::new(gl, shader_prefix, None, options.dithering)
             .map_err(|err| format!("Error starting glow painter: {err}"))?;
+        painter.set_texture_upload_budget(options.texture_upload_budget);
 
         Ok(Self {
             canvas,
@@ -63,6 +64,7 @@ impl WebPainter for WebPainterGlow {
         for (id, image_delta) in &textures_delta.set {
             self.painter.set_texture(*id, image_delta);
         }
+        self.painter.upload_pending_textures();
 
         egui_glow::painter::clear(self.painter.gl(), canvas_dimension, clear_color);
         self.painter
@@ -84,6 +86,10 @@ impl WebPainter for WebPainterGlow {
         self.painter.destroy();
     }
 
+    fn has_pending_work(&self) -> bool {
+        self.painter.has_pending_texture_uploads()
+    }
+
     fn handle_screenshots(&mut self, events: &mut Vec<Event>) {
         for (image, data) in self.screenshots.drain(..) {
             let image = Arc::new(image);
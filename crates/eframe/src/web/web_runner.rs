@@ -1,4 +1,7 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
 use wasm_bindgen::prelude::*;
 
@@ -26,9 +29,18 @@ pub struct WebRunner {
     events_to_unsubscribe: Rc<RefCell<Vec<EventToUnsubscribe>>>,
 
     /// Current animation frame in flight.
+    ///
+    /// Shared by all runners of the same [`super::MultiRunner`].
     frame: Rc<RefCell<Option<AnimationFrameRequest>>>,
 
+    /// If we were started by a [`super::MultiRunner`], these are all the runners
+    /// that share the same [`Self::frame`] (including ourselves).
+    group: Option<Rc<RefCell<Vec<WebRunner>>>>,
+
     resize_observer: Rc<RefCell<Option<ResizeObserverContext>>>,
+
+    /// See [`crate::WebOptions::event_listener_options`].
+    event_listener_options: Rc<Cell<fn(&str) -> crate::EventListenerOptions>>,
 }
 
 impl WebRunner {
@@ -42,10 +54,34 @@ impl WebRunner {
             runner: Rc::new(RefCell::new(None)),
             events_to_unsubscribe: Rc::new(RefCell::new(Default::default())),
             frame: Default::default(),
+            group: None,
             resize_observer: Default::default(),
+            event_listener_options: Rc::new(Cell::new(crate::EventListenerOptions::default_for)),
         }
     }
 
+    /// A runner that paints in the same animation frame as the rest of the `group`.
+    pub(super) fn new_in_group(
+        panic_handler: PanicHandler,
+        frame: Rc<RefCell<Option<AnimationFrameRequest>>>,
+        group: Rc<RefCell<Vec<Self>>>,
+    ) -> Self {
+        Self {
+            panic_handler,
+            runner: Rc::new(RefCell::new(None)),
+            events_to_unsubscribe: Rc::new(RefCell::new(Default::default())),
+            frame,
+            group: Some(group),
+            resize_observer: Default::default(),
+            event_listener_options: Rc::new(Cell::new(crate::EventListenerOptions::default_for)),
+        }
+    }
+
+    /// Is this the same runner as `other`?
+    pub(super) fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.runner, &other.runner)
+    }
+
     /// Create the application, install callbacks, and start running the app.
     ///
     /// # Errors
@@ -58,31 +94,112 @@ impl WebRunner {
     ) -> Result<(), JsValue> {
         self.destroy();
 
+        self.event_listener_options
+            .set(web_options.event_listener_options);
+
         let text_agent = TextAgent::attach(self)?;
 
+        let autofocus = web_options.autofocus;
+        #[cfg(feature = "accesskit")]
+        let accesskit = web_options.accesskit;
         let runner = AppRunner::new(canvas, web_options, app_creator, text_agent).await?;
 
         {
             // Make sure the canvas can be given focus.
+            // Undone by `restore_canvas`.
             // https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/tabindex
             runner.canvas().set_tab_index(0);
 
             // Don't outline the canvas when it has focus:
             runner.canvas().style().set_property("outline", "none")?;
+
+            // Don't let the browser pan or pinch-zoom the page when touching the canvas;
+            // egui handles those gestures itself:
+            runner
+                .canvas()
+                .style()
+                .set_property("touch-action", "none")?;
         }
 
         self.runner.replace(Some(runner));
 
+        if let Some(group) = &self.group {
+            group.borrow_mut().push(self.clone());
+        }
+
         {
             events::install_event_handlers(self)?;
+            super::repaint_waker::RepaintWaker::install(self)?;
+            super::autofill::Autofill::install(self)?;
+
+            #[cfg(feature = "accesskit")]
+            if accesskit {
+                super::accessibility::AccessibilityAdapter::install(self)?;
+            }
 
             // The resize observer handles calling `request_animation_frame` to start the render loop.
             events::install_resize_observer(self)?;
         }
 
+        if autofocus {
+            self.focus();
+        }
+
         Ok(())
     }
 
+    /// Does the app have keyboard focus?
+    ///
+    /// Returns `false` if the app has panicked or been destroyed.
+    pub fn has_focus(&self) -> bool {
+        self.try_lock().map_or(false, |runner| runner.has_focus())
+    }
+
+    /// Give keyboard focus to the canvas of the app.
+    pub fn focus(&self) {
+        if let Some(mut runner) = self.try_lock() {
+            runner.canvas().focus().ok();
+            runner.update_focus();
+        }
+    }
+
+    /// Where the time of the last painted frame went.
+    ///
+    /// Returns `None` before the first frame, or if the app has panicked or been destroyed.
+    pub fn frame_timings(&self) -> Option<super::FrameTimings> {
+        self.try_lock().and_then(|runner| runner.frame_timings())
+    }
+
+    /// Print the timings of the last painted frame with `console.table`.
+    ///
+    /// Handy to call from the browser console (if you expose it with `wasm_bindgen`)
+    /// when the app feels slow.
+    pub fn log_frame_timings(&self) {
+        if let Some(timings) = self.frame_timings() {
+            timings.log_table();
+        } else {
+            log::info!("No frame has been painted yet");
+        }
+    }
+
+    /// Call `hook` after each painted frame with the [`super::FrameTimings`] of that frame,
+    /// as an object with `events`, `update`, `tessellation`, `paint` and `total`, in milliseconds.
+    ///
+    /// Use this to track down long frames without a profiler:
+    ///
+    /// ```js
+    /// runner.set_frame_timings_hook((timings) => {
+    ///     if (timings.total > 50) console.table(timings);
+    /// });
+    /// ```
+    ///
+    /// Pass `None` to remove the hook.
+    pub fn set_frame_timings_hook(&self, hook: Option<js_sys::Function>) {
+        if let Some(mut runner) = self.try_lock() {
+            runner.frame_timings_hook = hook;
+        }
+    }
+
     /// Has there been a panic?
     pub fn has_panicked(&self) -> bool {
         self.panic_handler.has_panicked()
@@ -116,16 +233,45 @@ impl WebRunner {
     }
 
     /// Shut down eframe and clean up resources.
+    ///
+    /// This removes all event listeners, cancels the pending animation frame,
+    /// removes the text agent and the other elements eframe added to the page,
+    /// and frees the GPU resources.
+    /// The app is saved and [`App::on_exit`] is called first, unless it has panicked.
+    ///
+    /// The canvas is left in the page, and you can call [`Self::start`] on it again.
     pub fn destroy(&self) {
         self.unsubscribe_from_all_events();
 
-        if let Some(frame) = self.frame.take() {
-            let window = web_sys::window().unwrap();
-            window.cancel_animation_frame(frame.id).ok();
+        // Don't cancel the animation frame if it is shared with other runners that are still alive:
+        let is_last_in_group = self.group.as_ref().map_or(true, |group| {
+            let mut group = group.borrow_mut();
+            group.retain(|runner| !runner.ptr_eq(self));
+            group.is_empty()
+        });
+
+        if is_last_in_group {
+            if let Some(frame) = self.frame.take() {
+                let window = web_sys::window().unwrap();
+                window.cancel_animation_frame(frame.id).ok();
+            }
         }
 
-        if let Some(runner) = self.runner.replace(None) {
-            runner.destroy();
+        // If we panicked while the runner was borrowed we can't get at it anymore,
+        // but at least the event listeners are gone.
+        let runner = self
+            .runner
+            .try_borrow_mut()
+            .ok()
+            .and_then(|mut runner| runner.take());
+        if let Some(runner) = runner {
+            restore_canvas(runner.canvas());
+            if self.has_panicked() {
+                // Don't call into the app again, just drop it.
+                log::debug!("Dropping the AppRunner without saving, since it has panicked");
+            } else {
+                runner.destroy();
+            }
         }
     }
 
@@ -160,6 +306,8 @@ impl WebRunner {
     ///
     /// All events added with this method will automatically be unsubscribed on panic,
     /// or when [`Self::destroy`] is called.
+    ///
+    /// The listener is registered with [`crate::WebOptions::event_listener_options`].
     pub fn add_event_listener<E: wasm_bindgen::JsCast>(
         &self,
         target: &web_sys::EventTarget,
@@ -167,6 +315,7 @@ impl WebRunner {
         mut closure: impl FnMut(E, &mut AppRunner) + 'static,
     ) -> Result<(), wasm_bindgen::JsValue> {
         let runner_ref = self.clone();
+        let is_input = super::events::is_user_input(event_name);
 
         // Create a JS closure based on the FnMut provided
         let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
@@ -174,16 +323,25 @@ impl WebRunner {
             if let Some(mut runner_lock) = runner_ref.try_lock() {
                 // Cast the event to the expected event type
                 let event = event.unchecked_into::<E>();
-                closure(event, &mut runner_lock);
+                runner_lock.time_event_handler(is_input, |runner| closure(event, runner));
             }
         }) as Box<dyn FnMut(web_sys::Event)>);
 
         // Add the event listener to the target
-        target.add_event_listener_with_callback(event_name, closure.as_ref().unchecked_ref())?;
+        let options = (self.event_listener_options.get())(event_name);
+        let js_options = web_sys::AddEventListenerOptions::new();
+        js_options.set_passive(options.passive);
+        js_options.set_capture(options.capture);
+        target.add_event_listener_with_callback_and_add_event_listener_options(
+            event_name,
+            closure.as_ref().unchecked_ref(),
+            &js_options,
+        )?;
 
         let handle = TargetEvent {
             target: target.clone(),
             event_name: event_name.to_owned(),
+            capture: options.capture,
             closure,
         };
 
@@ -214,7 +372,17 @@ impl WebRunner {
                 // This drops the `closure` and allows another
                 // animation frame to be scheduled
                 let _ = runner_ref.frame.take();
-                events::paint_and_schedule(&runner_ref)
+
+                if let Some(group) = &runner_ref.group {
+                    // Paint all the runners of the `MultiRunner` in the same frame:
+                    let runners = group.borrow().clone();
+                    for runner in &runners {
+                        events::paint_and_schedule(runner)?;
+                    }
+                    Ok(())
+                } else {
+                    events::paint_and_schedule(&runner_ref)
+                }
             }
         });
 
@@ -227,6 +395,33 @@ impl WebRunner {
         Ok(())
     }
 
+    /// The page was restored from the back/forward cache.
+    ///
+    /// The animation frame we requested before the page was frozen may never arrive,
+    /// and the size of the canvas may have changed (e.g. with the browser zoom) without us noticing,
+    /// so we start over.
+    ///
+    /// Called while the [`AppRunner`] is locked, so it is passed in.
+    pub(crate) fn on_restored_from_bfcache(&self, runner: &mut AppRunner) {
+        runner.on_restored_from_bfcache();
+
+        if let Some(frame) = self.frame.take() {
+            if let Some(window) = web_sys::window() {
+                window.cancel_animation_frame(frame.id).ok();
+            }
+        }
+
+        if let Some(context) = &*self.resize_observer.borrow() {
+            // Observing again reports the current size, and requests an animation frame:
+            context.resize_observer.unobserve(runner.canvas());
+            events::observe_canvas_size(&context.resize_observer, runner.canvas());
+        }
+
+        if let Err(err) = self.request_animation_frame() {
+            log::error!("{}", super::string_from_js_value(&err));
+        }
+    }
+
     pub(crate) fn set_resize_observer(
         &self,
         resize_observer: web_sys::ResizeObserver,
@@ -241,10 +436,18 @@ impl WebRunner {
     }
 }
 
+/// Undo the changes [`WebRunner::start`] made to the canvas, so that it can be reused.
+fn restore_canvas(canvas: &web_sys::HtmlCanvasElement) {
+    canvas.remove_attribute("tabindex").ok();
+    let style = canvas.style();
+    style.remove_property("outline").ok();
+    style.remove_property("touch-action").ok();
+}
+
 // ----------------------------------------------------------------------------
 
 // https://rustwasm.github.io/wasm-bindgen/api/wasm_bindgen/closure/struct.Closure.html#using-fnonce-and-closureonce-with-requestanimationframe
-struct AnimationFrameRequest {
+pub(super) struct AnimationFrameRequest {
     /// Represents the ID of a frame in flight.
     id: i32,
 
@@ -261,6 +464,10 @@ struct ResizeObserverContext {
 struct TargetEvent {
     target: web_sys::EventTarget,
     event_name: String,
+
+    /// Needed to remove the listener again.
+    capture: bool,
+
     closure: Closure<dyn FnMut(web_sys::Event)>,
 }
 
@@ -281,9 +488,10 @@ impl EventToUnsubscribe {
     pub fn unsubscribe(self) -> Result<(), JsValue> {
         match self {
             Self::TargetEvent(handle) => {
-                handle.target.remove_event_listener_with_callback(
+                handle.target.remove_event_listener_with_callback_and_bool(
                     handle.event_name.as_str(),
                     handle.closure.as_ref().unchecked_ref(),
+                    handle.capture,
                 )?;
                 Ok(())
             }
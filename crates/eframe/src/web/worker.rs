@@ -0,0 +1,1045 @@
+//! Run the app in a [Web Worker](https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API),
+//! so that slow frames don't freeze the page.
+//!
+//! On the page, [`WorkerHost`] hands the canvas over to the worker as an
+//! [`OffscreenCanvas`](https://developer.mozilla.org/en-US/docs/Web/API/OffscreenCanvas),
+//! and forwards the input events of the canvas to it with `postMessage`.
+//! In the worker, [`WorkerRunner`] runs the app, and paints it with WebGL.
+//!
+//! Compared to [`super::WebRunner`], a worker has no access to the DOM, so there is:
+//! * no IME, virtual keyboard, screen reader or autofill support
+//! * no persistence, since `localStorage` isn't available to workers
+//! * no `wgpu`: the `glow` renderer is always used
+//! * only one pointer: touch and pen input is forwarded as mouse input
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use egui::{ViewportCommand, ViewportId};
+use egui_glow::glow;
+use wasm_bindgen::prelude::*;
+
+use crate::{epi, WebGlContextOption};
+
+use super::{
+    button_from_mouse_event, canvas_content_rect, modifiers_from_kb_event,
+    modifiers_from_mouse_event, modifiers_from_wheel_event, now_sec, text_from_keyboard_event,
+    translate_key, NeedRepaint, PanicHandler, WebInput,
+};
+
+// ----------------------------------------------------------------------------
+// The messages between the page and the worker.
+//
+// The first message to the worker is an array of the `OffscreenCanvas` and a RON-encoded [`Init`].
+// All other messages are RON-encoded [`ToWorker`] and [`ToPage`].
+
+/// What the worker needs to know about the page to start the app.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct Init {
+    user_agent: String,
+    location: epi::Location,
+    prefers_right_to_left: bool,
+    system_theme: Option<egui::Theme>,
+
+    /// The size of the canvas when it was handed over, before the `ResizeObserver` reports it.
+    canvas_size: CanvasSize,
+}
+
+/// The size of the canvas in physical pixels.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+struct CanvasSize {
+    width: u32,
+    height: u32,
+    native_pixels_per_point: f32,
+}
+
+impl CanvasSize {
+    fn of(canvas: &web_sys::HtmlCanvasElement) -> Self {
+        let native_pixels_per_point = super::native_pixels_per_point();
+        let size = super::canvas_content_rect(canvas).size() * native_pixels_per_point;
+        Self {
+            width: size.x.round() as u32,
+            height: size.y.round() as u32,
+            native_pixels_per_point,
+        }
+    }
+
+    fn screen_rect(&self, zoom_factor: f32) -> egui::Rect {
+        let pixels_per_point = self.native_pixels_per_point * zoom_factor;
+        let screen_size = egui::vec2(self.width as f32, self.height as f32) / pixels_per_point;
+        egui::Rect::from_min_size(egui::Pos2::ZERO, screen_size)
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+enum ToWorker {
+    /// The size of the canvas changed.
+    Resize(CanvasSize),
+
+    /// The canvas gained or lost keyboard focus.
+    Focus(bool),
+
+    SystemTheme(Option<egui::Theme>),
+
+    /// Input events, with positions in CSS pixels relative to the canvas.
+    Events(Vec<egui::Event>),
+}
+
+/// Sent after each frame.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct ToPage {
+    cursor_icon: egui::CursorIcon,
+
+    /// Should the page leave the mouse wheel to us, instead of scrolling?
+    wants_pointer_input: bool,
+
+    /// Should the page leave key presses to us?
+    wants_keyboard_input: bool,
+
+    commands: Vec<PageCommand>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+enum PageCommand {
+    CopyText(String),
+    OpenUrl(egui::OpenUrl),
+    Focus,
+}
+
+fn encode(message: &impl serde::Serialize) -> Option<JsValue> {
+    match ron::to_string(message) {
+        Ok(message) => Some(JsValue::from(message)),
+        Err(err) => {
+            log::error!("Failed to encode message: {err}");
+            None
+        }
+    }
+}
+
+fn decode<T: serde::de::DeserializeOwned>(message: &JsValue) -> Option<T> {
+    let Some(message) = message.as_string() else {
+        log::error!(
+            "Unexpected message: {}",
+            super::string_from_js_value(message)
+        );
+        return None;
+    };
+    match ron::from_str(&message) {
+        Ok(message) => Some(message),
+        Err(err) => {
+            log::error!("Failed to decode message: {err}");
+            None
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Runs on the page, and hands a canvas over to a [`WorkerRunner`] running in a Web Worker.
+///
+/// The worker is started by you, and must call [`WorkerRunner::start`].
+/// The canvas can't be painted to by the page anymore afterwards.
+///
+/// Dropping the host terminates the worker.
+///
+/// ``` no_run
+/// # fn start(canvas: web_sys::HtmlCanvasElement) -> Result<eframe::web::WorkerHost, wasm_bindgen::JsValue> {
+/// let worker = web_sys::Worker::new("./worker.js")?;
+/// let host = eframe::web::WorkerHost::start(&canvas, worker)?;
+/// # Ok(host) }
+/// ```
+pub struct WorkerHost {
+    worker: web_sys::Worker,
+    listeners: Vec<Listener>,
+    _on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+    resize_observer: web_sys::ResizeObserver,
+    _on_resize: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+struct Listener {
+    target: web_sys::EventTarget,
+    event_name: &'static str,
+    closure: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+/// What the page knows about the app running in the worker.
+#[derive(Default)]
+struct HostState {
+    wants_pointer_input: Cell<bool>,
+    wants_keyboard_input: Cell<bool>,
+
+    /// The keys we have sent a press for, but no release.
+    keys_down: RefCell<Vec<egui::Key>>,
+
+    /// Is the ctrl key really down? See the `wheel` handler.
+    ctrl_down: Cell<bool>,
+}
+
+impl WorkerHost {
+    /// Transfer the canvas to the `worker`, and start forwarding input to it.
+    pub fn start(
+        canvas: &web_sys::HtmlCanvasElement,
+        worker: web_sys::Worker,
+    ) -> Result<Self, JsValue> {
+        // Make the canvas focusable, so that we get keyboard events:
+        canvas.set_attribute("tabindex", "0")?;
+        // Don't let the browser pan or zoom the page when dragging on the canvas:
+        canvas.style().set_property("touch-action", "none")?;
+
+        let offscreen = canvas.transfer_control_to_offscreen()?;
+        let init = Init {
+            user_agent: super::user_agent().unwrap_or_default(),
+            location: super::web_location(),
+            prefers_right_to_left: crate::locale::prefers_right_to_left(),
+            system_theme: super::system_theme(),
+            canvas_size: CanvasSize::of(canvas),
+        };
+        let message = js_sys::Array::of2(&offscreen, &encode(&init).unwrap_or_default());
+        worker.post_message_with_transfer(&message, &js_sys::Array::of1(&offscreen))?;
+
+        let state = Rc::new(HostState::default());
+
+        let on_message = Closure::<dyn FnMut(_)>::new({
+            let canvas = canvas.clone();
+            let state = state.clone();
+            move |event: web_sys::MessageEvent| {
+                if let Some(message) = decode::<ToPage>(&event.data()) {
+                    handle_to_page(&canvas, &state, message);
+                }
+            }
+        });
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        let on_resize = Closure::<dyn FnMut(_)>::new({
+            let worker = worker.clone();
+            move |entries: js_sys::Array| match super::events::get_display_size(&entries) {
+                Ok((width, height)) => post(
+                    &worker,
+                    &ToWorker::Resize(CanvasSize {
+                        width,
+                        height,
+                        native_pixels_per_point: super::native_pixels_per_point(),
+                    }),
+                ),
+                Err(err) => log::error!("{}", super::string_from_js_value(&err)),
+            }
+        });
+        let resize_observer = web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref())?;
+        super::events::observe_canvas_size(&resize_observer, canvas);
+
+        let mut host = Self {
+            worker,
+            listeners: Vec::new(),
+            _on_message: on_message,
+            resize_observer,
+            _on_resize: on_resize,
+        };
+        host.install_event_handlers(canvas, &state)?;
+
+        Ok(host)
+    }
+
+    fn install_event_handlers(
+        &mut self,
+        canvas: &web_sys::HtmlCanvasElement,
+        state: &Rc<HostState>,
+    ) -> Result<(), JsValue> {
+        let target: &web_sys::EventTarget = canvas;
+
+        self.add_event_listener(target, "focus", |_: web_sys::FocusEvent, worker| {
+            post(worker, &ToWorker::Focus(true));
+        })?;
+
+        self.add_event_listener(target, "blur", |_: web_sys::FocusEvent, worker| {
+            post(worker, &ToWorker::Focus(false));
+        })?;
+
+        self.add_event_listener(target, "pointerdown", {
+            let canvas = canvas.clone();
+            move |event: web_sys::PointerEvent, worker| {
+                if let Some(button) = button_from_mouse_event(&event) {
+                    // Keep getting the pointer events while dragging outside the canvas:
+                    canvas.set_pointer_capture(event.pointer_id()).ok();
+                    canvas.focus().ok();
+                    post_event(
+                        worker,
+                        egui::Event::PointerButton {
+                            pos: pos_in_canvas(&canvas, &event),
+                            button,
+                            pressed: true,
+                            modifiers: modifiers_from_mouse_event(&event),
+                        },
+                    );
+                }
+            }
+        })?;
+
+        self.add_event_listener(target, "pointerup", {
+            let canvas = canvas.clone();
+            move |event: web_sys::PointerEvent, worker| {
+                if let Some(button) = button_from_mouse_event(&event) {
+                    post_event(
+                        worker,
+                        egui::Event::PointerButton {
+                            pos: pos_in_canvas(&canvas, &event),
+                            button,
+                            pressed: false,
+                            modifiers: modifiers_from_mouse_event(&event),
+                        },
+                    );
+                    event.prevent_default();
+                }
+            }
+        })?;
+
+        self.add_event_listener(target, "pointermove", {
+            let canvas = canvas.clone();
+            move |event: web_sys::PointerEvent, worker| {
+                post_event(
+                    worker,
+                    egui::Event::PointerMoved(pos_in_canvas(&canvas, &event)),
+                );
+            }
+        })?;
+
+        self.add_event_listener(
+            target,
+            "pointerleave",
+            |_: web_sys::PointerEvent, worker| {
+                post_event(worker, egui::Event::PointerGone);
+            },
+        )?;
+
+        self.add_event_listener(target, "wheel", {
+            let state = state.clone();
+            move |event: web_sys::WheelEvent, worker| {
+                let unit = match event.delta_mode() {
+                    web_sys::WheelEvent::DOM_DELTA_PIXEL => egui::MouseWheelUnit::Point,
+                    web_sys::WheelEvent::DOM_DELTA_LINE => egui::MouseWheelUnit::Line,
+                    web_sys::WheelEvent::DOM_DELTA_PAGE => egui::MouseWheelUnit::Page,
+                    _ => return,
+                };
+                let delta = -egui::vec2(event.delta_x() as f32, event.delta_y() as f32);
+                let modifiers = modifiers_from_wheel_event(&event);
+
+                let egui_event = if modifiers.ctrl && !state.ctrl_down.get() {
+                    // Pinch-to-zoom on a Mac trackpad, see `events::install_wheel`.
+                    let pinch_to_zoom_sensitivity = 0.01;
+                    egui::Event::Zoom((pinch_to_zoom_sensitivity * delta.y).exp())
+                } else {
+                    egui::Event::MouseWheel {
+                        unit,
+                        delta,
+                        modifiers,
+                    }
+                };
+                post_event(worker, egui_event);
+
+                // Let the page scroll if there is nothing in egui to scroll.
+                // We only know what was true at the last frame, but that is good enough.
+                if state.wants_pointer_input.get() {
+                    event.prevent_default();
+                }
+            }
+        })?;
+
+        self.add_event_listener(target, "keydown", {
+            let state = state.clone();
+            move |event: web_sys::KeyboardEvent, worker| {
+                if event.is_composing() || event.key_code() == 229 {
+                    return; // IME, which we don't support in workers.
+                }
+
+                let modifiers = modifiers_from_kb_event(&event);
+                state.ctrl_down.set(modifiers.ctrl);
+                let mut events = vec![];
+
+                if !modifiers.ctrl && !modifiers.command {
+                    if let Some(text) = text_from_keyboard_event(&event) {
+                        events.push(egui::Event::Text(text));
+                        event.prevent_default();
+                    }
+                }
+
+                if let Some(key) = translate_key(&event.key()) {
+                    state.keys_down.borrow_mut().push(key);
+                    events.push(egui::Event::Key {
+                        key,
+                        physical_key: None,
+                        logical_key: Some(key),
+                        pressed: true,
+                        repeat: false, // egui will fill this in for us!
+                        modifiers,
+                    });
+
+                    // Leave copy, cut and paste to the browser, so that we get the clipboard events:
+                    let is_clipboard_shortcut = modifiers.command
+                        && matches!(key, egui::Key::C | egui::Key::X | egui::Key::V);
+                    if (state.wants_keyboard_input.get() && !is_clipboard_shortcut)
+                        || key == egui::Key::Tab
+                    {
+                        event.prevent_default();
+                    }
+                }
+
+                if !events.is_empty() {
+                    post(worker, &ToWorker::Events(events));
+                }
+            }
+        })?;
+
+        self.add_event_listener(target, "keyup", {
+            let state = state.clone();
+            move |event: web_sys::KeyboardEvent, worker| {
+                let modifiers = modifiers_from_kb_event(&event);
+                state.ctrl_down.set(modifiers.ctrl);
+                let mut keys_down = state.keys_down.borrow_mut();
+
+                let released = if event.key() == "Meta" || event.key() == "Control" {
+                    // Chromium doesn't fire a `keyup` for the letter key of e.g. Cmd+A,
+                    // see `events::on_keyup`.
+                    std::mem::take(&mut *keys_down)
+                } else if let Some(key) = translate_key(&event.key()) {
+                    keys_down.retain(|&down| down != key);
+                    vec![key]
+                } else {
+                    vec![]
+                };
+
+                if !released.is_empty() {
+                    let events = released
+                        .into_iter()
+                        .map(|key| egui::Event::Key {
+                            key,
+                            physical_key: None,
+                            logical_key: Some(key),
+                            pressed: false,
+                            repeat: false,
+                            modifiers,
+                        })
+                        .collect();
+                    post(worker, &ToWorker::Events(events));
+                }
+            }
+        })?;
+
+        self.add_event_listener(target, "paste", |event: web_sys::ClipboardEvent, worker| {
+            if let Some(text) = event
+                .clipboard_data()
+                .and_then(|data| data.get_data("text").ok())
+            {
+                let text = text.replace("\r\n", "\n");
+                if !text.is_empty() {
+                    post_event(worker, egui::Event::Paste(text));
+                }
+                event.prevent_default();
+            }
+        })?;
+
+        // The copied text arrives with the next `ToPage`, outside of the event handler.
+        // This works in Chromium and Firefox, but not in Safari.
+        self.add_event_listener(target, "copy", |event: web_sys::ClipboardEvent, worker| {
+            post_event(worker, egui::Event::Copy);
+            event.prevent_default();
+        })?;
+
+        self.add_event_listener(target, "cut", |event: web_sys::ClipboardEvent, worker| {
+            post_event(worker, egui::Event::Cut);
+            event.prevent_default();
+        })?;
+
+        if let Some(window) = web_sys::window() {
+            if let Ok(Some(media_query_list)) = super::prefers_color_scheme_dark(&window) {
+                self.add_event_listener(
+                    &media_query_list,
+                    "change",
+                    |event: web_sys::MediaQueryListEvent, worker| {
+                        let theme = super::theme_from_dark_mode(event.matches());
+                        post(worker, &ToWorker::SystemTheme(Some(theme)));
+                    },
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_event_listener<E: JsCast>(
+        &mut self,
+        target: &web_sys::EventTarget,
+        event_name: &'static str,
+        mut handler: impl FnMut(E, &web_sys::Worker) + 'static,
+    ) -> Result<(), JsValue> {
+        let worker = self.worker.clone();
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::Event| {
+            handler(event.unchecked_into::<E>(), &worker);
+        });
+
+        // Not passive, so that we can prevent the default action:
+        let options = web_sys::AddEventListenerOptions::new();
+        options.set_passive(false);
+        target.add_event_listener_with_callback_and_add_event_listener_options(
+            event_name,
+            closure.as_ref().unchecked_ref(),
+            &options,
+        )?;
+
+        self.listeners.push(Listener {
+            target: target.clone(),
+            event_name,
+            closure,
+        });
+        Ok(())
+    }
+}
+
+impl Drop for WorkerHost {
+    fn drop(&mut self) {
+        for listener in self.listeners.drain(..) {
+            listener
+                .target
+                .remove_event_listener_with_callback(
+                    listener.event_name,
+                    listener.closure.as_ref().unchecked_ref(),
+                )
+                .ok();
+        }
+        self.resize_observer.disconnect();
+        self.worker.set_onmessage(None);
+        self.worker.terminate();
+    }
+}
+
+fn post(worker: &web_sys::Worker, message: &ToWorker) {
+    if let Some(message) = encode(message) {
+        if let Err(err) = worker.post_message(&message) {
+            log::error!(
+                "Failed to post to the worker: {}",
+                super::string_from_js_value(&err)
+            );
+        }
+    }
+}
+
+fn post_event(worker: &web_sys::Worker, event: egui::Event) {
+    post(worker, &ToWorker::Events(vec![event]));
+}
+
+/// In CSS pixels, since we don't know the zoom factor of egui.
+fn pos_in_canvas(canvas: &web_sys::HtmlCanvasElement, event: &web_sys::MouseEvent) -> egui::Pos2 {
+    let rect = canvas_content_rect(canvas);
+    egui::pos2(
+        event.client_x() as f32 - rect.left(),
+        event.client_y() as f32 - rect.top(),
+    )
+}
+
+fn handle_to_page(canvas: &web_sys::HtmlCanvasElement, state: &HostState, message: ToPage) {
+    let ToPage {
+        cursor_icon,
+        wants_pointer_input,
+        wants_keyboard_input,
+        commands,
+    } = message;
+
+    super::set_cursor_icon(cursor_icon);
+    state.wants_pointer_input.set(wants_pointer_input);
+    state.wants_keyboard_input.set(wants_keyboard_input);
+
+    for command in commands {
+        match command {
+            PageCommand::CopyText(text) => super::set_clipboard_text(&text),
+            PageCommand::OpenUrl(open_url) => {
+                super::open_url(&open_url.url, open_url.new_tab);
+            }
+            PageCommand::Focus => {
+                canvas.focus().ok();
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Runs the app in a Web Worker, painting to the canvas given to a [`WorkerHost`] on the page.
+///
+/// This is cheap to clone.
+///
+/// ``` no_run
+/// # struct MyApp;
+/// # impl eframe::App for MyApp { fn update(&mut self, _: &egui::Context, _: &mut eframe::Frame) {} }
+/// // Called by the script of the worker:
+/// async fn start_worker() -> Result<(), wasm_bindgen::JsValue> {
+///     eframe::web::WorkerRunner::new()
+///         .start(
+///             eframe::WebOptions::default(),
+///             Box::new(|_cc| Ok(Box::new(MyApp))),
+///         )
+///         .await
+/// }
+/// ```
+#[derive(Clone)]
+pub struct WorkerRunner {
+    panic_handler: PanicHandler,
+    state: Rc<RefCell<WorkerState>>,
+}
+
+#[derive(Default)]
+struct WorkerState {
+    input: WebInput,
+
+    /// `None` until we get the [`Init`] message. We don't paint before then.
+    canvas_size: Option<CanvasSize>,
+
+    /// Resolved with the first message, which carries the canvas.
+    on_init: Option<js_sys::Function>,
+
+    app: Option<WorkerApp>,
+    animation_frame: Option<Closure<dyn FnMut()>>,
+}
+
+impl WorkerRunner {
+    /// Will install a panic handler that will catch and log any panics
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            panic_handler: PanicHandler::install(),
+            state: Default::default(),
+        }
+    }
+
+    /// Has there been a panic?
+    pub fn has_panicked(&self) -> bool {
+        self.panic_handler.has_panicked()
+    }
+
+    /// Wait for the canvas from the [`WorkerHost`], then create the app and start running it.
+    ///
+    /// Must be called from within the worker.
+    pub async fn start(
+        &self,
+        web_options: crate::WebOptions,
+        app_creator: epi::AppCreator<'static>,
+    ) -> Result<(), JsValue> {
+        let scope: web_sys::DedicatedWorkerGlobalScope = js_sys::global().dyn_into()?;
+
+        let init = js_sys::Promise::new(&mut |resolve, _reject| {
+            self.state.borrow_mut().on_init = Some(resolve);
+        });
+
+        let on_message = Closure::<dyn FnMut(_)>::new({
+            let runner = self.clone();
+            move |event: web_sys::MessageEvent| runner.on_message(&event.data())
+        });
+        scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        on_message.forget(); // The worker runs until it is terminated.
+
+        let init: js_sys::Array = wasm_bindgen_futures::JsFuture::from(init)
+            .await?
+            .dyn_into()?;
+        let canvas: web_sys::OffscreenCanvas = init.get(0).dyn_into()?;
+        let init: Init = decode(&init.get(1)).ok_or("Bad init message")?;
+        self.state.borrow_mut().canvas_size = Some(init.canvas_size);
+
+        let app = WorkerApp::new(
+            canvas,
+            init,
+            &web_options,
+            app_creator,
+            &self.state.borrow(),
+        )?;
+        self.state.borrow_mut().app = Some(app);
+
+        let animation_frame = Closure::<dyn FnMut()>::new({
+            let runner = self.clone();
+            move || runner.on_animation_frame()
+        });
+        scope.request_animation_frame(animation_frame.as_ref().unchecked_ref())?;
+        self.state.borrow_mut().animation_frame = Some(animation_frame);
+
+        Ok(())
+    }
+
+    fn on_message(&self, data: &JsValue) {
+        if self.has_panicked() {
+            return;
+        }
+        let mut state = self.state.borrow_mut();
+
+        if let Some(on_init) = state.on_init.take() {
+            on_init.call1(&JsValue::NULL, data).ok();
+            return;
+        }
+
+        if let Some(message) = decode::<ToWorker>(data) {
+            state.handle_message(message);
+        }
+    }
+
+    fn on_animation_frame(&self) {
+        if self.has_panicked() {
+            return;
+        }
+        let mut state = self.state.borrow_mut();
+        let WorkerState {
+            input,
+            canvas_size,
+            app,
+            animation_frame,
+            ..
+        } = &mut *state;
+
+        if let (Some(app), Some(canvas_size)) = (app, *canvas_size) {
+            if app.needs_repaint.needs_repaint() {
+                // Clear the `needs_repaint` flags _before_
+                // running the logic, as the logic could cause it to be set again.
+                app.needs_repaint.clear();
+                let to_page = app.run_and_paint(input, canvas_size);
+                if let Some(message) = encode(&to_page) {
+                    let scope: web_sys::DedicatedWorkerGlobalScope =
+                        js_sys::global().unchecked_into();
+                    scope.post_message(&message).ok();
+                }
+            }
+        }
+
+        if let Some(animation_frame) = animation_frame {
+            let scope: web_sys::DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+            if let Err(err) =
+                scope.request_animation_frame(animation_frame.as_ref().unchecked_ref())
+            {
+                log::error!("{}", super::string_from_js_value(&err));
+            }
+        }
+    }
+}
+
+impl WorkerState {
+    fn handle_message(&mut self, message: ToWorker) {
+        match message {
+            ToWorker::Resize(canvas_size) => {
+                self.canvas_size = Some(canvas_size);
+                if let Some(app) = &self.app {
+                    app.canvas.set_width(canvas_size.width);
+                    app.canvas.set_height(canvas_size.height);
+                }
+            }
+            ToWorker::Focus(focused) => {
+                self.input.set_focus(focused);
+            }
+            ToWorker::SystemTheme(theme) => {
+                self.input.raw.system_theme = theme;
+                self.input
+                    .raw
+                    .events
+                    .extend(theme.map(egui::Event::SystemThemeChanged));
+            }
+            ToWorker::Events(events) => {
+                for event in &events {
+                    if let egui::Event::PointerButton { modifiers, .. }
+                    | egui::Event::Key { modifiers, .. }
+                    | egui::Event::MouseWheel { modifiers, .. } = event
+                    {
+                        self.input.raw.modifiers = *modifiers;
+                    }
+                }
+                self.input.raw.events.extend(events);
+            }
+        }
+
+        if let Some(app) = &self.app {
+            app.needs_repaint.repaint_asap();
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+struct WorkerApp {
+    egui_ctx: egui::Context,
+    canvas: web_sys::OffscreenCanvas,
+    painter: egui_glow::Painter,
+    app: Box<dyn epi::App>,
+    frame: epi::Frame,
+    needs_repaint: std::sync::Arc<NeedRepaint>,
+}
+
+impl WorkerApp {
+    fn new(
+        canvas: web_sys::OffscreenCanvas,
+        init: Init,
+        web_options: &crate::WebOptions,
+        app_creator: epi::AppCreator<'static>,
+        state: &WorkerState,
+    ) -> Result<Self, String> {
+        if let Some(canvas_size) = state.canvas_size {
+            canvas.set_width(canvas_size.width);
+            canvas.set_height(canvas_size.height);
+        }
+
+        let gl = init_glow_context(&canvas, web_options.webgl_context_option)?;
+        #[allow(clippy::arc_with_non_send_sync)]
+        let gl = std::sync::Arc::new(gl);
+        let mut painter = egui_glow::Painter::new(gl, "", None, web_options.dithering)
+            .map_err(|err| format!("Error starting glow painter: {err}"))?;
+        painter.set_texture_upload_budget(web_options.texture_upload_budget);
+
+        let Init {
+            user_agent,
+            location,
+            prefers_right_to_left,
+            system_theme: _, // Already in `state.input`
+            canvas_size: _,  // Already in `state`
+        } = init;
+
+        let egui_ctx = egui::Context::default();
+        egui_ctx.set_os(egui::os::OperatingSystem::from_user_agent(&user_agent));
+        egui_ctx.options_mut(|o| {
+            // Like `AppRunner`, follow the zoom factor of the browser:
+            o.zoom_with_keyboard = false;
+            o.zoom_factor = 1.0;
+        });
+        web_options
+            .layout_direction
+            .apply(&egui_ctx, prefers_right_to_left);
+
+        let info = epi::IntegrationInfo {
+            web_info: epi::WebInfo {
+                user_agent,
+                location,
+                capabilities: Default::default(),
+                navigation_events: Default::default(),
+            },
+            cpu_usage: None,
+            prefers_right_to_left,
+        };
+
+        let cc = epi::CreationContext {
+            egui_ctx: egui_ctx.clone(),
+            integration_info: info.clone(),
+            storage: None,
+            gl: Some(painter.gl().clone()),
+            get_proc_address: None,
+            #[cfg(feature = "wgpu")]
+            wgpu_render_state: None,
+        };
+        let app = app_creator(&cc).map_err(|err| err.to_string())?;
+
+        let frame = epi::Frame {
+            info,
+            storage: None,
+            gl: Some(painter.gl().clone()),
+            #[cfg(feature = "wgpu")]
+            wgpu_render_state: None,
+            inhibit_sleep: epi::InhibitSleep::Never,
+            repaint_mode: epi::RepaintMode::Reactive,
+            unsaved_changes: false,
+            screenshot_requests: Vec::new(),
+            recordings: Default::default(),
+            fullscreen_request: None,
+        };
+
+        let needs_repaint: std::sync::Arc<NeedRepaint> = Default::default();
+        {
+            let needs_repaint = needs_repaint.clone();
+            egui_ctx.set_request_repaint_callback(move |info| {
+                needs_repaint.repaint_after(info.delay.as_secs_f64());
+            });
+        }
+
+        Ok(Self {
+            egui_ctx,
+            canvas,
+            painter,
+            app,
+            frame,
+            needs_repaint,
+        })
+    }
+
+    fn run_and_paint(&mut self, input: &mut WebInput, canvas_size: CanvasSize) -> ToPage {
+        input.raw.max_texture_side = Some(self.painter.max_texture_side());
+
+        let zoom_factor = self.egui_ctx.zoom_factor();
+        let native_pixels_per_point = canvas_size.native_pixels_per_point;
+        let mut raw_input = egui::RawInput {
+            screen_rect: Some(canvas_size.screen_rect(zoom_factor)),
+            time: Some(now_sec()),
+            ..input.raw.take()
+        };
+        let viewport = raw_input.viewports.entry(ViewportId::ROOT).or_default();
+        viewport.native_pixels_per_point = Some(native_pixels_per_point);
+        viewport.focused = Some(raw_input.focused);
+        for event in &mut raw_input.events {
+            css_pixels_to_points(event, zoom_factor);
+        }
+
+        epi::deliver_system_theme_changes(self.app.as_mut(), &self.egui_ctx, &raw_input.events);
+        self.app.raw_input_hook(&self.egui_ctx, &mut raw_input);
+
+        let full_output = self.egui_ctx.run(raw_input, |egui_ctx| {
+            self.app.update(egui_ctx, &mut self.frame);
+            self.frame.send_viewport_commands(egui_ctx);
+        });
+        let egui::FullOutput {
+            platform_output,
+            textures_delta,
+            shapes,
+            pixels_per_point,
+            viewport_output,
+        } = full_output;
+
+        let mut commands = vec![];
+
+        for (_viewport_id, viewport_output) in viewport_output {
+            for command in viewport_output.commands {
+                match command {
+                    ViewportCommand::Focus => commands.push(PageCommand::Focus),
+                    ViewportCommand::User(user_data) => {
+                        input
+                            .raw
+                            .viewports
+                            .entry(ViewportId::ROOT)
+                            .or_default()
+                            .user_commands
+                            .push(user_data);
+                        self.needs_repaint.repaint_asap();
+                    }
+                    _ => {
+                        log::warn!(
+                            "Unhandled egui viewport command: {command:?} - not implemented in web workers"
+                        );
+                    }
+                }
+            }
+        }
+
+        #[allow(deprecated)]
+        let egui::PlatformOutput {
+            commands: output_commands,
+            cursor_icon,
+            open_url,
+            copied_text,
+            ..
+        } = platform_output;
+
+        for command in output_commands {
+            match command {
+                egui::OutputCommand::CopyText(text) => commands.push(PageCommand::CopyText(text)),
+                egui::OutputCommand::OpenUrl(open_url) => {
+                    commands.push(PageCommand::OpenUrl(open_url));
+                }
+                egui::OutputCommand::CopyImage(_)
+                | egui::OutputCommand::SetPrimarySelection(_)
+                | egui::OutputCommand::SubmitAutofill => {}
+            }
+        }
+        commands.extend(open_url.map(PageCommand::OpenUrl));
+        if !copied_text.is_empty() {
+            commands.push(PageCommand::CopyText(copied_text));
+        }
+
+        let clipped_primitives = self.egui_ctx.tessellate(shapes, pixels_per_point);
+        let size = [canvas_size.width, canvas_size.height];
+        self.paint(&clipped_primitives, pixels_per_point, &textures_delta, size);
+
+        ToPage {
+            cursor_icon,
+            wants_pointer_input: self.egui_ctx.is_pointer_over_area()
+                || self.egui_ctx.is_using_pointer(),
+            wants_keyboard_input: self.egui_ctx.wants_keyboard_input(),
+            commands,
+        }
+    }
+
+    fn paint(
+        &mut self,
+        clipped_primitives: &[egui::ClippedPrimitive],
+        pixels_per_point: f32,
+        textures_delta: &egui::TexturesDelta,
+        size: [u32; 2],
+    ) {
+        for (id, image_delta) in &textures_delta.set {
+            self.painter.set_texture(*id, image_delta);
+        }
+        self.painter.upload_pending_textures();
+
+        let clear_color = self.app.clear_color(&self.egui_ctx.style().visuals);
+        egui_glow::painter::clear(self.painter.gl(), size, clear_color);
+        self.painter
+            .paint_primitives(size, pixels_per_point, clipped_primitives);
+
+        for &id in &textures_delta.free {
+            self.painter.free_texture(id);
+        }
+
+        if self.painter.has_pending_texture_uploads() {
+            self.needs_repaint.repaint_asap();
+        }
+    }
+}
+
+/// The page sends positions in CSS pixels, since it doesn't know the zoom factor of egui.
+fn css_pixels_to_points(event: &mut egui::Event, zoom_factor: f32) {
+    match event {
+        egui::Event::PointerMoved(pos) | egui::Event::PointerButton { pos, .. } => {
+            *pos = *pos / zoom_factor;
+        }
+        _ => {}
+    }
+}
+
+fn init_glow_context(
+    canvas: &web_sys::OffscreenCanvas,
+    options: WebGlContextOption,
+) -> Result<glow::Context, String> {
+    let webgl1 = || {
+        let context = canvas.get_context("webgl").ok()??;
+        log::debug!("WebGL1 selected.");
+        Some(glow::Context::from_webgl1_context(context.unchecked_into()))
+    };
+    let webgl2 = || {
+        let context = canvas.get_context("webgl2").ok()??;
+        log::debug!("WebGL2 selected.");
+        Some(glow::Context::from_webgl2_context(context.unchecked_into()))
+    };
+
+    let gl = match options {
+        WebGlContextOption::WebGl1 => webgl1(),
+        WebGlContextOption::WebGl2 => webgl2(),
+        WebGlContextOption::BestFirst => webgl2().or_else(webgl1),
+        WebGlContextOption::CompatibilityFirst => webgl1().or_else(webgl2),
+    };
+    gl.ok_or_else(|| "WebGL isn't supported in this worker".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_before_resize() {
+        // An animation frame can come before the page has told us the size of the canvas,
+        // and `run_and_paint` would then divide by zero pixels per point:
+        let mut state = WorkerState::default();
+        assert_eq!(state.canvas_size, None);
+
+        let canvas_size = CanvasSize {
+            width: 800,
+            height: 600,
+            native_pixels_per_point: 2.0,
+        };
+        state.handle_message(ToWorker::Resize(canvas_size));
+        assert_eq!(state.canvas_size, Some(canvas_size));
+        assert_eq!(
+            canvas_size.screen_rect(1.0),
+            egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(400.0, 300.0))
+        );
+        assert_eq!(
+            canvas_size.screen_rect(2.0),
+            egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(200.0, 150.0))
+        );
+    }
+}
@@ -9,9 +9,10 @@ struct VertexOutput {
 struct Locals {
     screen_size: vec2<f32>,
     dithering: u32, // 1 if dithering is enabled, 0 otherwise
-    // Uniform buffers need to be at least 16 bytes in WebGL.
+    // Multiplier for linear output, used to make white as bright as the SDR white on HDR targets.
+    // Also pads this to 16 bytes, the minimum for uniform buffers in WebGL.
     // See https://github.com/gfx-rs/wgpu/issues/2072
-    _padding: u32,
+    sdr_white_level: f32,
 };
 @group(0) @binding(0) var<uniform> r_locals: Locals;
 
@@ -110,7 +111,8 @@ fn fs_main_linear_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
         out_color_gamma = vec4<f32>(out_color_gamma_rgb, out_color_gamma.a);
     }
     let out_color_linear = linear_from_gamma_rgb(out_color_gamma.rgb);
-    return vec4<f32>(out_color_linear, out_color_gamma.a);
+    // Premultiplied alpha, so scaling the color is all it takes:
+    return vec4<f32>(out_color_linear * r_locals.sdr_white_level, out_color_gamma.a);
 }
 
 @fragment
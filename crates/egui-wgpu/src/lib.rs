@@ -82,6 +82,11 @@ pub struct RenderState {
     /// The target texture format used for presenting to the window.
     pub target_format: wgpu::TextureFormat,
 
+    /// The MSAA sample count used for rendering.
+    ///
+    /// This is the requested count, lowered to the closest one supported by the adapter.
+    pub msaa_samples: u32,
+
     /// Egui renderer responsible for drawing the UI.
     pub renderer: Arc<RwLock<Renderer>>,
 }
@@ -242,15 +247,27 @@ impl RenderState {
                 |s| s.get_capabilities(&adapter).formats,
             )
         };
-        let target_format = crate::preferred_framebuffer_format(&surface_formats)?;
+        let target_format = if config.hdr.is_some() {
+            crate::preferred_hdr_framebuffer_format(&surface_formats)?
+        } else {
+            crate::preferred_framebuffer_format(&surface_formats)?
+        };
 
-        let renderer = Renderer::new(
+        let msaa_samples =
+            supported_msaa_samples(&adapter, target_format, depth_format, msaa_samples);
+
+        let mut renderer = Renderer::new(
             &device,
             target_format,
             depth_format,
             msaa_samples,
             dithering,
         );
+        if let Some(hdr) = &config.hdr {
+            if is_hdr_format(target_format) {
+                renderer.set_sdr_white_level(hdr.sdr_white_level);
+            }
+        }
 
         // On wasm, depending on feature flags, wgpu objects may or may not implement sync.
         // It doesn't make sense to switch to Rc for that special usecase, so simply disable the lint.
@@ -262,6 +279,7 @@ impl RenderState {
             device,
             queue,
             target_format,
+            msaa_samples,
             renderer: Arc::new(RwLock::new(renderer)),
         })
     }
@@ -282,6 +300,37 @@ fn describe_adapters(adapters: &[Arc<wgpu::Adapter>]) -> String {
     }
 }
 
+/// The highest sample count not above `requested` that the adapter supports for both formats.
+fn supported_msaa_samples(
+    adapter: &wgpu::Adapter,
+    target_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    requested: u32,
+) -> u32 {
+    let is_supported = |format: wgpu::TextureFormat, count: u32| {
+        adapter
+            .get_texture_format_features(format)
+            .flags
+            .sample_count_supported(count)
+    };
+
+    let supported = [16, 8, 4, 2]
+        .into_iter()
+        .filter(|&count| count <= requested)
+        .find(|&count| {
+            is_supported(target_format, count)
+                && depth_format.map_or(true, |depth_format| is_supported(depth_format, count))
+        })
+        .unwrap_or(1);
+
+    if supported != requested.max(1) {
+        log::warn!(
+            "MSAA sample count {requested} is not supported for {target_format:?}; using {supported}"
+        );
+    }
+    supported
+}
+
 /// Specifies which action should be taken as consequence of a [`wgpu::SurfaceError`]
 pub enum SurfaceErrorAction {
     /// Do nothing and skip the current frame.
@@ -311,6 +360,33 @@ pub struct WgpuConfiguration {
 
     /// Callback for surface errors.
     pub on_surface_error: Arc<dyn Fn(wgpu::SurfaceError) -> SurfaceErrorAction + Send + Sync>,
+
+    /// Render to an HDR surface, if the surface supports one.
+    ///
+    /// `None` = render to an 8-bit SDR surface (default).
+    pub hdr: Option<HdrOptions>,
+}
+
+/// Options for rendering egui to an HDR surface.
+///
+/// See [`WgpuConfiguration::hdr`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HdrOptions {
+    /// How bright egui's white should be, relative to `1.0` in the surface.
+    ///
+    /// For scRGB ([`wgpu::TextureFormat::Rgba16Float`]) `1.0` is 80 nits,
+    /// so `2.5` gives the common SDR white level of 200 nits.
+    ///
+    /// See [`Renderer::set_sdr_white_level`].
+    pub sdr_white_level: f32,
+}
+
+impl Default for HdrOptions {
+    fn default() -> Self {
+        Self {
+            sdr_white_level: 1.0,
+        }
+    }
 }
 
 #[test]
@@ -326,6 +402,7 @@ impl std::fmt::Debug for WgpuConfiguration {
             desired_maximum_frame_latency,
             wgpu_setup,
             on_surface_error: _,
+            hdr,
         } = self;
         f.debug_struct("WgpuConfiguration")
             .field("present_mode", &present_mode)
@@ -334,6 +411,7 @@ impl std::fmt::Debug for WgpuConfiguration {
                 &desired_maximum_frame_latency,
             )
             .field("wgpu_setup", &wgpu_setup)
+            .field("hdr", &hdr)
             .finish_non_exhaustive()
     }
 }
@@ -354,6 +432,7 @@ impl Default for WgpuConfiguration {
                 }
                 SurfaceErrorAction::SkipFrame
             }),
+            hdr: None,
         }
     }
 }
@@ -380,6 +459,30 @@ pub fn preferred_framebuffer_format(
         .ok_or(WgpuError::NoSurfaceFormatsAvailable)
 }
 
+/// Find the HDR framebuffer format that egui prefers, falling back to [`preferred_framebuffer_format`]
+/// if the surface doesn't support one.
+///
+/// # Errors
+/// Returns [`WgpuError::NoSurfaceFormatsAvailable`] if the given list of formats is empty.
+pub fn preferred_hdr_framebuffer_format(
+    formats: &[wgpu::TextureFormat],
+) -> Result<wgpu::TextureFormat, WgpuError> {
+    if formats.contains(&wgpu::TextureFormat::Rgba16Float) {
+        Ok(wgpu::TextureFormat::Rgba16Float)
+    } else {
+        log::warn!("HDR was requested, but the surface only supports {formats:?}");
+        preferred_framebuffer_format(formats)
+    }
+}
+
+/// Is this a floating point format, where colors are linear and may go above `1.0`?
+pub fn is_hdr_format(format: wgpu::TextureFormat) -> bool {
+    matches!(
+        format,
+        wgpu::TextureFormat::Rgba16Float | wgpu::TextureFormat::Rgba32Float
+    )
+}
+
 /// Take's epi's depth/stencil bits and returns the corresponding wgpu format.
 pub fn depth_format_from_bits(depth_buffer: u8, stencil_buffer: u8) -> Option<wgpu::TextureFormat> {
     match (depth_buffer, stencil_buffer) {
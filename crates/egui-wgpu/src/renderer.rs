@@ -120,6 +120,33 @@ pub trait CallbackTrait: Send + Sync {
     );
 }
 
+/// A full-screen pass (bloom, FXAA, color grading, …) that runs after all of egui has been painted,
+/// before the result ends up on the screen.
+///
+/// Register it with [`Renderer::add_post_processing_pass`].
+/// With [`crate::winit::Painter`] egui is then painted into an offscreen texture,
+/// which is passed through all registered passes in order, the last of which writes to the surface.
+/// If you use the [`Renderer`] directly, call [`Renderer::post_process`] yourself.
+pub trait PostProcessingPass: Send + Sync {
+    /// Read the image painted so far from `input`, and write the processed image to `output`,
+    /// e.g. by drawing a full-screen triangle in a render pass.
+    ///
+    /// Both textures have the size [`ScreenDescriptor::size_in_pixels`] and the output format of the renderer.
+    /// `input` can be sampled, and `output` can be used as a render attachment.
+    /// Every pixel of `output` should be written.
+    #[allow(clippy::too_many_arguments)]
+    fn process(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        input: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+        screen_descriptor: &ScreenDescriptor,
+        callback_resources: &CallbackResources,
+    );
+}
+
 /// Information about the screen used for rendering.
 pub struct ScreenDescriptor {
     /// Size of the window in physical pixels.
@@ -145,15 +172,16 @@ impl ScreenDescriptor {
 struct UniformBuffer {
     screen_size_in_points: [f32; 2],
     dithering: u32,
-    // Uniform buffers need to be at least 16 bytes in WebGL.
+    // Also pads this to 16 bytes, the minimum for uniform buffers in WebGL.
     // See https://github.com/gfx-rs/wgpu/issues/2072
-    _padding: u32,
+    sdr_white_level: f32,
 }
 
 impl PartialEq for UniformBuffer {
     fn eq(&self, other: &Self) -> bool {
         self.screen_size_in_points == other.screen_size_in_points
             && self.dithering == other.dithering
+            && self.sdr_white_level == other.sdr_white_level
     }
 }
 
@@ -195,11 +223,14 @@ pub struct Renderer {
     samplers: HashMap<epaint::textures::TextureOptions, wgpu::Sampler>,
 
     dithering: bool,
+    sdr_white_level: f32,
 
     /// Storage for resources shared with all invocations of [`CallbackTrait`]'s methods.
     ///
     /// See also [`CallbackTrait`].
     pub callback_resources: CallbackResources,
+
+    post_processing_passes: Vec<Box<dyn PostProcessingPass>>,
 }
 
 impl Renderer {
@@ -207,6 +238,9 @@ impl Renderer {
     ///
     /// `output_color_format` should preferably be [`wgpu::TextureFormat::Rgba8Unorm`] or
     /// [`wgpu::TextureFormat::Bgra8Unorm`], i.e. in gamma-space.
+    ///
+    /// For HDR, use [`wgpu::TextureFormat::Rgba16Float`] (scRGB), and see [`Self::set_sdr_white_level`].
+    /// egui's colors are converted to linear for it, and blended in linear space.
     pub fn new(
         device: &wgpu::Device,
         output_color_format: wgpu::TextureFormat,
@@ -230,7 +264,7 @@ impl Renderer {
             contents: bytemuck::cast_slice(&[UniformBuffer {
                 screen_size_in_points: [0.0, 0.0],
                 dithering: u32::from(dithering),
-                _padding: Default::default(),
+                sdr_white_level: 1.0,
             }]),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
@@ -343,7 +377,9 @@ impl Renderer {
 
                 fragment: Some(wgpu::FragmentState {
                     module: &module,
-                    entry_point: Some(if output_color_format.is_srgb() {
+                    entry_point: Some(if crate::is_hdr_format(output_color_format) {
+                        "fs_main_linear_framebuffer"
+                    } else if output_color_format.is_srgb() {
                         log::warn!("Detected a linear (sRGBA aware) framebuffer {:?}. egui prefers Rgba8Unorm or Bgra8Unorm", output_color_format);
                         "fs_main_linear_framebuffer"
                     } else {
@@ -395,7 +431,7 @@ impl Renderer {
             previous_uniform_buffer_content: UniformBuffer {
                 screen_size_in_points: [0.0, 0.0],
                 dithering: 0,
-                _padding: 0,
+                sdr_white_level: 1.0,
             },
             uniform_bind_group,
             texture_bind_group_layout,
@@ -403,7 +439,85 @@ impl Renderer {
             next_user_texture_id: 0,
             samplers: HashMap::default(),
             dithering,
+            sdr_white_level: 1.0,
             callback_resources: CallbackResources::default(),
+            post_processing_passes: Vec::new(),
+        }
+    }
+
+    /// How bright egui's white is on a linear HDR target, relative to `1.0` in the target
+    /// (for scRGB, that is 80 nits).
+    ///
+    /// egui's colors are authored for SDR, so set this to the brightness the user picked for SDR content
+    /// (e.g. 200 nits = 2.5), or the UI will look dim next to the rest of the screen.
+    ///
+    /// Only use this with [`wgpu::TextureFormat::Rgba16Float`] and other float targets,
+    /// as anything brighter than `1.0` is clipped by sRGB targets.
+    ///
+    /// Default: `1.0`.
+    pub fn set_sdr_white_level(&mut self, sdr_white_level: f32) {
+        self.sdr_white_level = sdr_white_level;
+    }
+
+    pub fn sdr_white_level(&self) -> f32 {
+        self.sdr_white_level
+    }
+
+    /// Add a pass that runs after egui has been painted, after the passes added before it.
+    ///
+    /// See [`PostProcessingPass`].
+    pub fn add_post_processing_pass(&mut self, pass: impl PostProcessingPass + 'static) {
+        self.post_processing_passes.push(Box::new(pass));
+    }
+
+    /// Remove all passes added with [`Self::add_post_processing_pass`].
+    pub fn clear_post_processing_passes(&mut self) {
+        self.post_processing_passes.clear();
+    }
+
+    /// Have any passes been added with [`Self::add_post_processing_pass`]?
+    pub fn has_post_processing_passes(&self) -> bool {
+        !self.post_processing_passes.is_empty()
+    }
+
+    /// Run all [`PostProcessingPass`]es, reading from `painted` and writing the result to `output`.
+    ///
+    /// `painted` should contain what egui painted, and is overwritten with intermediate results,
+    /// as is `scratch`. Both must be usable as render attachments and for sampling, and have the
+    /// size and format of `output`. `scratch` is only used with more than one pass.
+    ///
+    /// Does nothing if there are no passes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn post_process(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        painted: &wgpu::TextureView,
+        scratch: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+        screen_descriptor: &ScreenDescriptor,
+    ) {
+        profiling::function_scope!();
+
+        let num_passes = self.post_processing_passes.len();
+        for (i, pass) in self.post_processing_passes.iter().enumerate() {
+            // Ping-pong between the two textures, and write the last pass to the output:
+            let (input, next) = if i % 2 == 0 {
+                (painted, scratch)
+            } else {
+                (scratch, painted)
+            };
+            let target = if i + 1 == num_passes { output } else { next };
+            pass.process(
+                device,
+                queue,
+                encoder,
+                input,
+                target,
+                screen_descriptor,
+                &self.callback_resources,
+            );
         }
     }
 
@@ -856,7 +970,7 @@ impl Renderer {
         let uniform_buffer_content = UniformBuffer {
             screen_size_in_points,
             dithering: u32::from(self.dithering),
-            _padding: Default::default(),
+            sdr_white_level: self.sdr_white_level,
         };
         if uniform_buffer_content != self.previous_uniform_buffer_content {
             profiling::scope!("update uniforms");
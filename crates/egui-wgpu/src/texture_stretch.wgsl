@@ -0,0 +1,35 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// One large triangle covering the clip space, see `texture_copy.wgsl`.
+var<private> positions: array<vec2f, 3> = array<vec2f, 3>(
+    vec2f(-1.0, -3.0),
+    vec2f(-1.0, 1.0),
+    vec2f(3.0, 1.0)
+);
+
+// meant to be called with 3 vertex indices: 0, 1, 2
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let position = positions[vertex_index];
+    var result: VertexOutput;
+    result.position = vec4f(position, 0.0, 1.0);
+    result.uv = vec2f(0.5 + 0.5 * position.x, 0.5 - 0.5 * position.y);
+    return result;
+}
+
+@group(0)
+@binding(0)
+var r_color: texture_2d<f32>;
+
+@group(0)
+@binding(1)
+var r_sampler: sampler;
+
+// Unlike `texture_copy.wgsl`, the source may have a different size than the target:
+@fragment
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(r_color, r_sampler, vertex.uv);
+}
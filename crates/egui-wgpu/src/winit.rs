@@ -13,6 +13,214 @@ struct SurfaceState {
     height: u32,
 }
 
+/// Offscreen targets for the [`crate::PostProcessingPass`]es, see [`crate::Renderer::post_process`].
+struct PostProcessingTextures {
+    size: [u32; 2],
+    painted: wgpu::TextureView,
+    scratch: wgpu::TextureView,
+}
+
+impl PostProcessingTextures {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat, size: [u32; 2]) -> Self {
+        let create_view = |label| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d {
+                        width: size[0],
+                        height: size[1],
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[format],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        Self {
+            size,
+            painted: create_view("egui_post_processing_painted"),
+            scratch: create_view("egui_post_processing_scratch"),
+        }
+    }
+}
+
+/// Stretches a texture onto a render target of a different size, with linear filtering.
+struct TextureStretcher {
+    pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+}
+
+impl TextureStretcher {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("texture_stretch.wgsl"));
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("texture_stretch"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("texture_stretch"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self { pipeline, sampler }
+    }
+
+    fn bind_group(&self, device: &wgpu::Device, view: &wgpu::TextureView) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("texture_stretch"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    /// Fill all of `target` with the texture of `bind_group`.
+    fn stretch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        bind_group: &wgpu::BindGroup,
+        target: &wgpu::TextureView,
+    ) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("texture_stretch"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+/// Offscreen target egui is painted to when a viewport has a render scale, see [`Painter::set_render_scale`].
+///
+/// It has its own multisampling and depth textures, since they must match its size.
+struct RenderScaleTarget {
+    size: [u32; 2],
+    painted: wgpu::TextureView,
+    msaa: Option<wgpu::TextureView>,
+    depth: Option<wgpu::TextureView>,
+    bind_group: wgpu::BindGroup,
+}
+
+impl RenderScaleTarget {
+    fn new(
+        device: &wgpu::Device,
+        stretcher: &TextureStretcher,
+        format: wgpu::TextureFormat,
+        depth_format: Option<wgpu::TextureFormat>,
+        msaa_samples: u32,
+        size: [u32; 2],
+    ) -> Self {
+        let create_view = |label, format, sample_count, usage| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d {
+                        width: size[0],
+                        height: size[1],
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage,
+                    view_formats: &[format],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        let painted = create_view(
+            "egui_render_scale_painted",
+            format,
+            1,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        let msaa = (msaa_samples > 1).then(|| {
+            create_view(
+                "egui_render_scale_msaa",
+                format,
+                msaa_samples,
+                wgpu::TextureUsages::RENDER_ATTACHMENT,
+            )
+        });
+        let depth = depth_format.map(|depth_format| {
+            create_view(
+                "egui_render_scale_depth",
+                depth_format,
+                msaa_samples,
+                wgpu::TextureUsages::RENDER_ATTACHMENT,
+            )
+        });
+        let bind_group = stretcher.bind_group(device, &painted);
+
+        Self {
+            size,
+            painted,
+            msaa,
+            depth,
+            bind_group,
+        }
+    }
+}
+
+/// The size of the offscreen target for the given surface size, see [`Painter::set_render_scale`].
+fn render_scale_target_size(surface_size: [u32; 2], render_scale: f32, max_side: u32) -> [u32; 2] {
+    surface_size.map(|side| {
+        (side as f32 * render_scale)
+            .round()
+            .clamp(1.0, max_side as f32) as u32
+    })
+}
+
 /// Everything you need to paint egui with [`wgpu`] on [`winit`].
 ///
 /// Alternatively you can use [`crate::Renderer`] directly.
@@ -33,7 +241,11 @@ pub struct Painter {
     // Per viewport/window:
     depth_texture_view: ViewportIdMap<wgpu::TextureView>,
     msaa_texture_view: ViewportIdMap<wgpu::TextureView>,
+    post_processing_textures: ViewportIdMap<PostProcessingTextures>,
+    render_scales: ViewportIdMap<f32>,
+    render_scale_targets: ViewportIdMap<RenderScaleTarget>,
     surfaces: ViewportIdMap<SurfaceState>,
+    texture_stretcher: Option<TextureStretcher>,
     capture_tx: CaptureSender,
     capture_rx: CaptureReceiver,
 }
@@ -77,6 +289,10 @@ impl Painter {
             depth_texture_view: Default::default(),
             surfaces: Default::default(),
             msaa_texture_view: Default::default(),
+            post_processing_textures: Default::default(),
+            render_scales: Default::default(),
+            render_scale_targets: Default::default(),
+            texture_stretcher: None,
 
             capture_tx,
             capture_rx,
@@ -209,6 +425,8 @@ impl Painter {
                 self.dithering,
             )
             .await?;
+            // The adapter may not support the requested sample count:
+            self.msaa_samples = render_state.msaa_samples;
             self.render_state.get_or_insert(render_state)
         };
         let alpha_mode = if self.support_transparent_backbuffer {
@@ -348,6 +566,29 @@ impl Painter {
         }
     }
 
+    /// Paint the given viewport at a different resolution than its surface, and stretch the result to fit.
+    ///
+    /// Values above `1.0` supersample (e.g. for crisp screenshots),
+    /// values below `1.0` undersample (e.g. for performance on weak GPUs).
+    /// This does not change the layout, only the resolution of the painted result.
+    ///
+    /// The default is `1.0`, which paints directly to the surface.
+    pub fn set_render_scale(&mut self, viewport_id: ViewportId, render_scale: f32) {
+        let render_scale = if render_scale.is_finite() && 0.0 < render_scale {
+            render_scale
+        } else {
+            log::warn!("Invalid render scale: {render_scale}");
+            1.0
+        };
+
+        if render_scale == 1.0 {
+            self.render_scales.remove(&viewport_id);
+            self.render_scale_targets.remove(&viewport_id);
+        } else {
+            self.render_scales.insert(viewport_id, render_scale);
+        }
+    }
+
     /// Returns two things:
     ///
     /// The approximate number of seconds spent on vsync-waiting (if any),
@@ -382,12 +623,31 @@ impl Painter {
                     label: Some("encoder"),
                 });
 
-        // Upload all resources for the GPU.
-        let screen_descriptor = renderer::ScreenDescriptor {
+        let surface_descriptor = renderer::ScreenDescriptor {
             size_in_pixels: [surface_state.width, surface_state.height],
             pixels_per_point,
         };
 
+        // With a render scale, egui is painted offscreen at a different size, and then stretched to fit:
+        let render_scale = self.render_scales.get(&viewport_id).copied();
+
+        // Upload all resources for the GPU.
+        let screen_descriptor = if let Some(render_scale) = render_scale {
+            renderer::ScreenDescriptor {
+                size_in_pixels: render_scale_target_size(
+                    surface_descriptor.size_in_pixels,
+                    render_scale,
+                    render_state.device.limits().max_texture_dimension_2d,
+                ),
+                pixels_per_point: pixels_per_point * render_scale,
+            }
+        } else {
+            renderer::ScreenDescriptor {
+                size_in_pixels: surface_descriptor.size_in_pixels,
+                pixels_per_point,
+            }
+        };
+
         let user_cmd_bufs = {
             let mut renderer = render_state.renderer.write();
             for (id, image_delta) in &textures_delta.set {
@@ -446,12 +706,80 @@ impl Painter {
             };
             let target_view = target_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-            let (view, resolve_target) = (self.msaa_samples > 1)
-                .then_some(self.msaa_texture_view.get(&viewport_id))
-                .flatten()
-                .map_or((&target_view, None), |texture_view| {
-                    (texture_view, Some(&target_view))
+            // With post-processing, egui is painted offscreen first:
+            let post_processing = if renderer.has_post_processing_passes() {
+                let size = [surface_state.width, surface_state.height];
+                if self
+                    .post_processing_textures
+                    .get(&viewport_id)
+                    .map(|t| t.size)
+                    != Some(size)
+                {
+                    self.post_processing_textures.insert(
+                        viewport_id,
+                        PostProcessingTextures::new(
+                            &render_state.device,
+                            render_state.target_format,
+                            size,
+                        ),
+                    );
+                }
+                self.post_processing_textures.get(&viewport_id)
+            } else {
+                self.post_processing_textures.remove(&viewport_id);
+                None
+            };
+            let paint_view = post_processing.map_or(&target_view, |textures| &textures.painted);
+
+            let render_scale_target = if render_scale.is_some() {
+                let stretcher = self.texture_stretcher.get_or_insert_with(|| {
+                    TextureStretcher::new(&render_state.device, render_state.target_format)
                 });
+                let size = screen_descriptor.size_in_pixels;
+                if self
+                    .render_scale_targets
+                    .get(&viewport_id)
+                    .map(|target| target.size)
+                    != Some(size)
+                {
+                    self.render_scale_targets.insert(
+                        viewport_id,
+                        RenderScaleTarget::new(
+                            &render_state.device,
+                            stretcher,
+                            render_state.target_format,
+                            self.depth_format,
+                            self.msaa_samples,
+                            size,
+                        ),
+                    );
+                }
+                self.render_scale_targets.get(&viewport_id)
+            } else {
+                None
+            };
+
+            let (view, resolve_target, depth_view) = if let Some(target) = render_scale_target {
+                let (view, resolve_target) = target
+                    .msaa
+                    .as_ref()
+                    .map_or((&target.painted, None), |msaa| {
+                        (msaa, Some(&target.painted))
+                    });
+                (view, resolve_target, target.depth.as_ref())
+            } else {
+                let (view, resolve_target) = (self.msaa_samples > 1)
+                    .then_some(self.msaa_texture_view.get(&viewport_id))
+                    .flatten()
+                    .map_or((paint_view, None), |texture_view| {
+                        (texture_view, Some(paint_view))
+                    });
+                (
+                    view,
+                    resolve_target,
+                    self.depth_texture_view.get(&viewport_id),
+                )
+            };
 
             let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("egui_render"),
@@ -468,7 +796,7 @@ impl Painter {
                         store: wgpu::StoreOp::Store,
                     },
                 })],
-                depth_stencil_attachment: self.depth_texture_view.get(&viewport_id).map(|view| {
+                depth_stencil_attachment: depth_view.map(|view| {
                     wgpu::RenderPassDepthStencilAttachment {
                         view,
                         depth_ops: Some(wgpu::Operations {
@@ -493,6 +821,23 @@ impl Painter {
                 &screen_descriptor,
             );
 
+            if let (Some(target), Some(stretcher)) = (render_scale_target, &self.texture_stretcher)
+            {
+                stretcher.stretch(&mut encoder, &target.bind_group, paint_view);
+            }
+
+            if let Some(textures) = post_processing {
+                renderer.post_process(
+                    &render_state.device,
+                    &render_state.queue,
+                    &mut encoder,
+                    &textures.painted,
+                    &textures.scratch,
+                    &target_view,
+                    &surface_descriptor,
+                );
+            }
+
             if capture {
                 if let Some(capture_state) = &mut self.screen_capture_state {
                     capture_buffer = Some(capture_state.copy_textures(
@@ -573,6 +918,12 @@ impl Painter {
             .retain(|id, _| active_viewports.contains(id));
         self.msaa_texture_view
             .retain(|id, _| active_viewports.contains(id));
+        self.post_processing_textures
+            .retain(|id, _| active_viewports.contains(id));
+        self.render_scales
+            .retain(|id, _| active_viewports.contains(id));
+        self.render_scale_targets
+            .retain(|id, _| active_viewports.contains(id));
     }
 
     #[allow(clippy::needless_pass_by_ref_mut, clippy::unused_self)]
@@ -109,6 +109,134 @@ impl Clipboard {
         self.clipboard = text;
     }
 
+    /// Read the primary selection, i.e. the text the user last selected, for middle-click paste.
+    ///
+    /// Only X11 and Wayland have a primary selection, so this returns `None` everywhere else.
+    #[cfg(feature = "primary-selection")]
+    #[cfg_attr(
+        not(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )),
+        allow(clippy::unused_self, clippy::needless_pass_by_ref_mut)
+    )]
+    pub fn get_primary(&mut self) -> Option<String> {
+        #[cfg(all(
+            any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ),
+            feature = "smithay-clipboard",
+            feature = "primary-selection"
+        ))]
+        if let Some(clipboard) = &mut self.smithay {
+            return match clipboard.load_primary() {
+                Ok(text) => Some(text),
+                Err(err) => {
+                    log::error!("smithay primary selection paste error: {err}");
+                    None
+                }
+            };
+        }
+
+        #[cfg(all(
+            any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ),
+            feature = "arboard",
+            feature = "primary-selection"
+        ))]
+        if let Some(clipboard) = &mut self.arboard {
+            use arboard::{GetExtLinux as _, LinuxClipboardKind};
+
+            return match clipboard
+                .get()
+                .clipboard(LinuxClipboardKind::Primary)
+                .text()
+            {
+                Ok(text) => Some(text),
+                Err(err) => {
+                    log::error!("arboard primary selection paste error: {err}");
+                    None
+                }
+            };
+        }
+
+        None
+    }
+
+    /// Put the text in the primary selection, see [`Self::get_primary`].
+    ///
+    /// Does nothing on platforms without a primary selection.
+    #[cfg(feature = "primary-selection")]
+    #[cfg_attr(
+        not(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )),
+        allow(
+            clippy::unused_self,
+            clippy::needless_pass_by_ref_mut,
+            clippy::needless_pass_by_value
+        )
+    )]
+    pub fn set_primary_text(&mut self, text: String) {
+        #[cfg(all(
+            any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ),
+            feature = "smithay-clipboard",
+            feature = "primary-selection"
+        ))]
+        if let Some(clipboard) = &mut self.smithay {
+            clipboard.store_primary(text);
+            return;
+        }
+
+        #[cfg(all(
+            any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ),
+            feature = "arboard",
+            feature = "primary-selection"
+        ))]
+        if let Some(clipboard) = &mut self.arboard {
+            use arboard::{LinuxClipboardKind, SetExtLinux as _};
+
+            if let Err(err) = clipboard
+                .set()
+                .clipboard(LinuxClipboardKind::Primary)
+                .text(text)
+            {
+                log::error!("arboard primary selection copy error: {err}");
+            }
+            return;
+        }
+
+        _ = text;
+    }
+
     pub fn set_image(&mut self, image: &egui::ColorImage) {
         #[cfg(all(feature = "arboard", not(target_os = "android")))]
         if let Some(clipboard) = &mut self.arboard {
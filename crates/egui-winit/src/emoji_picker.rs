@@ -0,0 +1,72 @@
+//! Open the emoji picker of the operating system, for [`egui::ViewportCommand::ShowEmojiPicker`].
+//!
+//! winit has no API for this, so we talk to the OS directly.
+
+/// Open the emoji picker, like pressing `Win+.`.
+///
+/// There is no API for this that works for plain Win32 windows, so we press the shortcut for the user.
+#[cfg(windows)]
+#[allow(unsafe_code)]
+pub(crate) fn show_emoji_picker() {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        VIRTUAL_KEY, VK_LWIN, VK_OEM_PERIOD,
+    };
+
+    fn key(key: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+        INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: key,
+                    wScan: 0,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        }
+    }
+
+    let inputs = [
+        key(VK_LWIN, 0),
+        key(VK_OEM_PERIOD, 0),
+        key(VK_OEM_PERIOD, KEYEVENTF_KEYUP),
+        key(VK_LWIN, KEYEVENTF_KEYUP),
+    ];
+
+    // SAFETY: `inputs` is an array of `inputs.len()` keyboard inputs.
+    let sent = unsafe {
+        SendInput(
+            inputs.len() as u32,
+            inputs.as_ptr(),
+            std::mem::size_of::<INPUT>() as i32,
+        )
+    };
+    if sent as usize != inputs.len() {
+        log::warn!("ShowEmojiPicker: failed to press Win+. (blocked by another program?)");
+    }
+}
+
+/// Open the character palette, like pressing `Cmd+Ctrl+Space`.
+///
+/// Must be called on the main thread.
+#[cfg(target_os = "macos")]
+pub(crate) fn show_emoji_picker() {
+    use objc2_app_kit::NSApplication;
+    use objc2_foundation::MainThreadMarker;
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::warn!("ShowEmojiPicker: the character palette can only be opened on the main thread");
+        return;
+    };
+    NSApplication::sharedApplication(mtm).orderFrontCharacterPalette(None);
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub(crate) fn show_emoji_picker() {
+    log::warn!(
+        "ShowEmojiPicker: there is no system emoji picker on this platform. \
+        Use the emoji input of your input method instead (e.g. Ctrl+. in IBus)."
+    );
+}
@@ -0,0 +1,189 @@
+//! Ask the OS if the user wants more contrast, for [`egui::RawInput::system_high_contrast`].
+//!
+//! winit has no API for this, so we talk to the OS directly:
+//! * Windows: is a contrast theme active?
+//! * Mac: is "Increase contrast" enabled?
+//! * Linux and BSD: the `contrast` setting of the XDG desktop portal (requires the `dbus` feature)
+//!
+//! All of these are cheap enough to call every second.
+
+/// Has the user asked for more contrast? `None` if unknown.
+#[cfg(windows)]
+pub(crate) fn system_high_contrast(_egui_ctx: &egui::Context) -> Option<bool> {
+    use windows_sys::Win32::UI::{
+        Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW},
+        WindowsAndMessaging::{SystemParametersInfoW, SPI_GETHIGHCONTRAST},
+    };
+
+    let mut high_contrast = HIGHCONTRASTW {
+        cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+        dwFlags: 0,
+        lpszDefaultScheme: std::ptr::null_mut(),
+    };
+    // SAFETY: `high_contrast` is a correctly sized `HIGHCONTRASTW`.
+    #[allow(unsafe_code)]
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            high_contrast.cbSize,
+            std::ptr::addr_of_mut!(high_contrast).cast(),
+            0,
+        )
+    };
+    (ok != 0).then_some(high_contrast.dwFlags & HCF_HIGHCONTRASTON != 0)
+}
+
+/// Has the user asked for more contrast? `None` if unknown.
+#[cfg(target_os = "macos")]
+#[allow(clippy::unnecessary_wraps)] // same signature on all platforms
+pub(crate) fn system_high_contrast(_egui_ctx: &egui::Context) -> Option<bool> {
+    use objc2_app_kit::NSWorkspace;
+
+    // SAFETY: `NSWorkspace` is thread safe, and reading a setting has no side effects.
+    #[allow(unsafe_code)]
+    let increase_contrast =
+        unsafe { NSWorkspace::sharedWorkspace().accessibilityDisplayShouldIncreaseContrast() };
+    Some(increase_contrast)
+}
+
+/// Has the user asked for more contrast? `None` if unknown.
+///
+/// D-Bus calls block, so we make them on a background thread, with a connection we keep open.
+/// This returns the answer to the previous call, and asks again.
+/// `egui_ctx` is repainted when the answer changes.
+#[cfg(all(
+    feature = "dbus",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+pub(crate) fn system_high_contrast(egui_ctx: &egui::Context) -> Option<bool> {
+    portal::refresh(egui_ctx);
+    portal::latest()
+}
+
+#[cfg(all(
+    feature = "dbus",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+mod portal {
+    use std::sync::{
+        atomic::{AtomicU8, Ordering},
+        mpsc, OnceLock,
+    };
+
+    /// <https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Settings.html>
+    const CONTRAST_HIGH: u32 = 1;
+
+    const UNKNOWN: u8 = 0;
+    const NORMAL: u8 = 1;
+    const HIGH: u8 = 2;
+
+    /// The last answer from the portal.
+    static LATEST: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    /// Asks the background thread to read the setting, and whom to repaint if it changed.
+    static REQUESTS: OnceLock<Option<mpsc::Sender<egui::Context>>> = OnceLock::new();
+
+    pub fn latest() -> Option<bool> {
+        match LATEST.load(Ordering::Relaxed) {
+            NORMAL => Some(false),
+            HIGH => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn refresh(egui_ctx: &egui::Context) {
+        let requests = REQUESTS.get_or_init(|| {
+            let (tx, rx) = mpsc::channel();
+            let spawned = std::thread::Builder::new()
+                .name("egui-winit high contrast".to_owned())
+                .spawn(move || read_contrast_setting(&rx));
+            match spawned {
+                Ok(_) => Some(tx),
+                Err(err) => {
+                    log::warn!("Failed to spawn thread for reading the contrast setting: {err}");
+                    None
+                }
+            }
+        });
+        if let Some(requests) = requests {
+            requests.send(egui_ctx.clone()).ok();
+        }
+    }
+
+    fn read_contrast_setting(requests: &mpsc::Receiver<egui::Context>) {
+        let mut connection = None;
+        while let Ok(mut egui_ctx) = requests.recv() {
+            // Answer all the requests that piled up at once:
+            while let Ok(newer) = requests.try_recv() {
+                egui_ctx = newer;
+            }
+
+            if connection.is_none() {
+                connection = zbus::blocking::Connection::session()
+                    .map_err(|err| log::debug!("Failed to connect to D-Bus: {err}"))
+                    .ok();
+            }
+            let Some(conn) = &connection else {
+                continue;
+            };
+
+            let high_contrast = match read_high_contrast(conn) {
+                Ok(high_contrast) => high_contrast,
+                Err(err) => {
+                    log::debug!("Failed to read the contrast setting: {err}");
+                    connection = None; // Connect again next time
+                    continue;
+                }
+            };
+
+            let new = if high_contrast { HIGH } else { NORMAL };
+            if LATEST.swap(new, Ordering::Relaxed) != new {
+                egui_ctx.request_repaint();
+            }
+        }
+    }
+
+    fn read_high_contrast(connection: &zbus::blocking::Connection) -> zbus::Result<bool> {
+        let reply = connection.call_method(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            Some("org.freedesktop.portal.Settings"),
+            "ReadOne",
+            &("org.freedesktop.appearance", "contrast"),
+        )?;
+        let value: zbus::zvariant::OwnedValue = reply.body().deserialize()?;
+        let contrast = u32::try_from(value)?;
+        Ok(contrast == CONTRAST_HIGH)
+    }
+}
+
+/// Has the user asked for more contrast? `None` if unknown.
+#[cfg(not(any(
+    windows,
+    target_os = "macos",
+    all(
+        feature = "dbus",
+        any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )
+    )
+)))]
+pub(crate) fn system_high_contrast(_egui_ctx: &egui::Context) -> Option<bool> {
+    None
+}
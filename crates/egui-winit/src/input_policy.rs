@@ -0,0 +1,232 @@
+//! Sanitize input from untrusted sources (remote control, embedding) before it reaches egui.
+
+use ahash::HashSet;
+
+/// Common restrictions for untrusted input, for use with [`crate::State::set_input_filter`].
+///
+/// ```
+/// # use egui_winit::InputPolicy;
+/// let mut policy = InputPolicy::default();
+/// policy.max_text_chars_per_second = Some(50.0);
+/// policy.clamp_pointer_to_screen = true;
+/// policy.blocked_keys.insert(egui::Key::F11);
+/// // state.set_input_filter(move |raw_input| policy.apply(raw_input));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct InputPolicy {
+    /// Presses and releases of these keys are dropped.
+    pub blocked_keys: HashSet<egui::Key>,
+
+    /// Drop cut, copy and paste events.
+    pub block_clipboard: bool,
+
+    /// Drop dropped files.
+    pub block_dropped_files: bool,
+
+    /// Allow at most this many characters of text input per second, with bursts of up to one second's worth.
+    ///
+    /// Text beyond that is dropped.
+    pub max_text_chars_per_second: Option<f32>,
+
+    /// Keep pointer positions within [`egui::RawInput::screen_rect`].
+    pub clamp_pointer_to_screen: bool,
+
+    /// Drop all events beyond this many per frame.
+    ///
+    /// Releases (of keys, pointer buttons and touches), [`egui::Event::PointerGone`]
+    /// and [`egui::Event::WindowFocused`] are always kept, and don't count towards the limit,
+    /// so that no key or button gets stuck down.
+    pub max_events_per_frame: Option<usize>,
+
+    /// How many characters may be typed right now.
+    text_budget: Option<f32>,
+
+    last_time: Option<f64>,
+}
+
+impl InputPolicy {
+    /// Remove or change the events that violate the policy.
+    pub fn apply(&mut self, raw_input: &mut egui::RawInput) {
+        let dt = match (self.last_time, raw_input.time) {
+            (Some(last_time), Some(time)) => (time - last_time).max(0.0) as f32,
+            _ => 0.0,
+        };
+        self.last_time = raw_input.time.or(self.last_time);
+
+        if let Some(rate) = self.max_text_chars_per_second {
+            let budget = self.text_budget.get_or_insert(rate);
+            *budget = (*budget + rate * dt).min(rate);
+        }
+
+        if self.block_dropped_files {
+            raw_input.dropped_files.clear();
+            raw_input.hovered_files.clear();
+        }
+
+        if let Some(max_events) = self.max_events_per_frame {
+            let mut num_events = 0;
+            raw_input.events.retain(|event| {
+                if must_keep(event) {
+                    return true;
+                }
+                num_events += 1;
+                num_events <= max_events
+            });
+        }
+
+        let screen_rect = raw_input.screen_rect;
+        raw_input
+            .events
+            .retain_mut(|event| self.allow(event, screen_rect));
+    }
+
+    fn allow(&mut self, event: &mut egui::Event, screen_rect: Option<egui::Rect>) -> bool {
+        match event {
+            egui::Event::Key { key, .. } => !self.blocked_keys.contains(key),
+
+            egui::Event::Cut
+            | egui::Event::Copy
+            | egui::Event::Paste(_)
+            | egui::Event::PastePrimarySelection(_) => !self.block_clipboard,
+
+            egui::Event::Text(text) => {
+                let Some(budget) = &mut self.text_budget else {
+                    return true;
+                };
+                let allowed = (*budget as usize).min(text.chars().count());
+                *budget -= allowed as f32;
+                if allowed < text.chars().count() {
+                    log::debug!("Dropping text input beyond the rate limit");
+                    *text = text.chars().take(allowed).collect();
+                }
+                !text.is_empty()
+            }
+
+            egui::Event::PointerMoved(pos)
+            | egui::Event::PointerButton { pos, .. }
+            | egui::Event::Touch { pos, .. } => {
+                if self.clamp_pointer_to_screen {
+                    if let Some(screen_rect) = screen_rect {
+                        *pos = screen_rect.clamp(*pos);
+                    }
+                }
+                true
+            }
+
+            _ => true,
+        }
+    }
+}
+
+/// Events that are never dropped for [`InputPolicy::max_events_per_frame`],
+/// since dropping them would leave egui thinking something is still held down or focused.
+fn must_keep(event: &egui::Event) -> bool {
+    match event {
+        egui::Event::Key { pressed, .. } | egui::Event::PointerButton { pressed, .. } => !pressed,
+        egui::Event::Touch { phase, .. } => {
+            matches!(phase, egui::TouchPhase::End | egui::TouchPhase::Cancel)
+        }
+        egui::Event::PointerGone | egui::Event::WindowFocused(_) => true,
+        _ => false,
+    }
+}
+
+#[test]
+fn input_policy() {
+    let mut policy = InputPolicy {
+        max_text_chars_per_second: Some(4.0),
+        clamp_pointer_to_screen: true,
+        ..Default::default()
+    };
+    policy.blocked_keys.insert(egui::Key::F11);
+
+    let mut raw_input = egui::RawInput {
+        time: Some(0.0),
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(100.0, 100.0),
+        )),
+        events: vec![
+            egui::Event::Text("abc".to_owned()),
+            egui::Event::Text("def".to_owned()),
+            egui::Event::Key {
+                key: egui::Key::F11,
+                physical_key: None,
+                logical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: Default::default(),
+            },
+            egui::Event::PointerMoved(egui::pos2(-10.0, 500.0)),
+        ],
+        ..Default::default()
+    };
+    policy.apply(&mut raw_input);
+
+    assert_eq!(
+        raw_input.events,
+        vec![
+            egui::Event::Text("abc".to_owned()),
+            egui::Event::Text("d".to_owned()),
+            egui::Event::PointerMoved(egui::pos2(0.0, 100.0)),
+        ]
+    );
+
+    // Half a second later, two more characters are allowed:
+    let mut raw_input = egui::RawInput {
+        time: Some(0.5),
+        events: vec![egui::Event::Text("ghij".to_owned())],
+        ..Default::default()
+    };
+    policy.apply(&mut raw_input);
+    assert_eq!(raw_input.events, vec![egui::Event::Text("gh".to_owned())]);
+}
+
+#[test]
+fn max_events_per_frame_keeps_releases_and_focus() {
+    let key = |pressed| egui::Event::Key {
+        key: egui::Key::A,
+        physical_key: None,
+        logical_key: None,
+        pressed,
+        repeat: false,
+        modifiers: Default::default(),
+    };
+    let button = |pressed| egui::Event::PointerButton {
+        pos: egui::Pos2::ZERO,
+        button: egui::PointerButton::Primary,
+        pressed,
+        modifiers: Default::default(),
+    };
+
+    let mut policy = InputPolicy {
+        max_events_per_frame: Some(2),
+        ..Default::default()
+    };
+    let mut raw_input = egui::RawInput {
+        events: vec![
+            key(true),
+            button(true),
+            egui::Event::Text("a".to_owned()),
+            egui::Event::PointerMoved(egui::Pos2::ZERO),
+            key(false),
+            button(false),
+            egui::Event::WindowFocused(false),
+            egui::Event::PointerGone,
+        ],
+        ..Default::default()
+    };
+    policy.apply(&mut raw_input);
+
+    assert_eq!(
+        raw_input.events,
+        vec![
+            key(true),
+            button(true),
+            key(false),
+            button(false),
+            egui::Event::WindowFocused(false),
+            egui::Event::PointerGone,
+        ]
+    );
+}
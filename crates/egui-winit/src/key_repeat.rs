@@ -0,0 +1,134 @@
+//! Synthesized key repeat, for backends and devices that don't repeat held keys themselves.
+
+use std::time::Duration;
+
+use ahash::HashSet;
+use egui::Key;
+
+/// Repeat held keys in egui-winit, instead of relying on the operating system to do it.
+///
+/// Some embedded winit backends, gamepad mappers and TV remotes only send a single press
+/// and a single release, so holding down an arrow key moves just one step.
+/// With this set in [`crate::State::set_key_repeat`], a held key is pressed again
+/// after [`Self::delay`], and then every [`Self::interval`].
+///
+/// As soon as the operating system sends its own repeats for a held key, we stop synthesizing them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyRepeat {
+    /// Which keys to repeat.
+    ///
+    /// The default is the navigation keys: the arrow keys, page up/down, home and end.
+    pub keys: HashSet<Key>,
+
+    /// How long a key must be held before it starts repeating.
+    pub delay: Duration,
+
+    /// Time between repeats.
+    pub interval: Duration,
+}
+
+impl Default for KeyRepeat {
+    fn default() -> Self {
+        Self {
+            keys: [
+                Key::ArrowDown,
+                Key::ArrowLeft,
+                Key::ArrowRight,
+                Key::ArrowUp,
+                Key::PageUp,
+                Key::PageDown,
+                Key::Home,
+                Key::End,
+            ]
+            .into_iter()
+            .collect(),
+            delay: Duration::from_millis(500),
+            interval: Duration::from_millis(33),
+        }
+    }
+}
+
+impl KeyRepeat {
+    /// How long a key must be held before it starts repeating.
+    #[inline]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Time between repeats.
+    #[inline]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+/// A key that is being held down, and may be repeated.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct HeldKey {
+    pub key: Key,
+    pub physical_key: Option<Key>,
+    pub logical_key: Option<Key>,
+
+    /// When the next repeat is due.
+    next_repeat: web_time::Instant,
+}
+
+impl HeldKey {
+    pub fn new(
+        key: Key,
+        physical_key: Option<Key>,
+        logical_key: Option<Key>,
+        now: web_time::Instant,
+        config: &KeyRepeat,
+    ) -> Self {
+        Self {
+            key,
+            physical_key,
+            logical_key,
+            next_repeat: now + config.delay,
+        }
+    }
+
+    /// Is a repeat due by `now`, and how long until the next one?
+    ///
+    /// After a long frame we don't send a burst of repeats to catch up, at most one per call.
+    pub fn poll(&mut self, now: web_time::Instant, config: &KeyRepeat) -> (bool, Duration) {
+        let is_due = self.next_repeat <= now;
+        if is_due {
+            // Keep the cadence, unless we have fallen behind by more than an interval:
+            self.next_repeat += config.interval;
+            if self.next_repeat <= now {
+                self.next_repeat = now + config.interval;
+            }
+        }
+        (is_due, self.next_repeat.saturating_duration_since(now))
+    }
+}
+
+#[test]
+fn held_key_repeats_after_delay() {
+    let config = KeyRepeat::default()
+        .with_delay(Duration::from_millis(100))
+        .with_interval(Duration::from_millis(10));
+    let start = web_time::Instant::now();
+    let mut held = HeldKey::new(Key::ArrowDown, None, None, start, &config);
+
+    let (is_due, wait) = held.poll(start + Duration::from_millis(50), &config);
+    assert!(!is_due);
+    assert_eq!(wait, Duration::from_millis(50));
+
+    let (is_due, wait) = held.poll(start + Duration::from_millis(100), &config);
+    assert!(is_due);
+    assert_eq!(wait, Duration::from_millis(10));
+
+    let (is_due, wait) = held.poll(start + Duration::from_millis(105), &config);
+    assert!(!is_due);
+    assert_eq!(wait, Duration::from_millis(5));
+
+    // A long frame: one repeat, and then back to the normal interval.
+    let (is_due, wait) = held.poll(start + Duration::from_millis(200), &config);
+    assert!(is_due);
+    assert_eq!(wait, Duration::from_millis(10));
+}
@@ -0,0 +1,249 @@
+//! A preset for running an egui app unattended on a public display.
+
+use std::time::Duration;
+
+use egui::ViewportBuilder;
+use winit::{
+    event_loop::ActiveEventLoop,
+    window::{Fullscreen, Window, WindowAttributes},
+};
+
+/// Everything needed to run an app as a kiosk, in one place.
+///
+/// * fullscreen on the chosen monitor, without decorations, always on top
+/// * the mouse cursor is hidden after a while without pointer activity
+/// * the screen saver and display sleep are inhibited, see [`crate::SleepInhibitor`]
+/// * egui's own shortcuts that change the UI (like zooming with `Ctrl +`) are ignored
+///
+/// Shortcuts handled by the operating system (`Ctrl+Alt+Del`, `Alt+Tab`, `Cmd+Tab`, …)
+/// cannot be blocked by an application; lock those down in the kiosk configuration of the OS.
+/// To ignore close requests (e.g. `Alt+F4`), send [`egui::ViewportCommand::CancelClose`]
+/// whenever [`egui::ViewportInfo::close_requested`] is set.
+///
+/// Use [`Self::viewport_builder`] and [`Self::window_attributes`] when creating the window,
+/// and then call [`crate::State::set_kiosk_mode`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KioskMode {
+    /// Which monitor to go fullscreen on, as an index into [`Window::available_monitors`].
+    ///
+    /// `None` means the primary monitor.
+    pub monitor: Option<usize>,
+
+    /// Hide the mouse cursor after this long without any pointer activity.
+    ///
+    /// `None` means the cursor is never hidden.
+    pub hide_cursor_after: Option<Duration>,
+
+    /// Prevent the screen saver from starting and the display from going to sleep.
+    pub inhibit_screensaver: bool,
+
+    /// Ignore egui's own keyboard shortcuts that change the UI, like zooming.
+    pub ignore_shortcuts: bool,
+}
+
+impl Default for KioskMode {
+    fn default() -> Self {
+        Self {
+            monitor: None,
+            hide_cursor_after: Some(Duration::from_secs(3)),
+            inhibit_screensaver: true,
+            ignore_shortcuts: true,
+        }
+    }
+}
+
+impl KioskMode {
+    /// Go fullscreen on this monitor, as an index into [`Window::available_monitors`].
+    #[inline]
+    pub fn with_monitor(mut self, monitor: usize) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Hide the mouse cursor after this long without pointer activity, or never if `None`.
+    #[inline]
+    pub fn with_hide_cursor_after(mut self, hide_cursor_after: Option<Duration>) -> Self {
+        self.hide_cursor_after = hide_cursor_after;
+        self
+    }
+
+    /// Prevent the screen saver from starting and the display from going to sleep.
+    #[inline]
+    pub fn with_inhibit_screensaver(mut self, inhibit_screensaver: bool) -> Self {
+        self.inhibit_screensaver = inhibit_screensaver;
+        self
+    }
+
+    /// Ignore egui's own keyboard shortcuts that change the UI, like zooming.
+    #[inline]
+    pub fn with_ignore_shortcuts(mut self, ignore_shortcuts: bool) -> Self {
+        self.ignore_shortcuts = ignore_shortcuts;
+        self
+    }
+
+    /// Configure a [`ViewportBuilder`] for kiosk use:
+    /// fullscreen, no decorations, always on top, not resizable, and hidden from the taskbar.
+    ///
+    /// [`ViewportBuilder`] can't pick a monitor, so pass the resulting attributes
+    /// through [`Self::window_attributes`] before creating the window.
+    pub fn viewport_builder(builder: ViewportBuilder) -> ViewportBuilder {
+        builder
+            .with_fullscreen(true)
+            .with_decorations(false)
+            .with_always_on_top()
+            .with_resizable(false)
+            .with_taskbar(false)
+            .with_close_button(false)
+            .with_minimize_button(false)
+            .with_maximize_button(false)
+    }
+
+    /// Make the window fullscreen on the chosen monitor when it is created.
+    ///
+    /// Use on the attributes from [`crate::create_winit_window_attributes`].
+    pub fn window_attributes(
+        &self,
+        event_loop: &ActiveEventLoop,
+        attributes: WindowAttributes,
+    ) -> WindowAttributes {
+        let monitor = choose_monitor(
+            self.monitor,
+            event_loop.available_monitors(),
+            event_loop.primary_monitor(),
+        );
+        attributes
+            .with_decorations(false)
+            .with_window_level(winit::window::WindowLevel::AlwaysOnTop)
+            .with_fullscreen(Some(Fullscreen::Borderless(monitor)))
+    }
+
+    /// Make an existing window fullscreen on the chosen monitor, without decorations, and on top.
+    pub fn apply_to_window(&self, window: &Window) {
+        let monitor = choose_monitor(
+            self.monitor,
+            window.available_monitors(),
+            window.primary_monitor(),
+        );
+
+        window.set_decorations(false);
+        window.set_window_level(winit::window::WindowLevel::AlwaysOnTop);
+        if window.fullscreen().is_some() {
+            // Some platforms ignore a new monitor while the window is already fullscreen.
+            window.set_fullscreen(None);
+        }
+        window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+    }
+}
+
+/// The monitor at `index` in `available`, or the primary one if there is no such monitor.
+fn choose_monitor<T>(
+    index: Option<usize>,
+    mut available: impl Iterator<Item = T>,
+    primary: Option<T>,
+) -> Option<T> {
+    let chosen = index.and_then(|index| {
+        let monitor = available.nth(index);
+        if monitor.is_none() {
+            log::warn!("Kiosk mode: there is no monitor {index}; using the primary monitor");
+        }
+        monitor
+    });
+    chosen.or(primary)
+}
+
+/// Turn off egui's zoom shortcuts while `kiosk` asks for it, and restore them afterwards.
+///
+/// `saved` holds the setting from before kiosk mode changed it.
+pub(crate) fn update_shortcuts(
+    egui_ctx: &egui::Context,
+    kiosk: Option<&KioskMode>,
+    saved: &mut Option<bool>,
+) {
+    let ignore = kiosk.map_or(false, |kiosk| kiosk.ignore_shortcuts);
+    egui_ctx.options_mut(|o| {
+        if ignore {
+            saved.get_or_insert(o.zoom_with_keyboard);
+            o.zoom_with_keyboard = false;
+        } else if let Some(zoom_with_keyboard) = saved.take() {
+            o.zoom_with_keyboard = zoom_with_keyboard;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kiosk_viewport_builder() {
+        let builder = KioskMode::viewport_builder(ViewportBuilder::default().with_title("kiosk"));
+
+        assert_eq!(builder.title.as_deref(), Some("kiosk"));
+        assert_eq!(builder.fullscreen, Some(true));
+        assert_eq!(builder.decorations, Some(false));
+        assert_eq!(builder.window_level, Some(egui::WindowLevel::AlwaysOnTop));
+        assert_eq!(builder.resizable, Some(false));
+        assert_eq!(builder.taskbar, Some(false));
+    }
+
+    #[test]
+    fn kiosk_monitor_is_honored() {
+        let monitors = || ["left", "right", "top"].into_iter();
+
+        assert_eq!(
+            choose_monitor(Some(1), monitors(), Some("left")),
+            Some("right")
+        );
+        assert_eq!(
+            choose_monitor(Some(2), monitors(), Some("left")),
+            Some("top")
+        );
+        assert_eq!(
+            choose_monitor(None, monitors(), Some("right")),
+            Some("right")
+        );
+        assert_eq!(
+            choose_monitor(Some(7), monitors(), Some("left")),
+            Some("left")
+        );
+        assert_eq!(choose_monitor(Some(7), monitors(), None), None);
+    }
+
+    #[test]
+    fn kiosk_restores_zoom_shortcuts() {
+        let ctx = egui::Context::default();
+        let zoom = || ctx.options(|o| o.zoom_with_keyboard);
+        let mut saved = None;
+
+        let kiosk = KioskMode::default();
+        update_shortcuts(&ctx, Some(&kiosk), &mut saved);
+        assert!(!zoom());
+
+        // Changing the kiosk mode must not forget the original setting:
+        update_shortcuts(&ctx, Some(&kiosk), &mut saved);
+        assert!(!zoom());
+
+        update_shortcuts(
+            &ctx,
+            Some(&kiosk.clone().with_ignore_shortcuts(false)),
+            &mut saved,
+        );
+        assert!(zoom());
+
+        update_shortcuts(&ctx, Some(&kiosk), &mut saved);
+        update_shortcuts(&ctx, None, &mut saved);
+        assert!(zoom());
+        assert_eq!(saved, None);
+    }
+
+    #[test]
+    fn kiosk_leaves_disabled_zoom_disabled() {
+        let ctx = egui::Context::default();
+        ctx.options_mut(|o| o.zoom_with_keyboard = false);
+        let mut saved = None;
+
+        update_shortcuts(&ctx, Some(&KioskMode::default()), &mut saved);
+        update_shortcuts(&ctx, None, &mut saved);
+        assert!(!ctx.options(|o| o.zoom_with_keyboard));
+    }
+}
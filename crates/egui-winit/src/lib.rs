@@ -18,8 +18,28 @@ use egui::{Pos2, Rect, Theme, Vec2, ViewportBuilder, ViewportCommand, ViewportId
 pub use winit;
 
 pub mod clipboard;
+mod emoji_picker;
+mod high_contrast;
+mod input_policy;
+mod key_repeat;
+mod kiosk;
+mod modifier_keys;
+mod native_menu;
+#[cfg(feature = "recording")]
+mod recording;
+mod shortcuts;
+mod sleep_inhibit;
+mod telemetry;
 mod window_settings;
 
+pub use input_policy::InputPolicy;
+pub use key_repeat::KeyRepeat;
+pub use kiosk::KioskMode;
+#[cfg(feature = "recording")]
+pub use recording::{InputRecorder, InputReplay};
+pub use shortcuts::{ShortcutAction, ShortcutMap};
+pub use sleep_inhibit::SleepInhibitor;
+pub use telemetry::{FrameRecord, FrameTelemetry, WindowEventRecord, WindowStateChange};
 pub use window_settings::WindowSettings;
 
 use ahash::HashSet;
@@ -46,11 +66,45 @@ pub fn screen_size_in_pixels(window: &Window) -> egui::Vec2 {
 
 /// Calculate the `pixels_per_point` for a given window, given the current egui zoom factor
 pub fn pixels_per_point(egui_ctx: &egui::Context, window: &Window) -> f32 {
-    let native_pixels_per_point = window.scale_factor() as f32;
+    let native_pixels_per_point = native_pixels_per_point(window);
     let egui_zoom_factor = egui_ctx.zoom_factor();
     egui_zoom_factor * native_pixels_per_point
 }
 
+/// The scale factor of the window, see [`sanitize_scale_factor`].
+pub fn native_pixels_per_point(window: &Window) -> f32 {
+    sanitize_scale_factor(window.scale_factor())
+}
+
+/// The scale factors we believe in. Anything outside of this is a driver bug.
+const SANE_SCALE_FACTORS: std::ops::RangeInclusive<f64> = 0.1..=32.0;
+
+/// Replace a nonsensical scale factor reported by the OS with one we can use.
+///
+/// Some remote-desktop and virtual display drivers report a scale factor of zero,
+/// or a huge one, which would fill egui with NaNs and infinities.
+/// Zero, negative and non-finite scale factors are replaced by `1.0`,
+/// and the rest are clamped to a sane range.
+pub fn sanitize_scale_factor(scale_factor: f64) -> f32 {
+    static HAS_WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+    if SANE_SCALE_FACTORS.contains(&scale_factor) {
+        return scale_factor as f32;
+    }
+
+    let sanitized = if scale_factor.is_finite() && 0.0 < scale_factor {
+        scale_factor.clamp(*SANE_SCALE_FACTORS.start(), *SANE_SCALE_FACTORS.end())
+    } else {
+        1.0
+    };
+    if HAS_WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+        log::debug!("Got a scale factor of {scale_factor} - using {sanitized} instead");
+    } else {
+        log::warn!("Got a scale factor of {scale_factor} - using {sanitized} instead");
+    }
+    sanitized as f32
+}
+
 // ----------------------------------------------------------------------------
 
 #[must_use]
@@ -69,6 +123,100 @@ pub struct EventResponse {
     pub repaint: bool,
 }
 
+/// How [`State`] should treat bursts of pointer motion events that arrive between two frames.
+///
+/// High polling-rate mice can generate hundreds of motion events per frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PointerMoveCoalescing {
+    /// Forward every motion event to egui.
+    ///
+    /// Use this for drawing apps that want every intermediate pointer position.
+    #[default]
+    Off,
+
+    /// Of consecutive [`egui::Event::PointerMoved`], only the first and the latest are kept,
+    /// and consecutive [`egui::Event::MouseMoved`] deltas are summed,
+    /// so no relative motion is lost.
+    ///
+    /// egui samples the pointer history (and so the pointer velocity) once per frame,
+    /// at the latest position, so those are the same as with [`Self::Off`].
+    ///
+    /// Moves separated by other events (e.g. button presses) are never merged.
+    KeepLast,
+}
+
+/// Add a [`egui::Event::PointerMoved`], merged into the previous moves as per `coalescing`.
+fn push_pointer_moved(events: &mut Vec<egui::Event>, coalescing: PointerMoveCoalescing, pos: Pos2) {
+    if coalescing == PointerMoveCoalescing::KeepLast {
+        // Replace the latest move of the burst, but keep the first one:
+        if let [.., egui::Event::PointerMoved(_), egui::Event::PointerMoved(latest)] =
+            events.as_mut_slice()
+        {
+            *latest = pos;
+            return;
+        }
+    }
+    events.push(egui::Event::PointerMoved(pos));
+}
+
+/// Add a [`egui::Event::MouseMoved`], summed with the previous one as per `coalescing`.
+fn push_mouse_moved(events: &mut Vec<egui::Event>, coalescing: PointerMoveCoalescing, delta: Vec2) {
+    if coalescing == PointerMoveCoalescing::KeepLast {
+        if let Some(egui::Event::MouseMoved(previous)) = events.last_mut() {
+            *previous += delta;
+            return;
+        }
+    }
+    events.push(egui::Event::MouseMoved(delta));
+}
+
+/// Which key [`State`] reports as [`egui::Event::Key::key`] when the logical key
+/// (from the keyboard layout) and the physical key (the position on the keyboard) differ.
+///
+/// Both are always included in the event (as `logical_key` and `physical_key`),
+/// so you can also decide per binding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyResolution {
+    /// Use the logical key, falling back to the physical key if there is no logical key
+    /// (e.g. for non-Latin letters).
+    ///
+    /// This is what you want for editors and other text-heavy apps: Ctrl+Z is where the "Z" label is.
+    #[default]
+    LogicalFirst,
+
+    /// Use the physical key, falling back to the logical key if the position is unknown.
+    ///
+    /// This is what you want for games: WASD is in the same place on AZERTY and Dvorak.
+    PhysicalFirst,
+
+    /// Send one event for the logical key and, if it differs, another for the physical key.
+    ///
+    /// Bindings to either will then work, at the cost of the key press being reported twice.
+    Both,
+}
+
+impl KeyResolution {
+    /// The keys to send [`egui::Event::Key`] for, with the one to treat as pressed first.
+    fn keys(
+        self,
+        logical_key: Option<egui::Key>,
+        physical_key: Option<egui::Key>,
+    ) -> Vec<egui::Key> {
+        let active_key = match self {
+            Self::LogicalFirst | Self::Both => logical_key.or(physical_key),
+            Self::PhysicalFirst => physical_key.or(logical_key),
+        };
+        let Some(active_key) = active_key else {
+            return vec![];
+        };
+        let mut keys = vec![active_key];
+        if self == Self::Both {
+            keys.extend(physical_key.filter(|&physical_key| physical_key != active_key));
+        }
+        keys
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 /// Handles the integration between egui and a winit Window.
@@ -87,6 +235,11 @@ pub struct State {
 
     clipboard: clipboard::Clipboard,
 
+    /// Was there a mutable [`egui::TextEdit`] under the pointer in the last frame?
+    /// Only then do we read the primary selection on a middle-click.
+    #[cfg(feature = "primary-selection")]
+    mutable_text_under_cursor: bool,
+
     /// If `true`, mouse inputs will be treated as touches.
     /// Useful for debugging touch support in egui.
     ///
@@ -101,11 +254,65 @@ pub struct State {
     /// track ime state
     has_sent_ime_enabled: bool,
 
+    /// Has there been an IME preedit since the last commit?
+    has_ime_preedit: bool,
+
+    /// The widget with keyboard focus when we last handled the platform output.
+    ///
+    /// An IME composition is cancelled when the focus moves away from it.
+    ime_focus: Option<egui::Id>,
+
     #[cfg(feature = "accesskit")]
     accesskit: Option<accesskit_winit::Adapter>,
 
     allow_ime: bool,
     ime_rect_px: Option<egui::Rect>,
+
+    pointer_move_coalescing: PointerMoveCoalescing,
+
+    shortcut_map: ShortcutMap,
+
+    /// If set, we repeat held keys ourselves.
+    key_repeat: Option<KeyRepeat>,
+    held_key: Option<key_repeat::HeldKey>,
+
+    key_resolution: KeyResolution,
+
+    /// The modifiers according to the last [`WindowEvent::ModifiersChanged`].
+    ///
+    /// The modifiers we attach to events (in [`egui::RawInput::modifiers`])
+    /// also take [`Self::modifier_keys`] into account.
+    reported_modifiers: egui::Modifiers,
+    modifier_keys: modifier_keys::ModifierKeys,
+
+    /// When we last asked the OS about [`egui::RawInput::system_high_contrast`].
+    last_high_contrast_check: web_time::Instant,
+
+    /// Should [`Self::update_monitors`] ask the OS for the monitors again?
+    monitors_outdated: bool,
+
+    /// When [`Self::update_monitors`] last asked the OS for the monitors.
+    last_monitors_check: web_time::Instant,
+
+    /// Applied to the input of each frame, see [`Self::set_input_filter`].
+    input_filter: Option<Box<dyn FnMut(&mut egui::RawInput) + Send>>,
+
+    kiosk_mode: Option<KioskMode>,
+    kiosk_sleep_inhibitor: Option<SleepInhibitor>,
+
+    /// [`egui::Options::zoom_with_keyboard`] from before kiosk mode turned it off.
+    zoom_with_keyboard_before_kiosk: Option<bool>,
+
+    /// When the pointer last moved, clicked, scrolled, or touched. Used to hide the cursor in kiosk mode.
+    last_pointer_activity: web_time::Instant,
+
+    #[cfg(feature = "recording")]
+    input_recorder: Option<InputRecorder>,
+
+    #[cfg(feature = "recording")]
+    input_replay: Option<InputReplay>,
+
+    frame_telemetry: Option<FrameTelemetry>,
 }
 
 impl State {
@@ -137,25 +344,59 @@ impl State {
             clipboard: clipboard::Clipboard::new(
                 display_target.display_handle().ok().map(|h| h.as_raw()),
             ),
+            #[cfg(feature = "primary-selection")]
+            mutable_text_under_cursor: false,
 
             simulate_touch_screen: false,
             pointer_touch_id: None,
 
             has_sent_ime_enabled: false,
+            has_ime_preedit: false,
+            ime_focus: None,
 
             #[cfg(feature = "accesskit")]
             accesskit: None,
 
             allow_ime: false,
             ime_rect_px: None,
+
+            pointer_move_coalescing: Default::default(),
+
+            shortcut_map: Default::default(),
+
+            key_repeat: None,
+            held_key: None,
+            key_resolution: KeyResolution::default(),
+            reported_modifiers: Default::default(),
+            modifier_keys: Default::default(),
+            last_high_contrast_check: web_time::Instant::now(),
+            monitors_outdated: true,
+            last_monitors_check: web_time::Instant::now(),
+
+            input_filter: None,
+
+            kiosk_mode: None,
+            kiosk_sleep_inhibitor: None,
+            zoom_with_keyboard_before_kiosk: None,
+            last_pointer_activity: web_time::Instant::now(),
+
+            #[cfg(feature = "recording")]
+            input_recorder: None,
+
+            #[cfg(feature = "recording")]
+            input_replay: None,
+
+            frame_telemetry: None,
         };
 
         slf.egui_input
             .viewports
             .entry(ViewportId::ROOT)
             .or_default()
-            .native_pixels_per_point = native_pixels_per_point;
+            .native_pixels_per_point =
+            native_pixels_per_point.map(|ppp| sanitize_scale_factor(ppp.into()));
         slf.egui_input.system_theme = theme.map(to_egui_theme);
+        slf.egui_input.system_high_contrast = high_contrast::system_high_contrast(&slf.egui_ctx);
 
         if let Some(max_texture_side) = max_texture_side {
             slf.set_max_texture_side(max_texture_side);
@@ -203,6 +444,165 @@ impl State {
         self.allow_ime = allow;
     }
 
+    /// How bursts of pointer motion events are merged before being handed to egui.
+    pub fn pointer_move_coalescing(&self) -> PointerMoveCoalescing {
+        self.pointer_move_coalescing
+    }
+
+    /// Set how bursts of pointer motion events are merged before being handed to egui.
+    ///
+    /// Default: [`PointerMoveCoalescing::Off`].
+    pub fn set_pointer_move_coalescing(&mut self, coalescing: PointerMoveCoalescing) {
+        self.pointer_move_coalescing = coalescing;
+    }
+
+    /// Which key presses are translated into clipboard or custom events.
+    pub fn shortcut_map(&self) -> &ShortcutMap {
+        &self.shortcut_map
+    }
+
+    /// Remap or disable the clipboard shortcuts, or add your own. See [`ShortcutMap`].
+    pub fn shortcut_map_mut(&mut self) -> &mut ShortcutMap {
+        &mut self.shortcut_map
+    }
+
+    pub fn set_shortcut_map(&mut self, shortcut_map: ShortcutMap) {
+        self.shortcut_map = shortcut_map;
+    }
+
+    /// How held keys are repeated, if we synthesize the repeats ourselves.
+    pub fn key_repeat(&self) -> Option<&KeyRepeat> {
+        self.key_repeat.as_ref()
+    }
+
+    /// Synthesize key repeats for held keys, for backends and devices that don't repeat keys.
+    ///
+    /// Default: `None`, i.e. rely on the operating system. See [`KeyRepeat`].
+    pub fn set_key_repeat(&mut self, key_repeat: Option<KeyRepeat>) {
+        self.key_repeat = key_repeat;
+        self.held_key = None;
+    }
+
+    /// Which key is reported when the logical and physical keys differ.
+    pub fn key_resolution(&self) -> KeyResolution {
+        self.key_resolution
+    }
+
+    /// Prefer the logical or the physical key, e.g. [`KeyResolution::PhysicalFirst`] for games.
+    ///
+    /// Default: [`KeyResolution::LogicalFirst`].
+    pub fn set_key_resolution(&mut self, key_resolution: KeyResolution) {
+        self.key_resolution = key_resolution;
+    }
+
+    /// Inspect, change, or drop the input of each frame before it reaches egui.
+    ///
+    /// This is where embedders can enforce a security policy on untrusted input
+    /// (e.g. from a remote-control session): rate limits, blocked keys, coordinate bounds, …
+    /// [`InputPolicy`] implements the common cases:
+    ///
+    /// ```ignore
+    /// let mut policy = egui_winit::InputPolicy::default();
+    /// state.set_input_filter(move |raw_input| policy.apply(raw_input));
+    /// ```
+    ///
+    /// The filter runs at the end of [`Self::take_egui_input`].
+    pub fn set_input_filter(&mut self, filter: impl FnMut(&mut egui::RawInput) + Send + 'static) {
+        self.input_filter = Some(Box::new(filter));
+    }
+
+    /// Remove the filter set with [`Self::set_input_filter`].
+    pub fn clear_input_filter(&mut self) {
+        self.input_filter = None;
+    }
+
+    /// The current kiosk configuration, if any.
+    pub fn kiosk_mode(&self) -> Option<&KioskMode> {
+        self.kiosk_mode.as_ref()
+    }
+
+    /// Turn kiosk mode on or off for this window. See [`KioskMode`].
+    ///
+    /// Turning it off releases the screen saver inhibition, shows the cursor again,
+    /// and restores the keyboard shortcuts, but leaves the window as it is.
+    pub fn set_kiosk_mode(&mut self, window: &Window, kiosk_mode: Option<KioskMode>) {
+        let inhibit = kiosk_mode
+            .as_ref()
+            .map_or(false, |kiosk| kiosk.inhibit_screensaver);
+        if !inhibit {
+            self.kiosk_sleep_inhibitor = None;
+        } else if self.kiosk_sleep_inhibitor.is_none() {
+            self.kiosk_sleep_inhibitor =
+                SleepInhibitor::new(&window.title(), "Running in kiosk mode");
+        }
+
+        if let Some(kiosk) = &kiosk_mode {
+            kiosk.apply_to_window(window);
+        }
+        kiosk::update_shortcuts(
+            &self.egui_ctx,
+            kiosk_mode.as_ref(),
+            &mut self.zoom_with_keyboard_before_kiosk,
+        );
+
+        self.kiosk_mode = kiosk_mode;
+        self.last_pointer_activity = web_time::Instant::now();
+        self.current_cursor_icon = None; // make sure the cursor is updated
+        self.egui_ctx.request_repaint_of(self.viewport_id);
+    }
+
+    /// Record the [`egui::RawInput`] returned by [`Self::take_egui_input`] each frame.
+    ///
+    /// Set to `None` to stop recording.
+    #[cfg(feature = "recording")]
+    pub fn set_input_recorder(&mut self, recorder: Option<InputRecorder>) {
+        self.input_recorder = recorder;
+    }
+
+    /// Replay recorded input: until the replay is finished,
+    /// [`Self::take_egui_input`] returns the recorded frames, and the input of the window is ignored.
+    ///
+    /// Set to `None` to stop replaying.
+    #[cfg(feature = "recording")]
+    pub fn set_input_replay(&mut self, replay: Option<InputReplay>) {
+        self.input_replay = replay;
+        self.egui_ctx.request_repaint_of(self.viewport_id);
+    }
+
+    /// Are we currently replaying recorded input?
+    #[cfg(feature = "recording")]
+    pub fn is_replaying_input(&self) -> bool {
+        self.input_replay.is_some()
+    }
+
+    /// Record how frames are paced, to diagnose stutter. See [`FrameTelemetry`].
+    ///
+    /// For this to work, call [`Self::on_frame_presented`] after presenting each frame.
+    ///
+    /// Set to `None` to stop recording.
+    pub fn set_frame_telemetry(&mut self, telemetry: Option<FrameTelemetry>) {
+        self.frame_telemetry = telemetry;
+    }
+
+    /// The [`FrameTelemetry`] recorded so far, if enabled with [`Self::set_frame_telemetry`].
+    pub fn frame_telemetry(&self) -> Option<&FrameTelemetry> {
+        self.frame_telemetry.as_ref()
+    }
+
+    /// The [`FrameTelemetry`] recorded so far, if enabled with [`Self::set_frame_telemetry`].
+    pub fn frame_telemetry_mut(&mut self) -> Option<&mut FrameTelemetry> {
+        self.frame_telemetry.as_mut()
+    }
+
+    /// Call this right after the frame using the input from [`Self::take_egui_input`] has been presented.
+    ///
+    /// Only needed for [`Self::set_frame_telemetry`].
+    pub fn on_frame_presented(&mut self) {
+        if let Some(telemetry) = &mut self.frame_telemetry {
+            telemetry.on_frame_presented();
+        }
+    }
+
     #[inline]
     pub fn egui_ctx(&self) -> &egui::Context {
         &self.egui_ctx
@@ -234,6 +634,10 @@ impl State {
 
         self.egui_input.time = Some(self.start_time.elapsed().as_secs_f64());
 
+        if let Some(telemetry) = &mut self.frame_telemetry {
+            telemetry.on_frame_start(window);
+        }
+
         // On Windows, a minimized window will have 0 width and height.
         // See: https://github.com/rust-windowing/winit/issues/208
         // This solves an issue where egui window positions would be changed when minimizing on Windows.
@@ -252,9 +656,119 @@ impl State {
             .viewports
             .entry(self.viewport_id)
             .or_default()
-            .native_pixels_per_point = Some(window.scale_factor() as f32);
+            .native_pixels_per_point = Some(native_pixels_per_point(window));
+
+        self.repeat_held_key();
+
+        if self.last_high_contrast_check.elapsed() > std::time::Duration::from_secs(1) {
+            self.update_high_contrast();
+        }
+
+        let raw_input = self.egui_input.take();
+
+        #[cfg(feature = "recording")]
+        let mut raw_input = self.record_or_replay(raw_input);
+        #[cfg(not(feature = "recording"))]
+        let mut raw_input = raw_input;
+
+        if let Some(input_filter) = &mut self.input_filter {
+            input_filter(&mut raw_input);
+        }
+
+        raw_input
+    }
+
+    /// Work out the modifiers to attach to the events that follow, see [`modifier_keys::ModifierKeys`].
+    fn update_modifiers(&mut self) {
+        self.egui_input.modifiers = self.modifier_keys.apply(self.reported_modifiers);
+    }
 
-        self.egui_input.take()
+    /// Refresh [`ViewportInfo::monitors`] if the window may have moved to another monitor,
+    /// or the monitors may have changed, since the last call.
+    ///
+    /// winit doesn't tell us when monitors are (dis)connected or rearranged,
+    /// so we also ask the OS again every [`MONITORS_CHECK_INTERVAL`] while we are painting.
+    ///
+    /// If the monitors changed, this sends [`egui::ViewportEvent::MonitorsChanged`],
+    /// and moves the window to the primary monitor if it is no longer on any monitor.
+    ///
+    /// Call after [`update_viewport_info`].
+    pub fn update_monitors(&mut self, viewport_info: &mut ViewportInfo, window: &Window) {
+        let now = web_time::Instant::now();
+        let outdated = std::mem::take(&mut self.monitors_outdated);
+        if !is_monitors_check_due(outdated, self.last_monitors_check, now) {
+            return;
+        }
+        self.last_monitors_check = now;
+        profiling::function_scope!();
+
+        let monitors = monitors(window);
+        if !viewport_info.monitors.is_empty() && viewport_info.monitors != monitors {
+            log::debug!("Monitors changed: {monitors:?}");
+            viewport_info
+                .events
+                .push(egui::ViewportEvent::MonitorsChanged);
+
+            if let Some(outer_rect) = viewport_info.outer_rect {
+                let pixels_per_point = pixels_per_point(&self.egui_ctx, window);
+                let is_on_a_monitor = monitors
+                    .iter()
+                    .any(|monitor| (monitor.rect / pixels_per_point).intersects(outer_rect));
+                if !is_on_a_monitor {
+                    move_to_primary_monitor(window);
+                }
+            }
+        }
+        viewport_info.monitors = monitors;
+    }
+
+    /// Ask the OS again if the user wants more contrast.
+    fn update_high_contrast(&mut self) {
+        self.egui_input.system_high_contrast = high_contrast::system_high_contrast(&self.egui_ctx);
+        self.last_high_contrast_check = web_time::Instant::now();
+    }
+
+    /// Press the held key again if it is time, see [`Self::set_key_repeat`].
+    fn repeat_held_key(&mut self) {
+        let (Some(config), Some(held)) = (&self.key_repeat, &mut self.held_key) else {
+            return;
+        };
+
+        let (is_due, until_next) = held.poll(web_time::Instant::now(), config);
+        if is_due {
+            self.egui_input.events.push(egui::Event::Key {
+                key: held.key,
+                physical_key: held.physical_key,
+                logical_key: held.logical_key,
+                pressed: true,
+                repeat: true,
+                modifiers: self.egui_input.modifiers,
+            });
+        }
+        self.egui_ctx
+            .request_repaint_after_for(until_next, self.viewport_id);
+    }
+
+    #[cfg(feature = "recording")]
+    fn record_or_replay(&mut self, raw_input: egui::RawInput) -> egui::RawInput {
+        if let Some(replay) = &mut self.input_replay {
+            if let Some(recorded) = replay.next_frame() {
+                // Keep going until we run out of frames:
+                self.egui_ctx.request_repaint_of(self.viewport_id);
+                return recorded;
+            }
+            log::info!("Finished replaying recorded input");
+            self.input_replay = None;
+        }
+
+        if let Some(recorder) = &mut self.input_recorder {
+            if let Err(err) = recorder.record(&raw_input) {
+                log::warn!("Failed to record input, stopping the recording: {err}");
+                self.input_recorder = None;
+            }
+        }
+
+        raw_input
     }
 
     /// Call this when there is a new event.
@@ -273,9 +787,25 @@ impl State {
         }
 
         use winit::event::WindowEvent;
+
+        if let Some(telemetry) = &mut self.frame_telemetry {
+            telemetry.on_window_event(event);
+        }
+
+        if matches!(
+            event,
+            WindowEvent::CursorMoved { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::Touch(_)
+        ) {
+            self.last_pointer_activity = web_time::Instant::now();
+        }
+
         match event {
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
-                let native_pixels_per_point = *scale_factor as f32;
+                let native_pixels_per_point = sanitize_scale_factor(*scale_factor);
+                self.monitors_outdated = true;
 
                 self.egui_input
                     .viewports
@@ -358,17 +888,28 @@ impl State {
                     }
                     winit::event::Ime::Preedit(text, Some(_cursor)) => {
                         self.ime_event_enable();
+                        self.has_ime_preedit = true;
                         self.egui_input
                             .events
                             .push(egui::Event::Ime(egui::ImeEvent::Preedit(text.clone())));
                     }
                     winit::event::Ime::Commit(text) => {
-                        self.egui_input
-                            .events
-                            .push(egui::Event::Ime(egui::ImeEvent::Commit(text.clone())));
-                        self.ime_event_disable();
+                        if std::mem::take(&mut self.has_ime_preedit) {
+                            self.egui_input
+                                .events
+                                .push(egui::Event::Ime(egui::ImeEvent::Commit(text.clone())));
+                            self.ime_event_disable();
+                        } else if !text.is_empty() {
+                            // Committed without a composition, e.g. by the emoji picker.
+                            // `TextEdit` only accepts an IME commit that replaces a preedit,
+                            // so send it as normal text:
+                            self.egui_input.events.push(egui::Event::Text(text.clone()));
+                        }
                     }
                     winit::event::Ime::Disabled | winit::event::Ime::Preedit(_, None) => {
+                        // If this ends a composition without a commit, it was cancelled
+                        // (e.g. with Escape), so the uncommitted text must go:
+                        self.clear_ime_preedit();
                         self.ime_event_disable();
                     }
                 };
@@ -393,6 +934,16 @@ impl State {
                         consumed: false,
                     }
                 } else {
+                    if self.has_ime_preedit
+                        && event.state == ElementState::Pressed
+                        && event.logical_key
+                            == winit::keyboard::Key::Named(winit::keyboard::NamedKey::Escape)
+                    {
+                        // The IME didn't handle the Escape itself, so cancel the composition for it:
+                        self.clear_ime_preedit();
+                        self.cancel_ime_composition(window);
+                    }
+
                     self.on_keyboard_input(event);
 
                     // When pressing the Tab key, egui focuses the first focusable element, hence Tab always consumes.
@@ -407,6 +958,16 @@ impl State {
             }
             WindowEvent::Focused(focused) => {
                 self.egui_input.focused = *focused;
+                if *focused {
+                    // The user may have changed the setting, or plugged in a monitor, in the meantime:
+                    self.update_high_contrast();
+                    self.monitors_outdated = true;
+                } else {
+                    // We won't get the releases:
+                    self.held_key = None;
+                    self.modifier_keys.forget();
+                    self.update_modifiers();
+                }
                 self.egui_input
                     .events
                     .push(egui::Event::WindowFocused(*focused));
@@ -416,7 +977,14 @@ impl State {
                 }
             }
             WindowEvent::ThemeChanged(winit_theme) => {
-                self.egui_input.system_theme = Some(to_egui_theme(*winit_theme));
+                let theme = to_egui_theme(*winit_theme);
+                if self.egui_input.system_theme != Some(theme) {
+                    self.egui_input.system_theme = Some(theme);
+                    self.egui_input
+                        .events
+                        .push(egui::Event::SystemThemeChanged(theme));
+                }
+                self.update_high_contrast();
                 EventResponse {
                     repaint: true,
                     consumed: false,
@@ -451,22 +1019,10 @@ impl State {
                 }
             }
             WindowEvent::ModifiersChanged(state) => {
-                let state = state.state();
-
-                let alt = state.alt_key();
-                let ctrl = state.control_key();
-                let shift = state.shift_key();
-                let super_ = state.super_key();
-
-                self.egui_input.modifiers.alt = alt;
-                self.egui_input.modifiers.ctrl = ctrl;
-                self.egui_input.modifiers.shift = shift;
-                self.egui_input.modifiers.mac_cmd = cfg!(target_os = "macos") && super_;
-                self.egui_input.modifiers.command = if cfg!(target_os = "macos") {
-                    super_
-                } else {
-                    ctrl
-                };
+                self.reported_modifiers = modifier_keys::from_winit_state(state.state());
+                // If the key events and winit keep disagreeing about the modifiers, winit is probably right:
+                self.modifier_keys.sync(self.reported_modifiers);
+                self.update_modifiers();
 
                 EventResponse {
                     repaint: true,
@@ -474,13 +1030,21 @@ impl State {
                 }
             }
 
+            WindowEvent::Moved(_) => {
+                // Monitors being (dis)connected or rearranged usually moves the window.
+                self.monitors_outdated = true;
+                EventResponse {
+                    repaint: true,
+                    consumed: false,
+                }
+            }
+
             // Things that may require repaint:
             WindowEvent::RedrawRequested
             | WindowEvent::CursorEntered { .. }
             | WindowEvent::Destroyed
             | WindowEvent::Occluded(_)
             | WindowEvent::Resized(_)
-            | WindowEvent::Moved(_)
             | WindowEvent::TouchpadPressure { .. }
             | WindowEvent::CloseRequested => EventResponse {
                 repaint: true,
@@ -490,8 +1054,6 @@ impl State {
             // Things we completely ignore:
             WindowEvent::ActivationTokenDone { .. }
             | WindowEvent::AxisMotion { .. }
-            | WindowEvent::DoubleTapGesture { .. }
-            | WindowEvent::RotationGesture { .. }
             | WindowEvent::PanGesture { .. } => EventResponse {
                 repaint: false,
                 consumed: false,
@@ -507,6 +1069,24 @@ impl State {
                     consumed: self.egui_ctx.wants_pointer_input(),
                 }
             }
+
+            WindowEvent::RotationGesture { delta, .. } => {
+                self.egui_input
+                    .events
+                    .push(egui::Event::Rotate(rotation_from_winit(*delta)));
+                EventResponse {
+                    repaint: true,
+                    consumed: self.egui_ctx.wants_pointer_input(),
+                }
+            }
+
+            WindowEvent::DoubleTapGesture { .. } => {
+                self.egui_input.events.push(egui::Event::DoubleTapZoom);
+                EventResponse {
+                    repaint: true,
+                    consumed: self.egui_ctx.wants_pointer_input(),
+                }
+            }
         }
     }
 
@@ -526,11 +1106,39 @@ impl State {
         self.has_sent_ime_enabled = false;
     }
 
+    /// Tell the focused widget to remove the uncommitted text of the current IME composition, if any.
+    fn clear_ime_preedit(&mut self) {
+        if std::mem::take(&mut self.has_ime_preedit) {
+            self.egui_input
+                .events
+                .push(egui::Event::Ime(egui::ImeEvent::Preedit(String::new())));
+        }
+    }
+
+    /// Throw away the current IME composition, in egui as well as in the platform IME,
+    /// so that it isn't committed later.
+    fn cancel_ime_composition(&mut self, window: &Window) {
+        self.has_ime_preedit = false;
+        self.ime_event_disable();
+        if self.allow_ime {
+            // winit can't cancel a composition, but disabling the IME does:
+            profiling::scope!("set_ime_allowed");
+            window.set_ime_allowed(false);
+            window.set_ime_allowed(true);
+        }
+    }
+
     pub fn on_mouse_motion(&mut self, delta: (f64, f64)) {
-        self.egui_input.events.push(egui::Event::MouseMoved(Vec2 {
+        let delta = Vec2 {
             x: delta.0 as f32,
             y: delta.1 as f32,
-        }));
+        };
+
+        push_mouse_moved(
+            &mut self.egui_input.events,
+            self.pointer_move_coalescing,
+            delta,
+        );
     }
 
     /// Call this when there is a new [`accesskit::ActionRequest`].
@@ -559,6 +1167,22 @@ impl State {
                     modifiers: self.egui_input.modifiers,
                 });
 
+                // Middle-click pastes the primary selection into the `TextEdit` under the pointer:
+                #[cfg(feature = "primary-selection")]
+                if pressed
+                    && button == egui::PointerButton::Middle
+                    && self.mutable_text_under_cursor
+                {
+                    if let Some(contents) = self.clipboard.get_primary() {
+                        let contents = contents.replace("\r\n", "\n");
+                        if !contents.is_empty() {
+                            self.egui_input
+                                .events
+                                .push(egui::Event::PastePrimarySelection(contents));
+                        }
+                    }
+                }
+
                 if self.simulate_touch_screen {
                     if pressed {
                         self.any_pointer_button_down = true;
@@ -616,9 +1240,11 @@ impl State {
                 });
             }
         } else {
-            self.egui_input
-                .events
-                .push(egui::Event::PointerMoved(pos_in_points));
+            push_pointer_moved(
+                &mut self.egui_input.events,
+                self.pointer_move_coalescing,
+                pos_in_points,
+            );
         }
     }
 
@@ -734,12 +1360,17 @@ impl State {
             state,
 
             location: _, // e.g. is it on the numpad?
-            repeat: _,   // egui will figure this out for us
+            repeat, // egui will figure this out for us, but we need it for synthesized key repeats
             ..
         } = event;
 
         let pressed = *state == winit::event::ElementState::Pressed;
 
+        if let winit::keyboard::PhysicalKey::Code(keycode) = *physical_key {
+            self.modifier_keys.on_key(keycode, pressed);
+            self.update_modifiers();
+        }
+
         let physical_key = if let winit::keyboard::PhysicalKey::Code(keycode) = *physical_key {
             key_from_key_code(keycode)
         } else {
@@ -761,32 +1392,51 @@ impl State {
         // emit events as if the corresponding keys from the Latin layout were pressed. In this case, clipboard shortcuts
         // are mapped to the physical keys that normally contain C, X, V, etc.
         // See also: https://github.com/emilk/egui/issues/3653
-        if let Some(active_key) = logical_key.or(physical_key) {
+        // Shortcuts always use this, regardless of `self.key_resolution`.
+        if let Some(shortcut_key) = logical_key.or(physical_key) {
             if pressed {
-                if is_cut_command(self.egui_input.modifiers, active_key) {
-                    self.egui_input.events.push(egui::Event::Cut);
-                    return;
-                } else if is_copy_command(self.egui_input.modifiers, active_key) {
-                    self.egui_input.events.push(egui::Event::Copy);
-                    return;
-                } else if is_paste_command(self.egui_input.modifiers, active_key) {
-                    if let Some(contents) = self.clipboard.get() {
-                        let contents = contents.replace("\r\n", "\n");
-                        if !contents.is_empty() {
-                            self.egui_input.events.push(egui::Event::Paste(contents));
+                if let Some(action) = self
+                    .shortcut_map
+                    .find(self.egui_input.modifiers, shortcut_key)
+                {
+                    match action {
+                        ShortcutAction::Cut => {
+                            self.egui_input.events.push(egui::Event::Cut);
+                        }
+                        ShortcutAction::Copy => {
+                            self.egui_input.events.push(egui::Event::Copy);
+                        }
+                        ShortcutAction::Paste => {
+                            if let Some(contents) = self.clipboard.get() {
+                                let contents = contents.replace("\r\n", "\n");
+                                if !contents.is_empty() {
+                                    self.egui_input.events.push(egui::Event::Paste(contents));
+                                }
+                            }
+                        }
+                        ShortcutAction::Events(events) => {
+                            self.egui_input.events.extend(events.iter().cloned());
                         }
                     }
                     return;
                 }
             }
+        }
 
-            self.egui_input.events.push(egui::Event::Key {
-                key: active_key,
-                physical_key,
-                pressed,
-                repeat: false, // egui will fill this in for us!
-                modifiers: self.egui_input.modifiers,
-            });
+        let keys = self.key_resolution.keys(logical_key, physical_key);
+        if let Some(&active_key) = keys.first() {
+            for key in keys {
+                self.egui_input.events.push(egui::Event::Key {
+                    key,
+                    physical_key,
+                    logical_key,
+                    pressed,
+                    repeat: false, // egui will fill this in for us!
+                    modifiers: self.egui_input.modifiers,
+                });
+            }
+
+            self.update_held_key(active_key, physical_key, logical_key, pressed, *repeat);
         }
 
         if let Some(text) = &text {
@@ -809,6 +1459,39 @@ impl State {
         }
     }
 
+    /// Keep track of which key is held, for [`Self::set_key_repeat`].
+    fn update_held_key(
+        &mut self,
+        key: egui::Key,
+        physical_key: Option<egui::Key>,
+        logical_key: Option<egui::Key>,
+        pressed: bool,
+        os_repeat: bool,
+    ) {
+        let Some(config) = &self.key_repeat else {
+            return;
+        };
+
+        if pressed && !os_repeat {
+            self.held_key = config.keys.contains(&key).then(|| {
+                key_repeat::HeldKey::new(
+                    key,
+                    physical_key,
+                    logical_key,
+                    web_time::Instant::now(),
+                    config,
+                )
+            });
+            if self.held_key.is_some() {
+                self.egui_ctx
+                    .request_repaint_after_for(config.delay, self.viewport_id);
+            }
+        } else if os_repeat || self.held_key.is_some_and(|held| held.key == key) {
+            // Either the key was released, or the OS repeats it for us.
+            self.held_key = None;
+        }
+    }
+
     /// Call with the output given by `egui`.
     ///
     /// This will, if needed:
@@ -830,15 +1513,23 @@ impl State {
             cursor_icon,
             open_url,
             copied_text,
-            events: _,                    // handled elsewhere
-            mutable_text_under_cursor: _, // only used in eframe web
+            events: _, // handled elsewhere
+            mutable_text_under_cursor,
             ime,
+            autofill_fields: _, // only used in eframe web
             #[cfg(feature = "accesskit")]
             accesskit_update,
             num_completed_passes: _,    // `egui::Context::run` handles this
             request_discard_reasons: _, // `egui::Context::run` handles this
         } = platform_output;
 
+        #[cfg(feature = "primary-selection")]
+        {
+            self.mutable_text_under_cursor = mutable_text_under_cursor;
+        }
+        #[cfg(not(feature = "primary-selection"))]
+        let _ = mutable_text_under_cursor;
+
         for command in commands {
             match command {
                 egui::OutputCommand::CopyText(text) => {
@@ -847,12 +1538,22 @@ impl State {
                 egui::OutputCommand::CopyImage(image) => {
                     self.clipboard.set_image(&image);
                 }
+                egui::OutputCommand::SetPrimarySelection(text) => {
+                    #[cfg(feature = "primary-selection")]
+                    self.clipboard.set_primary_text(text);
+                    #[cfg(not(feature = "primary-selection"))]
+                    let _ = text;
+                }
                 egui::OutputCommand::OpenUrl(open_url) => {
                     open_url_in_browser(&open_url.url);
                 }
+                egui::OutputCommand::SubmitAutofill => {
+                    // Only browsers autofill.
+                }
             }
         }
 
+        let cursor_icon = self.kiosk_cursor_icon(cursor_icon);
         self.set_cursor_icon(window, cursor_icon);
 
         if let Some(open_url) = open_url {
@@ -863,6 +1564,14 @@ impl State {
             self.clipboard.set_text(copied_text);
         }
 
+        // A composition belongs to the widget it was started in.
+        // That widget removes the uncommitted text itself when it loses focus.
+        let focused = self.egui_ctx.memory(|mem| mem.focused());
+        if self.has_ime_preedit && (ime.is_none() || focused != self.ime_focus) {
+            self.cancel_ime_composition(window);
+        }
+        self.ime_focus = focused;
+
         let allow_ime = ime.is_some();
         if self.allow_ime != allow_ime {
             self.allow_ime = allow_ime;
@@ -871,8 +1580,10 @@ impl State {
         }
 
         if let Some(ime) = ime {
+            // The IME candidate window and the emoji picker are placed next to this area,
+            // so it should be the text cursor, not the whole `TextEdit`.
             let pixels_per_point = pixels_per_point(&self.egui_ctx, window);
-            let ime_rect_px = pixels_per_point * ime.rect;
+            let ime_rect_px = pixels_per_point * ime.cursor_rect;
             if self.ime_rect_px != Some(ime_rect_px)
                 || self.egui_ctx.input(|i| !i.events.is_empty())
             {
@@ -902,6 +1613,27 @@ impl State {
         }
     }
 
+    /// In kiosk mode, hide the cursor after a while without pointer activity.
+    fn kiosk_cursor_icon(&self, cursor_icon: egui::CursorIcon) -> egui::CursorIcon {
+        let Some(hide_after) = self
+            .kiosk_mode
+            .as_ref()
+            .and_then(|kiosk| kiosk.hide_cursor_after)
+        else {
+            return cursor_icon;
+        };
+
+        let idle = self.last_pointer_activity.elapsed();
+        if hide_after <= idle {
+            egui::CursorIcon::None
+        } else {
+            // Wake up in time to hide it:
+            self.egui_ctx
+                .request_repaint_after_for(hide_after - idle, self.viewport_id);
+            cursor_icon
+        }
+    }
+
     fn set_cursor_icon(&mut self, window: &Window, cursor_icon: egui::CursorIcon) {
         if self.current_cursor_icon == Some(cursor_icon) {
             // Prevent flickering near frame boundary when Windows OS tries to control cursor icon for window resizing.
@@ -962,6 +1694,9 @@ pub fn outer_rect_in_points(window: &Window, pixels_per_point: f32) -> Option<Re
 /// Call before [`State::take_egui_input`].
 ///
 /// If this is called right after window creation, `is_init` should be `true`, otherwise `false`.
+///
+/// Asking the OS for all monitors is slow, so [`ViewportInfo::monitors`] is only filled in when `is_init` is `true`.
+/// Use [`State::update_monitors`] to keep it up to date.
 pub fn update_viewport_info(
     viewport_info: &mut ViewportInfo,
     egui_ctx: &egui::Context,
@@ -998,8 +1733,13 @@ pub fn update_viewport_info(
         }
     };
 
+    if is_init {
+        profiling::scope!("monitors");
+        viewport_info.monitors = monitors(window);
+    }
+
     viewport_info.title = Some(window.title());
-    viewport_info.native_pixels_per_point = Some(window.scale_factor() as f32);
+    viewport_info.native_pixels_per_point = Some(native_pixels_per_point(window));
 
     viewport_info.monitor_size = monitor_size;
     viewport_info.inner_rect = inner_rect;
@@ -1015,6 +1755,56 @@ pub fn update_viewport_info(
 
     viewport_info.fullscreen = Some(window.fullscreen().is_some());
     viewport_info.focused = Some(window.has_focus());
+
+    native_menu::take_activated(window.id(), viewport_info);
+}
+
+/// How often [`State::update_monitors`] asks the OS for the monitors,
+/// to notice the changes winit doesn't tell us about.
+pub const MONITORS_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+fn is_monitors_check_due(
+    outdated: bool,
+    last_check: web_time::Instant,
+    now: web_time::Instant,
+) -> bool {
+    outdated || MONITORS_CHECK_INTERVAL <= now.saturating_duration_since(last_check)
+}
+
+fn monitors(window: &Window) -> Vec<egui::MonitorInfo> {
+    window
+        .available_monitors()
+        .map(|monitor| {
+            let pos = monitor.position();
+            let size = monitor.size();
+            let min = egui::pos2(pos.x as f32, pos.y as f32);
+            let size = egui::vec2(size.width as f32, size.height as f32);
+            egui::MonitorInfo {
+                name: monitor.name(),
+                rect: Rect::from_min_size(min, size),
+                native_pixels_per_point: sanitize_scale_factor(monitor.scale_factor()),
+            }
+        })
+        .collect()
+}
+
+/// Used when the monitor a window was on has been disconnected.
+fn move_to_primary_monitor(window: &Window) {
+    let Some(monitor) = window
+        .primary_monitor()
+        .or_else(|| window.available_monitors().next())
+    else {
+        return;
+    };
+
+    let size = window.outer_size();
+    let monitor_pos = monitor.position();
+    let monitor_size = monitor.size();
+    let x = monitor_pos.x + (monitor_size.width.saturating_sub(size.width) / 2) as i32;
+    let y = monitor_pos.y + (monitor_size.height.saturating_sub(size.height) / 2) as i32;
+
+    log::info!("The window ended up outside of all monitors; moving it to the primary monitor");
+    window.set_outer_position(PhysicalPosition::new(x, y));
 }
 
 fn open_url_in_browser(_url: &str) {
@@ -1041,24 +1831,6 @@ fn is_printable_char(chr: char) -> bool {
     !is_in_private_use_area && !chr.is_ascii_control()
 }
 
-fn is_cut_command(modifiers: egui::Modifiers, keycode: egui::Key) -> bool {
-    keycode == egui::Key::Cut
-        || (modifiers.command && keycode == egui::Key::X)
-        || (cfg!(target_os = "windows") && modifiers.shift && keycode == egui::Key::Delete)
-}
-
-fn is_copy_command(modifiers: egui::Modifiers, keycode: egui::Key) -> bool {
-    keycode == egui::Key::Copy
-        || (modifiers.command && keycode == egui::Key::C)
-        || (cfg!(target_os = "windows") && modifiers.ctrl && keycode == egui::Key::Insert)
-}
-
-fn is_paste_command(modifiers: egui::Modifiers, keycode: egui::Key) -> bool {
-    keycode == egui::Key::Paste
-        || (modifiers.command && keycode == egui::Key::V)
-        || (cfg!(target_os = "windows") && modifiers.shift && keycode == egui::Key::Insert)
-}
-
 fn translate_mouse_button(button: winit::event::MouseButton) -> Option<egui::PointerButton> {
     match button {
         winit::event::MouseButton::Left => Some(egui::PointerButton::Primary),
@@ -1333,6 +2105,24 @@ pub fn process_viewport_commands(
     }
 }
 
+/// [`ViewportCommand::Close`] queues a [`egui::ViewportEvent::Close`] for the next frame,
+/// and [`ViewportCommand::CancelClose`] removes it again.
+///
+/// Canceling a close request from the user is handled by the integration,
+/// which looks for [`ViewportCommand::CancelClose`] in the output of the frame with the `Close` event.
+fn process_close_command(command: &ViewportCommand, info: &mut ViewportInfo) {
+    match command {
+        ViewportCommand::Close => {
+            info.events.push(egui::ViewportEvent::Close);
+        }
+        ViewportCommand::CancelClose => {
+            info.events
+                .retain(|event| event != &egui::ViewportEvent::Close);
+        }
+        _ => {}
+    }
+}
+
 fn process_viewport_command(
     egui_ctx: &egui::Context,
     window: &Window,
@@ -1349,11 +2139,8 @@ fn process_viewport_command(
     let pixels_per_point = pixels_per_point(egui_ctx, window);
 
     match command {
-        ViewportCommand::Close => {
-            info.events.push(egui::ViewportEvent::Close);
-        }
-        ViewportCommand::CancelClose => {
-            // Need to be handled elsewhere
+        ViewportCommand::Close | ViewportCommand::CancelClose => {
+            process_close_command(&command, info);
         }
         ViewportCommand::StartDrag => {
             // If `.has_focus()` is not checked on x11 the input will be permanently taken until the app is killed!
@@ -1525,6 +2312,10 @@ fn process_viewport_command(
             }
         }
         ViewportCommand::CursorVisible(v) => window.set_cursor_visible(v),
+        ViewportCommand::RenderScale(render_scale) => {
+            // Handled by the renderer
+            info.render_scale = Some(render_scale.into_inner());
+        }
         ViewportCommand::MousePassthrough(passthrough) => {
             if let Err(err) = window.set_cursor_hittest(!passthrough) {
                 log::warn!("{command:?}: {err}");
@@ -1542,6 +2333,19 @@ fn process_viewport_command(
         ViewportCommand::RequestPaste => {
             actions_requested.insert(ActionRequested::Paste);
         }
+        ViewportCommand::ShowEmojiPicker => {
+            // The picker is placed at the IME cursor area, which `State::handle_platform_output`
+            // keeps at the text cursor of the focused `TextEdit`.
+            if window.has_focus() {
+                emoji_picker::show_emoji_picker();
+            }
+        }
+        ViewportCommand::SetNativeMenu(menu) => {
+            native_menu::set_native_menu(window, &menu, info);
+        }
+        ViewportCommand::User(user_data) => {
+            info.user_commands.push(user_data);
+        }
     }
 }
 
@@ -1584,7 +2388,7 @@ pub fn create_winit_window_attributes(
                 log::debug!("Failed to find a monitor - assuming native_pixels_per_point of 1.0");
                 1.0
             },
-            |m| m.scale_factor() as f32,
+            |m| sanitize_scale_factor(m.scale_factor()),
         );
     let zoom_factor = egui_ctx.zoom_factor();
     let pixels_per_point = zoom_factor * native_pixels_per_point;
@@ -1624,8 +2428,13 @@ pub fn create_winit_window_attributes(
         // x11
         window_type: _window_type,
 
+        window_group: _window_group,
+        owner: _, // Handled by `with_owner_window`
+        window_tabbing: _window_tabbing,
+
         mouse_passthrough: _, // handled in `apply_viewport_builder_to_window`
         clamp_size_to_monitor_size: _, // Handled in `viewport_builder` in `epi_integration.rs`
+        render_scale: _,      // Handled by the renderer
     } = viewport_builder;
 
     let mut window_attributes = winit::window::WindowAttributes::default()
@@ -1714,6 +2523,15 @@ pub fn create_winit_window_attributes(
         window_attributes = window_attributes.with_name(app_id, "");
     }
 
+    #[cfg(all(feature = "x11", target_os = "linux"))]
+    if let Some(group) = &_window_group {
+        use winit::platform::x11::{ActiveEventLoopExtX11 as _, WindowAttributesExtX11 as _};
+        // On Wayland this would replace the `app_id`:
+        if event_loop.is_x11() {
+            window_attributes = window_attributes.with_name(group, group);
+        }
+    }
+
     #[cfg(all(feature = "x11", target_os = "linux"))]
     {
         if let Some(window_type) = _window_type {
@@ -1757,11 +2575,100 @@ pub fn create_winit_window_attributes(
             .with_titlebar_buttons_hidden(!_titlebar_buttons_shown.unwrap_or(true))
             .with_titlebar_transparent(!_titlebar_shown.unwrap_or(true))
             .with_fullsize_content_view(_fullsize_content_view.unwrap_or(false));
+        if let Some(group) = &_window_group {
+            window_attributes = window_attributes.with_tabbing_identifier(group);
+        }
+
+        // This is app-wide, and must be set before the window is created:
+        if let Some(enabled) = _window_tabbing {
+            use winit::platform::macos::ActiveEventLoopExtMacOS as _;
+            event_loop.set_allows_automatic_window_tabbing(enabled);
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", all(feature = "x11", target_os = "linux"))))]
+    {
+        _ = _window_group;
+        _ = _window_tabbing;
+    }
+
+    window_attributes
+}
+
+/// Make the window owned by `owner`, as requested by [`ViewportBuilder::with_owner`].
+///
+/// Only supported on Windows and macOS.
+/// On Linux (both X11 and Wayland) and other platforms this does nothing, and the window is a normal top-level window.
+///
+/// Create the windows in the order given by [`owners_first`], so that the owner window exists.
+pub fn with_owner_window(
+    window_attributes: winit::window::WindowAttributes,
+    owner: &Window,
+) -> winit::window::WindowAttributes {
+    use raw_window_handle::HasWindowHandle as _;
+
+    let handle = match owner.window_handle() {
+        Ok(handle) => handle.as_raw(),
+        Err(err) => {
+            log::warn!("Failed to get the handle of the owner window: {err}");
+            return window_attributes;
+        }
+    };
+
+    #[cfg(target_os = "windows")]
+    if let raw_window_handle::RawWindowHandle::Win32(handle) = handle {
+        use winit::platform::windows::WindowAttributesExtWindows as _;
+        return window_attributes.with_owner_window(handle.hwnd.get());
     }
 
+    #[cfg(target_os = "macos")]
+    #[allow(unsafe_code)]
+    if let raw_window_handle::RawWindowHandle::AppKit(_) = handle {
+        // SAFETY: the handle comes from a live window.
+        // On macOS this makes a child window, which is not confined to its parent like on other platforms.
+        return unsafe { window_attributes.with_parent_window(Some(handle)) };
+    }
+
+    log::debug!("Owned windows are not supported for {handle:?}");
     window_attributes
 }
 
+/// The order in which to create the windows of the given viewports,
+/// so that the owner of a viewport (see [`ViewportBuilder::with_owner`]) gets its window first.
+///
+/// Takes each viewport together with its owner, if any.
+/// Owners that are not among the given viewports are ignored, and so are ownership cycles.
+pub fn owners_first(
+    viewports: impl IntoIterator<Item = (ViewportId, Option<ViewportId>)>,
+) -> Vec<ViewportId> {
+    fn visit(
+        id: ViewportId,
+        owners: &egui::ViewportIdMap<Option<ViewportId>>,
+        visited: &mut egui::ViewportIdSet,
+        order: &mut Vec<ViewportId>,
+    ) {
+        if !visited.insert(id) {
+            return; // Already ordered, or a cycle
+        }
+        if let Some(&Some(owner)) = owners.get(&id) {
+            if owners.contains_key(&owner) {
+                visit(owner, owners, visited, order);
+            }
+        }
+        order.push(id);
+    }
+
+    let viewports: Vec<_> = viewports.into_iter().collect();
+    let owners: egui::ViewportIdMap<Option<ViewportId>> = viewports.iter().copied().collect();
+
+    let mut visited = egui::ViewportIdSet::default();
+    let mut order = Vec::with_capacity(viewports.len());
+    for (id, _) in viewports {
+        visit(id, &owners, &mut visited, &mut order);
+    }
+    order
+}
+
 fn to_winit_icon(icon: &egui::IconData) -> Option<winit::window::Icon> {
     if icon.is_empty() {
         None
@@ -1848,6 +2755,14 @@ pub fn short_device_event_description(event: &winit::event::DeviceEvent) -> &'st
     }
 }
 
+/// A [`winit::event::WindowEvent::RotationGesture`] delta in radians, the way egui wants it.
+///
+/// winit: positive values are counterclockwise, in degrees.
+/// egui: positive values are clockwise, in radians.
+fn rotation_from_winit(delta_degrees: f32) -> f32 {
+    -delta_degrees.to_radians()
+}
+
 /// Short and fast description of a window event.
 /// Useful for logging and profiling.
 pub fn short_window_event_description(event: &winit::event::WindowEvent) -> &'static str {
@@ -1884,3 +2799,192 @@ pub fn short_window_event_description(event: &winit::event::WindowEvent) -> &'st
         WindowEvent::PanGesture { .. } => "WindowEvent::PanGesture",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sleep_inhibitor_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<SleepInhibitor>();
+    }
+
+    #[test]
+    fn state_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<State>();
+    }
+
+    #[test]
+    fn sanitize_scale_factor_replaces_nonsense() {
+        assert_eq!(sanitize_scale_factor(1.5), 1.5);
+        assert_eq!(sanitize_scale_factor(0.0), 1.0);
+        assert_eq!(sanitize_scale_factor(-2.0), 1.0);
+        assert_eq!(sanitize_scale_factor(f64::NAN), 1.0);
+        assert_eq!(sanitize_scale_factor(f64::INFINITY), 1.0);
+        assert_eq!(sanitize_scale_factor(1e-9), 0.1);
+        assert_eq!(sanitize_scale_factor(1e9), 32.0);
+    }
+
+    #[test]
+    fn key_resolution() {
+        use egui::Key;
+
+        // 'Z' on a French AZERTY keyboard is where 'W' is on QWERTY:
+        let (logical, physical) = (Some(Key::Z), Some(Key::W));
+        assert_eq!(
+            KeyResolution::LogicalFirst.keys(logical, physical),
+            [Key::Z]
+        );
+        assert_eq!(
+            KeyResolution::PhysicalFirst.keys(logical, physical),
+            [Key::W]
+        );
+        assert_eq!(
+            KeyResolution::Both.keys(logical, physical),
+            [Key::Z, Key::W]
+        );
+
+        // The same key is only sent once:
+        for resolution in [
+            KeyResolution::LogicalFirst,
+            KeyResolution::PhysicalFirst,
+            KeyResolution::Both,
+        ] {
+            assert_eq!(resolution.keys(Some(Key::A), Some(Key::A)), [Key::A]);
+        }
+
+        // A non-Latin letter has no logical key:
+        assert_eq!(KeyResolution::LogicalFirst.keys(None, physical), [Key::W]);
+        assert_eq!(KeyResolution::Both.keys(None, physical), [Key::W]);
+
+        // Unknown position:
+        assert_eq!(KeyResolution::PhysicalFirst.keys(logical, None), [Key::Z]);
+        assert_eq!(KeyResolution::Both.keys(logical, None), [Key::Z]);
+
+        assert!(KeyResolution::Both.keys(None, None).is_empty());
+    }
+
+    #[test]
+    fn rotation_is_clockwise_in_radians() {
+        assert_eq!(rotation_from_winit(0.0), 0.0);
+        assert!((rotation_from_winit(-90.0) - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert!((rotation_from_winit(180.0) + std::f32::consts::PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cancel_close_removes_close_sent_the_same_frame() {
+        let child = egui::ViewportEvent::ChildDestroyed(ViewportId::from_hash_of("child"));
+        let mut info = ViewportInfo::default();
+        info.events.push(child);
+
+        process_close_command(&ViewportCommand::Close, &mut info);
+        assert!(info.close_requested());
+
+        process_close_command(&ViewportCommand::CancelClose, &mut info);
+        assert!(!info.close_requested());
+        assert_eq!(info.events, vec![child]);
+
+        // A close sent after the cancel still goes through:
+        process_close_command(&ViewportCommand::Close, &mut info);
+        assert!(info.close_requested());
+    }
+
+    #[test]
+    fn cancel_close_without_close_is_a_no_op() {
+        let mut info = ViewportInfo::default();
+        info.events.push(egui::ViewportEvent::MonitorsChanged);
+
+        process_close_command(&ViewportCommand::CancelClose, &mut info);
+        assert_eq!(info.events, vec![egui::ViewportEvent::MonitorsChanged]);
+    }
+
+    #[test]
+    fn pointer_moves_are_coalesced_only_when_asked() {
+        use egui::{pos2, vec2, Event};
+
+        let moves = [pos2(1.0, 1.0), pos2(2.0, 2.0), pos2(3.0, 3.0)];
+
+        let mut events = vec![];
+        for pos in moves {
+            push_pointer_moved(&mut events, PointerMoveCoalescing::default(), pos);
+        }
+        assert_eq!(events, moves.map(Event::PointerMoved));
+
+        // The first and the latest move of a burst are kept:
+        let mut events = vec![Event::PointerGone];
+        for pos in moves {
+            push_pointer_moved(&mut events, PointerMoveCoalescing::KeepLast, pos);
+        }
+        assert_eq!(
+            events,
+            vec![
+                Event::PointerGone,
+                Event::PointerMoved(moves[0]),
+                Event::PointerMoved(moves[2]),
+            ]
+        );
+
+        // No relative motion is lost:
+        let mut events = vec![];
+        push_mouse_moved(&mut events, PointerMoveCoalescing::KeepLast, vec2(1.0, 2.0));
+        push_mouse_moved(&mut events, PointerMoveCoalescing::KeepLast, vec2(3.0, 4.0));
+        assert_eq!(events, vec![Event::MouseMoved(vec2(4.0, 6.0))]);
+    }
+
+    #[test]
+    fn pointer_velocity_is_the_same_with_coalescing() {
+        use egui::{pos2, RawInput};
+
+        fn velocity(coalescing: PointerMoveCoalescing) -> Vec2 {
+            let ctx = egui::Context::default();
+            for frame in 0..10 {
+                let mut events = vec![];
+                for step in 0..20 {
+                    let x = (frame * 20 + step) as f32;
+                    push_pointer_moved(&mut events, coalescing, pos2(x, 2.0 * x));
+                }
+                let input = RawInput {
+                    time: Some(frame as f64 / 60.0),
+                    events,
+                    ..Default::default()
+                };
+                let _ = ctx.run(input, |_| {});
+            }
+            ctx.input(|i| i.pointer.velocity())
+        }
+
+        let velocity_off = velocity(PointerMoveCoalescing::Off);
+        assert!(velocity_off.length() > 0.0);
+        assert_eq!(velocity_off, velocity(PointerMoveCoalescing::KeepLast));
+    }
+
+    #[test]
+    fn owners_get_their_windows_first() {
+        let [a, b, c, d] = ["a", "b", "c", "d"].map(ViewportId::from_hash_of);
+
+        // c is owned by b, which is owned by a:
+        let order = owners_first([(c, Some(b)), (a, None), (b, Some(a)), (d, None)]);
+        assert_eq!(order, vec![a, b, c, d]);
+
+        // Unknown owners are ignored:
+        let order = owners_first([(b, Some(c)), (a, None)]);
+        assert_eq!(order, vec![b, a]);
+
+        // Cycles don't hang, and every viewport is still listed once:
+        let order = owners_first([(a, Some(b)), (b, Some(a))]);
+        assert_eq!(order, vec![b, a]);
+    }
+
+    #[test]
+    fn monitors_are_checked_when_outdated_or_on_a_timer() {
+        let last_check = web_time::Instant::now();
+        let soon = last_check + MONITORS_CHECK_INTERVAL / 2;
+        let later = last_check + MONITORS_CHECK_INTERVAL;
+
+        assert!(!is_monitors_check_due(false, last_check, soon));
+        assert!(is_monitors_check_due(true, last_check, soon));
+        assert!(is_monitors_check_due(false, last_check, later));
+    }
+}
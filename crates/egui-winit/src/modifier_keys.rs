@@ -0,0 +1,211 @@
+//! Keep track of the modifier keys using the key events themselves.
+
+use winit::keyboard::{KeyCode, ModifiersState};
+
+const SHIFT: u8 = 0b0000_0011;
+const CTRL: u8 = 0b0000_1100;
+const ALT: u8 = 0b0011_0000;
+const SUPER: u8 = 0b1100_0000;
+
+/// Which modifier keys are held down, according to the [`winit::event::WindowEvent::KeyboardInput`] events.
+///
+/// winit reports the modifiers in a separate [`winit::event::WindowEvent::ModifiersChanged`],
+/// which some platforms deliver before or after the key events it belongs to.
+/// Trusting it alone turns e.g. a late `C` press of a `Ctrl+C` into a plain `C`,
+/// if the release of `Ctrl` was reported first.
+///
+/// The key presses and releases themselves always arrive in order, so once we have seen a key event
+/// for a modifier (say `ControlLeft`), that is what we report with the events that follow,
+/// rather than the last reported modifier state.
+/// We go back to trusting the reported state for a modifier when two reports in a row disagree with
+/// the key events (without a key event for it in between), and when losing focus,
+/// so that a missed key release can't leave a modifier stuck.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ModifierKeys {
+    /// One bit per key (left and right), see [`key_bit`].
+    held: u8,
+
+    /// Which modifiers (both bits of a pair) we have seen key events for.
+    known: u8,
+
+    /// Which modifiers (both bits of a pair) the last reported state disagreed with us about.
+    suspect: u8,
+}
+
+impl ModifierKeys {
+    /// Update with a key event. Does nothing if the key isn't a modifier key.
+    pub fn on_key(&mut self, key: KeyCode, pressed: bool) {
+        let Some(bit) = key_bit(key) else {
+            return;
+        };
+        if pressed {
+            self.held |= bit;
+        } else {
+            self.held &= !bit;
+        }
+        self.known |= pair_mask(bit);
+        self.suspect &= !pair_mask(bit);
+    }
+
+    /// Forget everything, e.g. because we won't see the key releases while unfocused.
+    pub fn forget(&mut self) {
+        *self = Self::default();
+    }
+
+    /// The modifiers to attach to an event, given the last state reported by winit.
+    pub fn apply(&self, reported: egui::Modifiers) -> egui::Modifiers {
+        let is_held = |mask: u8, reported: bool| {
+            if self.known & mask == 0 {
+                reported
+            } else {
+                self.held & mask != 0
+            }
+        };
+        let reported_super = if cfg!(target_os = "macos") {
+            reported.mac_cmd
+        } else {
+            // We don't keep track of super outside of mac, see `from_winit_state`:
+            false
+        };
+
+        let mut state = ModifiersState::empty();
+        state.set(ModifiersState::ALT, is_held(ALT, reported.alt));
+        state.set(ModifiersState::CONTROL, is_held(CTRL, reported.ctrl));
+        state.set(ModifiersState::SHIFT, is_held(SHIFT, reported.shift));
+        state.set(ModifiersState::SUPER, is_held(SUPER, reported_super));
+        from_winit_state(state)
+    }
+
+    /// Stop trusting the key events for the modifiers where they disagree with the reported state,
+    /// if the previous report disagreed too.
+    ///
+    /// A single disagreement is expected when the report arrives before the key event.
+    ///
+    /// Call this on each [`winit::event::WindowEvent::ModifiersChanged`].
+    pub fn sync(&mut self, reported: egui::Modifiers) {
+        let ours = self.apply(reported);
+        let mut disagree = 0;
+        for (mask, ours, reported) in [
+            (ALT, ours.alt, reported.alt),
+            (CTRL, ours.ctrl, reported.ctrl),
+            (SHIFT, ours.shift, reported.shift),
+        ] {
+            if ours != reported {
+                disagree |= mask;
+            }
+        }
+        if cfg!(target_os = "macos") && ours.mac_cmd != reported.mac_cmd {
+            disagree |= SUPER;
+        }
+
+        let stale = disagree & self.suspect;
+        self.known &= !stale;
+        self.held &= !stale;
+        self.suspect = disagree & !stale;
+    }
+}
+
+/// The [`egui::Modifiers`] corresponding to the state reported by winit.
+pub(crate) fn from_winit_state(state: ModifiersState) -> egui::Modifiers {
+    let ctrl = state.control_key();
+    let super_ = state.super_key();
+    egui::Modifiers {
+        alt: state.alt_key(),
+        ctrl,
+        shift: state.shift_key(),
+        mac_cmd: cfg!(target_os = "macos") && super_,
+        command: if cfg!(target_os = "macos") {
+            super_
+        } else {
+            ctrl
+        },
+    }
+}
+
+fn key_bit(key: KeyCode) -> Option<u8> {
+    Some(match key {
+        KeyCode::ShiftLeft => 0b0000_0001,
+        KeyCode::ShiftRight => 0b0000_0010,
+        KeyCode::ControlLeft => 0b0000_0100,
+        KeyCode::ControlRight => 0b0000_1000,
+        KeyCode::AltLeft => 0b0001_0000,
+        KeyCode::AltRight => 0b0010_0000,
+        KeyCode::SuperLeft => 0b0100_0000,
+        KeyCode::SuperRight => 0b1000_0000,
+        _ => return None,
+    })
+}
+
+/// Both bits of the left/right pair the given key bit belongs to.
+fn pair_mask(bit: u8) -> u8 {
+    [SHIFT, CTRL, ALT, SUPER]
+        .into_iter()
+        .find(|mask| mask & bit != 0)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctrl() -> egui::Modifiers {
+        from_winit_state(ModifiersState::CONTROL)
+    }
+
+    #[test]
+    fn early_release_is_ignored_until_the_key_event() {
+        let mut keys = ModifierKeys::default();
+
+        keys.on_key(KeyCode::ControlLeft, true);
+        // winit already reports the release of ctrl…
+        let reported = egui::Modifiers::NONE;
+        // …but the `C` press that came before it still gets ctrl:
+        assert_eq!(keys.apply(reported), ctrl());
+
+        keys.on_key(KeyCode::ControlLeft, false);
+        assert_eq!(keys.apply(reported), egui::Modifiers::NONE);
+    }
+
+    #[test]
+    fn late_report_is_not_needed() {
+        let mut keys = ModifierKeys::default();
+        keys.on_key(KeyCode::ControlRight, true);
+        assert_eq!(keys.apply(egui::Modifiers::NONE), ctrl());
+
+        // Releasing one side keeps it held:
+        keys.on_key(KeyCode::ControlLeft, true);
+        keys.on_key(KeyCode::ControlRight, false);
+        assert_eq!(keys.apply(egui::Modifiers::NONE), ctrl());
+    }
+
+    #[test]
+    fn falls_back_to_reported() {
+        let mut keys = ModifierKeys::default();
+        assert_eq!(keys.apply(ctrl()), ctrl());
+
+        // A missed key release doesn't leave ctrl stuck beyond the next report:
+        keys.on_key(KeyCode::ControlLeft, true);
+        keys.sync(egui::Modifiers::NONE);
+        assert_eq!(keys.apply(egui::Modifiers::NONE), ctrl());
+        keys.sync(egui::Modifiers::NONE);
+        assert_eq!(keys.apply(egui::Modifiers::NONE), egui::Modifiers::NONE);
+    }
+
+    #[test]
+    fn early_report_is_not_trusted_over_the_key_event() {
+        let mut keys = ModifierKeys::default();
+
+        keys.on_key(KeyCode::ControlLeft, true);
+        keys.sync(ctrl());
+        // The release of ctrl is reported before the `C` press and the release of ctrl:
+        keys.sync(egui::Modifiers::NONE);
+        assert_eq!(keys.apply(egui::Modifiers::NONE), ctrl());
+        keys.on_key(KeyCode::ControlLeft, false);
+        assert_eq!(keys.apply(egui::Modifiers::NONE), egui::Modifiers::NONE);
+
+        // The key event cleared the suspicion, so the next early report is tolerated too:
+        keys.on_key(KeyCode::ControlLeft, true);
+        keys.sync(egui::Modifiers::NONE);
+        assert_eq!(keys.apply(egui::Modifiers::NONE), ctrl());
+    }
+}
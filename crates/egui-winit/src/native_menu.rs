@@ -0,0 +1,488 @@
+//! Menus of the operating system, for [`egui::ViewportCommand::SetNativeMenu`].
+//!
+//! winit has no API for this, so we talk to the OS directly.
+
+use std::sync::LazyLock;
+
+use egui::{mutex::Mutex, Id, MenuSpec, ViewportEvent, ViewportInfo};
+use winit::window::{Window, WindowId};
+
+/// Items picked in menus that don't block until the user is done (the Mac menu bar),
+/// waiting to be delivered by [`take_activated`].
+static ACTIVATED: LazyLock<Mutex<Vec<(WindowId, Id)>>> = LazyLock::new(Default::default);
+
+/// Send the [`ViewportEvent::MenuItemActivated`] for the items picked in the menus of `window_id`
+/// since last time.
+pub(crate) fn take_activated(window_id: WindowId, info: &mut ViewportInfo) {
+    ACTIVATED.lock().retain(|&(window, id)| {
+        if window == window_id {
+            info.events.push(ViewportEvent::MenuItemActivated(id));
+            false
+        } else {
+            true
+        }
+    });
+}
+
+/// Show a popup menu, or set the menu bar.
+///
+/// Must be called on the main thread.
+#[cfg(windows)]
+pub(crate) fn set_native_menu(window: &Window, menu: &MenuSpec, info: &mut ViewportInfo) {
+    if !menu.popup {
+        log::warn!("SetNativeMenu: only popup menus are supported on Windows");
+        return;
+    }
+    let Some(hwnd) = windows::hwnd(window) else {
+        log::warn!("SetNativeMenu: failed to get the handle of the window");
+        return;
+    };
+
+    // The user has picked an item (or not) when this returns:
+    if let Some(id) = windows::show_popup(hwnd, &menu.items) {
+        info.events.push(ViewportEvent::MenuItemActivated(id));
+        window.request_redraw();
+    }
+}
+
+/// Show a popup menu, or set the menu bar.
+///
+/// Must be called on the main thread.
+#[cfg(target_os = "macos")]
+pub(crate) fn set_native_menu(window: &Window, menu: &MenuSpec, _info: &mut ViewportInfo) {
+    mac::set_native_menu(window, menu);
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub(crate) fn set_native_menu(_window: &Window, _menu: &MenuSpec, _info: &mut ViewportInfo) {
+    log::warn!("SetNativeMenu: native menus are only supported on Mac and Windows");
+}
+
+#[cfg(windows)]
+#[allow(unsafe_code)]
+mod windows {
+    use egui::{Id, ModifierNames, NativeMenuItem};
+    use windows_sys::Win32::{
+        Foundation::{HWND, POINT},
+        UI::WindowsAndMessaging::{
+            AppendMenuW, CreatePopupMenu, DestroyMenu, GetCursorPos, SetForegroundWindow,
+            TrackPopupMenu, HMENU, MF_CHECKED, MF_GRAYED, MF_POPUP, MF_SEPARATOR, MF_STRING,
+            TPM_RETURNCMD, TPM_RIGHTBUTTON,
+        },
+    };
+    use winit::window::Window;
+
+    pub fn hwnd(window: &Window) -> Option<HWND> {
+        use raw_window_handle::HasWindowHandle as _;
+
+        match window.window_handle().ok()?.as_raw() {
+            raw_window_handle::RawWindowHandle::Win32(handle) => Some(handle.hwnd.get() as _),
+            _ => None,
+        }
+    }
+
+    /// Show the menu at the mouse cursor, and wait for the user to pick an item (or not).
+    pub fn show_popup(hwnd: HWND, items: &[NativeMenuItem]) -> Option<Id> {
+        let mut ids = vec![];
+
+        // SAFETY: the menu (and with it its submenus) is destroyed before we return.
+        let command = unsafe {
+            let menu = build_menu(items, &mut ids);
+
+            let mut cursor = POINT { x: 0, y: 0 };
+            GetCursorPos(&mut cursor);
+
+            // Otherwise the menu doesn't close when the user clicks outside of it:
+            SetForegroundWindow(hwnd);
+
+            let command = TrackPopupMenu(
+                menu,
+                TPM_RETURNCMD | TPM_RIGHTBUTTON,
+                cursor.x,
+                cursor.y,
+                0,
+                hwnd,
+                std::ptr::null(),
+            );
+            DestroyMenu(menu);
+            command
+        };
+
+        // The command of an item is its index in `ids` plus one, since zero means that nothing was picked:
+        let index = usize::try_from(command).ok()?.checked_sub(1)?;
+        ids.get(index).copied()
+    }
+
+    /// # Safety
+    /// The caller must destroy the returned menu.
+    unsafe fn build_menu(items: &[NativeMenuItem], ids: &mut Vec<Id>) -> HMENU {
+        // SAFETY: the strings outlive the calls they are given to, which copy them.
+        unsafe {
+            let menu = CreatePopupMenu();
+
+            for item in items {
+                match item {
+                    NativeMenuItem::Button {
+                        id,
+                        label,
+                        shortcut,
+                        enabled,
+                        checked,
+                    } => {
+                        let mut text = label.clone();
+                        if let Some(shortcut) = shortcut {
+                            // Text after a tab is right-aligned:
+                            text += "\t";
+                            text += &shortcut.format(&ModifierNames::NAMES, false);
+                        }
+
+                        let mut flags = MF_STRING;
+                        if !enabled {
+                            flags |= MF_GRAYED;
+                        }
+                        if *checked == Some(true) {
+                            flags |= MF_CHECKED;
+                        }
+
+                        ids.push(*id);
+                        AppendMenuW(menu, flags, ids.len(), wide(&text).as_ptr());
+                    }
+                    NativeMenuItem::SubMenu { label, items } => {
+                        let submenu = build_menu(items, ids);
+                        AppendMenuW(menu, MF_POPUP, submenu as usize, wide(label).as_ptr());
+                    }
+                    NativeMenuItem::Separator => {
+                        AppendMenuW(menu, MF_SEPARATOR, 0, std::ptr::null());
+                    }
+                }
+            }
+
+            menu
+        }
+    }
+
+    /// A nul-terminated wide string.
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+}
+
+/// The items of our menus all call back into an object of our own class,
+/// which tells which one it was from its tag.
+#[cfg(target_os = "macos")]
+#[allow(unsafe_code)]
+mod mac {
+    use std::cell::RefCell;
+
+    use egui::{Id, KeyboardShortcut, MenuSpec, NativeMenuItem};
+    use objc2::{
+        declare::ClassBuilder,
+        msg_send_id,
+        rc::Retained,
+        runtime::{AnyClass, AnyObject, NSObject, Sel},
+        sel, ClassType as _,
+    };
+    use objc2_app_kit::{
+        NSApplication, NSControlStateValueOff, NSControlStateValueOn, NSEvent,
+        NSEventModifierFlags, NSMenu, NSMenuItem, NSView,
+    };
+    use objc2_foundation::{MainThreadMarker, NSString};
+    use winit::window::{Window, WindowId};
+
+    const TARGET_CLASS: &str = "EguiNativeMenuTarget";
+
+    /// Where the items of a menu we made go.
+    struct Registered {
+        window_id: WindowId,
+
+        /// Of the window, to wake it up.
+        view: Retained<NSView>,
+
+        /// Indexed by the tag of the items.
+        ids: Vec<Id>,
+    }
+
+    thread_local! {
+        /// Receives the actions of all our menu items, `None` if we failed to make it.
+        static TARGET: Option<Retained<AnyObject>> = make_target();
+
+        static MENU_BAR: RefCell<Option<(MenuSpec, Registered)>> = const { RefCell::new(None) };
+        static POPUP: RefCell<Option<Registered>> = const { RefCell::new(None) };
+
+        /// The menu bar we replaced, brought back by an empty menu bar.
+        static DEFAULT_MENU_BAR: RefCell<Option<Retained<NSMenu>>> = const { RefCell::new(None) };
+    }
+
+    pub fn set_native_menu(window: &Window, menu: &MenuSpec) {
+        let Some(mtm) = MainThreadMarker::new() else {
+            log::warn!("SetNativeMenu: menus can only be shown on the main thread");
+            return;
+        };
+        let Some(view) = ns_view(window) else {
+            log::warn!("SetNativeMenu: failed to get the view of the window");
+            return;
+        };
+
+        TARGET.with(|target| {
+            let Some(target) = target else {
+                return;
+            };
+            let window_id = window.id();
+
+            if menu.popup {
+                let mut ids = vec![];
+                // SAFETY: we are on the main thread.
+                let ns_menu = unsafe {
+                    build_menu(mtm, "", &menu.items, target, sel!(eguiPopupItem:), &mut ids)
+                };
+                POPUP.set(Some(Registered {
+                    window_id,
+                    view,
+                    ids,
+                }));
+
+                // SAFETY: we are on the main thread.
+                // Without a view the location is in screen coordinates, like the mouse location.
+                // This returns when the user has picked an item (or not).
+                unsafe {
+                    ns_menu.popUpMenuPositioningItem_atLocation_inView(
+                        None,
+                        NSEvent::mouseLocation(),
+                        None,
+                    );
+                }
+            } else {
+                let unchanged = MENU_BAR.with_borrow(|menu_bar| {
+                    menu_bar.as_ref().is_some_and(|(spec, registered)| {
+                        spec == menu && registered.window_id == window_id
+                    })
+                });
+                if unchanged {
+                    return; // Apps may well send the same menu every frame.
+                }
+
+                let app = NSApplication::sharedApplication(mtm);
+
+                if menu.items.is_empty() {
+                    if let Some(default_menu_bar) = DEFAULT_MENU_BAR.take() {
+                        app.setMainMenu(Some(&default_menu_bar));
+                    }
+                    MENU_BAR.set(None);
+                    return;
+                }
+
+                let mut ids = vec![];
+                // SAFETY: we are on the main thread.
+                let ns_menu = unsafe {
+                    build_menu(
+                        mtm,
+                        "",
+                        &menu.items,
+                        target,
+                        sel!(eguiMenuBarItem:),
+                        &mut ids,
+                    )
+                };
+                if DEFAULT_MENU_BAR.with_borrow(Option::is_none) {
+                    DEFAULT_MENU_BAR.set(app.mainMenu());
+                }
+                app.setMainMenu(Some(&ns_menu));
+                MENU_BAR.set(Some((
+                    menu.clone(),
+                    Registered {
+                        window_id,
+                        view,
+                        ids,
+                    },
+                )));
+            }
+        });
+    }
+
+    fn ns_view(window: &Window) -> Option<Retained<NSView>> {
+        use raw_window_handle::HasWindowHandle as _;
+
+        let raw_window_handle::RawWindowHandle::AppKit(handle) =
+            window.window_handle().ok()?.as_raw()
+        else {
+            return None;
+        };
+        // SAFETY: the handle comes from a live window, and points to its `NSView`.
+        unsafe { Retained::retain(handle.ns_view.as_ptr().cast::<NSView>()) }
+    }
+
+    fn make_target() -> Option<Retained<AnyObject>> {
+        let class = if let Some(mut builder) = ClassBuilder::new(TARGET_CLASS, NSObject::class()) {
+            // SAFETY: the functions have the signature `AppKit` calls actions with.
+            unsafe {
+                builder.add_method(
+                    sel!(eguiMenuBarItem:),
+                    on_menu_bar_item as unsafe extern "C" fn(_, _, _),
+                );
+                builder.add_method(
+                    sel!(eguiPopupItem:),
+                    on_popup_item as unsafe extern "C" fn(_, _, _),
+                );
+            }
+            builder.register()
+        } else {
+            log::debug!("{TARGET_CLASS} is already registered");
+            AnyClass::get(TARGET_CLASS)?
+        };
+        // SAFETY: the class is a subclass of `NSObject`, which is made with `new`.
+        Some(unsafe { msg_send_id![class, new] })
+    }
+
+    /// # Safety
+    /// Must be called on the main thread.
+    unsafe fn build_menu(
+        mtm: MainThreadMarker,
+        title: &str,
+        items: &[NativeMenuItem],
+        target: &AnyObject,
+        action: Sel,
+        ids: &mut Vec<Id>,
+    ) -> Retained<NSMenu> {
+        // SAFETY: plain method calls on the main thread, and `target` implements `action`.
+        unsafe {
+            let menu = NSMenu::initWithTitle(mtm.alloc(), &NSString::from_str(title));
+            // We enable and disable the items ourselves:
+            menu.setAutoenablesItems(false);
+
+            for item in items {
+                let ns_item = match item {
+                    NativeMenuItem::Button {
+                        id,
+                        label,
+                        shortcut,
+                        enabled,
+                        checked,
+                    } => {
+                        let (key, modifiers) = shortcut
+                            .map_or((String::new(), NSEventModifierFlags(0)), key_equivalent);
+                        let ns_item = NSMenuItem::initWithTitle_action_keyEquivalent(
+                            mtm.alloc(),
+                            &NSString::from_str(label),
+                            Some(action),
+                            &NSString::from_str(&key),
+                        );
+                        ns_item.setKeyEquivalentModifierMask(modifiers);
+                        ns_item.setTag(ids.len() as isize);
+                        ids.push(*id);
+                        ns_item.setTarget(Some(target));
+                        ns_item.setEnabled(*enabled);
+                        if let Some(checked) = checked {
+                            ns_item.setState(if *checked {
+                                NSControlStateValueOn
+                            } else {
+                                NSControlStateValueOff
+                            });
+                        }
+                        ns_item
+                    }
+                    NativeMenuItem::SubMenu { label, items } => {
+                        let submenu = build_menu(mtm, label, items, target, action, ids);
+                        let ns_item = NSMenuItem::new(mtm);
+                        ns_item.setTitle(&NSString::from_str(label));
+                        ns_item.setSubmenu(Some(&submenu));
+                        ns_item
+                    }
+                    NativeMenuItem::Separator => NSMenuItem::separatorItem(mtm),
+                };
+                menu.addItem(&ns_item);
+            }
+
+            menu
+        }
+    }
+
+    /// Only shortcuts with a single character key can be key equivalents, e.g. not `F1`.
+    fn key_equivalent(shortcut: KeyboardShortcut) -> (String, NSEventModifierFlags) {
+        let key = shortcut.logical_key.symbol_or_name();
+        if key.chars().count() != 1 {
+            return (String::new(), NSEventModifierFlags(0));
+        }
+
+        let modifiers = shortcut.modifiers;
+        let mut flags = 0;
+        if modifiers.command || modifiers.mac_cmd {
+            flags |= NSEventModifierFlags::NSEventModifierFlagCommand.0;
+        }
+        if modifiers.ctrl {
+            flags |= NSEventModifierFlags::NSEventModifierFlagControl.0;
+        }
+        if modifiers.alt {
+            flags |= NSEventModifierFlags::NSEventModifierFlagOption.0;
+        }
+        if modifiers.shift {
+            flags |= NSEventModifierFlags::NSEventModifierFlagShift.0;
+        }
+        (key.to_lowercase(), NSEventModifierFlags(flags))
+    }
+
+    unsafe extern "C" fn on_menu_bar_item(_this: &AnyObject, _cmd: Sel, item: &NSMenuItem) {
+        MENU_BAR.with_borrow(|menu_bar| {
+            activate(menu_bar.as_ref().map(|(_, registered)| registered), item);
+        });
+    }
+
+    unsafe extern "C" fn on_popup_item(_this: &AnyObject, _cmd: Sel, item: &NSMenuItem) {
+        POPUP.with_borrow(|popup| activate(popup.as_ref(), item));
+    }
+
+    fn activate(registered: Option<&Registered>, item: &NSMenuItem) {
+        let Some(registered) = registered else {
+            return;
+        };
+        // SAFETY: reading the tag has no side effects.
+        let tag = unsafe { item.tag() };
+        let Some(&id) = usize::try_from(tag)
+            .ok()
+            .and_then(|index| registered.ids.get(index))
+        else {
+            return;
+        };
+
+        super::ACTIVATED.lock().push((registered.window_id, id));
+
+        // Makes winit send a `RedrawRequested`, so that the window runs a frame with the event:
+        // SAFETY: AppKit calls actions on the main thread.
+        unsafe { registered.view.setNeedsDisplay(true) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activations_go_to_their_window() {
+        let window = WindowId::from(1_u64);
+        let other_window = WindowId::from(2_u64);
+        let save = Id::new("save");
+        let quit = Id::new("quit");
+        ACTIVATED
+            .lock()
+            .extend([(window, save), (other_window, quit), (window, quit)]);
+
+        let mut info = ViewportInfo::default();
+        take_activated(window, &mut info);
+        assert_eq!(
+            info.events,
+            vec![
+                ViewportEvent::MenuItemActivated(save),
+                ViewportEvent::MenuItemActivated(quit),
+            ]
+        );
+
+        info.events.clear();
+        take_activated(window, &mut info);
+        assert!(
+            info.events.is_empty(),
+            "activations are only delivered once"
+        );
+
+        take_activated(other_window, &mut info);
+        assert_eq!(info.events, vec![ViewportEvent::MenuItemActivated(quit)]);
+    }
+}
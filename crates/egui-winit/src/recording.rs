@@ -0,0 +1,184 @@
+//! Record the [`egui::RawInput`] of every frame to a file, and replay it later.
+//!
+//! This is useful for reproducing bugs in input handling (IME, touch, …),
+//! and for integration tests that need real input sequences.
+//!
+//! The file contains one line of [RON](https://github.com/ron-rs/ron) per frame.
+
+#![allow(clippy::missing_errors_doc)] // All errors are `std::io::Error`
+
+use std::{
+    collections::VecDeque,
+    io::{BufRead, Write},
+    path::Path,
+};
+
+/// Writes the [`egui::RawInput`] of each frame.
+///
+/// Give it to [`crate::State::set_input_recorder`] to record everything a [`crate::State`] produces.
+pub struct InputRecorder {
+    writer: Box<dyn Write + Send>,
+}
+
+impl std::fmt::Debug for InputRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InputRecorder").finish_non_exhaustive()
+    }
+}
+
+impl InputRecorder {
+    /// Record to a new file, replacing it if it already exists.
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = std::fs::File::create(path)?;
+        Ok(Self::from_writer(std::io::BufWriter::new(file)))
+    }
+
+    /// Record to anything, e.g. a socket or an in-memory buffer.
+    pub fn from_writer(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Box::new(writer),
+        }
+    }
+
+    /// Write the input of one frame.
+    pub fn record(&mut self, raw_input: &egui::RawInput) -> std::io::Result<()> {
+        let line = ron::to_string(raw_input)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        writeln!(self.writer, "{line}")
+    }
+
+    /// Make sure everything recorded so far is written. This also happens on drop.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl Drop for InputRecorder {
+    fn drop(&mut self) {
+        if let Err(err) = self.writer.flush() {
+            log::warn!("Failed to flush the input recording: {err}");
+        }
+    }
+}
+
+/// Recorded frames of [`egui::RawInput`], as written by an [`InputRecorder`].
+///
+/// Give it to [`crate::State::set_input_replay`] to feed the recorded input to egui
+/// instead of the input of the window, one frame at a time.
+///
+/// The recorded [`egui::RawInput::time`] is replayed too, so the replay is deterministic.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputReplay {
+    frames: VecDeque<egui::RawInput>,
+}
+
+impl InputReplay {
+    /// Load a recording from a file.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::from_reader(std::io::BufReader::new(file))
+    }
+
+    /// Parse a recording, one frame per line.
+    pub fn from_reader(reader: impl BufRead) -> std::io::Result<Self> {
+        let mut frames = VecDeque::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let raw_input = ron::from_str(&line).map_err(|err| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Bad input recording on line {}: {err}", index + 1),
+                )
+            })?;
+            frames.push_back(raw_input);
+        }
+        Ok(Self { frames })
+    }
+
+    /// Replay frames you already have, e.g. made up in a test.
+    pub fn from_frames(frames: impl IntoIterator<Item = egui::RawInput>) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+        }
+    }
+
+    /// The input of the next frame, if any is left.
+    pub fn next_frame(&mut self) -> Option<egui::RawInput> {
+        self.frames.pop_front()
+    }
+
+    /// How many frames are left to replay.
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Have all frames been replayed?
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lets the test read back what the recorder wrote.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(std::sync::Arc<egui::mutex::Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let frames = vec![
+            egui::RawInput {
+                time: Some(0.0),
+                events: vec![
+                    egui::Event::PointerMoved(egui::pos2(10.0, 20.0)),
+                    egui::Event::Touch {
+                        device_id: egui::TouchDeviceId(1),
+                        id: egui::TouchId(2),
+                        phase: egui::TouchPhase::Start,
+                        pos: egui::pos2(1.0, 2.0),
+                        force: Some(0.5),
+                    },
+                ],
+                ..Default::default()
+            },
+            egui::RawInput {
+                time: Some(0.016),
+                events: vec![
+                    egui::Event::Ime(egui::ImeEvent::Preedit("ni".to_owned())),
+                    egui::Event::Ime(egui::ImeEvent::Commit("你".to_owned())),
+                ],
+                ..Default::default()
+            },
+        ];
+
+        let buffer = SharedBuffer::default();
+        let mut recorder = InputRecorder::from_writer(buffer.clone());
+        for frame in &frames {
+            recorder.record(frame).unwrap();
+        }
+        drop(recorder);
+
+        let recording = buffer.0.lock().clone();
+        let mut replay = InputReplay::from_reader(recording.as_slice()).unwrap();
+        assert_eq!(replay.remaining(), frames.len());
+        for frame in frames {
+            assert_eq!(replay.next_frame(), Some(frame));
+        }
+        assert!(replay.is_finished());
+    }
+}
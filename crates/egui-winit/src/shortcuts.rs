@@ -0,0 +1,174 @@
+//! Translation of keyboard shortcuts into egui events, before they reach egui.
+
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+/// What a shortcut in a [`ShortcutMap`] is translated into.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShortcutAction {
+    /// Send [`egui::Event::Cut`].
+    Cut,
+
+    /// Send [`egui::Event::Copy`].
+    Copy,
+
+    /// Read the OS clipboard and send its contents as [`egui::Event::Paste`].
+    Paste,
+
+    /// Send these events instead of the key press.
+    Events(Vec<egui::Event>),
+}
+
+/// Which key presses are translated into something else than [`egui::Event::Key`],
+/// e.g. `Cmd+C` into [`egui::Event::Copy`].
+///
+/// The default contains the clipboard shortcuts of the platform.
+/// Use [`crate::State::shortcut_map_mut`] to remap or remove those,
+/// or to add your own.
+///
+/// Shortcuts are matched with [`Modifiers::matches_logically`],
+/// and later insertions take precedence over earlier ones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShortcutMap {
+    shortcuts: Vec<(KeyboardShortcut, ShortcutAction)>,
+}
+
+impl Default for ShortcutMap {
+    fn default() -> Self {
+        use ShortcutAction::{Copy, Cut, Paste};
+
+        let mut shortcuts = vec![
+            (Modifiers::NONE, Key::Cut, Cut),
+            (Modifiers::COMMAND, Key::X, Cut),
+            (Modifiers::NONE, Key::Copy, Copy),
+            (Modifiers::COMMAND, Key::C, Copy),
+            (Modifiers::NONE, Key::Paste, Paste),
+            (Modifiers::COMMAND, Key::V, Paste),
+        ];
+        if cfg!(target_os = "windows") {
+            shortcuts.extend([
+                (Modifiers::SHIFT, Key::Delete, Cut),
+                (Modifiers::CTRL, Key::Insert, Copy),
+                (Modifiers::SHIFT, Key::Insert, Paste),
+            ]);
+        }
+
+        Self {
+            shortcuts: shortcuts
+                .into_iter()
+                .map(|(modifiers, key, action)| (KeyboardShortcut::new(modifiers, key), action))
+                .collect(),
+        }
+    }
+}
+
+impl ShortcutMap {
+    /// No shortcuts at all: every key press reaches egui as [`egui::Event::Key`].
+    pub fn empty() -> Self {
+        Self {
+            shortcuts: Vec::new(),
+        }
+    }
+
+    /// Translate `shortcut` into `action`, replacing any previous action for the same shortcut.
+    pub fn insert(&mut self, shortcut: KeyboardShortcut, action: ShortcutAction) {
+        self.remove(&shortcut);
+        self.shortcuts.push((shortcut, action));
+    }
+
+    /// Stop translating `shortcut`, so that it reaches egui as a normal key press.
+    pub fn remove(&mut self, shortcut: &KeyboardShortcut) -> Option<ShortcutAction> {
+        let index = self.shortcuts.iter().position(|(s, _)| s == shortcut)?;
+        Some(self.shortcuts.remove(index).1)
+    }
+
+    /// Remove all shortcuts translated to `action`, e.g. to disable all paste shortcuts.
+    pub fn remove_action(&mut self, action: &ShortcutAction) {
+        self.shortcuts.retain(|(_, a)| a != action);
+    }
+
+    /// All shortcuts, in the order they were inserted.
+    pub fn iter(&self) -> impl Iterator<Item = &(KeyboardShortcut, ShortcutAction)> {
+        self.shortcuts.iter()
+    }
+
+    /// What the given key press is translated into, if anything.
+    pub fn find(&self, modifiers: Modifiers, key: Key) -> Option<&ShortcutAction> {
+        self.shortcuts
+            .iter()
+            .rev()
+            .find(|(shortcut, _)| {
+                shortcut.logical_key == key && modifiers.matches_logically(shortcut.modifiers)
+            })
+            .map(|(_, action)| action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What pressing Ctrl reports on Windows and Linux.
+    const CTRL: Modifiers = Modifiers::CTRL.plus(Modifiers::COMMAND);
+
+    #[test]
+    fn default_has_the_clipboard_shortcuts() {
+        let map = ShortcutMap::default();
+        assert_eq!(map.find(CTRL, Key::X), Some(&ShortcutAction::Cut));
+        assert_eq!(map.find(CTRL, Key::C), Some(&ShortcutAction::Copy));
+        assert_eq!(map.find(CTRL, Key::V), Some(&ShortcutAction::Paste));
+        assert_eq!(
+            map.find(Modifiers::NONE, Key::Copy),
+            Some(&ShortcutAction::Copy)
+        );
+
+        // Extra shift is fine, a missing command is not:
+        assert_eq!(
+            map.find(CTRL | Modifiers::SHIFT, Key::C),
+            Some(&ShortcutAction::Copy)
+        );
+        assert_eq!(map.find(Modifiers::NONE, Key::C), None);
+        assert_eq!(map.find(Modifiers::SHIFT, Key::V), None);
+        assert_eq!(map.find(CTRL, Key::A), None);
+
+        assert!(ShortcutMap::empty().find(CTRL, Key::C).is_none());
+    }
+
+    #[test]
+    fn shortcuts_can_be_remapped_and_removed() {
+        let mut map = ShortcutMap::default();
+        let ctrl_c = KeyboardShortcut::new(Modifiers::COMMAND, Key::C);
+
+        assert_eq!(map.remove(&ctrl_c), Some(ShortcutAction::Copy));
+        assert_eq!(map.remove(&ctrl_c), None);
+        assert_eq!(map.find(CTRL, Key::C), None);
+
+        map.insert(ctrl_c, ShortcutAction::Paste);
+        map.insert(ctrl_c, ShortcutAction::Cut);
+        assert_eq!(map.find(CTRL, Key::C), Some(&ShortcutAction::Cut));
+        assert_eq!(
+            map.iter()
+                .filter(|(shortcut, _)| *shortcut == ctrl_c)
+                .count(),
+            1,
+            "Inserting replaces"
+        );
+
+        map.remove_action(&ShortcutAction::Paste);
+        assert_eq!(map.find(CTRL, Key::V), None);
+        assert_eq!(map.find(Modifiers::NONE, Key::Paste), None);
+        assert_eq!(map.find(CTRL, Key::X), Some(&ShortcutAction::Cut));
+    }
+
+    #[test]
+    fn later_shortcuts_take_precedence() {
+        let mut map = ShortcutMap::default();
+        let custom = ShortcutAction::Events(vec![egui::Event::Text("copied as html".into())]);
+        map.insert(
+            KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::C),
+            custom.clone(),
+        );
+
+        assert_eq!(map.find(CTRL | Modifiers::SHIFT, Key::C), Some(&custom));
+        assert_eq!(map.find(CTRL, Key::C), Some(&ShortcutAction::Copy));
+    }
+}
@@ -0,0 +1,340 @@
+//! Keep the display awake, e.g. during video playback or on a dashboard.
+//!
+//! winit has no API for this, so we talk to the OS directly:
+//! * Windows: `SetThreadExecutionState`
+//! * Mac: an `NSProcessInfo` activity that disables idle display sleep
+//! * Linux and BSD: `org.freedesktop.ScreenSaver` over D-Bus (requires the `dbus` feature)
+
+/// Prevents the screen saver from starting and the display from going to sleep, for as long as it is alive.
+///
+/// ```no_run
+/// # use egui_winit::SleepInhibitor;
+/// let inhibitor = SleepInhibitor::new("My Player", "Playing a video");
+/// // … play the video …
+/// drop(inhibitor); // let the display sleep again
+/// ```
+///
+pub struct SleepInhibitor {
+    #[allow(dead_code)] // Only used for releasing the inhibition on some platforms
+    platform: platform::Inhibitor,
+}
+
+impl std::fmt::Debug for SleepInhibitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SleepInhibitor").finish_non_exhaustive()
+    }
+}
+
+impl SleepInhibitor {
+    /// Inhibit display sleep.
+    ///
+    /// `app_name` and `reason` may be shown to the user by the OS, e.g. in `pmset -g assertions` on Mac.
+    ///
+    /// Returns `None` (and logs a warning) if this is not supported on this platform, or failed.
+    /// On Windows, Linux and BSD the OS is called on a background thread, so failures are only logged.
+    pub fn new(app_name: &str, reason: &str) -> Option<Self> {
+        match platform::Inhibitor::new(app_name, reason) {
+            Ok(platform) => Some(Self { platform }),
+            Err(err) => {
+                log::warn!("Failed to inhibit display sleep: {err}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::sync::{mpsc, OnceLock};
+
+    use windows_sys::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+        EXECUTION_STATE,
+    };
+
+    /// `true` to inhibit, `false` to release one inhibition.
+    ///
+    /// The execution state belongs to the thread that sets it, so we set it on a thread of our own.
+    /// That way an [`Inhibitor`] can be dropped on any thread.
+    static REQUESTS: OnceLock<Option<mpsc::Sender<bool>>> = OnceLock::new();
+
+    fn send(inhibit: bool) -> Result<(), String> {
+        let requests = REQUESTS.get_or_init(|| {
+            let (tx, rx) = mpsc::channel();
+            std::thread::Builder::new()
+                .name("egui-winit sleep inhibitor".to_owned())
+                .spawn(move || handle_requests(&rx))
+                .map_err(|err| log::warn!("Failed to spawn thread for inhibiting sleep: {err}"))
+                .ok()
+                .map(|_| tx)
+        });
+        let requests = requests
+            .as_ref()
+            .ok_or_else(|| "no thread for inhibiting sleep".to_owned())?;
+        requests
+            .send(inhibit)
+            .map_err(|_err| "the thread for inhibiting sleep has stopped".to_owned())
+    }
+
+    fn handle_requests(requests: &mpsc::Receiver<bool>) {
+        // The execution state is a single flag, so we need to count the inhibitors.
+        let mut num_inhibitors = 0_usize;
+
+        while let Ok(inhibit) = requests.recv() {
+            if inhibit {
+                if num_inhibitors == 0 {
+                    if let Err(err) = set_execution_state(
+                        ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED,
+                    ) {
+                        log::warn!("Failed to inhibit display sleep: {err}");
+                    }
+                }
+                num_inhibitors += 1;
+            } else {
+                num_inhibitors = num_inhibitors.saturating_sub(1);
+                if num_inhibitors == 0 {
+                    set_execution_state(ES_CONTINUOUS).ok();
+                }
+            }
+        }
+    }
+
+    fn set_execution_state(flags: EXECUTION_STATE) -> Result<(), String> {
+        // SAFETY: `SetThreadExecutionState` has no preconditions.
+        #[allow(unsafe_code)]
+        let previous = unsafe { SetThreadExecutionState(flags) };
+        if previous == 0 {
+            Err("SetThreadExecutionState failed".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub struct Inhibitor;
+
+    impl Inhibitor {
+        pub fn new(_app_name: &str, _reason: &str) -> Result<Self, String> {
+            send(true)?;
+            Ok(Self)
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            send(false).ok();
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::{
+        rc::Retained,
+        runtime::{NSObjectProtocol, ProtocolObject},
+    };
+    use objc2_foundation::{NSActivityOptions, NSProcessInfo, NSString};
+
+    pub struct Inhibitor {
+        activity: Retained<ProtocolObject<dyn NSObjectProtocol>>,
+    }
+
+    // SAFETY: `NSProcessInfo` is thread-safe, and the activity is only ever handed back to it.
+    #[allow(unsafe_code)]
+    unsafe impl Send for Inhibitor {}
+
+    impl Inhibitor {
+        #[allow(clippy::unnecessary_wraps)] // same signature on all platforms
+        pub fn new(app_name: &str, reason: &str) -> Result<Self, String> {
+            let reason = NSString::from_str(&format!("{app_name}: {reason}"));
+            let options = NSActivityOptions::NSActivityIdleDisplaySleepDisabled
+                | NSActivityOptions::NSActivityUserInitiated;
+            // SAFETY: `reason` is a valid string, and we end the activity on drop.
+            #[allow(unsafe_code)]
+            let activity = unsafe {
+                NSProcessInfo::processInfo().beginActivityWithOptions_reason(options, &reason)
+            };
+            Ok(Self { activity })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            // SAFETY: the activity was begun by us, and is only ended once.
+            #[allow(unsafe_code)]
+            unsafe {
+                NSProcessInfo::processInfo().endActivity(&self.activity);
+            }
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "dbus",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+mod platform {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, OnceLock,
+    };
+
+    const DESTINATION: &str = "org.freedesktop.ScreenSaver";
+    const PATH: &str = "/org/freedesktop/ScreenSaver";
+    const INTERFACE: &str = "org.freedesktop.ScreenSaver";
+
+    enum Request {
+        Inhibit {
+            id: u64,
+            app_name: String,
+            reason: String,
+        },
+        UnInhibit {
+            id: u64,
+        },
+    }
+
+    /// D-Bus calls block, so we make them on a background thread.
+    static REQUESTS: OnceLock<Option<mpsc::Sender<Request>>> = OnceLock::new();
+
+    fn send(request: Request) -> Result<(), String> {
+        let requests = REQUESTS.get_or_init(|| {
+            let (tx, rx) = mpsc::channel();
+            std::thread::Builder::new()
+                .name("egui-winit sleep inhibitor".to_owned())
+                .spawn(move || handle_requests(&rx))
+                .map_err(|err| log::warn!("Failed to spawn thread for D-Bus calls: {err}"))
+                .ok()
+                .map(|_| tx)
+        });
+        let requests = requests
+            .as_ref()
+            .ok_or_else(|| "no thread for D-Bus calls".to_owned())?;
+        requests
+            .send(request)
+            .map_err(|_err| "the thread for D-Bus calls has stopped".to_owned())
+    }
+
+    fn handle_requests(requests: &mpsc::Receiver<Request>) {
+        // The inhibitions are released when the connection is closed, so we keep it open.
+        let mut connection = None;
+        let mut cookies = ahash::HashMap::<u64, u32>::default();
+
+        while let Ok(request) = requests.recv() {
+            match request {
+                Request::Inhibit {
+                    id,
+                    app_name,
+                    reason,
+                } => {
+                    if connection.is_none() {
+                        connection = zbus::blocking::Connection::session()
+                            .map_err(|err| log::warn!("Failed to inhibit display sleep: {err}"))
+                            .ok();
+                    }
+                    let Some(connection) = &connection else {
+                        continue;
+                    };
+                    match inhibit(connection, &app_name, &reason) {
+                        Ok(cookie) => {
+                            cookies.insert(id, cookie);
+                        }
+                        Err(err) => log::warn!("Failed to inhibit display sleep: {err}"),
+                    }
+                }
+                Request::UnInhibit { id } => {
+                    let (Some(cookie), Some(connection)) = (cookies.remove(&id), &connection)
+                    else {
+                        continue;
+                    };
+                    if let Err(err) = connection.call_method(
+                        Some(DESTINATION),
+                        PATH,
+                        Some(INTERFACE),
+                        "UnInhibit",
+                        &(cookie,),
+                    ) {
+                        log::debug!("Failed to release the display sleep inhibition: {err}");
+                    }
+                }
+            }
+        }
+    }
+
+    fn inhibit(
+        connection: &zbus::blocking::Connection,
+        app_name: &str,
+        reason: &str,
+    ) -> zbus::Result<u32> {
+        let reply = connection.call_method(
+            Some(DESTINATION),
+            PATH,
+            Some(INTERFACE),
+            "Inhibit",
+            &(app_name, reason),
+        )?;
+        reply.body().deserialize()
+    }
+
+    pub struct Inhibitor {
+        id: u64,
+    }
+
+    impl Inhibitor {
+        pub fn new(app_name: &str, reason: &str) -> Result<Self, String> {
+            static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            send(Request::Inhibit {
+                id,
+                app_name: app_name.to_owned(),
+                reason: reason.to_owned(),
+            })?;
+            Ok(Self { id })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            send(Request::UnInhibit { id: self.id }).ok();
+        }
+    }
+}
+
+#[cfg(not(any(
+    windows,
+    target_os = "macos",
+    all(
+        feature = "dbus",
+        any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )
+    )
+)))]
+mod platform {
+    pub struct Inhibitor;
+
+    impl Inhibitor {
+        pub fn new(_app_name: &str, _reason: &str) -> Result<Self, String> {
+            if cfg!(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            )) {
+                Err("enable the `dbus` feature of egui-winit".to_owned())
+            } else {
+                Err("not supported on this platform".to_owned())
+            }
+        }
+    }
+}
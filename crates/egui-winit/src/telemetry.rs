@@ -0,0 +1,444 @@
+//! Opt-in telemetry about frame pacing, to help diagnose stutter.
+//!
+//! See [`FrameTelemetry`].
+
+use std::{collections::VecDeque, fmt::Write as _};
+
+use raw_window_handle::{HasDisplayHandle as _, RawDisplayHandle};
+use web_time::Instant;
+use winit::{event::WindowEvent, window::Window};
+
+/// Assumed when we don't know the refresh rate of the monitor.
+const DEFAULT_REFRESH_RATE_HZ: f32 = 60.0;
+
+/// Records how frames are paced: frame intervals, late presents, input latency,
+/// and the window state changes (moves, resizes, …) around them.
+///
+/// Stutter is often hard to reproduce, so this is meant to be attached to bug reports:
+/// give it to [`crate::State::set_frame_telemetry`], have your integration call
+/// [`crate::State::on_frame_presented`] after presenting each frame (`eframe` does),
+/// and export it with [`Self::to_json`].
+///
+/// Only the last [`Self::max_frames`] frames and window events are kept,
+/// so it is cheap to leave it on.
+#[derive(Clone, Debug)]
+pub struct FrameTelemetry {
+    start: Instant,
+    max_frames: usize,
+
+    frames: VecDeque<FrameRecord>,
+    window_events: VecDeque<WindowEventRecord>,
+
+    /// When the oldest input event that no frame has handled yet arrived.
+    pending_input: Option<Instant>,
+
+    /// The frame being painted: when it started, and when the oldest input it handles arrived.
+    current_frame: Option<(Instant, Option<Instant>)>,
+
+    last_present: Option<Instant>,
+
+    refresh_rate_hz: Option<f32>,
+    windowing_system: Option<&'static str>,
+
+    total_frames: u64,
+    late_presents: u64,
+    dropped_frames: u64,
+}
+
+impl Default for FrameTelemetry {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            max_frames: 3600,
+            frames: Default::default(),
+            window_events: Default::default(),
+            pending_input: None,
+            current_frame: None,
+            last_present: None,
+            refresh_rate_hz: None,
+            windowing_system: None,
+            total_frames: 0,
+            late_presents: 0,
+            dropped_frames: 0,
+        }
+    }
+}
+
+/// One presented frame, see [`FrameTelemetry`].
+///
+/// All times are in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameRecord {
+    /// When the frame started, since the telemetry started.
+    pub start: f64,
+
+    /// From the start of the frame until it was presented.
+    pub duration: f64,
+
+    /// Since the previous frame was presented, if any.
+    pub interval: Option<f64>,
+
+    /// From the oldest input event handled by this frame until the frame was presented.
+    pub input_latency: Option<f64>,
+
+    /// How many refreshes of the monitor we missed between this and the previous frame,
+    /// if we were painting continuously.
+    pub dropped_frames: u32,
+
+    /// Did this frame miss the refresh of the monitor it was meant for?
+    pub late: bool,
+}
+
+/// A change of the window state, see [`FrameTelemetry`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowStateChange {
+    /// The new outer position, in physical pixels.
+    Moved {
+        x: i32,
+        y: i32,
+    },
+
+    /// The new inner size, in physical pixels.
+    Resized {
+        width: u32,
+        height: u32,
+    },
+
+    ScaleFactorChanged {
+        scale_factor: f64,
+    },
+
+    Occluded(bool),
+
+    Focused(bool),
+}
+
+/// A [`WindowStateChange`], and when it happened (in seconds since the telemetry started).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowEventRecord {
+    pub time: f64,
+    pub change: WindowStateChange,
+}
+
+impl FrameTelemetry {
+    /// How many frames (and window events) to keep.
+    ///
+    /// Default: 3600 (a minute at 60 Hz).
+    #[inline]
+    pub fn with_max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = max_frames;
+        self
+    }
+
+    #[inline]
+    pub fn max_frames(&self) -> usize {
+        self.max_frames
+    }
+
+    /// The last [`Self::max_frames`] presented frames, oldest first.
+    pub fn frames(&self) -> impl ExactSizeIterator<Item = &FrameRecord> {
+        self.frames.iter()
+    }
+
+    /// The last [`Self::max_frames`] window state changes, oldest first.
+    pub fn window_events(&self) -> impl ExactSizeIterator<Item = &WindowEventRecord> {
+        self.window_events.iter()
+    }
+
+    /// How many frames have been presented in total.
+    #[inline]
+    pub fn total_frames(&self) -> u64 {
+        self.total_frames
+    }
+
+    /// How many frames missed the refresh of the monitor they were meant for, in total.
+    #[inline]
+    pub fn late_presents(&self) -> u64 {
+        self.late_presents
+    }
+
+    /// How many refreshes of the monitor we missed while painting continuously, in total.
+    #[inline]
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    /// The refresh rate of the monitor the window was last on, if winit knows it.
+    #[inline]
+    pub fn refresh_rate_hz(&self) -> Option<f32> {
+        self.refresh_rate_hz
+    }
+
+    /// Forget everything recorded so far.
+    pub fn clear(&mut self) {
+        *self = Self {
+            max_frames: self.max_frames,
+            refresh_rate_hz: self.refresh_rate_hz,
+            windowing_system: self.windowing_system,
+            ..Default::default()
+        };
+    }
+
+    fn seconds_since_start(&self, time: Instant) -> f64 {
+        time.saturating_duration_since(self.start).as_secs_f64()
+    }
+
+    pub(crate) fn on_window_event(&mut self, event: &WindowEvent) {
+        let change = match event {
+            WindowEvent::KeyboardInput { .. }
+            | WindowEvent::MouseInput { .. }
+            | WindowEvent::MouseWheel { .. }
+            | WindowEvent::CursorMoved { .. }
+            | WindowEvent::Touch(_)
+            | WindowEvent::Ime(_) => {
+                self.pending_input.get_or_insert_with(Instant::now);
+                return;
+            }
+            WindowEvent::Moved(position) => WindowStateChange::Moved {
+                x: position.x,
+                y: position.y,
+            },
+            WindowEvent::Resized(size) => WindowStateChange::Resized {
+                width: size.width,
+                height: size.height,
+            },
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                WindowStateChange::ScaleFactorChanged {
+                    scale_factor: *scale_factor,
+                }
+            }
+            WindowEvent::Occluded(occluded) => WindowStateChange::Occluded(*occluded),
+            WindowEvent::Focused(focused) => WindowStateChange::Focused(*focused),
+            _ => return,
+        };
+
+        let time = self.seconds_since_start(Instant::now());
+        self.window_events
+            .push_back(WindowEventRecord { time, change });
+        while self.max_frames < self.window_events.len() {
+            self.window_events.pop_front();
+        }
+    }
+
+    /// Call when a frame starts, i.e. when its input is taken.
+    pub(crate) fn on_frame_start(&mut self, window: &Window) {
+        if let Some(monitor) = window.current_monitor() {
+            if let Some(millihertz) = monitor.refresh_rate_millihertz() {
+                self.refresh_rate_hz = Some(millihertz as f32 / 1000.0);
+            }
+        }
+        if self.windowing_system.is_none() {
+            self.windowing_system = window
+                .display_handle()
+                .ok()
+                .map(|handle| windowing_system(handle.as_raw()));
+        }
+        self.start_frame_at(Instant::now());
+    }
+
+    fn start_frame_at(&mut self, now: Instant) {
+        self.current_frame = Some((now, self.pending_input.take()));
+    }
+
+    /// Call after the frame has been presented.
+    pub(crate) fn on_frame_presented(&mut self) {
+        self.present_at(Instant::now());
+    }
+
+    fn present_at(&mut self, now: Instant) {
+        let Some((frame_start, oldest_input)) = self.current_frame.take() else {
+            return; // We never saw the frame start
+        };
+
+        let refresh_period = 1.0
+            / self
+                .refresh_rate_hz
+                .unwrap_or(DEFAULT_REFRESH_RATE_HZ)
+                .max(1.0) as f64;
+
+        let duration = now.saturating_duration_since(frame_start).as_secs_f64();
+        let interval = self
+            .last_present
+            .map(|last| now.saturating_duration_since(last).as_secs_f64());
+
+        // Only if we started right after the previous present were we painting continuously,
+        // and any gap longer than a refresh is a dropped frame (rather than us idling):
+        let continuous = self.last_present.is_some_and(|last| {
+            frame_start.saturating_duration_since(last).as_secs_f64() < 0.5 * refresh_period
+        });
+        let dropped_frames = match interval {
+            Some(interval) if continuous => {
+                ((interval / refresh_period).round() as u32).saturating_sub(1)
+            }
+            _ => 0,
+        };
+        let late = 0 < dropped_frames || 1.5 * refresh_period < duration;
+
+        let record = FrameRecord {
+            start: self.seconds_since_start(frame_start),
+            duration,
+            interval,
+            input_latency: oldest_input
+                .map(|input| now.saturating_duration_since(input).as_secs_f64()),
+            dropped_frames,
+            late,
+        };
+
+        self.total_frames += 1;
+        self.late_presents += late as u64;
+        self.dropped_frames += dropped_frames as u64;
+        self.last_present = Some(now);
+
+        self.frames.push_back(record);
+        while self.max_frames < self.frames.len() {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Everything recorded, as JSON, e.g. to attach to a bug report.
+    pub fn to_json(&self) -> String {
+        fn opt(value: Option<impl std::fmt::Display>) -> String {
+            value.map_or_else(|| "null".to_owned(), |value| value.to_string())
+        }
+
+        let mut json = String::new();
+        json += "{\n";
+        let _ = writeln!(json, "  \"os\": \"{}\",", std::env::consts::OS);
+        let _ = writeln!(
+            json,
+            "  \"windowing_system\": {},",
+            opt(self.windowing_system.map(|name| format!("\"{name}\"")))
+        );
+        let _ = writeln!(
+            json,
+            "  \"refresh_rate_hz\": {},",
+            opt(self.refresh_rate_hz)
+        );
+        let _ = writeln!(json, "  \"total_frames\": {},", self.total_frames);
+        let _ = writeln!(json, "  \"late_presents\": {},", self.late_presents);
+        let _ = writeln!(json, "  \"dropped_frames\": {},", self.dropped_frames);
+
+        json += "  \"frames\": [";
+        for (i, frame) in self.frames.iter().enumerate() {
+            let FrameRecord {
+                start,
+                duration,
+                interval,
+                input_latency,
+                dropped_frames,
+                late,
+            } = frame;
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(
+                json,
+                "{separator}\n    {{\"start\": {start}, \"duration\": {duration}, \"interval\": {}, \"input_latency\": {}, \"dropped_frames\": {dropped_frames}, \"late\": {late}}}",
+                opt(*interval),
+                opt(*input_latency),
+            );
+        }
+        json += "\n  ],\n";
+
+        json += "  \"window_events\": [";
+        for (i, WindowEventRecord { time, change }) in self.window_events.iter().enumerate() {
+            let change = match change {
+                WindowStateChange::Moved { x, y } => {
+                    format!("\"change\": \"moved\", \"x\": {x}, \"y\": {y}")
+                }
+                WindowStateChange::Resized { width, height } => {
+                    format!("\"change\": \"resized\", \"width\": {width}, \"height\": {height}")
+                }
+                WindowStateChange::ScaleFactorChanged { scale_factor } => {
+                    format!(
+                        "\"change\": \"scale_factor_changed\", \"scale_factor\": {scale_factor}"
+                    )
+                }
+                WindowStateChange::Occluded(occluded) => {
+                    format!("\"change\": \"occluded\", \"occluded\": {occluded}")
+                }
+                WindowStateChange::Focused(focused) => {
+                    format!("\"change\": \"focused\", \"focused\": {focused}")
+                }
+            };
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(json, "{separator}\n    {{\"time\": {time}, {change}}}");
+        }
+        json += "\n  ]\n}\n";
+
+        json
+    }
+}
+
+/// What draws our windows, which matters a lot for how frames are paced.
+fn windowing_system(handle: RawDisplayHandle) -> &'static str {
+    match handle {
+        RawDisplayHandle::Xlib(_) | RawDisplayHandle::Xcb(_) => "x11",
+        RawDisplayHandle::Wayland(_) => "wayland",
+        RawDisplayHandle::Windows(_) => "windows",
+        RawDisplayHandle::AppKit(_) => "appkit",
+        RawDisplayHandle::UiKit(_) => "uikit",
+        RawDisplayHandle::Android(_) => "android",
+        RawDisplayHandle::Web(_) => "web",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn frame(telemetry: &mut FrameTelemetry, start: Instant, duration_ms: u64) -> FrameRecord {
+        telemetry.start_frame_at(start);
+        telemetry.present_at(start + Duration::from_millis(duration_ms));
+        *telemetry.frames().last().unwrap()
+    }
+
+    #[test]
+    fn counts_dropped_and_late_frames() {
+        let mut telemetry = FrameTelemetry {
+            refresh_rate_hz: Some(100.0),
+            ..Default::default()
+        };
+        let t0 = telemetry.start;
+        let ms = Duration::from_millis;
+
+        let first = frame(&mut telemetry, t0, 10);
+        assert_eq!(first.interval, None);
+        assert!(!first.late);
+
+        // Painting continuously, right on time:
+        let on_time = frame(&mut telemetry, t0 + ms(10), 10);
+        assert_eq!(on_time.dropped_frames, 0);
+        assert!(!on_time.late);
+
+        // Painting continuously, but missing two refreshes:
+        let slow = frame(&mut telemetry, t0 + ms(20), 30);
+        assert_eq!(slow.dropped_frames, 2);
+        assert!(slow.late);
+
+        // Idling for a while is not dropping frames:
+        let after_idle = frame(&mut telemetry, t0 + ms(1000), 10);
+        assert_eq!(after_idle.dropped_frames, 0);
+        assert!(!after_idle.late);
+
+        assert_eq!(telemetry.total_frames(), 4);
+        assert_eq!(telemetry.late_presents(), 1);
+        assert_eq!(telemetry.dropped_frames(), 2);
+
+        let json = telemetry.to_json();
+        assert!(json.contains("\"late_presents\": 1,"));
+        assert!(json.contains("\"dropped_frames\": 2, \"late\": true"));
+    }
+
+    #[test]
+    fn keeps_the_last_frames() {
+        let mut telemetry = FrameTelemetry::default().with_max_frames(2);
+        let t0 = telemetry.start;
+        for i in 0..5 {
+            frame(&mut telemetry, t0 + Duration::from_millis(100 * i), 10);
+        }
+        assert_eq!(telemetry.frames().len(), 2);
+        assert_eq!(telemetry.total_frames(), 5);
+    }
+}
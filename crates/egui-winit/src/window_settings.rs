@@ -1,10 +1,19 @@
 use egui::ViewportBuilder;
 
-/// Can be used to store native window settings (position and size).
-#[derive(Clone, Copy, Debug, Default)]
+/// Can be used to store native window settings (position, size, monitor, …),
+/// so that a window can be restored the way it was in a previous session.
+///
+/// The serialized format is versioned: settings stored by an older version of egui-winit
+/// can still be loaded, and are upgraded by [`Self::migrate`].
+#[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "serde", serde(default))]
 pub struct WindowSettings {
+    /// Version of the format these settings were stored with.
+    ///
+    /// Missing in settings from before versioning, which are thus version 0.
+    version: u32,
+
     /// Position of window content in physical pixels.
     inner_position_pixels: Option<egui::Pos2>,
 
@@ -17,13 +26,33 @@ pub struct WindowSettings {
 
     /// Inner size of window in logical pixels
     inner_size_points: Option<egui::Vec2>,
+
+    /// Name of the monitor the window was on.
+    monitor_name: Option<String>,
+
+    /// Position of that monitor in physical pixels,
+    /// so we can follow it if it has been moved in the display arrangement.
+    monitor_position_pixels: Option<egui::Pos2>,
+
+    /// Size of that monitor in physical pixels,
+    /// to tell it apart from other monitors with the same name.
+    monitor_size_pixels: Option<egui::Vec2>,
+
+    /// The [`egui::Context::zoom_factor`] used in the window.
+    zoom_factor: Option<f32>,
 }
 
 impl WindowSettings {
+    /// The version of the serialized format written by this version of egui-winit.
+    ///
+    /// * 0: position, size, fullscreen and maximized
+    /// * 1: adds the monitor and the zoom factor
+    pub const CURRENT_VERSION: u32 = 1;
+
     pub fn from_window(egui_zoom_factor: f32, window: &winit::window::Window) -> Self {
-        let inner_size_points = window
-            .inner_size()
-            .to_logical::<f32>(egui_zoom_factor as f64 * window.scale_factor());
+        let inner_size_points = window.inner_size().to_logical::<f32>(f64::from(
+            egui_zoom_factor * crate::native_pixels_per_point(window),
+        ));
 
         let inner_position_pixels = window
             .inner_position()
@@ -35,7 +64,11 @@ impl WindowSettings {
             .ok()
             .map(|p| egui::pos2(p.x as f32, p.y as f32));
 
+        let monitor = window.current_monitor();
+
         Self {
+            version: Self::CURRENT_VERSION,
+
             inner_position_pixels,
             outer_position_pixels,
 
@@ -46,13 +79,63 @@ impl WindowSettings {
                 inner_size_points.width,
                 inner_size_points.height,
             )),
+
+            monitor_name: monitor.as_ref().and_then(|monitor| monitor.name()),
+            monitor_position_pixels: monitor.as_ref().map(|monitor| {
+                let pos = monitor.position();
+                egui::pos2(pos.x as f32, pos.y as f32)
+            }),
+            monitor_size_pixels: monitor.map(|monitor| {
+                let size = monitor.size();
+                egui::vec2(size.width as f32, size.height as f32)
+            }),
+
+            zoom_factor: Some(egui_zoom_factor),
         }
     }
 
+    /// The version of the format these settings were stored with.
+    ///
+    /// After [`Self::migrate`], this is [`Self::CURRENT_VERSION`].
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Upgrade settings loaded from an older version of the format.
+    ///
+    /// Call this right after deserializing.
+    /// Settings from a newer version are kept as they are, ignoring what we don't understand.
+    pub fn migrate(&mut self) {
+        if Self::CURRENT_VERSION < self.version {
+            log::debug!(
+                "Window settings are from a newer format (version {}); some of them may be ignored",
+                self.version
+            );
+            return;
+        }
+
+        // Version 0 had no monitor or zoom factor, which are left as `None`.
+        // The window is then restored on whatever monitor contains its position,
+        // at the zoom factor the app starts with.
+        self.version = Self::CURRENT_VERSION;
+    }
+
     pub fn inner_size_points(&self) -> Option<egui::Vec2> {
         self.inner_size_points
     }
 
+    /// The zoom factor that was used in the window, if known.
+    ///
+    /// Restore it with [`egui::Context::set_zoom_factor`].
+    pub fn zoom_factor(&self) -> Option<f32> {
+        self.zoom_factor
+    }
+
+    /// The name of the monitor the window was on, if known.
+    pub fn monitor_name(&self) -> Option<&str> {
+        self.monitor_name.as_deref()
+    }
+
     pub fn initialize_viewport_builder(
         &self,
         egui_zoom_factor: f32,
@@ -71,7 +154,9 @@ impl WindowSettings {
         if let Some(pos) = pos_px {
             let monitor_scale_factor = if let Some(inner_size_points) = self.inner_size_points {
                 find_active_monitor(egui_zoom_factor, event_loop, inner_size_points, &pos)
-                    .map_or(1.0, |monitor| monitor.scale_factor() as f32)
+                    .map_or(1.0, |monitor| {
+                        crate::sanitize_scale_factor(monitor.scale_factor())
+                    })
             } else {
                 1.0
             };
@@ -114,11 +199,17 @@ impl WindowSettings {
         }
     }
 
+    /// Make sure the window ends up on a monitor that is connected.
+    ///
+    /// If the monitor the window was on has been moved in the display arrangement,
+    /// the window is moved along with it.
     pub fn clamp_position_to_monitors(
         &mut self,
         egui_zoom_factor: f32,
         event_loop: &winit::event_loop::ActiveEventLoop,
     ) {
+        self.follow_monitor(event_loop);
+
         // If the app last ran on two monitors and only one is now connected, then
         // the given position is invalid.
         // If this happens on Mac, the window is clamped into valid area.
@@ -139,6 +230,85 @@ impl WindowSettings {
             clamp_pos_to_monitors(egui_zoom_factor, event_loop, inner_size_points, pos_px);
         }
     }
+
+    /// If the monitor we were on is still connected, but at a different position,
+    /// move the window by the same amount so it reopens on that monitor.
+    fn follow_monitor(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let (Some(name), Some(old_monitor_pos)) =
+            (&self.monitor_name, self.monitor_position_pixels)
+        else {
+            return;
+        };
+
+        let monitors = event_loop.available_monitors().map(|monitor| {
+            let pos = monitor.position();
+            let size = monitor.size();
+            MonitorDesc {
+                name: monitor.name(),
+                position: egui::pos2(pos.x as f32, pos.y as f32),
+                size: egui::vec2(size.width as f32, size.height as f32),
+            }
+        });
+        let Some(new_monitor_pos) =
+            find_moved_monitor(name, old_monitor_pos, self.monitor_size_pixels, monitors)
+        else {
+            return; // Not moved, disconnected, or we can't tell which one it is.
+        };
+
+        let delta = new_monitor_pos - old_monitor_pos;
+        if delta != egui::Vec2::ZERO {
+            for pos in [
+                &mut self.inner_position_pixels,
+                &mut self.outer_position_pixels,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                *pos += delta;
+            }
+            self.monitor_position_pixels = Some(new_monitor_pos);
+        }
+    }
+}
+
+/// What we know about a connected monitor, see [`find_moved_monitor`].
+struct MonitorDesc {
+    name: Option<String>,
+    position: egui::Pos2,
+    size: egui::Vec2,
+}
+
+/// The new position of the monitor with this name (and size, if known) that used to be at `old_position`.
+///
+/// Names are not unique, e.g. with two monitors of the same model,
+/// so this is `None` if the monitor is still at its old position
+/// (even if there are others of the same name elsewhere),
+/// if it is disconnected, or if several monitors elsewhere could be it.
+fn find_moved_monitor(
+    name: &str,
+    old_position: egui::Pos2,
+    old_size: Option<egui::Vec2>,
+    monitors: impl Iterator<Item = MonitorDesc>,
+) -> Option<egui::Pos2> {
+    let candidates = monitors.filter(|monitor| {
+        monitor.name.as_deref() == Some(name) && old_size.map_or(true, |size| monitor.size == size)
+    });
+
+    let mut moved_to = None;
+    let mut num_moved = 0;
+    for monitor in candidates {
+        if monitor.position == old_position {
+            return None; // It didn't move.
+        }
+        moved_to = Some(monitor.position);
+        num_moved += 1;
+    }
+
+    if num_moved == 1 {
+        moved_to
+    } else {
+        None
+    }
 }
 
 fn find_active_monitor(
@@ -159,7 +329,8 @@ fn find_active_monitor(
     };
 
     for monitor in monitors {
-        let window_size_px = window_size_pts * (egui_zoom_factor * monitor.scale_factor() as f32);
+        let window_size_px = window_size_pts
+            * (egui_zoom_factor * crate::sanitize_scale_factor(monitor.scale_factor()));
         let monitor_x_range = (monitor.position().x - window_size_px.x as i32)
             ..(monitor.position().x + monitor.size().width as i32);
         let monitor_y_range = (monitor.position().y - window_size_px.y as i32)
@@ -189,13 +360,13 @@ fn clamp_pos_to_monitors(
         return; // no monitors 🤷
     };
 
-    let mut window_size_px =
-        window_size_pts * (egui_zoom_factor * active_monitor.scale_factor() as f32);
+    let mut window_size_px = window_size_pts
+        * (egui_zoom_factor * crate::sanitize_scale_factor(active_monitor.scale_factor()));
     // Add size of title bar. This is 32 px by default in Win 10/11.
     if cfg!(target_os = "windows") {
         window_size_px += egui::Vec2::new(
             0.0,
-            32.0 * egui_zoom_factor * active_monitor.scale_factor() as f32,
+            32.0 * egui_zoom_factor * crate::sanitize_scale_factor(active_monitor.scale_factor()),
         );
     }
     let monitor_position = egui::Pos2::new(
@@ -214,3 +385,118 @@ fn clamp_pos_to_monitors(
     // can have.
     *position_px = position_px.clamp(monitor_position, monitor_position + window_size);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: f32, width: f32) -> MonitorDesc {
+        MonitorDesc {
+            name: Some(name.to_owned()),
+            position: egui::pos2(x, 0.0),
+            size: egui::vec2(width, 1080.0),
+        }
+    }
+
+    #[test]
+    fn moved_monitor_is_followed() {
+        let size = Some(egui::vec2(1920.0, 1080.0));
+        let old_position = egui::pos2(1920.0, 0.0);
+
+        let monitors = [monitor("A", 0.0, 1920.0), monitor("B", 2560.0, 1920.0)];
+        assert_eq!(
+            find_moved_monitor("B", old_position, size, monitors.into_iter()),
+            Some(egui::pos2(2560.0, 0.0))
+        );
+
+        // Not moved:
+        let monitors = [monitor("A", 0.0, 1920.0), monitor("B", 1920.0, 1920.0)];
+        assert_eq!(
+            find_moved_monitor("B", old_position, size, monitors.into_iter()),
+            None
+        );
+
+        // Disconnected:
+        let monitors = [monitor("A", 0.0, 1920.0)];
+        assert_eq!(
+            find_moved_monitor("B", old_position, size, monitors.into_iter()),
+            None
+        );
+    }
+
+    #[test]
+    fn monitors_are_not_matched_by_name_only() {
+        let size = Some(egui::vec2(1920.0, 1080.0));
+        let old_position = egui::pos2(1920.0, 0.0);
+
+        // Another monitor of the same name is still where ours was:
+        let monitors = [
+            monitor("Same", 0.0, 1920.0),
+            monitor("Same", 1920.0, 1920.0),
+        ];
+        assert_eq!(
+            find_moved_monitor("Same", old_position, size, monitors.into_iter()),
+            None
+        );
+
+        // Two monitors of the same name elsewhere, and we can't tell which one was ours:
+        let monitors = [
+            monitor("Same", 3840.0, 1920.0),
+            monitor("Same", 5760.0, 1920.0),
+        ];
+        assert_eq!(
+            find_moved_monitor("Same", old_position, size, monitors.into_iter()),
+            None
+        );
+
+        // …unless the size tells them apart:
+        let monitors = [
+            monitor("Same", 3840.0, 2560.0),
+            monitor("Same", 6400.0, 1920.0),
+        ];
+        assert_eq!(
+            find_moved_monitor("Same", old_position, size, monitors.into_iter()),
+            Some(egui::pos2(6400.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn migrate_version_0() {
+        let mut settings = WindowSettings {
+            version: 0,
+            maximized: true,
+            inner_size_points: Some(egui::vec2(800.0, 600.0)),
+            ..Default::default()
+        };
+        settings.migrate();
+        assert_eq!(settings.version(), WindowSettings::CURRENT_VERSION);
+        assert!(settings.maximized);
+        assert_eq!(settings.zoom_factor(), None);
+        assert_eq!(settings.monitor_name(), None);
+
+        // Settings from the future are kept as they are:
+        let mut settings = WindowSettings {
+            version: WindowSettings::CURRENT_VERSION + 1,
+            ..Default::default()
+        };
+        settings.migrate();
+        assert_eq!(settings.version(), WindowSettings::CURRENT_VERSION + 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn migrate_unversioned_settings() {
+        // As stored before the format was versioned:
+        let old = "(inner_position_pixels:Some((x:10.0,y:20.0)),outer_position_pixels:Some((x:9.0,y:0.0)),fullscreen:false,maximized:true,inner_size_points:Some((x:800.0,y:600.0)))";
+
+        let mut settings: WindowSettings = ron::from_str(old).unwrap();
+        assert_eq!(settings.version(), 0);
+        assert!(settings.maximized);
+        assert_eq!(settings.inner_size_points(), Some(egui::vec2(800.0, 600.0)));
+        assert_eq!(settings.zoom_factor(), None);
+        assert_eq!(settings.monitor_name(), None);
+
+        settings.migrate();
+        assert_eq!(settings.version(), WindowSettings::CURRENT_VERSION);
+    }
+}
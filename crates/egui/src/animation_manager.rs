@@ -1,7 +1,4 @@
-use crate::{
-    emath::{remap_clamp, NumExt as _},
-    Id, IdMap, InputState,
-};
+use crate::{emath::NumExt as _, Id, IdMap, InputState, Transition};
 
 #[derive(Clone, Default)]
 pub(crate) struct AnimationManager {
@@ -65,46 +62,48 @@ impl AnimationManager {
         }
     }
 
+    pub fn clear(&mut self, id: Id) {
+        self.bools.remove(&id);
+        self.values.remove(&id);
+    }
+
+    /// See [`crate::Context::animate_value_with_transition`] for documentation.
+    ///
+    /// If this is the first time we see `id`, we start animating from `initial_value` if given,
+    /// otherwise we start at `value`.
     pub fn animate_value(
         &mut self,
         input: &InputState,
-        animation_time: f32,
+        transition: &Transition,
         id: Id,
         value: f32,
+        initial_value: Option<f32>,
     ) -> f32 {
-        match self.values.get_mut(&id) {
-            None => {
-                self.values.insert(
-                    id,
-                    ValueAnim {
-                        from_value: value,
-                        to_value: value,
-                        toggle_time: -f64::INFINITY, // long time ago
-                    },
-                );
-                value
-            }
-            Some(anim) => {
-                let time_since_toggle = (input.time - anim.toggle_time) as f32;
-                // On the frame we toggle we don't want to return the old value,
-                // so we extrapolate forwards by half a frame:
-                let time_since_toggle = time_since_toggle + input.predicted_dt / 2.0;
-                let current_value = remap_clamp(
-                    time_since_toggle,
-                    0.0..=animation_time,
-                    anim.from_value..=anim.to_value,
-                );
-                if anim.to_value != value {
-                    anim.from_value = current_value; //start new animation from current position of playing animation
-                    anim.to_value = value;
-                    anim.toggle_time = input.time;
-                }
-                if animation_time == 0.0 {
-                    anim.from_value = value;
-                    anim.to_value = value;
-                }
-                current_value
-            }
+        let anim = self.values.entry(id).or_insert_with(|| ValueAnim {
+            from_value: initial_value.unwrap_or(value),
+            to_value: value,
+            toggle_time: if initial_value.is_some() {
+                input.time
+            } else {
+                -f64::INFINITY // long time ago
+            },
+        });
+
+        let time_since_toggle = (input.time - anim.toggle_time) as f32;
+        // On the frame we toggle we don't want to return the old value,
+        // so we extrapolate forwards by half a frame:
+        let time_since_toggle = time_since_toggle + input.predicted_dt / 2.0;
+        let t = transition.progress(time_since_toggle);
+        let current_value = emath::lerp(anim.from_value..=anim.to_value, t);
+        if anim.to_value != value {
+            anim.from_value = current_value; //start new animation from current position of playing animation
+            anim.to_value = value;
+            anim.toggle_time = input.time;
+        }
+        if transition.total_time() == 0.0 {
+            anim.from_value = value;
+            anim.to_value = value;
         }
+        current_value
     }
 }
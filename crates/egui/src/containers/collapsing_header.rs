@@ -1,8 +1,9 @@
 use std::hash::Hash;
 
 use crate::{
-    emath, epaint, pos2, remap, remap_clamp, vec2, Context, Id, InnerResponse, NumExt, Rect,
-    Response, Sense, Stroke, TextStyle, TextWrapMode, Ui, Vec2, WidgetInfo, WidgetText, WidgetType,
+    emath, epaint, pos2, remap, remap_clamp, vec2, Context, EventFilter, Id, InnerResponse, Key,
+    NumExt, Rect, Response, Sense, Stroke, TextStyle, TextWrapMode, Ui, Vec2, WidgetInfo,
+    WidgetText, WidgetType,
 };
 use emath::GuiRounding as _;
 use epaint::Shape;
@@ -559,6 +560,28 @@ impl CollapsingHeader {
         } else if header_response.clicked() {
             state.toggle(ui);
             header_response.mark_changed();
+        } else if header_response.has_focus() {
+            // Like in a tree view, the left and right arrows collapse and expand,
+            // while up and down move focus:
+            ui.memory_mut(|mem| {
+                mem.set_focus_lock_filter(
+                    id,
+                    EventFilter {
+                        horizontal_arrows: true,
+                        ..Default::default()
+                    },
+                );
+            });
+            let (left, right) = ui.input(|i| {
+                (
+                    i.key_pressed(Key::ArrowLeft),
+                    i.key_pressed(Key::ArrowRight),
+                )
+            });
+            if (left && state.is_open()) || (right && !state.is_open()) {
+                state.toggle(ui);
+                header_response.mark_changed();
+            }
         }
 
         header_response.widget_info(|| {
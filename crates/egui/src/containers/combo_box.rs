@@ -1,9 +1,9 @@
 use epaint::Shape;
 
 use crate::{
-    epaint, style::WidgetVisuals, vec2, Align2, Context, Id, InnerResponse, NumExt, Painter,
-    PopupCloseBehavior, Rect, Response, ScrollArea, Sense, Stroke, TextStyle, TextWrapMode, Ui,
-    UiBuilder, Vec2, WidgetInfo, WidgetText, WidgetType,
+    epaint, style::WidgetVisuals, vec2, Align2, Context, Id, InnerResponse, NavScope, NumExt,
+    Painter, PopupCloseBehavior, Rect, Response, ScrollArea, Sense, Stroke, TextStyle,
+    TextWrapMode, Ui, UiBuilder, Vec2, WidgetInfo, WidgetText, WidgetType,
 };
 
 #[allow(unused_imports)] // Documentation
@@ -415,6 +415,8 @@ fn combo_box_dyn<'c, R>(
         above_or_below,
         close_behavior,
         |ui| {
+            // The arrow keys move between the items, and not out of the popup:
+            ui.set_nav_scope(Some(NavScope::new(popup_id).contained(true)));
             ScrollArea::vertical()
                 .max_height(height)
                 .show(ui, |ui| {
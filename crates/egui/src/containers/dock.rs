@@ -0,0 +1,1133 @@
+//! A docking system: tabs the user can drag around to split, regroup, and tear off into their own windows.
+//!
+//! The layout lives in a [`DockState`], which you own and can persist with [`DockState::store`].
+//! The contents of the tabs are shown by your implementation of [`TabViewer`].
+//!
+//! ```
+//! # egui::__run_test_ui(|ui| {
+//! use egui::dock::{DockArea, DockState, TabViewer};
+//!
+//! struct Viewer;
+//!
+//! impl TabViewer for Viewer {
+//!     type Tab = String;
+//!
+//!     fn title(&mut self, tab: &mut String) -> egui::WidgetText {
+//!         tab.as_str().into()
+//!     }
+//!
+//!     fn id(&mut self, tab: &mut String) -> egui::Id {
+//!         egui::Id::new(tab.as_str())
+//!     }
+//!
+//!     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut String) {
+//!         ui.label(format!("Contents of {tab}"));
+//!     }
+//! }
+//!
+//! let id = egui::Id::new("my_dock_state");
+//! let mut state = DockState::load(ui.ctx(), id)
+//!     .unwrap_or_else(|| DockState::new(vec!["Scene".to_owned(), "Inspector".to_owned()]));
+//! DockArea::new(&mut state).show_inside(ui, &mut Viewer);
+//! state.store(ui.ctx(), id);
+//! # });
+//! ```
+
+use std::hash::Hash;
+
+use emath::{pos2, vec2, NumExt as _, Pos2, Rect, Vec2};
+
+use crate::{
+    util::id_type_map::SerializableAny, CentralPanel, Context, CursorIcon, DragAndDrop, Frame, Id,
+    Rounding, ScrollArea, Sense, TextStyle, TextWrapMode, Ui, UiBuilder, ViewportBuilder,
+    ViewportClass, ViewportId, WidgetText, Window,
+};
+
+/// How a [`DockNode::Split`] divides its space between its two children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SplitDirection {
+    /// Side by side: the first child to the left, the second to the right.
+    Horizontal,
+
+    /// Stacked: the first child on top, the second below.
+    Vertical,
+}
+
+impl SplitDirection {
+    #[inline]
+    fn axis(self) -> usize {
+        match self {
+            Self::Horizontal => 0,
+            Self::Vertical => 1,
+        }
+    }
+}
+
+/// A node in the layout of a [`DockState`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum DockNode<Tab> {
+    /// A group of tabs, of which the active one is shown.
+    Leaf { tabs: Vec<Tab>, active: usize },
+
+    /// Two nodes sharing the space.
+    Split {
+        direction: SplitDirection,
+
+        /// How much of the space goes to the first child, in `0..=1`.
+        fraction: f32,
+
+        children: Box<[DockNode<Tab>; 2]>,
+    },
+}
+
+impl<Tab> DockNode<Tab> {
+    /// A group of tabs, with the first one active.
+    pub fn leaf(tabs: Vec<Tab>) -> Self {
+        Self::Leaf { tabs, active: 0 }
+    }
+
+    /// Split the space between `self` and `other`, giving `fraction` of it to `self`.
+    pub fn split(self, direction: SplitDirection, fraction: f32, other: Self) -> Self {
+        Self::Split {
+            direction,
+            fraction: fraction.clamp(0.0, 1.0),
+            children: Box::new([self, other]),
+        }
+    }
+
+    /// All the tabs in this node, in order.
+    pub fn tabs(&self) -> Box<dyn Iterator<Item = &Tab> + '_> {
+        match self {
+            Self::Leaf { tabs, .. } => Box::new(tabs.iter()),
+            Self::Split { children, .. } => {
+                Box::new(children.iter().flat_map(|child| child.tabs()))
+            }
+        }
+    }
+
+    /// Add a tab to the first group of tabs and make it active.
+    pub fn push_tab(&mut self, tab: Tab) {
+        match self {
+            Self::Leaf { tabs, active } => {
+                tabs.push(tab);
+                *active = tabs.len() - 1;
+            }
+            Self::Split { children, .. } => children[0].push_tab(tab),
+        }
+    }
+
+    fn is_empty_leaf(&self) -> bool {
+        matches!(self, Self::Leaf { tabs, .. } if tabs.is_empty())
+    }
+
+    fn into_tabs(self) -> Vec<Tab> {
+        match self {
+            Self::Leaf { tabs, .. } => tabs,
+            Self::Split { children, .. } => {
+                let [first, second] = *children;
+                let mut tabs = first.into_tabs();
+                tabs.extend(second.into_tabs());
+                tabs
+            }
+        }
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut Self> {
+        match path.split_first() {
+            None => Some(self),
+            Some((&child, rest)) => match self {
+                Self::Leaf { .. } => None,
+                Self::Split { children, .. } => children.get_mut(child)?.node_mut(rest),
+            },
+        }
+    }
+
+    /// The active tab of the first group of tabs.
+    fn first_active_tab_mut(&mut self) -> Option<&mut Tab> {
+        match self {
+            Self::Leaf { tabs, active } => tabs.get_mut(*active),
+            Self::Split { children, .. } => children[0].first_active_tab_mut(),
+        }
+    }
+
+    fn take_tab(&mut self, index: usize) -> Option<Tab> {
+        let Self::Leaf { tabs, active } = self else {
+            return None;
+        };
+        if tabs.len() <= index {
+            return None;
+        }
+        let tab = tabs.remove(index);
+        if index < *active || tabs.len() <= *active {
+            *active = active.saturating_sub(1);
+        }
+        Some(tab)
+    }
+
+    fn insert(&mut self, zone: DropZone, tab: Tab) {
+        let side = match zone {
+            DropZone::Tabs(index) => {
+                if let Self::Leaf { tabs, active } = self {
+                    let index = index.min(tabs.len());
+                    tabs.insert(index, tab);
+                    *active = index;
+                } else {
+                    self.push_tab(tab);
+                }
+                return;
+            }
+            DropZone::Center => {
+                self.push_tab(tab);
+                return;
+            }
+            DropZone::Left | DropZone::Right | DropZone::Top | DropZone::Bottom => zone,
+        };
+
+        let old = std::mem::replace(self, Self::leaf(Vec::new()));
+        let new = Self::leaf(vec![tab]);
+        *self = match side {
+            DropZone::Left => new.split(SplitDirection::Horizontal, 0.5, old),
+            DropZone::Top => new.split(SplitDirection::Vertical, 0.5, old),
+            DropZone::Right => old.split(SplitDirection::Horizontal, 0.5, new),
+            _ => old.split(SplitDirection::Vertical, 0.5, new),
+        };
+    }
+
+    fn retain_tabs(&mut self, keep: &mut impl FnMut(&mut Tab) -> bool) {
+        match self {
+            Self::Leaf { tabs, active } => {
+                tabs.retain_mut(|tab| keep(tab));
+                *active = (*active).min(tabs.len().saturating_sub(1));
+            }
+            Self::Split { children, .. } => {
+                for child in children.iter_mut() {
+                    child.retain_tabs(keep);
+                }
+            }
+        }
+    }
+
+    fn focus_tab(&mut self, predicate: &mut impl FnMut(&Tab) -> bool) -> bool {
+        match self {
+            Self::Leaf { tabs, active } => {
+                if let Some(index) = tabs.iter().position(&mut *predicate) {
+                    *active = index;
+                    true
+                } else {
+                    false
+                }
+            }
+            Self::Split { children, .. } => {
+                children.iter_mut().any(|child| child.focus_tab(predicate))
+            }
+        }
+    }
+
+    /// Collapse splits where one of the children has no tabs.
+    fn simplify(&mut self) {
+        if let Self::Split { children, .. } = self {
+            for child in children.iter_mut() {
+                child.simplify();
+            }
+            if children.iter().any(Self::is_empty_leaf) {
+                let [first, second] = *std::mem::replace(
+                    children,
+                    Box::new([Self::leaf(Vec::new()), Self::leaf(Vec::new())]),
+                );
+                *self = if first.is_empty_leaf() { second } else { first };
+            }
+        }
+    }
+}
+
+/// Tabs torn off from the [`DockArea`] into their own window.
+///
+/// If the platform supports multiple viewports, this is a native window,
+/// otherwise it is a [`Window`] inside the viewport of the [`DockArea`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct DockWindow<Tab> {
+    /// Identifies the window, or its viewport.
+    pub id: Id,
+
+    pub root: DockNode<Tab>,
+
+    /// The position and size of the window, in points.
+    ///
+    /// For a native window, this is the outer position on the desktop, and the inner size.
+    pub rect: Rect,
+}
+
+/// The layout of a [`DockArea`]: which tabs there are, and where.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct DockState<Tab> {
+    /// The tabs in the [`DockArea`] itself.
+    pub root: DockNode<Tab>,
+
+    /// The tabs that have been torn off into their own windows.
+    pub windows: Vec<DockWindow<Tab>>,
+}
+
+impl<Tab> Default for DockState<Tab> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl<Tab> DockState<Tab> {
+    /// All the tabs in one group, with the first one active.
+    pub fn new(tabs: Vec<Tab>) -> Self {
+        Self {
+            root: DockNode::leaf(tabs),
+            windows: Vec::new(),
+        }
+    }
+
+    /// All the tabs, including the ones in windows.
+    pub fn iter_tabs(&self) -> impl Iterator<Item = &Tab> + '_ {
+        std::iter::once(&self.root)
+            .chain(self.windows.iter().map(|window| &window.root))
+            .flat_map(|node| node.tabs())
+    }
+
+    /// Add a tab to the first group of tabs in the [`DockArea`], and make it active.
+    pub fn push_tab(&mut self, tab: Tab) {
+        self.root.push_tab(tab);
+    }
+
+    /// Remove the tabs for which `keep` returns `false`.
+    pub fn retain_tabs(&mut self, mut keep: impl FnMut(&mut Tab) -> bool) {
+        self.root.retain_tabs(&mut keep);
+        for window in &mut self.windows {
+            window.root.retain_tabs(&mut keep);
+        }
+        self.simplify();
+    }
+
+    /// Make the first tab matching `predicate` the active one in its group.
+    ///
+    /// Returns `false` if no tab matched.
+    pub fn focus_tab(&mut self, mut predicate: impl FnMut(&Tab) -> bool) -> bool {
+        self.root.focus_tab(&mut predicate)
+            || self
+                .windows
+                .iter_mut()
+                .any(|window| window.root.focus_tab(&mut predicate))
+    }
+
+    fn surface_mut(&mut self, surface: Surface) -> Option<&mut DockNode<Tab>> {
+        match surface {
+            Surface::Main => Some(&mut self.root),
+            Surface::Window(index) => self.windows.get_mut(index).map(|window| &mut window.root),
+        }
+    }
+
+    fn take_tab(&mut self, location: &TabLocation) -> Option<Tab> {
+        self.surface_mut(location.surface)?
+            .node_mut(&location.path)?
+            .take_tab(location.index)
+    }
+
+    fn simplify(&mut self) {
+        self.root.simplify();
+        for window in &mut self.windows {
+            window.root.simplify();
+        }
+        self.windows.retain(|window| !window.root.is_empty_leaf());
+    }
+
+    fn apply<V: TabViewer<Tab = Tab>>(&mut self, action: Action, viewer: &mut V) {
+        match action {
+            Action::Close(location) => {
+                let close = self
+                    .surface_mut(location.surface)
+                    .and_then(|node| node.node_mut(&location.path))
+                    .and_then(|node| match node {
+                        DockNode::Leaf { tabs, .. } => tabs.get_mut(location.index),
+                        DockNode::Split { .. } => None,
+                    })
+                    .is_some_and(|tab| viewer.on_close(tab));
+                if close {
+                    self.take_tab(&location);
+                }
+            }
+            Action::Move { from, mut to } => {
+                let Some(tab) = self.take_tab(&from) else {
+                    return;
+                };
+                if let DropZone::Tabs(index) = &mut to.zone {
+                    if from.surface == to.surface && from.path == to.path && from.index < *index {
+                        *index -= 1;
+                    }
+                }
+                // The paths are still valid, since empty groups are only removed by `simplify`:
+                match self
+                    .surface_mut(to.surface)
+                    .and_then(|node| node.node_mut(&to.path))
+                {
+                    Some(node) => node.insert(to.zone, tab),
+                    None => self.root.push_tab(tab),
+                }
+            }
+            Action::TearOff { from, rect } => {
+                if let Some(mut tab) = self.take_tab(&from) {
+                    self.windows.push(DockWindow {
+                        id: viewer.id(&mut tab).with("dock_window"),
+                        root: DockNode::leaf(vec![tab]),
+                        rect,
+                    });
+                }
+            }
+            Action::CloseWindow(index) => {
+                if index < self.windows.len() {
+                    for tab in self.windows.remove(index).root.into_tabs() {
+                        self.root.push_tab(tab);
+                    }
+                }
+            }
+        }
+        self.simplify();
+    }
+}
+
+impl<Tab: SerializableAny> DockState<Tab> {
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_persisted(id))
+    }
+
+    pub fn store(&self, ctx: &Context, id: Id) {
+        ctx.data_mut(|d| d.insert_persisted(id, self.clone()));
+    }
+}
+
+/// Shows the contents of the tabs of a [`DockArea`].
+pub trait TabViewer {
+    type Tab;
+
+    /// The text in the header of the tab.
+    fn title(&mut self, tab: &mut Self::Tab) -> WidgetText;
+
+    /// Show the contents of the tab.
+    fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab);
+
+    /// A unique id for the tab, used for the state of the widgets in it.
+    ///
+    /// It must stay the same while the tab is moved around, and differ between tabs,
+    /// even ones with the same title.
+    fn id(&mut self, tab: &mut Self::Tab) -> Id;
+
+    /// Does the tab have a close button? Default: `true`.
+    fn closeable(&mut self, _tab: &mut Self::Tab) -> bool {
+        true
+    }
+
+    /// The user wants to close the tab.
+    ///
+    /// Return `false` to keep it open, e.g. to first ask about unsaved changes.
+    fn on_close(&mut self, _tab: &mut Self::Tab) -> bool {
+        true
+    }
+}
+
+/// Tabs that the user can drag around to split the space, regroup them,
+/// or tear them off into their own windows.
+///
+/// See the [module-level docs](self) for an example.
+#[must_use = "You should call .show() or .show_inside()"]
+pub struct DockArea<'a, Tab> {
+    id_salt: Id,
+    state: &'a mut DockState<Tab>,
+    show_close_buttons: bool,
+    tear_off: bool,
+}
+
+impl<'a, Tab> DockArea<'a, Tab> {
+    pub fn new(state: &'a mut DockState<Tab>) -> Self {
+        Self {
+            id_salt: Id::new("dock_area"),
+            state,
+            show_close_buttons: true,
+            tear_off: true,
+        }
+    }
+
+    /// Must be unique among the dock areas in the same [`Ui`].
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl Hash) -> Self {
+        self.id_salt = Id::new(id_salt);
+        self
+    }
+
+    /// Show close buttons on the tabs that are [`TabViewer::closeable`]. Default: `true`.
+    #[inline]
+    pub fn show_close_buttons(mut self, show_close_buttons: bool) -> Self {
+        self.show_close_buttons = show_close_buttons;
+        self
+    }
+
+    /// Can the user tear off tabs into their own windows,
+    /// by dropping them outside the dock area? Default: `true`.
+    ///
+    /// The windows are native windows if the platform supports multiple viewports.
+    #[inline]
+    pub fn tear_off(mut self, tear_off: bool) -> Self {
+        self.tear_off = tear_off;
+        self
+    }
+
+    /// Fill the space left after the panels, like a [`CentralPanel`].
+    pub fn show(self, ctx: &Context, viewer: &mut impl TabViewer<Tab = Tab>) {
+        CentralPanel::default()
+            .frame(Frame::central_panel(&ctx.style()).inner_margin(0.0))
+            .show(ctx, |ui| {
+                self.show_inside(ui, viewer);
+            });
+    }
+
+    /// Fill the available space of the [`Ui`].
+    pub fn show_inside(self, ui: &mut Ui, viewer: &mut impl TabViewer<Tab = Tab>) {
+        let Self {
+            id_salt,
+            state,
+            show_close_buttons,
+            tear_off,
+        } = self;
+
+        let rect = ui.available_rect_before_wrap();
+        let mut dock = DockUi {
+            id: ui.make_persistent_id(id_salt),
+            viewer,
+            show_close_buttons,
+            tear_off,
+            window_rects: state.windows.iter().map(|window| window.rect).collect(),
+            action: None,
+        };
+
+        dock.show_node(ui, rect, &mut state.root, Surface::Main, &mut Vec::new());
+        for (index, window) in state.windows.iter_mut().enumerate() {
+            dock.show_window(ui.ctx(), index, window);
+        }
+        dock.drop_outside(ui.ctx(), Surface::Main, rect);
+        ui.allocate_rect(rect, Sense::hover());
+
+        let DockUi { viewer, action, .. } = dock;
+        if let Some(action) = action {
+            state.apply(action, viewer);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Where tabs are shown: in the [`DockArea`] itself, or in one of its windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Surface {
+    Main,
+    Window(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TabLocation {
+    surface: Surface,
+
+    /// The child indices leading from the root to the group of tabs.
+    path: Vec<usize>,
+
+    index: usize,
+}
+
+/// Where in a group of tabs a dragged tab is dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DropZone {
+    /// In the tab bar, before the tab with this index.
+    Tabs(usize),
+
+    /// Into the group.
+    Center,
+
+    /// Split the group, with the tab on this side.
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct DropTarget {
+    surface: Surface,
+    path: Vec<usize>,
+    zone: DropZone,
+}
+
+/// The payload when dragging a tab.
+struct TabDrag {
+    dock_id: Id,
+    from: TabLocation,
+
+    /// The size of the group the tab was dragged from, used when tearing it off.
+    size: Vec2,
+}
+
+/// A change to the [`DockState`], applied after showing it.
+enum Action {
+    Close(TabLocation),
+    Move { from: TabLocation, to: DropTarget },
+    TearOff { from: TabLocation, rect: Rect },
+    CloseWindow(usize),
+}
+
+struct DockUi<'v, V> {
+    id: Id,
+    viewer: &'v mut V,
+    show_close_buttons: bool,
+    tear_off: bool,
+    window_rects: Vec<Rect>,
+    action: Option<Action>,
+}
+
+impl<V: TabViewer> DockUi<'_, V> {
+    fn show_node(
+        &mut self,
+        ui: &mut Ui,
+        rect: Rect,
+        node: &mut DockNode<V::Tab>,
+        surface: Surface,
+        path: &mut Vec<usize>,
+    ) {
+        match node {
+            DockNode::Leaf { tabs, active } => {
+                self.show_leaf(ui, rect, tabs, active, surface, path);
+            }
+            DockNode::Split {
+                direction,
+                fraction,
+                children,
+            } => {
+                let axis = direction.axis();
+                let gap = ui.spacing().item_spacing[axis];
+                let size = (rect.size()[axis] - gap).at_least(0.0);
+                let split = rect.min[axis] + size * *fraction;
+
+                let mut first = rect;
+                let mut second = rect;
+                let mut separator = rect;
+                first.max[axis] = split;
+                second.min[axis] = split + gap;
+                separator.min[axis] = split;
+                separator.max[axis] = split + gap;
+
+                for (child_index, (child, child_rect)) in
+                    children.iter_mut().zip([first, second]).enumerate()
+                {
+                    path.push(child_index);
+                    self.show_node(ui, child_rect, child, surface, path);
+                    path.pop();
+                }
+
+                let mut grab_margin = Vec2::ZERO;
+                grab_margin[axis] = ui.style().interaction.resize_grab_radius_side;
+                let response = ui.interact(
+                    separator.expand2(grab_margin),
+                    self.id.with(("separator", surface, &*path)),
+                    Sense::drag(),
+                );
+                if response.dragged() && 0.0 < size {
+                    let min_fraction = (2.0 * ui.spacing().interact_size.y / size).min(0.5);
+                    *fraction = (*fraction + response.drag_delta()[axis] / size)
+                        .clamp(min_fraction, 1.0 - min_fraction);
+                }
+                if response.hovered() || response.dragged() {
+                    ui.ctx().set_cursor_icon(match direction {
+                        SplitDirection::Horizontal => CursorIcon::ResizeHorizontal,
+                        SplitDirection::Vertical => CursorIcon::ResizeVertical,
+                    });
+                }
+
+                let stroke = if response.dragged() {
+                    ui.visuals().widgets.active.bg_stroke
+                } else if response.hovered() {
+                    ui.visuals().widgets.hovered.bg_stroke
+                } else {
+                    ui.visuals().widgets.noninteractive.bg_stroke
+                };
+                let center = separator.center();
+                match direction {
+                    SplitDirection::Horizontal => {
+                        ui.painter().vline(center.x, separator.y_range(), stroke);
+                    }
+                    SplitDirection::Vertical => {
+                        ui.painter().hline(separator.x_range(), center.y, stroke);
+                    }
+                }
+            }
+        }
+    }
+
+    fn show_leaf(
+        &mut self,
+        ui: &mut Ui,
+        rect: Rect,
+        tabs: &mut [V::Tab],
+        active: &mut usize,
+        surface: Surface,
+        path: &[usize],
+    ) {
+        let bar_height = ui.spacing().interact_size.y;
+        let bar_rect = Rect::from_min_max(rect.min, pos2(rect.right(), rect.top() + bar_height));
+        let body_rect = Rect::from_min_max(pos2(rect.left(), bar_rect.bottom()), rect.max);
+
+        ui.painter()
+            .rect_filled(bar_rect, 0.0, ui.visuals().extreme_bg_color);
+
+        let mut bar_ui = ui.new_child(UiBuilder::new().max_rect(bar_rect));
+        bar_ui.set_clip_rect(bar_rect.intersect(ui.clip_rect()));
+
+        let mut tab_rects = Vec::with_capacity(tabs.len());
+        let mut x = bar_rect.left();
+        for (index, tab) in tabs.iter_mut().enumerate() {
+            let location = TabLocation {
+                surface,
+                path: path.to_vec(),
+                index,
+            };
+            let response = self.tab_header(
+                &bar_ui,
+                bar_rect,
+                x,
+                tab,
+                index == *active,
+                location,
+                rect.size(),
+            );
+            if response.clicked() {
+                *active = index;
+            }
+            x = response.rect.right();
+            tab_rects.push(response.rect);
+        }
+
+        ui.painter().hline(
+            bar_rect.x_range(),
+            bar_rect.bottom(),
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+
+        if let Some(tab) = tabs.get_mut(*active) {
+            let tab_id = self.viewer.id(tab);
+            let mut tab_ui = ui.new_child(
+                UiBuilder::new()
+                    .id_salt(tab_id)
+                    .max_rect(body_rect.shrink(f32::from(ui.spacing().window_margin.left))),
+            );
+            tab_ui.set_clip_rect(body_rect.intersect(ui.clip_rect()));
+            ScrollArea::both()
+                .id_salt(self.id.with(("body", tab_id)))
+                .auto_shrink(false)
+                .show(&mut tab_ui, |ui| self.viewer.ui(ui, tab));
+        }
+
+        self.drop_target(ui, rect, bar_rect, body_rect, &tab_rects, surface, path);
+    }
+
+    /// Returns the response of the header, which starts at `x`.
+    #[allow(clippy::too_many_arguments)]
+    fn tab_header(
+        &mut self,
+        ui: &Ui,
+        bar_rect: Rect,
+        x: f32,
+        tab: &mut V::Tab,
+        is_active: bool,
+        location: TabLocation,
+        group_size: Vec2,
+    ) -> crate::Response {
+        let title = self.viewer.title(tab);
+        let title_text = title.text().to_owned();
+        let galley = title.into_galley(
+            ui,
+            Some(TextWrapMode::Extend),
+            f32::INFINITY,
+            TextStyle::Button,
+        );
+        let closeable = self.show_close_buttons && self.viewer.closeable(tab);
+
+        let padding = ui.spacing().button_padding.x;
+        let close_size = ui.spacing().icon_width;
+        let mut width = padding + galley.size().x + padding;
+        if closeable {
+            width += ui.spacing().item_spacing.x + close_size;
+        }
+        let tab_rect = Rect::from_min_size(pos2(x, bar_rect.top()), vec2(width, bar_rect.height()));
+
+        let tab_id = self.id.with(("tab", self.viewer.id(tab)));
+        let response = ui.interact(tab_rect, tab_id, Sense::click_and_drag());
+        response.dnd_set_drag_payload(TabDrag {
+            dock_id: self.id,
+            from: location.clone(),
+            size: group_size,
+        });
+
+        let visuals = ui.visuals();
+        let rounding = visuals.widgets.inactive.rounding;
+        let rounding = Rounding {
+            sw: 0,
+            se: 0,
+            ..rounding
+        };
+        if is_active {
+            ui.painter().rect(
+                tab_rect,
+                rounding,
+                visuals.panel_fill,
+                visuals.widgets.noninteractive.bg_stroke,
+            );
+        } else if response.hovered() {
+            ui.painter()
+                .rect_filled(tab_rect, rounding, visuals.widgets.hovered.weak_bg_fill);
+        }
+        let text_color = if is_active {
+            visuals.strong_text_color()
+        } else {
+            visuals.widgets.style(&response).text_color()
+        };
+        let text_pos = pos2(
+            tab_rect.left() + padding,
+            tab_rect.center().y - 0.5 * galley.size().y,
+        );
+        ui.painter().galley(text_pos, galley, text_color);
+
+        let mut close = response.middle_clicked();
+        if closeable {
+            let close_rect = Rect::from_center_size(
+                pos2(
+                    tab_rect.right() - padding - 0.5 * close_size,
+                    tab_rect.center().y,
+                ),
+                Vec2::splat(close_size),
+            );
+            let close_response = ui.interact(close_rect, tab_id.with("close"), Sense::click());
+            let stroke = ui.visuals().widgets.style(&close_response).fg_stroke;
+            let cross = close_rect.shrink(0.25 * close_size);
+            ui.painter()
+                .line_segment([cross.left_top(), cross.right_bottom()], stroke);
+            ui.painter()
+                .line_segment([cross.right_top(), cross.left_bottom()], stroke);
+            close |= close_response.clicked();
+        }
+
+        let mut undock = false;
+        response.context_menu(|ui| {
+            if self.tear_off && ui.button("Undock").clicked() {
+                undock = true;
+                ui.close_menu();
+            }
+            if closeable && ui.button("Close").clicked() {
+                close = true;
+                ui.close_menu();
+            }
+        });
+
+        if response.dragged() {
+            crate::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), tab_id, |ui| {
+                ui.label(title_text);
+            });
+        }
+
+        if close {
+            self.action = Some(Action::Close(location));
+        } else if undock {
+            let offset = vec2(2.0, 1.0) * ui.spacing().interact_size.y;
+            let min = if ui.ctx().embed_viewports() {
+                tab_rect.left_bottom() + offset
+            } else {
+                screen_pos(ui.ctx(), tab_rect.left_bottom()) + offset
+            };
+            self.action = Some(Action::TearOff {
+                from: location,
+                rect: Rect::from_min_size(min, group_size),
+            });
+        }
+
+        response
+    }
+
+    /// Show where a tab dragged over this group of tabs would go, and move it there if dropped.
+    #[allow(clippy::too_many_arguments)]
+    fn drop_target(
+        &mut self,
+        ui: &Ui,
+        rect: Rect,
+        bar_rect: Rect,
+        body_rect: Rect,
+        tab_rects: &[Rect],
+        surface: Surface,
+        path: &[usize],
+    ) {
+        let Some(drag) = DragAndDrop::payload::<TabDrag>(ui.ctx()) else {
+            return;
+        };
+        if drag.dock_id != self.id || !ui.rect_contains_pointer(rect) {
+            return;
+        }
+        let Some(pointer) = ui.ctx().pointer_latest_pos() else {
+            return;
+        };
+
+        let zone = drop_zone(pointer, bar_rect, body_rect, tab_rects);
+        let preview = match zone {
+            DropZone::Tabs(index) => {
+                let x = match tab_rects.get(index) {
+                    Some(tab_rect) => tab_rect.left(),
+                    None => tab_rects.last().map_or(bar_rect.left(), |r| r.right()),
+                };
+                Rect::from_x_y_ranges(x - 1.0..=x + 1.0, bar_rect.y_range())
+            }
+            DropZone::Center => body_rect,
+            DropZone::Left => body_rect.with_max_x(body_rect.center().x),
+            DropZone::Right => body_rect.with_min_x(body_rect.center().x),
+            DropZone::Top => body_rect.with_max_y(body_rect.center().y),
+            DropZone::Bottom => body_rect.with_min_y(body_rect.center().y),
+        };
+        let selection = ui.visuals().selection;
+        ui.painter().rect(
+            preview,
+            ui.visuals().widgets.inactive.rounding,
+            selection.bg_fill.gamma_multiply(0.5),
+            selection.stroke,
+        );
+
+        if ui.input(|i| i.pointer.any_released()) {
+            if let Some(drag) = DragAndDrop::take_payload::<TabDrag>(ui.ctx()) {
+                self.action = Some(Action::Move {
+                    from: drag.from.clone(),
+                    to: DropTarget {
+                        surface,
+                        path: path.to_vec(),
+                        zone,
+                    },
+                });
+            }
+        }
+    }
+
+    /// A tab was dropped in the current viewport, but not on a group of tabs.
+    ///
+    /// If it was dropped outside of `rect`, move it to the window under the pointer, or tear it off.
+    fn drop_outside(&mut self, ctx: &Context, surface: Surface, rect: Rect) {
+        if !ctx.input(|i| i.pointer.any_released()) {
+            return;
+        }
+        let Some(drag) = DragAndDrop::payload::<TabDrag>(ctx) else {
+            return;
+        };
+        let Some(pointer) = ctx.pointer_latest_pos() else {
+            return;
+        };
+        if drag.dock_id != self.id || !self.tear_off || rect.contains(pointer) {
+            return;
+        }
+        DragAndDrop::clear_payload(ctx);
+
+        if ctx.embed_viewports() {
+            self.action = Some(Action::TearOff {
+                from: drag.from.clone(),
+                rect: Rect::from_min_size(pointer, drag.size),
+            });
+            return;
+        }
+
+        let pointer = screen_pos(ctx, pointer);
+        let to_surface = if surface != Surface::Main
+            && ctx
+                .input_for(ViewportId::ROOT, |i| i.viewport().inner_rect)
+                .is_some_and(|main_rect| main_rect.contains(pointer))
+        {
+            Some(Surface::Main)
+        } else {
+            self.window_rects
+                .iter()
+                .position(|window_rect| window_rect.contains(pointer))
+                .map(Surface::Window)
+                .filter(|&window| window != surface)
+        };
+
+        self.action = Some(match to_surface {
+            Some(surface) => Action::Move {
+                from: drag.from.clone(),
+                to: DropTarget {
+                    surface,
+                    path: Vec::new(),
+                    zone: DropZone::Center,
+                },
+            },
+            None => Action::TearOff {
+                from: drag.from.clone(),
+                rect: Rect::from_min_size(pointer, drag.size),
+            },
+        });
+    }
+
+    fn show_window(&mut self, ctx: &Context, index: usize, window: &mut DockWindow<V::Tab>) {
+        let surface = Surface::Window(index);
+        let title = window
+            .root
+            .first_active_tab_mut()
+            .map_or_else(WidgetText::default, |tab| self.viewer.title(tab));
+
+        let builder = ViewportBuilder::default()
+            .with_title(title.text())
+            .with_position(window.rect.min)
+            .with_inner_size(window.rect.size());
+
+        ctx.show_viewport_immediate(
+            ViewportId::from_hash_of(window.id),
+            builder,
+            |ctx, class| {
+                if class == ViewportClass::Embedded {
+                    let mut open = true;
+                    let response = Window::new(title.clone())
+                        .id(window.id)
+                        .open(&mut open)
+                        .collapsible(false)
+                        .default_rect(window.rect)
+                        .show(ctx, |ui| {
+                            let rect = ui.available_rect_before_wrap();
+                            self.show_node(ui, rect, &mut window.root, surface, &mut Vec::new());
+                            ui.allocate_rect(rect, Sense::hover());
+                        });
+                    if let Some(response) = response {
+                        window.rect = response.response.rect;
+                    }
+                    if !open {
+                        self.action = Some(Action::CloseWindow(index));
+                    }
+                    return;
+                }
+
+                CentralPanel::default()
+                    .frame(Frame::central_panel(&ctx.style()).inner_margin(0.0))
+                    .show(ctx, |ui| {
+                        let rect = ui.max_rect();
+                        self.show_node(ui, rect, &mut window.root, surface, &mut Vec::new());
+                        self.drop_outside(ctx, surface, rect);
+                    });
+
+                let (close_requested, outer_rect, inner_rect) = ctx.input(|i| {
+                    let viewport = i.viewport();
+                    (
+                        viewport.close_requested(),
+                        viewport.outer_rect,
+                        viewport.inner_rect,
+                    )
+                });
+                if let (Some(outer_rect), Some(inner_rect)) = (outer_rect, inner_rect) {
+                    window.rect = Rect::from_min_size(outer_rect.min, inner_rect.size());
+                }
+                if close_requested {
+                    self.action = Some(Action::CloseWindow(index));
+                }
+            },
+        );
+    }
+}
+
+/// Where a tab dropped at `pointer` would go.
+fn drop_zone(pointer: Pos2, bar_rect: Rect, body_rect: Rect, tab_rects: &[Rect]) -> DropZone {
+    if pointer.y < bar_rect.bottom() {
+        let index = tab_rects
+            .iter()
+            .position(|tab_rect| pointer.x < tab_rect.center().x)
+            .unwrap_or(tab_rects.len());
+        return DropZone::Tabs(index);
+    }
+
+    let relative = (pointer - body_rect.min) / body_rect.size().max(Vec2::splat(1.0));
+    let (distance, side) = [
+        (relative.x, DropZone::Left),
+        (1.0 - relative.x, DropZone::Right),
+        (relative.y, DropZone::Top),
+        (1.0 - relative.y, DropZone::Bottom),
+    ]
+    .into_iter()
+    .min_by(|a, b| a.0.total_cmp(&b.0))
+    .unwrap_or((1.0, DropZone::Center));
+
+    if distance < 0.25 {
+        side
+    } else {
+        DropZone::Center
+    }
+}
+
+/// A position in the current viewport, on the desktop.
+fn screen_pos(ctx: &Context, pos: Pos2) -> Pos2 {
+    let origin = ctx
+        .input(|i| i.viewport().inner_rect)
+        .map_or(Pos2::ZERO, |rect| rect.min);
+    origin + pos.to_vec2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DockNode, DockState, DropZone, SplitDirection};
+
+    #[test]
+    fn split_and_simplify() {
+        let mut node = DockNode::leaf(vec![1, 2]);
+        let tab = node.take_tab(1).unwrap();
+        node.insert(DropZone::Right, tab);
+        assert_eq!(
+            node,
+            DockNode::leaf(vec![1]).split(SplitDirection::Horizontal, 0.5, DockNode::leaf(vec![2]))
+        );
+
+        // Moving the last tab of a group away removes the group:
+        let tab = node.node_mut(&[1]).unwrap().take_tab(0).unwrap();
+        node.node_mut(&[0]).unwrap().insert(DropZone::Tabs(0), tab);
+        node.simplify();
+        assert_eq!(
+            node,
+            DockNode::Leaf {
+                tabs: vec![2, 1],
+                active: 0
+            }
+        );
+    }
+
+    #[test]
+    fn active_tab_follows_removal() {
+        let mut node = DockNode::Leaf {
+            tabs: vec![1, 2, 3],
+            active: 2,
+        };
+        node.take_tab(0);
+        assert_eq!(
+            node,
+            DockNode::Leaf {
+                tabs: vec![2, 3],
+                active: 1
+            }
+        );
+        node.take_tab(1);
+        assert_eq!(
+            node,
+            DockNode::Leaf {
+                tabs: vec![2],
+                active: 0
+            }
+        );
+    }
+
+    #[test]
+    fn retain_and_focus() {
+        let mut state = DockState::new(vec![1, 2]);
+        state.root.insert(DropZone::Bottom, 3);
+        state.retain_tabs(|tab| *tab != 2);
+        assert_eq!(state.iter_tabs().copied().collect::<Vec<_>>(), vec![1, 3]);
+        assert!(state.focus_tab(|tab| *tab == 3));
+        assert!(!state.focus_tab(|tab| *tab == 2));
+    }
+}
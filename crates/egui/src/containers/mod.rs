@@ -5,6 +5,7 @@
 pub(crate) mod area;
 pub mod collapsing_header;
 mod combo_box;
+pub mod dock;
 pub mod frame;
 pub mod modal;
 pub mod panel;
@@ -12,6 +13,7 @@ pub mod popup;
 pub(crate) mod resize;
 pub mod scroll_area;
 mod sides;
+pub mod table;
 pub(crate) mod window;
 
 pub use {
@@ -18,8 +18,8 @@
 use emath::GuiRounding as _;
 
 use crate::{
-    lerp, vec2, Align, Context, CursorIcon, Frame, Id, InnerResponse, LayerId, Layout, NumExt,
-    Rangef, Rect, Sense, Stroke, Ui, UiBuilder, UiKind, UiStackInfo, Vec2,
+    lerp, vec2, Context, CursorIcon, Frame, Id, InnerResponse, LayerId, NumExt, Rangef, Rect,
+    Sense, Stroke, Ui, UiBuilder, UiKind, UiStackInfo, Vec2,
 };
 
 fn animate_expansion(ctx: &Context, id: Id, is_expanded: bool) -> f32 {
@@ -283,7 +283,7 @@ impl SidePanel {
                     Side::Right => UiKind::RightPanel,
                 }))
                 .max_rect(panel_rect)
-                .layout(Layout::top_down(Align::Min)),
+                .layout(ui.style().default_layout()),
         );
         panel_ui.expand_to_include_rect(panel_rect);
         panel_ui.set_clip_rect(panel_rect); // If we overflow, don't do so visibly (#4475)
@@ -780,7 +780,7 @@ impl TopBottomPanel {
                     TopBottomSide::Bottom => UiKind::BottomPanel,
                 }))
                 .max_rect(panel_rect)
-                .layout(Layout::top_down(Align::Min)),
+                .layout(ui.style().default_layout()),
         );
         panel_ui.expand_to_include_rect(panel_rect);
         panel_ui.set_clip_rect(panel_rect); // If we overflow, don't do so visibly (#4475)
@@ -1116,7 +1116,7 @@ impl CentralPanel {
             UiBuilder::new()
                 .ui_stack_info(UiStackInfo::new(UiKind::CentralPanel))
                 .max_rect(panel_rect)
-                .layout(Layout::top_down(Align::Min)),
+                .layout(ui.style().default_layout()),
         );
         panel_ui.set_clip_rect(panel_rect); // If we overflow, don't do so visibly (#4475)
 
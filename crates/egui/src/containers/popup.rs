@@ -190,7 +190,7 @@ fn show_tooltip_at_dyn<'c, R>(
         .and_then(|area| area.size)
         .unwrap_or(vec2(64.0, 32.0));
 
-    let screen_rect = ctx.screen_rect();
+    let screen_rect = tooltip_bounds(ctx, widget_rect);
 
     let (pivot, anchor) = find_tooltip_position(
         screen_rect,
@@ -204,6 +204,7 @@ fn show_tooltip_at_dyn<'c, R>(
         .order(Order::Tooltip)
         .pivot(pivot)
         .fixed_pos(anchor)
+        .constrain_to(screen_rect)
         .default_width(ctx.style().spacing.tooltip_width)
         .sense(Sense::hover()) // don't click to bring to front
         .show(ctx, |ui| {
@@ -239,6 +240,36 @@ pub fn tooltip_id(widget_id: Id, tooltip_count: usize) -> Id {
     widget_id.with(tooltip_count)
 }
 
+/// Where tooltips for the given widget (in global coordinates) may go:
+/// the part of the viewport that is on the same monitor as the widget.
+///
+/// A window can stick out from its monitor, or straddle two of them,
+/// and a tooltip that is only on screen as far as the window is concerned may not be visible at all.
+fn tooltip_bounds(ctx: &Context, widget_rect: Rect) -> Rect {
+    let screen_rect = ctx.screen_rect();
+    let monitor_rect = ctx.input(|i| {
+        let viewport = i.viewport();
+        let inner_rect = viewport.inner_rect?;
+        let widget_center = inner_rect.min + widget_rect.center().to_vec2();
+        let monitor_rect = viewport
+            .monitors
+            .iter()
+            .map(|monitor| {
+                Rect::from_min_max(
+                    monitor.rect.min / i.pixels_per_point,
+                    monitor.rect.max / i.pixels_per_point,
+                )
+            })
+            .find(|monitor_rect| monitor_rect.contains(widget_center))?;
+        Some(monitor_rect.translate(-inner_rect.min.to_vec2()))
+    });
+
+    monitor_rect
+        .map(|monitor_rect| monitor_rect.intersect(screen_rect))
+        .filter(|bounds| bounds.is_positive())
+        .unwrap_or(screen_rect)
+}
+
 /// Returns `(PIVOT, POS)` to mean: put the `PIVOT` corner of the tooltip at `POS`.
 ///
 /// Note: the position might need to be constrained to the screen,
@@ -288,8 +319,25 @@ fn find_tooltip_position(
 
     // It doesn't fit anywhere :(
 
-    // Just show it anyway:
-    (Align2::LEFT_TOP, screen_rect.left_top())
+    // Put it where there is the most room, so that it covers as little of the widget as possible.
+    // The `Area` will keep it on screen.
+    let room_below = if allow_placing_below {
+        screen_rect.bottom() - widget_rect.bottom()
+    } else {
+        f32::NEG_INFINITY
+    };
+    let room_above = widget_rect.top() - screen_rect.top();
+    if room_above < room_below {
+        (
+            Align2::LEFT_TOP,
+            widget_rect.left_bottom() + spacing * Vec2::DOWN,
+        )
+    } else {
+        (
+            Align2::LEFT_BOTTOM,
+            widget_rect.left_top() + spacing * Vec2::UP,
+        )
+    }
 }
 
 /// Show some text at the current pointer position (if any).
@@ -455,3 +503,57 @@ pub fn popup_above_or_below_widget<R>(
     }
     Some(response.inner)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pos2, MonitorInfo, RawInput, ViewportId, ViewportInfo};
+
+    #[test]
+    fn tooltip_stays_on_the_monitor() {
+        let ctx = Context::default();
+        let mut input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(200.0, 100.0))),
+            ..Default::default()
+        };
+        input.viewports.insert(
+            ViewportId::ROOT,
+            ViewportInfo {
+                native_pixels_per_point: Some(1.0),
+                // The right half of the window is past the edge of the only monitor:
+                inner_rect: Some(Rect::from_min_size(pos2(300.0, 0.0), vec2(200.0, 100.0))),
+                monitors: vec![MonitorInfo {
+                    name: None,
+                    rect: Rect::from_min_size(Pos2::ZERO, vec2(400.0, 300.0)),
+                    native_pixels_per_point: 1.0,
+                }],
+                ..Default::default()
+            },
+        );
+
+        let _ = ctx.run(input, |ctx| {
+            let widget_rect = Rect::from_min_size(pos2(10.0, 10.0), vec2(20.0, 20.0));
+            assert_eq!(
+                tooltip_bounds(ctx, widget_rect),
+                Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0))
+            );
+        });
+    }
+
+    #[test]
+    fn tooltip_that_does_not_fit_avoids_the_widget() {
+        let screen_rect = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0));
+        let widget_rect = Rect::from_min_size(pos2(10.0, 20.0), vec2(80.0, 20.0));
+        let tooltip_size = vec2(100.0, 70.0);
+
+        // There is more room below the widget than above it:
+        let (pivot, pos) = find_tooltip_position(screen_rect, widget_rect, true, tooltip_size);
+        assert_eq!(pivot, Align2::LEFT_TOP);
+        assert!(widget_rect.bottom() <= pos.y);
+
+        // …but we can't go there if there is a finger below:
+        let (pivot, pos) = find_tooltip_position(screen_rect, widget_rect, false, tooltip_size);
+        assert_eq!(pivot, Align2::LEFT_BOTTOM);
+        assert!(pos.y <= widget_rect.top());
+    }
+}
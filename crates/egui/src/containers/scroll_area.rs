@@ -1,8 +1,8 @@
 #![allow(clippy::needless_range_loop)]
 
 use crate::{
-    emath, epaint, lerp, pass_state, pos2, remap, remap_clamp, vec2, Context, Id, NumExt, Pos2,
-    Rangef, Rect, Sense, Ui, UiBuilder, UiKind, UiStackInfo, Vec2, Vec2b,
+    emath, epaint, lerp, pass_state, pos2, remap, remap_clamp, vec2, Context, DragAndDrop, Id,
+    NumExt, Pos2, Rangef, Rect, Sense, Ui, UiBuilder, UiKind, UiStackInfo, Vec2, Vec2b,
 };
 
 #[derive(Clone, Copy, Debug)]
@@ -984,6 +984,42 @@ impl Prepared {
             }
         }
 
+        // Scroll while something is dragged close to the edge, so that it can be dropped further away:
+        if scrolling_enabled && is_hovering_outer_rect && DragAndDrop::has_any_payload(ui.ctx()) {
+            if let Some(pointer_pos) = ui.ctx().pointer_hover_pos() {
+                /// How fast we scroll with the pointer at the very edge, in points per second.
+                const MAX_SPEED: f32 = 1000.0;
+
+                let dt = ui.input(|i| i.stable_dt).at_most(0.1);
+                for d in 0..2 {
+                    if !content_is_too_large[d] {
+                        continue;
+                    }
+
+                    let edge_size =
+                        (0.25 * inner_rect.size()[d]).at_most(2.0 * ui.spacing().interact_size.y);
+                    let into_start_edge = inner_rect.min[d] + edge_size - pointer_pos[d];
+                    let into_end_edge = pointer_pos[d] - (inner_rect.max[d] - edge_size);
+                    let speed = if 0.0 < into_start_edge {
+                        -into_start_edge.at_most(edge_size) / edge_size
+                    } else if 0.0 < into_end_edge {
+                        into_end_edge.at_most(edge_size) / edge_size
+                    } else {
+                        continue;
+                    };
+
+                    let offset =
+                        (state.offset[d] + speed * MAX_SPEED * dt).clamp(0.0, max_offset[d]);
+                    if offset != state.offset[d] {
+                        state.offset[d] = offset;
+                        state.scroll_stuck_to_end[d] = false;
+                        state.offset_target[d] = None;
+                        ui.ctx().request_repaint();
+                    }
+                }
+            }
+        }
+
         let show_scroll_this_frame = match scroll_bar_visibility {
             ScrollBarVisibility::AlwaysHidden => Vec2b::FALSE,
             ScrollBarVisibility::VisibleWhenNeeded => content_is_too_large,
@@ -0,0 +1,723 @@
+//! A [`Table`] with a sticky header, virtualized rows, and columns the user can resize and reorder.
+
+use std::hash::Hash;
+
+use emath::{pos2, vec2, Align, NumExt as _, Rangef, Rect};
+
+use crate::{
+    Context, CursorIcon, Id, Label, Layout, Response, ScrollArea, Sense, Ui, UiBuilder, WidgetText,
+};
+
+/// The direction of a [`TableSort`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Which column a [`Table`] is sorted by.
+///
+/// The table only shows it in the header, and changes it when the user clicks a sortable column.
+/// Sorting the rows is up to you.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TableSort {
+    /// Index of the column, in the order the columns were added to the [`Table`].
+    pub column: usize,
+
+    pub order: SortOrder,
+}
+
+impl TableSort {
+    /// The sort after the user clicked the header of `column`.
+    ///
+    /// Clicking the sorted column flips the order, and clicking another column sorts by it, ascending.
+    pub fn after_click(sort: Option<Self>, column: usize) -> Self {
+        match sort {
+            Some(Self {
+                column: sorted,
+                order: SortOrder::Ascending,
+            }) if sorted == column => Self {
+                column,
+                order: SortOrder::Descending,
+            },
+            _ => Self {
+                column,
+                order: SortOrder::Ascending,
+            },
+        }
+    }
+}
+
+/// A column of a [`Table`].
+#[derive(Clone)]
+pub struct TableColumn {
+    title: WidgetText,
+    initial_width: Option<f32>,
+    width_range: Rangef,
+    resizable: bool,
+    sortable: bool,
+}
+
+impl TableColumn {
+    /// A column with this title in the header.
+    pub fn new(title: impl Into<WidgetText>) -> Self {
+        Self {
+            title: title.into(),
+            initial_width: None,
+            width_range: Rangef::new(8.0, f32::INFINITY),
+            resizable: true,
+            sortable: false,
+        }
+    }
+
+    /// The width before the user resizes the column.
+    ///
+    /// Default: [`crate::Spacing::interact_size`]`.x * 2`.
+    #[inline]
+    pub fn initial_width(mut self, initial_width: f32) -> Self {
+        self.initial_width = Some(initial_width);
+        self
+    }
+
+    /// How narrow and wide the user can make the column.
+    #[inline]
+    pub fn width_range(mut self, width_range: impl Into<Rangef>) -> Self {
+        self.width_range = width_range.into();
+        self
+    }
+
+    /// Can the user resize the column by dragging its right edge? Default: `true`.
+    #[inline]
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Can the user sort by this column by clicking its header? Default: `false`.
+    ///
+    /// See [`Table::sort`].
+    #[inline]
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    fn default_width(&self, ui: &Ui) -> f32 {
+        let width = self
+            .initial_width
+            .unwrap_or_else(|| 2.0 * ui.spacing().interact_size.x);
+        self.width_range.clamp(width)
+    }
+}
+
+/// The widths and order of the columns of a [`Table`], as changed by the user.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TableState {
+    /// The width of each column, in the order the columns were added to the [`Table`].
+    pub widths: Vec<f32>,
+
+    /// The order the columns are shown in.
+    ///
+    /// `order[i]` is the index of the column shown at position `i`.
+    pub order: Vec<usize>,
+}
+
+impl TableState {
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_persisted(id))
+    }
+
+    pub fn store(self, ctx: &Context, id: Id) {
+        ctx.data_mut(|d| d.insert_persisted(id, self));
+    }
+
+    /// Forget the changes made by the user.
+    pub fn reset(ctx: &Context, id: Id) {
+        ctx.data_mut(|d| d.remove::<Self>(id));
+    }
+
+    /// Make sure the state matches the columns, e.g. if columns were added.
+    fn validate(&mut self, ui: &Ui, columns: &[TableColumn]) {
+        if self.widths.len() != columns.len() {
+            self.widths = columns
+                .iter()
+                .map(|column| column.default_width(ui))
+                .collect();
+        }
+        for (width, column) in self.widths.iter_mut().zip(columns) {
+            *width = column.width_range.clamp(*width);
+        }
+
+        let mut sorted_order = self.order.clone();
+        sorted_order.sort_unstable();
+        if !sorted_order.iter().copied().eq(0..columns.len()) {
+            self.order = (0..columns.len()).collect();
+        }
+    }
+}
+
+/// Which cell to show, see [`Table::show`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TableCell {
+    /// The index of the row, in `0..total_rows`.
+    pub row: usize,
+
+    /// The index of the column, in the order the columns were added to the [`Table`].
+    ///
+    /// This does not change when the user reorders the columns.
+    pub column: usize,
+}
+
+/// What [`Table::show`] returns.
+pub struct TableResponse {
+    /// The whole table.
+    pub response: Response,
+
+    /// The id of the [`TableState`].
+    pub id: Id,
+
+    /// The widths and order of the columns.
+    pub state: TableState,
+
+    /// Did the user click a sortable column this frame?
+    ///
+    /// If so, you should sort your rows by the new [`Table::sort`].
+    pub sort_changed: bool,
+}
+
+/// The payload when dragging a column header to reorder the columns.
+struct ColumnDrag {
+    table_id: Id,
+    position: usize,
+}
+
+/// A table of many rows, that stays fast by only showing the visible ones.
+///
+/// All rows have the same height.
+/// The header stays visible when scrolling vertically.
+/// The user can resize columns by dragging their right edge,
+/// and reorder them by dragging their headers.
+///
+/// For more control over the layout, see `egui_extras::TableBuilder`.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let names = vec!["Alice"; 1_000_000];
+/// use egui::table::{Table, TableColumn};
+///
+/// let mut sort = None;
+/// Table::new("people")
+///     .column(TableColumn::new("#"))
+///     .column(TableColumn::new("Name").sortable(true))
+///     .sort(&mut sort)
+///     .show(ui, names.len(), |ui, cell| {
+///         match cell.column {
+///             0 => ui.label(cell.row.to_string()),
+///             _ => ui.label(names[cell.row]),
+///         };
+///     });
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+pub struct Table<'a> {
+    id_salt: Id,
+    columns: Vec<TableColumn>,
+    row_height: Option<f32>,
+    max_height: f32,
+    striped: Option<bool>,
+    reorderable: bool,
+    sort: Option<&'a mut Option<TableSort>>,
+}
+
+impl<'a> Table<'a> {
+    /// `id_salt` must be unique among the tables in the same [`Ui`].
+    pub fn new(id_salt: impl Hash) -> Self {
+        Self {
+            id_salt: Id::new(id_salt),
+            columns: Vec::new(),
+            row_height: None,
+            max_height: f32::INFINITY,
+            striped: None,
+            reorderable: true,
+            sort: None,
+        }
+    }
+
+    /// Add a column.
+    #[inline]
+    pub fn column(mut self, column: TableColumn) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Add several columns.
+    #[inline]
+    pub fn columns(mut self, columns: impl IntoIterator<Item = TableColumn>) -> Self {
+        self.columns.extend(columns);
+        self
+    }
+
+    /// The height of every row, including the header.
+    ///
+    /// Default: [`crate::Spacing::interact_size`]`.y`.
+    #[inline]
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = Some(row_height);
+        self
+    }
+
+    /// The maximum height of the table, including the header.
+    ///
+    /// Default: the available height.
+    #[inline]
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    /// Paint every other row with a slightly different background.
+    ///
+    /// Default: [`crate::Visuals::striped`].
+    #[inline]
+    pub fn striped(mut self, striped: bool) -> Self {
+        self.striped = Some(striped);
+        self
+    }
+
+    /// Can the user reorder the columns by dragging their headers? Default: `true`.
+    #[inline]
+    pub fn reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// Show which column the table is sorted by, and let the user change it.
+    ///
+    /// Only columns that are [`TableColumn::sortable`] can be clicked.
+    /// When the user clicks one, `sort` is changed with [`TableSort::after_click`]
+    /// and [`TableResponse::sort_changed`] is set.
+    #[inline]
+    pub fn sort(mut self, sort: &'a mut Option<TableSort>) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// Show the table with `total_rows` rows.
+    ///
+    /// `add_cell` is only called for the cells of the visible rows.
+    pub fn show(
+        self,
+        ui: &mut Ui,
+        total_rows: usize,
+        mut add_cell: impl FnMut(&mut Ui, TableCell),
+    ) -> TableResponse {
+        let Self {
+            id_salt,
+            columns,
+            row_height,
+            max_height,
+            striped,
+            reorderable,
+            mut sort,
+        } = self;
+
+        let id = ui.make_persistent_id(id_salt);
+        let mut state = TableState::load(ui.ctx(), id).unwrap_or_default();
+        state.validate(ui, &columns);
+
+        let row_height = row_height.unwrap_or_else(|| ui.spacing().interact_size.y);
+        let striped = striped.unwrap_or_else(|| ui.visuals().striped);
+        let spacing_x = ui.spacing().item_spacing.x;
+        let total_width = state
+            .order
+            .iter()
+            .map(|&column| state.widths[column] + spacing_x)
+            .sum::<f32>()
+            - spacing_x;
+
+        let mut sort_changed = false;
+        let mut new_widths = state.widths.clone();
+        let mut new_order = state.order.clone();
+
+        let response = ScrollArea::horizontal()
+            .id_salt(id.with("horizontal"))
+            .show(ui, |ui| {
+                ui.vertical(|ui| {
+                    let table_top = ui.cursor().top();
+
+                    // The header is outside the vertical scroll area, so that it stays visible:
+                    let (header_rect, _) =
+                        ui.allocate_exact_size(vec2(total_width, row_height), Sense::hover());
+                    let mut x = header_rect.left();
+                    for (position, &column) in state.order.iter().enumerate() {
+                        let cell_rect = Rect::from_min_size(
+                            pos2(x, header_rect.top()),
+                            vec2(state.widths[column], row_height),
+                        );
+                        x += state.widths[column] + spacing_x;
+
+                        let sorted_by = sort
+                            .as_deref()
+                            .copied()
+                            .flatten()
+                            .filter(|sort| sort.column == column)
+                            .map(|sort| sort.order);
+                        header_cell(ui, cell_rect, &columns[column], sorted_by);
+
+                        let sortable = columns[column].sortable && sort.is_some();
+                        let sense = match (sortable, reorderable) {
+                            (true, true) => Sense::click_and_drag(),
+                            (true, false) => Sense::click(),
+                            (false, true) => Sense::drag(),
+                            (false, false) => Sense::hover(),
+                        };
+                        let response = ui.interact(cell_rect, id.with(("header", column)), sense);
+
+                        if response.clicked() {
+                            if let Some(sort) = sort.as_deref_mut() {
+                                *sort = Some(TableSort::after_click(*sort, column));
+                                sort_changed = true;
+                            }
+                        }
+
+                        if reorderable {
+                            response.dnd_set_drag_payload(ColumnDrag {
+                                table_id: id,
+                                position,
+                            });
+                            if let Some(drag) = response.dnd_hover_payload::<ColumnDrag>() {
+                                if drag.table_id == id && drag.position != position {
+                                    ui.painter().vline(
+                                        if drag.position < position {
+                                            cell_rect.right() + 0.5 * spacing_x
+                                        } else {
+                                            cell_rect.left() - 0.5 * spacing_x
+                                        },
+                                        cell_rect.y_range(),
+                                        ui.visuals().selection.stroke,
+                                    );
+                                }
+                            }
+                            if let Some(drag) = response.dnd_release_payload::<ColumnDrag>() {
+                                if drag.table_id == id && drag.position != position {
+                                    let dragged = new_order.remove(drag.position);
+                                    new_order.insert(position, dragged);
+                                }
+                            }
+                        }
+                    }
+                    ui.painter().hline(
+                        header_rect.x_range(),
+                        header_rect.bottom() + 0.5 * ui.spacing().item_spacing.y,
+                        ui.visuals().widgets.noninteractive.bg_stroke,
+                    );
+
+                    let max_body_height = (max_height - row_height).at_least(0.0);
+                    let body = ScrollArea::vertical()
+                        .id_salt(id.with("vertical"))
+                        .max_height(max_body_height)
+                        .show_rows(ui, row_height, total_rows, |ui, rows| {
+                            for row in rows {
+                                let (row_rect, _) = ui.allocate_exact_size(
+                                    vec2(total_width, row_height),
+                                    Sense::hover(),
+                                );
+                                if striped && row % 2 == 1 {
+                                    ui.painter().rect_filled(
+                                        row_rect
+                                            .expand2(vec2(0.0, 0.5 * ui.spacing().item_spacing.y)),
+                                        0.0,
+                                        ui.visuals().faint_bg_color,
+                                    );
+                                }
+
+                                let mut x = row_rect.left();
+                                for &column in &state.order {
+                                    let cell_rect = Rect::from_min_size(
+                                        pos2(x, row_rect.top()),
+                                        vec2(state.widths[column], row_height),
+                                    );
+                                    x += state.widths[column] + spacing_x;
+
+                                    let mut cell_ui = ui.new_child(
+                                        UiBuilder::new()
+                                            .id_salt((row, column))
+                                            .max_rect(cell_rect)
+                                            .layout(Layout::left_to_right(Align::Center)),
+                                    );
+                                    cell_ui.set_clip_rect(cell_rect.intersect(ui.clip_rect()));
+                                    add_cell(&mut cell_ui, TableCell { row, column });
+                                }
+                            }
+                        });
+
+                    // Resize handles, spanning both the header and the visible rows:
+                    let table_y_range = Rangef::new(table_top, body.inner_rect.bottom());
+                    let mut x = header_rect.left();
+                    for &column in &state.order {
+                        x += state.widths[column];
+                        if columns[column].resizable {
+                            resize_handle(
+                                ui,
+                                id.with(("resize", column)),
+                                x + 0.5 * spacing_x,
+                                table_y_range,
+                                &columns[column],
+                                &mut new_widths[column],
+                            );
+                        }
+                        x += spacing_x;
+                    }
+                })
+                .response
+            });
+
+        if sort_changed {
+            // Give the app a chance to sort its rows.
+            ui.ctx().request_repaint();
+        }
+
+        let state = TableState {
+            widths: new_widths,
+            order: new_order,
+        };
+        state.clone().store(ui.ctx(), id);
+
+        TableResponse {
+            response: response.inner,
+            id,
+            state,
+            sort_changed,
+        }
+    }
+}
+
+fn header_cell(ui: &mut Ui, rect: Rect, column: &TableColumn, sorted_by: Option<SortOrder>) {
+    let mut cell_ui = ui.new_child(
+        UiBuilder::new()
+            .max_rect(rect)
+            .layout(Layout::left_to_right(Align::Center)),
+    );
+    cell_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+    cell_ui.add(
+        Label::new(column.title.clone().strong())
+            .truncate()
+            .selectable(false),
+    );
+    if let Some(order) = sorted_by {
+        cell_ui.label(match order {
+            SortOrder::Ascending => "⏶",
+            SortOrder::Descending => "⏷",
+        });
+    }
+}
+
+/// The handle at the right edge of a column. Double-click to go back to the initial width.
+fn resize_handle(ui: &Ui, id: Id, x: f32, y_range: Rangef, column: &TableColumn, width: &mut f32) {
+    let rect = Rect::from_x_y_ranges(Rangef::point(x), y_range)
+        .expand2(vec2(ui.style().interaction.resize_grab_radius_side, 0.0));
+    let response = ui.interact(rect, id, Sense::click_and_drag());
+
+    if response.double_clicked() {
+        *width = column.default_width(ui);
+    } else if response.dragged() {
+        *width = column.width_range.clamp(*width + response.drag_delta().x);
+    }
+
+    if response.hovered() || response.dragged() {
+        ui.ctx().set_cursor_icon(CursorIcon::ResizeColumn);
+    }
+    let stroke = if response.dragged() {
+        ui.visuals().widgets.active.bg_stroke
+    } else if response.hovered() {
+        ui.visuals().widgets.hovered.bg_stroke
+    } else {
+        ui.visuals().widgets.noninteractive.bg_stroke
+    };
+    ui.painter().vline(x, y_range, stroke);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SortOrder, Table, TableColumn, TableResponse, TableSort, TableState};
+    use crate::{pos2, vec2, CentralPanel, Context, Event, PointerButton, Pos2, RawInput, Rect};
+
+    const ROW_HEIGHT: f32 = 20.0;
+
+    /// Show a table of two columns in a 400x300 screen, and return what it returned.
+    fn show(
+        ctx: &Context,
+        events: Vec<Event>,
+        sort: &mut Option<TableSort>,
+        mut on_cell: impl FnMut(usize),
+    ) -> TableResponse {
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(400.0, 300.0))),
+            events,
+            ..Default::default()
+        };
+        let mut response = None;
+        let _ = ctx.run(input, |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                response = Some(
+                    Table::new("table")
+                        .column(TableColumn::new("A").initial_width(100.0))
+                        .column(
+                            TableColumn::new("B")
+                                .initial_width(100.0)
+                                .width_range(50.0..=150.0)
+                                .sortable(true),
+                        )
+                        .row_height(ROW_HEIGHT)
+                        .sort(sort)
+                        .show(ui, 1_000_000, |_ui, cell| on_cell(cell.row)),
+                );
+            });
+        });
+        response.unwrap()
+    }
+
+    fn press(pos: Pos2, pressed: bool) -> Event {
+        Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        }
+    }
+
+    #[test]
+    fn only_visible_rows_are_shown() {
+        let ctx = Context::default();
+        let mut max_row = 0;
+        let mut num_cells = 0;
+        show(&ctx, vec![], &mut None, |row| {
+            max_row = max_row.max(row);
+            num_cells += 1;
+        });
+
+        let visible_rows = (300.0 / ROW_HEIGHT) as usize;
+        assert!(max_row <= visible_rows, "Row {max_row} should not be shown");
+        assert!(num_cells <= 2 * (visible_rows + 1));
+    }
+
+    #[test]
+    fn state_follows_the_columns() {
+        let ctx = Context::default();
+        let first = show(&ctx, vec![], &mut None, |_| {});
+        assert_eq!(first.state.widths, vec![100.0, 100.0]);
+        assert_eq!(first.state.order, vec![0, 1]);
+
+        // E.g. stored by an older version of the app with other columns:
+        TableState {
+            widths: vec![10.0, 500.0, 30.0],
+            order: vec![2, 0, 1],
+        }
+        .store(&ctx, first.id);
+        let response = show(&ctx, vec![], &mut None, |_| {});
+        assert_eq!(response.state.widths, vec![100.0, 100.0]);
+        assert_eq!(response.state.order, vec![0, 1]);
+
+        // Widths outside of the allowed range are clamped:
+        TableState {
+            widths: vec![100.0, 500.0],
+            order: vec![1, 0],
+        }
+        .store(&ctx, first.id);
+        let response = show(&ctx, vec![], &mut None, |_| {});
+        assert_eq!(response.state.widths, vec![100.0, 150.0]);
+        assert_eq!(response.state.order, vec![1, 0]);
+    }
+
+    #[test]
+    fn clicking_a_sortable_header_sorts() {
+        let ctx = Context::default();
+        let mut sort = None;
+        let table = show(&ctx, vec![], &mut sort, |_| {}).response.rect;
+        let spacing = ctx.style().spacing.item_spacing.x;
+
+        // The header of the first column is not sortable:
+        let a = table.left_top() + vec2(50.0, 0.5 * ROW_HEIGHT);
+        show(
+            &ctx,
+            vec![Event::PointerMoved(a), press(a, true)],
+            &mut sort,
+            |_| {},
+        );
+        let response = show(&ctx, vec![press(a, false)], &mut sort, |_| {});
+        assert!(!response.sort_changed);
+        assert_eq!(sort, None);
+
+        let b = table.left_top() + vec2(150.0 + spacing, 0.5 * ROW_HEIGHT);
+        show(
+            &ctx,
+            vec![Event::PointerMoved(b), press(b, true)],
+            &mut sort,
+            |_| {},
+        );
+        let response = show(&ctx, vec![press(b, false)], &mut sort, |_| {});
+        assert!(response.sort_changed);
+        assert_eq!(
+            sort,
+            Some(TableSort {
+                column: 1,
+                order: SortOrder::Ascending
+            })
+        );
+    }
+
+    #[test]
+    fn dragging_the_edge_resizes_the_column() {
+        let ctx = Context::default();
+        let table = show(&ctx, vec![], &mut None, |_| {}).response.rect;
+        let spacing = ctx.style().spacing.item_spacing.x;
+
+        let edge = pos2(table.left() + 100.0 + 0.5 * spacing, table.top() + 50.0);
+        show(
+            &ctx,
+            vec![Event::PointerMoved(edge), press(edge, true)],
+            &mut None,
+            |_| {},
+        );
+        for dx in [10.0, 20.0, 30.0] {
+            show(
+                &ctx,
+                vec![Event::PointerMoved(edge + vec2(dx, 0.0))],
+                &mut None,
+                |_| {},
+            );
+        }
+        let response = show(
+            &ctx,
+            vec![press(edge + vec2(30.0, 0.0), false)],
+            &mut None,
+            |_| {},
+        );
+
+        assert_eq!(response.state.widths, vec![130.0, 100.0]);
+    }
+
+    #[test]
+    fn sort_after_click() {
+        let sort = TableSort::after_click(None, 2);
+        assert_eq!(sort.order, SortOrder::Ascending);
+
+        let sort = TableSort::after_click(Some(sort), 2);
+        assert_eq!(sort.order, SortOrder::Descending);
+
+        let sort = TableSort::after_click(Some(sort), 2);
+        assert_eq!(sort.order, SortOrder::Ascending);
+
+        let sort = TableSort::after_click(Some(sort), 0);
+        assert_eq!(
+            sort,
+            TableSort {
+                column: 0,
+                order: SortOrder::Ascending
+            }
+        );
+    }
+}
@@ -1225,7 +1225,12 @@ impl TitleBar {
 
         if collapsible {
             // Show collapse-button:
-            let button_center = Align2::LEFT_CENTER
+            let side = if ui.style().right_to_left {
+                Align2::RIGHT_CENTER
+            } else {
+                Align2::LEFT_CENTER
+            };
+            let button_center = side
                 .align_size_within_rect(Vec2::splat(self.inner_rect.height()), self.inner_rect)
                 .center();
             let button_size = Vec2::splat(ui.spacing().icon_width);
@@ -1292,12 +1297,18 @@ impl TitleBar {
     }
 
     /// Paints the "Close" button at the right side of the title bar
+    /// (or the left side if [`crate::Style::right_to_left`] is set)
     /// and processes clicks on it.
     ///
     /// The button is square and its size is determined by the
     /// [`crate::style::Spacing::icon_width`] setting.
     fn close_button_ui(&self, ui: &mut Ui) -> Response {
-        let button_center = Align2::RIGHT_CENTER
+        let side = if ui.style().right_to_left {
+            Align2::LEFT_CENTER
+        } else {
+            Align2::RIGHT_CENTER
+        };
+        let button_center = side
             .align_size_within_rect(Vec2::splat(self.inner_rect.height()), self.inner_rect)
             .center();
         let button_size = Vec2::splat(ui.spacing().icon_width);
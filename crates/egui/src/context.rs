@@ -31,13 +31,14 @@ use crate::{
     output::FullOutput,
     pass_state::PassState,
     resize, response, scroll_area,
-    util::IdTypeMap,
+    util::{undo_stack::UndoStack, IdTypeMap},
     viewport::ViewportClass,
     Align2, CursorIcon, DeferredViewportUiCallback, FontDefinitions, Grid, Id, ImmediateViewport,
     ImmediateViewportRendererCallback, Key, KeyboardShortcut, Label, LayerId, Memory,
     ModifierNames, NumExt, Order, Painter, RawInput, Response, RichText, ScrollArea, Sense, Style,
-    TextStyle, TextureHandle, TextureOptions, Ui, ViewportBuilder, ViewportCommand, ViewportId,
-    ViewportIdMap, ViewportIdPair, ViewportIdSet, ViewportOutput, Widget, WidgetRect, WidgetText,
+    TextStyle, TextureHandle, TextureOptions, Transition, Ui, ViewportBuilder, ViewportCommand,
+    ViewportId, ViewportIdMap, ViewportIdPair, ViewportIdSet, ViewportOutput, Widget, WidgetRect,
+    WidgetText,
 };
 
 #[cfg(feature = "accesskit")]
@@ -408,6 +409,8 @@ struct ContextImpl {
 
     plugins: Plugins,
 
+    undo_stack: UndoStack,
+
     /// All viewports share the same texture manager and texture namespace.
     ///
     /// In all viewports, [`TextureId::default`] is special, and points to the font atlas.
@@ -957,6 +960,36 @@ impl Context {
         self.write(move |ctx| writer(&mut ctx.memory.data))
     }
 
+    /// Read-only access to the [`UndoStack`], e.g. to show "Undo …" in a menu.
+    #[inline]
+    pub fn undo_stack<R>(&self, reader: impl FnOnce(&UndoStack) -> R) -> R {
+        self.read(move |ctx| reader(&ctx.undo_stack))
+    }
+
+    /// Read-write access to the [`UndoStack`], e.g. to push your own undoable actions.
+    ///
+    /// To undo or redo, use [`Self::undo`] and [`Self::redo`].
+    #[inline]
+    pub fn undo_stack_mut<R>(&self, writer: impl FnOnce(&mut UndoStack) -> R) -> R {
+        self.write(move |ctx| writer(&mut ctx.undo_stack))
+    }
+
+    /// Undo the last step on the [`UndoStack`].
+    ///
+    /// Returns `false` if there was nothing to undo.
+    /// Called when the user presses `Cmd+Z`, see [`Options::undo_with_keyboard`].
+    pub fn undo(&self) -> bool {
+        crate::util::undo_stack::undo_in(self)
+    }
+
+    /// Redo the last undone step on the [`UndoStack`].
+    ///
+    /// Returns `false` if there was nothing to redo.
+    /// Called when the user presses `Cmd+Shift+Z` or `Cmd+Y`, see [`Options::undo_with_keyboard`].
+    pub fn redo(&self) -> bool {
+        crate::util::undo_stack::redo_in(self)
+    }
+
     /// Read-write access to [`GraphicLayers`], where painted [`crate::Shape`]s are written to.
     #[inline]
     pub fn graphics_mut<R>(&self, writer: impl FnOnce(&mut GraphicLayers) -> R) -> R {
@@ -1833,6 +1866,13 @@ impl Context {
         self.memory(|mem| mem.options.system_theme)
     }
 
+    /// Has the user asked the OS for more contrast?
+    ///
+    /// See [`crate::Options::follow_system_contrast`].
+    pub fn system_high_contrast(&self) -> Option<bool> {
+        self.memory(|mem| mem.options.system_high_contrast)
+    }
+
     /// The [`Theme`] used to select the appropriate [`Style`] (dark or light)
     /// used by all subsequent windows, panels etc.
     pub fn theme(&self) -> Theme {
@@ -2126,6 +2166,10 @@ impl Context {
             crate::gui_zoom::zoom_with_keyboard(self);
         }
 
+        if self.options(|o| o.undo_with_keyboard) {
+            crate::util::undo_stack::undo_with_keyboard(self);
+        }
+
         // Plugins run just before the pass ends.
         self.read(|ctx| ctx.plugins.clone()).on_end_pass(self);
 
@@ -2895,12 +2939,107 @@ impl Context {
     /// When it is called with a new value, it linearly interpolates to it in the given time.
     #[track_caller] // To track repaint cause
     pub fn animate_value_with_time(&self, id: Id, target_value: f32, animation_time: f32) -> f32 {
+        self.animate_value_with_transition(id, target_value, &Transition::new(animation_time))
+    }
+
+    /// Like [`Self::animate_value_with_time`], but follows the given easing curve,
+    /// e.g. [`emath::easing::cubic_out`].
+    #[track_caller] // To track repaint cause
+    pub fn animate_value_with_curve(
+        &self,
+        id: Id,
+        target_value: f32,
+        animation_time: f32,
+        easing: fn(f32) -> f32,
+    ) -> f32 {
+        self.animate_value_with_transition(
+            id,
+            target_value,
+            &Transition::new(animation_time).easing(easing),
+        )
+    }
+
+    /// Smoothly animate an `f32` value, following the given [`Transition`].
+    ///
+    /// At the first call the value is written to memory, and returned as is.
+    /// When it is called with a new value, it animates towards it.
+    /// If that happens in the middle of an animation, the new one starts from wherever the old one was.
+    ///
+    /// The function will call [`Self::request_repaint()`] while the animation is in progress.
+    #[track_caller] // To track repaint cause
+    pub fn animate_value_with_transition(
+        &self,
+        id: Id,
+        target_value: f32,
+        transition: &Transition,
+    ) -> f32 {
+        self.animate_value_impl(id, target_value, transition, None)
+    }
+
+    /// Animate from 0 to 1, starting the first time this is called with the given `id`.
+    ///
+    /// This is useful to fade or slide in things when they appear, e.g. a popup or the items of a list
+    /// (see [`Transition::staggered`]).
+    /// Call [`Self::clear_animation`] to make it play again the next time.
+    #[track_caller] // To track repaint cause
+    pub fn animate_appear(&self, id: Id, transition: &Transition) -> f32 {
+        self.animate_value_impl(id, 1.0, transition, Some(0.0))
+    }
+
+    /// Smoothly animate a [`Vec2`], e.g. an offset or a size.
+    ///
+    /// See [`Self::animate_value_with_transition`].
+    #[track_caller] // To track repaint cause
+    pub fn animate_vec2_with_transition(
+        &self,
+        id: Id,
+        target_value: Vec2,
+        transition: &Transition,
+    ) -> Vec2 {
+        vec2(
+            self.animate_value_with_transition(id.with("x"), target_value.x, transition),
+            self.animate_value_with_transition(id.with("y"), target_value.y, transition),
+        )
+    }
+
+    /// Smoothly animate a [`Rect`], e.g. the position and size of a panel.
+    ///
+    /// See [`Self::animate_value_with_transition`].
+    #[track_caller] // To track repaint cause
+    pub fn animate_rect_with_transition(
+        &self,
+        id: Id,
+        target_value: Rect,
+        transition: &Transition,
+    ) -> Rect {
+        let min = self.animate_vec2_with_transition(
+            id.with("min"),
+            target_value.min.to_vec2(),
+            transition,
+        );
+        let max = self.animate_vec2_with_transition(
+            id.with("max"),
+            target_value.max.to_vec2(),
+            transition,
+        );
+        Rect::from_min_max(min.to_pos2(), max.to_pos2())
+    }
+
+    #[track_caller] // To track repaint cause
+    fn animate_value_impl(
+        &self,
+        id: Id,
+        target_value: f32,
+        transition: &Transition,
+        initial_value: Option<f32>,
+    ) -> f32 {
         let animated_value = self.write(|ctx| {
             ctx.animation_manager.animate_value(
                 &ctx.viewports.entry(ctx.viewport_id()).or_default().input,
-                animation_time,
+                transition,
                 id,
                 target_value,
+                initial_value,
             )
         });
         let animation_in_progress = animated_value != target_value;
@@ -2915,6 +3054,11 @@ impl Context {
     pub fn clear_animations(&self) {
         self.write(|ctx| ctx.animation_manager = Default::default());
     }
+
+    /// Forget the animation with the given `id`, so that it starts over the next time.
+    pub fn clear_animation(&self, id: Id) {
+        self.write(|ctx| ctx.animation_manager.clear(id));
+    }
 }
 
 impl Context {
@@ -3530,6 +3674,11 @@ impl Context {
         self.read(|ctx| ctx.parent_viewport_id())
     }
 
+    /// The ids of all viewports we know of, including the root viewport.
+    pub(crate) fn all_viewport_ids(&self) -> ViewportIdSet {
+        self.read(|ctx| ctx.all_viewport_ids())
+    }
+
     /// Read the state of the current viewport.
     pub fn viewport<R>(&self, reader: impl FnOnce(&ViewportState) -> R) -> R {
         self.write(|ctx| reader(ctx.viewport()))
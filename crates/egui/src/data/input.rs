@@ -81,6 +81,13 @@ pub struct RawInput {
     ///
     /// `None` means "don't know".
     pub system_theme: Option<Theme>,
+
+    /// Has the user asked the OS for more contrast?
+    ///
+    /// E.g. a Windows contrast theme, "Increase contrast" on Mac, or `prefers-contrast: more` on web.
+    ///
+    /// `None` means "don't know".
+    pub system_high_contrast: Option<bool>,
 }
 
 impl Default for RawInput {
@@ -98,6 +105,7 @@ impl Default for RawInput {
             dropped_files: Default::default(),
             focused: true, // integrations opt into global focus tracking
             system_theme: None,
+            system_high_contrast: None,
         }
     }
 }
@@ -131,6 +139,7 @@ impl RawInput {
             dropped_files: std::mem::take(&mut self.dropped_files),
             focused: self.focused,
             system_theme: self.system_theme,
+            system_high_contrast: self.system_high_contrast,
         }
     }
 
@@ -149,6 +158,7 @@ impl RawInput {
             mut dropped_files,
             focused,
             system_theme,
+            system_high_contrast,
         } = newer;
 
         self.viewport_id = viewport_ids;
@@ -163,6 +173,7 @@ impl RawInput {
         self.dropped_files.append(&mut dropped_files);
         self.focused = focused;
         self.system_theme = system_theme;
+        self.system_high_contrast = system_high_contrast;
     }
 }
 
@@ -172,15 +183,66 @@ impl RawInput {
 pub enum ViewportEvent {
     /// The user clicked the close-button on the window, or similar.
     ///
-    /// If this is the root viewport, the application will exit
-    /// after this frame unless you send a
-    /// [`crate::ViewportCommand::CancelClose`] command.
+    /// This is the first step of closing a viewport:
+    /// 1. [`Self::Close`]: closing was requested. You can veto it.
+    /// 2. [`Self::Closing`]: the viewport is rendered one last time.
+    /// 3. [`Self::ChildDestroyed`]: a child viewport is gone, sent to its parent.
     ///
-    /// If this is not the root viewport,
-    /// it is up to the user to hide this viewport the next frame.
+    /// Send a [`crate::ViewportCommand::CancelClose`] command this frame to veto the close.
+    /// If you don't, the next frame gets [`Self::Closing`], and is the last one.
+    ///
+    /// If this is the root viewport, the application exits after that last frame.
+    /// Note that this means the root gets one more frame after the user closed it than it used to.
+    /// If this is not the root viewport, it is up to the user to stop showing it,
+    /// either right away or during the [`Self::Closing`] frame.
+    /// eframe closes the window of a child viewport that is still shown after that anyway.
     ///
     /// This even will wake up both the child and parent viewport.
     Close,
+
+    /// This is the last frame of the viewport.
+    ///
+    /// This comes the frame after a [`Self::Close`] that was not canceled,
+    /// and can no longer be canceled.
+    /// Use it to e.g. finish up work before the window goes away.
+    ///
+    /// A child viewport should stop being shown this frame.
+    /// If it is still shown, eframe destroys its window anyway,
+    /// and won't create a new one until you have stopped showing it for a frame.
+    Closing,
+
+    /// A child viewport of this viewport was closed, and its window destroyed.
+    ///
+    /// This is sent to the parent both when you stop showing the child,
+    /// and when its window was destroyed by the system.
+    ChildDestroyed(crate::ViewportId),
+
+    /// A monitor was connected, disconnected, moved, or changed resolution or scale.
+    ///
+    /// The new list of monitors is in [`ViewportInfo::monitors`].
+    ///
+    /// Native windows that end up outside of all monitors are moved back onto one.
+    MonitorsChanged,
+
+    /// The user picked the [`crate::NativeMenuItem::Button`] with this id
+    /// in a menu sent with [`crate::ViewportCommand::SetNativeMenu`].
+    MenuItemActivated(crate::Id),
+}
+
+/// A monitor (display) connected to the computer.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct MonitorInfo {
+    /// Human-readable name of the monitor, if known.
+    pub name: Option<String>,
+
+    /// Where the monitor is on the desktop, in physical pixels.
+    ///
+    /// Divide by the `pixels_per_point` of a viewport to compare with its [`ViewportInfo::outer_rect`].
+    pub rect: Rect,
+
+    /// The OS native pixels-per-point of the monitor.
+    pub native_pixels_per_point: f32,
 }
 
 /// Information about the current viewport, given as input each frame.
@@ -200,6 +262,10 @@ pub struct ViewportInfo {
 
     pub events: Vec<ViewportEvent>,
 
+    /// The custom commands sent to this viewport with [`crate::ViewportCommand::User`]
+    /// since the last time its UI ran, in order.
+    pub user_commands: Vec<crate::UserData>,
+
     /// The OS native pixels-per-point.
     ///
     /// This should always be set, if known.
@@ -211,6 +277,14 @@ pub struct ViewportInfo {
     /// Current monitor size in egui points.
     pub monitor_size: Option<Vec2>,
 
+    /// All monitors connected to the computer, if known.
+    ///
+    /// When this changes, [`ViewportEvent::MonitorsChanged`] is sent.
+    ///
+    /// Asking the OS for this is slow, so native backends only refresh it
+    /// when the window moves, changes scale factor, or gains focus.
+    pub monitors: Vec<MonitorInfo>,
+
     /// The inner rectangle of the native window, in monitor space and ui points scale.
     ///
     /// This is the content rectangle of the viewport.
@@ -234,37 +308,58 @@ pub struct ViewportInfo {
     ///
     /// This should be the same as [`RawInput::focused`].
     pub focused: Option<bool>,
+
+    /// The factor of the native resolution the viewport is rendered at.
+    ///
+    /// See [`crate::ViewportBuilder::with_render_scale`].
+    pub render_scale: Option<f32>,
 }
 
 impl ViewportInfo {
     /// This viewport has been told to close.
     ///
-    /// If this is the root viewport, the application will exit
-    /// after this frame unless you send a
-    /// [`crate::ViewportCommand::CancelClose`] command.
-    ///
-    /// If this is not the root viewport,
-    /// it is up to the user to hide this viewport the next frame.
+    /// The viewport will close unless you send a
+    /// [`crate::ViewportCommand::CancelClose`] command this frame.
+    /// See [`ViewportEvent::Close`].
     pub fn close_requested(&self) -> bool {
         self.events
             .iter()
-            .any(|&event| event == ViewportEvent::Close)
+            .any(|event| event == &ViewportEvent::Close)
+    }
+
+    /// This is the last frame of the viewport, see [`ViewportEvent::Closing`].
+    pub fn is_closing(&self) -> bool {
+        self.events
+            .iter()
+            .any(|event| event == &ViewportEvent::Closing)
+    }
+
+    /// The child viewports that were destroyed since the last time this viewport ran,
+    /// see [`ViewportEvent::ChildDestroyed`].
+    pub fn destroyed_children(&self) -> impl Iterator<Item = crate::ViewportId> + '_ {
+        self.events.iter().filter_map(|event| match event {
+            ViewportEvent::ChildDestroyed(id) => Some(*id),
+            _ => None,
+        })
     }
 
-    /// Helper: move [`Self::events`], clone the other fields.
+    /// Helper: move [`Self::events`] and [`Self::user_commands`], clone the other fields.
     pub fn take(&mut self) -> Self {
         Self {
             parent: self.parent,
             title: self.title.clone(),
             events: std::mem::take(&mut self.events),
+            user_commands: std::mem::take(&mut self.user_commands),
             native_pixels_per_point: self.native_pixels_per_point,
             monitor_size: self.monitor_size,
+            monitors: self.monitors.clone(),
             inner_rect: self.inner_rect,
             outer_rect: self.outer_rect,
             minimized: self.minimized,
             maximized: self.maximized,
             fullscreen: self.fullscreen,
             focused: self.focused,
+            render_scale: self.render_scale,
         }
     }
 
@@ -273,14 +368,17 @@ impl ViewportInfo {
             parent,
             title,
             events,
+            user_commands,
             native_pixels_per_point,
             monitor_size,
+            monitors,
             inner_rect,
             outer_rect,
             minimized,
             maximized,
             fullscreen,
             focused,
+            render_scale,
         } = self;
 
         crate::Grid::new("viewport_info").show(ui, |ui| {
@@ -296,6 +394,10 @@ impl ViewportInfo {
             ui.label(format!("{events:?}"));
             ui.end_row();
 
+            ui.label("User commands:");
+            ui.label(user_commands.len().to_string());
+            ui.end_row();
+
             ui.label("Native pixels-per-point:");
             ui.label(opt_as_str(native_pixels_per_point));
             ui.end_row();
@@ -304,6 +406,20 @@ impl ViewportInfo {
             ui.label(opt_as_str(monitor_size));
             ui.end_row();
 
+            ui.label("Monitors:");
+            ui.vertical(|ui| {
+                for monitor in monitors {
+                    ui.label(format!(
+                        "{}: pos: {:?}, size: {:?}, ppp: {}",
+                        monitor.name.as_deref().unwrap_or("?"),
+                        monitor.rect.min,
+                        monitor.rect.size(),
+                        monitor.native_pixels_per_point
+                    ));
+                }
+            });
+            ui.end_row();
+
             ui.label("Inner rect:");
             ui.label(opt_rect_as_string(inner_rect));
             ui.end_row();
@@ -328,6 +444,10 @@ impl ViewportInfo {
             ui.label(opt_as_str(focused));
             ui.end_row();
 
+            ui.label("Render scale:");
+            ui.label(opt_as_str(render_scale));
+            ui.end_row();
+
             fn opt_rect_as_string(v: &Option<Rect>) -> String {
                 v.as_ref().map_or(String::new(), |r| {
                     format!("Pos: {:?}, size: {:?}", r.min, r.size())
@@ -387,6 +507,12 @@ pub enum Event {
     /// The integration detected a "paste" event (e.g. Cmd+V).
     Paste(String),
 
+    /// The integration detected a middle-click paste of the primary selection (X11 and Wayland).
+    ///
+    /// Sent right after the [`Self::PointerButton`] press of [`PointerButton::Middle`].
+    /// A mutable [`crate::TextEdit`] under the pointer pastes this where it was clicked.
+    PastePrimarySelection(String),
+
     /// Text input, e.g. via keyboard.
     ///
     /// When the user presses enter/return, do not send a [`Text`](Event::Text) (just [`Key::Enter`]).
@@ -400,6 +526,8 @@ pub enum Event {
         /// If it's impossible to determine the logical key on desktop platforms (say, in case of non-Latin letters),
         /// `key` falls back to the value of the corresponding physical key. This is necessary for proper work of
         /// standard shortcuts that only respond to Latin-based bindings (such as `Ctrl` + `V`).
+        ///
+        /// Some integrations can be told to prefer the physical key instead, e.g. for games.
         key: Key,
 
         /// The physical key, corresponding to the actual position on the keyboard.
@@ -412,6 +540,13 @@ pub enum Event {
         /// `eframe` does not (yet) implement this on web.
         physical_key: Option<Key>,
 
+        /// The logical key, heeding the active keymap, if it could be determined.
+        ///
+        /// This is `None` e.g. for non-Latin letters, in which case `key` is usually the physical key.
+        /// Together with `physical_key` this lets you decide per binding
+        /// whether to match on the label of the key or on its position.
+        logical_key: Option<Key>,
+
         /// Was it pressed or released?
         pressed: bool,
 
@@ -471,6 +606,20 @@ pub enum Event {
     /// As a user, check [`crate::InputState::smooth_scroll_delta`] to see if the user did any zooming this frame.
     Zoom(f32),
 
+    /// Rotation in radians, e.g. from a rotation gesture on a trackpad.
+    ///
+    /// Positive values are clockwise on screen, like [`crate::MultiTouchInfo::rotation_delta`].
+    ///
+    /// As a user, check [`crate::InputState::rotation_delta`] to see if the user did any rotating this frame.
+    Rotate(f32),
+
+    /// The user asked for a "smart zoom", e.g. by double-tapping with two fingers on a trackpad
+    /// ("smart magnify" on macOS).
+    ///
+    /// Apps usually respond by zooming in on the content under the pointer,
+    /// or back out if already zoomed in.
+    DoubleTapZoom,
+
     /// IME Event
     Ime(ImeEvent),
 
@@ -522,6 +671,14 @@ pub enum Event {
     /// The native window gained or lost focused (e.g. the user clicked alt-tab).
     WindowFocused(bool),
 
+    /// The OS switched between dark and light mode while the app was running.
+    ///
+    /// [`RawInput::system_theme`] is updated at the same time.
+    /// If the [`crate::ThemePreference`] is [`crate::ThemePreference::System`],
+    /// egui will switch between [`crate::Options::dark_style`] and [`crate::Options::light_style`]
+    /// on its own, so you only need to look for this event if you want to do something more.
+    SystemThemeChanged(Theme),
+
     /// An assistive technology (e.g. screen reader) requested an action.
     #[cfg(feature = "accesskit")]
     AccessKitActionRequest(accesskit::ActionRequest),
@@ -535,6 +692,16 @@ pub enum Event {
 
         image: std::sync::Arc<ColorImage>,
     },
+
+    /// The browser autofilled a [`crate::TextEdit`], e.g. with a saved password.
+    ///
+    /// See [`crate::TextEdit::autocomplete`].
+    Autofill {
+        /// The id of the [`crate::TextEdit`].
+        id: crate::Id,
+
+        text: String,
+    },
 }
 
 /// IME event.
@@ -1082,6 +1249,7 @@ impl RawInput {
             dropped_files,
             focused,
             system_theme,
+            system_high_contrast,
         } = self;
 
         ui.label(format!("Active viwport: {viewport_id:?}"));
@@ -1107,6 +1275,7 @@ impl RawInput {
         ui.label(format!("dropped_files: {}", dropped_files.len()));
         ui.label(format!("focused: {focused}"));
         ui.label(format!("system_theme: {system_theme:?}"));
+        ui.label(format!("system_high_contrast: {system_high_contrast:?}"));
         ui.scope(|ui| {
             ui.set_min_height(150.0);
             ui.label(format!("events: {events:#?}"))
@@ -79,6 +79,25 @@ pub struct IMEOutput {
     pub cursor_rect: crate::Rect,
 }
 
+/// A [`crate::TextEdit`] that the browser may autofill, see [`crate::TextEdit::autocomplete`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct AutofillField {
+    /// The id of the [`crate::TextEdit`], used in [`crate::Event::Autofill`].
+    pub id: crate::Id,
+
+    /// The value of the HTML `autocomplete` attribute, e.g. `"username"` or `"current-password"`.
+    pub autocomplete: String,
+
+    /// Is this a [`crate::TextEdit::password`] field?
+    pub password: bool,
+
+    /// The current text, so that password managers can offer to save it.
+    ///
+    /// The text of password fields only goes into the DOM on [`OutputCommand::SubmitAutofill`].
+    pub text: String,
+}
+
 /// Commands that the egui integration should execute at the end of a frame.
 ///
 /// Commands that are specific to a viewport should be put in [`crate::ViewportCommand`] instead.
@@ -93,8 +112,20 @@ pub enum OutputCommand {
     /// Put this image to the system clipboard.
     CopyImage(crate::ColorImage),
 
+    /// Put this text in the primary selection, for middle-click paste.
+    ///
+    /// Sent by [`crate::TextEdit`] when the user is done selecting text.
+    /// Only X11 and Wayland have a primary selection, so other integrations ignore this.
+    SetPrimarySelection(String),
+
     /// Open this url in a browser.
     OpenUrl(OpenUrl),
+
+    /// The user submitted the [`crate::TextEdit::autocomplete`] fields, e.g. by clicking "Log in".
+    ///
+    /// On web this hands their text (including passwords) to the browser,
+    /// so that password managers can offer to save it. Other integrations ignore this.
+    SubmitAutofill,
 }
 
 /// The non-rendering part of what egui emits each frame.
@@ -133,7 +164,8 @@ pub struct PlatformOutput {
     pub events: Vec<OutputEvent>,
 
     /// Is there a mutable [`TextEdit`](crate::TextEdit) under the cursor?
-    /// Use by `eframe` web to show/hide mobile keyboard and IME agent.
+    /// Use by `eframe` web to show/hide mobile keyboard and IME agent,
+    /// and by `egui-winit` for middle-click paste.
     pub mutable_text_under_cursor: bool,
 
     /// This is set if, and only if, the user is currently editing text.
@@ -141,6 +173,11 @@ pub struct PlatformOutput {
     /// Useful for IME.
     pub ime: Option<IMEOutput>,
 
+    /// The [`crate::TextEdit`]s shown this frame that the browser may autofill.
+    ///
+    /// Used by `eframe` web, see [`crate::TextEdit::autocomplete`].
+    pub autofill_fields: Vec<AutofillField>,
+
     /// The difference in the widget tree since last frame.
     ///
     /// NOTE: this needs to be per-viewport.
@@ -195,6 +232,7 @@ impl PlatformOutput {
             mut events,
             mutable_text_under_cursor,
             ime,
+            mut autofill_fields,
             #[cfg(feature = "accesskit")]
             accesskit_update,
             num_completed_passes,
@@ -212,6 +250,7 @@ impl PlatformOutput {
         self.events.append(&mut events);
         self.mutable_text_under_cursor = mutable_text_under_cursor;
         self.ime = ime.or(self.ime);
+        self.autofill_fields.append(&mut autofill_fields);
         self.num_completed_passes += num_completed_passes;
         self.request_discard_reasons
             .append(&mut request_discard_reasons);
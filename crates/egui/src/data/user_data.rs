@@ -1,7 +1,7 @@
 use std::{any::Any, sync::Arc};
 
 /// A wrapper around `dyn Any`, used for passing custom user data
-/// to [`crate::ViewportCommand::Screenshot`].
+/// to [`crate::ViewportCommand::Screenshot`] and [`crate::ViewportCommand::User`].
 #[derive(Clone, Debug, Default)]
 pub struct UserData {
     /// A user value given to the screenshot command,
@@ -1,13 +1,17 @@
 use std::{any::Any, sync::Arc};
 
-use crate::{Context, CursorIcon, Id};
+use crate::{
+    emath::TSTransform, epaint::ClippedShape, Context, CursorIcon, Id, LayerId, Order, Pos2,
+    ViewportId,
+};
 
 /// Tracking of drag-and-drop payload.
 ///
 /// This is a low-level API.
 ///
 /// For a higher-level API, see:
-/// - [`crate::Ui::dnd_drag_source`]
+/// - [`crate::Ui::drag_source`]
+/// - [`crate::Ui::drop_target`]
 /// - [`crate::Ui::dnd_drop_zone`]
 /// - [`crate::Response::dnd_set_drag_payload`]
 /// - [`crate::Response::dnd_hover_payload`]
@@ -19,6 +23,30 @@ use crate::{Context, CursorIcon, Id};
 pub struct DragAndDrop {
     /// If set, something is currently being dragged
     payload: Option<Arc<dyn Any + Send + Sync>>,
+
+    /// What [`crate::Ui::drag_source`] painted at the pointer,
+    /// so that we can paint it in other viewports too.
+    preview: Option<Preview>,
+}
+
+/// The preview painted by [`crate::Ui::drag_source`].
+#[derive(Clone)]
+struct Preview {
+    shapes: Arc<Vec<ClippedShape>>,
+
+    /// Where the pointer was, in the ui points of the viewport the preview was painted in.
+    pointer_pos: Pos2,
+
+    /// The `pixels_per_point` of the viewport the preview was painted in.
+    pixels_per_point: f32,
+
+    /// Where the pointer was, in physical pixels on the screen.
+    ///
+    /// `None` if we don't know where the viewport is on the screen.
+    screen_pos: Option<Pos2>,
+
+    /// Another viewport the pointer is over, where we paint the preview too.
+    other_viewport: Option<ViewportId>,
 }
 
 impl DragAndDrop {
@@ -64,10 +92,102 @@ impl DragAndDrop {
                         o.cursor_icon = CursorIcon::Grabbing;
                     }
                 });
+
+                Self::paint_preview_from_other_viewport(ctx);
             }
         }
     }
 
+    /// If the pointer has moved from the viewport of the drag source to this one,
+    /// paint the drag preview here.
+    fn paint_preview_from_other_viewport(ctx: &Context) {
+        let Some(preview) = ctx.data(|data| data.get_temp::<Self>(Id::NULL)?.preview) else {
+            return;
+        };
+        let Some(screen_pos) = preview.screen_pos else {
+            return;
+        };
+        if preview.other_viewport != Some(ctx.viewport_id()) {
+            return;
+        }
+
+        let (pixels_per_point, inner_rect) =
+            ctx.input(|i| (i.pixels_per_point, i.viewport().inner_rect));
+        let Some(inner_rect) = inner_rect else {
+            return;
+        };
+
+        let pointer_pos = screen_pos / pixels_per_point - inner_rect.min.to_vec2();
+        let scaling = preview.pixels_per_point / pixels_per_point;
+        let transform = TSTransform::new(
+            pointer_pos.to_vec2() - scaling * preview.pointer_pos.to_vec2(),
+            scaling,
+        );
+
+        let layer_id = LayerId::new(Order::Tooltip, Id::new("drag_and_drop_preview"));
+        ctx.graphics_mut(|graphics| {
+            let list = graphics.entry(layer_id);
+            for ClippedShape { clip_rect, shape } in preview.shapes.iter().cloned() {
+                let mut shape = shape;
+                shape.transform(transform);
+                list.add(transform.mul_rect(clip_rect), shape);
+            }
+        });
+    }
+
+    /// Set the shapes to show at the pointer while dragging.
+    ///
+    /// These are already painted in the current viewport;
+    /// this makes sure they follow the pointer into other viewports too.
+    pub(crate) fn set_preview(ctx: &Context, shapes: Vec<ClippedShape>, pointer_pos: Pos2) {
+        let viewport_id = ctx.viewport_id();
+        let (pixels_per_point, inner_rect, screen_rect) =
+            ctx.input(|i| (i.pixels_per_point, i.viewport().inner_rect, i.screen_rect()));
+
+        let screen_pos = inner_rect
+            .map(|inner_rect| (inner_rect.min + pointer_pos.to_vec2()) * pixels_per_point);
+        let other_viewport = if screen_rect.contains(pointer_pos) {
+            None
+        } else {
+            screen_pos.and_then(|screen_pos| viewport_at(ctx, viewport_id, screen_pos))
+        };
+
+        let previous_other_viewport = ctx.data_mut(|data| {
+            let state = data.get_temp_mut_or_default::<Self>(Id::NULL);
+            let previous = state
+                .preview
+                .take()
+                .and_then(|preview| preview.other_viewport);
+            state.preview = Some(Preview {
+                shapes: Arc::new(shapes),
+                pointer_pos,
+                pixels_per_point,
+                screen_pos,
+                other_viewport,
+            });
+            previous
+        });
+
+        // Show the preview where the pointer is, and remove it from where it was:
+        for other_viewport in [previous_other_viewport, other_viewport]
+            .into_iter()
+            .flatten()
+        {
+            ctx.request_repaint_of(other_viewport);
+        }
+    }
+
+    /// Stop showing the preview in other viewports.
+    fn clear_preview(ctx: &Context, state: &mut Self) {
+        if let Some(other_viewport) = state
+            .preview
+            .take()
+            .and_then(|preview| preview.other_viewport)
+        {
+            ctx.request_repaint_of(other_viewport);
+        }
+    }
+
     /// Set a drag-and-drop payload.
     ///
     /// This can be read by [`Self::payload`] until the pointer is released.
@@ -83,10 +203,9 @@ impl DragAndDrop {
 
     /// Clears the payload, setting it to `None`.
     pub fn clear_payload(ctx: &Context) {
-        ctx.data_mut(|data| {
-            let state = data.get_temp_mut_or_default::<Self>(Id::NULL);
-            state.payload = None;
-        });
+        let mut state =
+            ctx.data_mut(|data| std::mem::take(data.get_temp_mut_or_default::<Self>(Id::NULL)));
+        Self::clear_preview(ctx, &mut state);
     }
 
     /// Retrieve the payload, if any.
@@ -116,11 +235,10 @@ impl DragAndDrop {
     where
         Payload: Any + Send + Sync,
     {
-        ctx.data_mut(|data| {
-            let state = data.get_temp_mut_or_default::<Self>(Id::NULL);
-            let payload = state.payload.take()?;
-            payload.downcast().ok()
-        })
+        let mut state =
+            ctx.data_mut(|data| std::mem::take(data.get_temp_mut_or_default::<Self>(Id::NULL)));
+        Self::clear_preview(ctx, &mut state);
+        state.payload?.downcast().ok()
     }
 
     /// Are we carrying a payload of the given type?
@@ -145,3 +263,78 @@ impl DragAndDrop {
         })
     }
 }
+
+/// Another viewport than `except` that contains the given position (in physical pixels on the screen).
+fn viewport_at(ctx: &Context, except: ViewportId, screen_pos: Pos2) -> Option<ViewportId> {
+    ctx.all_viewport_ids()
+        .into_iter()
+        .filter(|&viewport_id| viewport_id != except)
+        .find(|&viewport_id| {
+            ctx.viewport_for(viewport_id, |viewport| {
+                let pixels_per_point = viewport.input.pixels_per_point;
+                viewport
+                    .input
+                    .viewport()
+                    .inner_rect
+                    .is_some_and(|inner_rect| inner_rect.contains(screen_pos / pixels_per_point))
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pos2, vec2, CentralPanel, Context, Event, Id, PointerButton, RawInput, Rect};
+
+    #[test]
+    fn drag_source_to_drop_target() {
+        let ctx = Context::default();
+
+        // Returns the hovering and dropped payloads:
+        let run = |events: Vec<Event>| {
+            let mut hovering = None;
+            let mut dropped = None;
+            let input = RawInput {
+                screen_rect: Some(Rect::from_min_size(pos2(0.0, 0.0), vec2(200.0, 200.0))),
+                events,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.drag_source(Id::new("source"), 42_u32, |ui| {
+                        ui.label("Drag me");
+                    });
+                    ui.add_space(50.0);
+                    let (_, payload) = ui.drop_target::<u32, _>(|ui, hovering_payload| {
+                        hovering = hovering_payload.map(|payload| *payload);
+                        ui.label("Drop here");
+                    });
+                    if let Some(payload) = payload {
+                        dropped = Some(*payload);
+                    }
+                });
+            });
+            (hovering, dropped)
+        };
+
+        let press = |pos, pressed| Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        };
+        let source_pos = pos2(20.0, 15.0);
+        let target_pos = pos2(20.0, 80.0);
+
+        run(vec![]);
+        run(vec![
+            Event::PointerMoved(source_pos),
+            press(source_pos, true),
+        ]);
+        for y in [30.0, 50.0, 70.0] {
+            run(vec![Event::PointerMoved(pos2(20.0, y))]);
+        }
+        assert_eq!(run(vec![Event::PointerMoved(target_pos)]), (Some(42), None));
+        assert_eq!(run(vec![press(target_pos, false)]), (Some(42), Some(42)));
+        assert!(!super::DragAndDrop::has_any_payload(&ctx));
+    }
+}
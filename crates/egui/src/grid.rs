@@ -1,8 +1,8 @@
 use emath::GuiRounding as _;
 
 use crate::{
-    vec2, Align2, Color32, Context, Id, InnerResponse, NumExt, Painter, Rect, Region, Style, Ui,
-    UiBuilder, Vec2,
+    vec2, Align2, Color32, Context, Id, InnerResponse, NavScope, NumExt, Painter, Rect, Region,
+    Style, Ui, UiBuilder, Vec2,
 };
 
 #[cfg(debug_assertions)]
@@ -438,7 +438,9 @@ impl Grid {
         // which we do here:
         let max_rect = ui.cursor().intersect(ui.max_rect());
 
-        let mut ui_builder = UiBuilder::new().max_rect(max_rect);
+        let mut ui_builder = UiBuilder::new()
+            .max_rect(max_rect)
+            .nav_scope(NavScope::new(id));
         if prev_state.is_none() {
             // The initial frame will be glitchy, because we don't know the sizes of things to come.
 
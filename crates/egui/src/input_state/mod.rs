@@ -2,7 +2,7 @@ mod touch_state;
 
 use crate::data::input::{
     Event, EventFilter, KeyboardShortcut, Modifiers, MouseWheelUnit, PointerButton, RawInput,
-    TouchDeviceId, ViewportInfo, NUM_POINTER_BUTTONS,
+    TouchDeviceId, TouchId, ViewportInfo, NUM_POINTER_BUTTONS,
 };
 use crate::{
     emath::{vec2, NumExt, Pos2, Rect, Vec2},
@@ -14,12 +14,13 @@ use std::{
 };
 
 pub use crate::Key;
-pub use touch_state::MultiTouchInfo;
 use touch_state::TouchState;
+pub use touch_state::{ClaimedTouch, MultiTouchInfo};
 
 /// Options for input state handling.
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct InputOptions {
     /// After a pointer-down event, if the pointer moves more than this, it won't become a click.
     pub max_click_dist: f32,
@@ -35,6 +36,25 @@ pub struct InputOptions {
     /// The new pointer press must come within this many seconds from previous pointer release
     /// for double click (or when this value is doubled, triple click) to count.
     pub max_double_click_delay: f64,
+
+    /// Scrolling the mouse wheel while holding these modifiers zooms instead of scrolling.
+    ///
+    /// [`Modifiers::COMMAND`] matches both ctrl and cmd on Mac.
+    /// Set to [`Modifiers::NONE`] to never zoom with the mouse wheel.
+    ///
+    /// The zoom speed is [`crate::Options::scroll_zoom_speed`].
+    pub zoom_modifier: Modifiers,
+
+    /// Scrolling the mouse wheel while holding these modifiers scrolls horizontally.
+    ///
+    /// Set to [`Modifiers::NONE`] to never turn vertical scrolling into horizontal scrolling.
+    pub horizontal_scroll_modifier: Modifiers,
+
+    /// Invert the direction of mouse wheel scrolling.
+    ///
+    /// Use this to get "natural scrolling" on platforms where the OS does not provide it, or the other way around.
+    /// The scroll speed is [`crate::Options::line_scroll_speed`].
+    pub invert_scroll: bool,
 }
 
 impl Default for InputOptions {
@@ -43,17 +63,34 @@ impl Default for InputOptions {
             max_click_dist: 6.0,
             max_click_duration: 0.8,
             max_double_click_delay: 0.3,
+            zoom_modifier: Modifiers::COMMAND,
+            horizontal_scroll_modifier: Modifiers::SHIFT,
+            invert_scroll: false,
         }
     }
 }
 
 impl InputOptions {
+    /// Should a mouse wheel event with these modifiers zoom?
+    pub fn is_scroll_zoom(&self, modifiers: Modifiers) -> bool {
+        !self.zoom_modifier.is_none() && modifiers.contains(self.zoom_modifier)
+    }
+
+    /// Should a mouse wheel event with these modifiers scroll horizontally?
+    pub fn is_horizontal_scroll(&self, modifiers: Modifiers) -> bool {
+        !self.horizontal_scroll_modifier.is_none()
+            && modifiers.contains(self.horizontal_scroll_modifier)
+    }
+
     /// Show the options in the ui.
     pub fn ui(&mut self, ui: &mut crate::Ui) {
         let Self {
             max_click_dist,
             max_click_duration,
             max_double_click_delay,
+            zoom_modifier: _,
+            horizontal_scroll_modifier: _,
+            invert_scroll,
         } = self;
         crate::containers::CollapsingHeader::new("InputOptions")
             .default_open(false)
@@ -84,6 +121,7 @@ impl InputOptions {
                     )
                     .on_hover_text("Max time interval for double click to count");
                 });
+                ui.checkbox(invert_scroll, "Invert mouse wheel scrolling");
             });
     }
 }
@@ -156,6 +194,9 @@ pub struct InputState {
     /// * `zoom > 1`: pinch spread
     zoom_factor_delta: f32,
 
+    /// Rotation this frame in radians, from [`Event::Rotate`].
+    rotation_radians: f32,
+
     // ----------------------------------------------
     /// Position and size of the egui area.
     pub screen_rect: Rect,
@@ -243,6 +284,7 @@ impl Default for InputState {
             raw_scroll_delta: Vec2::ZERO,
             smooth_scroll_delta: Vec2::ZERO,
             zoom_factor_delta: 1.0,
+            rotation_radians: 0.0,
 
             screen_rect: Rect::from_min_size(Default::default(), vec2(10_000.0, 10_000.0)),
             pixels_per_point: 1.0,
@@ -291,6 +333,7 @@ impl InputState {
 
         let mut keys_down = self.keys_down;
         let mut zoom_factor_delta = 1.0; // TODO(emilk): smoothing for zoom factor
+        let mut rotation_radians = 0.0;
         let mut raw_scroll_delta = Vec2::ZERO;
 
         let mut unprocessed_scroll_delta = self.unprocessed_scroll_delta;
@@ -324,7 +367,11 @@ impl InputState {
                         MouseWheelUnit::Page => screen_rect.height() * *delta,
                     };
 
-                    if modifiers.shift {
+                    if options.input_options.invert_scroll {
+                        delta = -delta;
+                    }
+
+                    if options.input_options.is_horizontal_scroll(*modifiers) {
                         // Treat as horizontal scrolling.
                         // Note: one Mac we already get horizontal scroll events when shift is down.
                         delta = vec2(delta.x + delta.y, 0.0);
@@ -342,7 +389,7 @@ impl InputState {
                         MouseWheelUnit::Line | MouseWheelUnit::Page => false,
                     };
 
-                    let is_zoom = modifiers.ctrl || modifiers.mac_cmd || modifiers.command;
+                    let is_zoom = options.input_options.is_scroll_zoom(*modifiers);
 
                     #[allow(clippy::collapsible_else_if)]
                     if is_zoom {
@@ -362,6 +409,9 @@ impl InputState {
                 Event::Zoom(factor) => {
                     zoom_factor_delta *= *factor;
                 }
+                Event::Rotate(radians) => {
+                    rotation_radians += *radians;
+                }
                 _ => {}
             }
         }
@@ -416,6 +466,7 @@ impl InputState {
             raw_scroll_delta,
             smooth_scroll_delta,
             zoom_factor_delta,
+            rotation_radians,
 
             screen_rect,
             pixels_per_point,
@@ -483,6 +534,16 @@ impl InputState {
         )
     }
 
+    /// Rotation in radians this frame (e.g. from a trackpad rotation gesture, or twisting two fingers).
+    ///
+    /// Positive values are clockwise on screen.
+    #[inline(always)]
+    pub fn rotation_delta(&self) -> f32 {
+        // A multi touch gesture measures the fingers directly, so prefer it.
+        self.multi_touch()
+            .map_or(self.rotation_radians, |touch| touch.rotation_delta)
+    }
+
     /// How long has it been (in seconds) since the use last scrolled?
     #[inline(always)]
     pub fn time_since_last_scroll(&self) -> f32 {
@@ -678,6 +739,34 @@ impl InputState {
         self.touch_states.values().any(|t| t.any_touches())
     }
 
+    /// All active touches that have been claimed by the widget with the given id.
+    ///
+    /// See [`crate::Response::claim_touches`].
+    pub fn claimed_touches(&self, widget: crate::Id) -> Vec<ClaimedTouch> {
+        self.touch_states
+            .values()
+            .flat_map(|t| t.claimed_touches(widget))
+            .collect()
+    }
+
+    /// Touches that started this pass and have not been claimed by any widget yet.
+    pub(crate) fn unclaimed_new_touches(&self) -> Vec<(TouchDeviceId, TouchId, Pos2)> {
+        self.touch_states
+            .iter()
+            .flat_map(|(device_id, t)| {
+                t.unclaimed_new_touches()
+                    .map(|(id, pos)| (*device_id, id, pos))
+            })
+            .collect()
+    }
+
+    /// Let `widget` own the given touch until the finger is lifted.
+    pub(crate) fn claim_touch(&mut self, device_id: TouchDeviceId, id: TouchId, widget: crate::Id) {
+        if let Some(touch_state) = self.touch_states.get_mut(&device_id) {
+            touch_state.claim(id, widget);
+        }
+    }
+
     /// True if we have ever received a touch event.
     pub fn has_touch_screen(&self) -> bool {
         !self.touch_states.is_empty()
@@ -1341,6 +1430,7 @@ impl InputState {
             smooth_scroll_delta,
 
             zoom_factor_delta,
+            rotation_radians,
             screen_rect,
             pixels_per_point,
             max_texture_side,
@@ -1392,6 +1482,7 @@ impl InputState {
             "smooth_scroll_delta: {smooth_scroll_delta:?} points"
         ));
         ui.label(format!("zoom_factor_delta: {zoom_factor_delta:4.2}x"));
+        ui.label(format!("rotation_radians: {rotation_radians:.3}"));
 
         ui.label(format!("screen_rect: {screen_rect:?} points"));
         ui.label(format!(
@@ -1465,3 +1556,98 @@ impl PointerState {
         ui.label(format!("pointer_events: {pointer_events:?}"));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_zoom_modifier() {
+        let options = InputOptions::default();
+        assert!(options.is_scroll_zoom(Modifiers::COMMAND));
+        assert!(options.is_scroll_zoom(Modifiers::COMMAND | Modifiers::SHIFT));
+        assert!(!options.is_scroll_zoom(Modifiers::NONE));
+        assert!(!options.is_scroll_zoom(Modifiers::SHIFT));
+
+        let options = InputOptions {
+            zoom_modifier: Modifiers::ALT,
+            ..Default::default()
+        };
+        assert!(options.is_scroll_zoom(Modifiers::ALT));
+        assert!(!options.is_scroll_zoom(Modifiers::COMMAND));
+
+        let options = InputOptions {
+            zoom_modifier: Modifiers::NONE,
+            ..Default::default()
+        };
+        assert!(!options.is_scroll_zoom(Modifiers::NONE));
+        assert!(!options.is_scroll_zoom(Modifiers::COMMAND));
+    }
+
+    #[test]
+    fn horizontal_scroll_modifier() {
+        let options = InputOptions::default();
+        assert!(options.is_horizontal_scroll(Modifiers::SHIFT));
+        assert!(!options.is_horizontal_scroll(Modifiers::NONE));
+        assert!(!options.is_horizontal_scroll(Modifiers::COMMAND));
+
+        let options = InputOptions {
+            horizontal_scroll_modifier: Modifiers::NONE,
+            ..Default::default()
+        };
+        assert!(!options.is_horizontal_scroll(Modifiers::NONE));
+        assert!(!options.is_horizontal_scroll(Modifiers::SHIFT));
+    }
+
+    #[test]
+    fn rotation_and_double_tap_zoom() {
+        let options = crate::Options::default();
+        let pass = |input: InputState, events| {
+            let raw = RawInput {
+                events,
+                ..Default::default()
+            };
+            input.begin_pass(raw, false, 1.0, &options)
+        };
+
+        let input = pass(
+            InputState::default(),
+            vec![
+                Event::Rotate(0.25),
+                Event::DoubleTapZoom,
+                Event::Rotate(0.5),
+            ],
+        );
+        assert_eq!(
+            input.rotation_delta(),
+            0.75,
+            "Rotations add up over the frame"
+        );
+        assert!(input.events.contains(&Event::DoubleTapZoom));
+
+        let input = pass(input, vec![]);
+        assert_eq!(
+            input.rotation_delta(),
+            0.0,
+            "Nothing carries over to the next frame"
+        );
+        assert!(input.events.is_empty());
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn input_options_from_before_scroll_modifiers() {
+        // Saved before `zoom_modifier`, `horizontal_scroll_modifier` and `invert_scroll` existed:
+        let options: InputOptions = ron::from_str(
+            "(max_click_dist: 3.0, max_click_duration: 0.8, max_double_click_delay: 0.3)",
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            InputOptions {
+                max_click_dist: 3.0,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -3,9 +3,30 @@ use std::{collections::BTreeMap, fmt::Debug};
 use crate::{
     data::input::TouchDeviceId,
     emath::{normalized_angle, Pos2, Vec2},
-    Event, RawInput, TouchId, TouchPhase,
+    Event, Id, RawInput, TouchId, TouchPhase,
 };
 
+/// A single finger (or pen) on a touch surface that has been claimed by a widget.
+///
+/// See [`crate::Response::claim_touches`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClaimedTouch {
+    /// The touch device this touch belongs to.
+    pub device_id: TouchDeviceId,
+
+    /// Unique identifier of the touch, valid for as long as the finger touches the surface.
+    pub id: TouchId,
+
+    /// Where the touch started.
+    pub start_pos: Pos2,
+
+    /// Where the touch is now.
+    pub pos: Pos2,
+
+    /// Current force of the touch, if the device reports it. A value in the interval [0.0 .. 1.0]
+    pub force: Option<f32>,
+}
+
 /// All you probably need to know about a multi-touch gesture.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct MultiTouchInfo {
@@ -118,6 +139,17 @@ struct DynGestureState {
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 struct ActiveTouch {
+    /// Position where the touch started.
+    start_pos: Pos2,
+
+    /// Did this touch start during the current pass?
+    started_this_pass: bool,
+
+    /// The widget that has claimed this touch, if any.
+    ///
+    /// Claimed touches do not take part in multi-touch gestures.
+    owner: Option<Id>,
+
     /// Current position of this touch, in device coordinates (not necessarily screen position)
     pos: Pos2,
 
@@ -139,6 +171,9 @@ impl TouchState {
 
     pub fn begin_pass(&mut self, time: f64, new: &RawInput, pointer_pos: Option<Pos2>) {
         let mut added_or_removed_touches = false;
+        for touch in self.active_touches.values_mut() {
+            touch.started_this_pass = false;
+        }
         for event in &new.events {
             match *event {
                 Event::Touch {
@@ -149,7 +184,16 @@ impl TouchState {
                     force,
                 } if device_id == self.device_id => match phase {
                     TouchPhase::Start => {
-                        self.active_touches.insert(id, ActiveTouch { pos, force });
+                        self.active_touches.insert(
+                            id,
+                            ActiveTouch {
+                                start_pos: pos,
+                                started_this_pass: true,
+                                owner: None,
+                                pos,
+                                force,
+                            },
+                        );
                         added_or_removed_touches = true;
                     }
                     TouchPhase::Move => {
@@ -185,6 +229,45 @@ impl TouchState {
         !self.active_touches.is_empty()
     }
 
+    /// Touches that started this pass and have not been claimed by any widget yet.
+    pub fn unclaimed_new_touches(&self) -> impl Iterator<Item = (TouchId, Pos2)> + '_ {
+        self.active_touches
+            .iter()
+            .filter(|(_, touch)| touch.started_this_pass && touch.owner.is_none())
+            .map(|(id, touch)| (*id, touch.start_pos))
+    }
+
+    /// Let `widget` own the given touch until the finger is lifted.
+    pub fn claim(&mut self, id: TouchId, widget: Id) {
+        if let Some(touch) = self.active_touches.get_mut(&id) {
+            touch.owner = Some(widget);
+
+            // The set of touches making up the gesture changed, so start over:
+            self.gesture_state = None;
+        }
+    }
+
+    /// All active touches owned by `widget`.
+    pub fn claimed_touches(&self, widget: Id) -> impl Iterator<Item = ClaimedTouch> + '_ {
+        self.active_touches
+            .iter()
+            .filter(move |(_, touch)| touch.owner == Some(widget))
+            .map(|(id, touch)| ClaimedTouch {
+                device_id: self.device_id,
+                id: *id,
+                start_pos: touch.start_pos,
+                pos: touch.pos,
+                force: touch.force,
+            })
+    }
+
+    /// Touches that are free to take part in multi-touch gestures.
+    fn gesture_touches(&self) -> impl Iterator<Item = &ActiveTouch> {
+        self.active_touches
+            .values()
+            .filter(|touch| touch.owner.is_none())
+    }
+
     pub fn info(&self) -> Option<MultiTouchInfo> {
         self.gesture_state.as_ref().map(|state| {
             // state.previous can be `None` when the number of simultaneous touches has just
@@ -211,7 +294,7 @@ impl TouchState {
             MultiTouchInfo {
                 start_time: state.start_time,
                 start_pos: state.start_pointer_pos,
-                num_touches: self.active_touches.len(),
+                num_touches: self.gesture_touches().count(),
                 zoom_delta,
                 zoom_delta_2d: zoom_delta2,
                 rotation_delta: normalized_angle(state.current.heading - state_previous.heading),
@@ -233,7 +316,7 @@ impl TouchState {
                 self.gesture_state = Some(GestureState {
                     start_time: time,
                     start_pointer_pos: pointer_pos,
-                    pinch_type: PinchType::classify(&self.active_touches),
+                    pinch_type: PinchType::classify(&self.gesture_touches().collect::<Vec<_>>()),
                     previous: None,
                     current: dyn_state,
                 });
@@ -246,7 +329,7 @@ impl TouchState {
 
     /// `None` if less than two fingers
     fn calc_dynamic_state(&self) -> Option<DynGestureState> {
-        let num_touches = self.active_touches.len();
+        let num_touches = self.gesture_touches().count();
         if num_touches < 2 {
             None
         } else {
@@ -260,7 +343,7 @@ impl TouchState {
             let num_touches_recip = 1. / num_touches as f32;
 
             // first pass: calculate force and center of touch positions:
-            for touch in self.active_touches.values() {
+            for touch in self.gesture_touches() {
                 state.avg_force += touch.force.unwrap_or(0.0);
                 state.avg_pos.x += touch.pos.x;
                 state.avg_pos.y += touch.pos.y;
@@ -270,7 +353,7 @@ impl TouchState {
             state.avg_pos.y *= num_touches_recip;
 
             // second pass: calculate distances from center:
-            for touch in self.active_touches.values() {
+            for touch in self.gesture_touches() {
                 state.avg_distance += state.avg_pos.distance(touch.pos);
                 state.avg_abs_distance2.x += (state.avg_pos.x - touch.pos.x).abs();
                 state.avg_abs_distance2.y += (state.avg_pos.y - touch.pos.y).abs();
@@ -288,7 +371,7 @@ impl TouchState {
             // touch individually, and then calculate the average of all individual changes in
             // direction. But this approach cannot be implemented locally in this method, making
             // everything a bit more complicated.
-            let first_touch = self.active_touches.values().next().unwrap();
+            let first_touch = self.gesture_touches().next().unwrap();
             state.heading = (state.avg_pos - first_touch.pos).angle();
 
             Some(state)
@@ -322,7 +405,7 @@ enum PinchType {
 }
 
 impl PinchType {
-    fn classify(touches: &BTreeMap<TouchId, ActiveTouch>) -> Self {
+    fn classify(touches: &[&ActiveTouch]) -> Self {
         // For non-proportional 2d zooming:
         // If the user is pinching with two fingers that have roughly the same Y coord,
         // then the Y zoom is unstable and should be 1.
@@ -331,9 +414,8 @@ impl PinchType {
         // If the fingers are roughly on a diagonal, we revert to the proportional zooming.
 
         if touches.len() == 2 {
-            let mut touches = touches.values();
-            let t0 = touches.next().unwrap().pos;
-            let t1 = touches.next().unwrap().pos;
+            let t0 = touches[0].pos;
+            let t1 = touches[1].pos;
 
             let dx = (t0.x - t1.x).abs();
             let dy = (t0.y - t1.y).abs();
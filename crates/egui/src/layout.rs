@@ -149,7 +149,7 @@ pub struct Layout {
 
 impl Default for Layout {
     fn default() -> Self {
-        // TODO(emilk): Get from `Style` instead.
+        // Top-level `Ui`:s use `Style::default_layout` instead, which supports right-to-left.
         Self::top_down(Align::LEFT) // This is a very euro-centric default.
     }
 }
@@ -431,6 +431,7 @@ pub mod response;
 mod sense;
 pub mod style;
 pub mod text_selection;
+mod transition;
 mod ui;
 mod ui_builder;
 mod ui_stack;
@@ -491,16 +492,17 @@ pub use self::{
     epaint::text::TextWrapMode,
     grid::Grid,
     id::{Id, IdMap},
-    input_state::{InputState, MultiTouchInfo, PointerState},
+    input_state::{ClaimedTouch, InputState, MultiTouchInfo, PointerState},
     layers::{LayerId, Order},
     layout::*,
     load::SizeHint,
-    memory::{Memory, Options, Theme, ThemePreference},
+    memory::{Memory, NavMemory, NavScope, Options, Theme, ThemePreference},
     painter::Painter,
     response::{InnerResponse, Response},
     sense::Sense,
     style::{FontSelection, Spacing, Style, TextStyle, Visuals},
     text::{Galley, TextFormat},
+    transition::Transition,
     ui::Ui,
     ui_builder::UiBuilder,
     ui_stack::*,
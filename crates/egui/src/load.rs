@@ -531,6 +531,17 @@ pub trait TextureLoader {
         let _ = frame_index;
     }
 
+    /// The last pass in which a texture of `uri` was loaded, if this loader has one cached.
+    ///
+    /// Passes are counted with [`Context::cumulative_pass_nr_for`] the root viewport.
+    /// Image caches use this to check that an image is no longer shown before freeing its texture.
+    ///
+    /// The default implementation returns `None`.
+    fn last_used(&self, uri: &str) -> Option<usize> {
+        let _ = uri;
+        None
+    }
+
     /// If the loader caches any data, this should return the size of that cache.
     fn byte_size(&self) -> usize;
 }
@@ -560,3 +571,26 @@ impl Default for Loaders {
         }
     }
 }
+
+impl Loaders {
+    /// Let all loaders do their work at the end of a pass, see [`BytesLoader::end_pass`].
+    ///
+    /// egui does not call this itself.
+    /// Call it at the end of each pass of the root viewport with its [`Context::cumulative_pass_nr`]
+    /// if you have loaders that need it.
+    pub fn end_pass(&self, pass_index: usize) {
+        // Clone the loaders, so that they may add loaders while we call them:
+        let bytes = self.bytes.lock().clone();
+        let image = self.image.lock().clone();
+        let texture = self.texture.lock().clone();
+        for loader in bytes {
+            loader.end_pass(pass_index);
+        }
+        for loader in image {
+            loader.end_pass(pass_index);
+        }
+        for loader in texture {
+            loader.end_pass(pass_index);
+        }
+    }
+}
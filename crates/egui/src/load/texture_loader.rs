@@ -1,13 +1,26 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use super::{
     BytesLoader, Context, HashMap, ImagePoll, Mutex, SizeHint, SizedTexture, TextureHandle,
     TextureLoadResult, TextureLoader, TextureOptions, TexturePoll,
 };
+use crate::ViewportId;
+
+struct Entry {
+    handle: TextureHandle,
+
+    /// The last pass (of the root viewport) in which the texture was loaded.
+    ///
+    /// Atomic, so we can update it through a lookup with a borrowed `uri`.
+    last_used: AtomicUsize,
+}
 
 #[derive(Default)]
 pub struct DefaultTextureLoader {
-    cache: Mutex<HashMap<(Cow<'static, str>, TextureOptions), TextureHandle>>,
+    cache: Mutex<HashMap<(Cow<'static, str>, TextureOptions), Entry>>,
 }
 
 impl TextureLoader for DefaultTextureLoader {
@@ -22,9 +35,11 @@ impl TextureLoader for DefaultTextureLoader {
         texture_options: TextureOptions,
         size_hint: SizeHint,
     ) -> TextureLoadResult {
+        let pass_index = ctx.cumulative_pass_nr_for(ViewportId::ROOT) as usize;
         let mut cache = self.cache.lock();
-        if let Some(handle) = cache.get(&(Cow::Borrowed(uri), texture_options)) {
-            let texture = SizedTexture::from_handle(handle);
+        if let Some(entry) = cache.get(&(Cow::Borrowed(uri), texture_options)) {
+            entry.last_used.store(pass_index, Ordering::Relaxed);
+            let texture = SizedTexture::from_handle(&entry.handle);
             Ok(TexturePoll::Ready { texture })
         } else {
             match ctx.try_load_image(uri, size_hint)? {
@@ -32,7 +47,13 @@ impl TextureLoader for DefaultTextureLoader {
                 ImagePoll::Ready { image } => {
                     let handle = ctx.load_texture(uri, image, texture_options);
                     let texture = SizedTexture::from_handle(&handle);
-                    cache.insert((Cow::Owned(uri.to_owned()), texture_options), handle);
+                    cache.insert(
+                        (Cow::Owned(uri.to_owned()), texture_options),
+                        Entry {
+                            handle,
+                            last_used: AtomicUsize::new(pass_index),
+                        },
+                    );
                     let reduce_texture_memory = ctx.options(|o| o.reduce_texture_memory);
                     if reduce_texture_memory {
                         let loaders = ctx.loaders();
@@ -66,11 +87,20 @@ impl TextureLoader for DefaultTextureLoader {
 
     fn end_pass(&self, _: usize) {}
 
+    fn last_used(&self, uri: &str) -> Option<usize> {
+        self.cache
+            .lock()
+            .iter()
+            .filter(|((u, _), _)| u == uri)
+            .map(|(_, entry)| entry.last_used.load(Ordering::Relaxed))
+            .max()
+    }
+
     fn byte_size(&self) -> usize {
         self.cache
             .lock()
             .values()
-            .map(|texture| texture.byte_size())
+            .map(|entry| entry.handle.byte_size())
             .sum()
     }
 }
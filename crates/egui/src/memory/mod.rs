@@ -10,7 +10,9 @@ use crate::{
     ViewportId, ViewportIdMap, ViewportIdSet,
 };
 
+mod nav;
 mod theme;
+pub use nav::{NavMemory, NavScope};
 pub use theme::{Theme, ThemePreference};
 
 // ----------------------------------------------------------------------------
@@ -205,6 +207,23 @@ pub struct Options {
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) system_theme: Option<Theme>,
 
+    /// If the OS asks for more contrast, switch both the dark and the light style
+    /// to [`crate::Visuals::high_contrast`], and back again when it stops asking.
+    ///
+    /// Switching back only restores the visuals that you haven't changed in the meantime.
+    ///
+    /// Default: `false`.
+    pub follow_system_contrast: bool,
+
+    /// Has the user asked the OS for more contrast?
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) system_high_contrast: Option<bool>,
+
+    /// The dark and light visuals from before we switched to high contrast, so we can restore them,
+    /// and the high contrast visuals we switched to, so we know what was changed since.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    visuals_before_high_contrast: Option<Box<[(crate::Visuals, crate::Visuals); 2]>>,
+
     /// Global zoom factor of the UI.
     ///
     /// This is used to calculate the `pixels_per_point`
@@ -229,6 +248,16 @@ pub struct Options {
     #[cfg_attr(feature = "serde", serde(skip))]
     pub zoom_with_keyboard: bool,
 
+    /// If `true`, egui will undo the last step on the [`crate::util::undo_stack::UndoStack`]
+    /// when the user presses Cmd+Z, and redo with Cmd+Shift+Z or Cmd+Y,
+    /// unless a [`crate::TextEdit`] has keyboard focus (it has its own undo).
+    ///
+    /// Leave this off if your app has its own undo on these shortcuts,
+    /// or the same key press would undo twice.
+    ///
+    /// This is `false` by default.
+    pub undo_with_keyboard: bool,
+
     /// Controls the tessellator.
     pub tessellation_options: epaint::TessellationOptions,
 
@@ -321,8 +350,12 @@ impl Default for Options {
             theme_preference: ThemePreference::System,
             fallback_theme: Theme::Dark,
             system_theme: None,
+            follow_system_contrast: false,
+            system_high_contrast: None,
+            visuals_before_high_contrast: None,
             zoom_factor: 1.0,
             zoom_with_keyboard: true,
+            undo_with_keyboard: false,
             tessellation_options: Default::default(),
             repaint_on_widget_change: false,
             max_passes: NonZeroUsize::new(2).unwrap(),
@@ -342,6 +375,41 @@ impl Default for Options {
 impl Options {
     pub(crate) fn begin_pass(&mut self, new_raw_input: &RawInput) {
         self.system_theme = new_raw_input.system_theme;
+        self.system_high_contrast = new_raw_input.system_high_contrast;
+        self.follow_high_contrast();
+    }
+
+    /// Switch to or from high contrast, see [`Self::follow_system_contrast`].
+    fn follow_high_contrast(&mut self) {
+        let high_contrast = self.follow_system_contrast && self.system_high_contrast == Some(true);
+        if high_contrast == self.visuals_before_high_contrast.is_some() {
+            return;
+        }
+
+        let dark_style = std::sync::Arc::make_mut(&mut self.dark_style);
+        let light_style = std::sync::Arc::make_mut(&mut self.light_style);
+        if high_contrast {
+            let dark = dark_style.visuals.clone().high_contrast();
+            let light = light_style.visuals.clone().high_contrast();
+            self.visuals_before_high_contrast = Some(Box::new([
+                (
+                    std::mem::replace(&mut dark_style.visuals, dark.clone()),
+                    dark,
+                ),
+                (
+                    std::mem::replace(&mut light_style.visuals, light.clone()),
+                    light,
+                ),
+            ]));
+        } else if let Some(visuals) = self.visuals_before_high_contrast.take() {
+            let [(dark_before, dark_applied), (light_before, light_applied)] = *visuals;
+            dark_style
+                .visuals
+                .undo_high_contrast(&dark_before, &dark_applied);
+            light_style
+                .visuals
+                .undo_high_contrast(&light_before, &light_applied);
+        }
     }
 
     /// The currently active theme (may depend on the system theme).
@@ -379,8 +447,12 @@ impl Options {
             theme_preference,
             fallback_theme: _,
             system_theme: _,
+            follow_system_contrast,
+            system_high_contrast: _,
+            visuals_before_high_contrast: _,
             zoom_factor: _, // TODO(emilk)
             zoom_with_keyboard,
+            undo_with_keyboard,
             tessellation_options,
             repaint_on_widget_change,
             max_passes,
@@ -415,6 +487,11 @@ impl Options {
                     "Zoom with keyboard (Cmd +, Cmd -, Cmd 0)",
                 );
 
+                ui.checkbox(
+                    undo_with_keyboard,
+                    "Undo with keyboard (Cmd Z, Cmd Shift Z)",
+                );
+
                 ui.checkbox(warn_on_id_clash, "Warn if two widgets have the same Id");
 
                 ui.checkbox(reduce_texture_memory, "Reduce texture memory");
@@ -424,6 +501,10 @@ impl Options {
             .default_open(true)
             .show(ui, |ui| {
                 theme_preference.radio_buttons(ui);
+                ui.checkbox(
+                    follow_system_contrast,
+                    "Follow system high contrast setting",
+                );
 
                 std::sync::Arc::make_mut(match theme {
                     Theme::Dark => dark_style,
@@ -526,6 +607,9 @@ pub(crate) struct Focus {
 
     /// A cache of widget IDs that are interested in focus with their corresponding rectangles.
     focus_widgets_cache: IdMap<Rect>,
+
+    /// Moving focus with the arrow keys.
+    nav: NavMemory,
 }
 
 /// The widget with focus.
@@ -570,6 +654,7 @@ impl Focus {
         }
 
         self.focus_direction = FocusDirection::None;
+        self.nav.begin_pass();
 
         for event in &new_input.events {
             if !event_filter.matches(event) {
@@ -635,6 +720,8 @@ impl Focus {
             }
         }
 
+        self.nav.end_pass(self.focused());
+
         self.top_modal_layer = self.top_modal_layer_current_frame.take();
     }
 
@@ -739,41 +826,84 @@ impl Focus {
             }
         });
 
-        let current_rect = self.focus_widgets_cache.get(&current_focused.id)?;
+        let current_rect = *self.focus_widgets_cache.get(&current_focused.id)?;
 
-        let mut best_score = f32::INFINITY;
-        let mut best_id = None;
+        // Line up with where a series of moves started, rather than with the focused widget:
+        let vertical = search_direction.x == 0.0;
+        let anchor = self.nav.anchor(current_focused.id);
+        let (current_x_range, current_y_range) = match anchor {
+            Some(anchor) if vertical => (Rangef::point(anchor.x), current_rect.y_range()),
+            Some(anchor) => (current_rect.x_range(), Rangef::point(anchor.y)),
+            None => (current_rect.x_range(), current_rect.y_range()),
+        };
 
-        for (candidate_id, candidate_rect) in &self.focus_widgets_cache {
-            if *candidate_id == current_focused.id {
-                continue;
-            }
+        let best_candidate = |is_candidate: &dyn Fn(Id) -> bool| {
+            let mut best_score = f32::INFINITY;
+            let mut best_id = None;
 
-            // There is a lot of room for improvement here.
-            let to_candidate = vec2(
-                range_diff(candidate_rect.x_range(), current_rect.x_range()),
-                range_diff(candidate_rect.y_range(), current_rect.y_range()),
-            );
+            for (candidate_id, candidate_rect) in &self.focus_widgets_cache {
+                if *candidate_id == current_focused.id || !is_candidate(*candidate_id) {
+                    continue;
+                }
 
-            let acos_angle = to_candidate.normalized().dot(search_direction);
+                // There is a lot of room for improvement here.
+                let to_candidate = vec2(
+                    range_diff(candidate_rect.x_range(), current_x_range),
+                    range_diff(candidate_rect.y_range(), current_y_range),
+                );
 
-            // Only interested in widgets that fall in a 90° cone (±45°)
-            // of the search direction.
-            let is_in_search_cone = 0.5_f32.sqrt() <= acos_angle;
-            if is_in_search_cone {
-                let distance = to_candidate.length();
+                let acos_angle = to_candidate.normalized().dot(search_direction);
 
-                // There is a lot of room for improvement here.
-                let score = distance / (acos_angle * acos_angle);
+                // Only interested in widgets that fall in a 90° cone (±45°)
+                // of the search direction.
+                let is_in_search_cone = 0.5_f32.sqrt() <= acos_angle;
+                if is_in_search_cone {
+                    let distance = to_candidate.length();
+
+                    // There is a lot of room for improvement here.
+                    let score = distance / (acos_angle * acos_angle);
+
+                    if score < best_score {
+                        best_score = score;
+                        best_id = Some(*candidate_id);
+                    }
+                }
+            }
+
+            best_id
+        };
+
+        // Prefer staying within the scope of the focused widget:
+        let current_scope = self.nav.scope_of(current_focused.id);
+        let mut best_id = match current_scope {
+            Some(scope) => {
+                best_candidate(&|id| self.nav.scope_of(id) == Some(scope)).or_else(|| {
+                    if scope.contained {
+                        None
+                    } else {
+                        best_candidate(&|_| true)
+                    }
+                })
+            }
+            None => best_candidate(&|_| true),
+        };
 
-                if score < best_score {
-                    best_score = score;
-                    best_id = Some(*candidate_id);
+        // When entering another scope, go back to where we last were in it:
+        if let Some(new_scope) = best_id.and_then(|id| self.nav.scope_of(id)) {
+            if current_scope != Some(new_scope) {
+                if let Some(last_focused) = self.nav.last_focused(new_scope.id) {
+                    if self.focus_widgets_cache.contains_key(&last_focused) {
+                        best_id = Some(last_focused);
+                    }
                 }
             }
         }
 
-        best_id
+        let best_id = best_id?;
+        let best_rect = *self.focus_widgets_cache.get(&best_id)?;
+        let anchor = anchor.unwrap_or_else(|| current_rect.center());
+        self.nav.moved(anchor, vertical, best_id, best_rect);
+        Some(best_id)
     }
 }
 
@@ -879,6 +1009,16 @@ impl Memory {
         self.focus().and_then(|f| f.focused())
     }
 
+    /// How the user has been moving focus with the arrow keys.
+    pub fn nav_memory(&self) -> Option<&NavMemory> {
+        self.focus().map(|f| &f.nav)
+    }
+
+    /// Put a widget that is interested in focus in a [`NavScope`].
+    pub(crate) fn set_nav_scope(&mut self, id: Id, scope: NavScope) {
+        self.focus_mut().nav.register(id, scope);
+    }
+
     /// Set an event filter for a widget.
     ///
     /// This allows you to control whether the widget will loose focus
@@ -1354,6 +1494,72 @@ fn memory_impl_send_sync() {
     assert_send_sync::<Memory>();
 }
 
+#[test]
+fn options_follow_system_high_contrast() {
+    let mut options = Options {
+        follow_system_contrast: true,
+        ..Default::default()
+    };
+    std::sync::Arc::make_mut(&mut options.dark_style)
+        .visuals
+        .hyperlink_color = crate::Color32::RED;
+    let custom = options.clone();
+
+    let mut raw_input = RawInput {
+        system_high_contrast: Some(true),
+        ..Default::default()
+    };
+    options.begin_pass(&raw_input);
+    assert_eq!(
+        options.dark_style.visuals,
+        custom.dark_style.visuals.clone().high_contrast()
+    );
+
+    raw_input.system_high_contrast = Some(false);
+    options.begin_pass(&raw_input);
+    assert_eq!(options.dark_style.visuals, custom.dark_style.visuals);
+    assert_eq!(options.light_style.visuals, custom.light_style.visuals);
+}
+
+#[test]
+fn options_keep_changes_made_during_high_contrast() {
+    let mut options = Options {
+        follow_system_contrast: true,
+        ..Default::default()
+    };
+    let before = options.dark_style.visuals.clone();
+
+    let mut raw_input = RawInput {
+        system_high_contrast: Some(true),
+        ..Default::default()
+    };
+    options.begin_pass(&raw_input);
+
+    // The app changes a setting that high contrast changed, and one it didn't:
+    let visuals = &mut std::sync::Arc::make_mut(&mut options.dark_style).visuals;
+    visuals.panel_fill = crate::Color32::RED;
+    visuals.striped = !visuals.striped;
+
+    raw_input.system_high_contrast = Some(false);
+    options.begin_pass(&raw_input);
+    let visuals = &options.dark_style.visuals;
+    assert_eq!(visuals.panel_fill, crate::Color32::RED);
+    assert_eq!(visuals.striped, !before.striped);
+    assert_eq!(visuals.window_fill, before.window_fill);
+    assert_eq!(visuals.widgets, before.widgets);
+}
+
+#[test]
+fn options_ignore_system_high_contrast_by_default() {
+    let mut options = Options::default();
+    let before = options.dark_style.visuals.clone();
+    options.begin_pass(&RawInput {
+        system_high_contrast: Some(true),
+        ..Default::default()
+    });
+    assert_eq!(options.dark_style.visuals, before);
+}
+
 #[test]
 fn order_map_total_ordering() {
     let mut layers = [
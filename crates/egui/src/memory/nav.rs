@@ -0,0 +1,232 @@
+use emath::{pos2, Pos2, Rect};
+
+use crate::{Id, IdMap};
+
+/// A group of widgets that the arrow keys move focus between, like the cells of a [`crate::Grid`].
+///
+/// Set it with [`crate::UiBuilder::nav_scope`] or [`crate::Ui::set_nav_scope`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NavScope {
+    /// Identifies the scope, e.g. the id of the [`crate::Grid`].
+    pub id: Id,
+
+    /// If `true`, the arrow keys never move focus out of the scope,
+    /// e.g. out of a popup. Default: `false`.
+    pub contained: bool,
+}
+
+impl NavScope {
+    /// A scope that the arrow keys can move focus out of.
+    #[inline]
+    pub fn new(id: Id) -> Self {
+        Self {
+            id,
+            contained: false,
+        }
+    }
+
+    /// Never let the arrow keys move focus out of the scope.
+    #[inline]
+    pub fn contained(mut self, contained: bool) -> Self {
+        self.contained = contained;
+        self
+    }
+}
+
+/// Remembers how the user moves focus around with the arrow keys.
+///
+/// The arrow keys first look for a widget in the same [`NavScope`] as the focused one.
+/// When they move focus into another scope, the widget that last had focus there gets it back.
+///
+/// Repeated moves stay lined up with where they started,
+/// so that moving down through rows of different widths keeps to the same column.
+///
+/// You can read it with [`crate::Memory::nav_memory`].
+#[derive(Clone, Debug, Default)]
+pub struct NavMemory {
+    /// The scope of each widget in a scope that was interested in focus this pass.
+    scopes: IdMap<NavScope>,
+
+    /// The widget that last had focus in each scope.
+    last_focused: IdMap<Id>,
+
+    /// The widget that focus was last moved to with the arrow keys,
+    /// and the point that the next move should line up with.
+    anchor: Option<(Id, Pos2)>,
+}
+
+impl NavMemory {
+    /// The scope of a widget, if it is in one.
+    pub fn scope_of(&self, id: Id) -> Option<NavScope> {
+        self.scopes.get(&id).copied()
+    }
+
+    /// The widget that last had focus in the scope with this id.
+    pub fn last_focused(&self, scope_id: Id) -> Option<Id> {
+        self.last_focused.get(&scope_id).copied()
+    }
+
+    pub(crate) fn begin_pass(&mut self) {
+        self.scopes.clear();
+    }
+
+    pub(crate) fn register(&mut self, id: Id, scope: NavScope) {
+        self.scopes.insert(id, scope);
+    }
+
+    pub(crate) fn end_pass(&mut self, focused: Option<Id>) {
+        if let Some(id) = focused {
+            if let Some(scope) = self.scopes.get(&id) {
+                self.last_focused.insert(scope.id, id);
+            }
+        }
+
+        // Forget widgets that are no longer shown in their scope:
+        let scopes = &self.scopes;
+        self.last_focused
+            .retain(|scope_id, id| scopes.get(id).map(|scope| scope.id) == Some(*scope_id));
+    }
+
+    /// The point that a move away from the focused widget should line up with,
+    /// if focus was moved to it with the arrow keys.
+    pub(crate) fn anchor(&self, focused: Id) -> Option<Pos2> {
+        self.anchor
+            .filter(|(id, _)| *id == focused)
+            .map(|(_, anchor)| anchor)
+    }
+
+    /// Focus was moved from `anchor` to `to`, up or down if `vertical`, else left or right.
+    pub(crate) fn moved(&mut self, anchor: Pos2, vertical: bool, to: Id, to_rect: Rect) {
+        let center = to_rect.center();
+        let anchor = if vertical {
+            pos2(anchor.x, center.y)
+        } else {
+            pos2(center.x, anchor.y)
+        };
+        self.anchor = Some((to, anchor));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, Event, Key, Modifiers, RawInput, UiBuilder};
+
+    fn press(key: Key) -> Event {
+        Event::Key {
+            key,
+            physical_key: None,
+            logical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    /// Shows two columns of three buttons, each column its own scope,
+    /// and returns the ids of the buttons by name, e.g. `"left 2"`.
+    fn run(
+        ctx: &Context,
+        events: Vec<Event>,
+        show_right: bool,
+        focus: Option<&str>,
+    ) -> IdMap<String> {
+        let mut names = IdMap::default();
+        let input = RawInput {
+            events,
+            ..Default::default()
+        };
+        let _ = ctx.run(input, |ctx| {
+            crate::CentralPanel::default().show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for column in ["left", "right"] {
+                        if column == "right" && !show_right {
+                            continue;
+                        }
+                        let scope = NavScope::new(Id::new(column));
+                        ui.scope_builder(UiBuilder::new().nav_scope(scope), |ui| {
+                            ui.vertical(|ui| {
+                                for row in 0..3 {
+                                    let name = format!("{column} {row}");
+                                    let response = ui.button(&name);
+                                    if focus == Some(name.as_str()) {
+                                        response.request_focus();
+                                    }
+                                    names.insert(response.id, name);
+                                }
+                            });
+                        });
+                    }
+                });
+            });
+        });
+        names
+    }
+
+    fn focused(ctx: &Context, names: &IdMap<String>) -> Option<String> {
+        ctx.memory(|mem| mem.focused())
+            .and_then(|id| names.get(&id).cloned())
+    }
+
+    #[test]
+    fn entering_a_scope_returns_to_where_focus_was() {
+        let ctx = Context::default();
+        run(&ctx, vec![], true, None);
+        run(&ctx, vec![], true, Some("left 2"));
+        let names = run(&ctx, vec![], true, None);
+        assert_eq!(focused(&ctx, &names).as_deref(), Some("left 2"));
+
+        let step = |key, expected| {
+            let names = run(&ctx, vec![press(key)], true, None);
+            assert_eq!(focused(&ctx, &names).as_deref(), Some(expected));
+        };
+        step(Key::ArrowRight, "right 2");
+        step(Key::ArrowUp, "right 1");
+        // Back to the left column, where "left 2" had focus:
+        step(Key::ArrowLeft, "left 2");
+        // …and into the right column again, where "right 1" had focus:
+        step(Key::ArrowRight, "right 1");
+    }
+
+    #[test]
+    fn scopes_that_are_gone_are_forgotten() {
+        let ctx = Context::default();
+        let last_focused =
+            |scope: &str| ctx.memory(|mem| mem.nav_memory().unwrap().last_focused(Id::new(scope)));
+
+        run(&ctx, vec![], true, None);
+        run(&ctx, vec![], true, Some("right 0"));
+        run(&ctx, vec![], true, Some("left 0"));
+        assert!(last_focused("right").is_some());
+        assert!(last_focused("left").is_some());
+
+        run(&ctx, vec![], false, None);
+        assert_eq!(last_focused("right"), None);
+        assert!(last_focused("left").is_some());
+    }
+
+    #[test]
+    fn last_focused_is_dropped_when_the_widget_leaves_the_scope() {
+        let scope = NavScope::new(Id::new("scope"));
+        let (a, b) = (Id::new("a"), Id::new("b"));
+        let mut nav = NavMemory::default();
+
+        nav.begin_pass();
+        nav.register(a, scope);
+        nav.register(b, scope);
+        nav.end_pass(Some(a));
+        assert_eq!(nav.last_focused(scope.id), Some(a));
+
+        // Focus moved out of the scope, but `a` is still shown in it:
+        nav.begin_pass();
+        nav.register(a, scope);
+        nav.end_pass(None);
+        assert_eq!(nav.last_focused(scope.id), Some(a));
+
+        // `a` is no longer shown in the scope:
+        nav.begin_pass();
+        nav.register(b, scope);
+        nav.end_pass(None);
+        assert_eq!(nav.last_focused(scope.id), None);
+    }
+}
@@ -22,8 +22,8 @@ use super::{
 use crate::{
     epaint, vec2,
     widgets::{Button, ImageButton},
-    Align2, Area, Color32, Frame, Key, LayerId, Layout, NumExt, Order, Stroke, Style, TextWrapMode,
-    UiKind, WidgetText,
+    Align2, Area, Color32, Frame, Key, LayerId, Layout, NavScope, NumExt, Order, Stroke, Style,
+    TextWrapMode, UiKind, WidgetText,
 };
 use epaint::mutex::RwLock;
 use std::sync::Arc;
@@ -196,6 +196,7 @@ fn menu_popup<'c, R>(
         Frame::menu(ui.style())
             .show(ui, |ui| {
                 ui.set_menu_state(Some(menu_state_arc.clone()));
+                ui.set_nav_scope(Some(NavScope::new(area_id).contained(true)));
                 ui.with_layout(Layout::top_down_justified(Align::LEFT), add_contents)
                     .inner
             })
@@ -2,8 +2,8 @@ use std::{any::Any, sync::Arc};
 
 use crate::{
     emath::{Align, Pos2, Rect, Vec2},
-    menu, pass_state, AreaState, Context, CursorIcon, Id, LayerId, Order, PointerButton, Sense, Ui,
-    WidgetRect, WidgetText,
+    menu, pass_state, AreaState, ClaimedTouch, Context, CursorIcon, Id, LayerId, Order,
+    PointerButton, Sense, Ui, WidgetRect, WidgetText,
 };
 // ----------------------------------------------------------------------------
 
@@ -471,6 +471,44 @@ impl Response {
         self.interact_pointer_pos
     }
 
+    /// Opt-in multi-touch interaction.
+    ///
+    /// Normally only the first finger on a touch screen acts as the pointer,
+    /// so e.g. two sliders cannot be dragged at the same time.
+    /// Widgets that support being used by several fingers at once can call this each frame:
+    /// every touch that starts on this widget is claimed by it until the finger is lifted,
+    /// and all touches currently owned by this widget are returned, each one an
+    /// independent interaction stream.
+    ///
+    /// Claimed touches no longer take part in multi-touch gestures (see [`crate::InputState::multi_touch`]).
+    /// The first finger still also moves the pointer as usual.
+    ///
+    /// Returns nothing if the widget is disabled or does not sense clicks or drags.
+    pub fn claim_touches(&self) -> Vec<ClaimedTouch> {
+        if !self.enabled() || !(self.sense.senses_click() || self.sense.senses_drag()) {
+            return vec![];
+        }
+
+        let from_global = self.ctx.layer_transform_from_global(self.layer_id);
+        let to_local = |pos: Pos2| from_global.map_or(pos, |from_global| from_global * pos);
+
+        for (device_id, touch_id, start_pos) in self.ctx.input(|i| i.unclaimed_new_touches()) {
+            if self.interact_rect.contains(to_local(start_pos))
+                && self.ctx.layer_id_at(start_pos) == Some(self.layer_id)
+            {
+                self.ctx
+                    .input_mut(|i| i.claim_touch(device_id, touch_id, self.id));
+            }
+        }
+
+        let mut touches = self.ctx.input(|i| i.claimed_touches(self.id));
+        for touch in &mut touches {
+            touch.start_pos = to_local(touch.start_pos);
+            touch.pos = to_local(touch.pos);
+        }
+        touches
+    }
+
     /// If it is a good idea to show a tooltip, where is pointer?
     ///
     /// None if the pointer is outside the response area.
@@ -556,6 +594,19 @@ impl Response {
         self
     }
 
+    /// Like [`Self::on_hover_ui`], but wait this many seconds before showing the tooltip,
+    /// instead of [`crate::style::Interaction::tooltip_delay`].
+    ///
+    /// Use `0.0` for tooltips that should show right away,
+    /// or a longer delay for widgets that are often hovered on the way to something else.
+    #[doc(alias = "tooltip")]
+    pub fn on_hover_ui_with_delay(self, delay: f32, add_contents: impl FnOnce(&mut Ui)) -> Self {
+        if self.flags.contains(Flags::ENABLED) && self.should_show_hover_ui_after(delay) {
+            self.show_tooltip_ui(add_contents);
+        }
+        self
+    }
+
     /// Show this UI when hovering if the widget is disabled.
     pub fn on_disabled_hover_ui(self, add_contents: impl FnOnce(&mut Ui)) -> Self {
         if !self.enabled() && self.should_show_hover_ui() {
@@ -611,6 +662,11 @@ impl Response {
     }
 
     fn should_show_hover_ui(&self) -> bool {
+        self.should_show_hover_ui_after(self.ctx.style().interaction.tooltip_delay)
+    }
+
+    /// Should we show the tooltip, after the pointer has rested for `tooltip_delay` seconds?
+    fn should_show_hover_ui_after(&self, tooltip_delay: f32) -> bool {
         if self.ctx.memory(|mem| mem.everything_is_visible()) {
             return true;
         }
@@ -627,7 +683,6 @@ impl Response {
 
         let style = self.ctx.style();
 
-        let tooltip_delay = style.interaction.tooltip_delay;
         let tooltip_grace_time = style.interaction.tooltip_grace_time;
 
         let (
@@ -812,6 +867,26 @@ impl Response {
         })
     }
 
+    /// Like [`Self::on_hover_text`], but wait this many seconds before showing the tooltip,
+    /// instead of [`crate::style::Interaction::tooltip_delay`].
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// ui.button("Save")
+    ///     .on_hover_text_with_delay(0.0, "Save the document (Ctrl+S)");
+    /// # });
+    /// ```
+    #[doc(alias = "tooltip")]
+    pub fn on_hover_text_with_delay(self, delay: f32, text: impl Into<WidgetText>) -> Self {
+        self.on_hover_ui_with_delay(delay, |ui| {
+            // Prevent `Area` auto-sizing from shrinking tooltips with dynamic content.
+            // See https://github.com/emilk/egui/issues/5167
+            ui.set_max_width(ui.spacing().tooltip_width);
+
+            ui.add(crate::widgets::Label::new(text));
+        })
+    }
+
     /// Highlight this widget, to make it look like it is hovered, even if it isn't.
     ///
     /// The highlight takes one frame to take effect if you call this after the widget has been fully rendered.
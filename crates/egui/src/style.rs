@@ -290,6 +290,14 @@ pub struct Style {
 
     /// The animation that should be used when scrolling a [`crate::ScrollArea`] using e.g. [`Ui::scroll_to_rect`].
     pub scroll_animation: ScrollAnimation,
+
+    /// Lay out panels, windows and other top-level [`Ui`]:s from right to left,
+    /// for languages like Arabic and Hebrew.
+    ///
+    /// This makes [`Self::default_layout`] right-aligned, so that [`Ui::horizontal`] and friends
+    /// also go from right to left. The close and collapse buttons of a [`crate::Window`] swap sides.
+    /// Layouts that are chosen explicitly are not mirrored, and text is not reordered.
+    pub right_to_left: bool,
 }
 
 #[test]
@@ -327,6 +335,67 @@ impl Style {
     pub fn text_styles(&self) -> Vec<TextStyle> {
         self.text_styles.keys().cloned().collect()
     }
+
+    /// The layout of top-level [`Ui`]:s, e.g. in panels and windows.
+    ///
+    /// Top-down, aligned to the left, or to the right if [`Self::right_to_left`] is set.
+    pub fn default_layout(&self) -> crate::Layout {
+        if self.right_to_left {
+            crate::Layout::top_down(Align::RIGHT)
+        } else {
+            crate::Layout::top_down(Align::LEFT)
+        }
+    }
+
+    /// Parse a style from [RON](https://github.com/ron-rs/ron), e.g. written by [`Self::to_ron`].
+    ///
+    /// Fields that are left out get their default value, so a theme file only needs to
+    /// contain what it changes.
+    ///
+    /// See also `egui_extras::ThemeLoader`.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if the RON is malformed.
+    #[cfg(feature = "ron")]
+    pub fn from_ron(ron: &str) -> Result<Self, String> {
+        ron::from_str(ron).map_err(|err| err.to_string())
+    }
+
+    /// Write the style as [RON](https://github.com/ron-rs/ron), to be read by [`Self::from_ron`].
+    ///
+    /// # Errors
+    /// Returns a description of the problem if serialization fails.
+    #[cfg(feature = "ron")]
+    pub fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+    }
+
+    /// Parse a style from [TOML](https://toml.io), e.g. written by [`Self::to_toml`].
+    ///
+    /// Fields that are left out get their default value, so a theme file only needs to
+    /// contain what it changes.
+    ///
+    /// TOML only supports string keys, so custom [`TextStyle::Name`] entries in
+    /// [`Self::text_styles`] are only supported by [`Self::from_ron`].
+    ///
+    /// See also `egui_extras::ThemeLoader`.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if the TOML is malformed.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, String> {
+        toml::from_str(toml).map_err(|err| err.to_string())
+    }
+
+    /// Write the style as [TOML](https://toml.io), to be read by [`Self::from_toml`].
+    ///
+    /// # Errors
+    /// Returns a description of the problem if serialization fails.
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|err| err.to_string())
+    }
 }
 
 /// Controls the sizes and distances between widgets.
@@ -1020,6 +1089,90 @@ impl Visuals {
     pub fn gray_out(&self, color: Color32) -> Color32 {
         crate::ecolor::tint_color_towards(color, self.fade_out_to_color())
     }
+
+    /// Blend between two visuals, e.g. to fade from a light to a dark theme.
+    ///
+    /// `t = 0` gives `self` and `t = 1` gives `other`.
+    /// Colors and stroke widths are interpolated,
+    /// everything else (roundings, flags, …) switches over at `t = 0.5`.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let color = |a: Color32, b: Color32| a.lerp_to_gamma(b, t);
+        let stroke = |a: Stroke, b: Stroke| {
+            Stroke::new(emath::lerp(a.width..=b.width, t), color(a.color, b.color))
+        };
+        let shadow = |a: Shadow, b: Shadow| Shadow {
+            color: color(a.color, b.color),
+            ..if t < 0.5 { a } else { b }
+        };
+        let widget = |a: &WidgetVisuals, b: &WidgetVisuals| WidgetVisuals {
+            bg_fill: color(a.bg_fill, b.bg_fill),
+            weak_bg_fill: color(a.weak_bg_fill, b.weak_bg_fill),
+            bg_stroke: stroke(a.bg_stroke, b.bg_stroke),
+            fg_stroke: stroke(a.fg_stroke, b.fg_stroke),
+            expansion: emath::lerp(a.expansion..=b.expansion, t),
+            ..if t < 0.5 { *a } else { *b }
+        };
+
+        let mut visuals = if t < 0.5 { self.clone() } else { other.clone() };
+
+        if let (Some(a), Some(b)) = (self.override_text_color, other.override_text_color) {
+            visuals.override_text_color = Some(color(a, b));
+        }
+
+        let (a, b) = (&self.widgets, &other.widgets);
+        visuals.widgets = Widgets {
+            noninteractive: widget(&a.noninteractive, &b.noninteractive),
+            inactive: widget(&a.inactive, &b.inactive),
+            hovered: widget(&a.hovered, &b.hovered),
+            active: widget(&a.active, &b.active),
+            open: widget(&a.open, &b.open),
+        };
+
+        visuals.selection = Selection {
+            bg_fill: color(self.selection.bg_fill, other.selection.bg_fill),
+            stroke: stroke(self.selection.stroke, other.selection.stroke),
+        };
+
+        visuals.hyperlink_color = color(self.hyperlink_color, other.hyperlink_color);
+        visuals.faint_bg_color = color(self.faint_bg_color, other.faint_bg_color);
+        visuals.extreme_bg_color = color(self.extreme_bg_color, other.extreme_bg_color);
+        visuals.code_bg_color = color(self.code_bg_color, other.code_bg_color);
+        visuals.warn_fg_color = color(self.warn_fg_color, other.warn_fg_color);
+        visuals.error_fg_color = color(self.error_fg_color, other.error_fg_color);
+        visuals.window_shadow = shadow(self.window_shadow, other.window_shadow);
+        visuals.window_fill = color(self.window_fill, other.window_fill);
+        visuals.window_stroke = stroke(self.window_stroke, other.window_stroke);
+        visuals.panel_fill = color(self.panel_fill, other.panel_fill);
+        visuals.popup_shadow = shadow(self.popup_shadow, other.popup_shadow);
+        visuals.text_cursor.stroke = stroke(self.text_cursor.stroke, other.text_cursor.stroke);
+
+        visuals
+    }
+}
+
+#[test]
+fn visuals_lerp() {
+    let (dark, light) = (Visuals::dark(), Visuals::light());
+    assert_eq!(dark.lerp(&light, 0.0), dark);
+    assert_eq!(dark.lerp(&light, 1.0), light);
+
+    let halfway = dark.lerp(&light, 0.5);
+    assert_ne!(halfway.panel_fill, dark.panel_fill);
+    assert_ne!(halfway.panel_fill, light.panel_fill);
+}
+
+#[cfg(feature = "ron")]
+#[test]
+fn style_ron_round_trip() {
+    let style = Style::default();
+    let parsed = Style::from_ron(&style.to_ron().unwrap()).unwrap();
+    assert_eq!(parsed.spacing, style.spacing);
+    assert_eq!(parsed.visuals, style.visuals);
+
+    let parsed = Style::from_ron("(animation_time: 0.5)").unwrap();
+    assert_eq!(parsed.animation_time, 0.5);
+    assert_eq!(parsed.visuals, style.visuals);
 }
 
 /// Selected text, selected elements etc
@@ -1230,6 +1383,7 @@ impl Default for Style {
             url_in_tooltip: false,
             always_scroll_the_only_direction: false,
             scroll_animation: ScrollAnimation::default(),
+            right_to_left: false,
         }
     }
 }
@@ -1374,6 +1528,156 @@ impl Visuals {
             ..Self::dark()
         }
     }
+
+    /// A high-contrast version of these visuals, for users who have asked the OS for more contrast.
+    ///
+    /// Backgrounds become black (white in light mode), text and outlines white (black),
+    /// and all widgets get a visible outline.
+    /// See also [`crate::Options::follow_system_contrast`].
+    #[inline]
+    pub fn high_contrast(mut self) -> Self {
+        let (fg, bg, accent) = if self.dark_mode {
+            (
+                Color32::WHITE,
+                Color32::BLACK,
+                Color32::from_rgb(255, 255, 0),
+            )
+        } else {
+            (Color32::BLACK, Color32::WHITE, Color32::from_rgb(0, 0, 200))
+        };
+
+        self.override_text_color = None;
+        self.hyperlink_color = accent;
+        self.faint_bg_color = bg;
+        self.extreme_bg_color = bg;
+        self.panel_fill = bg;
+        self.window_fill = bg;
+        self.window_stroke = Stroke::new(1.0, fg);
+        self.selection = Selection {
+            bg_fill: accent,
+            stroke: Stroke::new(1.0, bg),
+        };
+        self.text_cursor.stroke.color = fg;
+
+        let Widgets {
+            noninteractive,
+            inactive,
+            hovered,
+            active,
+            open,
+        } = &mut self.widgets;
+        for widget in [noninteractive, inactive, open] {
+            widget.weak_bg_fill = bg;
+            widget.bg_fill = bg;
+            widget.bg_stroke = Stroke::new(1.0, fg);
+            widget.fg_stroke.color = fg;
+        }
+        for widget in [hovered, active] {
+            widget.bg_stroke = Stroke::new(2.0, accent);
+            widget.fg_stroke.color = fg;
+        }
+
+        self
+    }
+
+    /// Undo [`Self::high_contrast`], except for the settings that were changed since.
+    ///
+    /// `before` are the visuals [`Self::high_contrast`] was called on, and `applied` what it returned.
+    pub(crate) fn undo_high_contrast(&mut self, before: &Self, applied: &Self) {
+        fn restore<T: Clone + PartialEq>(current: &mut T, before: &T, applied: &T) {
+            if current == applied {
+                *current = before.clone();
+            }
+        }
+
+        restore(
+            &mut self.override_text_color,
+            &before.override_text_color,
+            &applied.override_text_color,
+        );
+        restore(
+            &mut self.hyperlink_color,
+            &before.hyperlink_color,
+            &applied.hyperlink_color,
+        );
+        restore(
+            &mut self.faint_bg_color,
+            &before.faint_bg_color,
+            &applied.faint_bg_color,
+        );
+        restore(
+            &mut self.extreme_bg_color,
+            &before.extreme_bg_color,
+            &applied.extreme_bg_color,
+        );
+        restore(
+            &mut self.panel_fill,
+            &before.panel_fill,
+            &applied.panel_fill,
+        );
+        restore(
+            &mut self.window_fill,
+            &before.window_fill,
+            &applied.window_fill,
+        );
+        restore(
+            &mut self.window_stroke,
+            &before.window_stroke,
+            &applied.window_stroke,
+        );
+        restore(&mut self.selection, &before.selection, &applied.selection);
+        restore(
+            &mut self.text_cursor.stroke.color,
+            &before.text_cursor.stroke.color,
+            &applied.text_cursor.stroke.color,
+        );
+
+        let widgets = [
+            (
+                &mut self.widgets.noninteractive,
+                &before.widgets.noninteractive,
+                &applied.widgets.noninteractive,
+            ),
+            (
+                &mut self.widgets.inactive,
+                &before.widgets.inactive,
+                &applied.widgets.inactive,
+            ),
+            (
+                &mut self.widgets.hovered,
+                &before.widgets.hovered,
+                &applied.widgets.hovered,
+            ),
+            (
+                &mut self.widgets.active,
+                &before.widgets.active,
+                &applied.widgets.active,
+            ),
+            (
+                &mut self.widgets.open,
+                &before.widgets.open,
+                &applied.widgets.open,
+            ),
+        ];
+        for (current, before, applied) in widgets {
+            restore(
+                &mut current.weak_bg_fill,
+                &before.weak_bg_fill,
+                &applied.weak_bg_fill,
+            );
+            restore(&mut current.bg_fill, &before.bg_fill, &applied.bg_fill);
+            restore(
+                &mut current.bg_stroke,
+                &before.bg_stroke,
+                &applied.bg_stroke,
+            );
+            restore(
+                &mut current.fg_stroke.color,
+                &before.fg_stroke.color,
+                &applied.fg_stroke.color,
+            );
+        }
+    }
 }
 
 impl Default for Visuals {
@@ -1531,6 +1835,7 @@ impl Style {
             url_in_tooltip,
             always_scroll_the_only_direction,
             scroll_animation,
+            right_to_left,
         } = self;
 
         crate::Grid::new("_options").show(ui, |ui| {
@@ -1648,6 +1953,11 @@ impl Style {
                 "If scrolling is enabled for only one direction, allow horizontal scrolling without pressing shift",
             );
 
+        ui.checkbox(right_to_left, "Right-to-left layout")
+            .on_hover_text(
+                "Lay out panels and windows from right to left, e.g. for Arabic and Hebrew",
+            );
+
         ui.vertical_centered(|ui| reset_button(ui, self, "Reset style"));
     }
 }
@@ -125,6 +125,13 @@ impl CursorRange {
         modifiers: &Modifiers,
         key: Key,
     ) -> bool {
+        // In right-to-left text, the arrow keys move the other way through the text:
+        let key = match key {
+            Key::ArrowLeft if galley.is_rtl_at(&self.primary) => Key::ArrowRight,
+            Key::ArrowRight if galley.is_rtl_at(&self.primary) => Key::ArrowLeft,
+            key => key,
+        };
+
         match key {
             Key::A if modifiers.command => {
                 *self = Self::select_all(galley);
@@ -0,0 +1,147 @@
+/// Describes how a value animates towards a new target:
+/// how long it takes, how it eases in and out, and how long to wait before starting.
+///
+/// Use it with [`crate::Context::animate_value_with_transition`] and friends.
+///
+/// Transitions can be staggered (e.g. to animate the items of a list one after the other)
+/// with [`Self::staggered`], and chained (to start one animation when another ends) with [`Self::then`].
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::{emath::easing, Transition};
+///
+/// // Fade in the items one after the other:
+/// let fade_in = Transition::new(0.2).easing(easing::cubic_out);
+/// for (i, item) in ["one", "two", "three"].into_iter().enumerate() {
+///     let id = ui.id().with(("item", i));
+///     let opacity = ui.ctx().animate_appear(id, &fade_in.staggered(i, 0.05));
+///     ui.scope(|ui| {
+///         ui.multiply_opacity(opacity);
+///         ui.label(item);
+///     });
+/// }
+///
+/// // …and then the footer:
+/// let footer = fade_in.staggered(2, 0.05).then(0.3);
+/// let opacity = ui.ctx().animate_appear(ui.id().with("footer"), &footer);
+/// ui.scope(|ui| {
+///     ui.multiply_opacity(opacity);
+///     ui.label("That's all!");
+/// });
+/// # });
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Transition {
+    /// How long the animation takes, in seconds, not counting [`Self::delay`].
+    pub duration: f32,
+
+    /// How long to wait before starting the animation, in seconds.
+    pub delay: f32,
+
+    /// Maps the linear progress in `[0, 1]` to how far the value has come,
+    /// e.g. [`emath::easing::cubic_out`] for a quick start and a slow end.
+    ///
+    /// Should map 0 to 0 and 1 to 1.
+    pub easing: fn(f32) -> f32,
+}
+
+impl Transition {
+    /// A linear transition taking the given number of seconds.
+    ///
+    /// A good default is [`crate::Style::animation_time`].
+    pub fn new(duration: f32) -> Self {
+        Self {
+            duration,
+            delay: 0.0,
+            easing: emath::easing::linear,
+        }
+    }
+
+    /// Use an easing function from [`emath::easing`], or your own.
+    #[inline]
+    pub fn easing(mut self, easing: fn(f32) -> f32) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Wait this many seconds before starting the animation.
+    #[inline]
+    pub fn delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Delay the animation of the item at `index` by `index * step` seconds (on top of any [`Self::delay`]),
+    /// so that the items of a list animate one after the other.
+    #[inline]
+    pub fn staggered(mut self, index: usize, step: f32) -> Self {
+        self.delay += index as f32 * step;
+        self
+    }
+
+    /// A transition of the given duration that starts when this one ends, with the same easing.
+    #[inline]
+    pub fn then(&self, duration: f32) -> Self {
+        Self {
+            duration,
+            delay: self.total_time(),
+            easing: self.easing,
+        }
+    }
+
+    /// The time from the start of the transition until it is done, including the [`Self::delay`].
+    #[inline]
+    pub fn total_time(&self) -> f32 {
+        self.delay + self.duration
+    }
+
+    /// How far the value has come after the given number of seconds since the start of the transition.
+    ///
+    /// This is 0.0 until the [`Self::delay`] has passed, and exactly 1.0 once the transition is done,
+    /// with the [`Self::easing`] applied in between.
+    pub fn progress(&self, time_since_start: f32) -> f32 {
+        let t = time_since_start - self.delay;
+        if self.duration <= t {
+            1.0
+        } else if t <= 0.0 {
+            0.0
+        } else {
+            (self.easing)(t / self.duration)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let transition = Transition::new(2.0).delay(1.0);
+        assert_eq!(transition.progress(-1.0), 0.0);
+        assert_eq!(transition.progress(1.0), 0.0);
+        assert_eq!(transition.progress(2.0), 0.5);
+        assert_eq!(transition.progress(3.0), 1.0);
+        assert_eq!(transition.progress(f32::INFINITY), 1.0);
+
+        let eased = transition.easing(emath::easing::quadratic_in);
+        assert_eq!(eased.progress(2.0), 0.25);
+
+        // An instant transition is done right away:
+        assert_eq!(Transition::new(0.0).progress(0.0), 1.0);
+    }
+
+    #[test]
+    fn test_stagger_and_chain() {
+        let first = Transition::new(0.5).delay(0.25);
+        let third = first.staggered(2, 0.5);
+        assert_eq!(third.delay, 1.25);
+        assert_eq!(third.total_time(), 1.75);
+
+        let next = third.then(1.0);
+        assert_eq!(next.delay, 1.75);
+        assert_eq!(next.duration, 1.0);
+        assert_eq!(next.progress(1.75), 0.0);
+        assert_eq!(next.progress(2.75), 1.0);
+    }
+}
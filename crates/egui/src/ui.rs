@@ -25,8 +25,9 @@ use crate::{
         RadioButton, SelectableLabel, Separator, Spinner, TextEdit, Widget,
     },
     Align, Color32, Context, CursorIcon, DragAndDrop, Id, InnerResponse, InputState, LayerId,
-    Memory, Order, Painter, PlatformOutput, Pos2, Rangef, Rect, Response, Rgba, RichText, Sense,
-    Style, TextStyle, TextWrapMode, UiBuilder, UiStack, UiStackInfo, Vec2, WidgetRect, WidgetText,
+    Memory, NavScope, Order, Painter, PlatformOutput, Pos2, Rangef, Rect, Response, Rgba, RichText,
+    Sense, Style, TextStyle, TextWrapMode, UiBuilder, UiStack, UiStackInfo, Vec2, WidgetRect,
+    WidgetText,
 };
 
 #[cfg(debug_assertions)]
@@ -101,6 +102,9 @@ pub struct Ui {
     /// Indicates whether this Ui belongs to a Menu.
     menu_state: Option<Arc<RwLock<MenuState>>>,
 
+    /// The arrow-key navigation scope of the widgets in this Ui.
+    nav_scope: Option<NavScope>,
+
     /// The [`UiStack`] for this [`Ui`].
     stack: Arc<UiStack>,
 
@@ -133,6 +137,7 @@ impl Ui {
             sizing_pass,
             style,
             sense,
+            nav_scope,
         } = ui_builder;
 
         let layer_id = layer_id.unwrap_or(LayerId::background());
@@ -144,9 +149,9 @@ impl Ui {
 
         let max_rect = max_rect.unwrap_or_else(|| ctx.screen_rect());
         let clip_rect = max_rect;
-        let layout = layout.unwrap_or_default();
         let disabled = disabled || invisible;
         let style = style.unwrap_or_else(|| ctx.style());
+        let layout = layout.unwrap_or_else(|| style.default_layout());
         let sense = sense.unwrap_or(Sense::hover());
 
         let placer = Placer::new(max_rect, layout);
@@ -168,6 +173,7 @@ impl Ui {
             enabled: true,
             sizing_pass,
             menu_state: None,
+            nav_scope,
             stack: Arc::new(ui_stack),
             sense,
             min_rect_already_remembered: false,
@@ -259,6 +265,7 @@ impl Ui {
             sizing_pass,
             style,
             sense,
+            nav_scope,
         } = ui_builder;
 
         let mut painter = self.painter.clone();
@@ -312,6 +319,7 @@ impl Ui {
             enabled,
             sizing_pass,
             menu_state: self.menu_state.clone(),
+            nav_scope: nav_scope.or(self.nav_scope),
             stack: Arc::new(ui_stack),
             sense,
             min_rect_already_remembered: false,
@@ -1058,7 +1066,7 @@ impl Ui {
 impl Ui {
     /// Check for clicks, drags and/or hover on a specific region of this [`Ui`].
     pub fn interact(&self, rect: Rect, id: Id, sense: Sense) -> Response {
-        self.ctx().create_widget(
+        let response = self.ctx().create_widget(
             WidgetRect {
                 id,
                 layer_id: self.layer_id(),
@@ -1068,7 +1076,30 @@ impl Ui {
                 enabled: self.enabled,
             },
             true,
-        )
+        );
+        if let Some(nav_scope) = self.nav_scope {
+            if sense.is_focusable() {
+                self.ctx()
+                    .memory_mut(|mem| mem.set_nav_scope(id, nav_scope));
+            }
+        }
+        response
+    }
+
+    /// The [`NavScope`] of the widgets in this [`Ui`], if any.
+    ///
+    /// The arrow keys first move focus between widgets in the same scope.
+    #[inline]
+    pub fn nav_scope(&self) -> Option<NavScope> {
+        self.nav_scope
+    }
+
+    /// Put the widgets added from now on in this [`NavScope`], or in none.
+    ///
+    /// Child [`Ui`]s inherit it.
+    #[inline]
+    pub fn set_nav_scope(&mut self, nav_scope: Option<NavScope>) {
+        self.nav_scope = nav_scope;
     }
 
     /// Deprecated: use [`Self::interact`] instead.
@@ -2761,11 +2792,32 @@ impl Ui {
     ///
     /// The `id` needs to be globally unique.
     /// The payload is what will be dropped if the user starts dragging.
+    /// Use [`Self::drop_target`] or [`Self::dnd_drop_zone`] to receive it.
     ///
     /// In contrast to [`Response::dnd_set_drag_payload`],
-    /// this function will paint the widget at the mouse cursor while the user is dragging.
+    /// this function will paint the widget at the mouse cursor while the user is dragging,
+    /// even when the cursor moves into another viewport.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// ui.drag_source(egui::Id::new("apple"), "apple", |ui| {
+    ///     ui.label("🍎 Drag me");
+    /// });
+    ///
+    /// let (_, dropped) = ui.drop_target::<&str, _>(|ui, hovering_payload| {
+    ///     if let Some(fruit) = hovering_payload {
+    ///         ui.label(format!("Drop the {fruit} here!"));
+    ///     } else {
+    ///         ui.label("Fruit basket");
+    ///     }
+    /// });
+    /// if let Some(fruit) = dropped {
+    ///     println!("Got an {fruit}");
+    /// }
+    /// # });
+    /// ```
     #[doc(alias = "drag and drop")]
-    pub fn dnd_drag_source<Payload, R>(
+    pub fn drag_source<Payload, R>(
         &mut self,
         id: Id,
         payload: Payload,
@@ -2795,6 +2847,15 @@ impl Ui {
                 let delta = pointer_pos - response.rect.center();
                 self.ctx()
                     .transform_layer_shapes(layer_id, emath::TSTransform::from_translation(delta));
+
+                // Let the preview follow the pointer into other viewports:
+                let shapes = self.ctx().graphics(|graphics| {
+                    graphics
+                        .get(layer_id)
+                        .map(|list| list.all_entries().cloned().collect())
+                        .unwrap_or_default()
+                });
+                DragAndDrop::set_preview(self.ctx(), shapes, pointer_pos);
             }
 
             InnerResponse::new(inner, response)
@@ -2810,6 +2871,52 @@ impl Ui {
         }
     }
 
+    /// Create something that can be drag-and-dropped.
+    #[doc(alias = "drag and drop")]
+    #[deprecated = "Renamed to `drag_source`"]
+    pub fn dnd_drag_source<Payload, R>(
+        &mut self,
+        id: Id,
+        payload: Payload,
+        add_contents: impl FnOnce(&mut Self) -> R,
+    ) -> InnerResponse<R>
+    where
+        Payload: Any + Send + Sync,
+    {
+        self.drag_source(id, payload, add_contents)
+    }
+
+    /// Something you can drop a payload of the given type onto,
+    /// e.g. from a [`Self::drag_source`].
+    ///
+    /// `add_contents` is given the payload while it is being dragged over the target,
+    /// so that it can show what would happen if it was dropped there.
+    /// Nothing is painted around the contents; for that, see [`Self::dnd_drop_zone`].
+    ///
+    /// Returns the dropped payload, if it was released over the target this frame.
+    #[doc(alias = "drag and drop")]
+    pub fn drop_target<Payload, R>(
+        &mut self,
+        add_contents: impl FnOnce(&mut Ui, Option<Arc<Payload>>) -> R,
+    ) -> (InnerResponse<R>, Option<Arc<Payload>>)
+    where
+        Payload: Any + Send + Sync,
+    {
+        let InnerResponse { inner, response } = self.scope(|ui| {
+            // We don't know the final size of the target yet, so use the one from last pass:
+            let hovering_payload = if ui.response().contains_pointer() {
+                DragAndDrop::payload::<Payload>(ui.ctx())
+            } else {
+                None
+            };
+            add_contents(ui, hovering_payload)
+        });
+
+        let payload = response.dnd_release_payload::<Payload>();
+
+        (InnerResponse { inner, response }, payload)
+    }
+
     /// Surround the given ui with a frame which
     /// changes colors when you can drop something onto it.
     ///
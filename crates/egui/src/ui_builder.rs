@@ -1,6 +1,6 @@
 use std::{hash::Hash, sync::Arc};
 
-use crate::{Id, LayerId, Layout, Rect, Sense, Style, UiStackInfo};
+use crate::{Id, LayerId, Layout, NavScope, Rect, Sense, Style, UiStackInfo};
 
 #[allow(unused_imports)] // Used for doclinks
 use crate::Ui;
@@ -23,6 +23,7 @@ pub struct UiBuilder {
     pub sizing_pass: bool,
     pub style: Option<Arc<Style>>,
     pub sense: Option<Sense>,
+    pub nav_scope: Option<NavScope>,
 }
 
 impl UiBuilder {
@@ -135,4 +136,14 @@ impl UiBuilder {
         self.sense = Some(sense);
         self
     }
+
+    /// Put the widgets of the new `Ui` in this [`NavScope`],
+    /// so that the arrow keys first move focus between them.
+    ///
+    /// Will otherwise be inherited from the parent.
+    #[inline]
+    pub fn nav_scope(mut self, nav_scope: NavScope) -> Self {
+        self.nav_scope = Some(nav_scope);
+        self
+    }
 }
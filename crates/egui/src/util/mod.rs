@@ -2,6 +2,7 @@
 
 pub(crate) mod fixed_cache;
 pub mod id_type_map;
+pub mod undo_stack;
 pub mod undoer;
 
 pub use id_type_map::IdTypeMap;
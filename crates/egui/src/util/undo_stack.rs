@@ -0,0 +1,553 @@
+//! A global undo/redo stack of user actions, see [`UndoStack`].
+
+use std::borrow::Borrow;
+
+use crate::{Context, Id, Key, KeyboardShortcut, Modifiers};
+
+/// The keyboard shortcuts for undo and redo, used by [`crate::Options::undo_with_keyboard`].
+pub mod kb_shortcuts {
+    use super::{Key, KeyboardShortcut, Modifiers};
+
+    /// Undo the last action (`Cmd` + `Z`).
+    pub const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+
+    /// Redo the last undone action (`Cmd` + `Shift` + `Z`).
+    pub const REDO: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+
+    /// Secondary shortcut for redo (`Cmd` + `Y`), common on Windows.
+    pub const REDO_SECONDARY: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+}
+
+/// Something that was done, and can be undone.
+///
+/// See [`UndoStack::push`].
+pub trait UndoAction: Send + Sync {
+    /// Revert the action.
+    fn undo(&mut self, ctx: &Context);
+
+    /// Do the action again, after it was undone.
+    fn redo(&mut self, ctx: &Context);
+}
+
+/// A change of a value owned by a widget (or anything else with an [`Id`]).
+///
+/// Since egui doesn't own your values, undoing this doesn't change the value directly.
+/// Instead the old value is handed back to whoever shows the value the next time they call [`take_restored`].
+/// This is what [`crate::TextEdit`], [`crate::DragValue`] and [`crate::Slider`] do.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let mut volume = 0.5_f32;
+/// use egui::util::undo_stack::{take_restored, ValueChange};
+///
+/// let id = ui.make_persistent_id("volume");
+/// if let Some(restored) = take_restored(ui.ctx(), id) {
+///     volume = restored;
+/// }
+/// if ui.button("Mute").clicked() {
+///     ui.ctx().undo_stack_mut(|stack| {
+///         stack.push("Mute", ValueChange::new(id, volume, 0.0));
+///     });
+///     volume = 0.0;
+/// }
+/// # });
+/// ```
+#[derive(Clone, Debug)]
+pub struct ValueChange<T> {
+    /// Where to restore the value.
+    pub id: Id,
+
+    /// The value before the change, restored by undo.
+    pub before: T,
+
+    /// The value after the change, restored by redo.
+    pub after: T,
+}
+
+impl<T> ValueChange<T> {
+    pub fn new(id: Id, before: T, after: T) -> Self {
+        Self { id, before, after }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> UndoAction for ValueChange<T> {
+    fn undo(&mut self, ctx: &Context) {
+        restore(ctx, self.id, self.before.clone());
+    }
+
+    fn redo(&mut self, ctx: &Context) {
+        restore(ctx, self.id, self.after.clone());
+    }
+}
+
+/// An [`UndoAction`] made from two closures, see [`UndoStack::push_fn`].
+struct FnAction<U, R> {
+    undo: U,
+    redo: R,
+}
+
+impl<U, R> UndoAction for FnAction<U, R>
+where
+    U: FnMut(&Context) + Send + Sync,
+    R: FnMut(&Context) + Send + Sync,
+{
+    fn undo(&mut self, ctx: &Context) {
+        (self.undo)(ctx);
+    }
+
+    fn redo(&mut self, ctx: &Context) {
+        (self.redo)(ctx);
+    }
+}
+
+/// Hand a value back to the widget with the given id, the next time it calls [`take_restored`].
+pub fn restore<T: Clone + Send + Sync + 'static>(ctx: &Context, id: Id, value: T) {
+    ctx.data_mut(|data| data.insert_temp(restore_id(id), value));
+    ctx.request_repaint();
+}
+
+/// If an undo or redo restored the value of the widget with the given id, take it.
+pub fn take_restored<T: Clone + Send + Sync + 'static>(ctx: &Context, id: Id) -> Option<T> {
+    ctx.data_mut(|data| take_temp(data, restore_id(id)))
+}
+
+/// Like [`crate::util::IdTypeMap::remove_temp`], without requiring [`Default`].
+fn take_temp<T: Clone + Send + Sync + 'static>(
+    data: &mut crate::util::IdTypeMap,
+    id: Id,
+) -> Option<T> {
+    let value = data.get_temp(id);
+    data.remove::<T>(id);
+    value
+}
+
+fn restore_id(id: Id) -> Id {
+    id.with("undo_stack_restore")
+}
+
+// ----------------------------------------------------------------------------
+
+/// One or more actions that are undone and redone together.
+struct Transaction {
+    description: String,
+    actions: Vec<Box<dyn UndoAction>>,
+}
+
+impl Transaction {
+    fn undo(&mut self, ctx: &Context) {
+        for action in self.actions.iter_mut().rev() {
+            action.undo(ctx);
+        }
+    }
+
+    fn redo(&mut self, ctx: &Context) {
+        for action in &mut self.actions {
+            action.redo(ctx);
+        }
+    }
+}
+
+/// A stack of things that were done, so that they can be undone and redone.
+///
+/// Each [`crate::Context`] has one, accessed with [`crate::Context::undo_stack_mut`].
+/// Edits in [`crate::TextEdit`], [`crate::DragValue`] and [`crate::Slider`] are pushed to it,
+/// and [`crate::Context::undo`] undoes the last of them.
+/// Turn on [`crate::Options::undo_with_keyboard`] to also undo with `Cmd+Z`
+/// (when no text field has keyboard focus).
+///
+/// You can push your own [`UndoAction`]s, and group several of them into one undo step
+/// with [`Self::begin_transaction`] and [`Self::end_transaction`].
+///
+/// You can also use an [`UndoStack`] of your own, e.g. one per document.
+///
+/// For a simpler undo system that just compares snapshots of your state,
+/// see [`super::undoer::Undoer`].
+pub struct UndoStack {
+    /// The most recent action is last.
+    undos: Vec<Transaction>,
+
+    /// The most recently undone action is last. Cleared when a new action is pushed.
+    redos: Vec<Transaction>,
+
+    /// The transaction being built, and how many times it has been begun.
+    open: Option<(usize, Transaction)>,
+
+    max_len: usize,
+
+    /// Widgets being edited, see [`track_edit`].
+    editing: crate::id::IdSet,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self {
+            undos: Vec::new(),
+            redos: Vec::new(),
+            open: None,
+            max_len: 100,
+            editing: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for UndoStack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            undos,
+            redos,
+            open,
+            max_len,
+            editing: _,
+        } = self;
+        f.debug_struct("UndoStack")
+            .field("undo count", &undos.len())
+            .field("redo count", &redos.len())
+            .field("in transaction", &open.is_some())
+            .field("max_len", max_len)
+            .finish()
+    }
+}
+
+impl UndoStack {
+    /// The maximum number of undo steps to keep. Older steps are forgotten.
+    ///
+    /// Default: `100`.
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len;
+        self.truncate();
+    }
+
+    /// Record something that was done, so that it can be undone.
+    ///
+    /// This clears the redo stack.
+    /// If a transaction is open, the action becomes part of it, and the `description` is ignored.
+    pub fn push(&mut self, description: impl Into<String>, action: impl UndoAction + 'static) {
+        self.redos.clear();
+        if let Some((_, transaction)) = &mut self.open {
+            transaction.actions.push(Box::new(action));
+        } else {
+            self.undos.push(Transaction {
+                description: description.into(),
+                actions: vec![Box::new(action)],
+            });
+            self.truncate();
+        }
+    }
+
+    /// Like [`Self::push`], with the action given as two closures.
+    pub fn push_fn(
+        &mut self,
+        description: impl Into<String>,
+        undo: impl FnMut(&Context) + Send + Sync + 'static,
+        redo: impl FnMut(&Context) + Send + Sync + 'static,
+    ) {
+        self.push(description, FnAction { undo, redo });
+    }
+
+    /// Start grouping the actions pushed from now on into one undo step,
+    /// until the matching call to [`Self::end_transaction`].
+    ///
+    /// Transactions can be nested, in which case the outermost one wins.
+    pub fn begin_transaction(&mut self, description: impl Into<String>) {
+        if let Some((depth, _)) = &mut self.open {
+            *depth += 1;
+        } else {
+            let transaction = Transaction {
+                description: description.into(),
+                actions: Vec::new(),
+            };
+            self.open = Some((1, transaction));
+        }
+    }
+
+    /// End the transaction started by [`Self::begin_transaction`].
+    ///
+    /// Once the outermost transaction ends, its actions become one undo step (if there were any).
+    pub fn end_transaction(&mut self) {
+        let Some((depth, _)) = &mut self.open else {
+            #[cfg(feature = "log")]
+            log::warn!("UndoStack::end_transaction called without a matching begin_transaction");
+            return;
+        };
+        *depth -= 1;
+        if *depth == 0 {
+            self.close_transaction();
+        }
+    }
+
+    /// Is a transaction open?
+    pub fn is_in_transaction(&self) -> bool {
+        self.open.is_some()
+    }
+
+    fn close_transaction(&mut self) {
+        if let Some((_, transaction)) = self.open.take() {
+            if !transaction.actions.is_empty() {
+                self.undos.push(transaction);
+                self.truncate();
+            }
+        }
+    }
+
+    fn truncate(&mut self) {
+        if self.max_len < self.undos.len() {
+            let excess = self.undos.len() - self.max_len;
+            self.undos.drain(..excess);
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undos.is_empty()
+            || self
+                .open
+                .as_ref()
+                .is_some_and(|(_, t)| !t.actions.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redos.is_empty()
+    }
+
+    /// The description of what [`Self::undo`] would undo, e.g. for an "Undo …" menu item.
+    pub fn undo_description(&self) -> Option<&str> {
+        match &self.open {
+            Some((_, transaction)) if !transaction.actions.is_empty() => {
+                Some(&transaction.description)
+            }
+            _ => self.undos.last().map(|t| t.description.as_str()),
+        }
+    }
+
+    /// The description of what [`Self::redo`] would redo.
+    pub fn redo_description(&self) -> Option<&str> {
+        self.redos.last().map(|t| t.description.as_str())
+    }
+
+    /// Forget everything.
+    pub fn clear(&mut self) {
+        self.undos.clear();
+        self.redos.clear();
+        self.open = None;
+    }
+
+    /// Undo the last step, ending any open transaction first.
+    ///
+    /// Returns `false` if there was nothing to undo.
+    ///
+    /// To undo on the stack of a [`Context`], use [`Context::undo`] instead.
+    pub fn undo(&mut self, ctx: &Context) -> bool {
+        let Some(mut transaction) = self.pop_undo() else {
+            return false;
+        };
+        transaction.undo(ctx);
+        self.redos.push(transaction);
+        true
+    }
+
+    /// Redo the last undone step.
+    ///
+    /// Returns `false` if there was nothing to redo.
+    ///
+    /// To redo on the stack of a [`Context`], use [`Context::redo`] instead.
+    pub fn redo(&mut self, ctx: &Context) -> bool {
+        let Some(mut transaction) = self.redos.pop() else {
+            return false;
+        };
+        transaction.redo(ctx);
+        self.undos.push(transaction);
+        true
+    }
+
+    fn pop_undo(&mut self) -> Option<Transaction> {
+        self.close_transaction();
+        self.undos.pop()
+    }
+}
+
+/// Take the last step out of the stack of the [`Context`], undo it, and put it back on the redo stack.
+///
+/// The actions are run without holding the lock on the [`Context`].
+pub(crate) fn undo_in(ctx: &Context) -> bool {
+    let Some(mut transaction) = ctx.undo_stack_mut(|stack| stack.pop_undo()) else {
+        return false;
+    };
+    transaction.undo(ctx);
+    ctx.undo_stack_mut(|stack| stack.redos.push(transaction));
+    true
+}
+
+/// See [`undo_in`].
+pub(crate) fn redo_in(ctx: &Context) -> bool {
+    let Some(mut transaction) = ctx.undo_stack_mut(|stack| stack.redos.pop()) else {
+        return false;
+    };
+    transaction.redo(ctx);
+    ctx.undo_stack_mut(|stack| stack.undos.push(transaction));
+    true
+}
+
+/// Undo and redo with the keyboard, unless a text field has keyboard focus (it has its own undo).
+///
+/// [`crate::Context`] calls this function at the end of each pass
+/// if [`crate::Options::undo_with_keyboard`] is on.
+pub(crate) fn undo_with_keyboard(ctx: &Context) {
+    let text_has_focus = ctx
+        .memory(|mem| mem.focused())
+        .is_some_and(|id| crate::text_edit::TextEditState::load(ctx, id).is_some());
+    if text_has_focus {
+        return;
+    }
+
+    // Check redo first, since `Cmd+Shift+Z` also logically matches `Cmd+Z`:
+    if ctx.input_mut(|i| {
+        i.consume_shortcut(&kb_shortcuts::REDO) || i.consume_shortcut(&kb_shortcuts::REDO_SECONDARY)
+    }) {
+        ctx.redo();
+    } else if ctx.input_mut(|i| i.consume_shortcut(&kb_shortcuts::UNDO)) {
+        ctx.undo();
+    }
+}
+
+/// Record the edits of a value shown by the widget with the given id on the [`Context`]'s [`UndoStack`].
+///
+/// While `is_editing` (e.g. while dragging, or while the widget has keyboard focus)
+/// all changes are collected into one undo step, pushed when the editing stops.
+/// Other changes (`before != now`) are pushed right away.
+///
+/// Only call this for widgets that can be edited.
+/// Of those, the ones that are not being edited and did not change cost only a set lookup.
+pub(crate) fn track_edit<T>(
+    ctx: &Context,
+    id: Id,
+    description: &str,
+    is_editing: bool,
+    before: &T,
+    now: &T,
+) where
+    T: ?Sized + ToOwned + PartialEq,
+    T::Owned: Clone + Send + Sync + 'static,
+{
+    let start_id = id.with("undo_stack_edit_start");
+    if is_editing {
+        let started = ctx.undo_stack_mut(|stack| stack.editing.insert(id));
+        if started {
+            ctx.data_mut(|data| data.insert_temp(start_id, before.to_owned()));
+        }
+    } else {
+        let was_editing = ctx.undo_stack(|stack| stack.editing.contains(&id));
+        if !was_editing && before == now {
+            return; // Nothing to record
+        }
+        if was_editing {
+            ctx.undo_stack_mut(|stack| stack.editing.remove(&id));
+        }
+
+        let start = ctx.data_mut(|data| take_temp::<T::Owned>(data, start_id));
+        let start = start.as_ref().map_or(before, |start| start.borrow());
+        if start != now {
+            let change = ValueChange::new(id, start.to_owned(), now.to_owned());
+            ctx.undo_stack_mut(|stack| stack.push(description, change));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_value(value: i32) -> impl FnMut(&Context) + Send + Sync {
+        move |ctx| restore(ctx, Id::NULL, value)
+    }
+
+    #[test]
+    fn undo_redo() {
+        let ctx = Context::default();
+        let mut stack = UndoStack::default();
+        assert!(!stack.can_undo());
+
+        stack.push_fn("one", set_value(0), set_value(1));
+        stack.push_fn("two", set_value(1), set_value(2));
+        assert_eq!(stack.undo_description(), Some("two"));
+
+        assert!(stack.undo(&ctx));
+        assert_eq!(take_restored::<i32>(&ctx, Id::NULL), Some(1));
+        assert_eq!(stack.redo_description(), Some("two"));
+
+        assert!(stack.redo(&ctx));
+        assert_eq!(take_restored::<i32>(&ctx, Id::NULL), Some(2));
+
+        // Pushing clears the redos:
+        assert!(stack.undo(&ctx));
+        stack.push_fn("three", set_value(1), set_value(3));
+        assert!(!stack.can_redo());
+        assert_eq!(take_restored::<i32>(&ctx, Id::NULL), Some(1));
+    }
+
+    #[test]
+    fn transactions() {
+        let ctx = Context::default();
+        let mut stack = UndoStack::default();
+
+        stack.begin_transaction("both");
+        stack.push_fn("ignored", set_value(0), set_value(1));
+        stack.begin_transaction("inner");
+        stack.push_fn("ignored", set_value(1), set_value(2));
+        stack.end_transaction();
+        assert!(stack.is_in_transaction());
+        stack.end_transaction();
+        assert!(!stack.is_in_transaction());
+
+        assert_eq!(stack.undo_description(), Some("both"));
+        assert!(stack.undo(&ctx));
+        // The actions were undone last-to-first:
+        assert_eq!(take_restored::<i32>(&ctx, Id::NULL), Some(0));
+        assert!(!stack.can_undo());
+
+        // Empty transactions are not recorded:
+        stack.begin_transaction("empty");
+        stack.end_transaction();
+        assert_eq!(stack.undo_description(), None);
+    }
+
+    #[test]
+    fn max_len() {
+        let mut stack = UndoStack::default();
+        stack.set_max_len(2);
+        for i in 0..5 {
+            stack.push_fn(i.to_string(), set_value(i), set_value(i + 1));
+        }
+        let ctx = Context::default();
+        assert!(stack.undo(&ctx));
+        assert!(stack.undo(&ctx));
+        assert!(!stack.undo(&ctx));
+        assert_eq!(take_restored::<i32>(&ctx, Id::NULL), Some(3));
+    }
+
+    #[test]
+    fn track_edit_records_one_step_per_edit() {
+        let ctx = Context::default();
+        let id = Id::new("value");
+        let start_id = id.with("undo_stack_edit_start");
+
+        // Not edited and unchanged: nothing is tracked.
+        track_edit(&ctx, id, "edit", false, &1, &1);
+        assert!(ctx.data(|data| data.get_temp::<i32>(start_id).is_none()));
+        assert!(!ctx.undo_stack(|stack| stack.can_undo()));
+
+        // A drag from 1 to 3 is one step, pushed when it ends:
+        track_edit(&ctx, id, "edit", true, &1, &2);
+        track_edit(&ctx, id, "edit", true, &2, &3);
+        assert!(!ctx.undo_stack(|stack| stack.can_undo()));
+        track_edit(&ctx, id, "edit", false, &3, &3);
+        assert!(ctx.undo_stack(|stack| stack.editing.is_empty()));
+        assert!(ctx.undo());
+        assert_eq!(take_restored::<i32>(&ctx, id), Some(1));
+        assert!(!ctx.undo_stack(|stack| stack.can_undo()));
+
+        // A change without editing (e.g. a click) is pushed right away:
+        track_edit(&ctx, id, "edit", false, &1, &5);
+        assert!(ctx.undo());
+        assert_eq!(take_restored::<i32>(&ctx, id), Some(1));
+    }
+}
@@ -69,7 +69,7 @@
 
 use std::sync::Arc;
 
-use epaint::{Pos2, Vec2};
+use epaint::{emath::OrderedFloat, Pos2, Vec2};
 
 use crate::{Context, Id};
 
@@ -259,7 +259,7 @@ pub type ImmediateViewportRendererCallback = dyn for<'a> Fn(&Context, ImmediateV
 ///
 /// The default values are implementation defined, so you may want to explicitly
 /// configure the size of the window, and what buttons are shown.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 #[allow(clippy::option_option)]
 pub struct ViewportBuilder {
     /// The title of the viewport.
@@ -307,8 +307,24 @@ pub struct ViewportBuilder {
 
     pub mouse_passthrough: Option<bool>,
 
+    /// See [`Self::with_render_scale`].
+    pub render_scale: Option<OrderedFloat<f32>>,
+
     // X11
     pub window_type: Option<X11WindowType>,
+
+    /// See [`Self::with_owner`].
+    pub owner: Option<ViewportId>,
+
+    /// See [`Self::with_window_group`].
+    pub window_group: Option<String>,
+
+    /// See [`Self::with_window_tabbing`].
+    pub window_tabbing: Option<bool>,
+}
+
+fn is_valid_render_scale(render_scale: f32) -> bool {
+    render_scale.is_finite() && 0.0 < render_scale
 }
 
 impl ViewportBuilder {
@@ -600,6 +616,24 @@ impl ViewportBuilder {
         self
     }
 
+    /// Render the viewport at a different resolution than the display,
+    /// as a factor of the native pixels-per-point.
+    ///
+    /// Values above `1.0` supersample (e.g. for crisp screenshots or video capture),
+    /// values below `1.0` undersample (e.g. for performance on weak GPUs).
+    /// The layout of egui is not affected; the renderer stretches the result to fit the window.
+    ///
+    /// The default is `1.0`. Values that are not finite and positive (e.g. NaN) are ignored.
+    ///
+    /// With the `glow` renderer this requires OpenGL 3 (or WebGL2) and a screen framebuffer without multisampling.
+    #[inline]
+    pub fn with_render_scale(mut self, render_scale: f32) -> Self {
+        if is_valid_render_scale(render_scale) {
+            self.render_scale = Some(OrderedFloat(render_scale));
+        }
+        self
+    }
+
     /// ### On X11
     /// This sets the window type.
     /// Maps directly to [`_NET_WM_WINDOW_TYPE`](https://specifications.freedesktop.org/wm-spec/wm-spec-1.5.html).
@@ -609,6 +643,47 @@ impl ViewportBuilder {
         self
     }
 
+    /// Make the window owned by the window of another viewport, e.g. for tool windows and dialogs.
+    ///
+    /// An owned window always stays above its owner (but not above other windows),
+    /// and is minimized and restored together with it.
+    ///
+    /// eframe creates the window of the owner first.
+    /// If the owner has no window yet, the window of this viewport is created once it has one.
+    ///
+    /// ### Platform-specific
+    /// * Windows: an [owned window](https://learn.microsoft.com/en-us/windows/win32/winmsg/window-features#owned-windows).
+    /// * macOS: a child window, which also moves together with its owner.
+    /// * Linux (X11 and Wayland), web, mobile: unsupported, the window is a normal top-level window.
+    #[inline]
+    pub fn with_owner(mut self, owner: ViewportId) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Put the window in a group of windows that the window manager treats as belonging together.
+    ///
+    /// ### Platform-specific
+    /// * X11: sets the class (and instance) of `WM_CLASS`, which is used to group windows in the taskbar.
+    /// * macOS: sets the tabbing identifier; windows with the same identifier can be merged into tabs.
+    /// * Others: unsupported.
+    #[inline]
+    pub fn with_window_group(mut self, group: impl Into<String>) -> Self {
+        self.window_group = Some(group.into());
+        self
+    }
+
+    /// macOS: allow the system to merge windows into tabs (the default), or not.
+    ///
+    /// Set this to `false` to remove the tab-related items from the "View" menu,
+    /// and to stop windows from opening as tabs when the user has asked for that in the system settings.
+    /// Note that this affects all windows of the application.
+    #[inline]
+    pub fn with_window_tabbing(mut self, enabled: bool) -> Self {
+        self.window_tabbing = Some(enabled);
+        self
+    }
+
     /// Update this `ViewportBuilder` with a delta,
     /// returning a list of commands and a bool indicating if the window needs to be recreated.
     #[must_use]
@@ -639,8 +714,12 @@ impl ViewportBuilder {
             maximize_button: new_maximize_button,
             window_level: new_window_level,
             mouse_passthrough: new_mouse_passthrough,
+            render_scale: new_render_scale,
             taskbar: new_taskbar,
             window_type: new_window_type,
+            owner: new_owner,
+            window_group: new_window_group,
+            window_tabbing: new_window_tabbing,
         } = new_vp_builder;
 
         let mut commands = Vec::new();
@@ -748,6 +827,13 @@ impl ViewportBuilder {
             }
         }
 
+        if let Some(new_render_scale) = new_render_scale.filter(|s| is_valid_render_scale(s.0)) {
+            if Some(new_render_scale) != self.render_scale {
+                self.render_scale = Some(new_render_scale);
+                commands.push(ViewportCommand::RenderScale(new_render_scale));
+            }
+        }
+
         // --------------------------------------------------------------
         // Things we don't have commands for require a full window recreation.
         // The reason we don't have commands for them is that `winit` doesn't support
@@ -826,6 +912,21 @@ impl ViewportBuilder {
             recreate_window = true;
         }
 
+        if new_owner.is_some() && self.owner != new_owner {
+            self.owner = new_owner;
+            recreate_window = true;
+        }
+
+        if new_window_group.is_some() && self.window_group != new_window_group {
+            self.window_group = new_window_group;
+            recreate_window = true;
+        }
+
+        if new_window_tabbing.is_some() && self.window_tabbing != new_window_tabbing {
+            self.window_tabbing = new_window_tabbing;
+            recreate_window = true;
+        }
+
         (commands, recreate_window)
     }
 }
@@ -934,6 +1035,89 @@ pub enum ResizeDirection {
     SouthWest,
 }
 
+/// A menu shown by the operating system, see [`ViewportCommand::SetNativeMenu`].
+///
+/// When the user picks a [`NativeMenuItem::Button`], the viewport that sent the menu
+/// gets a [`crate::ViewportEvent::MenuItemActivated`] with the id of the button.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct MenuSpec {
+    pub items: Vec<NativeMenuItem>,
+
+    /// Show the menu at the mouse cursor, e.g. as a context menu,
+    /// instead of in the menu bar of the application.
+    pub popup: bool,
+}
+
+impl MenuSpec {
+    /// The menu bar of the application, replacing the current one.
+    ///
+    /// Each item should be a [`NativeMenuItem::SubMenu`].
+    /// On Mac the first one becomes the application menu (the one named after the app),
+    /// whatever its label.
+    ///
+    /// An empty menu bar brings back the default one.
+    pub fn menu_bar(items: Vec<NativeMenuItem>) -> Self {
+        Self {
+            items,
+            popup: false,
+        }
+    }
+
+    /// A menu at the mouse cursor, e.g. for a right-click.
+    pub fn popup(items: Vec<NativeMenuItem>) -> Self {
+        Self { items, popup: true }
+    }
+}
+
+/// An item of a [`MenuSpec`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum NativeMenuItem {
+    Button {
+        /// Sent back in [`crate::ViewportEvent::MenuItemActivated`].
+        id: crate::Id,
+
+        label: String,
+
+        /// Shown next to the label.
+        /// On Mac, pressing it also activates the button.
+        shortcut: Option<crate::KeyboardShortcut>,
+
+        enabled: bool,
+
+        /// `Some` for a button that shows a check mark when `true`.
+        checked: Option<bool>,
+    },
+
+    SubMenu {
+        label: String,
+        items: Vec<NativeMenuItem>,
+    },
+
+    Separator,
+}
+
+impl NativeMenuItem {
+    /// An enabled button without shortcut or check mark.
+    pub fn button(id: crate::Id, label: impl Into<String>) -> Self {
+        Self::Button {
+            id,
+            label: label.into(),
+            shortcut: None,
+            enabled: true,
+            checked: None,
+        }
+    }
+
+    pub fn sub_menu(label: impl Into<String>, items: Vec<Self>) -> Self {
+        Self::SubMenu {
+            label: label.into(),
+            items,
+        }
+    }
+}
+
 /// An output [viewport](crate::viewport)-command from egui to the backend, e.g. to change the window title or size.
 ///
 /// You can send a [`ViewportCommand`] to the viewport with [`Context::send_viewport_cmd`].
@@ -951,11 +1135,16 @@ pub enum ResizeDirection {
 pub enum ViewportCommand {
     /// Request this viewport to be closed.
     ///
+    /// This sends a [`crate::ViewportEvent::Close`] to the viewport the next frame,
+    /// just like when the user clicks the close button.
     /// For the root viewport, this usually results in the application shutting down.
     /// For other viewports, the [`crate::ViewportInfo::close_requested`] flag will be set.
     Close,
 
     /// Cancel the closing that was signaled by [`crate::ViewportInfo::close_requested`].
+    ///
+    /// Send this the same frame as you get the [`crate::ViewportEvent::Close`].
+    /// It also cancels a [`Self::Close`] sent earlier in the same frame.
     CancelClose,
 
     /// Set the window title.
@@ -1018,6 +1207,12 @@ pub enum ViewportCommand {
     /// Set window to be always-on-top, always-on-bottom, or neither.
     WindowLevel(WindowLevel),
 
+    /// Render at this factor of the native resolution.
+    ///
+    /// Must be finite and positive.
+    /// See [`ViewportBuilder::with_render_scale`].
+    RenderScale(OrderedFloat<f32>),
+
     /// The window icon.
     Icon(Option<Arc<IconData>>),
 
@@ -1026,12 +1221,13 @@ pub enum ViewportCommand {
     IMEAllowed(bool),
     IMEPurpose(IMEPurpose),
 
-    /// Bring the window into focus (native only).
+    /// Bring the window into focus.
     ///
     /// This command puts the window on top of other applications and takes input focus away from them,
     /// which, if unexpected, will disturb the user.
     ///
     /// Has no effect on Wayland, or if the window is minimized or invisible.
+    /// On web, this gives keyboard focus to the canvas.
     Focus,
 
     /// If the window is unfocused, attract the user's attention (native only).
@@ -1078,6 +1274,32 @@ pub enum ViewportCommand {
     ///
     /// This is equivalent to the system keyboard shortcut for paste (e.g. CTRL + V).
     RequestPaste,
+
+    /// Open the emoji picker of the operating system,
+    /// next to the text cursor of the focused [`crate::TextEdit`].
+    ///
+    /// The picked emoji arrive as [`crate::Event::Text`].
+    ///
+    /// This is equivalent to the system keyboard shortcut (`Win+.` on Windows, `Cmd+Ctrl+Space` on Mac).
+    /// On other platforms (Linux, web) there is no system emoji picker that we can open,
+    /// but the emoji input of the input method (if any) works.
+    ShowEmojiPicker,
+
+    /// Show a menu of the operating system, see [`MenuSpec`].
+    ///
+    /// On Mac, a [`MenuSpec::menu_bar`] replaces the menu bar of the application
+    /// (there is only one, shared by all windows), and a [`MenuSpec::popup`] is shown at the mouse cursor.
+    /// On Windows only [`MenuSpec::popup`] is supported.
+    /// On other platforms, and on the web, this does nothing: use [`crate::menu`] instead.
+    SetNativeMenu(MenuSpec),
+
+    /// A custom command for the app itself.
+    ///
+    /// The backend does nothing with it except deliver it back to the viewport
+    /// in [`crate::ViewportInfo::user_commands`] the next time its UI runs.
+    /// Together with [`crate::Context::send_viewport_cmd_to`] this lets you send your own
+    /// messages to a specific viewport from anywhere, e.g. "scroll the log window to the end".
+    User(crate::UserData),
 }
 
 impl ViewportCommand {
@@ -1175,3 +1397,42 @@ pub struct ImmediateViewport<'a> {
     /// The user-code that shows the GUI.
     pub viewport_ui_cb: Box<dyn FnMut(&Context) + 'a>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_render_scale_is_ignored() {
+        for invalid in [f32::NAN, f32::INFINITY, 0.0, -1.0] {
+            let builder = ViewportBuilder::default().with_render_scale(invalid);
+            assert_eq!(builder.render_scale, None, "{invalid}");
+            assert_eq!(builder, builder.clone());
+
+            let builder = ViewportBuilder::default()
+                .with_render_scale(2.0)
+                .with_render_scale(invalid);
+            assert_eq!(builder.render_scale, Some(OrderedFloat(2.0)), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn nan_render_scale_is_not_resent() {
+        let mut builder = ViewportBuilder::default();
+        let (commands, _) = builder.patch(ViewportBuilder::default().with_render_scale(2.0));
+        assert_eq!(
+            commands,
+            vec![ViewportCommand::RenderScale(OrderedFloat(2.0))]
+        );
+
+        let new_builder = ViewportBuilder {
+            render_scale: Some(OrderedFloat(f32::NAN)),
+            ..Default::default()
+        };
+        for _ in 0..2 {
+            let (commands, _) = builder.patch(new_builder.clone());
+            assert!(commands.is_empty(), "{commands:?}");
+        }
+        assert_eq!(builder.render_scale, Some(OrderedFloat(2.0)));
+    }
+}
@@ -429,6 +429,7 @@ impl RichText {
                 underline,
                 strikethrough,
                 valign,
+                ..Default::default()
             },
         )
     }
@@ -580,3 +580,353 @@ fn color_cache_set(ctx: &Context, rgba: impl Into<Rgba>, hsva: Hsva) {
 fn use_color_cache<R>(ctx: &Context, f: impl FnOnce(&mut FixedCache<Rgba, Hsva>) -> R) -> R {
     ctx.data_mut(|d| f(d.get_temp_mut_or_default(Id::NULL)))
 }
+
+// ----------------------------------------------------------------------------
+
+/// A color at some point along a [`Gradient`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ColorStop {
+    /// Where along the gradient this color is, in `0.0..=1.0`.
+    pub t: f32,
+
+    pub color: Color32,
+}
+
+/// A color gradient, made up of [`ColorStop`]s, e.g. for a colormap.
+///
+/// Between the stops, the colors are interpolated in gamma space.
+/// Edit it with a [`GradientEditor`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Gradient {
+    /// Always sorted by [`ColorStop::t`].
+    stops: Vec<ColorStop>,
+}
+
+impl Default for Gradient {
+    /// From black to white.
+    fn default() -> Self {
+        Self::new(vec![
+            ColorStop {
+                t: 0.0,
+                color: Color32::BLACK,
+            },
+            ColorStop {
+                t: 1.0,
+                color: Color32::WHITE,
+            },
+        ])
+    }
+}
+
+impl Gradient {
+    /// The stops will be sorted, and their positions clamped to `0.0..=1.0`.
+    pub fn new(mut stops: Vec<ColorStop>) -> Self {
+        for stop in &mut stops {
+            stop.t = stop.t.clamp(0.0, 1.0);
+        }
+        stops.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Self { stops }
+    }
+
+    /// The stops, sorted by [`ColorStop::t`].
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    /// Add a stop, and return its index.
+    pub fn add_stop(&mut self, t: f32, color: Color32) -> usize {
+        let t = t.clamp(0.0, 1.0);
+        let index = self.stops.partition_point(|stop| stop.t <= t);
+        self.stops.insert(index, ColorStop { t, color });
+        index
+    }
+
+    /// Remove the stop at the given index and return it, if there is one.
+    pub fn remove_stop(&mut self, index: usize) -> Option<ColorStop> {
+        (index < self.stops.len()).then(|| self.stops.remove(index))
+    }
+
+    /// Change the color of the stop at the given index, if there is one.
+    pub fn set_stop_color(&mut self, index: usize, color: Color32) {
+        if let Some(stop) = self.stops.get_mut(index) {
+            stop.color = color;
+        }
+    }
+
+    /// The color at `t`, where `0.0` is the start and `1.0` the end of the gradient.
+    ///
+    /// Before the first stop this is the color of the first stop, and likewise for the last one.
+    /// Without any stops, this is [`Color32::TRANSPARENT`].
+    pub fn color_at(&self, t: f32) -> Color32 {
+        let index = self.stops.partition_point(|stop| stop.t <= t);
+        match (
+            index.checked_sub(1).map(|i| self.stops[i]),
+            self.stops.get(index).copied(),
+        ) {
+            (Some(before), Some(after)) => {
+                let span = after.t - before.t;
+                if span <= 0.0 {
+                    after.color
+                } else {
+                    before
+                        .color
+                        .lerp_to_gamma(after.color, (t - before.t) / span)
+                }
+            }
+            (Some(stop), None) | (None, Some(stop)) => stop.color,
+            (None, None) => Color32::TRANSPARENT,
+        }
+    }
+}
+
+/// Lets the user add, move, recolor and remove the stops of a [`Gradient`].
+///
+/// * Double-click the gradient to add a stop there.
+/// * Drag the markers below the gradient to move the stops.
+/// * Click a marker to select it and edit its color.
+/// * Right-click a marker to remove it.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let mut gradient = egui::color_picker::Gradient::default();
+/// ui.add(egui::color_picker::GradientEditor::new(&mut gradient));
+/// # });
+/// ```
+#[must_use = "You should put this widget in a ui with `ui.add(widget);`"]
+pub struct GradientEditor<'a> {
+    gradient: &'a mut Gradient,
+    alpha: Alpha,
+}
+
+impl<'a> GradientEditor<'a> {
+    pub fn new(gradient: &'a mut Gradient) -> Self {
+        Self {
+            gradient,
+            alpha: Alpha::OnlyBlend,
+        }
+    }
+
+    /// What alpha options to show when editing the stop colors.
+    ///
+    /// Default: [`Alpha::OnlyBlend`].
+    #[inline]
+    pub fn alpha(mut self, alpha: Alpha) -> Self {
+        self.alpha = alpha;
+        self
+    }
+}
+
+impl Widget for GradientEditor<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self { gradient, alpha } = self;
+
+        let id = ui.next_auto_id();
+        let selected_id = id.with("selected");
+        let mut selected = ui
+            .data(|d| d.get_temp::<usize>(selected_id))
+            .filter(|&index| index < gradient.stops.len());
+        let mut changed = false;
+
+        let mut response = ui
+            .vertical(|ui| {
+                let desired_size = vec2(ui.spacing().slider_width, ui.spacing().interact_size.y);
+                let (rect, bar_response) = ui.allocate_exact_size(desired_size, Sense::click());
+                let marker_size = vec2(desired_size.y / 2.0, desired_size.y * 0.75);
+                let (markers_rect, _) =
+                    ui.allocate_exact_size(vec2(rect.width(), marker_size.y), Sense::hover());
+
+                if bar_response.double_clicked() {
+                    if let Some(pos) = bar_response.interact_pointer_pos() {
+                        let t = remap_clamp(pos.x, rect.x_range(), 0.0..=1.0);
+                        selected = Some(gradient.add_stop(t, gradient.color_at(t)));
+                        changed = true;
+                    }
+                }
+
+                let mut remove = None;
+                for index in 0..gradient.stops.len() {
+                    let x = lerp(rect.x_range(), gradient.stops[index].t);
+                    let marker_rect =
+                        Rect::from_center_size(pos2(x, markers_rect.center().y), marker_size);
+                    let marker_response =
+                        ui.interact(marker_rect, id.with(index), Sense::click_and_drag());
+
+                    if marker_response.clicked() || marker_response.drag_started() {
+                        selected = Some(index);
+                    }
+                    if marker_response.secondary_clicked() && 1 < gradient.stops.len() {
+                        remove = Some(index);
+                    }
+                    if marker_response.dragged() {
+                        if let Some(pos) = marker_response.interact_pointer_pos() {
+                            // Stops can't pass each other, so they stay sorted:
+                            let min = index.checked_sub(1).map_or(0.0, |i| gradient.stops[i].t);
+                            let max = gradient.stops.get(index + 1).map_or(1.0, |stop| stop.t);
+                            let t = remap_clamp(pos.x, rect.x_range(), 0.0..=1.0).clamp(min, max);
+                            if gradient.stops[index].t != t {
+                                gradient.stops[index].t = t;
+                                changed = true;
+                            }
+                        }
+                    }
+                    marker_response.widget_info(|| WidgetInfo::new(WidgetType::ColorButton));
+                }
+
+                if let Some(index) = remove {
+                    gradient.remove_stop(index);
+                    selected = None;
+                    changed = true;
+                }
+
+                if ui.is_rect_visible(rect.union(markers_rect)) {
+                    paint_gradient(ui, rect, markers_rect, gradient, selected);
+                }
+
+                if let Some(index) = selected {
+                    ui.horizontal(|ui| {
+                        let min = index.checked_sub(1).map_or(0.0, |i| gradient.stops[i].t);
+                        let max = gradient.stops.get(index + 1).map_or(1.0, |stop| stop.t);
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut gradient.stops[index].t)
+                                    .range(min..=max)
+                                    .speed(0.01)
+                                    .prefix("t: "),
+                            )
+                            .changed();
+                        if ui
+                            .add_enabled(1 < gradient.stops.len(), crate::Button::new("🗑"))
+                            .on_hover_text("Remove this stop")
+                            .clicked()
+                        {
+                            gradient.remove_stop(index);
+                            selected = None;
+                            changed = true;
+                        }
+                    });
+                }
+
+                if let Some(index) = selected {
+                    changed |= color_picker_color32(ui, &mut gradient.stops[index].color, alpha);
+                }
+            })
+            .response;
+
+        ui.data_mut(|d| match selected {
+            Some(index) => d.insert_temp(selected_id, index),
+            None => d.remove::<usize>(selected_id),
+        });
+
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }
+}
+
+fn paint_gradient(
+    ui: &Ui,
+    rect: Rect,
+    markers_rect: Rect,
+    gradient: &Gradient,
+    selected: Option<usize>,
+) {
+    let painter = ui.painter();
+    let visuals = &ui.visuals().widgets.inactive;
+
+    background_checkers(painter, rect);
+
+    // A vertex pair at each stop, so that the colors are exactly those of the gradient:
+    let mut ts: Vec<f32> = gradient.stops.iter().map(|stop| stop.t).collect();
+    ts.insert(0, 0.0);
+    ts.push(1.0);
+
+    let mut mesh = Mesh::default();
+    for (i, &t) in ts.iter().enumerate() {
+        // At a stop, use its own color (there may be several stops at the same `t`):
+        let color = if 0 < i && i < ts.len() - 1 {
+            gradient.stops[i - 1].color
+        } else {
+            gradient.color_at(t)
+        };
+        let x = lerp(rect.x_range(), t);
+        mesh.colored_vertex(pos2(x, rect.top()), color);
+        mesh.colored_vertex(pos2(x, rect.bottom()), color);
+        if 0 < i {
+            let i = 2 * i as u32;
+            mesh.add_triangle(i - 2, i - 1, i);
+            mesh.add_triangle(i - 1, i, i + 1);
+        }
+    }
+    painter.add(Shape::mesh(mesh));
+    painter.rect_stroke(rect, 0.0, visuals.bg_stroke);
+
+    // Markers below the gradient, pointing at their stops:
+    let [top, bottom] = [markers_rect.top(), markers_rect.bottom()];
+    let r = markers_rect.height() / 3.0;
+    for (index, stop) in gradient.stops.iter().enumerate() {
+        let x = lerp(rect.x_range(), stop.t);
+        let stroke = if selected == Some(index) {
+            ui.visuals().selection.stroke
+        } else {
+            Stroke::new(visuals.fg_stroke.width, contrast_color(stop.color))
+        };
+        painter.add(Shape::convex_polygon(
+            vec![
+                pos2(x, top),         // tip
+                pos2(x + r, top + r), // right
+                pos2(x + r, bottom),  // right bottom
+                pos2(x - r, bottom),  // left bottom
+                pos2(x - r, top + r), // left
+            ],
+            stop.color.to_opaque(),
+            stroke,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorStop, Gradient};
+    use epaint::Color32;
+
+    #[test]
+    fn gradient_color_at() {
+        let gradient = Gradient::new(vec![
+            ColorStop {
+                t: 0.75,
+                color: Color32::WHITE,
+            },
+            ColorStop {
+                t: 0.25,
+                color: Color32::BLACK,
+            },
+        ]);
+
+        assert_eq!(
+            gradient.stops()[0].color,
+            Color32::BLACK,
+            "stops are sorted"
+        );
+        assert_eq!(gradient.color_at(0.0), Color32::BLACK);
+        assert_eq!(gradient.color_at(0.5), Color32::from_gray(128));
+        assert_eq!(gradient.color_at(1.0), Color32::WHITE);
+        assert_eq!(Gradient::new(vec![]).color_at(0.5), Color32::TRANSPARENT);
+    }
+
+    #[test]
+    fn gradient_add_and_remove_stops() {
+        let mut gradient = Gradient::default();
+        let red = Color32::RED;
+
+        assert_eq!(gradient.add_stop(0.5, red), 1);
+        assert_eq!(gradient.color_at(0.5), red);
+        assert_eq!(gradient.add_stop(2.0, red), 3, "clamped to the end");
+
+        assert_eq!(gradient.remove_stop(1).map(|stop| stop.color), Some(red));
+        assert_eq!(gradient.remove_stop(10), None);
+        assert_eq!(gradient.stops().len(), 3);
+    }
+}
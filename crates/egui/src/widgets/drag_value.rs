@@ -460,6 +460,11 @@ impl Widget for DragValue<'_> {
             ui.data_mut(|data| data.remove::<String>(id));
         }
 
+        let restored = crate::util::undo_stack::take_restored::<f64>(ui.ctx(), id);
+        if let Some(restored) = restored {
+            set(&mut get_set_value, restored);
+        }
+
         let old_value = get(&mut get_set_value);
         let mut value = old_value;
         let aim_rad = ui.input(|i| i.aim_radius() as f64);
@@ -556,6 +561,7 @@ impl Widget for DragValue<'_> {
                 .unwrap_or_else(|| value_text.clone());
             let response = ui.add(
                 TextEdit::singleline(&mut value_text)
+                    .undoable(false) // We record the parsed value instead
                     .clip_text(false)
                     .horizontal_align(ui.layout().horizontal_align())
                     .vertical_align(ui.layout().vertical_align())
@@ -660,10 +666,23 @@ impl Widget for DragValue<'_> {
             response
         };
 
-        if get(&mut get_set_value) != old_value {
+        let new_value = get(&mut get_set_value);
+        if new_value != old_value || restored.is_some() {
             response.mark_changed();
         }
 
+        if ui.is_enabled() {
+            let is_editing = ui.ctx().is_being_dragged(id) || ui.memory(|mem| mem.has_focus(id));
+            crate::util::undo_stack::track_edit(
+                ui.ctx(),
+                id,
+                "Change value",
+                is_editing,
+                &old_value,
+                &new_value,
+            );
+        }
+
         response.widget_info(|| WidgetInfo::drag_value(ui.is_enabled(), value));
 
         #[cfg(feature = "accesskit")]
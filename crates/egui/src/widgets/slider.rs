@@ -117,6 +117,7 @@ pub struct Slider<'a> {
     custom_parser: Option<NumParser<'a>>,
     trailing_fill: Option<bool>,
     handle_shape: Option<HandleShape>,
+    multi_touch: bool,
 }
 
 impl<'a> Slider<'a> {
@@ -167,6 +168,7 @@ impl<'a> Slider<'a> {
             custom_parser: None,
             trailing_fill: None,
             handle_shape: None,
+            multi_touch: false,
         }
     }
 
@@ -397,6 +399,19 @@ impl<'a> Slider<'a> {
         self
     }
 
+    /// Let each finger on a touch screen drag its own slider.
+    ///
+    /// Normally only the first finger acts as the pointer, so only one slider can be dragged at a time.
+    /// With this enabled, any touch that starts on this slider drags it until lifted,
+    /// independently of other touches. See [`Response::claim_touches`].
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn multi_touch(mut self, multi_touch: bool) -> Self {
+        self.multi_touch = multi_touch;
+        self
+    }
+
     /// Set custom formatter defining how numbers are converted into text.
     ///
     /// A custom formatter takes a `f64` for the numeric value and a `RangeInclusive<usize>` representing
@@ -654,14 +669,14 @@ impl Slider<'_> {
     }
 
     /// Just the slider, no text
-    fn slider_ui(&mut self, ui: &Ui, response: &Response) {
+    fn slider_ui(&mut self, ui: &Ui, response: &Response, touch_pos: Option<Pos2>) {
         let rect = &response.rect;
         let handle_shape = self
             .handle_shape
             .unwrap_or_else(|| ui.style().visuals.handle_shape);
         let position_range = self.position_range(rect, &handle_shape);
 
-        if let Some(pointer_position_2d) = response.interact_pointer_pos() {
+        if let Some(pointer_position_2d) = touch_pos.or_else(|| response.interact_pointer_pos()) {
             let position = self.pointer_position(pointer_position_2d);
             let new_value = if self.smart_aim {
                 let aim_radius = ui.input(|i| i.aim_radius());
@@ -943,12 +958,34 @@ impl Slider<'_> {
             .text_style_height(&TextStyle::Body)
             .at_least(ui.spacing().interact_size.y);
         let mut response = self.allocate_slider_space(ui, thickness);
-        self.slider_ui(ui, &response);
+
+        let restored = crate::util::undo_stack::take_restored::<f64>(ui.ctx(), response.id);
+        if let Some(restored) = restored {
+            self.set_value(restored);
+        }
+        let value_before_edit = self.get_value();
+
+        let touches = if self.multi_touch {
+            response.claim_touches()
+        } else {
+            vec![]
+        };
+        self.slider_ui(ui, &response, touches.last().map(|touch| touch.pos));
 
         let value = self.get_value();
         if value != old_value {
             response.mark_changed();
         }
+        if ui.is_enabled() {
+            crate::util::undo_stack::track_edit(
+                ui.ctx(),
+                response.id,
+                "Change value",
+                response.dragged() || response.has_focus() || !touches.is_empty(),
+                &value_before_edit,
+                &value,
+            );
+        }
         response.widget_info(|| WidgetInfo::slider(ui.is_enabled(), value, self.text.text()));
 
         #[cfg(feature = "accesskit")]
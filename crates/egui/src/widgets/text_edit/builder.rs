@@ -12,8 +12,9 @@ use crate::{
         text_cursor_state::cursor_rect, visuals::paint_text_selection, CCursorRange, CursorRange,
     },
     vec2, Align, Align2, Color32, Context, CursorIcon, Event, EventFilter, FontSelection, Id,
-    ImeEvent, Key, KeyboardShortcut, Margin, Modifiers, NumExt, Response, Sense, Shape, TextBuffer,
-    TextStyle, TextWrapMode, Ui, Vec2, Widget, WidgetInfo, WidgetText, WidgetWithState,
+    ImeEvent, Key, KeyboardShortcut, Margin, Modifiers, NumExt, Painter, Pos2, Response, Sense,
+    Shape, Stroke, TextBuffer, TextStyle, TextWrapMode, Ui, Vec2, Widget, WidgetInfo, WidgetText,
+    WidgetWithState,
 };
 
 use super::{TextEditOutput, TextEditState};
@@ -86,6 +87,8 @@ pub struct TextEdit<'t> {
     char_limit: usize,
     return_key: Option<KeyboardShortcut>,
     background_color: Option<Color32>,
+    autocomplete: Option<String>,
+    undoable: bool,
 }
 
 impl WidgetWithState for TextEdit<'_> {
@@ -145,6 +148,8 @@ impl<'t> TextEdit<'t> {
             char_limit: usize::MAX,
             return_key: Some(KeyboardShortcut::new(Modifiers::NONE, Key::Enter)),
             background_color: None,
+            autocomplete: None,
+            undoable: true,
         }
     }
 
@@ -226,6 +231,48 @@ impl<'t> TextEdit<'t> {
         self
     }
 
+    /// Let the browser autofill this field, e.g. with a saved username or password.
+    ///
+    /// `autocomplete` is an [HTML `autocomplete` value](https://developer.mozilla.org/en-US/docs/Web/HTML/Attributes/autocomplete),
+    /// like `"username"`, `"current-password"`, `"new-password"` or `"one-time-code"`.
+    /// Use [`Self::password`] for password fields.
+    ///
+    /// Only `eframe` web supports this: it mirrors the field as a hidden `<input>` that password managers can fill in.
+    /// Elsewhere this does nothing.
+    ///
+    /// When the user submits the fields (e.g. clicks "Log in"), send [`crate::OutputCommand::SubmitAutofill`]
+    /// so that password managers can offer to save what they typed:
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// # let (mut username, mut password) = (String::new(), String::new());
+    /// ui.add(egui::TextEdit::singleline(&mut username).autocomplete("username"));
+    /// ui.add(egui::TextEdit::singleline(&mut password).password(true).autocomplete("current-password"));
+    /// if ui.button("Log in").clicked() {
+    ///     ui.ctx().send_cmd(egui::OutputCommand::SubmitAutofill);
+    /// }
+    /// # });
+    /// ```
+    #[inline]
+    pub fn autocomplete(mut self, autocomplete: impl Into<String>) -> Self {
+        self.autocomplete = Some(autocomplete.into());
+        self
+    }
+
+    /// Record each editing session (from gaining to losing keyboard focus) as one step
+    /// on the [`crate::util::undo_stack::UndoStack`], so that it can be undone with
+    /// [`crate::Context::undo`] (or `Cmd+Z`, see [`crate::Options::undo_with_keyboard`])
+    /// once the text field no longer has focus.
+    ///
+    /// While the text field has focus, `Cmd+Z` undoes the individual edits, regardless of this setting.
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn undoable(mut self, undoable: bool) -> Self {
+        self.undoable = undoable;
+        self
+    }
+
     /// Pick a [`crate::FontId`] or [`TextStyle`].
     #[inline]
     pub fn font(mut self, font_selection: impl Into<FontSelection>) -> Self {
@@ -491,6 +538,8 @@ impl TextEdit<'_> {
             char_limit,
             return_key,
             background_color: _,
+            autocomplete,
+            undoable,
         } = self;
 
         let text_color = text_color
@@ -567,6 +616,38 @@ impl TextEdit<'_> {
 
         // Don't sent `OutputEvent::Clicked` when a user presses the space bar
         response.flags -= response::Flags::FAKE_PRIMARY_CLICKED;
+
+        let autocomplete = autocomplete.filter(|_| interactive && text.is_mutable());
+        if autocomplete.is_some() {
+            let autofilled = ui.input(|i| {
+                i.events.iter().rev().find_map(|event| match event {
+                    Event::Autofill {
+                        id: autofill_id,
+                        text,
+                    } if *autofill_id == id => Some(text.clone()),
+                    _ => None,
+                })
+            });
+            if let Some(autofilled) = autofilled {
+                text.replace_with(&autofilled);
+                galley = layouter(ui, text.as_str(), wrap_width);
+                response.mark_changed();
+            }
+        }
+
+        // An undo or redo on the `UndoStack`:
+        let restored = if undoable && text.is_mutable() {
+            crate::util::undo_stack::take_restored::<String>(ui.ctx(), id)
+        } else {
+            None
+        };
+        if let Some(restored) = &restored {
+            text.replace_with(restored);
+            galley = layouter(ui, text.as_str(), wrap_width);
+            state.clear_undoer();
+            response.mark_changed();
+        }
+
         let text_clip_rect = rect;
         let painter = ui.painter_at(text_clip_rect.expand(1.0)); // expand to avoid clipping cursor
 
@@ -606,6 +687,27 @@ impl TextEdit<'_> {
 
                     state.last_interaction_time = ui.ctx().input(|i| i.time);
                 }
+
+                // Middle-click paste of the primary selection, at the click position:
+                if response.hovered() && text.is_mutable() {
+                    let primary_selection = ui.input(|i| {
+                        i.events.iter().find_map(|event| match event {
+                            Event::PastePrimarySelection(text) => Some(text.clone()),
+                            _ => None,
+                        })
+                    });
+                    if let Some(primary_selection) = primary_selection {
+                        ui.memory_mut(|mem| mem.request_focus(response.id));
+                        let mut ccursor = cursor_at_pointer.ccursor;
+                        text.insert_text_at(&mut ccursor, &primary_selection, char_limit);
+                        state
+                            .cursor
+                            .set_char_range(Some(CCursorRange::one(ccursor)));
+                        state.last_interaction_time = ui.ctx().input(|i| i.time);
+                        galley = layouter(ui, text.as_str(), wrap_width);
+                        response.mark_changed();
+                    }
+                }
             }
         }
 
@@ -688,6 +790,19 @@ impl TextEdit<'_> {
             false
         };
 
+        // Offer the selection for middle-click paste once the user is done selecting:
+        let selection_done = (selection_changed && !ui.ctx().is_being_dragged(id))
+            || (response.drag_stopped() && cursor_range.is_some());
+        if selection_done && !password {
+            if let Some(cursor_range) = cursor_range {
+                if !cursor_range.is_empty() {
+                    let selected = cursor_range.slice_str(text.as_str()).to_owned();
+                    ui.ctx()
+                        .send_cmd(crate::OutputCommand::SetPrimarySelection(selected));
+                }
+            }
+        }
+
         if ui.is_rect_visible(rect) {
             if text.as_str().is_empty() && !hint_text.is_empty() {
                 let hint_text_color = ui.visuals().weak_text_color();
@@ -736,6 +851,9 @@ impl TextEdit<'_> {
             painter.galley(galley_pos, galley.clone(), text_color);
 
             if has_focus {
+                if let Some(preedit) = state.ime_preedit {
+                    paint_ime_preedit(&painter, galley_pos, &galley, preedit, text_color);
+                }
                 if let Some(cursor_range) = state.cursor.range(&galley) {
                     let primary_cursor_rect =
                         cursor_rect(galley_pos, &galley, &cursor_range.primary, row_height);
@@ -785,7 +903,15 @@ impl TextEdit<'_> {
         // Ensures correct IME behavior when the text input area gains or loses focus.
         if state.ime_enabled && (response.gained_focus() || response.lost_focus()) {
             state.ime_enabled = false;
-            if let Some(mut ccursor_range) = state.cursor.char_range() {
+            if let Some(preedit) = state.ime_preedit.take() {
+                // The composition was never committed, so it must not stay in the text:
+                let ccursor = text.delete_selected_ccursor_range(preedit.sorted());
+                state
+                    .cursor
+                    .set_char_range(Some(CCursorRange::one(ccursor)));
+                response.mark_changed();
+                ui.ctx().request_repaint();
+            } else if let Some(mut ccursor_range) = state.cursor.char_range() {
                 ccursor_range.secondary.index = ccursor_range.primary.index;
                 state.cursor.set_char_range(Some(ccursor_range));
             }
@@ -794,6 +920,28 @@ impl TextEdit<'_> {
 
         state.clone().store(ui.ctx(), id);
 
+        if undoable && interactive && text.is_mutable() && restored.is_none() {
+            crate::util::undo_stack::track_edit(
+                ui.ctx(),
+                id,
+                "Edit text",
+                ui.memory(|mem| mem.has_focus(id)),
+                prev_text.as_str(),
+                text.as_str(),
+            );
+        }
+
+        if let Some(autocomplete) = autocomplete {
+            ui.output_mut(|o| {
+                o.autofill_fields.push(crate::output::AutofillField {
+                    id,
+                    autocomplete,
+                    password,
+                    text: text.as_str().to_owned(),
+                });
+            });
+        }
+
         if response.changed() {
             response.widget_info(|| {
                 WidgetInfo::text_edit(
@@ -1041,7 +1189,7 @@ fn events(
             Event::Ime(ime_event) => match ime_event {
                 ImeEvent::Enabled => {
                     state.ime_enabled = true;
-                    state.ime_cursor_range = cursor_range;
+                    state.ime_preedit = None;
                     None
                 }
                 ImeEvent::Preedit(text_mark) => {
@@ -1050,13 +1198,14 @@ fn events(
                     } else {
                         // Empty prediction can be produced when user press backspace
                         // or escape during IME, so we clear current text.
-                        let mut ccursor = text.delete_selected(&cursor_range);
-                        let start_cursor = ccursor;
+                        let replaced = take_ime_preedit(state, &cursor_range);
+                        let start_ccursor = text.delete_selected_ccursor_range(replaced);
+                        let mut ccursor = start_ccursor;
                         if !text_mark.is_empty() {
                             text.insert_text_at(&mut ccursor, text_mark, char_limit);
+                            state.ime_preedit = Some(CCursorRange::two(start_ccursor, ccursor));
                         }
-                        state.ime_cursor_range = cursor_range;
-                        Some(CCursorRange::two(start_cursor, ccursor))
+                        Some(CCursorRange::one(ccursor))
                     }
                 }
                 ImeEvent::Commit(prediction) => {
@@ -1065,21 +1214,15 @@ fn events(
                     } else {
                         state.ime_enabled = false;
 
-                        if !prediction.is_empty()
-                            && cursor_range.secondary.ccursor.index
-                                == state.ime_cursor_range.secondary.ccursor.index
-                        {
-                            let mut ccursor = text.delete_selected(&cursor_range);
-                            text.insert_text_at(&mut ccursor, prediction, char_limit);
-                            Some(CCursorRange::one(ccursor))
-                        } else {
-                            let ccursor = cursor_range.primary.ccursor;
-                            Some(CCursorRange::one(ccursor))
-                        }
+                        let replaced = take_ime_preedit(state, &cursor_range);
+                        let mut ccursor = text.delete_selected_ccursor_range(replaced);
+                        text.insert_text_at(&mut ccursor, prediction, char_limit);
+                        Some(CCursorRange::one(ccursor))
                     }
                 }
                 ImeEvent::Disabled => {
                     state.ime_enabled = false;
+                    state.ime_preedit = None;
                     None
                 }
             },
@@ -1113,6 +1256,51 @@ fn events(
 
 // ----------------------------------------------------------------------------
 
+/// The characters to replace with the next IME preedit or commit:
+/// the current preedit, or the selection if there is none.
+fn take_ime_preedit(state: &mut TextEditState, cursor_range: &CursorRange) -> [CCursor; 2] {
+    // If the cursor has moved away from the preedit, it is no longer ours to replace:
+    let preedit = state.ime_preedit.take().filter(|preedit| {
+        cursor_range.is_empty() && cursor_range.primary.ccursor.index == preedit.primary.index
+    });
+    preedit.map_or_else(
+        || cursor_range.as_ccursor_range().sorted(),
+        |preedit| preedit.sorted(),
+    )
+}
+
+/// Underline the IME preedit, to show that it is not committed yet.
+fn paint_ime_preedit(
+    painter: &Painter,
+    galley_pos: Pos2,
+    galley: &Galley,
+    preedit: CCursorRange,
+    color: Color32,
+) {
+    let [min, max] = preedit
+        .sorted()
+        .map(|ccursor| galley.from_ccursor(ccursor).rcursor);
+
+    for ri in min.row..=max.row {
+        let row = &galley.rows[ri];
+        let left = if ri == min.row {
+            row.x_offset(min.column)
+        } else {
+            row.rect.left()
+        };
+        let right = if ri == max.row {
+            row.x_offset(max.column)
+        } else {
+            row.rect.right()
+        };
+        painter.hline(
+            galley_pos.x + left..=galley_pos.x + right,
+            galley_pos.y + row.max_y() - 0.5,
+            Stroke::new(1.0, color),
+        );
+    }
+}
+
 fn remove_ime_incompatible_events(events: &mut Vec<Event>) {
     // Remove key events which cause problems while 'IME' is being used.
     // See https://github.com/emilk/egui/pull/4509
@@ -45,9 +45,9 @@ pub struct TextEditState {
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) ime_enabled: bool,
 
-    // cursor range for IME candidate.
+    // The characters of the IME composition (preedit) that are not yet committed.
     #[cfg_attr(feature = "serde", serde(skip))]
-    pub(crate) ime_cursor_range: CursorRange,
+    pub(crate) ime_preedit: Option<CCursorRange>,
 
     // Visual offset when editing singleline text bigger than the width.
     #[cfg_attr(feature = "serde", serde(skip))]
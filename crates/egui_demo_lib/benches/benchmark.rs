@@ -49,6 +49,35 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         });
     }
 
+    {
+        // What a high polling-rate mouse does to a frame,
+        // with and without coalescing the pointer moves (as `egui-winit` does by default).
+        let ctx = egui::Context::default();
+        let mut demo_windows = egui_demo_lib::DemoWindows::default();
+
+        let pointer_moves = |n: usize| RawInput {
+            events: (0..n)
+                .map(|i| egui::Event::PointerMoved(egui::pos2(100.0 + i as f32 * 0.1, 100.0)))
+                .collect(),
+            ..Default::default()
+        };
+
+        c.bench_function("demo_500_pointer_moves", |b| {
+            b.iter(|| {
+                ctx.run(pointer_moves(500), |ctx| {
+                    demo_windows.ui(ctx);
+                })
+            });
+        });
+        c.bench_function("demo_1_pointer_move", |b| {
+            b.iter(|| {
+                ctx.run(pointer_moves(1), |ctx| {
+                    demo_windows.ui(ctx);
+                })
+            });
+        });
+    }
+
     {
         let ctx = egui::Context::default();
         let _ = ctx.run(RawInput::default(), |ctx| {
@@ -69,7 +69,7 @@ impl crate::View for DragAndDropDemo {
                             row: row_idx,
                         };
                         let response = ui
-                            .dnd_drag_source(item_id, item_location, |ui| {
+                            .drag_source(item_id, item_location, |ui| {
                                 ui.label(item);
                             })
                             .response;
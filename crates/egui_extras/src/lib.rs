@@ -21,6 +21,10 @@ mod loaders;
 mod sizing;
 mod strip;
 mod table;
+#[cfg(all(any(feature = "ron", feature = "toml"), not(target_arch = "wasm32")))]
+mod theme_loader;
+#[cfg(feature = "video")]
+mod video;
 
 #[cfg(feature = "chrono")]
 pub use crate::datepicker::DatePickerButton;
@@ -32,8 +36,15 @@ pub(crate) use crate::layout::StripLayout;
 pub use crate::sizing::Size;
 pub use crate::strip::*;
 pub use crate::table::*;
+#[cfg(all(any(feature = "ron", feature = "toml"), not(target_arch = "wasm32")))]
+pub use crate::theme_loader::ThemeLoader;
 
-pub use loaders::install_image_loaders;
+#[cfg(all(feature = "video", target_arch = "wasm32"))]
+pub use crate::video::WebVideo;
+#[cfg(feature = "video")]
+pub use crate::video::{ImageSequence, Video, VideoBackend};
+
+pub use loaders::{install_image_loaders, install_image_loaders_with_options, ImageLoaderOptions};
 
 // ---------------------------------------------------------------------------
 
@@ -1,5 +1,3 @@
-// TODO(jprochazk): automatic cache eviction
-
 /// Installs a set of image loaders.
 ///
 /// Calling this enables the use of [`egui::Image`] and [`egui::Ui::image`].
@@ -54,8 +52,59 @@
 /// The content type specified by [`BytesPoll::Ready::mime`][`egui::load::BytesPoll::Ready::mime`] always takes precedence,
 /// and must include `svg` for it to be considered supported. For example, `image/svg+xml` would be loaded by the `svg` loader.
 ///
+/// The `http` and `image` loaders evict the least recently used entries from their caches
+/// once they grow beyond [`ImageLoaderOptions::cache_budget`].
+/// Loads that are no longer requested by any widget are cancelled.
+/// They do this at the end of each pass of the root viewport, using [`egui::Context::on_end_pass`].
+/// Use [`install_image_loaders_with_options`] to change this.
+///
 /// See [`egui::load`] for more information about how loaders work.
 pub fn install_image_loaders(ctx: &egui::Context) {
+    install_image_loaders_with_options(ctx, &ImageLoaderOptions::default());
+}
+
+/// Options for [`install_image_loaders_with_options`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageLoaderOptions {
+    /// How many bytes each of the `http` and `image` loaders may keep in its cache.
+    ///
+    /// Once a cache grows beyond this, the least recently used entries are evicted,
+    /// except for those used in the current pass.
+    /// The textures of evicted images are freed as soon as the images are no longer shown.
+    /// Evicted images are loaded again if they are shown again.
+    ///
+    /// `None` means no limit.
+    ///
+    /// Default: 256 MiB.
+    pub cache_budget: Option<usize>,
+
+    /// Decode images on a background thread instead of blocking the UI thread.
+    ///
+    /// While an image is decoding, [`egui::Image`] shows a spinner,
+    /// using the size from the image header if it is known.
+    ///
+    /// When off, images show up in the first frame they are requested in,
+    /// which is what most tests want.
+    ///
+    /// Ignored on the web, where images are always decoded on the UI thread.
+    ///
+    /// Default: `false`.
+    pub decode_in_background: bool,
+}
+
+impl Default for ImageLoaderOptions {
+    fn default() -> Self {
+        Self {
+            cache_budget: Some(256 * 1024 * 1024),
+            decode_in_background: false,
+        }
+    }
+}
+
+/// Like [`install_image_loaders`], but with the given options.
+///
+/// The options only affect loaders that are not installed yet.
+pub fn install_image_loaders_with_options(ctx: &egui::Context, options: &ImageLoaderOptions) {
     #[cfg(all(not(target_arch = "wasm32"), feature = "file"))]
     if !ctx.is_loader_installed(self::file_loader::FileLoader::ID) {
         ctx.add_bytes_loader(std::sync::Arc::new(self::file_loader::FileLoader::default()));
@@ -64,17 +113,31 @@ pub fn install_image_loaders(ctx: &egui::Context) {
 
     #[cfg(feature = "http")]
     if !ctx.is_loader_installed(self::ehttp_loader::EhttpLoader::ID) {
-        ctx.add_bytes_loader(std::sync::Arc::new(
-            self::ehttp_loader::EhttpLoader::default(),
-        ));
+        let loader = std::sync::Arc::new(self::ehttp_loader::EhttpLoader::new(options));
+        ctx.add_bytes_loader(loader.clone());
+        ctx.on_end_pass(
+            "egui_extras::EhttpLoader",
+            std::sync::Arc::new(move |ctx| {
+                if ctx.viewport_id() == egui::ViewportId::ROOT {
+                    egui::load::BytesLoader::end_pass(&*loader, ctx.cumulative_pass_nr() as usize);
+                }
+            }),
+        );
         log::trace!("installed EhttpLoader");
     }
 
     #[cfg(feature = "image")]
     if !ctx.is_loader_installed(self::image_loader::ImageCrateLoader::ID) {
-        ctx.add_image_loader(std::sync::Arc::new(
-            self::image_loader::ImageCrateLoader::default(),
-        ));
+        let loader = std::sync::Arc::new(self::image_loader::ImageCrateLoader::new(options));
+        ctx.add_image_loader(loader.clone());
+        ctx.on_end_pass(
+            "egui_extras::ImageCrateLoader",
+            std::sync::Arc::new(move |ctx| {
+                if ctx.viewport_id() == egui::ViewportId::ROOT {
+                    loader.end_pass_and_free_textures(ctx, ctx.cumulative_pass_nr() as usize);
+                }
+            }),
+        );
         log::trace!("installed ImageCrateLoader");
     }
 
@@ -104,7 +167,47 @@ pub fn install_image_loaders(ctx: &egui::Context) {
     ))]
     log::warn!("`install_image_loaders` was called, but no loaders are enabled");
 
-    let _ = ctx;
+    let _ = (ctx, options);
+}
+
+/// Remove the least recently used entries of a loader cache,
+/// until the remaining entries take up at most `budget` bytes.
+///
+/// Entries that were used in `current_pass`, or take up no space (e.g. pending loads), are kept.
+///
+/// Returns the URIs of the evicted entries.
+#[cfg(any(feature = "http", feature = "image"))]
+fn evict_least_recently_used<V>(
+    cache: &mut ahash::HashMap<String, V>,
+    budget: usize,
+    current_pass: usize,
+    last_used: impl Fn(&V) -> usize,
+    byte_size: impl Fn(&V) -> usize,
+) -> Vec<String> {
+    let mut total: usize = cache.values().map(&byte_size).sum();
+    if total <= budget {
+        return Vec::new();
+    }
+
+    let mut candidates: Vec<(usize, String)> = cache
+        .iter()
+        .filter(|(_, entry)| last_used(entry) < current_pass && 0 < byte_size(entry))
+        .map(|(uri, entry)| (last_used(entry), uri.clone()))
+        .collect();
+    candidates.sort_unstable();
+
+    let mut evicted = Vec::new();
+    for (_, uri) in candidates {
+        if total <= budget {
+            break;
+        }
+        if let Some(entry) = cache.remove(&uri) {
+            total -= byte_size(&entry);
+            log::trace!("evicted {uri:?} from the cache");
+            evicted.push(uri);
+        }
+    }
+    evicted
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -121,3 +224,38 @@ mod image_loader;
 mod svg_loader;
 #[cfg(feature = "webp")]
 mod webp_loader;
+
+#[cfg(all(test, any(feature = "http", feature = "image")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_first() {
+        // (last used, size)
+        let mut cache: ahash::HashMap<String, (usize, usize)> = [
+            ("old".to_owned(), (1, 10)),
+            ("older".to_owned(), (0, 10)),
+            ("pending".to_owned(), (0, 0)),
+            ("current".to_owned(), (5, 100)),
+            ("recent".to_owned(), (4, 10)),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut evicted = evict_least_recently_used(&mut cache, 115, 5, |e| e.0, |e| e.1);
+        evicted.sort_unstable();
+        assert_eq!(evicted, ["old", "older"]);
+        let mut kept: Vec<&str> = cache.keys().map(String::as_str).collect();
+        kept.sort_unstable();
+        assert_eq!(kept, ["current", "pending", "recent"]);
+
+        // Whatever is used in the current pass stays, even if over budget:
+        assert_eq!(
+            evict_least_recently_used(&mut cache, 0, 5, |e| e.0, |e| e.1),
+            ["recent"]
+        );
+        let mut kept: Vec<&str> = cache.keys().map(String::as_str).collect();
+        kept.sort_unstable();
+        assert_eq!(kept, ["current", "pending"]);
+    }
+}
@@ -2,9 +2,12 @@ use ahash::HashMap;
 use egui::{
     load::{Bytes, BytesLoadResult, BytesLoader, BytesPoll, LoadError},
     mutex::Mutex,
+    ViewportId,
 };
 use std::{sync::Arc, task::Poll};
 
+use super::ImageLoaderOptions;
+
 #[derive(Clone)]
 struct File {
     bytes: Arc<[u8]>,
@@ -37,15 +40,43 @@ impl File {
     }
 }
 
-type Entry = Poll<Result<File, String>>;
+struct Entry {
+    state: Poll<Result<File, String>>,
+
+    /// The last pass (of the root viewport) in which the file was requested.
+    last_used: usize,
+}
+
+impl Entry {
+    fn byte_size(&self) -> usize {
+        match &self.state {
+            Poll::Ready(Ok(file)) => file.bytes.len() + file.mime.as_ref().map_or(0, |m| m.len()),
+            Poll::Ready(Err(err)) => err.len(),
+            Poll::Pending => 0,
+        }
+    }
+}
 
-#[derive(Default)]
 pub struct EhttpLoader {
     cache: Arc<Mutex<HashMap<String, Entry>>>,
+    cache_budget: Option<usize>,
+}
+
+impl Default for EhttpLoader {
+    fn default() -> Self {
+        Self::new(&ImageLoaderOptions::default())
+    }
 }
 
 impl EhttpLoader {
     pub const ID: &'static str = egui::generate_loader_id!(EhttpLoader);
+
+    pub fn new(options: &ImageLoaderOptions) -> Self {
+        Self {
+            cache: Default::default(),
+            cache_budget: options.cache_budget,
+        }
+    }
 }
 
 const PROTOCOLS: &[&str] = &["http://", "https://"];
@@ -64,22 +95,31 @@ impl BytesLoader for EhttpLoader {
             return Err(LoadError::NotSupported);
         }
 
+        let pass_index = ctx.cumulative_pass_nr_for(ViewportId::ROOT) as usize;
+
         let mut cache = self.cache.lock();
-        if let Some(entry) = cache.get(uri).cloned() {
-            match entry {
+        if let Some(entry) = cache.get_mut(uri) {
+            entry.last_used = pass_index;
+            match &entry.state {
                 Poll::Ready(Ok(file)) => Ok(BytesPoll::Ready {
                     size: None,
-                    bytes: Bytes::Shared(file.bytes),
-                    mime: file.mime,
+                    bytes: Bytes::Shared(file.bytes.clone()),
+                    mime: file.mime.clone(),
                 }),
-                Poll::Ready(Err(err)) => Err(LoadError::Loading(err)),
+                Poll::Ready(Err(err)) => Err(LoadError::Loading(err.clone())),
                 Poll::Pending => Ok(BytesPoll::Pending { size: None }),
             }
         } else {
             log::trace!("started loading {uri:?}");
 
             let uri = uri.to_owned();
-            cache.insert(uri.clone(), Poll::Pending);
+            cache.insert(
+                uri.clone(),
+                Entry {
+                    state: Poll::Pending,
+                    last_used: pass_index,
+                },
+            );
             drop(cache);
 
             ehttp::fetch(ehttp::Request::get(uri.clone()), {
@@ -95,8 +135,14 @@ impl BytesLoader for EhttpLoader {
                         }
                     };
                     log::trace!("finished loading {uri:?}");
-                    cache.lock().insert(uri, Poll::Ready(result));
-                    ctx.request_repaint();
+
+                    // The request may have been forgotten or cancelled meanwhile:
+                    if let Some(entry) = cache.lock().get_mut(&uri) {
+                        if entry.state.is_pending() {
+                            entry.state = Poll::Ready(result);
+                            ctx.request_repaint();
+                        }
+                    }
                 }
             });
 
@@ -112,17 +158,31 @@ impl BytesLoader for EhttpLoader {
         self.cache.lock().clear();
     }
 
+    fn end_pass(&self, pass_index: usize) {
+        let mut cache = self.cache.lock();
+
+        // Drop the requests that nobody asked for in this or the previous pass.
+        // We can't abort them, but we ignore their responses.
+        cache.retain(|uri, entry| {
+            let wanted = entry.state.is_ready() || pass_index <= entry.last_used + 1;
+            if !wanted {
+                log::trace!("cancelling loading {uri:?}");
+            }
+            wanted
+        });
+
+        if let Some(budget) = self.cache_budget {
+            super::evict_least_recently_used(
+                &mut cache,
+                budget,
+                pass_index,
+                |entry| entry.last_used,
+                Entry::byte_size,
+            );
+        }
+    }
+
     fn byte_size(&self) -> usize {
-        self.cache
-            .lock()
-            .values()
-            .map(|entry| match entry {
-                Poll::Ready(Ok(file)) => {
-                    file.bytes.len() + file.mime.as_ref().map_or(0, |m| m.len())
-                }
-                Poll::Ready(Err(err)) => err.len(),
-                _ => 0,
-            })
-            .sum()
+        self.cache.lock().values().map(Entry::byte_size).sum()
     }
 }
@@ -1,21 +1,234 @@
-use ahash::HashMap;
+use ahash::{HashMap, HashSet};
 use egui::{
-    load::{BytesPoll, ImageLoadResult, ImageLoader, ImagePoll, LoadError, SizeHint},
+    load::{
+        BytesPoll, ImageLoadResult, ImageLoader, ImagePoll, LoadError, SizeHint, TextureLoader,
+    },
     mutex::Mutex,
-    ColorImage,
+    ColorImage, ViewportId,
 };
 use image::ImageFormat;
-use std::{mem::size_of, path::Path, sync::Arc};
+use std::{
+    mem::size_of,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use super::ImageLoaderOptions;
+
+type ImageResult = Result<Arc<ColorImage>, LoadError>;
+
+enum State {
+    /// Being decoded on the background thread.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))] // We don't have one on the web
+    Pending {
+        /// Set when nobody wants the image anymore.
+        cancelled: Arc<AtomicBool>,
+
+        /// From the image header, if we could read it.
+        size: Option<egui::Vec2>,
+    },
+    Ready(ImageResult),
+}
 
-type Entry = Result<Arc<ColorImage>, LoadError>;
+struct Entry {
+    state: State,
+
+    /// The last pass (of the root viewport) in which the image was requested.
+    last_used: usize,
+}
+
+impl Entry {
+    fn byte_size(&self) -> usize {
+        match &self.state {
+            State::Pending { .. } => 0,
+            State::Ready(Ok(image)) => image.pixels.len() * size_of::<egui::Color32>(),
+            State::Ready(Err(err)) => err.byte_size(),
+        }
+    }
+}
+
+type Cache = Arc<Mutex<HashMap<String, Entry>>>;
+
+/// An image to decode on the background thread.
+#[cfg(not(target_arch = "wasm32"))]
+struct Job {
+    ctx: egui::Context,
+    cache: Cache,
+    uri: String,
+    bytes: egui::load::Bytes,
+    cancelled: Arc<AtomicBool>,
+}
 
-#[derive(Default)]
 pub struct ImageCrateLoader {
-    cache: Mutex<HashMap<String, Entry>>,
+    cache: Cache,
+    cache_budget: Option<usize>,
+
+    /// Evicted images whose textures we still need to free, once they are no longer shown.
+    evicted_textures: Mutex<HashSet<String>>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    decode_in_background: bool,
+
+    /// Sends [`Job`]s to the background thread, which is spawned on first use.
+    #[cfg(not(target_arch = "wasm32"))]
+    jobs: Mutex<Option<std::sync::mpsc::Sender<Job>>>,
+}
+
+impl Default for ImageCrateLoader {
+    fn default() -> Self {
+        Self::new(&ImageLoaderOptions::default())
+    }
 }
 
 impl ImageCrateLoader {
     pub const ID: &'static str = egui::generate_loader_id!(ImageCrateLoader);
+
+    pub fn new(options: &ImageLoaderOptions) -> Self {
+        Self {
+            cache: Default::default(),
+            cache_budget: options.cache_budget,
+            evicted_textures: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            decode_in_background: options.decode_in_background,
+            #[cfg(not(target_arch = "wasm32"))]
+            jobs: Default::default(),
+        }
+    }
+
+    /// Cancel unwanted loads and evict images beyond the cache budget.
+    ///
+    /// Returns the URIs of the evicted images.
+    fn evict(&self, pass_index: usize) -> Vec<String> {
+        let mut cache = self.cache.lock();
+
+        // Cancel the decoding of images that nobody asked for in this or the previous pass:
+        cache.retain(|uri, entry| match &entry.state {
+            State::Pending { cancelled, .. } if entry.last_used + 1 < pass_index => {
+                log::trace!("cancelling loading {uri:?}");
+                cancelled.store(true, Ordering::Relaxed);
+                false
+            }
+            _ => true,
+        });
+
+        if let Some(budget) = self.cache_budget {
+            super::evict_least_recently_used(
+                &mut cache,
+                budget,
+                pass_index,
+                |entry| entry.last_used,
+                Entry::byte_size,
+            )
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Like [`ImageLoader::end_pass`], but also free the textures of evicted images
+    /// once they are no longer shown.
+    pub fn end_pass_and_free_textures(&self, ctx: &egui::Context, pass_index: usize) {
+        let evicted = self.evict(pass_index);
+        let mut evicted_textures = self.evicted_textures.lock();
+        evicted_textures.extend(evicted);
+        if !evicted_textures.is_empty() {
+            let texture_loaders = ctx.loaders().texture.lock().clone();
+            free_unused_textures(&texture_loaders, &mut evicted_textures, pass_index);
+        }
+    }
+
+    /// Decode on the background thread.
+    ///
+    /// Returns the job back if the thread could not be spawned.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn send_job(&self, job: Job) -> Result<(), Job> {
+        let mut jobs = self.jobs.lock();
+        if jobs.is_none() {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let spawned = std::thread::Builder::new()
+                .name("egui_extras image decoder".to_owned())
+                .spawn(move || decode_jobs(&rx));
+            if let Err(err) = spawned {
+                log::warn!("Failed to spawn image decoding thread: {err}");
+                return Err(job);
+            }
+            *jobs = Some(tx);
+        }
+        let tx = jobs.as_ref().expect("just set");
+        tx.send(job).map_err(|err| err.0)
+    }
+}
+
+/// Free the textures of `uris` that were not used in `pass_index`.
+///
+/// The `uris` whose textures are still used are kept, so we can try again later.
+fn free_unused_textures(
+    texture_loaders: &[Arc<dyn TextureLoader + Send + Sync>],
+    uris: &mut HashSet<String>,
+    pass_index: usize,
+) {
+    uris.retain(|uri| {
+        let last_used = texture_loaders
+            .iter()
+            .filter_map(|loader| loader.last_used(uri))
+            .max();
+        match last_used {
+            Some(last_used) if pass_index <= last_used => true, // Still shown
+            Some(_) => {
+                log::trace!("freeing the texture of {uri:?}");
+                for loader in texture_loaders {
+                    loader.forget(uri);
+                }
+                false
+            }
+            None => false, // No texture
+        }
+    });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn decode_jobs(jobs: &std::sync::mpsc::Receiver<Job>) {
+    while let Ok(job) = jobs.recv() {
+        let Job {
+            ctx,
+            cache,
+            uri,
+            bytes,
+            cancelled,
+        } = job;
+
+        if cancelled.load(Ordering::Relaxed) {
+            log::trace!("cancelled loading {uri:?}");
+            continue;
+        }
+
+        log::trace!("started loading {uri:?}");
+        let result = crate::image::load_image_bytes(&bytes).map(Arc::new);
+        log::trace!("finished loading {uri:?}");
+
+        // Only fill in the entry we were decoding for; it may have been forgotten or cancelled meanwhile:
+        let mut cache = cache.lock();
+        if let Some(entry) = cache.get_mut(&uri) {
+            if matches!(&entry.state, State::Pending { cancelled: ours, .. } if Arc::ptr_eq(ours, &cancelled))
+            {
+                entry.state = State::Ready(result);
+                ctx.request_repaint();
+            }
+        }
+    }
+}
+
+/// The size of the image, read from its header.
+#[cfg(not(target_arch = "wasm32"))]
+fn image_size(bytes: &[u8]) -> Option<egui::Vec2> {
+    let (width, height) = image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()?;
+    Some(egui::vec2(width as f32, height as f32))
 }
 
 fn is_supported_uri(uri: &str) -> bool {
@@ -63,11 +276,17 @@ impl ImageLoader for ImageCrateLoader {
             return Err(LoadError::NotSupported);
         }
 
+        let pass_index = ctx.cumulative_pass_nr_for(ViewportId::ROOT) as usize;
+
         let mut cache = self.cache.lock();
-        if let Some(entry) = cache.get(uri).cloned() {
-            match entry {
-                Ok(image) => Ok(ImagePoll::Ready { image }),
-                Err(err) => Err(err),
+        if let Some(entry) = cache.get_mut(uri) {
+            entry.last_used = pass_index;
+            match &entry.state {
+                State::Pending { size, .. } => Ok(ImagePoll::Pending { size: *size }),
+                State::Ready(Ok(image)) => Ok(ImagePoll::Ready {
+                    image: image.clone(),
+                }),
+                State::Ready(Err(err)) => Err(err.clone()),
             }
         } else {
             match ctx.try_load_bytes(uri) {
@@ -88,10 +307,50 @@ impl ImageLoader for ImageCrateLoader {
                     }
 
                     // (3)
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let bytes = if self.decode_in_background {
+                        let size = image_size(&bytes);
+                        let cancelled = Arc::new(AtomicBool::new(false));
+                        cache.insert(
+                            uri.to_owned(),
+                            Entry {
+                                state: State::Pending {
+                                    cancelled: cancelled.clone(),
+                                    size,
+                                },
+                                last_used: pass_index,
+                            },
+                        );
+                        drop(cache);
+
+                        let job = Job {
+                            ctx: ctx.clone(),
+                            cache: self.cache.clone(),
+                            uri: uri.to_owned(),
+                            bytes,
+                            cancelled,
+                        };
+                        match self.send_job(job) {
+                            Ok(()) => return Ok(ImagePoll::Pending { size }),
+                            Err(job) => {
+                                cache = self.cache.lock();
+                                job.bytes
+                            }
+                        }
+                    } else {
+                        bytes
+                    };
+
                     log::trace!("started loading {uri:?}");
                     let result = crate::image::load_image_bytes(&bytes).map(Arc::new);
                     log::trace!("finished loading {uri:?}");
-                    cache.insert(uri.into(), result.clone());
+                    cache.insert(
+                        uri.into(),
+                        Entry {
+                            state: State::Ready(result.clone()),
+                            last_used: pass_index,
+                        },
+                    );
                     result.map(|image| ImagePoll::Ready { image })
                 }
                 Ok(BytesPoll::Pending { size }) => Ok(ImagePoll::Pending { size }),
@@ -108,15 +367,12 @@ impl ImageLoader for ImageCrateLoader {
         self.cache.lock().clear();
     }
 
+    fn end_pass(&self, pass_index: usize) {
+        self.evict(pass_index);
+    }
+
     fn byte_size(&self) -> usize {
-        self.cache
-            .lock()
-            .values()
-            .map(|result| match result {
-                Ok(image) => image.pixels.len() * size_of::<egui::Color32>(),
-                Err(err) => err.byte_size(),
-            })
-            .sum()
+        self.cache.lock().values().map(Entry::byte_size).sum()
     }
 }
 
@@ -132,4 +388,63 @@ mod tests {
         assert!(is_supported_uri("file://test"));
         assert!(!is_supported_uri("test.svg"));
     }
+
+    /// Pretends to have a texture for each URI, used in the given pass.
+    struct FakeTextureLoader(Mutex<HashMap<String, usize>>);
+
+    impl TextureLoader for FakeTextureLoader {
+        fn id(&self) -> &str {
+            "FakeTextureLoader"
+        }
+
+        fn load(
+            &self,
+            _: &egui::Context,
+            _: &str,
+            _: egui::TextureOptions,
+            _: SizeHint,
+        ) -> egui::load::TextureLoadResult {
+            Err(LoadError::NotSupported)
+        }
+
+        fn forget(&self, uri: &str) {
+            self.0.lock().remove(uri);
+        }
+
+        fn forget_all(&self) {
+            self.0.lock().clear();
+        }
+
+        fn last_used(&self, uri: &str) -> Option<usize> {
+            self.0.lock().get(uri).copied()
+        }
+
+        fn byte_size(&self) -> usize {
+            0
+        }
+    }
+
+    #[test]
+    fn textures_are_freed_once_no_longer_shown() {
+        let textures = Arc::new(FakeTextureLoader(Mutex::new(
+            [("shown".to_owned(), 5), ("hidden".to_owned(), 4)]
+                .into_iter()
+                .collect(),
+        )));
+        let loaders: [Arc<dyn TextureLoader + Send + Sync>; 1] = [textures.clone()];
+        let mut uris: HashSet<String> = ["shown", "hidden", "no_texture"]
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+
+        free_unused_textures(&loaders, &mut uris, 5);
+        assert_eq!(uris, std::iter::once("shown".to_owned()).collect());
+        assert_eq!(textures.last_used("hidden"), None, "should be freed");
+        assert_eq!(textures.last_used("shown"), Some(5), "should be kept");
+
+        // Once it is no longer shown, it is freed too:
+        free_unused_textures(&loaders, &mut uris, 6);
+        assert!(uris.is_empty());
+        assert_eq!(textures.last_used("shown"), None);
+    }
 }
@@ -0,0 +1,226 @@
+//! Load a [`Style`] from a file, and optionally reload it when the file changes.
+
+use std::{path::PathBuf, sync::Arc, time::SystemTime};
+
+use egui::{Context, Style, Visuals};
+
+/// Loads a [`Style`] from a theme file, and optionally reloads it whenever the file changes.
+///
+/// With [`Self::hot_reload`] you can tweak the look of your app without recompiling it.
+/// Files ending in `.ron` are read with [`Style::from_ron`] (requires the `ron` feature),
+/// and files ending in `.toml` with [`Style::from_toml`] (requires the `toml` feature).
+///
+/// Not available on the web, since it reads the file from disk.
+///
+/// When a new theme is loaded, the [`Visuals`] fade over from the current ones,
+/// see [`Self::transition_time`].
+///
+/// ```
+/// struct MyApp {
+///     theme: egui_extras::ThemeLoader,
+/// }
+///
+/// impl MyApp {
+///     fn new() -> Self {
+///         Self {
+///             theme: egui_extras::ThemeLoader::new("theme.ron").hot_reload(cfg!(debug_assertions)),
+///         }
+///     }
+///
+///     fn update(&mut self, ctx: &egui::Context) {
+///         self.theme.update(ctx);
+///
+///         // …
+///     }
+/// }
+/// ```
+pub struct ThemeLoader {
+    path: PathBuf,
+    hot_reload: bool,
+    poll_interval: f32,
+    transition_time: f32,
+
+    /// When the file was last changed, if it has been read.
+    modified: Option<SystemTime>,
+
+    /// When we last checked the file, in [`egui::InputState::time`].
+    last_check: Option<f64>,
+
+    style: Option<Arc<Style>>,
+    transition: Option<Transition>,
+    error: Option<String>,
+}
+
+struct Transition {
+    from: Visuals,
+    start_time: f64,
+}
+
+impl ThemeLoader {
+    /// Load the theme from this file the first time [`Self::update`] is called.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            hot_reload: false,
+            poll_interval: 0.5,
+            transition_time: 0.3,
+            modified: None,
+            last_check: None,
+            style: None,
+            transition: None,
+            error: None,
+        }
+    }
+
+    /// Check if the file has changed every [`Self::poll_interval`], and reload it if it has.
+    /// Default: `false`.
+    ///
+    /// This keeps the app repainting while it waits for changes,
+    /// so only enable it while you are working on the theme.
+    #[inline]
+    pub fn hot_reload(mut self, hot_reload: bool) -> Self {
+        self.hot_reload = hot_reload;
+        self
+    }
+
+    /// How often to check if the file has changed with [`Self::hot_reload`], in seconds.
+    /// Default: `0.5`.
+    #[inline]
+    pub fn poll_interval(mut self, seconds: f32) -> Self {
+        self.poll_interval = seconds;
+        self
+    }
+
+    /// How long it takes to fade from the old visuals to the new ones, in seconds.
+    ///
+    /// The first theme that is loaded is applied immediately. Default: `0.3`.
+    #[inline]
+    pub fn transition_time(mut self, seconds: f32) -> Self {
+        self.transition_time = seconds;
+        self
+    }
+
+    /// The theme file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// The last style that was successfully loaded.
+    pub fn style(&self) -> Option<&Arc<Style>> {
+        self.style.as_ref()
+    }
+
+    /// Why the file could not be loaded the last time we tried, if it failed.
+    ///
+    /// The last style that was successfully loaded stays in use until the file is fixed.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Load the file the first time, reload it if it has changed and [`Self::hot_reload`] is on,
+    /// and apply the style to the current theme with [`Context::set_style`].
+    ///
+    /// Call this once each frame.
+    pub fn update(&mut self, ctx: &Context) {
+        let time = ctx.input(|i| i.time);
+
+        let poll_now = self.last_check.map_or(true, |last_check| {
+            self.hot_reload && f64::from(self.poll_interval) <= time - last_check
+        });
+        if poll_now {
+            self.last_check = Some(time);
+            self.reload_if_changed(ctx, time);
+        }
+        if self.hot_reload {
+            ctx.request_repaint_after_secs(self.poll_interval);
+        }
+
+        let Some(style) = &self.style else {
+            return;
+        };
+        let Some(transition) = &self.transition else {
+            return;
+        };
+
+        let t = if 0.0 < self.transition_time {
+            ((time - transition.start_time) as f32 / self.transition_time).min(1.0)
+        } else {
+            1.0
+        };
+        if t < 1.0 {
+            let mut blended = (**style).clone();
+            blended.visuals = transition.from.lerp(&style.visuals, t);
+            ctx.set_style(blended);
+            ctx.request_repaint();
+        } else {
+            ctx.set_style(style.clone());
+            self.transition = None;
+        }
+    }
+
+    fn reload_if_changed(&mut self, ctx: &Context, time: f64) {
+        let modified = match std::fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(err) => {
+                self.set_error(format!("Failed to read {:?}: {err}", self.path));
+                return;
+            }
+        };
+        if self.modified == Some(modified) {
+            return;
+        }
+        self.modified = Some(modified);
+
+        let style = std::fs::read_to_string(&self.path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| self.parse(&text));
+        match style {
+            Ok(style) => {
+                let style = Arc::new(style);
+                if self.style.is_some() {
+                    self.transition = Some(Transition {
+                        from: ctx.style().visuals.clone(),
+                        start_time: time,
+                    });
+                } else {
+                    ctx.set_style(style.clone());
+                }
+                self.style = Some(style);
+                self.error = None;
+            }
+            Err(err) => {
+                self.set_error(format!("Failed to load theme {:?}: {err}", self.path));
+            }
+        }
+    }
+
+    fn parse(&self, text: &str) -> Result<Style, String> {
+        let extension = self.path.extension().and_then(|ext| ext.to_str());
+        match extension {
+            #[cfg(feature = "ron")]
+            Some("ron") => Style::from_ron(text),
+
+            #[cfg(feature = "toml")]
+            Some("toml") => Style::from_toml(text),
+
+            _ => {
+                let supported = [
+                    #[cfg(feature = "ron")]
+                    "ron",
+                    #[cfg(feature = "toml")]
+                    "toml",
+                ];
+                Err(format!(
+                    "Unsupported theme file extension {extension:?}, expected one of {supported:?}"
+                ))
+            }
+        }
+    }
+
+    fn set_error(&mut self, error: String) {
+        if self.error.as_ref() != Some(&error) {
+            log::warn!("{error}");
+        }
+        self.error = Some(error);
+    }
+}
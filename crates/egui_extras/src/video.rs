@@ -0,0 +1,536 @@
+//! Play videos into an egui texture, see [`Video`].
+
+use std::sync::Arc;
+
+use egui::{ColorImage, Response, Sense, TextureHandle, TextureOptions, Ui, Widget};
+
+/// Something that decodes a video, for [`Video`].
+///
+/// The backend keeps its own clock, and [`Video`] asks it for the latest frame every time it is shown.
+///
+/// Use [`ImageSequence`] for frames you already have in memory,
+/// `WebVideo` to play anything the browser can play (on the web),
+/// or implement it yourself on top of a decoder like `ffmpeg` or `gstreamer`.
+pub trait VideoBackend {
+    /// The length of the video in seconds, if known.
+    fn duration(&self) -> Option<f64>;
+
+    /// The current playback position in seconds.
+    fn position(&self) -> f64;
+
+    fn is_playing(&self) -> bool;
+
+    fn play(&mut self);
+
+    fn pause(&mut self);
+
+    /// Jump to the given position in seconds.
+    fn seek(&mut self, position: f64);
+
+    /// The frame to show now, if it changed since the last call.
+    ///
+    /// `now` is [`egui::InputState::time`], for backends that need a clock.
+    fn poll_frame(&mut self, now: f64) -> Option<ColorImage>;
+}
+
+// ----------------------------------------------------------------------------
+
+/// A video widget: shows the frames of a [`VideoBackend`] in a texture,
+/// with optional play/pause and seek controls below.
+///
+/// Unlike most widgets, you need to keep it around between frames, as it owns the decoder.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui_extras::{ImageSequence, Video};
+///
+/// let frames = vec![egui::ColorImage::new([64, 64], egui::Color32::RED); 10];
+/// let mut video = Video::new(ImageSequence::new(frames, 25.0).looping(true));
+///
+/// // Every frame:
+/// if ui.add(&mut video).clicked() {
+///     video.toggle_playing();
+/// }
+/// # });
+/// ```
+pub struct Video {
+    backend: Box<dyn VideoBackend>,
+    texture: Option<TextureHandle>,
+    texture_options: TextureOptions,
+    show_controls: bool,
+}
+
+impl Video {
+    pub fn new(backend: impl VideoBackend + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
+            texture: None,
+            texture_options: TextureOptions::LINEAR,
+            show_controls: true,
+        }
+    }
+
+    /// Play the video at the given URL using the browser's `<video>` element, see [`WebVideo`].
+    #[cfg(target_arch = "wasm32")]
+    pub fn from_url(url: &str) -> Self {
+        Self::new(WebVideo::new(url))
+    }
+
+    /// Show play/pause and seek controls below the video.
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn show_controls(mut self, show_controls: bool) -> Self {
+        self.show_controls = show_controls;
+        self
+    }
+
+    /// How to sample the frames.
+    ///
+    /// Default: [`TextureOptions::LINEAR`].
+    #[inline]
+    pub fn texture_options(mut self, texture_options: TextureOptions) -> Self {
+        self.texture_options = texture_options;
+        self
+    }
+
+    pub fn backend(&self) -> &dyn VideoBackend {
+        self.backend.as_ref()
+    }
+
+    pub fn backend_mut(&mut self) -> &mut dyn VideoBackend {
+        self.backend.as_mut()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.backend.is_playing()
+    }
+
+    pub fn play(&mut self) {
+        self.backend.play();
+    }
+
+    pub fn pause(&mut self) {
+        self.backend.pause();
+    }
+
+    pub fn toggle_playing(&mut self) {
+        if self.is_playing() {
+            self.pause();
+        } else {
+            self.play();
+        }
+    }
+
+    /// Jump to the given position in seconds.
+    pub fn seek(&mut self, position: f64) {
+        self.backend.seek(position);
+    }
+
+    /// The current playback position in seconds.
+    pub fn position(&self) -> f64 {
+        self.backend.position()
+    }
+
+    /// The length of the video in seconds, if known.
+    pub fn duration(&self) -> Option<f64> {
+        self.backend.duration()
+    }
+
+    /// Upload the latest frame, if any.
+    fn update_texture(&mut self, ui: &Ui) {
+        let now = ui.input(|i| i.time);
+        let Some(frame) = self.backend.poll_frame(now) else {
+            return;
+        };
+        match &mut self.texture {
+            Some(texture) => texture.set(frame, self.texture_options),
+            None => {
+                self.texture = Some(ui.ctx().load_texture(
+                    "egui_extras::Video",
+                    frame,
+                    self.texture_options,
+                ));
+            }
+        }
+    }
+
+    /// Show the video (and the controls, if enabled).
+    ///
+    /// The returned [`Response`] is for the video itself, and senses clicks.
+    pub fn ui(&mut self, ui: &mut Ui) -> Response {
+        self.update_texture(ui);
+
+        if self.is_playing() {
+            ui.ctx().request_repaint();
+        }
+
+        let response = ui
+            .vertical(|ui| {
+                let response = if let Some(texture) = &self.texture {
+                    ui.add(
+                        egui::Image::from_texture(texture)
+                            .max_width(ui.available_width())
+                            .sense(Sense::click()),
+                    )
+                } else {
+                    // No frame yet:
+                    let size = egui::Vec2::splat(ui.spacing().interact_size.y * 4.0);
+                    let (rect, response) = ui.allocate_exact_size(size, Sense::click());
+                    egui::Spinner::new().paint_at(ui, rect.shrink(size.x / 4.0));
+                    response
+                };
+
+                if self.show_controls {
+                    self.controls_ui(ui);
+                }
+
+                response
+            })
+            .inner;
+
+        response.on_hover_cursor(egui::CursorIcon::PointingHand)
+    }
+
+    fn controls_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let playing = self.is_playing();
+            let (icon, hover_text) = if playing {
+                ("⏸", "Pause")
+            } else {
+                ("▶", "Play")
+            };
+            if ui.button(icon).on_hover_text(hover_text).clicked() {
+                self.toggle_playing();
+            }
+
+            let mut position = self.position();
+            let duration = self.duration();
+            if let Some(duration) = duration.filter(|duration| 0.0 < *duration) {
+                let slider = egui::Slider::new(&mut position, 0.0..=duration).show_value(false);
+                if ui.add(slider).changed() {
+                    self.seek(position);
+                }
+            }
+
+            let time = match duration {
+                Some(duration) => format!("{} / {}", format_time(position), format_time(duration)),
+                None => format_time(position),
+            };
+            ui.monospace(time);
+        });
+    }
+}
+
+impl Widget for &mut Video {
+    fn ui(self, ui: &mut Ui) -> Response {
+        Video::ui(self, ui)
+    }
+}
+
+/// E.g. `1:05`, or `1:02:05` for long videos.
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if 0 < hours {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A [`VideoBackend`] playing frames you already have in memory, at a fixed frame rate.
+///
+/// Useful for short clips, rendered animations, and image sequences from e.g. a camera.
+#[derive(Clone, Debug)]
+pub struct ImageSequence {
+    frames: Vec<Arc<ColorImage>>,
+    fps: f64,
+    looping: bool,
+
+    position: f64,
+    playing: bool,
+
+    /// The `now` of the last [`VideoBackend::poll_frame`], to advance [`Self::position`] while playing.
+    last_poll: Option<f64>,
+
+    /// The index of the frame we returned last.
+    shown_frame: Option<usize>,
+}
+
+impl ImageSequence {
+    /// The frames should all have the same size.
+    pub fn new(frames: Vec<ColorImage>, fps: f64) -> Self {
+        Self {
+            frames: frames.into_iter().map(Arc::new).collect(),
+            fps: fps.max(f64::EPSILON),
+            looping: false,
+            position: 0.0,
+            playing: false,
+            last_poll: None,
+            shown_frame: None,
+        }
+    }
+
+    /// Start over when reaching the end, instead of pausing.
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    fn frame_index(&self) -> Option<usize> {
+        let last = self.frames.len().checked_sub(1)?;
+        Some(((self.position * self.fps) as usize).min(last))
+    }
+
+    fn total_duration(&self) -> f64 {
+        self.frames.len() as f64 / self.fps
+    }
+}
+
+impl VideoBackend for ImageSequence {
+    fn duration(&self) -> Option<f64> {
+        Some(self.total_duration())
+    }
+
+    fn position(&self) -> f64 {
+        self.position
+    }
+
+    fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    fn play(&mut self) {
+        if self.total_duration() <= self.position {
+            self.position = 0.0; // Start over
+        }
+        self.playing = true;
+    }
+
+    fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    fn seek(&mut self, position: f64) {
+        self.position = position.clamp(0.0, self.total_duration());
+    }
+
+    fn poll_frame(&mut self, now: f64) -> Option<ColorImage> {
+        let dt = self
+            .last_poll
+            .map_or(0.0, |last_poll| (now - last_poll).max(0.0));
+        self.last_poll = Some(now);
+
+        if self.playing {
+            let duration = self.total_duration();
+            self.position += dt;
+            if duration <= self.position {
+                if self.looping && 0.0 < duration {
+                    self.position %= duration;
+                } else {
+                    self.position = duration;
+                    self.playing = false;
+                }
+            }
+        }
+
+        let index = self.frame_index()?;
+        if self.shown_frame == Some(index) {
+            return None;
+        }
+        self.shown_frame = Some(index);
+        Some(ColorImage::clone(&self.frames[index]))
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A [`VideoBackend`] using the browser's `<video>` element,
+/// so it plays whatever the browser can play, including streams.
+///
+/// The frames are captured through a canvas, so videos from other origins
+/// need to be served with CORS headers.
+#[cfg(target_arch = "wasm32")]
+pub struct WebVideo {
+    video: web_sys::HtmlVideoElement,
+    canvas: Option<(
+        web_sys::HtmlCanvasElement,
+        web_sys::CanvasRenderingContext2d,
+    )>,
+
+    /// The `currentTime` of the frame we captured last.
+    captured_time: Option<f64>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl WebVideo {
+    /// Start loading the video at the given URL.
+    ///
+    /// # Panics
+    /// If called outside of a browser window.
+    pub fn new(url: &str) -> Self {
+        use wasm_bindgen::JsCast as _;
+
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .expect("WebVideo needs a browser window");
+        let video: web_sys::HtmlVideoElement = document
+            .create_element("video")
+            .expect("failed to create a <video> element")
+            .unchecked_into();
+        video.set_cross_origin(Some("anonymous"));
+        video.set_preload("auto");
+        video.set_src(url);
+
+        Self {
+            video,
+            canvas: None,
+            captured_time: None,
+        }
+    }
+
+    /// The `<video>` element, e.g. to change its volume or to mute it.
+    pub fn element(&self) -> &web_sys::HtmlVideoElement {
+        &self.video
+    }
+
+    fn capture_frame(&mut self) -> Option<ColorImage> {
+        use wasm_bindgen::JsCast as _;
+
+        let [width, height] = [self.video.video_width(), self.video.video_height()];
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        if self.canvas.is_none() {
+            let canvas: web_sys::HtmlCanvasElement = web_sys::window()?
+                .document()?
+                .create_element("canvas")
+                .ok()?
+                .unchecked_into();
+            let context = canvas
+                .get_context("2d")
+                .ok()??
+                .dyn_into::<web_sys::CanvasRenderingContext2d>()
+                .ok()?;
+            self.canvas = Some((canvas, context));
+        }
+        let (canvas, context) = self.canvas.as_ref()?;
+        if canvas.width() != width || canvas.height() != height {
+            canvas.set_width(width);
+            canvas.set_height(height);
+        }
+
+        context
+            .draw_image_with_html_video_element(&self.video, 0.0, 0.0)
+            .ok()?;
+        let pixels = match context.get_image_data(0.0, 0.0, width as f64, height as f64) {
+            Ok(image_data) => image_data.data(),
+            Err(err) => {
+                log::warn!("Failed to capture a video frame (missing CORS headers?): {err:?}");
+                return None;
+            }
+        };
+        Some(ColorImage::from_rgba_unmultiplied(
+            [width as usize, height as usize],
+            &pixels,
+        ))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl VideoBackend for WebVideo {
+    fn duration(&self) -> Option<f64> {
+        let duration = self.video.duration();
+        duration.is_finite().then_some(duration)
+    }
+
+    fn position(&self) -> f64 {
+        self.video.current_time()
+    }
+
+    fn is_playing(&self) -> bool {
+        !self.video.paused() && !self.video.ended()
+    }
+
+    fn play(&mut self) {
+        if let Err(err) = self.video.play() {
+            log::warn!("Failed to play video: {err:?}");
+        }
+    }
+
+    fn pause(&mut self) {
+        if let Err(err) = self.video.pause() {
+            log::warn!("Failed to pause video: {err:?}");
+        }
+    }
+
+    fn seek(&mut self, position: f64) {
+        self.video.set_current_time(position);
+    }
+
+    fn poll_frame(&mut self, _now: f64) -> Option<ColorImage> {
+        const HAVE_CURRENT_DATA: u16 = 2;
+        if self.video.ready_state() < HAVE_CURRENT_DATA {
+            return None;
+        }
+
+        let time = self.video.current_time();
+        if self.captured_time == Some(time) {
+            return None;
+        }
+        let frame = self.capture_frame()?;
+        self.captured_time = Some(time);
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_sequence_plays_and_stops() {
+        let frames = (0..4)
+            .map(|i| ColorImage::new([1, 1], egui::Color32::from_gray(i)))
+            .collect();
+        let mut sequence = ImageSequence::new(frames, 2.0);
+        assert_eq!(sequence.duration(), Some(2.0));
+
+        // The first frame is shown even while paused, but only once:
+        assert!(sequence.poll_frame(0.0).is_some());
+        assert!(sequence.poll_frame(1.0).is_none());
+        assert_eq!(sequence.position(), 0.0);
+
+        sequence.play();
+        assert!(sequence.poll_frame(1.6).is_some());
+        assert!((sequence.position() - 0.6).abs() < 1e-9);
+
+        // Stops at the end:
+        sequence.poll_frame(10.0);
+        assert!(!sequence.is_playing());
+        assert_eq!(sequence.position(), 2.0);
+
+        // …and starts over when played again:
+        sequence.play();
+        assert_eq!(sequence.position(), 0.0);
+
+        sequence.pause();
+        sequence.seek(1.0);
+        let frame = sequence.poll_frame(10.0).unwrap();
+        assert_eq!(frame.pixels[0], egui::Color32::from_gray(2));
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(5.9), "0:05");
+        assert_eq!(format_time(65.0), "1:05");
+        assert_eq!(format_time(3725.0), "1:02:05");
+    }
+}
@@ -98,6 +98,29 @@ pub struct Painter {
 
     next_native_tex_id: u64,
 
+    /// See [`Self::set_render_scale`].
+    render_scale: f32,
+
+    /// Can we use [`Self::render_scale`]?
+    ///
+    /// Requires framebuffer blitting, and a screen framebuffer that is not multisampled.
+    supports_render_scale: bool,
+
+    /// Offscreen target we paint to when [`Self::render_scale`] is not `1.0`.
+    render_target: Option<RenderTarget>,
+
+    /// Target of [`Self::paint_to_texture`].
+    texture_target: Option<TextureTarget>,
+
+    /// Set while inside [`Self::paint_to_texture`].
+    is_painting_to_texture: bool,
+
+    /// See [`Self::set_texture_upload_budget`].
+    texture_upload_budget: Option<usize>,
+
+    /// Texture replacements that are being uploaded over several frames.
+    pending_uploads: HashMap<egui::TextureId, PendingUpload>,
+
     /// Stores outdated OpenGL textures that are yet to be deleted
     textures_to_destroy: Vec<glow::Texture>,
 
@@ -105,6 +128,44 @@ pub struct Painter {
     destroyed: bool,
 }
 
+/// An offscreen framebuffer, used for rendering at a different resolution than the screen.
+struct RenderTarget {
+    fbo: glow::Framebuffer,
+    renderbuffer: glow::Renderbuffer,
+    size_px: [u32; 2],
+}
+
+/// A framebuffer with a texture attached, so that it can be sampled by the host application.
+struct TextureTarget {
+    fbo: glow::Framebuffer,
+    texture: glow::Texture,
+    size_px: [u32; 2],
+}
+
+/// A texture that is uploaded a few rows at a time,
+/// and replaces the old texture once it is complete.
+struct PendingUpload {
+    texture: glow::Texture,
+    size: [usize; 2],
+    options: egui::TextureOptions,
+
+    /// sRGBA pixels.
+    data: Vec<u8>,
+
+    /// How many rows of [`Self::data`] have been uploaded so far.
+    rows_uploaded: usize,
+}
+
+impl PendingUpload {
+    /// Apply a partial update to the not-yet-uploaded texture.
+    fn patch(&mut self, pos: [usize; 2], size: [usize; 2], data: &[u8]) {
+        patch_pixels(&mut self.data, self.size[0], pos, size, data);
+
+        // Rows that were already uploaded need to be uploaded again:
+        self.rows_uploaded = self.rows_uploaded.min(pos[1]);
+    }
+}
+
 /// A callback function that can be used to compose an [`egui::PaintCallback`] for custom rendering
 /// with [`glow`].
 ///
@@ -186,6 +247,10 @@ impl Painter {
             });
         log::debug!("SRGB framebuffer Support: {:?}", supports_srgb_framebuffer);
 
+        let supports_render_scale =
+            !matches!(shader_version, ShaderVersion::Gl120 | ShaderVersion::Es100)
+                && unsafe { gl.get_parameter_i32(glow::SAMPLE_BUFFERS) } == 0;
+
         unsafe {
             let vert = compile_shader(
                 &gl,
@@ -272,6 +337,13 @@ impl Painter {
                 element_array_buffer,
                 textures: Default::default(),
                 next_native_tex_id: 1 << 32,
+                render_scale: 1.0,
+                supports_render_scale,
+                render_target: None,
+                texture_target: None,
+                is_painting_to_texture: false,
+                texture_upload_budget: None,
+                pending_uploads: Default::default(),
                 textures_to_destroy: Vec::new(),
                 destroyed: false,
             })
@@ -296,11 +368,185 @@ impl Painter {
     /// So if in a [`egui::Shape::Callback`] you need to use an offscreen FBO, you should
     /// then restore to this afterwards with
     /// `gl.bind_framebuffer(glow::FRAMEBUFFER, painter.intermediate_fbo());`
-    #[allow(clippy::unused_self)]
     pub fn intermediate_fbo(&self) -> Option<glow::Framebuffer> {
-        // We don't currently ever render to an offscreen buffer,
-        // but we may want to start to in order to do anti-aliasing on web, for instance.
-        None
+        if self.is_painting_to_texture {
+            return self.texture_target.as_ref().map(|target| target.fbo);
+        }
+
+        // We only render to an offscreen buffer when we have a render scale:
+        if self.is_render_scaled() {
+            self.render_target.as_ref().map(|target| target.fbo)
+        } else {
+            None
+        }
+    }
+
+    /// Render at this factor of the screen resolution, and then stretch the result onto the screen.
+    ///
+    /// Values above `1.0` supersample (e.g. for crisp screenshots),
+    /// values below `1.0` undersample (e.g. for performance on weak GPUs).
+    /// This does not change the layout, only the resolution of the painted result.
+    ///
+    /// The default is `1.0`, which paints directly to the screen.
+    ///
+    /// Requires OpenGL 3 (or WebGL2) and a screen framebuffer without multisampling.
+    /// Otherwise this is ignored.
+    pub fn set_render_scale(&mut self, render_scale: f32) {
+        let render_scale = if render_scale.is_finite() && 0.0 < render_scale {
+            render_scale
+        } else {
+            log::warn!("Invalid render scale: {render_scale}");
+            1.0
+        };
+
+        if render_scale != 1.0 && !self.supports_render_scale && self.render_scale == 1.0 {
+            log::warn!("Render scale is not supported by this OpenGL context; ignoring it");
+        }
+
+        self.render_scale = render_scale;
+    }
+
+    fn is_render_scaled(&self) -> bool {
+        self.render_scale != 1.0 && self.supports_render_scale
+    }
+
+    /// The size of the offscreen render target for the given screen size.
+    fn render_target_size(&self, [width_in_pixels, height_in_pixels]: [u32; 2]) -> [u32; 2] {
+        let max_side = self.max_texture_side as f32;
+        let scale = |side: u32| {
+            (side as f32 * self.render_scale)
+                .round()
+                .clamp(1.0, max_side) as u32
+        };
+        [scale(width_in_pixels), scale(height_in_pixels)]
+    }
+
+    /// Make sure [`Self::render_target`] exists and has the given size, and return its framebuffer.
+    unsafe fn prepare_render_target(
+        &mut self,
+        size_px: [u32; 2],
+    ) -> Result<glow::Framebuffer, String> {
+        if let Some(target) = &self.render_target {
+            if target.size_px == size_px {
+                return Ok(target.fbo);
+            }
+        }
+
+        unsafe {
+            if let Some(old_target) = self.render_target.take() {
+                self.gl.delete_framebuffer(old_target.fbo);
+                self.gl.delete_renderbuffer(old_target.renderbuffer);
+            }
+
+            let renderbuffer = self.gl.create_renderbuffer()?;
+            self.gl
+                .bind_renderbuffer(glow::RENDERBUFFER, Some(renderbuffer));
+            self.gl.renderbuffer_storage(
+                glow::RENDERBUFFER,
+                glow::RGBA8,
+                size_px[0] as i32,
+                size_px[1] as i32,
+            );
+            self.gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+            let fbo = self.gl.create_framebuffer()?;
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
+            self.gl.framebuffer_renderbuffer(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                glow::RENDERBUFFER,
+                Some(renderbuffer),
+            );
+            let status = self.gl.check_framebuffer_status(glow::FRAMEBUFFER);
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+
+            self.render_target = Some(RenderTarget {
+                fbo,
+                renderbuffer,
+                size_px,
+            });
+
+            check_for_gl_error!(&self.gl, "prepare_render_target");
+
+            if status == glow::FRAMEBUFFER_COMPLETE {
+                Ok(fbo)
+            } else {
+                Err(format!(
+                    "Render target framebuffer is incomplete: {status:#x}"
+                ))
+            }
+        }
+    }
+
+    /// Make sure [`Self::texture_target`] exists and has the given size, and return its framebuffer.
+    unsafe fn prepare_texture_target(
+        &mut self,
+        size_px: [u32; 2],
+    ) -> Result<glow::Framebuffer, String> {
+        if let Some(target) = &self.texture_target {
+            if target.size_px == size_px {
+                return Ok(target.fbo);
+            }
+        }
+
+        unsafe {
+            if let Some(old_target) = self.texture_target.take() {
+                self.gl.delete_framebuffer(old_target.fbo);
+                self.gl.delete_texture(old_target.texture);
+            }
+
+            let texture = self.gl.create_texture()?;
+            self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            self.gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                glow::RGBA as i32,
+                size_px[0] as i32,
+                size_px[1] as i32,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(None),
+            );
+            // No mipmaps, so the texture is complete without them:
+            for (parameter, value) in [
+                (glow::TEXTURE_MIN_FILTER, glow::LINEAR),
+                (glow::TEXTURE_MAG_FILTER, glow::LINEAR),
+                (glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+                (glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+            ] {
+                self.gl
+                    .tex_parameter_i32(glow::TEXTURE_2D, parameter, value as i32);
+            }
+            self.gl.bind_texture(glow::TEXTURE_2D, None);
+
+            let fbo = self.gl.create_framebuffer()?;
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
+            self.gl.framebuffer_texture_2d(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                glow::TEXTURE_2D,
+                Some(texture),
+                0,
+            );
+            let status = self.gl.check_framebuffer_status(glow::FRAMEBUFFER);
+
+            self.texture_target = Some(TextureTarget {
+                fbo,
+                texture,
+                size_px,
+            });
+
+            check_for_gl_error!(&self.gl, "prepare_texture_target");
+
+            if status == glow::FRAMEBUFFER_COMPLETE {
+                Ok(fbo)
+            } else {
+                Err(format!(
+                    "Texture target framebuffer is incomplete: {status:#x}"
+                ))
+            }
+        }
     }
 
     unsafe fn prepare_painting(
@@ -371,6 +617,7 @@ impl Painter {
         for (id, image_delta) in &textures_delta.set {
             self.set_texture(*id, image_delta);
         }
+        self.upload_pending_textures();
 
         self.paint_primitives(screen_size_px, pixels_per_point, clipped_primitives);
 
@@ -408,6 +655,130 @@ impl Painter {
         profiling::function_scope!();
         self.assert_not_destroyed();
 
+        if !self.is_render_scaled() {
+            self.paint_primitives_to_current_framebuffer(
+                screen_size_px,
+                pixels_per_point,
+                clipped_primitives,
+            );
+            return;
+        }
+
+        let target_size_px = self.render_target_size(screen_size_px);
+        let fbo = match unsafe { self.prepare_render_target(target_size_px) } {
+            Ok(fbo) => fbo,
+            Err(err) => {
+                log::error!("Failed to create render target: {err}");
+                self.supports_render_scale = false;
+                self.paint_primitives_to_current_framebuffer(
+                    screen_size_px,
+                    pixels_per_point,
+                    clipped_primitives,
+                );
+                return;
+            }
+        };
+
+        unsafe {
+            // Start with whatever is on screen (e.g. the clear color):
+            blit_framebuffer(&self.gl, None, screen_size_px, Some(fbo), target_size_px);
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
+        }
+
+        self.paint_primitives_to_current_framebuffer(
+            target_size_px,
+            pixels_per_point * self.render_scale,
+            clipped_primitives,
+        );
+
+        unsafe {
+            blit_framebuffer(&self.gl, Some(fbo), target_size_px, None, screen_size_px);
+            self.gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+        }
+
+        check_for_gl_error!(&self.gl, "paint_primitives with render scale");
+    }
+
+    /// Paint a full egui pass into a texture, instead of onto the screen.
+    ///
+    /// Use this to embed egui in an existing OpenGL scene, e.g. as a panel in a 3D world.
+    /// Run the [`egui::Context`] with a [`egui::RawInput::screen_rect`] of `size_px / pixels_per_point`,
+    /// and pass on its output here, like you would to [`Self::paint_and_update_textures`].
+    ///
+    /// The texture is cleared to `clear_color` first, and then contains gamma-space RGBA
+    /// with premultiplied alpha, so composite it with the blend function `(ONE, ONE_MINUS_SRC_ALPHA)`.
+    /// As with any OpenGL framebuffer, the first row is the bottom of the UI.
+    ///
+    /// The texture and its framebuffer are owned by the painter.
+    /// They are reused by the next call with the same size, resized by a call with another size,
+    /// and deleted in [`Self::destroy`], so don't delete the texture yourself.
+    ///
+    /// [`Self::set_render_scale`] is ignored here; choose `size_px` and `pixels_per_point` instead.
+    /// Afterwards, the framebuffer that was bound before the call is bound again.
+    ///
+    /// # Errors
+    /// If the framebuffer could not be created, e.g. if the size is zero or too large.
+    pub fn paint_to_texture(
+        &mut self,
+        size_px: [u32; 2],
+        pixels_per_point: f32,
+        clear_color: [f32; 4],
+        clipped_primitives: &[egui::ClippedPrimitive],
+        textures_delta: &egui::TexturesDelta,
+    ) -> Result<glow::Texture, PainterError> {
+        profiling::function_scope!();
+        self.assert_not_destroyed();
+
+        let max_side = self.max_texture_side as u32;
+        if size_px[0] == 0 || size_px[1] == 0 || max_side < size_px[0] || max_side < size_px[1] {
+            return Err(PainterError(format!(
+                "Invalid texture size {size_px:?}; the maximum texture side is {max_side}"
+            )));
+        }
+
+        let previous_fbo = unsafe { self.gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING) };
+
+        let fbo = unsafe { self.prepare_texture_target(size_px) };
+        let fbo = match fbo {
+            Ok(fbo) => fbo,
+            Err(err) => {
+                unsafe { self.gl.bind_framebuffer(glow::FRAMEBUFFER, previous_fbo) };
+                return Err(PainterError(err));
+            }
+        };
+
+        unsafe { self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo)) };
+        self.clear(size_px, clear_color);
+
+        for (id, image_delta) in &textures_delta.set {
+            self.set_texture(*id, image_delta);
+        }
+        self.upload_pending_textures();
+
+        self.is_painting_to_texture = true;
+        self.paint_primitives_to_current_framebuffer(size_px, pixels_per_point, clipped_primitives);
+        self.is_painting_to_texture = false;
+
+        for &id in &textures_delta.free {
+            self.free_texture(id);
+        }
+
+        unsafe { self.gl.bind_framebuffer(glow::FRAMEBUFFER, previous_fbo) };
+        check_for_gl_error!(&self.gl, "paint_to_texture");
+
+        Ok(self
+            .texture_target
+            .as_ref()
+            .map(|target| target.texture)
+            .expect("texture target was just prepared"))
+    }
+
+    fn paint_primitives_to_current_framebuffer(
+        &mut self,
+        screen_size_px: [u32; 2],
+        pixels_per_point: f32,
+        clipped_primitives: &[egui::ClippedPrimitive],
+    ) {
         unsafe { self.prepare_painting(screen_size_px, pixels_per_point) };
 
         for egui::ClippedPrimitive {
@@ -507,20 +878,36 @@ impl Painter {
 
     // ------------------------------------------------------------------------
 
+    /// Spread uploads of large replacement textures over several frames,
+    /// uploading at most this many bytes each frame, to avoid frame time spikes.
+    ///
+    /// This only affects full replacements of existing textures (e.g. a font atlas rebuild).
+    /// Until the new texture is completely uploaded the old one is used for painting,
+    /// so the replaced content (or text) may be briefly out of date.
+    /// Font atlases get partial updates for new glyphs all the time,
+    /// so those are applied to the pending font atlas.
+    /// A partial update of any other pending texture finishes its upload first.
+    /// Call [`Self::upload_pending_textures`] each frame (done by [`Self::paint_and_update_textures`]),
+    /// and keep repainting while [`Self::has_pending_texture_uploads`] is `true`.
+    ///
+    /// `None` (the default) uploads everything right away.
+    pub fn set_texture_upload_budget(&mut self, bytes_per_frame: Option<usize>) {
+        self.texture_upload_budget = bytes_per_frame;
+    }
+
+    /// Are there texture uploads that will continue next frame?
+    ///
+    /// See [`Self::set_texture_upload_budget`].
+    pub fn has_pending_texture_uploads(&self) -> bool {
+        !self.pending_uploads.is_empty()
+    }
+
     pub fn set_texture(&mut self, tex_id: egui::TextureId, delta: &egui::epaint::ImageDelta) {
         profiling::function_scope!();
 
         self.assert_not_destroyed();
 
-        let glow_texture = *self
-            .textures
-            .entry(tex_id)
-            .or_insert_with(|| unsafe { self.gl.create_texture().unwrap() });
-        unsafe {
-            self.gl.bind_texture(glow::TEXTURE_2D, Some(glow_texture));
-        }
-
-        match &delta.image {
+        let (size, data): ([usize; 2], std::borrow::Cow<'_, [u8]>) = match &delta.image {
             egui::ImageData::Color(image) => {
                 assert_eq!(
                     image.width() * image.height(),
@@ -528,9 +915,10 @@ impl Painter {
                     "Mismatch between texture size and texel count"
                 );
 
-                let data: &[u8] = bytemuck::cast_slice(image.pixels.as_ref());
-
-                self.upload_texture_srgb(delta.pos, image.size, delta.options, data);
+                (
+                    image.size,
+                    bytemuck::cast_slice(image.pixels.as_ref()).into(),
+                )
             }
             egui::ImageData::Font(image) => {
                 assert_eq!(
@@ -547,20 +935,52 @@ impl Painter {
                         .collect()
                 };
 
-                self.upload_texture_srgb(delta.pos, image.size, delta.options, &data);
+                (image.size, data.into())
             }
         };
+
+        if let Some(pos) = delta.pos {
+            if matches!(delta.image, egui::ImageData::Font(_)) {
+                if let Some(pending) = self.pending_uploads.get_mut(&tex_id) {
+                    // New glyphs belong in the new font atlas:
+                    pending.patch(pos, size, &data);
+                    return;
+                }
+            }
+
+            // Partial updates are applied to the live texture, so it must be complete first:
+            self.finish_pending_upload(tex_id);
+        } else if let Some(pending) = self.pending_uploads.remove(&tex_id) {
+            // A new full image replaces the pending one:
+            unsafe { self.gl.delete_texture(pending.texture) };
+        }
+
+        if self.textures.contains_key(&tex_id)
+            && spread_upload_over_frames(delta, data.len(), self.texture_upload_budget)
+        {
+            self.begin_pending_upload(tex_id, size, delta.options, data.into_owned());
+            return;
+        }
+
+        let glow_texture = *self
+            .textures
+            .entry(tex_id)
+            .or_insert_with(|| unsafe { self.gl.create_texture().unwrap() });
+        unsafe {
+            self.gl.bind_texture(glow::TEXTURE_2D, Some(glow_texture));
+        }
+
+        self.upload_texture_srgb(delta.pos, size, delta.options, &data);
     }
 
-    fn upload_texture_srgb(
+    /// Allocate a new texture, which will be filled in by [`Self::upload_pending_textures`].
+    fn begin_pending_upload(
         &mut self,
-        pos: Option<[usize; 2]>,
+        tex_id: egui::TextureId,
         [w, h]: [usize; 2],
         options: egui::TextureOptions,
-        data: &[u8],
+        data: Vec<u8>,
     ) {
-        profiling::function_scope!();
-        assert_eq!(data.len(), w * h * 4);
         assert!(
             w <= self.max_texture_side && h <= self.max_texture_side,
             "Got a texture image of size {}x{}, but the maximum supported texture side is only {}",
@@ -569,6 +989,151 @@ impl Painter {
             self.max_texture_side
         );
 
+        let (internal_format, src_format) = self.texture_formats();
+
+        unsafe {
+            let texture = self.gl.create_texture().unwrap();
+            self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            self.set_texture_options(options);
+            self.gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                internal_format as _,
+                w as _,
+                h as _,
+                0,
+                src_format,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(None),
+            );
+            check_for_gl_error!(&self.gl, "tex_image_2d");
+
+            self.pending_uploads.insert(
+                tex_id,
+                PendingUpload {
+                    texture,
+                    size: [w, h],
+                    options,
+                    data,
+                    rows_uploaded: 0,
+                },
+            );
+        }
+    }
+
+    /// Continue the texture uploads started because of [`Self::set_texture_upload_budget`].
+    ///
+    /// Textures that are completely uploaded replace the old ones.
+    pub fn upload_pending_textures(&mut self) {
+        if self.pending_uploads.is_empty() {
+            return;
+        }
+
+        profiling::function_scope!();
+
+        let mut budget = self.texture_upload_budget.unwrap_or(usize::MAX);
+        let (_, src_format) = self.texture_formats();
+        let mut completed = vec![];
+
+        for (tex_id, pending) in &mut self.pending_uploads {
+            let rows = rows_to_upload(pending.size, pending.rows_uploaded, budget);
+            Self::upload_pending_rows(&self.gl, src_format, pending, rows);
+            budget = budget.saturating_sub(rows * pending.size[0] * 4);
+
+            if pending.rows_uploaded == pending.size[1] {
+                completed.push(*tex_id);
+            }
+
+            if budget == 0 {
+                break;
+            }
+        }
+
+        for tex_id in completed {
+            if let Some(pending) = self.pending_uploads.remove(&tex_id) {
+                self.replace_with_pending_upload(tex_id, &pending);
+            }
+        }
+    }
+
+    /// Upload the rest of a pending texture right away, and start using it.
+    fn finish_pending_upload(&mut self, tex_id: egui::TextureId) {
+        let Some(mut pending) = self.pending_uploads.remove(&tex_id) else {
+            return;
+        };
+        let (_, src_format) = self.texture_formats();
+        let rows_left = pending.size[1] - pending.rows_uploaded;
+        Self::upload_pending_rows(&self.gl, src_format, &mut pending, rows_left);
+        self.replace_with_pending_upload(tex_id, &pending);
+    }
+
+    /// Upload the next `rows` rows of a pending texture.
+    fn upload_pending_rows(
+        gl: &glow::Context,
+        src_format: u32,
+        pending: &mut PendingUpload,
+        rows: usize,
+    ) {
+        if rows == 0 {
+            return;
+        }
+
+        let width = pending.size[0];
+        let bytes_per_row = width * 4;
+        let start = pending.rows_uploaded * bytes_per_row;
+        let end = start + rows * bytes_per_row;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(pending.texture));
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+            gl.tex_sub_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                0,
+                pending.rows_uploaded as _,
+                width as _,
+                rows as _,
+                src_format,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(Some(&pending.data[start..end])),
+            );
+        }
+        check_for_gl_error!(gl, "tex_sub_image_2d");
+        pending.rows_uploaded += rows;
+    }
+
+    /// Start painting with a completely uploaded texture, freeing the old one.
+    fn replace_with_pending_upload(&mut self, tex_id: egui::TextureId, pending: &PendingUpload) {
+        unsafe {
+            if pending.options.mipmap_mode.is_some() {
+                self.gl
+                    .bind_texture(glow::TEXTURE_2D, Some(pending.texture));
+                self.gl.generate_mipmap(glow::TEXTURE_2D);
+                check_for_gl_error!(&self.gl, "generate_mipmap");
+            }
+            if let Some(old_texture) = self.textures.insert(tex_id, pending.texture) {
+                self.gl.delete_texture(old_texture);
+            }
+        }
+    }
+
+    /// Returns `(internal_format, src_format)` for our sRGBA textures.
+    fn texture_formats(&self) -> (u32, u32) {
+        if self.is_webgl_1 {
+            let format = if self.srgb_textures {
+                glow::SRGB_ALPHA
+            } else {
+                glow::RGBA
+            };
+            (format, format)
+        } else if self.srgb_textures {
+            (glow::SRGB8_ALPHA8, glow::RGBA)
+        } else {
+            (glow::RGBA8, glow::RGBA)
+        }
+    }
+
+    /// Set the parameters of the currently bound texture.
+    unsafe fn set_texture_options(&self, options: egui::TextureOptions) {
         unsafe {
             self.gl.tex_parameter_i32(
                 glow::TEXTURE_2D,
@@ -592,19 +1157,30 @@ impl Painter {
                 options.wrap_mode.glow_code() as i32,
             );
             check_for_gl_error!(&self.gl, "tex_parameter");
+        }
+    }
 
-            let (internal_format, src_format) = if self.is_webgl_1 {
-                let format = if self.srgb_textures {
-                    glow::SRGB_ALPHA
-                } else {
-                    glow::RGBA
-                };
-                (format, format)
-            } else if self.srgb_textures {
-                (glow::SRGB8_ALPHA8, glow::RGBA)
-            } else {
-                (glow::RGBA8, glow::RGBA)
-            };
+    fn upload_texture_srgb(
+        &mut self,
+        pos: Option<[usize; 2]>,
+        [w, h]: [usize; 2],
+        options: egui::TextureOptions,
+        data: &[u8],
+    ) {
+        profiling::function_scope!();
+        assert_eq!(data.len(), w * h * 4);
+        assert!(
+            w <= self.max_texture_side && h <= self.max_texture_side,
+            "Got a texture image of size {}x{}, but the maximum supported texture side is only {}",
+            w,
+            h,
+            self.max_texture_side
+        );
+
+        unsafe {
+            self.set_texture_options(options);
+
+            let (internal_format, src_format) = self.texture_formats();
 
             self.gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
 
@@ -651,6 +1227,9 @@ impl Painter {
         if let Some(old_tex) = self.textures.remove(&tex_id) {
             unsafe { self.gl.delete_texture(old_tex) };
         }
+        if let Some(pending) = self.pending_uploads.remove(&tex_id) {
+            unsafe { self.gl.delete_texture(pending.texture) };
+        }
     }
 
     /// Get the [`glow::Texture`] bound to a [`egui::TextureId`].
@@ -674,11 +1253,25 @@ impl Painter {
         }
     }
 
+    /// Read back what was last painted.
+    ///
+    /// If a render scale is set (see [`Self::set_render_scale`]),
+    /// this reads the offscreen render target at its full resolution, ignoring the given size.
     pub fn read_screen_rgba(&self, [w, h]: [u32; 2]) -> egui::ColorImage {
         profiling::function_scope!();
 
+        let render_target = self
+            .render_target
+            .as_ref()
+            .filter(|_| self.is_render_scaled());
+        let [w, h] = render_target.map_or([w, h], |target| target.size_px);
+
         let mut pixels = vec![0_u8; (w * h * 4) as usize];
         unsafe {
+            if let Some(target) = render_target {
+                self.gl
+                    .bind_framebuffer(glow::READ_FRAMEBUFFER, Some(target.fbo));
+            }
             self.gl.read_pixels(
                 0,
                 0,
@@ -688,6 +1281,9 @@ impl Painter {
                 glow::UNSIGNED_BYTE,
                 glow::PixelPackData::Slice(Some(&mut pixels)),
             );
+            if render_target.is_some() {
+                self.gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+            }
         }
         let mut flipped = Vec::with_capacity((w * h * 4) as usize);
         for row in pixels.chunks_exact((w * 4) as usize).rev() {
@@ -727,6 +1323,17 @@ impl Painter {
             for t in &self.textures_to_destroy {
                 self.gl.delete_texture(*t);
             }
+            for pending in self.pending_uploads.values() {
+                self.gl.delete_texture(pending.texture);
+            }
+            if let Some(target) = &self.render_target {
+                self.gl.delete_framebuffer(target.fbo);
+                self.gl.delete_renderbuffer(target.renderbuffer);
+            }
+            if let Some(target) = &self.texture_target {
+                self.gl.delete_framebuffer(target.fbo);
+                self.gl.delete_texture(target.texture);
+            }
         }
     }
 
@@ -767,6 +1374,38 @@ pub fn clear(gl: &glow::Context, screen_size_in_pixels: [u32; 2], clear_color: [
     }
 }
 
+/// Copy (and stretch) the contents of one framebuffer to another.
+///
+/// `None` is the screen framebuffer.
+unsafe fn blit_framebuffer(
+    gl: &glow::Context,
+    src: Option<glow::Framebuffer>,
+    [src_width, src_height]: [u32; 2],
+    dst: Option<glow::Framebuffer>,
+    [dst_width, dst_height]: [u32; 2],
+) {
+    unsafe {
+        // The scissor test applies to blits too:
+        gl.disable(glow::SCISSOR_TEST);
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, src);
+        gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, dst);
+        gl.blit_framebuffer(
+            0,
+            0,
+            src_width as i32,
+            src_height as i32,
+            0,
+            0,
+            dst_width as i32,
+            dst_height as i32,
+            glow::COLOR_BUFFER_BIT,
+            glow::LINEAR,
+        );
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+        gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, None);
+    }
+}
+
 impl Drop for Painter {
     fn drop(&mut self) {
         if !self.destroyed {
@@ -810,3 +1449,93 @@ fn set_clip_rect(
         );
     }
 }
+
+/// Should this texture update be spread over several frames (see [`Painter::set_texture_upload_budget`])?
+///
+/// Only full replacements larger than the budget are spread.
+fn spread_upload_over_frames(
+    delta: &egui::epaint::ImageDelta,
+    num_bytes: usize,
+    budget: Option<usize>,
+) -> bool {
+    delta.pos.is_none() && budget.is_some_and(|budget| budget < num_bytes)
+}
+
+/// Copy the sRGBA `src` image of size `[w, h]` into `dst`, an image `width` pixels wide, at `[x, y]`.
+fn patch_pixels(dst: &mut [u8], width: usize, [x, y]: [usize; 2], [w, h]: [usize; 2], src: &[u8]) {
+    let bytes_per_row = width * 4;
+    for row in 0..h {
+        let dst_start = (y + row) * bytes_per_row + x * 4;
+        let src_start = row * w * 4;
+        dst[dst_start..dst_start + w * 4].copy_from_slice(&src[src_start..src_start + w * 4]);
+    }
+}
+
+/// How many rows of a pending upload to upload this frame.
+///
+/// Always makes some progress, even with a tiny budget, and never goes past the last row.
+fn rows_to_upload([width, height]: [usize; 2], rows_uploaded: usize, budget: usize) -> usize {
+    let rows_left = height - rows_uploaded;
+    let bytes_per_row = (width * 4).max(1);
+    (budget / bytes_per_row).max(1).min(rows_left)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::{epaint::ImageDelta, Color32, ColorImage, FontImage, TextureOptions};
+
+    #[test]
+    fn only_large_full_updates_are_spread_over_frames() {
+        let budget = Some(1024);
+        let size = [64, 64];
+        let num_bytes = 64 * 64 * 4;
+
+        let color = ColorImage::new(size, Color32::RED);
+        let full = ImageDelta::full(color.clone(), TextureOptions::LINEAR);
+        assert!(spread_upload_over_frames(&full, num_bytes, budget));
+        assert!(!spread_upload_over_frames(&full, num_bytes, None));
+        assert!(!spread_upload_over_frames(&full, 1000, budget));
+
+        let partial = ImageDelta::partial([0, 0], color, TextureOptions::LINEAR);
+        assert!(!spread_upload_over_frames(&partial, num_bytes, budget));
+
+        let font = ImageDelta::full(FontImage::new(size), TextureOptions::LINEAR);
+        assert!(spread_upload_over_frames(&font, num_bytes, budget));
+
+        let new_glyphs = ImageDelta::partial([0, 0], FontImage::new(size), TextureOptions::LINEAR);
+        assert!(!spread_upload_over_frames(&new_glyphs, num_bytes, budget));
+    }
+
+    #[test]
+    fn patch_pixels_only_touches_the_patched_rect() {
+        // A 3x3 image, patched with a 2x1 image at [1, 2]:
+        let mut image = vec![0_u8; 3 * 3 * 4];
+        let patch = [1_u8; 2 * 4];
+        patch_pixels(&mut image, 3, [1, 2], [2, 1], &patch);
+
+        for (i, pixel) in image.chunks_exact(4).enumerate() {
+            let [x, y] = [i % 3, i / 3];
+            let expected = u8::from(y == 2 && 1 <= x);
+            assert_eq!(pixel, [expected; 4], "pixel {x},{y}");
+        }
+    }
+
+    #[test]
+    fn budgeted_upload_covers_every_row_exactly_once() {
+        let size = [100, 37];
+        for budget in [0, 1, 399, 400, 1000, 100 * 37 * 4, usize::MAX] {
+            let mut rows_uploaded = 0;
+            let mut frames = 0;
+            while rows_uploaded < size[1] {
+                let rows = rows_to_upload(size, rows_uploaded, budget);
+                assert!(rows >= 1, "no progress with budget {budget}");
+                rows_uploaded += rows;
+                frames += 1;
+                assert!(frames <= size[1]);
+            }
+            assert_eq!(rows_uploaded, size[1], "budget {budget}");
+            assert_eq!(rows_to_upload(size, rows_uploaded, budget), 0);
+        }
+    }
+}
@@ -388,6 +388,7 @@ impl<'a, State> Harness<'a, State> {
             modifiers,
             repeat: false,
             physical_key: None,
+            logical_key: Some(key),
         });
         self.input.events.push(egui::Event::Key {
             key,
@@ -395,6 +396,7 @@ impl<'a, State> Harness<'a, State> {
             modifiers,
             repeat: false,
             physical_key: None,
+            logical_key: Some(key),
         });
     }
 
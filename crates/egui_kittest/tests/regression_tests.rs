@@ -94,3 +94,342 @@ fn test_combobox() {
         }
     }
 }
+
+#[test]
+fn text_edit_ime_preedit_is_replaced_by_commit() {
+    let mut harness = Harness::new_ui_state(
+        |ui, text: &mut String| {
+            ui.text_edit_singleline(text).request_focus();
+        },
+        "ab".to_owned(),
+    );
+    harness.run();
+
+    fn send(harness: &mut Harness<'_, String>, event: egui::ImeEvent) {
+        harness.input_mut().events.push(egui::Event::Ime(event));
+        harness.run();
+    }
+
+    send(&mut harness, egui::ImeEvent::Enabled);
+    send(&mut harness, egui::ImeEvent::Preedit("n".to_owned()));
+    send(&mut harness, egui::ImeEvent::Preedit("に".to_owned()));
+    assert_eq!(harness.state(), "abに");
+
+    send(&mut harness, egui::ImeEvent::Preedit("にほ".to_owned()));
+    assert_eq!(harness.state(), "abにほ");
+
+    send(&mut harness, egui::ImeEvent::Commit("日本".to_owned()));
+    send(&mut harness, egui::ImeEvent::Disabled);
+    assert_eq!(harness.state(), "ab日本");
+}
+
+#[test]
+fn text_edit_ime_preedit_is_removed_on_focus_loss() {
+    let mut harness = Harness::new_ui_state(
+        |ui, (text, focus): &mut (String, bool)| {
+            let id = egui::Id::new("text_edit");
+            if !*focus {
+                // Lose focus before the text edit is shown, like a click elsewhere would:
+                ui.memory_mut(|mem| mem.surrender_focus(id));
+            }
+            let response = egui::TextEdit::singleline(text).id(id).show(ui).response;
+            if *focus {
+                response.request_focus();
+            }
+        },
+        ("ab".to_owned(), true),
+    );
+    harness.run();
+
+    for event in [
+        egui::ImeEvent::Enabled,
+        egui::ImeEvent::Preedit("にほ".to_owned()),
+    ] {
+        harness.input_mut().events.push(egui::Event::Ime(event));
+        harness.run();
+    }
+    assert_eq!(harness.state().0, "abにほ");
+
+    harness.state_mut().1 = false;
+    harness.run();
+    assert_eq!(harness.state().0, "ab");
+}
+
+#[test]
+fn text_edit_is_undone_after_losing_focus() {
+    let mut harness = Harness::new_ui_state(
+        |ui, (text, focus): &mut (String, bool)| {
+            let response = ui.text_edit_singleline(text);
+            if *focus {
+                response.request_focus();
+            } else {
+                response.surrender_focus();
+            }
+        },
+        ("ab".to_owned(), true),
+    );
+    harness.ctx.options_mut(|o| o.undo_with_keyboard = true);
+    harness.run();
+
+    harness
+        .input_mut()
+        .events
+        .push(egui::Event::Text("c".to_owned()));
+    harness.run();
+    let edited = harness.state().0.clone();
+    assert_ne!(edited, "ab");
+
+    harness.state_mut().1 = false;
+    harness.run();
+
+    harness.press_key_modifiers(egui::Modifiers::COMMAND, egui::Key::Z);
+    harness.run();
+    assert_eq!(harness.state().0, "ab");
+
+    harness.press_key_modifiers(
+        egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+        egui::Key::Z,
+    );
+    harness.run();
+    assert_eq!(harness.state().0, edited);
+}
+
+#[test]
+fn undo_with_keyboard_is_opt_in() {
+    let mut harness = Harness::new_ui_state(
+        |ui, (text, focus): &mut (String, bool)| {
+            let response = ui.text_edit_singleline(text);
+            if *focus {
+                response.request_focus();
+            } else {
+                response.surrender_focus();
+            }
+        },
+        ("ab".to_owned(), true),
+    );
+    harness.run();
+    harness
+        .input_mut()
+        .events
+        .push(egui::Event::Text("c".to_owned()));
+    harness.run();
+    harness.state_mut().1 = false;
+    harness.run();
+    let edited = harness.state().0.clone();
+
+    // The app may use Cmd+Z for its own undo:
+    harness.press_key_modifiers(egui::Modifiers::COMMAND, egui::Key::Z);
+    harness.run();
+    assert_eq!(harness.state().0, edited);
+
+    // The edit is still on the undo stack:
+    assert!(harness.ctx.undo());
+    harness.run();
+    assert_eq!(harness.state().0, "ab");
+}
+
+#[test]
+fn multi_touch_sliders_are_dragged_independently() {
+    struct State {
+        values: [f32; 2],
+        rects: [egui::Rect; 2],
+    }
+
+    let mut harness = Harness::new_ui_state(
+        |ui, state: &mut State| {
+            for (value, rect) in state.values.iter_mut().zip(&mut state.rects) {
+                let slider = egui::Slider::new(value, 0.0..=100.0).multi_touch(true);
+                *rect = ui.add(slider).rect;
+            }
+        },
+        State {
+            values: [0.0; 2],
+            rects: [egui::Rect::NOTHING; 2],
+        },
+    );
+    harness.run();
+
+    fn touch(harness: &mut Harness<'_, State>, id: u64, phase: egui::TouchPhase, pos: egui::Pos2) {
+        harness.input_mut().events.push(egui::Event::Touch {
+            device_id: egui::TouchDeviceId(0),
+            id: egui::TouchId(id),
+            phase,
+            pos,
+            force: None,
+        });
+    }
+
+    let rects = harness.state().rects;
+    for (id, rect) in rects.iter().enumerate() {
+        touch(
+            &mut harness,
+            id as u64,
+            egui::TouchPhase::Start,
+            rect.left_center(),
+        );
+    }
+    harness.run();
+    assert_eq!(harness.state().values, [0.0, 0.0]);
+
+    touch(&mut harness, 0, egui::TouchPhase::Move, rects[0].center());
+    touch(
+        &mut harness,
+        1,
+        egui::TouchPhase::Move,
+        rects[0].right_center(),
+    );
+    harness.run();
+    let values = harness.state().values;
+    assert!(0.0 < values[0] && values[0] < 100.0, "{values:?}");
+    assert!(
+        values[1] > 0.0,
+        "the second touch should keep dragging its own slider"
+    );
+
+    touch(
+        &mut harness,
+        1,
+        egui::TouchPhase::End,
+        rects[0].right_center(),
+    );
+    harness.run();
+    touch(
+        &mut harness,
+        0,
+        egui::TouchPhase::Move,
+        rects[0].left_center(),
+    );
+    harness.run();
+    assert_eq!(harness.state().values, [0.0, values[1]]);
+}
+
+#[test]
+fn middle_click_pastes_primary_selection_where_clicked() {
+    let mut harness = Harness::new_ui_state(
+        |ui, (texts, rects): &mut ([String; 2], [egui::Rect; 2])| {
+            for (text, rect) in texts.iter_mut().zip(rects) {
+                *rect = ui.text_edit_singleline(text).rect;
+            }
+        },
+        (
+            ["world".to_owned(), "other".to_owned()],
+            [egui::Rect::NOTHING; 2],
+        ),
+    );
+    harness.run();
+    let [first, second] = harness.state().1;
+
+    let middle_click_paste = |harness: &mut Harness<'_, _>, pos: egui::Pos2| {
+        harness
+            .input_mut()
+            .events
+            .push(egui::Event::PointerMoved(pos));
+        harness.run();
+        let events = &mut harness.input_mut().events;
+        events.push(egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Middle,
+            pressed: true,
+            modifiers: Default::default(),
+        });
+        events.push(egui::Event::PastePrimarySelection("hello ".to_owned()));
+        harness.run();
+        harness.input_mut().events.push(egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Middle,
+            pressed: false,
+            modifiers: Default::default(),
+        });
+        harness.run();
+    };
+
+    // Missing the text edits pastes nothing, even into a focused one:
+    harness
+        .get_all_by_role(Role::TextInput)
+        .nth(1)
+        .expect("missing text edit")
+        .focus();
+    harness.run();
+    middle_click_paste(&mut harness, second.center_bottom() + egui::vec2(0.0, 50.0));
+    assert_eq!(harness.state().0, ["world", "other"]);
+
+    // The unfocused text edit under the pointer gets it, at the click position:
+    middle_click_paste(&mut harness, first.left_center());
+    assert_eq!(harness.state().0, ["hello world", "other"]);
+    assert!(harness
+        .get_all_by_role(Role::TextInput)
+        .next()
+        .expect("missing text edit")
+        .is_focused());
+}
+
+#[test]
+fn selection_is_offered_for_middle_click_paste_once_the_drag_ends() {
+    let mut harness = Harness::new_ui_state(
+        |ui, (text, rect): &mut (String, egui::Rect)| {
+            *rect = ui.text_edit_singleline(text).rect;
+        },
+        ("hello world".to_owned(), egui::Rect::NOTHING),
+    );
+    harness.run();
+    let rect = harness.state().1;
+
+    let mut primary_selections = vec![];
+    let mut step = |harness: &mut Harness<'_, _>, event: egui::Event| {
+        harness.input_mut().events.push(event);
+        harness.step();
+        for command in &harness.output().platform_output.commands {
+            if let egui::OutputCommand::SetPrimarySelection(text) = command {
+                primary_selections.push(text.clone());
+            }
+        }
+    };
+
+    let start = rect.left_center();
+    step(&mut harness, egui::Event::PointerMoved(start));
+    step(
+        &mut harness,
+        egui::Event::PointerButton {
+            pos: start,
+            button: egui::PointerButton::Primary,
+            pressed: true,
+            modifiers: Default::default(),
+        },
+    );
+    for x in (10..=200).step_by(10) {
+        step(
+            &mut harness,
+            egui::Event::PointerMoved(start + egui::vec2(x as f32, 0.0)),
+        );
+    }
+    step(
+        &mut harness,
+        egui::Event::PointerButton {
+            pos: start + egui::vec2(200.0, 0.0),
+            button: egui::PointerButton::Primary,
+            pressed: false,
+            modifiers: Default::default(),
+        },
+    );
+    harness.run();
+
+    assert_eq!(primary_selections, ["hello world"]);
+}
+
+#[test]
+fn window_buttons_swap_sides_right_to_left() {
+    fn button_x(right_to_left: bool, label: &str) -> f64 {
+        let mut harness = Harness::new(|ctx| {
+            ctx.style_mut(|style| style.right_to_left = right_to_left);
+            egui::Window::new("Window")
+                .open(&mut true)
+                .show(ctx, |ui| ui.label("Content"));
+        });
+        harness.run();
+        let bounds = harness.get_by_label(label).bounding_box().unwrap();
+        bounds.x0
+    }
+
+    assert!(button_x(false, "Hide") < button_x(false, "Close window"));
+    assert!(button_x(true, "Close window") < button_x(true, "Hide"));
+}
@@ -56,11 +56,12 @@ pub use self::{
     roundingf::Roundingf,
     shadow::Shadow,
     shapes::{
-        CircleShape, CubicBezierShape, EllipseShape, PaintCallback, PaintCallbackInfo, PathShape,
-        QuadraticBezierShape, RectShape, Shape, TextShape,
+        CircleShape, CubicBezierShape, EllipseShape, FillRule, PaintCallback, PaintCallbackInfo,
+        PathShape, QuadraticBezierShape, RectShape, Shape, SvgPath, SvgPathError, TextShape,
+        VectorShape,
     },
     stats::PaintStats,
-    stroke::{PathStroke, Stroke, StrokeKind},
+    stroke::{DashPattern, LineCap, LineJoin, PathStroke, Stroke, StrokeKind},
     tessellator::{TessellationOptions, Tessellator},
     text::{FontFamily, FontId, Fonts, Galley},
     texture_atlas::TextureAtlas,
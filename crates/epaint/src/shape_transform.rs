@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use crate::{
     color, CircleShape, Color32, ColorMode, CubicBezierShape, EllipseShape, Mesh, PathShape,
-    QuadraticBezierShape, RectShape, Shape, TextShape,
+    QuadraticBezierShape, RectShape, Shape, TextShape, VectorShape,
 };
 
 /// Remember to handle [`Color32::PLACEHOLDER`] specially!
@@ -41,6 +41,14 @@ pub fn adjust_colors(
             closed: _,
             fill,
             stroke,
+        })
+        | Shape::Vector(VectorShape {
+            path: _,
+            pos: _,
+            scale: _,
+            fill,
+            fill_rule: _,
+            stroke,
         }) => {
             adjust_color(fill);
             adjust_color_mode(&mut stroke.color, adjust_color);
@@ -6,6 +6,7 @@ mod path_shape;
 mod rect_shape;
 mod shape;
 mod text_shape;
+mod vector_shape;
 
 pub use self::{
     bezier_shape::{CubicBezierShape, QuadraticBezierShape},
@@ -16,4 +17,5 @@ pub use self::{
     rect_shape::RectShape,
     shape::Shape,
     text_shape::TextShape,
+    vector_shape::{FillRule, SvgPath, SvgPathError, VectorShape},
 };
@@ -5,14 +5,14 @@ use std::sync::Arc;
 use emath::{pos2, Align2, Pos2, Rangef, Rect, TSTransform, Vec2};
 
 use crate::{
-    stroke::PathStroke,
+    stroke::{DashPattern, LineCap, PathStroke},
     text::{FontId, Fonts, Galley},
     Color32, Mesh, Rounding, Stroke, TextureId,
 };
 
 use super::{
     CircleShape, CubicBezierShape, EllipseShape, PaintCallback, PathShape, QuadraticBezierShape,
-    RectShape, TextShape,
+    RectShape, SvgPath, SvgPathError, TextShape, VectorShape,
 };
 
 /// A paint primitive such as a circle or a piece of text.
@@ -66,6 +66,11 @@ pub enum Shape {
     /// A cubic [Bézier Curve](https://en.wikipedia.org/wiki/B%C3%A9zier_curve).
     CubicBezier(CubicBezierShape),
 
+    /// A parsed [`SvgPath`], scaled and positioned on screen.
+    ///
+    /// Unlike [`Self::Path`], this is flattened at the resolution it is painted at.
+    Vector(VectorShape),
+
     /// Backend-specific painting.
     Callback(PaintCallback),
 }
@@ -82,6 +87,28 @@ fn shape_size() {
     );
 }
 
+#[test]
+fn dashed_path_keeps_stroke_style() {
+    let points = [pos2(0.0, 0.0), pos2(10.0, 0.0)];
+    let stroke = PathStroke::new(1.0, Color32::WHITE).cap(LineCap::Round);
+
+    let dashes = Shape::dashed_path(&points, false, stroke.clone(), &DashPattern::new(2.0, 2.0));
+    assert_eq!(dashes.len(), 3);
+    for dash in &dashes {
+        let Shape::Path(path) = dash else {
+            panic!("expected a path, got {dash:?}");
+        };
+        assert_eq!(path.stroke, stroke);
+        assert!(!path.closed);
+    }
+
+    // Dots need a cap to be visible:
+    let dots = DashPattern::dots(5.0);
+    assert_eq!(Shape::dashed_path(&points, false, stroke, &dots).len(), 3);
+    let butt = PathStroke::new(1.0, Color32::WHITE);
+    assert!(Shape::dashed_path(&points, false, butt, &dots).is_empty());
+}
+
 #[test]
 fn shape_impl_send_sync() {
     fn assert_send_sync<T: Send + Sync>() {}
@@ -243,6 +270,41 @@ impl Shape {
         );
     }
 
+    /// Turn a path into dashes following a [`DashPattern`].
+    ///
+    /// Each dash is an open [`PathShape`] with the given stroke,
+    /// so the [`PathStroke::cap`] and [`PathStroke::join`] apply to the ends and corners of every dash.
+    /// Dashes of zero length are drawn as dots, unless the cap is [`LineCap::Butt`].
+    ///
+    /// Change [`DashPattern::offset`] a little each frame to make the dashes march along the path.
+    pub fn dashed_path(
+        points: &[Pos2],
+        closed: bool,
+        stroke: impl Into<PathStroke>,
+        pattern: &DashPattern,
+    ) -> Vec<Self> {
+        let stroke = stroke.into();
+        if points.len() < 2 || stroke.is_empty() {
+            return vec![];
+        }
+        if !pattern.is_valid() {
+            let path = if closed {
+                PathShape::closed_line(points.to_vec(), stroke)
+            } else {
+                PathShape::line(points.to_vec(), stroke)
+            };
+            return vec![path.into()];
+        }
+
+        let mut dashes = vec![];
+        let skip_dots = stroke.cap == LineCap::Butt;
+        crate::tessellator::dashes_from_path(points, closed, pattern, skip_dots, &mut dashes);
+        dashes
+            .into_iter()
+            .map(|dash| PathShape::line(dash, stroke.clone()).into())
+            .collect()
+    }
+
     /// A convex polygon with a fill and optional stroke.
     ///
     /// The most performant winding order is clockwise.
@@ -334,6 +396,23 @@ impl Shape {
         Self::galley_with_override_text_color(pos, galley, text_color)
     }
 
+    /// Parse SVG path data (the `d` attribute of a `<path>`) and paint it
+    /// with its own coordinates in points.
+    ///
+    /// The flattened curves are cached in the [`SvgPath`], so if you paint the same path every frame,
+    /// parse it once with [`SvgPath::parse`] and reuse it with [`VectorShape::new`] instead.
+    ///
+    /// # Errors
+    /// Fails with a [`SvgPathError`] if the path data is malformed.
+    pub fn svg_path(
+        d: &str,
+        fill: impl Into<Color32>,
+        stroke: impl Into<PathStroke>,
+    ) -> Result<Self, SvgPathError> {
+        let path = SvgPath::parse(d)?;
+        Ok(VectorShape::new(path, fill, stroke).into())
+    }
+
     #[inline]
     pub fn mesh(mesh: impl Into<Arc<Mesh>>) -> Self {
         let mesh = mesh.into();
@@ -379,6 +458,7 @@ impl Shape {
             Self::Mesh(mesh) => mesh.calc_bounds(),
             Self::QuadraticBezier(bezier) => bezier.visual_bounding_rect(),
             Self::CubicBezier(bezier) => bezier.visual_bounding_rect(),
+            Self::Vector(vector_shape) => vector_shape.visual_bounding_rect(),
             Self::Callback(custom) => custom.rect,
         }
     }
@@ -482,6 +562,11 @@ impl Shape {
                 }
                 cubic_curve.stroke.width *= transform.scaling;
             }
+            Self::Vector(vector_shape) => {
+                vector_shape.pos = transform * vector_shape.pos;
+                vector_shape.scale *= transform.scaling;
+                vector_shape.stroke.width *= transform.scaling;
+            }
             Self::Callback(shape) => {
                 shape.rect = transform * shape.rect;
             }
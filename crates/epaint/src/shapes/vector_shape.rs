@@ -0,0 +1,825 @@
+use std::sync::Arc;
+
+use emath::{vec2, Pos2, Rect, Rot2, Vec2};
+
+use crate::{mutex::Mutex, Color32, CubicBezierShape, PathStroke, QuadraticBezierShape, Shape};
+
+/// How many different scales we keep flattened versions of, per [`SvgPath`].
+const MAX_CACHED_SCALES: usize = 8;
+
+/// Parsed [SVG path data](https://www.w3.org/TR/SVG2/paths.html#PathData),
+/// i.e. the contents of the `d` attribute of an SVG `<path>`.
+///
+/// All commands are supported, including arcs (which are converted to cubic Bézier curves).
+///
+/// The path remembers how it was flattened into line segments at the last few scales it was painted at,
+/// so parse it once and keep it around (e.g. in an [`Arc`]) rather than re-parsing it each frame.
+///
+/// See [`VectorShape`].
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SvgPath {
+    subpaths: Vec<SvgSubpath>,
+
+    /// Bounding box of all points, including control points.
+    bounds: Rect,
+
+    #[cfg_attr(feature = "serde", serde(skip, default = "empty_cache"))]
+    flattened: Mutex<Vec<(FlattenKey, Arc<[FlatSubpath]>)>>,
+}
+
+#[cfg(feature = "serde")]
+fn empty_cache() -> Mutex<Vec<(FlattenKey, Arc<[FlatSubpath]>)>> {
+    Mutex::new(Vec::new())
+}
+
+/// The bits of the quantized scale and of the tolerance.
+type FlattenKey = (u32, u32);
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct SvgSubpath {
+    start: Pos2,
+    segments: Vec<SvgSegment>,
+    closed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+enum SvgSegment {
+    Line(Pos2),
+    Quadratic([Pos2; 2]),
+    Cubic([Pos2; 3]),
+}
+
+impl SvgSegment {
+    /// The control points followed by the end point.
+    fn points(&self) -> &[Pos2] {
+        match self {
+            Self::Line(to) => std::slice::from_ref(to),
+            Self::Quadratic(points) => points,
+            Self::Cubic(points) => points,
+        }
+    }
+
+    fn end(&self) -> Pos2 {
+        self.points()[self.points().len() - 1]
+    }
+}
+
+/// A subpath flattened into line segments, in path coordinates.
+pub(crate) struct FlatSubpath {
+    pub points: Vec<Pos2>,
+    pub closed: bool,
+}
+
+impl SvgPath {
+    /// Parse the `d` attribute of an SVG `<path>`, e.g. `"M 0 0 L 10 0 L 5 8 Z"`.
+    ///
+    /// # Errors
+    /// Fails with a [`SvgPathError`] if the path data is malformed.
+    pub fn parse(d: &str) -> Result<Self, SvgPathError> {
+        let subpaths = Parser::new(d).parse()?;
+
+        let mut bounds = Rect::NOTHING;
+        for subpath in &subpaths {
+            bounds.extend_with(subpath.start);
+            for segment in &subpath.segments {
+                for p in segment.points() {
+                    bounds.extend_with(*p);
+                }
+            }
+        }
+
+        Ok(Self {
+            subpaths,
+            bounds,
+            flattened: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// A bounding box of the path, in path coordinates.
+    ///
+    /// This includes the control points of the curves, so it may be a bit larger than the path itself.
+    #[inline]
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// Is there nothing to paint?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.subpaths.is_empty()
+    }
+
+    /// The subpaths flattened into line segments, in path coordinates.
+    ///
+    /// `scale` is the number of physical pixels per path unit,
+    /// and `tolerance` is the max distance between the curves and the lines, in physical pixels.
+    pub(crate) fn flattened(&self, scale: f32, tolerance: f32) -> Arc<[FlatSubpath]> {
+        // Quantize the scale to quarter octaves, so that animated zooming doesn't thrash the cache.
+        // We round up, so we are never flattening more coarsely than asked for.
+        let quantized_scale = ((scale.max(f32::MIN_POSITIVE).log2() * 4.0).ceil() / 4.0).exp2();
+        let key = (quantized_scale.to_bits(), tolerance.to_bits());
+
+        let mut cache = self.flattened.lock();
+        if let Some((_, flattened)) = cache.iter().find(|(cached_key, _)| *cached_key == key) {
+            return flattened.clone();
+        }
+
+        let flattened: Arc<[FlatSubpath]> = self
+            .subpaths
+            .iter()
+            .map(|subpath| subpath.flatten(tolerance / quantized_scale))
+            .collect();
+
+        if MAX_CACHED_SCALES <= cache.len() {
+            cache.remove(0);
+        }
+        cache.push((key, flattened.clone()));
+        flattened
+    }
+}
+
+impl SvgSubpath {
+    fn flatten(&self, tolerance: f32) -> FlatSubpath {
+        let mut points = vec![self.start];
+        let mut current = self.start;
+        for segment in &self.segments {
+            match *segment {
+                SvgSegment::Line(to) => {
+                    points.push(to);
+                    current = to;
+                }
+                SvgSegment::Quadratic([control, to]) => {
+                    let curve = QuadraticBezierShape::from_points_stroke(
+                        [current, control, to],
+                        false,
+                        Color32::TRANSPARENT,
+                        PathStroke::NONE,
+                    );
+                    curve.for_each_flattened_with_t(tolerance, &mut |p, _t| points.push(p));
+                    current = to;
+                }
+                SvgSegment::Cubic([control_1, control_2, to]) => {
+                    let curve = CubicBezierShape::from_points_stroke(
+                        [current, control_1, control_2, to],
+                        false,
+                        Color32::TRANSPARENT,
+                        PathStroke::NONE,
+                    );
+                    curve.for_each_flattened_with_t(tolerance, &mut |p, _t| points.push(p));
+                    current = to;
+                }
+            }
+        }
+
+        if self.closed && 2 < points.len() && points.first() == points.last() {
+            // The closing line is implicit.
+            points.pop();
+        }
+
+        FlatSubpath {
+            points,
+            closed: self.closed,
+        }
+    }
+}
+
+impl std::fmt::Debug for SvgPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SvgPath")
+            .field("subpaths", &self.subpaths)
+            .field("bounds", &self.bounds)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for SvgPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.subpaths == other.subpaths
+    }
+}
+
+impl std::str::FromStr for SvgPath {
+    type Err = SvgPathError;
+
+    fn from_str(d: &str) -> Result<Self, Self::Err> {
+        Self::parse(d)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Why [`SvgPath::parse`] failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SvgPathError {
+    /// The path data must start with a move-to command (`M` or `m`).
+    MissingMoveTo,
+
+    /// Found a character that is not a path command where a command was expected.
+    UnknownCommand {
+        /// Byte offset into the path data.
+        offset: usize,
+        command: char,
+    },
+
+    /// Expected a number (or an arc flag) for the arguments of a command.
+    ExpectedNumber {
+        /// Byte offset into the path data.
+        offset: usize,
+    },
+}
+
+impl std::fmt::Display for SvgPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingMoveTo => f.write_str("SVG path data must start with a move-to command"),
+            Self::UnknownCommand { offset, command } => {
+                write!(f, "Unknown SVG path command {command:?} at byte {offset}")
+            }
+            Self::ExpectedNumber { offset } => {
+                write!(f, "Expected a number in SVG path data at byte {offset}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SvgPathError {}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+
+    subpaths: Vec<SvgSubpath>,
+
+    /// Where we are.
+    current: Pos2,
+
+    /// Where the current subpath started, and where `Z` takes us back to.
+    subpath_start: Pos2,
+
+    /// The last control point of the previous command, if it was a curve of the same order.
+    /// Used for the smooth `S` and `T` commands.
+    last_cubic_control: Option<Pos2>,
+    last_quadratic_control: Option<Pos2>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(d: &'a str) -> Self {
+        Self {
+            bytes: d.as_bytes(),
+            offset: 0,
+            subpaths: Vec::new(),
+            current: Pos2::ZERO,
+            subpath_start: Pos2::ZERO,
+            last_cubic_control: None,
+            last_quadratic_control: None,
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<SvgSubpath>, SvgPathError> {
+        let mut previous_command = None;
+
+        while !self.at_end() {
+            let byte = self.bytes[self.offset];
+            let command = if byte.is_ascii_alphabetic() {
+                self.offset += 1;
+                byte
+            } else if let Some(previous_command) = previous_command
+                .filter(|command| !matches!(command, b'Z' | b'z'))
+                .filter(|_| self.at_number())
+            {
+                // Repeated arguments repeat the command, except that a move-to becomes a line-to:
+                match previous_command {
+                    b'M' => b'L',
+                    b'm' => b'l',
+                    other => other,
+                }
+            } else {
+                return Err(SvgPathError::UnknownCommand {
+                    offset: self.offset,
+                    command: self.next_char(),
+                });
+            };
+
+            if previous_command.is_none() && !matches!(command, b'M' | b'm') {
+                return Err(SvgPathError::MissingMoveTo);
+            }
+
+            self.command(command)?;
+            previous_command = Some(command);
+        }
+
+        Ok(self.subpaths)
+    }
+
+    fn command(&mut self, command: u8) -> Result<(), SvgPathError> {
+        let relative = command.is_ascii_lowercase();
+        let origin = if relative {
+            self.current.to_vec2()
+        } else {
+            Vec2::ZERO
+        };
+
+        let mut cubic_control = None;
+        let mut quadratic_control = None;
+
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                let to = self.point()? + origin;
+                self.subpaths.push(SvgSubpath {
+                    start: to,
+                    segments: Vec::new(),
+                    closed: false,
+                });
+                self.current = to;
+                self.subpath_start = to;
+            }
+            b'L' => {
+                let to = self.point()? + origin;
+                self.push(SvgSegment::Line(to));
+            }
+            b'H' => {
+                let x = self.number()? + origin.x;
+                self.push(SvgSegment::Line(Pos2::new(x, self.current.y)));
+            }
+            b'V' => {
+                let y = self.number()? + origin.y;
+                self.push(SvgSegment::Line(Pos2::new(self.current.x, y)));
+            }
+            b'C' => {
+                let control_1 = self.point()? + origin;
+                let control_2 = self.point()? + origin;
+                let to = self.point()? + origin;
+                self.push(SvgSegment::Cubic([control_1, control_2, to]));
+                cubic_control = Some(control_2);
+            }
+            b'S' => {
+                let control_1 = self.reflected(self.last_cubic_control);
+                let control_2 = self.point()? + origin;
+                let to = self.point()? + origin;
+                self.push(SvgSegment::Cubic([control_1, control_2, to]));
+                cubic_control = Some(control_2);
+            }
+            b'Q' => {
+                let control = self.point()? + origin;
+                let to = self.point()? + origin;
+                self.push(SvgSegment::Quadratic([control, to]));
+                quadratic_control = Some(control);
+            }
+            b'T' => {
+                let control = self.reflected(self.last_quadratic_control);
+                let to = self.point()? + origin;
+                self.push(SvgSegment::Quadratic([control, to]));
+                quadratic_control = Some(control);
+            }
+            b'A' => {
+                let radii = vec2(self.number()?, self.number()?);
+                let x_axis_rotation = self.number()?.to_radians();
+                let large_arc = self.flag()?;
+                let sweep = self.flag()?;
+                let to = self.point()? + origin;
+                self.arc_to(radii, x_axis_rotation, large_arc, sweep, to);
+            }
+            b'Z' => {
+                if let Some(subpath) = self.subpaths.last_mut() {
+                    subpath.closed = true;
+                }
+                self.current = self.subpath_start;
+            }
+            _ => {
+                return Err(SvgPathError::UnknownCommand {
+                    offset: self.offset - 1,
+                    command: command as char,
+                });
+            }
+        }
+
+        self.last_cubic_control = cubic_control;
+        self.last_quadratic_control = quadratic_control;
+        Ok(())
+    }
+
+    /// Add a segment starting at the current position.
+    fn push(&mut self, segment: SvgSegment) {
+        if self.subpaths.last().map_or(true, |subpath| subpath.closed) {
+            // Drawing after a `Z` starts a new subpath where the closed one started.
+            self.subpaths.push(SvgSubpath {
+                start: self.current,
+                segments: Vec::new(),
+                closed: false,
+            });
+        }
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.segments.push(segment);
+        }
+        self.current = segment.end();
+    }
+
+    /// The reflection of the last control point about the current position,
+    /// or the current position if the previous command was not a curve of the same kind.
+    fn reflected(&self, last_control: Option<Pos2>) -> Pos2 {
+        last_control.map_or(self.current, |control| {
+            self.current + (self.current - control)
+        })
+    }
+
+    /// Convert an elliptical arc to cubic Bézier curves.
+    ///
+    /// See <https://www.w3.org/TR/SVG2/implnote.html#ArcImplementationNotes>.
+    fn arc_to(
+        &mut self,
+        radii: Vec2,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Pos2,
+    ) {
+        let from = self.current;
+        if from == to {
+            return;
+        }
+
+        let mut radii = radii.abs();
+        if radii.x == 0.0 || radii.y == 0.0 {
+            self.push(SvgSegment::Line(to));
+            return;
+        }
+
+        let rot = Rot2::from_angle(x_axis_rotation);
+        let p = rot.inverse() * ((from - to) / 2.0);
+
+        // Scale up the radii if they are too small to reach from one point to the other:
+        let lambda = (p.x / radii.x).powi(2) + (p.y / radii.y).powi(2);
+        if 1.0 < lambda {
+            radii *= lambda.sqrt();
+        }
+
+        let (rx2, ry2) = (radii.x * radii.x, radii.y * radii.y);
+        let numerator = rx2 * ry2 - rx2 * p.y * p.y - ry2 * p.x * p.x;
+        let denominator = rx2 * p.y * p.y + ry2 * p.x * p.x;
+        let mut coefficient = (numerator / denominator).max(0.0).sqrt();
+        if large_arc == sweep {
+            coefficient = -coefficient;
+        }
+        let center_prime = coefficient * vec2(radii.x * p.y / radii.y, -radii.y * p.x / radii.x);
+        let center = from + (to - from) / 2.0 + rot * center_prime;
+
+        let start_angle = ((p - center_prime) / radii).angle();
+        let end_angle = ((-p - center_prime) / radii).angle();
+        let mut sweep_angle = end_angle - start_angle;
+        if sweep && sweep_angle < 0.0 {
+            sweep_angle += std::f32::consts::TAU;
+        } else if !sweep && 0.0 < sweep_angle {
+            sweep_angle -= std::f32::consts::TAU;
+        }
+
+        // Each curve covers at most a quarter of the ellipse:
+        let num_curves = (sweep_angle.abs() / std::f32::consts::FRAC_PI_2)
+            .ceil()
+            .max(1.0) as usize;
+        let step = sweep_angle / num_curves as f32;
+        let handle_length = 4.0 / 3.0 * (step / 4.0).tan();
+        let on_ellipse = |unit: Vec2| center + rot * (unit * radii);
+        // The tangent of the unit circle, in the direction of increasing angle:
+        let tangent = |unit: Vec2| vec2(-unit.y, unit.x);
+
+        for i in 0..num_curves {
+            let angle_0 = start_angle + step * i as f32;
+            let angle_1 = angle_0 + step;
+            let unit_0 = Vec2::angled(angle_0);
+            let unit_1 = Vec2::angled(angle_1);
+            let control_1 = on_ellipse(unit_0 + handle_length * tangent(unit_0));
+            let control_2 = on_ellipse(unit_1 - handle_length * tangent(unit_1));
+            let end = if i + 1 == num_curves {
+                to // avoid accumulating rounding errors
+            } else {
+                on_ellipse(unit_1)
+            };
+            self.push(SvgSegment::Cubic([control_1, control_2, end]));
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(byte) = self.bytes.get(self.offset) {
+            if byte.is_ascii_whitespace() || *byte == b',' {
+                self.offset += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.bytes.len() <= self.offset
+    }
+
+    fn at_number(&mut self) -> bool {
+        self.skip_separators();
+        matches!(
+            self.bytes.get(self.offset),
+            Some(b'0'..=b'9' | b'-' | b'+' | b'.')
+        )
+    }
+
+    fn next_char(&self) -> char {
+        std::str::from_utf8(&self.bytes[self.offset..])
+            .ok()
+            .and_then(|rest| rest.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.offset;
+        while self.bytes.get(self.offset).is_some_and(u8::is_ascii_digit) {
+            self.offset += 1;
+        }
+        self.offset - start
+    }
+
+    fn number(&mut self) -> Result<f32, SvgPathError> {
+        self.skip_separators();
+        let start = self.offset;
+        let error = SvgPathError::ExpectedNumber { offset: start };
+
+        if matches!(self.bytes.get(self.offset), Some(b'-' | b'+')) {
+            self.offset += 1;
+        }
+        let mut num_digits = self.skip_digits();
+        if self.bytes.get(self.offset) == Some(&b'.') {
+            self.offset += 1;
+            num_digits += self.skip_digits();
+        }
+        if num_digits == 0 {
+            self.offset = start;
+            return Err(error);
+        }
+
+        // Only treat an `e` as an exponent if it is followed by digits,
+        // so that we don't swallow a command letter.
+        if matches!(self.bytes.get(self.offset), Some(b'e' | b'E')) {
+            let mantissa_end = self.offset;
+            self.offset += 1;
+            if matches!(self.bytes.get(self.offset), Some(b'-' | b'+')) {
+                self.offset += 1;
+            }
+            if self.skip_digits() == 0 {
+                self.offset = mantissa_end;
+            }
+        }
+
+        // Everything we consumed is ASCII, so this is a valid `str`:
+        std::str::from_utf8(&self.bytes[start..self.offset])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or(error)
+    }
+
+    fn point(&mut self) -> Result<Pos2, SvgPathError> {
+        Ok(Pos2::new(self.number()?, self.number()?))
+    }
+
+    /// Arc flags are a single `0` or `1`, and need no separator after them.
+    fn flag(&mut self) -> Result<bool, SvgPathError> {
+        self.skip_separators();
+        let flag = match self.bytes.get(self.offset) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => {
+                return Err(SvgPathError::ExpectedNumber {
+                    offset: self.offset,
+                })
+            }
+        };
+        self.offset += 1;
+        Ok(flag)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// How to decide which parts of a path are inside it, like the `fill-rule` of SVG.
+///
+/// This matters for paths with several subpaths (e.g. holes) and for self-intersecting subpaths.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FillRule {
+    /// A point is inside if the path winds around it a non-zero number of times.
+    ///
+    /// This is the default in SVG. A subpath going the other way than its surroundings is a hole.
+    #[default]
+    NonZero,
+
+    /// A point is inside if a ray from it crosses the path an odd number of times.
+    ///
+    /// Every subpath inside another one is a hole, whichever way it goes.
+    EvenOdd,
+}
+
+/// An [`SvgPath`] placed on screen, with a fill and/or stroke.
+///
+/// The path is flattened at a tolerance that depends on the physical size it is painted at,
+/// so it stays crisp at any zoom level or `pixels_per_point`,
+/// and the flattened path is cached in the [`SvgPath`] so it is only redone when that size changes.
+///
+/// Any path can be filled, including concave and self-intersecting ones and ones with holes,
+/// see [`FillRule`].
+/// Open subpaths are closed before they are filled, but not before they are stroked.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct VectorShape {
+    pub path: Arc<SvgPath>,
+
+    /// Where the origin of the path coordinates ends up, in points.
+    pub pos: Pos2,
+
+    /// Points per path unit.
+    pub scale: f32,
+
+    pub fill: Color32,
+
+    /// Which parts of [`Self::path`] are filled.
+    pub fill_rule: FillRule,
+
+    /// Color and thickness of the outline, in points (not scaled by [`Self::scale`]).
+    pub stroke: PathStroke,
+}
+
+impl VectorShape {
+    /// Paint the path at its own coordinates, one point per path unit.
+    ///
+    /// Use [`Self::fit_to_rect`] to place it somewhere else.
+    #[inline]
+    pub fn new(
+        path: impl Into<Arc<SvgPath>>,
+        fill: impl Into<Color32>,
+        stroke: impl Into<PathStroke>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            pos: Pos2::ZERO,
+            scale: 1.0,
+            fill: fill.into(),
+            fill_rule: FillRule::default(),
+            stroke: stroke.into(),
+        }
+    }
+
+    /// Which parts of the path are filled. Default: [`FillRule::NonZero`].
+    #[inline]
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    /// Scale and move the path so that its [`SvgPath::bounds`] fits in the middle of `rect`,
+    /// keeping its aspect ratio.
+    ///
+    /// This is what you want for icons.
+    #[inline]
+    pub fn fit_to_rect(mut self, rect: Rect) -> Self {
+        let bounds = self.path.bounds();
+        if !bounds.is_positive() {
+            self.pos = rect.center() - bounds.center().to_vec2();
+            self.scale = 1.0;
+            return self;
+        }
+        self.scale = (rect.width() / bounds.width()).min(rect.height() / bounds.height());
+        self.pos = rect.center() - self.scale * bounds.center().to_vec2();
+        self
+    }
+
+    /// Go from path coordinates to screen coordinates (points).
+    #[inline]
+    pub fn transform_pos(&self, pos: Pos2) -> Pos2 {
+        self.pos + self.scale * pos.to_vec2()
+    }
+
+    /// The visual bounding rectangle (includes stroke width)
+    pub fn visual_bounding_rect(&self) -> Rect {
+        if self.path.is_empty() || (self.fill == Color32::TRANSPARENT && self.stroke.is_empty()) {
+            Rect::NOTHING
+        } else {
+            let bounds = self.path.bounds();
+            Rect::from_two_pos(
+                self.transform_pos(bounds.min),
+                self.transform_pos(bounds.max),
+            )
+            .expand(self.stroke.width / 2.0)
+        }
+    }
+}
+
+impl From<VectorShape> for Shape {
+    #[inline(always)]
+    fn from(shape: VectorShape) -> Self {
+        Self::Vector(shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use emath::pos2;
+
+    use super::*;
+
+    fn only_subpath(d: &str) -> SvgSubpath {
+        let path = SvgPath::parse(d).unwrap();
+        assert_eq!(path.subpaths.len(), 1);
+        path.subpaths[0].clone()
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let subpath = only_subpath("M10,20 l 5 0 h-5 V 0 z");
+        assert_eq!(subpath.start, pos2(10.0, 20.0));
+        assert!(subpath.closed);
+        assert_eq!(
+            subpath.segments,
+            vec![
+                SvgSegment::Line(pos2(15.0, 20.0)),
+                SvgSegment::Line(pos2(10.0, 20.0)),
+                SvgSegment::Line(pos2(10.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_implicit_commands_and_compact_numbers() {
+        // Extra pairs after a move-to are line-tos, and numbers need no separators:
+        let subpath = only_subpath("m1-2 3.5.5-1e1,0");
+        assert_eq!(subpath.start, pos2(1.0, -2.0));
+        assert_eq!(
+            subpath.segments,
+            vec![
+                SvgSegment::Line(pos2(4.5, -1.5)),
+                SvgSegment::Line(pos2(-5.5, -1.5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_smooth_curves() {
+        let subpath = only_subpath("M0 0 C 0 10 10 10 10 0 S 20 -10 20 0");
+        assert_eq!(
+            subpath.segments[1],
+            SvgSegment::Cubic([pos2(10.0, -10.0), pos2(20.0, -10.0), pos2(20.0, 0.0)])
+        );
+
+        let subpath = only_subpath("M0 0 Q 5 10 10 0 T 20 0");
+        assert_eq!(
+            subpath.segments[1],
+            SvgSegment::Quadratic([pos2(15.0, -10.0), pos2(20.0, 0.0)])
+        );
+    }
+
+    #[test]
+    fn test_parse_arc() {
+        // Half a circle with radius 10, with flags written without separators:
+        let subpath = only_subpath("M0 0 A10 10 0 0120 0");
+        assert_eq!(subpath.segments.len(), 2);
+        let SvgSegment::Cubic([_, _, end]) = subpath.segments[1] else {
+            panic!("Expected a cubic curve");
+        };
+        assert_eq!(end, pos2(20.0, 0.0));
+
+        let flat = subpath.flatten(0.01);
+        for p in &flat.points {
+            let distance = (*p - pos2(10.0, 0.0)).length();
+            assert!((distance - 10.0).abs() < 0.05, "{p:?} is not on the circle");
+
+            // With the sweep flag set, the arc goes clockwise on screen, i.e. over the top:
+            assert!(p.y <= 0.0, "{p:?} is on the wrong half of the circle");
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(SvgPath::parse("L 1 2"), Err(SvgPathError::MissingMoveTo));
+        assert_eq!(
+            SvgPath::parse("M 1 2 X"),
+            Err(SvgPathError::UnknownCommand {
+                offset: 6,
+                command: 'X'
+            })
+        );
+        assert_eq!(
+            SvgPath::parse("M 1"),
+            Err(SvgPathError::ExpectedNumber { offset: 3 })
+        );
+        assert!(SvgPath::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_flatten_cache() {
+        let path = SvgPath::parse("M0 0 Q 5 10 10 0").unwrap();
+        let a = path.flattened(2.0, 0.1);
+        let b = path.flattened(2.0, 0.1);
+        assert!(Arc::ptr_eq(&a, &b));
+
+        let zoomed = path.flattened(16.0, 0.1);
+        assert!(!Arc::ptr_eq(&a, &zoomed));
+        assert!(a[0].points.len() < zoomed[0].points.len());
+    }
+}
@@ -75,8 +75,121 @@ impl Default for StrokeKind {
     }
 }
 
+/// How the ends of an open path, and of each dash, are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LineCap {
+    /// The stroke ends right at the end point.
+    #[default]
+    Butt,
+
+    /// A half circle around the end point.
+    Round,
+
+    /// The stroke continues half its width past the end point.
+    Square,
+}
+
+/// How the corners of a path are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LineJoin {
+    /// The outer edges continue until they meet.
+    ///
+    /// Corners sharper than a right angle are cut off, so they don't grow too long.
+    #[default]
+    Miter,
+
+    /// The corner is cut off straight.
+    Bevel,
+
+    /// The corner is rounded.
+    Round,
+}
+
+/// A repeating pattern of dashes and gaps, see [`crate::Shape::dashed_path`].
+///
+/// A dash of length zero is drawn as a dot if the stroke has [`LineCap::Round`] or [`LineCap::Square`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct DashPattern {
+    /// The length of each dash, in points.
+    ///
+    /// Dashes and gaps alternate, starting with `dash_lengths[0]`, then `gap_lengths[0]`,
+    /// then `dash_lengths[1]`, and so on, and then the pattern repeats.
+    /// Must be the same length as [`Self::gap_lengths`].
+    pub dash_lengths: Vec<f32>,
+
+    /// The length of the gap after each dash, in points.
+    pub gap_lengths: Vec<f32>,
+
+    /// How far into the pattern the path starts, in points.
+    ///
+    /// Increase it a little each frame to make the dashes march along the path.
+    pub offset: f32,
+}
+
+impl DashPattern {
+    /// Dashes of the same length, with gaps of the same length between them.
+    #[inline]
+    pub fn new(dash_length: f32, gap_length: f32) -> Self {
+        Self::from_lengths(vec![dash_length], vec![gap_length])
+    }
+
+    /// Dots (dashes of length zero), with `spacing` between their centers.
+    ///
+    /// Use it with a stroke with [`LineCap::Round`] or [`LineCap::Square`].
+    #[inline]
+    pub fn dots(spacing: f32) -> Self {
+        Self::new(0.0, spacing)
+    }
+
+    /// Dashes and gaps of different lengths, see [`Self::dash_lengths`].
+    pub fn from_lengths(dash_lengths: Vec<f32>, gap_lengths: Vec<f32>) -> Self {
+        assert_eq!(
+            dash_lengths.len(),
+            gap_lengths.len(),
+            "There must be a gap after each dash"
+        );
+        Self {
+            dash_lengths,
+            gap_lengths,
+            offset: 0.0,
+        }
+    }
+
+    /// How far into the pattern the path starts, in points.
+    #[inline]
+    pub fn offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// The length of the whole pattern, before it repeats.
+    pub fn period(&self) -> f32 {
+        self.dash_lengths.iter().sum::<f32>() + self.gap_lengths.iter().sum::<f32>()
+    }
+
+    /// Can the pattern be drawn?
+    ///
+    /// If not, the path is drawn without dashes.
+    pub(crate) fn is_valid(&self) -> bool {
+        !self.dash_lengths.is_empty()
+            && self.dash_lengths.len() == self.gap_lengths.len()
+            && self
+                .dash_lengths
+                .iter()
+                .chain(&self.gap_lengths)
+                .all(|length| length.is_finite() && 0.0 <= *length)
+            && 0.0 < self.period()
+            && self.offset.is_finite()
+    }
+}
+
 /// Describes the width and color of paths. The color can either be solid or provided by a callback. For more information, see [`ColorMode`]
 ///
+/// The stroke can also have rounded ends and corners.
+///
 /// The default stroke is the same as [`Stroke::NONE`].
 #[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -84,6 +197,12 @@ pub struct PathStroke {
     pub width: f32,
     pub color: ColorMode,
     pub kind: StrokeKind,
+
+    /// How the ends of the path, and of each dash, are drawn.
+    pub cap: LineCap,
+
+    /// How the corners of the path are drawn.
+    pub join: LineJoin,
 }
 
 impl PathStroke {
@@ -92,6 +211,8 @@ impl PathStroke {
         width: 0.0,
         color: ColorMode::TRANSPARENT,
         kind: StrokeKind::Middle,
+        cap: LineCap::Butt,
+        join: LineJoin::Miter,
     };
 
     #[inline]
@@ -99,7 +220,7 @@ impl PathStroke {
         Self {
             width: width.into(),
             color: ColorMode::Solid(color.into()),
-            kind: StrokeKind::default(),
+            ..Self::NONE
         }
     }
 
@@ -114,7 +235,7 @@ impl PathStroke {
         Self {
             width: width.into(),
             color: ColorMode::UV(Arc::new(callback)),
-            kind: StrokeKind::default(),
+            ..Self::NONE
         }
     }
 
@@ -142,11 +263,29 @@ impl PathStroke {
         }
     }
 
+    /// How the ends of the path, and of each dash, are drawn.
+    #[inline]
+    pub fn cap(self, cap: LineCap) -> Self {
+        Self { cap, ..self }
+    }
+
+    /// How the corners of the path are drawn.
+    #[inline]
+    pub fn join(self, join: LineJoin) -> Self {
+        Self { join, ..self }
+    }
+
     /// True if width is zero or color is solid and transparent
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.width <= 0.0 || self.color == ColorMode::TRANSPARENT
     }
+
+    /// Does this stroke have the default ends and corners?
+    #[inline]
+    pub(crate) fn is_plain(&self) -> bool {
+        self.cap == LineCap::Butt && self.join == LineJoin::Miter
+    }
 }
 
 impl<Color> From<(f32, Color)> for PathStroke
@@ -165,11 +304,7 @@ impl From<Stroke> for PathStroke {
             // Important, since we use the stroke color when doing feathering of the fill!
             Self::NONE
         } else {
-            Self {
-                width: value.width,
-                color: ColorMode::Solid(value.color),
-                kind: StrokeKind::default(),
-            }
+            Self::new(value.width, value.color)
         }
     }
 }
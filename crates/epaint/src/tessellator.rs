@@ -8,13 +8,13 @@
 use crate::texture_atlas::PreparedDisc;
 use crate::{
     color, emath, stroke, CircleShape, ClippedPrimitive, ClippedShape, Color32, CubicBezierShape,
-    EllipseShape, Mesh, PathShape, Primitive, QuadraticBezierShape, RectShape, Rounding, Shape,
-    Stroke, TextShape, TextureId, Vertex, WHITE_UV,
+    EllipseShape, FillRule, Mesh, PathShape, Primitive, QuadraticBezierShape, RectShape, Rounding,
+    Shape, Stroke, TextShape, TextureId, VectorShape, Vertex, WHITE_UV,
 };
 use emath::{pos2, remap, vec2, GuiRounding as _, NumExt, Pos2, Rect, Rot2, Vec2};
 
 use self::color::ColorMode;
-use self::stroke::PathStroke;
+use self::stroke::{DashPattern, LineCap, LineJoin, PathStroke};
 
 // ----------------------------------------------------------------------------
 
@@ -403,28 +403,160 @@ impl Path {
                     n1 = n0;
                 }
 
-                let normal = (n0 + n1) / 2.0;
-                let length_sq = normal.length_sq();
-                let right_angle_length_sq = 0.5;
-                let sharper_than_a_right_angle = length_sq < right_angle_length_sq;
+                self.add_join(points[i], n0, n1, 0.0, LineJoin::Miter);
+
+                n0 = n1;
+            }
+            self.add_point(
+                points[n - 1],
+                (points[n - 1] - points[n - 2]).normalized().rot90(),
+            );
+        }
+    }
+
+    /// Like [`Self::add_open_points`], but with the given ends and corners.
+    ///
+    /// `width` is the width of the stroke, which decides how far the ends reach past the end points.
+    pub fn add_open_points_with_style(
+        &mut self,
+        points: &[Pos2],
+        width: f32,
+        cap: LineCap,
+        join: LineJoin,
+    ) {
+        let n = points.len();
+        assert!(n >= 2);
+
+        let half_width = 0.5 * width;
+        let start_dir = first_direction(points.iter().copied());
+        let end_dir = -first_direction(points.iter().rev().copied());
+        let (mut first, mut last) = (points[0], points[n - 1]);
+        if cap == LineCap::Square {
+            first -= half_width * start_dir;
+            last += half_width * end_dir;
+        }
+
+        let start_normal = start_dir.rot90();
+        if cap == LineCap::Round {
+            // Half a circle around the back of the start point:
+            let steps = half_circle_steps(half_width);
+            self.reserve(n + 2 * steps);
+            self.add_arc(
+                first,
+                -start_normal,
+                -start_dir,
+                std::f32::consts::PI,
+                steps,
+            );
+        } else {
+            self.reserve(n);
+        }
+        self.add_point(first, start_normal);
+
+        let mut n0 = start_normal;
+        for i in 1..n - 1 {
+            let n1 = (points[i + 1] - points[i]).normalized().rot90();
+            if n1 == Vec2::ZERO {
+                // Duplicated point
+                continue;
+            }
+            self.add_join(points[i], n0, n1, half_width, join);
+            n0 = n1;
+        }
+
+        let end_normal = end_dir.rot90();
+        self.add_point(last, end_normal);
+        if cap == LineCap::Round {
+            let steps = half_circle_steps(half_width);
+            self.add_arc(last, end_normal, end_dir, std::f32::consts::PI, steps);
+        }
+    }
+
+    /// Like [`Self::add_line_loop`], but with the given corners.
+    ///
+    /// `width` is the width of the stroke, which decides how many points round corners get.
+    pub fn add_line_loop_with_join(&mut self, points: &[Pos2], width: f32, join: LineJoin) {
+        if join == LineJoin::Miter {
+            self.add_line_loop(points);
+            return;
+        }
+
+        let n = points.len();
+        assert!(n >= 2);
+        self.reserve(n);
+
+        let mut n0 = (points[0] - points[n - 1]).normalized().rot90();
+        if n0 == Vec2::ZERO {
+            n0 = -first_direction(points.iter().rev().copied()).rot90();
+        }
+        for i in 0..n {
+            let next_i = if i + 1 == n { 0 } else { i + 1 };
+            let n1 = (points[next_i] - points[i]).normalized().rot90();
+            if n1 == Vec2::ZERO {
+                // Duplicated point
+                continue;
+            }
+            self.add_join(points[i], n0, n1, 0.5 * width, join);
+            n0 = n1;
+        }
+    }
+
+    /// Add the corner at `pos` between a segment with normal `n0` and the next one, with normal `n1`.
+    ///
+    /// `half_width` is half the width of the stroke, used for round corners.
+    fn add_join(&mut self, pos: Pos2, n0: Vec2, n1: Vec2, half_width: f32, join: LineJoin) {
+        let normal = (n0 + n1) / 2.0;
+        let length_sq = normal.length_sq();
+        let nearly_straight = 0.999 < length_sq;
+        let right_angle_length_sq = 0.5;
+        let sharper_than_a_right_angle = length_sq < right_angle_length_sq;
+
+        match join {
+            _ if nearly_straight => {
+                self.add_point(pos, normal / length_sq);
+            }
+            LineJoin::Miter => {
                 if sharper_than_a_right_angle {
                     // cut off the sharp corner
                     let center_normal = normal.normalized();
                     let n0c = (n0 + center_normal) / 2.0;
                     let n1c = (n1 + center_normal) / 2.0;
-                    self.add_point(points[i], n0c / n0c.length_sq());
-                    self.add_point(points[i], n1c / n1c.length_sq());
+                    self.add_point(pos, n0c / n0c.length_sq());
+                    self.add_point(pos, n1c / n1c.length_sq());
                 } else {
                     // miter join
-                    self.add_point(points[i], normal / length_sq);
+                    self.add_point(pos, normal / length_sq);
                 }
-
-                n0 = n1;
             }
-            self.add_point(
-                points[n - 1],
-                (points[n - 1] - points[n - 2]).normalized().rot90(),
-            );
+            LineJoin::Bevel => {
+                self.add_point(pos, n0);
+                self.add_point(pos, n1);
+            }
+            LineJoin::Round => {
+                let angle = n0.dot(n1).clamp(-1.0, 1.0).acos();
+                let mut towards_n1 = (n1 - n0.dot(n1) * n0).normalized();
+                if towards_n1 == Vec2::ZERO {
+                    // The line turns right back, so go round the front:
+                    towards_n1 = -n0.rot90();
+                }
+                let steps = angle / std::f32::consts::PI * half_circle_steps(half_width) as f32;
+                let steps = (steps.ceil() as usize).max(1);
+                self.add_point(pos, n0);
+                self.add_arc(pos, n0, towards_n1, angle, steps);
+                self.add_point(pos, n1);
+            }
+        }
+    }
+
+    /// Add points at `pos` with normals turning `angle` radians from `from` towards `towards`,
+    /// not including the first and last normals.
+    ///
+    /// `towards` must be a unit vector at a right angle to `from`.
+    /// The stroke around such points is a fan, which gives round ends and corners.
+    fn add_arc(&mut self, pos: Pos2, from: Vec2, towards: Vec2, angle: f32, steps: usize) {
+        for i in 1..steps {
+            let (sin, cos) = (angle * i as f32 / steps as f32).sin_cos();
+            self.add_point(pos, cos * from + sin * towards);
         }
     }
 
@@ -523,6 +655,130 @@ impl Path {
     }
 }
 
+/// The direction from the first point to the first one that is different from it.
+fn first_direction(mut points: impl Iterator<Item = Pos2>) -> Vec2 {
+    let Some(first) = points.next() else {
+        return Vec2::X;
+    };
+    points
+        .find(|point| *point != first)
+        .map_or(Vec2::X, |point| (point - first).normalized())
+}
+
+/// How many steps to use for a half circle, so that it looks round.
+///
+/// Same cutoffs as in [`Path::add_circle`].
+fn half_circle_steps(radius: f32) -> usize {
+    if radius <= 2.0 {
+        4
+    } else if radius <= 5.0 {
+        8
+    } else if radius < 18.0 {
+        16
+    } else if radius < 50.0 {
+        32
+    } else {
+        64
+    }
+}
+
+/// Split a line into the dashes of a [`DashPattern`], and add them to `dashes`.
+///
+/// Dashes of zero length (dots) get a tiny length in the direction of the line,
+/// so that their ends can be drawn. They are left out if `skip_dots` is set.
+pub(crate) fn dashes_from_path(
+    points: &[Pos2],
+    closed: bool,
+    pattern: &DashPattern,
+    skip_dots: bool,
+    dashes: &mut Vec<Vec<Pos2>>,
+) {
+    const DOT_LENGTH: f32 = 0.01;
+
+    // Dashes are at even indices, gaps at odd ones:
+    let lengths: Vec<f32> = pattern
+        .dash_lengths
+        .iter()
+        .zip(&pattern.gap_lengths)
+        .flat_map(|(&dash, &gap)| [dash, gap])
+        .collect();
+    let is_dash = |i: usize| i % 2 == 0;
+
+    // Find where in the pattern the line starts:
+    let mut i = 0;
+    let mut phase = pattern.offset.rem_euclid(pattern.period());
+    while 0.0 < phase && lengths[i] <= phase {
+        phase -= lengths[i];
+        i = (i + 1) % lengths.len();
+    }
+    let mut remaining = lengths[i] - phase;
+
+    fn push_point(dash: &mut Vec<Pos2>, point: Pos2) {
+        if dash.last() != Some(&point) {
+            dash.push(point);
+        }
+    }
+
+    let first_dash = dashes.len();
+    let starts_in_dash = is_dash(i);
+    let mut dash = vec![];
+    if starts_in_dash {
+        dash.push(points[0]);
+    }
+
+    let n = points.len();
+    let num_segments = if closed { n } else { n - 1 };
+    for segment in 0..num_segments {
+        let (a, b) = (points[segment], points[(segment + 1) % n]);
+        let length = a.distance(b);
+        let direction = if 0.0 < length {
+            (b - a) / length
+        } else {
+            Vec2::X
+        };
+
+        let mut t = 0.0;
+        while remaining <= length - t {
+            t += remaining;
+            let point = a + t * direction;
+            if is_dash(i) {
+                push_point(&mut dash, point);
+                if dash.len() == 1 {
+                    if skip_dots {
+                        dash.clear();
+                    } else {
+                        dash.push(point + DOT_LENGTH * direction);
+                    }
+                }
+                if !dash.is_empty() {
+                    dashes.push(std::mem::take(&mut dash));
+                }
+            } else {
+                dash = vec![point];
+            }
+            i = (i + 1) % lengths.len();
+            remaining = lengths[i];
+        }
+        remaining -= length - t;
+
+        if is_dash(i) {
+            push_point(&mut dash, b);
+        }
+    }
+
+    if is_dash(i) {
+        if closed && starts_in_dash && first_dash < dashes.len() {
+            // The dash goes across the start of the loop, so continue into the first dash:
+            for point in dashes.remove(first_dash) {
+                push_point(&mut dash, point);
+            }
+        }
+        if 2 <= dash.len() {
+            dashes.push(dash);
+        }
+    }
+}
+
 pub mod path {
     //! Helpers for constructing paths
     use crate::Rounding;
@@ -1408,6 +1664,7 @@ impl Tessellator {
                 self.tessellate_quadratic_bezier(&quadratic_shape, out);
             }
             Shape::CubicBezier(cubic_shape) => self.tessellate_cubic_bezier(&cubic_shape, out),
+            Shape::Vector(vector_shape) => self.tessellate_vector(&vector_shape, out),
             Shape::Callback(_) => {
                 panic!("Shape::Callback passed to Tessellator");
             }
@@ -1656,6 +1913,10 @@ impl Tessellator {
             self.scratchpad_path
                 .fill(self.feathering, *fill, stroke, out);
         }
+        if !stroke.is_plain() {
+            self.stroke_styled_path(points, *closed, stroke, out);
+            return;
+        }
         let typ = if *closed {
             PathType::Closed
         } else {
@@ -1964,6 +2225,82 @@ impl Tessellator {
         }
     }
 
+    /// Tessellate a single [`VectorShape`] into a [`Mesh`].
+    ///
+    /// The curves are flattened with [`TessellationOptions::bezier_tolerance`] measured in physical pixels,
+    /// so the result is equally smooth at any scale and `pixels_per_point`.
+    ///
+    /// * `vector_shape`: the shape to tessellate.
+    /// * `out`: triangles are appended to this.
+    pub fn tessellate_vector(&mut self, vector_shape: &VectorShape, out: &mut Mesh) {
+        if self.options.coarse_tessellation_culling
+            && !vector_shape
+                .visual_bounding_rect()
+                .intersects(self.clip_rect)
+        {
+            return;
+        }
+
+        let VectorShape {
+            path,
+            pos: _,
+            scale,
+            fill,
+            fill_rule,
+            stroke,
+        } = vector_shape;
+
+        let flattened = path.flattened(
+            *scale * self.pixels_per_point,
+            self.options.bezier_tolerance,
+        );
+
+        let mut points = std::mem::take(&mut self.scratchpad_points);
+        points.clear();
+        let mut subpaths = Vec::with_capacity(flattened.len());
+        for subpath in flattened.iter() {
+            let start = points.len();
+            points.extend(
+                subpath
+                    .points
+                    .iter()
+                    .map(|p| vector_shape.transform_pos(*p)),
+            );
+            subpaths.push(start..points.len());
+        }
+
+        if *fill != Color32::TRANSPARENT {
+            // Like in SVG, open subpaths are closed for filling, but not for stroking.
+            if let [subpath] = subpaths.as_slice() {
+                if is_convex(&points[subpath.clone()]) {
+                    // The fast path, with feathering on all sides:
+                    self.tessellate_bezier_complete(
+                        &points[subpath.clone()],
+                        *fill,
+                        true,
+                        &PathStroke::NONE,
+                        out,
+                    );
+                } else {
+                    fill_polygons(&points, &subpaths, *fill_rule, *fill, self.feathering, out);
+                }
+            } else {
+                fill_polygons(&points, &subpaths, *fill_rule, *fill, self.feathering, out);
+            }
+        }
+
+        for (subpath, range) in flattened.iter().zip(&subpaths) {
+            self.tessellate_bezier_complete(
+                &points[range.clone()],
+                Color32::TRANSPARENT,
+                subpath.closed,
+                stroke,
+                out,
+            );
+        }
+        self.scratchpad_points = points;
+    }
+
     fn tessellate_bezier_complete(
         &mut self,
         points: &[Pos2],
@@ -1990,6 +2327,10 @@ impl Tessellator {
             self.scratchpad_path
                 .fill(self.feathering, fill, stroke, out);
         }
+        if !stroke.is_plain() {
+            self.stroke_styled_path(points, closed, stroke, out);
+            return;
+        }
         let typ = if closed {
             PathType::Closed
         } else {
@@ -1998,6 +2339,36 @@ impl Tessellator {
         self.scratchpad_path
             .stroke(self.feathering, typ, stroke, out);
     }
+
+    /// Stroke a line through the points, with the ends and corners of the stroke.
+    fn stroke_styled_path(
+        &mut self,
+        points: &[Pos2],
+        closed: bool,
+        stroke: &PathStroke,
+        out: &mut Mesh,
+    ) {
+        if stroke.is_empty() {
+            return;
+        }
+
+        self.scratchpad_path.clear();
+        if closed {
+            self.scratchpad_path
+                .add_line_loop_with_join(points, stroke.width, stroke.join);
+            self.scratchpad_path
+                .stroke_closed(self.feathering, stroke, out);
+        } else {
+            self.scratchpad_path.add_open_points_with_style(
+                points,
+                stroke.width,
+                stroke.cap,
+                stroke.join,
+            );
+            self.scratchpad_path
+                .stroke_open(self.feathering, stroke, out);
+        }
+    }
 }
 
 fn round_line_segment(coord: &mut f32, stroke: &Stroke, pixels_per_point: f32) {
@@ -2026,6 +2397,199 @@ fn is_nearest_integer_odd(width: f32) -> bool {
     (width * 0.5 + 0.25).fract() > 0.5
 }
 
+/// Is this a simple convex polygon, i.e. one that [`Path::fill`] can fill?
+fn is_convex(points: &[Pos2]) -> bool {
+    let n = points.len();
+    if n < 3 {
+        return true; // Nothing to fill
+    }
+
+    let mut sign = 0.0;
+    let mut total_turn = 0.0;
+    for i in 0..n {
+        let a = points[i];
+        let b = points[(i + 1) % n];
+        let c = points[(i + 2) % n];
+        let (ab, bc) = (b - a, c - b);
+        let cross = ab.x * bc.y - ab.y * bc.x;
+        if cross != 0.0 {
+            if sign * cross < 0.0 {
+                return false; // Turns both ways
+            }
+            sign = cross;
+        }
+        total_turn += cross.atan2(ab.dot(bc));
+    }
+
+    // Turning the same way all around is not enough: a star turns around more than once.
+    total_turn.abs() < 3.0 * std::f32::consts::PI
+}
+
+/// One edge of a polygon, going from `top` to `bottom`.
+struct FillEdge {
+    top: Pos2,
+    bottom: Pos2,
+
+    /// `+1` if the polygon goes down along this edge, `-1` if it goes up.
+    winding: i32,
+}
+
+impl FillEdge {
+    fn x_at(&self, y: f32) -> f32 {
+        let t = (y - self.top.y) / (self.bottom.y - self.top.y);
+        emath::lerp(self.top.x..=self.bottom.x, t)
+    }
+}
+
+/// Fill the polygons `points[range]` for each range in `polygons`, according to `fill_rule`.
+///
+/// Unlike [`Path::fill`] this handles concave and self-intersecting polygons, and holes.
+/// The area is cut into horizontal bands at every vertex and every crossing of two edges,
+/// so that the inside of each band is a number of trapezoids.
+/// The sloped sides of those get a `feathering` wide anti-aliasing fringe on their outside.
+fn fill_polygons(
+    points: &[Pos2],
+    polygons: &[std::ops::Range<usize>],
+    fill_rule: FillRule,
+    color: Color32,
+    feathering: f32,
+    out: &mut Mesh,
+) {
+    let mut edges = vec![];
+    for range in polygons {
+        let polygon = &points[range.clone()];
+        for (i, &a) in polygon.iter().enumerate() {
+            let b = polygon[(i + 1) % polygon.len()];
+            if a.y < b.y {
+                edges.push(FillEdge {
+                    top: a,
+                    bottom: b,
+                    winding: 1,
+                });
+            } else if b.y < a.y {
+                edges.push(FillEdge {
+                    top: b,
+                    bottom: a,
+                    winding: -1,
+                });
+            } // Horizontal edges never cross a band
+        }
+    }
+    if edges.len() < 2 {
+        return;
+    }
+    edges.sort_by(|a, b| a.top.y.total_cmp(&b.top.y));
+
+    let mut ys: Vec<f32> = edges.iter().flat_map(|e| [e.top.y, e.bottom.y]).collect();
+    for (i, a) in edges.iter().enumerate() {
+        for b in &edges[i + 1..] {
+            if a.bottom.y <= b.top.y {
+                break; // Sorted by top, so no later edge overlaps `a` vertically either
+            }
+            if let Some(y) = edges_cross_at(a, b) {
+                ys.push(y);
+            }
+        }
+    }
+    ys.sort_by(f32::total_cmp);
+    ys.dedup();
+
+    let is_inside = |winding: i32| match fill_rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    };
+
+    let mut next_edge = 0;
+    let mut active: Vec<&FillEdge> = vec![];
+    let mut crossings: Vec<(f32, f32, &FillEdge)> = vec![];
+    for band in ys.windows(2) {
+        let (y0, y1) = (band[0], band[1]);
+        active.retain(|edge| y1 <= edge.bottom.y);
+        while next_edge < edges.len() && edges[next_edge].top.y <= y0 {
+            if y1 <= edges[next_edge].bottom.y {
+                active.push(&edges[next_edge]);
+            }
+            next_edge += 1;
+        }
+
+        crossings.clear();
+        crossings.extend(
+            active
+                .iter()
+                .map(|edge| (edge.x_at(y0), edge.x_at(y1), *edge)),
+        );
+        // The edges don't cross within a band, so the middle gives the order:
+        crossings.sort_by(|a, b| (a.0 + a.1).total_cmp(&(b.0 + b.1)));
+
+        let mut winding = 0;
+        let mut span_start = None;
+        for &(x0, x1, edge) in &crossings {
+            let was_inside = is_inside(winding);
+            winding += edge.winding;
+            match (was_inside, is_inside(winding)) {
+                (false, true) => span_start = Some((x0, x1)),
+                (true, false) => {
+                    if let Some((left0, left1)) = span_start.take() {
+                        add_trapezoid(
+                            [pos2(left0, y0), pos2(x0, y0), pos2(x1, y1), pos2(left1, y1)],
+                            color,
+                            feathering,
+                            out,
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The height at which two edges cross, if they cross somewhere other than their ends.
+fn edges_cross_at(a: &FillEdge, b: &FillEdge) -> Option<f32> {
+    let top = a.top.y.max(b.top.y);
+    let bottom = a.bottom.y.min(b.bottom.y);
+    if bottom <= top {
+        return None;
+    }
+    let top_dx = a.x_at(top) - b.x_at(top);
+    let bottom_dx = a.x_at(bottom) - b.x_at(bottom);
+    if (top_dx < 0.0) == (bottom_dx < 0.0) || top_dx == 0.0 || bottom_dx == 0.0 {
+        return None;
+    }
+    let t = top_dx / (top_dx - bottom_dx);
+    Some(emath::lerp(top..=bottom, t))
+}
+
+/// Fill the trapezoid `[top_left, top_right, bottom_right, bottom_left]`,
+/// with a `feathering` wide fringe outside its left and right sides.
+fn add_trapezoid(corners: [Pos2; 4], color: Color32, feathering: f32, out: &mut Mesh) {
+    let [top_left, top_right, bottom_right, bottom_left] = corners;
+
+    let idx = out.vertices.len() as u32;
+    for corner in corners {
+        out.colored_vertex(corner, color);
+    }
+    out.add_triangle(idx, idx + 1, idx + 2);
+    out.add_triangle(idx, idx + 2, idx + 3);
+
+    if 0.0 < feathering {
+        let left_normal = -(bottom_left - top_left).normalized().rot90();
+        let right_normal = (bottom_right - top_right).normalized().rot90();
+        for (top, bottom, normal) in [
+            (top_left, bottom_left, left_normal),
+            (top_right, bottom_right, right_normal),
+        ] {
+            let idx = out.vertices.len() as u32;
+            out.colored_vertex(top, color);
+            out.colored_vertex(bottom, color);
+            out.colored_vertex(top + feathering * normal, Color32::TRANSPARENT);
+            out.colored_vertex(bottom + feathering * normal, Color32::TRANSPARENT);
+            out.add_triangle(idx, idx + 1, idx + 2);
+            out.add_triangle(idx + 1, idx + 2, idx + 3);
+        }
+    }
+}
+
 #[test]
 fn test_is_nearest_integer_odd() {
     assert!(is_nearest_integer_odd(0.6));
@@ -2129,7 +2693,10 @@ impl Tessellator {
 
                 Shape::Path(path_shape) => 32 < path_shape.points.len(),
 
-                Shape::QuadraticBezier(_) | Shape::CubicBezier(_) | Shape::Ellipse(_) => true,
+                Shape::QuadraticBezier(_)
+                | Shape::CubicBezier(_)
+                | Shape::Ellipse(_)
+                | Shape::Vector(_) => true,
 
                 Shape::Noop
                 | Shape::Text(_)
@@ -2265,3 +2832,183 @@ fn path_bounding_box() {
         );
     }
 }
+
+#[test]
+fn dashes_from_path_follow_the_pattern() {
+    let line = [pos2(0.0, 0.0), pos2(10.0, 0.0), pos2(10.0, 10.0)];
+
+    let mut dashes = vec![];
+    dashes_from_path(&line, false, &DashPattern::new(4.0, 2.0), true, &mut dashes);
+    assert_eq!(
+        dashes,
+        vec![
+            vec![pos2(0.0, 0.0), pos2(4.0, 0.0)],
+            vec![pos2(6.0, 0.0), pos2(10.0, 0.0)],
+            vec![pos2(10.0, 2.0), pos2(10.0, 6.0)],
+            vec![pos2(10.0, 8.0), pos2(10.0, 10.0)],
+        ]
+    );
+
+    // The offset moves the pattern back along the line, and dashes go around corners:
+    let mut dashes = vec![];
+    let pattern = DashPattern::new(4.0, 2.0).offset(3.0);
+    dashes_from_path(&line, false, &pattern, true, &mut dashes);
+    assert_eq!(dashes[0], vec![pos2(0.0, 0.0), pos2(1.0, 0.0)]);
+    assert_eq!(
+        dashes[2],
+        vec![pos2(9.0, 0.0), pos2(10.0, 0.0), pos2(10.0, 3.0)]
+    );
+
+    // Dots are left out, unless the ends are drawn past the end points:
+    let mut dashes = vec![];
+    dashes_from_path(&line, false, &DashPattern::dots(5.0), true, &mut dashes);
+    assert!(dashes.is_empty());
+    dashes_from_path(&line, false, &DashPattern::dots(5.0), false, &mut dashes);
+    assert_eq!(dashes.len(), 5);
+
+    // A dash across the start of a closed loop is joined into one:
+    let square = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    let mut dashes = vec![];
+    let pattern = DashPattern::new(4.0, 6.0).offset(2.0);
+    dashes_from_path(&square, true, &pattern, true, &mut dashes);
+    assert_eq!(dashes.len(), 4);
+    assert_eq!(
+        dashes[3],
+        vec![pos2(0.0, 2.0), pos2(0.0, 0.0), pos2(2.0, 0.0)]
+    );
+}
+
+#[test]
+fn convex_polygons_are_told_apart() {
+    let square = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    assert!(is_convex(&square));
+    assert!(is_convex(&[
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(0.0, 10.0)
+    ]));
+
+    let l_shape = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 5.0),
+        pos2(5.0, 5.0),
+        pos2(5.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    assert!(!is_convex(&l_shape));
+
+    // Turns the same way at every corner, but goes around twice:
+    let pentagram: Vec<Pos2> = (0..5)
+        .map(|i| {
+            let angle = i as f32 * 2.0 * std::f32::consts::TAU / 5.0;
+            pos2(angle.cos(), angle.sin())
+        })
+        .collect();
+    assert!(!is_convex(&pentagram));
+}
+
+#[test]
+fn fill_polygons_follows_the_fill_rule() {
+    fn filled_area(polygons: &[&[Pos2]], fill_rule: FillRule) -> f32 {
+        let mut points = vec![];
+        let mut ranges = vec![];
+        for polygon in polygons {
+            ranges.push(points.len()..points.len() + polygon.len());
+            points.extend_from_slice(polygon);
+        }
+        let mut mesh = Mesh::default();
+        fill_polygons(&points, &ranges, fill_rule, Color32::WHITE, 0.0, &mut mesh);
+        mesh.indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].pos);
+                ((b - a).x * (c - a).y - (b - a).y * (c - a).x).abs() / 2.0
+            })
+            .sum()
+    }
+
+    let square = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    let hole = [
+        pos2(3.0, 3.0),
+        pos2(7.0, 3.0),
+        pos2(7.0, 7.0),
+        pos2(3.0, 7.0),
+    ];
+    let reversed_hole = [
+        pos2(3.0, 3.0),
+        pos2(3.0, 7.0),
+        pos2(7.0, 7.0),
+        pos2(7.0, 3.0),
+    ];
+
+    assert_eq!(filled_area(&[&square], FillRule::NonZero), 100.0);
+    assert_eq!(filled_area(&[&square, &hole], FillRule::NonZero), 100.0);
+    assert_eq!(
+        filled_area(&[&square, &reversed_hole], FillRule::NonZero),
+        84.0
+    );
+    assert_eq!(filled_area(&[&square, &hole], FillRule::EvenOdd), 84.0);
+    assert_eq!(
+        filled_area(&[&square, &reversed_hole], FillRule::EvenOdd),
+        84.0
+    );
+
+    let l_shape = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 0.0),
+        pos2(10.0, 5.0),
+        pos2(5.0, 5.0),
+        pos2(5.0, 10.0),
+        pos2(0.0, 10.0),
+    ];
+    assert_eq!(filled_area(&[&l_shape], FillRule::NonZero), 75.0);
+
+    // Two triangles meeting where the edges cross:
+    let bowtie = [
+        pos2(0.0, 0.0),
+        pos2(10.0, 10.0),
+        pos2(10.0, 0.0),
+        pos2(0.0, 10.0),
+    ];
+    let area = filled_area(&[&bowtie], FillRule::NonZero);
+    assert!((area - 50.0).abs() < 1e-3, "area: {area}");
+
+    // The anti-aliasing fringe goes on the outside:
+    let mut mesh = Mesh::default();
+    fill_polygons(
+        &l_shape,
+        std::slice::from_ref(&(0..l_shape.len())),
+        FillRule::NonZero,
+        Color32::WHITE,
+        1.0,
+        &mut mesh,
+    );
+    for vertex in &mesh.vertices {
+        let Pos2 { x, y } = vertex.pos;
+        if vertex.color == Color32::TRANSPARENT {
+            let strictly_inside =
+                0.0 < x && x < 10.0 && 0.0 < y && y < 10.0 && !(5.0 <= x && 5.0 <= y);
+            assert!(!strictly_inside, "{vertex:?}");
+        } else {
+            let inside_or_on_edge =
+                0.0 <= x && x <= 10.0 && 0.0 <= y && y <= 10.0 && !(5.0 < x && 5.0 < y);
+            assert!(inside_or_on_edge, "{vertex:?}");
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Putting right-to-left text in visual order.
+//!
+//! The bidi levels come from the `shaping` feature; without it, every glyph is left-to-right
+//! and these are no-ops.
+
+use super::{Glyph, Row};
+
+/// Move the glyphs of a row into visual order,
+/// following rule L2 of the [Unicode bidi algorithm](https://unicode.org/reports/tr9/#L2).
+///
+/// Only the x positions change, [`Row::glyphs`] stays in logical order.
+pub(crate) fn reorder_row(row: &mut Row) {
+    let glyphs = &mut row.glyphs;
+    let Some(max_level) = glyphs.iter().map(|glyph| glyph.bidi_level).max() else {
+        return;
+    };
+    if max_level == 0 {
+        return;
+    }
+    let min_level = glyphs
+        .iter()
+        .map(|glyph| glyph.bidi_level)
+        .min()
+        .unwrap_or(0);
+    let lowest_odd_level = min_level | 1;
+
+    let mut visual_order: Vec<usize> = (0..glyphs.len()).collect();
+    for level in (lowest_odd_level..=max_level).rev() {
+        let mut i = 0;
+        while i < visual_order.len() {
+            if glyphs[visual_order[i]].bidi_level < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < visual_order.len() && level <= glyphs[visual_order[i]].bidi_level {
+                i += 1;
+            }
+            visual_order[start..i].reverse();
+        }
+    }
+
+    // The space each glyph takes up, including kerning and justification:
+    let widths: Vec<f32> = (0..glyphs.len())
+        .map(|i| match glyphs.get(i + 1) {
+            Some(next) => next.pos.x - glyphs[i].pos.x,
+            None => glyphs[i].advance_width,
+        })
+        .collect();
+
+    let mut x = glyphs[0].pos.x;
+    for i in visual_order {
+        glyphs[i].pos.x = x;
+        x += widths[i];
+    }
+}
+
+/// The glyphs of a row sorted by x, for drawing backgrounds and underlines.
+pub(crate) fn visual_glyphs(row: &Row) -> std::borrow::Cow<'_, [Glyph]> {
+    if row.glyphs.iter().any(Glyph::is_rtl) {
+        let mut glyphs = row.glyphs.clone();
+        glyphs.sort_by(|a, b| a.pos.x.total_cmp(&b.pos.x));
+        std::borrow::Cow::Owned(glyphs)
+    } else {
+        std::borrow::Cow::Borrowed(&row.glyphs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use emath::{pos2, Rect};
+
+    use super::*;
+    use crate::text::{font::UvRect, RowVisuals};
+
+    /// A row of glyphs of the given widths and bidi levels, laid out left-to-right.
+    fn row(glyphs: &[(char, f32, u8)]) -> Row {
+        let mut x = 0.0;
+        let glyphs = glyphs
+            .iter()
+            .map(|&(chr, advance_width, bidi_level)| {
+                let glyph = Glyph {
+                    chr,
+                    pos: pos2(x, 0.0),
+                    advance_width,
+                    line_height: 1.0,
+                    font_ascent: 1.0,
+                    font_height: 1.0,
+                    font_impl_ascent: 1.0,
+                    font_impl_height: 1.0,
+                    uv_rect: UvRect::default(),
+                    section_index: 0,
+                    bidi_level,
+                };
+                x += advance_width;
+                glyph
+            })
+            .collect();
+        Row {
+            section_index_at_start: 0,
+            glyphs,
+            rect: Rect::NOTHING,
+            visuals: RowVisuals::default(),
+            ends_with_newline: false,
+        }
+    }
+
+    fn x_positions(row: &Row) -> Vec<f32> {
+        row.glyphs.iter().map(|glyph| glyph.pos.x).collect()
+    }
+
+    #[test]
+    fn left_to_right_rows_are_untouched() {
+        let mut ltr = row(&[('a', 1.0, 0), ('b', 2.0, 0), ('c', 3.0, 0)]);
+        reorder_row(&mut ltr);
+        assert_eq!(x_positions(&ltr), [0.0, 1.0, 3.0]);
+        assert!(matches!(visual_glyphs(&ltr), std::borrow::Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn right_to_left_runs_are_reversed() {
+        // "ab" followed by three right-to-left letters and then "c":
+        let mut mixed = row(&[
+            ('a', 1.0, 0),
+            ('b', 1.0, 0),
+            ('א', 1.0, 1),
+            ('ב', 2.0, 1),
+            ('ג', 3.0, 1),
+            ('c', 1.0, 0),
+        ]);
+        reorder_row(&mut mixed);
+
+        // The glyphs stay in logical order, but the run is drawn right-to-left:
+        assert_eq!(mixed.text(), "abאבגc");
+        assert_eq!(x_positions(&mixed), [0.0, 1.0, 7.0, 5.0, 2.0, 8.0]);
+
+        let visual: String = visual_glyphs(&mixed)
+            .iter()
+            .map(|glyph| glyph.chr)
+            .collect();
+        assert_eq!(visual, "abגבאc");
+    }
+
+    #[test]
+    fn left_to_right_text_inside_right_to_left_text_keeps_its_order() {
+        // A right-to-left paragraph (level 1) with an embedded number (level 2):
+        let mut nested = row(&[('א', 1.0, 1), ('1', 1.0, 2), ('2', 1.0, 2), ('ב', 1.0, 1)]);
+        reorder_row(&mut nested);
+
+        let visual: String = visual_glyphs(&nested)
+            .iter()
+            .map(|glyph| glyph.chr)
+            .collect();
+        assert_eq!(visual, "ב12א");
+    }
+}
@@ -80,6 +80,23 @@ pub struct FontImpl {
     pixels_per_point: f32,
     glyph_info_cache: RwLock<ahash::HashMap<char, GlyphInfo>>, // TODO(emilk): standard Mutex
     atlas: Arc<Mutex<TextureAtlas>>,
+
+    /// The font file, for shaping.
+    #[cfg(feature = "shaping")]
+    font_data: Option<Arc<super::FontData>>,
+
+    /// The shaped clusters we have drawn to the atlas.
+    #[cfg(feature = "shaping")]
+    cluster_cache: RwLock<ahash::HashMap<Vec<ShapedGlyph>, UvRect>>,
+}
+
+/// A glyph in a shaped cluster, positioned relative to the start of the cluster in font units.
+#[cfg(feature = "shaping")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct ShapedGlyph {
+    id: u16,
+    x: i32,
+    y: i32,
 }
 
 impl FontImpl {
@@ -130,9 +147,20 @@ impl FontImpl {
             pixels_per_point,
             glyph_info_cache: Default::default(),
             atlas,
+            #[cfg(feature = "shaping")]
+            font_data: None,
+            #[cfg(feature = "shaping")]
+            cluster_cache: Default::default(),
         }
     }
 
+    /// Keep the font file around, so that text in this font can be shaped.
+    #[cfg(feature = "shaping")]
+    pub(crate) fn with_font_data(mut self, font_data: Arc<super::FontData>) -> Self {
+        self.font_data = Some(font_data);
+        self
+    }
+
     /// Code points that will always be replaced by the replacement character.
     ///
     /// See also [`invisible_char`].
@@ -322,6 +350,166 @@ impl FontImpl {
     }
 }
 
+#[cfg(feature = "shaping")]
+impl FontImpl {
+    /// Shape a run of text with [`rustybuzz`], in the given direction.
+    ///
+    /// Returns the byte range in `text` and the glyph of each cluster, in logical order.
+    /// A cluster is one or more characters that are drawn together, e.g. a ligature.
+    ///
+    /// Clusters that are just the usual glyph of a single character are left out,
+    /// since those look the same laid out glyph by glyph.
+    ///
+    /// Returns `None` if the font file is not available.
+    pub(crate) fn shape(
+        &self,
+        text: &str,
+        rtl: bool,
+    ) -> Option<Vec<(std::ops::Range<usize>, GlyphInfo)>> {
+        use ab_glyph::{Font as _, ScaleFont as _};
+
+        let font_data = self.font_data.as_ref()?;
+        let face = rustybuzz::Face::from_slice(&font_data.font, font_data.index)?;
+
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.set_direction(if rtl {
+            rustybuzz::Direction::RightToLeft
+        } else {
+            rustybuzz::Direction::LeftToRight
+        });
+        buffer.guess_segment_properties();
+        let output = rustybuzz::shape(&face, &[], buffer);
+
+        // The glyphs come out in visual order, so clusters are reversed for right-to-left text:
+        let mut clusters: Vec<(usize, Vec<ShapedGlyph>, i32)> = vec![];
+        for (info, pos) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+            let cluster = info.cluster as usize;
+            if clusters.last().map_or(true, |(c, _, _)| *c != cluster) {
+                clusters.push((cluster, vec![], 0));
+            }
+            let (_, glyphs, advance) = clusters.last_mut().unwrap();
+            glyphs.push(ShapedGlyph {
+                id: info.glyph_id as u16,
+                x: *advance + pos.x_offset,
+                y: pos.y_offset,
+            });
+            *advance += pos.x_advance;
+        }
+        clusters.sort_by_key(|(cluster, _, _)| *cluster);
+
+        let cluster_ends: Vec<usize> = clusters
+            .iter()
+            .skip(1)
+            .map(|(cluster, _, _)| *cluster)
+            .chain([text.len()])
+            .collect();
+
+        let points_per_unit = self
+            .ab_glyph_font
+            .as_scaled(self.scale_in_pixels as f32)
+            .h_scale_factor()
+            / self.pixels_per_point;
+
+        Some(
+            clusters
+                .into_iter()
+                .zip(cluster_ends)
+                .map(|((start, glyphs, advance), end)| (start..end, glyphs, advance))
+                .filter(|(cluster, glyphs, _)| !self.is_unshaped(&text[cluster.clone()], glyphs))
+                .map(|(cluster, glyphs, advance)| {
+                    let uv_rect = self.cluster_uv_rect(glyphs);
+                    let glyph_info = GlyphInfo {
+                        id: ab_glyph::GlyphId(0),
+                        advance_width: advance as f32 * points_per_unit,
+                        uv_rect,
+                    };
+                    (cluster, glyph_info)
+                })
+                .collect(),
+        )
+    }
+
+    /// Is this cluster just the usual glyph of a single character, in its usual place?
+    fn is_unshaped(&self, cluster_text: &str, glyphs: &[ShapedGlyph]) -> bool {
+        use ab_glyph::Font as _;
+
+        let mut chars = cluster_text.chars();
+        match (chars.next(), chars.next(), glyphs) {
+            (Some(chr), None, [glyph]) => {
+                glyph.x == 0 && glyph.y == 0 && self.ab_glyph_font.glyph_id(chr).0 == glyph.id
+            }
+            _ => false,
+        }
+    }
+
+    /// Draw all the glyphs of a shaped cluster into one spot in the atlas.
+    fn cluster_uv_rect(&self, glyphs: Vec<ShapedGlyph>) -> UvRect {
+        use ab_glyph::{Font as _, ScaleFont as _};
+
+        if let Some(uv_rect) = self.cluster_cache.read().get(&glyphs) {
+            return *uv_rect;
+        }
+
+        let scale = self.scale_in_pixels as f32;
+        let pixels_per_unit = self.ab_glyph_font.as_scaled(scale).h_scale_factor();
+
+        let outlines: Vec<_> = glyphs
+            .iter()
+            .filter_map(|glyph| {
+                let position = ab_glyph::point(
+                    glyph.x as f32 * pixels_per_unit,
+                    -glyph.y as f32 * pixels_per_unit,
+                );
+                let glyph = ab_glyph::GlyphId(glyph.id).with_scale_and_position(scale, position);
+                self.ab_glyph_font.outline_glyph(glyph)
+            })
+            .collect();
+
+        let bounds = outlines
+            .iter()
+            .map(|outline| outline.px_bounds())
+            .reduce(|a, b| ab_glyph::Rect {
+                min: ab_glyph::point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+                max: ab_glyph::point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+            });
+
+        let uv_rect = bounds.map_or_else(UvRect::default, |bb| {
+            let width = bb.width() as usize;
+            let height = bb.height() as usize;
+            if width == 0 || height == 0 {
+                return UvRect::default();
+            }
+
+            let atlas = &mut self.atlas.lock();
+            let (atlas_pos, image) = atlas.allocate((width, height));
+            for outline in &outlines {
+                let min = outline.px_bounds().min;
+                let dx = atlas_pos.0 + (min.x - bb.min.x) as usize;
+                let dy = atlas_pos.1 + (min.y - bb.min.y) as usize;
+                outline.draw(|x, y, v| {
+                    if 0.0 < v {
+                        let pixel = &mut image[(dx + x as usize, dy + y as usize)];
+                        *pixel = pixel.max(v);
+                    }
+                });
+            }
+
+            let offset = vec2(bb.min.x, bb.min.y) / self.pixels_per_point
+                + self.y_offset_in_points * Vec2::Y;
+            UvRect {
+                offset,
+                size: vec2(width as f32, height as f32) / self.pixels_per_point,
+                min: [atlas_pos.0 as u16, atlas_pos.1 as u16],
+                max: [(atlas_pos.0 + width) as u16, (atlas_pos.1 + height) as u16],
+            }
+        });
+
+        self.cluster_cache.write().insert(glyphs, uv_rect);
+        uv_rect
+    }
+}
+
 type FontIndex = usize;
 
 // TODO(emilk): rename?
@@ -468,6 +656,13 @@ impl Font {
         (Some(font_impl), glyph_info)
     }
 
+    /// The sub-font that will be used for this character.
+    #[cfg(feature = "shaping")]
+    pub(crate) fn font_impl_for(&mut self, c: char) -> Option<&Arc<FontImpl>> {
+        let (font_index, _) = self.glyph_info(c);
+        self.fonts.get(font_index)
+    }
+
     pub(crate) fn ascent(&self) -> f32 {
         if let Some(first) = self.fonts.first() {
             first.ascent()
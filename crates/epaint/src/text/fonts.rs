@@ -798,6 +798,10 @@ struct FontImplCache {
     pixels_per_point: f32,
     ab_glyph_fonts: BTreeMap<String, (FontTweak, ab_glyph::FontArc)>,
 
+    /// The font files, for shaping.
+    #[cfg(feature = "shaping")]
+    font_data: BTreeMap<String, Arc<FontData>>,
+
     /// Map font pixel sizes and names to the cached [`FontImpl`].
     cache: ahash::HashMap<(u32, String), Arc<FontImpl>>,
 }
@@ -821,6 +825,8 @@ impl FontImplCache {
             atlas,
             pixels_per_point,
             ab_glyph_fonts,
+            #[cfg(feature = "shaping")]
+            font_data: font_data.clone(),
             cache: Default::default(),
         }
     }
@@ -849,14 +855,22 @@ impl FontImplCache {
                 font_name.to_owned(),
             ))
             .or_insert_with(|| {
-                Arc::new(FontImpl::new(
+                let font_impl = FontImpl::new(
                     self.atlas.clone(),
                     self.pixels_per_point,
                     font_name.to_owned(),
                     ab_glyph_font,
                     scale_in_pixels,
                     tweak,
-                ))
+                );
+
+                #[cfg(feature = "shaping")]
+                let font_impl = match self.font_data.get(font_name) {
+                    Some(font_data) => font_impl.with_font_data(font_data.clone()),
+                    None => font_impl,
+                };
+
+                Arc::new(font_impl)
             })
             .clone()
     }
@@ -1,8 +1,11 @@
 //! Everything related to text, fonts, text layout, cursors etc.
 
+mod bidi;
 pub mod cursor;
 mod font;
 mod fonts;
+#[cfg(feature = "shaping")]
+mod shaping;
 mod text_layout;
 mod text_layout_types;
 
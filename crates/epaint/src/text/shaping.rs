@@ -0,0 +1,106 @@
+//! Text shaping and bidirectional text, enabled by the `shaping` feature.
+//!
+//! The layout keeps one [`super::Glyph`] per character, in logical order, so that cursors keep working.
+//! Shaping may draw several characters as one glyph (e.g. a ligature),
+//! in which case the first character gets the glyph and the rest get zero width.
+//! Right-to-left text is laid out left-to-right first, and then moved into place row by row,
+//! see [`super::bidi::reorder_row`].
+
+use std::ops::Range;
+
+use unicode_bidi::{BidiClass, BidiInfo};
+
+use super::{font::Font, font::GlyphInfo};
+
+/// The bidi embedding level of each byte of the text.
+///
+/// Returns `None` if all the text is left-to-right.
+pub(crate) fn bidi_levels(text: &str) -> Option<Vec<u8>> {
+    let any_rtl = text.chars().any(|c| {
+        matches!(
+            unicode_bidi::bidi_class(c),
+            BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI
+        )
+    });
+    if !any_rtl {
+        return None;
+    }
+
+    let bidi_info = BidiInfo::new(text, None);
+    Some(
+        bidi_info
+            .levels
+            .iter()
+            .map(|level| level.number())
+            .collect(),
+    )
+}
+
+/// Shape the runs of a section.
+///
+/// `byte_range` is the range of the section in `text`,
+/// and `levels` the bidi level of each byte of `text`, if any text is right-to-left.
+///
+/// Returns the glyph to use for each character that shaping changed, by byte offset into `text`.
+/// The other characters are laid out glyph by glyph like without the `shaping` feature.
+pub(crate) fn shape_section(
+    font: &mut Font,
+    text: &str,
+    byte_range: Range<usize>,
+    levels: Option<&[u8]>,
+) -> ahash::HashMap<usize, GlyphInfo> {
+    let mut shaped = ahash::HashMap::default();
+
+    let level_at = |byte: usize| levels.map_or(0, |levels| levels[byte]);
+
+    // Split the section into runs of the same font and bidi level:
+    let mut runs: Vec<(Range<usize>, u8)> = vec![];
+    let mut run_font = None;
+    for (offset, c) in text[byte_range.clone()].char_indices() {
+        let byte = byte_range.start + offset;
+        let font_impl = font.font_impl_for(c).map(std::sync::Arc::as_ptr);
+        let level = level_at(byte);
+        let continues_run = c != '\n'
+            && font_impl == run_font
+            && runs
+                .last()
+                .is_some_and(|(_, run_level)| *run_level == level);
+        if continues_run {
+            if let Some((run, _)) = runs.last_mut() {
+                run.end = byte + c.len_utf8();
+            }
+        } else {
+            runs.push((byte..byte + c.len_utf8(), level));
+        }
+        run_font = font_impl;
+    }
+
+    for (run, level) in runs {
+        let run_text = &text[run.clone()];
+        if !font.has_glyphs(run_text) {
+            continue;
+        }
+        let Some(first) = run_text.chars().next() else {
+            continue;
+        };
+        let Some(font_impl) = font.font_impl_for(first).cloned() else {
+            continue;
+        };
+        let Some(clusters) = font_impl.shape(run_text, level % 2 == 1) else {
+            continue;
+        };
+
+        for (cluster, glyph_info) in clusters {
+            for (offset, _) in run_text[cluster.clone()].char_indices() {
+                let glyph_info = if offset == 0 {
+                    glyph_info
+                } else {
+                    GlyphInfo::default()
+                };
+                shaped.insert(run.start + cluster.start + offset, glyph_info);
+            }
+        }
+    }
+
+    shaped
+}
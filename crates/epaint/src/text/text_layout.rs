@@ -86,9 +86,21 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
 
     // For most of this we ignore the y coordinate:
 
+    #[cfg(feature = "shaping")]
+    let bidi_levels = super::shaping::bidi_levels(&job.text);
+    #[cfg(not(feature = "shaping"))]
+    let bidi_levels: Option<Vec<u8>> = None;
+
     let mut paragraphs = vec![Paragraph::from_section_index(0)];
     for (section_index, section) in job.sections.iter().enumerate() {
-        layout_section(fonts, &job, section_index as u32, section, &mut paragraphs);
+        layout_section(
+            fonts,
+            &job,
+            section_index as u32,
+            section,
+            bidi_levels.as_deref(),
+            &mut paragraphs,
+        );
     }
 
     let point_scale = PointScale::new(fonts.pixels_per_point());
@@ -121,6 +133,12 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
         }
     }
 
+    if bidi_levels.is_some() {
+        for row in &mut rows {
+            super::bidi::reorder_row(row);
+        }
+    }
+
     // Calculate the Y positions and tessellate the text:
     galley_from_rows(point_scale, job, rows, elided)
 }
@@ -131,6 +149,7 @@ fn layout_section(
     job: &LayoutJob,
     section_index: u32,
     section: &LayoutSection,
+    bidi_levels: Option<&[u8]>,
     out_paragraphs: &mut Vec<Paragraph>,
 ) {
     let LayoutSection {
@@ -152,16 +171,29 @@ fn layout_section(
 
     paragraph.cursor_x += leading_space;
 
+    #[cfg(feature = "shaping")]
+    let shaped = super::shaping::shape_section(font, &job.text, byte_range.clone(), bidi_levels);
+
     let mut last_glyph_id = None;
 
-    for chr in job.text[byte_range.clone()].chars() {
+    for (offset, chr) in job.text[byte_range.clone()].char_indices() {
+        let byte = byte_range.start + offset;
         if job.break_on_newline && chr == '\n' {
             out_paragraphs.push(Paragraph::from_section_index(section_index));
             paragraph = out_paragraphs.last_mut().unwrap();
             paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
         } else {
-            let (font_impl, glyph_info) = font.font_impl_and_glyph_info(chr);
-            if let Some(font_impl) = font_impl {
+            let (font_impl, mut glyph_info) = font.font_impl_and_glyph_info(chr);
+
+            #[cfg(feature = "shaping")]
+            let shaped_glyph_info = shaped.get(&byte).copied();
+            #[cfg(not(feature = "shaping"))]
+            let shaped_glyph_info = None;
+
+            if let Some(shaped_glyph_info) = shaped_glyph_info {
+                // Kerning and letter spacing are already taken care of by the shaping:
+                glyph_info = shaped_glyph_info;
+            } else if let Some(font_impl) = font_impl {
                 if let Some(last_glyph_id) = last_glyph_id {
                     paragraph.cursor_x += font_impl.pair_kerning(last_glyph_id, glyph_info.id);
                     paragraph.cursor_x += extra_letter_spacing;
@@ -179,11 +211,12 @@ fn layout_section(
                 font_ascent: font.ascent(),
                 uv_rect: glyph_info.uv_rect,
                 section_index,
+                bidi_level: bidi_levels.map_or(0, |levels| levels[byte]),
             });
 
             paragraph.cursor_x += glyph_info.advance_width;
             paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
-            last_glyph_id = Some(glyph_info.id);
+            last_glyph_id = shaped_glyph_info.is_none().then_some(glyph_info.id);
         }
     }
 }
@@ -403,6 +436,7 @@ fn replace_last_glyph_with_overflow_character(
             font_ascent: font.ascent(),
             uv_rect: replacement_glyph_info.uv_rect,
             section_index,
+            bidi_level: 0,
         });
     } else {
         let section_index = row.section_index_at_start;
@@ -425,6 +459,7 @@ fn replace_last_glyph_with_overflow_character(
             font_ascent: font.ascent(),
             uv_rect: replacement_glyph_info.uv_rect,
             section_index,
+            bidi_level: 0,
         });
     }
 
@@ -687,6 +722,8 @@ struct FormatSummary {
     any_background: bool,
     any_underline: bool,
     any_strikethrough: bool,
+    any_outline: bool,
+    any_shadow: bool,
 }
 
 fn format_summary(job: &LayoutJob) -> FormatSummary {
@@ -695,6 +732,8 @@ fn format_summary(job: &LayoutJob) -> FormatSummary {
         format_summary.any_background |= section.format.background != Color32::TRANSPARENT;
         format_summary.any_underline |= section.format.underline != Stroke::NONE;
         format_summary.any_strikethrough |= section.format.strikethrough != Stroke::NONE;
+        format_summary.any_outline |= !section.format.outline.is_empty();
+        format_summary.any_shadow |= !section.format.shadow.is_none();
     }
     format_summary
 }
@@ -718,6 +757,15 @@ fn tessellate_row(
         add_row_backgrounds(job, row, &mut mesh);
     }
 
+    // Shadows and outlines go before the glyphs, so they end up behind all of them,
+    // and outside of `glyph_vertex_range` (so they keep their color when the text is recolored):
+    if format_summary.any_shadow {
+        tessellate_glyph_shadows(point_scale, job, row, &mut mesh);
+    }
+    if format_summary.any_outline {
+        tessellate_glyph_outlines(point_scale, job, row, &mut mesh);
+    }
+
     let glyph_index_start = mesh.indices.len();
     let glyph_vertex_start = mesh.vertices.len();
     tessellate_glyphs(point_scale, job, row, &mut mesh);
@@ -769,7 +817,9 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     let mut run_start = None;
     let mut last_rect = Rect::NAN;
 
-    for glyph in &row.glyphs {
+    let glyphs = super::bidi::visual_glyphs(row);
+
+    for glyph in &glyphs[..] {
         let format = &job.sections[glyph.section_index as usize].format;
         let color = format.background;
         let rect = glyph.logical_rect();
@@ -798,56 +848,148 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
 
 fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     for glyph in &row.glyphs {
-        let uv_rect = glyph.uv_rect;
-        if !uv_rect.is_nothing() {
-            let mut left_top = glyph.pos + uv_rect.offset;
-            left_top.x = point_scale.round_to_pixel(left_top.x);
-            left_top.y = point_scale.round_to_pixel(left_top.y);
-
-            let rect = Rect::from_min_max(left_top, left_top + uv_rect.size);
-            let uv = Rect::from_min_max(
-                pos2(uv_rect.min[0] as f32, uv_rect.min[1] as f32),
-                pos2(uv_rect.max[0] as f32, uv_rect.max[1] as f32),
-            );
-
-            let format = &job.sections[glyph.section_index as usize].format;
+        let format = &job.sections[glyph.section_index as usize].format;
+        add_glyph_quad(
+            point_scale,
+            glyph,
+            format.italics,
+            Vec2::ZERO,
+            format.color,
+            mesh,
+        );
+    }
+}
 
-            let color = format.color;
+/// Paint the [`super::TextFormat::shadow`] of each glyph: a moved copy of it.
+fn tessellate_glyph_shadows(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
+    for glyph in &row.glyphs {
+        let format = &job.sections[glyph.section_index as usize].format;
+        if !format.shadow.is_none() {
+            let shadow = format.shadow;
+            add_glyph_quad(
+                point_scale,
+                glyph,
+                format.italics,
+                shadow.offset,
+                shadow.color,
+                mesh,
+            );
+        }
+    }
+}
 
-            if format.italics {
-                let idx = mesh.vertices.len() as u32;
-                mesh.add_triangle(idx, idx + 1, idx + 2);
-                mesh.add_triangle(idx + 2, idx + 1, idx + 3);
+/// Paint the [`super::TextFormat::outline`] of each glyph,
+/// by surrounding it with copies of itself in the outline color.
+fn tessellate_glyph_outlines(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
+    let mut offsets: Option<(f32, Vec<Vec2>)> = None;
 
-                let top_offset = rect.height() * 0.25 * Vec2::X;
+    for glyph in &row.glyphs {
+        let format = &job.sections[glyph.section_index as usize].format;
+        let outline = format.outline;
+        if outline.is_empty() {
+            continue;
+        }
 
-                mesh.vertices.push(Vertex {
-                    pos: rect.left_top() + top_offset,
-                    uv: uv.left_top(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.right_top() + top_offset,
-                    uv: uv.right_top(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.left_bottom(),
-                    uv: uv.left_bottom(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.right_bottom(),
-                    uv: uv.right_bottom(),
-                    color,
-                });
-            } else {
-                mesh.add_rect_with_uv(rect, uv, color);
+        let offsets = match &mut offsets {
+            Some((width, offsets)) if *width == outline.width => offsets,
+            _ => {
+                let new_offsets = outline_offsets(point_scale, outline.width);
+                &offsets.insert((outline.width, new_offsets)).1
             }
+        };
+        for &offset in offsets {
+            add_glyph_quad(
+                point_scale,
+                glyph,
+                format.italics,
+                offset,
+                outline.color,
+                mesh,
+            );
         }
     }
 }
 
+/// Where to put copies of a glyph to paint an outline of the given width (in points) around it.
+fn outline_offsets(point_scale: PointScale, width: f32) -> Vec<Vec2> {
+    let pixels_per_point = point_scale.pixels_per_point();
+    let mut offsets = vec![];
+
+    // Rings of copies, one pixel apart, so that even thin glyph strokes are surrounded without gaps:
+    let mut radius_in_pixels = width * pixels_per_point;
+    while radius_in_pixels > 0.0 {
+        let num_copies = (std::f32::consts::TAU * radius_in_pixels)
+            .ceil()
+            .clamp(8.0, 32.0) as usize;
+        let radius = radius_in_pixels / pixels_per_point;
+        offsets.extend(
+            (0..num_copies).map(|i| {
+                radius * Vec2::angled(std::f32::consts::TAU * i as f32 / num_copies as f32)
+            }),
+        );
+        radius_in_pixels -= 1.0;
+    }
+
+    offsets
+}
+
+/// Add the textured quad of a glyph, moved by `offset` (which is not rounded to pixels).
+fn add_glyph_quad(
+    point_scale: PointScale,
+    glyph: &Glyph,
+    italics: bool,
+    offset: Vec2,
+    color: Color32,
+    mesh: &mut Mesh,
+) {
+    let uv_rect = glyph.uv_rect;
+    if uv_rect.is_nothing() {
+        return;
+    }
+
+    let mut left_top = glyph.pos + uv_rect.offset;
+    left_top.x = point_scale.round_to_pixel(left_top.x);
+    left_top.y = point_scale.round_to_pixel(left_top.y);
+    left_top += offset;
+
+    let rect = Rect::from_min_max(left_top, left_top + uv_rect.size);
+    let uv = Rect::from_min_max(
+        pos2(uv_rect.min[0] as f32, uv_rect.min[1] as f32),
+        pos2(uv_rect.max[0] as f32, uv_rect.max[1] as f32),
+    );
+
+    if italics {
+        let idx = mesh.vertices.len() as u32;
+        mesh.add_triangle(idx, idx + 1, idx + 2);
+        mesh.add_triangle(idx + 2, idx + 1, idx + 3);
+
+        let top_offset = rect.height() * 0.25 * Vec2::X;
+
+        mesh.vertices.push(Vertex {
+            pos: rect.left_top() + top_offset,
+            uv: uv.left_top(),
+            color,
+        });
+        mesh.vertices.push(Vertex {
+            pos: rect.right_top() + top_offset,
+            uv: uv.right_top(),
+            color,
+        });
+        mesh.vertices.push(Vertex {
+            pos: rect.left_bottom(),
+            uv: uv.left_bottom(),
+            color,
+        });
+        mesh.vertices.push(Vertex {
+            pos: rect.right_bottom(),
+            uv: uv.right_bottom(),
+            color,
+        });
+    } else {
+        mesh.add_rect_with_uv(rect, uv, color);
+    }
+}
+
 /// Add a horizontal line over a row of glyphs with a stroke and y decided by a callback.
 fn add_row_hline(
     point_scale: PointScale,
@@ -864,7 +1006,9 @@ fn add_row_hline(
     let mut line_start = None;
     let mut last_right_x = f32::NAN;
 
-    for glyph in &row.glyphs {
+    let glyphs = super::bidi::visual_glyphs(row);
+
+    for glyph in &glyphs[..] {
         let (stroke, y) = stroke_and_y(glyph);
 
         if stroke == Stroke::NONE {
@@ -1148,4 +1292,90 @@ mod tests {
         let row = &galley.rows[0];
         assert_eq!(row.rect.max.x, row.glyphs.last().unwrap().max_x());
     }
+
+    #[test]
+    fn test_outline_and_shadow() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let plain = TextFormat::simple(FontId::proportional(14.0), Color32::WHITE);
+        let plain_galley = layout(
+            &mut fonts,
+            LayoutJob::single_section("Hi".into(), plain.clone()).into(),
+        );
+
+        let fancy = TextFormat {
+            outline: Stroke::new(2.0, Color32::BLACK),
+            shadow: TextShadow::new(vec2(3.0, 3.0), Color32::RED),
+            ..plain
+        };
+        let galley = layout(
+            &mut fonts,
+            LayoutJob::single_section("Hi".into(), fancy).into(),
+        );
+
+        // Same layout, just more paint:
+        assert_eq!(galley.rect, plain_galley.rect);
+        assert!(plain_galley.mesh_bounds.is_positive());
+        assert!(galley
+            .mesh_bounds
+            .contains_rect(plain_galley.mesh_bounds.expand(1.0)));
+
+        // The glyphs themselves come last, and keep their color:
+        let visuals = &galley.rows[0].visuals;
+        let plain_visuals = &plain_galley.rows[0].visuals;
+        assert_eq!(
+            visuals.glyph_vertex_range.len(),
+            plain_visuals.glyph_vertex_range.len()
+        );
+        let (before, glyphs) = visuals
+            .mesh
+            .vertices
+            .split_at(visuals.glyph_vertex_range.start);
+        assert!(before.iter().any(|v| v.color == Color32::BLACK));
+        assert!(before.iter().any(|v| v.color == Color32::RED));
+        assert!(glyphs[..visuals.glyph_vertex_range.len()]
+            .iter()
+            .all(|v| v.color == Color32::WHITE));
+    }
+
+    #[cfg(feature = "shaping")]
+    #[test]
+    fn test_only_what_shaping_changes_is_shaped() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let font = fonts.font(&FontId::default());
+
+        // Nothing to do for plain latin text:
+        let text = "Hello world";
+        assert!(super::super::shaping::shape_section(font, text, 0..text.len(), None).is_empty());
+
+        // Brackets in right-to-left text are mirrored, the letter between them is left alone:
+        let text = "(a)";
+        let rtl = [1; 3];
+        let shaped = super::super::shaping::shape_section(font, text, 0..text.len(), Some(&rtl));
+        let mut shaped_bytes: Vec<usize> = shaped.keys().copied().collect();
+        shaped_bytes.sort_unstable();
+        assert_eq!(shaped_bytes, [0, 2]);
+    }
+
+    #[cfg(feature = "shaping")]
+    #[test]
+    fn test_bidi_reordering() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let layout_job = LayoutJob::single_section("ab אבג cd".into(), TextFormat::default());
+        let galley = layout(&mut fonts, layout_job.into());
+        assert_eq!(galley.rows.len(), 1);
+
+        // The text stays in logical order…
+        let row = &galley.rows[0];
+        assert_eq!(row.text(), "ab אבג cd");
+
+        // …but the Hebrew is placed right-to-left:
+        let x: Vec<f32> = row.glyphs.iter().map(|glyph| glyph.pos.x).collect();
+        assert!(x[0] < x[1] && x[1] < x[2] && x[2] < x[5]);
+        assert!(x[5] < x[4] && x[4] < x[3] && x[3] < x[6] && x[6] < x[7]);
+        assert!(!row.glyphs[2].is_rtl());
+        assert!(row.glyphs[3].is_rtl());
+
+        // A cursor before the first Hebrew letter sits on its right side:
+        assert_eq!(row.x_offset(3), row.glyphs[3].max_x());
+    }
 }
@@ -278,6 +278,19 @@ pub struct TextFormat {
 
     pub strikethrough: Stroke,
 
+    /// An outline around each glyph, to keep the text readable on top of busy backgrounds,
+    /// e.g. labels on a map or in a game HUD.
+    ///
+    /// Works best with an opaque color, and a width of a point or two.
+    ///
+    /// Default: [`Stroke::NONE`].
+    pub outline: Stroke,
+
+    /// A hard drop shadow behind the text (and its [`Self::outline`]).
+    ///
+    /// Default: [`TextShadow::NONE`].
+    pub shadow: TextShadow,
+
     /// If you use a small font and [`Align::TOP`] you
     /// can get the effect of raised text.
     ///
@@ -302,6 +315,8 @@ impl Default for TextFormat {
             italics: false,
             underline: Stroke::NONE,
             strikethrough: Stroke::NONE,
+            outline: Stroke::NONE,
+            shadow: TextShadow::NONE,
             valign: Align::BOTTOM,
         }
     }
@@ -319,6 +334,8 @@ impl std::hash::Hash for TextFormat {
             italics,
             underline,
             strikethrough,
+            outline,
+            shadow,
             valign,
         } = self;
         font_id.hash(state);
@@ -331,6 +348,8 @@ impl std::hash::Hash for TextFormat {
         italics.hash(state);
         underline.hash(state);
         strikethrough.hash(state);
+        outline.hash(state);
+        shadow.hash(state);
         valign.hash(state);
     }
 }
@@ -346,6 +365,51 @@ impl TextFormat {
     }
 }
 
+/// A hard drop shadow behind some text, see [`TextFormat::shadow`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TextShadow {
+    /// Move the shadow by this much, in points.
+    ///
+    /// For example, a value of `[1.0, 2.0]` will place the shadow 1 point to the right and 2 points down.
+    pub offset: Vec2,
+
+    /// Color of the shadow.
+    pub color: Color32,
+}
+
+impl TextShadow {
+    /// No shadow.
+    pub const NONE: Self = Self {
+        offset: Vec2::ZERO,
+        color: Color32::TRANSPARENT,
+    };
+
+    #[inline]
+    pub fn new(offset: impl Into<Vec2>, color: impl Into<Color32>) -> Self {
+        Self {
+            offset: offset.into(),
+            color: color.into(),
+        }
+    }
+
+    /// True if the shadow is not visible.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.color == Color32::TRANSPARENT
+    }
+}
+
+impl std::hash::Hash for TextShadow {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let Self { offset, color } = self;
+        OrderedFloat(offset.x).hash(state);
+        OrderedFloat(offset.y).hash(state);
+        color.hash(state);
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 /// How to wrap and elide text.
@@ -635,6 +699,10 @@ pub struct Glyph {
 
     /// Index into [`LayoutJob::sections`]. Decides color etc.
     pub section_index: u32,
+
+    /// The embedding level of the glyph from the Unicode bidi algorithm,
+    /// see [`Self::bidi_level`].
+    pub(crate) bidi_level: u8,
 }
 
 impl Glyph {
@@ -653,6 +721,21 @@ impl Glyph {
     pub fn logical_rect(&self) -> Rect {
         Rect::from_min_size(self.pos - vec2(0.0, self.font_ascent), self.size())
     }
+
+    /// The embedding level of the glyph from the Unicode bidi algorithm.
+    ///
+    /// Odd levels are right-to-left.
+    /// Always zero unless the `shaping` feature is enabled.
+    #[inline]
+    pub fn bidi_level(&self) -> u8 {
+        self.bidi_level
+    }
+
+    /// Is this glyph part of right-to-left text, e.g. Arabic or Hebrew?
+    #[inline]
+    pub fn is_rtl(&self) -> bool {
+        self.bidi_level % 2 == 1
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -693,6 +776,16 @@ impl Row {
     /// Closest char at the desired x coordinate.
     /// Returns something in the range `[0, char_count_excluding_newline()]`.
     pub fn char_at(&self, desired_x: f32) -> usize {
+        if self.glyphs.iter().any(Glyph::is_rtl) {
+            // The glyphs are not in visual order, so look for the closest cursor position:
+            return (0..=self.char_count_excluding_newline())
+                .min_by(|&a, &b| {
+                    let distance = |column| (self.x_offset(column) - desired_x).abs();
+                    distance(a).total_cmp(&distance(b))
+                })
+                .unwrap_or_default();
+        }
+
         for (i, glyph) in self.glyphs.iter().enumerate() {
             if desired_x < glyph.logical_rect().center().x {
                 return i;
@@ -701,8 +794,17 @@ impl Row {
         self.char_count_excluding_newline()
     }
 
+    /// The x coordinate of a cursor placed before the char at `column`.
+    ///
+    /// In right-to-left text, that is on the right side of the glyph.
     pub fn x_offset(&self, column: usize) -> f32 {
         if let Some(glyph) = self.glyphs.get(column) {
+            if glyph.is_rtl() {
+                glyph.max_x()
+            } else {
+                glyph.pos.x
+            }
+        } else if let Some(glyph) = self.glyphs.last().filter(|glyph| glyph.is_rtl()) {
             glyph.pos.x
         } else {
             self.rect.right()
@@ -932,6 +1034,21 @@ impl Galley {
             Default::default()
         }
     }
+
+    /// Is the text at this cursor right-to-left?
+    ///
+    /// Looks at the character after the cursor, or before it at the end of a row.
+    /// Always `false` unless the `shaping` feature is enabled.
+    pub fn is_rtl_at(&self, cursor: &Cursor) -> bool {
+        let Some(row) = self.rows.get(cursor.rcursor.row) else {
+            return false;
+        };
+        let column = cursor.rcursor.column;
+        row.glyphs
+            .get(column)
+            .or_else(|| row.glyphs.get(column.checked_sub(1)?))
+            .is_some_and(Glyph::is_rtl)
+    }
 }
 
 /// ## Cursor conversions
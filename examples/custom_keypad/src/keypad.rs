@@ -36,6 +36,7 @@ impl State {
             events.push(egui::Event::Key {
                 key,
                 physical_key: Some(key),
+                logical_key: Some(key),
                 pressed: true,
                 repeat: false,
                 modifiers: Default::default(),
@@ -49,6 +50,7 @@ impl State {
         events.push(egui::Event::Key {
             key,
             physical_key: Some(key),
+            logical_key: Some(key),
             pressed: true,
             repeat: false,
             modifiers: Default::default(),